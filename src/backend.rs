@@ -1,20 +1,66 @@
 //! Application backend.
 //! This includes all logic unrelated to UI.
 
+pub mod activity_log;
+pub mod backlog_monitor;
+pub mod clients;
+pub mod connections;
+pub mod contract_weights;
 pub mod contracts;
+pub mod document_export;
+pub mod document_generators;
+pub mod document_import;
+pub mod document_stats;
+pub mod document_tracker;
 pub mod documents;
+pub mod dry_run;
 pub mod error;
+pub mod frequency_profile;
+pub mod genesis_import;
 pub mod identities;
 pub mod insight;
+pub mod key_maintenance;
+pub mod key_reuse;
+pub mod key_vault;
+pub mod load_controller;
+pub mod load_profile;
+pub mod maintenance;
+pub mod mirror;
+pub mod mock;
+pub mod operation_metrics;
 pub mod platform_info;
+pub mod rate_limiter;
+pub mod rejections;
+pub mod reserve_top_up;
+pub mod retry_policy;
+pub mod run_chain;
+pub mod run_defaults;
+pub mod run_history;
+pub mod run_progress;
+pub mod run_report;
+pub mod run_schedule;
+pub mod signing_benchmark;
+pub mod snapshot;
 pub mod state;
 pub mod strategies;
+pub mod strategy_file;
+pub mod strategy_fixture;
+pub mod strategy_lint;
+pub mod strategy_runs;
+pub mod strategy_runtime;
+pub mod strategy_scaling;
+pub mod strategy_templates;
+pub mod traffic;
+pub mod transition_inspector;
+pub mod transition_trace;
+pub mod upgrade_window;
 pub mod wallet;
 
 use std::{
     borrow::Cow,
     collections::BTreeMap,
     fmt::{self, Display},
+    str::FromStr,
     sync::LazyLock,
     time::Duration,
 };
@@ -29,22 +75,41 @@ use dpp::{
     voting::vote_polls::contested_document_resource_vote_poll::ContestedDocumentResourceVotePoll,
 };
 use drive_proof_verifier::types::{Contenders, ContestedResources};
+use rs_dapi_client::AddressList;
 use serde::Serialize;
 pub(crate) use state::AppState;
 use strategy_tests::Strategy;
 use tokio::sync::{MappedMutexGuard, Mutex, MutexGuard};
 
-use self::state::KnownContractsMap;
+use self::{
+    connections::{ActiveAddressSet, ConnectionCheck},
+    document_generators::DocumentGenerators,
+    key_maintenance::KeyMaintenanceOp,
+    state::{IdentityPrivateKeysMap, KnownContractsMap},
+};
 pub(crate) use self::{
     contracts::ContractTask,
-    state::StrategyContractNames,
+    state::{ContractAliases, StrategyContractNames},
     strategies::StrategyTask,
     wallet::{Wallet, WalletTask},
 };
 use crate::{
     backend::{
-        documents::DocumentTask, identities::IdentityTask, insight::InsightAPIClient,
-        platform_info::PlatformInfoTask, state::StrategiesMap,
+        backlog_monitor::BacklogIncident,
+        documents::DocumentTask,
+        identities::IdentityTask,
+        insight::InsightAPIClient,
+        load_controller::LoadControlReport,
+        load_profile::{LoadPhaseReport, LoadProfile},
+        maintenance::MaintenanceTask,
+        mirror::MirrorReport,
+        operation_metrics::OperationTypeReport,
+        platform_info::PlatformInfoTask,
+        rejections::RejectionCategory,
+        run_report::BaselineComparison,
+        state::StrategiesMap,
+        strategies::TopUpRecord,
+        traffic::TrafficReport,
     },
     config::Config,
 };
@@ -64,12 +129,21 @@ async fn set_clipboard<'a, T: Into<Cow<'a, str>>>(t: T) -> Result<(), &'static a
 #[derive(Debug, Clone)]
 pub enum Task {
     FetchIdentityById(String, bool),
+    TestConnections,
+    /// Points the backend at a configured DAPI address set by its name
+    SwitchAddressSet(String),
+    /// Lifts the ban of a DAPI address, as listed in the connection checks
+    UnbanAddress(String),
+    /// Adds the contracts and identities listed in a local network's genesis
+    /// dump file to the known ones
+    ImportGenesisDump(String),
     PlatformInfo(PlatformInfoTask),
     Strategy(StrategyTask),
     Wallet(WalletTask),
     Identity(IdentityTask),
     Contract(ContractTask),
     Document(DocumentTask),
+    Maintenance(MaintenanceTask),
 }
 
 /// A positive task execution result.
@@ -84,6 +158,8 @@ pub enum CompletedTaskPayload {
     ContestedResources(ContestedResources),
     ContestedResourceContenders(ContestedDocumentResourceVotePoll, Contenders, Option<u64>),
     DocumentsAndContestedResources(IndexMap<Identifier, Option<Document>>, ContestedResources),
+    /// Connection and environment checks, failed ones included
+    ConnectionChecks(Vec<ConnectionCheck>),
 }
 
 impl From<String> for CompletedTaskPayload {
@@ -102,6 +178,9 @@ impl Display for CompletedTaskPayload {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             CompletedTaskPayload::String(s) => write!(f, "{}", s),
+            CompletedTaskPayload::ConnectionChecks(checks) => {
+                write!(f, "{}", connections::format_connection_checks(checks))
+            }
             _ => write!(f, "Executed successfully"),
         }
     }
@@ -138,6 +217,8 @@ pub(crate) enum AppStateUpdate<'s> {
     KnownContracts(MutexGuard<'s, KnownContractsMap>),
     KnownIdentities(MappedMutexGuard<'s, BTreeMap<Identifier, Identity>>),
     LoadedWallet(MappedMutexGuard<'s, Wallet>),
+    /// Receive address of the sweep wallet, `None` if it was cleared
+    SweepWalletAddress(Option<String>),
     Strategies(
         MutexGuard<'s, StrategiesMap>,
         MutexGuard<'s, BTreeMap<String, StrategyContractNames>>,
@@ -147,7 +228,13 @@ pub(crate) enum AppStateUpdate<'s> {
         MappedMutexGuard<'s, Strategy>,
         MappedMutexGuard<'s, StrategyContractNames>,
     ),
+    StrategyContractAliases(String, MappedMutexGuard<'s, ContractAliases>),
+    StrategyKeyMaintenance(String, MappedMutexGuard<'s, Vec<KeyMaintenanceOp>>),
+    StrategyDocumentGenerators(String, MappedMutexGuard<'s, DocumentGenerators>),
     IdentityRegistrationProgressed, // TODO provide state update details
+    /// Number of failed identity registrations waiting to be retried
+    IdentityRegistrationQueue(usize),
+    IdentityPrivateKeys(MutexGuard<'s, IdentityPrivateKeysMap>),
     LoadedIdentity(MappedMutexGuard<'s, Identity>),
     LoadedEvonodeIdentity(MappedMutexGuard<'s, Identity>),
     LoadedKnownIdentity(MappedMutexGuard<'s, Identity>),
@@ -178,16 +265,87 @@ pub(crate) enum StrategyCompletionResult {
         success_percent: u64,
         dash_spent_identity: f64,
         dash_spent_wallet: f64,
+        /// Document broadcasts recovered from a nonce error with a fresh nonce
+        nonce_recoveries: u64,
+        /// Waits given up on because of too many outstanding ones
+        wait_unknowns: u64,
+        /// Why the run stopped before its duration, if it did
+        halt_reason: Option<String>,
+        /// Windows where the platform stalled or was slow to include transitions
+        backlog_incidents: Vec<BacklogIncident>,
+        /// Strategy misconfigurations found before the run started
+        lint_warnings: Vec<String>,
+        /// Top ups of identities that ran out of credits during the run
+        top_ups: Vec<TopUpRecord>,
+        /// Throughput found by a latency targeted run
+        load_control: Option<LoadControlReport>,
+        load_profile: LoadProfile,
+        /// Numbers of each phase of the load profile, empty for a constant
+        /// load
+        load_phases: Vec<LoadPhaseReport>,
+        /// Transitions rejected by Platform, by what rejected them
+        rejections: Vec<(RejectionCategory, u64)>,
+        /// Path of the trace written with every transition of the load, if any
+        transition_trace: Option<String>,
+        /// Numbers of each transition type over the measured time
+        operation_types: Vec<OperationTypeReport>,
+        /// Start contracts already on chain from an earlier run, not created
+        /// again
+        reused_contracts: Vec<String>,
+        /// Aliases of the contracts the run created or reused, as "alias: ID"
+        contract_aliases: Vec<String>,
+        /// Steady phase the rates and wait times cover, if warm-up or cool-down
+        /// were excluded
+        measured_time: Option<Duration>,
+        /// DAPI address the run was pinned to, if any
+        pinned_address: Option<String>,
+        /// DAPI addresses banned at the end of the run
+        banned_addresses: Vec<String>,
+        /// Comparison with the mirror network, if transitions were mirrored
+        mirror_report: Option<MirrorReport>,
+        /// Seed of the run's random choices, to replay it
+        seed: u64,
+        /// Loops held off by the cap on transitions in flight, and for how long
+        throttled_loops: u64,
+        throttled_time: Duration,
+        /// Bytes sent and received over the run, by kind of request
+        traffic: TrafficReport,
+        /// Comparison to the strategy's baseline run, if it has one
+        baseline_comparison: Option<BaselineComparison>,
     },
     PartiallyCompleted {
         reached_block_height: u64,
         reason: String,
     },
+    /// Stopped from the UI, the created identities were still withdrawn from
+    Cancelled {
+        run_time: Duration,
+        transition_count: u64,
+        success_count: u64,
+        dash_spent_identity: f64,
+        dash_spent_wallet: f64,
+    },
+}
+
+/// What the UI needs from a backend: the application state and task
+/// execution. [Backend] works against the network, [mock::MockBackend] with
+/// canned data for the demo mode.
+#[allow(async_fn_in_trait)]
+pub trait TaskBackend {
+    fn state(&self) -> &AppState;
+
+    async fn run_task(&self, task: Task) -> BackendEvent;
+
+    /// Writes the state to disk, backends without a state file of their own
+    /// keep it in memory.
+    fn save_state(&self) {}
 }
 
 /// Application state, dependencies are task execution logic around it.
 pub struct Backend<'a> {
     pub sdk: &'a Sdk,
+    /// Address set switched to, `None` while the configured addresses are used
+    address_set: Mutex<Option<ActiveAddressSet>>,
     app_state: AppState,
     insight: InsightAPIClient,
     core_client: Client,
@@ -203,6 +361,7 @@ impl<'a> Backend<'a> {
     ) -> Backend<'a> {
         Backend {
             sdk,
+            address_set: Mutex::new(None),
             app_state: AppState::load(&insight, &core_client, &config).await,
             insight,
             core_client,
@@ -215,10 +374,11 @@ impl<'a> Backend<'a> {
     }
 
     pub async fn run_task(&self, task: Task) -> BackendEvent {
+        let mut address_set = self.address_set.lock().await;
+        let sdk = address_set.as_ref().map_or(self.sdk, |set| &set.sdk);
         match task {
             Task::FetchIdentityById(ref base58_id, add_to_known_identities) => {
-                let execution_result =
-                    identities::fetch_identity_by_b58_id(self.sdk, base58_id).await;
+                let execution_result = identities::fetch_identity_by_b58_id(sdk, base58_id).await;
                 if add_to_known_identities {
                     if let Ok((Some(identity), _)) = &execution_result {
                         let mut loaded_identities = self.app_state.known_identities.lock().await;
@@ -234,15 +394,64 @@ impl<'a> Backend<'a> {
                     execution_result: execution_info_result,
                 }
             }
+            Task::TestConnections => {
+                let dapi_addresses = address_set
+                    .as_ref()
+                    .map_or(&self.config.dapi_addresses, |set| &set.addresses);
+                let mut checks = connections::check_connections(
+                    sdk,
+                    dapi_addresses,
+                    &self.core_client,
+                    &self.insight,
+                    &self.config,
+                )
+                .await;
+                checks.extend(connections::check_loaded(
+                    self.app_state.loaded_wallet.lock().await.is_some(),
+                    self.app_state.loaded_identity.lock().await.is_some(),
+                ));
+
+                BackendEvent::TaskCompleted {
+                    task,
+                    execution_result: Ok(CompletedTaskPayload::ConnectionChecks(checks)),
+                }
+            }
+            Task::SwitchAddressSet(ref name) => {
+                let execution_result = self
+                    .switch_address_set(&mut address_set, name)
+                    .map(Into::into);
+                BackendEvent::TaskCompleted {
+                    task,
+                    execution_result,
+                }
+            }
+            Task::UnbanAddress(ref address) => {
+                let execution_result = connections::unban_address(sdk, address).map(Into::into);
+                BackendEvent::TaskCompleted {
+                    task,
+                    execution_result,
+                }
+            }
+            Task::ImportGenesisDump(ref path) => {
+                let execution_result = self
+                    .app_state
+                    .import_genesis_dump(sdk, path)
+                    .await
+                    .map(Into::into);
+                BackendEvent::TaskCompleted {
+                    task,
+                    execution_result,
+                }
+            }
             Task::Strategy(strategy_task) => {
                 self.app_state
-                    .run_strategy_task(&self.sdk, strategy_task, &self.insight, &self.core_client)
+                    .run_strategy_task(sdk, strategy_task, &self.insight, &self.core_client)
                     .await
             }
             Task::Wallet(wallet_task) => {
                 wallet::run_wallet_task(
-                    self.sdk,
-                    &self.app_state.loaded_wallet,
+                    sdk,
+                    &self.app_state,
                     wallet_task,
                     &self.insight,
                     &self.core_client,
@@ -251,24 +460,74 @@ impl<'a> Backend<'a> {
             }
             Task::Contract(contract_task) => {
                 self.app_state
-                    .run_contract_task(self.sdk, &self.app_state.known_contracts, contract_task)
+                    .run_contract_task(sdk, &self.app_state.known_contracts, contract_task)
                     .await
             }
             Task::Identity(identity_task) => {
-                self.app_state
-                    .run_identity_task(self.sdk, identity_task)
-                    .await
+                self.app_state.run_identity_task(sdk, identity_task).await
             }
             Task::Document(document_task) => {
-                self.app_state
-                    .run_document_task(&self.sdk, document_task)
-                    .await
+                self.app_state.run_document_task(sdk, document_task).await
             }
             Task::PlatformInfo(platform_info_task) => {
-                platform_info::run_platform_task(self.sdk, platform_info_task).await
+                platform_info::run_platform_task(sdk, platform_info_task).await
+            }
+            Task::Maintenance(maintenance_task) => {
+                self.app_state
+                    .run_maintenance_task(&self.config, sdk.version(), maintenance_task)
+                    .await
             }
         }
     }
+
+    /// Builds an Sdk for the named address set, the default set goes back to
+    /// the Sdk the backend was created with.
+    fn switch_address_set(
+        &self,
+        address_set: &mut Option<ActiveAddressSet>,
+        name: &str,
+    ) -> Result<String, String> {
+        let (name, addresses) = self
+            .config
+            .dapi_address_sets()
+            .into_iter()
+            .find(|(set_name, _)| set_name == name)
+            .ok_or_else(|| format!("No DAPI address set named {}", name))?;
+        let address_count = addresses
+            .split(',')
+            .filter(|a| !a.trim().is_empty())
+            .count();
+
+        if name == Config::DEFAULT_ADDRESS_SET {
+            *address_set = None;
+        } else {
+            let address_list = AddressList::from_str(&addresses)
+                .map_err(|e| format!("Invalid addresses in set {}: {}", name, e))?;
+            let sdk = connections::build_sdk(&self.config, address_list, self.sdk.version())?;
+            *address_set = Some(ActiveAddressSet { addresses, sdk });
+        }
+        tracing::info!("switched to DAPI address set {}", name);
+        Ok(format!(
+            "Using DAPI address set {} ({} addresses)",
+            name, address_count
+        ))
+    }
+}
+
+impl TaskBackend for Backend<'_> {
+    fn state(&self) -> &AppState {
+        Backend::state(self)
+    }
+
+    async fn run_task(&self, task: Task) -> BackendEvent {
+        let event = Backend::run_task(self, task).await;
+        self.app_state.publish_snapshot();
+        event
+    }
+
+    fn save_state(&self) {
+        self.app_state.save(&self.config)
+    }
 }
 
 impl Drop for Backend<'_> {
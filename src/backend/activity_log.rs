@@ -0,0 +1,261 @@
+//! Tasks executed in the current session.
+//!
+//! Every task the UI loop runs is recorded with the time it started, how long
+//! it took and whether it succeeded, so a session can be retraced when
+//! something goes wrong. Only task kinds are recorded: task payloads and
+//! successful results may hold private keys or other secrets, errors are kept
+//! as they are. Tasks run concurrently, the ones still running can be
+//! cancelled from the log, strategy runs are asked to stop and wrap up rather
+//! than dropped. The log lives in memory only and starts anew with each
+//! launch.
+
+use std::{
+    collections::VecDeque,
+    fmt::{self, Display},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Local};
+use futures::future::AbortHandle;
+
+use super::{
+    strategies::StrategyTask, strategy_runs::StrategyRuns, AppState, BackendEvent,
+    StrategyCompletionResult, Task,
+};
+
+/// Finished entries kept, screens refreshing periodically would grow the log
+/// forever. Running entries are always kept, the log grows past this while
+/// they're all running.
+const MAX_ENTRIES: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ActivityOutcome {
+    Running,
+    Succeeded,
+    /// The task was stopped on request
+    Cancelled,
+    Failed(String),
+}
+
+impl Display for ActivityOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActivityOutcome::Running => write!(f, "running"),
+            ActivityOutcome::Succeeded => write!(f, "ok"),
+            ActivityOutcome::Cancelled => write!(f, "cancelled"),
+            ActivityOutcome::Failed(_) => write!(f, "failed"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActivityEntry {
+    /// Position of the task in the session, starting from 1
+    pub number: u64,
+    pub started_at: DateTime<Local>,
+    /// Task kind, e.g. `Identity / Refresh`
+    pub task: String,
+    /// `None` while the task is running or if it never finished
+    pub duration: Option<Duration>,
+    pub outcome: ActivityOutcome,
+    /// Strategies the task runs
+    pub strategies: Vec<String>,
+}
+
+/// In-memory log of the session's tasks, not persisted. Clones share the
+/// log, so a screen can keep one to follow it.
+#[derive(Debug, Clone)]
+pub struct ActivityLog {
+    session_started_at: DateTime<Local>,
+    session_start: Instant,
+    /// Entries with the time they started at and, while running, the handle
+    /// cancelling them
+    entries: Arc<Mutex<VecDeque<(Instant, ActivityEntry, Option<AbortHandle>)>>>,
+    next_number: Arc<Mutex<u64>>,
+    /// Runs of the strategies tasks run, to stop them
+    strategy_runs: StrategyRuns,
+}
+
+impl AppState {
+    pub fn activity_log(&self) -> &ActivityLog {
+        &self.activity_log
+    }
+}
+
+impl ActivityLog {
+    pub fn new(strategy_runs: StrategyRuns) -> Self {
+        ActivityLog {
+            session_started_at: Local::now(),
+            session_start: Instant::now(),
+            entries: Arc::default(),
+            next_number: Arc::new(Mutex::new(1)),
+            strategy_runs,
+        }
+    }
+
+    /// Time the application was started at and how long ago that was.
+    pub fn session_time(&self) -> (DateTime<Local>, Duration) {
+        (self.session_started_at, self.session_start.elapsed())
+    }
+
+    /// Recorded tasks of the session, oldest first.
+    pub fn entries(&self) -> Vec<ActivityEntry> {
+        let entries = self.entries.lock().expect("activity log lock");
+        entries.iter().map(|(_, entry, _)| entry.clone()).collect()
+    }
+
+    /// Number the next started task will be recorded with.
+    pub fn next_task_number(&self) -> u64 {
+        *self.next_number.lock().expect("activity log lock")
+    }
+
+    /// Whether the task recorded with `number` is still running.
+    pub fn is_running(&self, number: u64) -> bool {
+        let entries = self.entries.lock().expect("activity log lock");
+        entries.iter().any(|(_, entry, _)| {
+            entry.number == number && entry.outcome == ActivityOutcome::Running
+        })
+    }
+
+    /// Records a task being started, `abort_handle` cancels it. Returns the
+    /// number it's recorded with.
+    pub fn record_task_started(&self, task: &Task, abort_handle: AbortHandle) -> u64 {
+        let mut next_number = self.next_number.lock().expect("activity log lock");
+        let number = *next_number;
+        *next_number += 1;
+
+        let mut entries = self.entries.lock().expect("activity log lock");
+        if entries.len() >= MAX_ENTRIES {
+            // Running tasks are kept, so they can still be cancelled
+            if let Some(finished) = entries
+                .iter()
+                .position(|(_, entry, _)| entry.outcome != ActivityOutcome::Running)
+            {
+                entries.remove(finished);
+            }
+        }
+        entries.push_back((
+            Instant::now(),
+            ActivityEntry {
+                number,
+                started_at: Local::now(),
+                task: task_kind(task),
+                duration: None,
+                outcome: ActivityOutcome::Running,
+                strategies: strategies_run_by(task),
+            },
+            Some(abort_handle),
+        ));
+        number
+    }
+
+    /// Records the outcome of the task recorded with `number`, unless it was
+    /// cancelled meanwhile.
+    pub fn record_task_finished(&self, number: u64, event: &BackendEvent) {
+        let mut entries = self.entries.lock().expect("activity log lock");
+        let Some((started, entry, abort_handle)) = entries
+            .iter_mut()
+            .find(|(_, entry, _)| entry.number == number)
+        else {
+            return;
+        };
+        if entry.outcome != ActivityOutcome::Running {
+            return;
+        }
+        *abort_handle = None;
+        entry.duration = Some(started.elapsed());
+        entry.outcome = match event {
+            BackendEvent::TaskCompleted {
+                execution_result: Err(error),
+                ..
+            }
+            | BackendEvent::TaskCompletedStateChange {
+                execution_result: Err(error),
+                ..
+            }
+            | BackendEvent::StrategyError { error } => ActivityOutcome::Failed(error.clone()),
+            BackendEvent::StrategyCompleted {
+                result: StrategyCompletionResult::Cancelled { .. },
+                ..
+            } => ActivityOutcome::Cancelled,
+            _ => ActivityOutcome::Succeeded,
+        };
+    }
+
+    /// Asks the strategy runs of the task recorded with `number` to stop, they
+    /// wrap up and the task finishes as cancelled. Returns `false` if the task
+    /// finished or none of its strategies is running.
+    pub fn stop_strategy_runs(&self, number: u64) -> bool {
+        let entries = self.entries.lock().expect("activity log lock");
+        let Some((_, entry, _)) = entries.iter().find(|(_, entry, _)| {
+            entry.number == number && entry.outcome == ActivityOutcome::Running
+        }) else {
+            return false;
+        };
+        entry
+            .strategies
+            .iter()
+            .fold(false, |stopped, strategy_name| {
+                self.strategy_runs.cancel(strategy_name) || stopped
+            })
+    }
+
+    /// Cancels the task recorded with `number` if it's still running. Strategy
+    /// runs are stopped as with [Self::stop_strategy_runs], other tasks are
+    /// dropped wherever they are. Returns `false` if it already finished.
+    pub fn cancel(&self, number: u64) -> bool {
+        if self.stop_strategy_runs(number) {
+            return true;
+        }
+        let mut entries = self.entries.lock().expect("activity log lock");
+        let Some((started, entry, abort_handle)) = entries
+            .iter_mut()
+            .find(|(_, entry, _)| entry.number == number)
+        else {
+            return false;
+        };
+        let Some(abort_handle) = abort_handle.take() else {
+            return false;
+        };
+        abort_handle.abort();
+        entry.duration = Some(started.elapsed());
+        entry.outcome = ActivityOutcome::Cancelled;
+        true
+    }
+}
+
+/// Strategies a task runs, whose runs can be stopped.
+fn strategies_run_by(task: &Task) -> Vec<String> {
+    match task {
+        Task::Strategy(StrategyTask::RunStrategy(strategy_name, ..))
+        | Task::Strategy(StrategyTask::RunAcrossUpgrade { strategy_name, .. }) => {
+            vec![strategy_name.clone()]
+        }
+        Task::Strategy(StrategyTask::RunChain { strategy_names, .. }) => strategy_names.clone(),
+        _ => Vec::new(),
+    }
+}
+
+/// Names of a task's variant and of its subtask's, if any, without their
+/// fields which may hold secrets.
+fn task_kind(task: &Task) -> String {
+    let debug = format!("{:?}", task);
+    let mut rest = debug.as_str();
+    let mut names = Vec::new();
+    while names.len() < 2 {
+        let end = rest
+            .find(|c: char| !c.is_alphanumeric() && c != '_')
+            .unwrap_or(rest.len());
+        let name = &rest[..end];
+        if !name.starts_with(|c: char| c.is_ascii_uppercase()) {
+            break;
+        }
+        names.push(name);
+        match rest[end..].strip_prefix('(') {
+            Some(inner) => rest = inner,
+            None => break,
+        }
+    }
+    names.join(" / ")
+}
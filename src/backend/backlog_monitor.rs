@@ -0,0 +1,177 @@
+//! Platform backlog monitoring during strategy runs.
+//!
+//! A run keeps broadcasting whatever the network does, so a slow run may be
+//! the network's fault rather than the strategy's. The monitor is fed the
+//! platform block height and the recent wait latency while the run goes on and
+//! records the time windows where blocks stopped being produced or inclusion
+//! got slow, to be shown with the run report.
+
+use std::{
+    fmt::{self, Display},
+    time::Duration,
+};
+
+use dapi_grpc::{
+    platform::v0::{get_epochs_info_request, get_epochs_info_response, GetEpochsInfoRequest},
+    Message,
+};
+use dash_sdk::Sdk;
+use rs_dapi_client::{DapiRequestExecutor, RequestSettings};
+
+use super::traffic::{RequestCategory, Traffic};
+use crate::format::format_duration;
+
+/// No new platform block for this long is reported as a stall
+const STALL_THRESHOLD: Duration = Duration::from_secs(60);
+/// Average wait for inclusion above this is reported as slow inclusion
+const LATENCY_THRESHOLD: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum IncidentKind {
+    BlockProductionStalled,
+    SlowInclusion,
+}
+
+/// A window of the run, as offsets from the run start, where the network was
+/// not keeping up.
+#[derive(Debug, Clone)]
+pub(crate) struct BacklogIncident {
+    pub(crate) kind: IncidentKind,
+    pub(crate) start: Duration,
+    pub(crate) end: Duration,
+    pub(crate) detail: String,
+}
+
+impl Display for BacklogIncident {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            IncidentKind::BlockProductionStalled => "block production stalled",
+            IncidentKind::SlowInclusion => "slow inclusion",
+        };
+        write!(
+            f,
+            "{} - {}: {} ({})",
+            format_duration(self.start),
+            format_duration(self.end),
+            kind,
+            self.detail
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct BacklogMonitor {
+    last_height: u64,
+    last_height_change: Duration,
+    stall: Option<BacklogIncident>,
+    slow_inclusion: Option<BacklogIncident>,
+    incidents: Vec<BacklogIncident>,
+}
+
+impl BacklogMonitor {
+    /// Platform block height seen `elapsed` after the run start.
+    pub(crate) fn observe_height(&mut self, height: u64, elapsed: Duration) {
+        if height > self.last_height {
+            self.last_height = height;
+            self.last_height_change = elapsed;
+            if let Some(mut stall) = self.stall.take() {
+                stall.end = elapsed;
+                tracing::warn!("Platform block production resumed at height {}", height);
+                self.incidents.push(stall);
+            }
+        } else if self.stall.is_none()
+            && elapsed.saturating_sub(self.last_height_change) > STALL_THRESHOLD
+        {
+            tracing::warn!(
+                "Platform block production stalled at height {} for more than {}s",
+                height,
+                STALL_THRESHOLD.as_secs()
+            );
+            self.stall = Some(BacklogIncident {
+                kind: IncidentKind::BlockProductionStalled,
+                start: self.last_height_change,
+                end: elapsed,
+                detail: format!("at height {}", height),
+            });
+        }
+    }
+
+    /// Average time waited for inclusion by the waits completed since the last
+    /// observation, `None` if there were none.
+    pub(crate) fn observe_wait_latency(&mut self, average: Option<Duration>, elapsed: Duration) {
+        let Some(average) = average else {
+            return;
+        };
+        match self.slow_inclusion.as_mut() {
+            None if average > LATENCY_THRESHOLD => {
+                tracing::warn!(
+                    "Average inclusion latency is {}s, above {}s",
+                    average.as_secs(),
+                    LATENCY_THRESHOLD.as_secs()
+                );
+                self.slow_inclusion = Some(BacklogIncident {
+                    kind: IncidentKind::SlowInclusion,
+                    start: elapsed,
+                    end: elapsed,
+                    detail: format!("up to {}s average wait", average.as_secs()),
+                });
+            }
+            Some(incident) if average > LATENCY_THRESHOLD => {
+                incident.end = elapsed;
+                incident.detail = format!("up to {}s average wait", average.as_secs());
+            }
+            Some(_) => {
+                let mut incident = self.slow_inclusion.take().expect("matched above");
+                incident.end = elapsed;
+                self.incidents.push(incident);
+            }
+            None => {}
+        }
+    }
+
+    /// Closes incidents still going on and returns all of them in start order.
+    pub(crate) fn finish(mut self, elapsed: Duration) -> Vec<BacklogIncident> {
+        for mut incident in [self.stall.take(), self.slow_inclusion.take()]
+            .into_iter()
+            .flatten()
+        {
+            incident.end = elapsed;
+            self.incidents.push(incident);
+        }
+        self.incidents.sort_by_key(|incident| incident.start);
+        self.incidents
+    }
+}
+
+/// Current platform block height from the metadata of a light request,
+/// counted in the run's traffic.
+pub(crate) async fn fetch_platform_block_height(sdk: &Sdk, traffic: &Traffic) -> Option<u64> {
+    let request = GetEpochsInfoRequest {
+        version: Some(get_epochs_info_request::Version::V0(
+            get_epochs_info_request::GetEpochsInfoRequestV0 {
+                start_epoch: None,
+                count: 1,
+                ascending: false,
+                prove: false,
+            },
+        )),
+    };
+    let request_size = request.encoded_len();
+    let result = sdk.execute(request, RequestSettings::default()).await;
+    traffic.record(
+        RequestCategory::Query,
+        request_size,
+        result
+            .as_ref()
+            .map_or(0, |response| response.inner.encoded_len()),
+    );
+    let response = result
+        .map_err(|e| tracing::debug!("Failed to fetch platform block height: {:?}", e))
+        .ok()?;
+    match response.inner.version {
+        Some(get_epochs_info_response::Version::V0(response_v0)) => {
+            response_v0.metadata.map(|metadata| metadata.height)
+        }
+        _ => None,
+    }
+}
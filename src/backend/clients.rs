@@ -0,0 +1,107 @@
+//! What the backend asks Platform, Core and Insight, behind traits.
+//!
+//! The Sdk, the Core RPC client and the Insight client implement them against
+//! the network, the [mock](super::mock) clients with canned data for the demo
+//! mode. Strategy runs and state transitions still take the Sdk itself, the
+//! traits cover the connection checks, version detection, identity lookups
+//! and wallet UTXOs the UI can be walked through with offline.
+
+use std::collections::HashMap;
+
+use dapi_grpc::tonic::transport::Uri;
+use dash_sdk::{
+    dashcore_rpc::{Client, RpcApi},
+    platform::{fetch_current_no_parameters::FetchCurrent, Fetch},
+    Sdk,
+};
+use dpp::{
+    block::extended_epoch_info::{v0::ExtendedEpochInfoV0Getters, ExtendedEpochInfo},
+    dashcore::{Address, OutPoint, TxOut},
+    prelude::{Identifier, Identity},
+    util::deserializer::ProtocolVersion,
+};
+
+use super::{connections, insight::InsightError};
+
+/// UTXOs with the number of their confirmations, if known
+pub type UtxosWithConfirmations = HashMap<OutPoint, (TxOut, Option<u32>)>;
+
+#[allow(async_fn_in_trait)]
+pub trait PlatformApi {
+    /// Protocol version of the current epoch, fetched and verified.
+    async fn current_protocol_version(&self) -> Result<ProtocolVersion, String>;
+
+    async fn fetch_identity(&self, id: Identifier) -> Result<Option<Identity>, String>;
+
+    /// DAPI addresses banned after failed requests.
+    fn banned_addresses(&self) -> Vec<String>;
+}
+
+pub trait CoreApi {
+    /// Blocks of the best chain Core knows.
+    fn block_count(&self) -> Result<u64, String>;
+
+    /// UTXOs of `addresses` with at least one confirmation.
+    fn unspent_outputs(&self, addresses: &[&Address]) -> Result<UtxosWithConfirmations, String>;
+}
+
+#[allow(async_fn_in_trait)]
+pub trait InsightApi {
+    fn uri(&self) -> &Uri;
+
+    /// Requests the Insight status endpoint, returning the HTTP status code.
+    async fn status(&self) -> Result<reqwest::StatusCode, InsightError>;
+
+    /// Fetches the unspent transaction outputs (UTXOs) with amounts for the
+    /// specified addresses.
+    async fn utxos_with_amount_for_addresses(
+        &self,
+        addresses: &[&Address],
+    ) -> Result<UtxosWithConfirmations, InsightError>;
+}
+
+impl PlatformApi for Sdk {
+    async fn current_protocol_version(&self) -> Result<ProtocolVersion, String> {
+        ExtendedEpochInfo::fetch_current(self)
+            .await
+            .map(|epoch_info| epoch_info.protocol_version())
+            .map_err(|e| e.to_string())
+    }
+
+    async fn fetch_identity(&self, id: Identifier) -> Result<Option<Identity>, String> {
+        Identity::fetch(self, id).await.map_err(|e| e.to_string())
+    }
+
+    fn banned_addresses(&self) -> Vec<String> {
+        connections::banned_addresses(self)
+    }
+}
+
+impl CoreApi for Client {
+    fn block_count(&self) -> Result<u64, String> {
+        self.get_blockchain_info()
+            .map(|info| info.blocks)
+            .map_err(|e| e.to_string())
+    }
+
+    fn unspent_outputs(&self, addresses: &[&Address]) -> Result<UtxosWithConfirmations, String> {
+        let utxos = self
+            .list_unspent(Some(1), None, Some(addresses), None, None)
+            .map_err(|e| e.to_string())?;
+        Ok(utxos
+            .into_iter()
+            .map(|utxo| {
+                (
+                    OutPoint::new(utxo.txid, utxo.vout),
+                    (
+                        TxOut {
+                            value: utxo.amount.to_sat(),
+                            script_pubkey: utxo.script_pub_key,
+                        },
+                        Some(utxo.confirmations),
+                    ),
+                )
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,392 @@
+//! Connectivity checks for Core RPC, DAPI and Insight.
+//!
+//! Errors coming out of the SDK are usually deep chains that don't say much
+//! about what is actually misconfigured, so each check here maps failures
+//! into a short actionable hint.
+
+use std::{str::FromStr, time::Duration};
+
+use dash_sdk::{sdk::Uri, RequestSettings, Sdk, SdkBuilder};
+use dpp::version::PlatformVersion;
+use rs_dapi_client::AddressList;
+use tokio::net::TcpStream;
+
+use super::clients::{CoreApi, InsightApi, PlatformApi};
+use crate::config::Config;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Outcome of a single connectivity check.
+#[derive(Debug, Clone)]
+pub struct ConnectionCheck {
+    /// What the check is summarized under, several DAPI addresses are all "DAPI"
+    pub group: &'static str,
+    pub service: String,
+    pub ok: bool,
+    pub message: String,
+    /// Address the check is about if the Sdk banned it, to be unbanned
+    pub banned_address: Option<String>,
+}
+
+impl ConnectionCheck {
+    pub(crate) fn ok(
+        group: &'static str,
+        service: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        ConnectionCheck {
+            group,
+            service: service.into(),
+            ok: true,
+            message: message.into(),
+            banned_address: None,
+        }
+    }
+
+    pub(crate) fn failed(
+        group: &'static str,
+        service: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        ConnectionCheck {
+            group,
+            service: service.into(),
+            ok: false,
+            message: message.into(),
+            banned_address: None,
+        }
+    }
+
+    fn banned(address: String) -> Self {
+        ConnectionCheck {
+            banned_address: Some(address.clone()),
+            ..ConnectionCheck::failed(
+                "DAPI",
+                format!("DAPI {}", address),
+                "Banned by the Sdk after failed requests, it is skipped until the ban expires \
+                 or it is unbanned (b)",
+            )
+        }
+    }
+}
+
+/// DAPI address set switched to at runtime and the Sdk built for it.
+pub(crate) struct ActiveAddressSet {
+    pub addresses: String,
+    pub sdk: Sdk,
+}
+
+/// Builds the Sdk talking to the given DAPI addresses.
+pub fn build_sdk(
+    config: &Config,
+    address_list: AddressList,
+    platform_version: &'static PlatformVersion,
+) -> Result<Sdk, String> {
+    let request_settings = RequestSettings {
+        connect_timeout: Some(Duration::from_secs(10)),
+        timeout: Some(Duration::from_secs(10)),
+        retries: None,
+        ban_failed_address: Some(false),
+    };
+    SdkBuilder::new(address_list)
+        .with_version(platform_version)
+        .with_network(config.core_network())
+        .with_core(
+            &config.core_host,
+            config.core_rpc_port,
+            &config.core_rpc_user,
+            &config.core_rpc_password,
+        )
+        .with_settings(request_settings)
+        .build()
+        .map_err(|e| format!("Unable to build the Sdk: {}", e))
+}
+
+/// DAPI addresses of the Sdk banned after failed requests.
+pub(crate) fn banned_addresses(sdk: &Sdk) -> Vec<String> {
+    let address_list = sdk.address_list();
+    address_list
+        .clone()
+        .into_iter()
+        .filter(|address| address_list.is_banned(address))
+        .map(|address| address.uri().to_string())
+        .collect()
+}
+
+/// Lifts the Sdk's ban of a DAPI address, given as listed by
+/// [banned_addresses].
+pub(crate) fn unban_address(sdk: &Sdk, uri: &str) -> Result<String, String> {
+    let address_list = sdk.address_list();
+    let address = address_list
+        .clone()
+        .into_iter()
+        .find(|address| address.uri().to_string() == uri)
+        .ok_or_else(|| format!("{} is not one of the DAPI addresses in use", uri))?;
+    address_list
+        .unban(&address)
+        .map_err(|e| format!("Unable to unban {}: {}", uri, e))?;
+    tracing::info!("unbanned DAPI address {}", uri);
+    Ok(format!("Unbanned {}", uri))
+}
+
+/// Runs all connectivity checks, one per configured service (and one per DAPI
+/// address of the active set).
+pub(crate) async fn check_connections(
+    platform: &impl PlatformApi,
+    dapi_addresses: &str,
+    core_client: &impl CoreApi,
+    insight: &impl InsightApi,
+    config: &Config,
+) -> Vec<ConnectionCheck> {
+    let mut checks = vec![check_core_rpc(core_client, config)];
+    checks.extend(check_dapi_addresses(dapi_addresses).await);
+    checks.extend(
+        platform
+            .banned_addresses()
+            .into_iter()
+            .map(ConnectionCheck::banned),
+    );
+    checks.push(check_platform(platform).await);
+    checks.push(check_insight(insight).await);
+
+    for check in checks.iter() {
+        if check.ok {
+            tracing::info!("connection check {}: {}", check.service, check.message);
+        } else {
+            tracing::warn!("connection check {}: {}", check.service, check.message);
+        }
+    }
+
+    checks
+}
+
+/// Platform version the Sdk should be built with: the configured protocol
+/// version if any, otherwise the one of the network's current epoch. The
+/// latest version known to this build is used if neither can be used, the
+/// given Sdk only needs to reach the network to ask.
+pub async fn detect_platform_version(
+    platform: &impl PlatformApi,
+    config: &Config,
+) -> &'static PlatformVersion {
+    let protocol_version = match config.protocol_version {
+        Some(protocol_version) => protocol_version,
+        None => match platform.current_protocol_version().await {
+            Ok(protocol_version) => protocol_version,
+            Err(e) => {
+                tracing::warn!(
+                    "unable to detect the network's protocol version, using the latest one: {}",
+                    e
+                );
+                return PlatformVersion::latest();
+            }
+        },
+    };
+
+    match PlatformVersion::get(protocol_version) {
+        Ok(platform_version) => {
+            tracing::info!("using protocol version {}", protocol_version);
+            platform_version
+        }
+        Err(_) => {
+            tracing::warn!(
+                "protocol version {} is unknown to this build, using the latest one",
+                protocol_version
+            );
+            PlatformVersion::latest()
+        }
+    }
+}
+
+/// Checks of the wallet and identity being loaded, reported with the
+/// connection checks as the environment health.
+pub(crate) fn check_loaded(wallet_loaded: bool, identity_loaded: bool) -> Vec<ConnectionCheck> {
+    let wallet = if wallet_loaded {
+        ConnectionCheck::ok("Wallet", "Wallet", "loaded")
+    } else {
+        ConnectionCheck::failed(
+            "Wallet",
+            "Wallet",
+            "No wallet loaded — add one by private key on the Wallet screen (w), it funds \
+             identities and strategy runs",
+        )
+    };
+    let identity = if identity_loaded {
+        ConnectionCheck::ok("Identity", "Identity", "loaded")
+    } else {
+        ConnectionCheck::failed(
+            "Identity",
+            "Identity",
+            "No identity loaded — register or load one on the Identities screen (i), it signs \
+             documents, contracts and strategy transitions",
+        )
+    };
+    vec![wallet, identity]
+}
+
+/// One status per check group, in order of first appearance. A group is only
+/// ok if all its checks are.
+pub(crate) fn summarize_checks(checks: &[ConnectionCheck]) -> Vec<(&'static str, bool)> {
+    let mut groups: Vec<(&'static str, bool)> = Vec::new();
+    for check in checks {
+        match groups.iter_mut().find(|(group, _)| *group == check.group) {
+            Some((_, ok)) => *ok &= check.ok,
+            None => groups.push((check.group, check.ok)),
+        }
+    }
+    groups
+}
+
+/// Renders connection checks as a human readable report.
+pub(crate) fn format_connection_checks(checks: &[ConnectionCheck]) -> String {
+    checks
+        .iter()
+        .map(|check| {
+            format!(
+                "[{}] {}: {}",
+                if check.ok { "OK" } else { "FAIL" },
+                check.service,
+                check.message
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn check_core_rpc(core_client: &impl CoreApi, config: &Config) -> ConnectionCheck {
+    let service = format!("Core RPC {}:{}", config.core_host, config.core_rpc_port);
+    match core_client.block_count() {
+        Ok(blocks) => ConnectionCheck::ok("Core", service, format!("{} blocks", blocks)),
+        Err(e) => ConnectionCheck::failed("Core", service, core_rpc_hint(&e, config)),
+    }
+}
+
+fn core_rpc_hint(error: &str, config: &Config) -> String {
+    let lowercase = error.to_lowercase();
+    if lowercase.contains("refused") {
+        format!(
+            "Core RPC refused connection on {} — is dashd running with -server?",
+            config.core_rpc_port
+        )
+    } else if lowercase.contains("401") || lowercase.contains("unauthorized") {
+        "Core RPC rejected the credentials — check EXPLORER_CORE_RPC_USER and \
+         EXPLORER_CORE_RPC_PASSWORD against rpcuser/rpcpassword in dash.conf"
+            .to_owned()
+    } else if lowercase.contains("timed out") || lowercase.contains("timeout") {
+        format!(
+            "Core RPC at {}:{} timed out — check EXPLORER_CORE_HOST and that rpcallowip permits \
+             this machine",
+            config.core_host, config.core_rpc_port
+        )
+    } else if lowercase.contains("dns") || lowercase.contains("resolve") {
+        format!(
+            "Unable to resolve Core host {} — check EXPLORER_CORE_HOST",
+            config.core_host
+        )
+    } else {
+        format!("Core RPC error: {}", error)
+    }
+}
+
+async fn check_dapi_addresses(dapi_addresses: &str) -> Vec<ConnectionCheck> {
+    let mut checks = Vec::new();
+
+    for address in dapi_addresses
+        .split(',')
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+    {
+        let service = format!("DAPI {}", address);
+        let Ok(uri) = Uri::from_str(address) else {
+            checks.push(ConnectionCheck::failed(
+                "DAPI",
+                service,
+                "Not a valid URI — expected e.g. https://127.0.0.1:2443 in EXPLORER_DAPI_ADDRESSES",
+            ));
+            continue;
+        };
+        let Some(host) = uri.host() else {
+            checks.push(ConnectionCheck::failed("DAPI", service, "URI has no host"));
+            continue;
+        };
+        let port = uri
+            .port_u16()
+            .unwrap_or(if uri.scheme_str() == Some("http") {
+                80
+            } else {
+                443
+            });
+
+        let check =
+            match tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect((host, port))).await {
+                Ok(Ok(_)) => ConnectionCheck::ok("DAPI", service, "reachable"),
+                Ok(Err(e)) if e.kind() == std::io::ErrorKind::ConnectionRefused => {
+                    ConnectionCheck::failed(
+                        "DAPI",
+                        service,
+                        format!(
+                        "Connection refused on port {} — is the node running and is DAPI exposed \
+                         on this port?",
+                        port
+                    ),
+                    )
+                }
+                Ok(Err(e)) => {
+                    ConnectionCheck::failed("DAPI", service, format!("Unable to connect: {}", e))
+                }
+                Err(_) => ConnectionCheck::failed(
+                    "DAPI",
+                    service,
+                    format!(
+                        "No response within {}s — check the address and any firewall in between",
+                        CONNECT_TIMEOUT.as_secs()
+                    ),
+                ),
+            };
+        checks.push(check);
+    }
+
+    checks
+}
+
+async fn check_platform(platform: &impl PlatformApi) -> ConnectionCheck {
+    let service = "Platform";
+    match platform.current_protocol_version().await {
+        Ok(_) => ConnectionCheck::ok(service, service, "current epoch info fetched and verified"),
+        Err(error) => {
+            let hint = if error.to_lowercase().contains("quorum") {
+                "Unable to verify proofs — Core RPC is used to fetch quorum keys, check the Core \
+                 RPC connection"
+                    .to_owned()
+            } else if error.to_lowercase().contains("no available addresses") {
+                "No DAPI addresses are available — all of them failed or are banned".to_owned()
+            } else {
+                format!("Platform query failed: {}", error)
+            };
+            ConnectionCheck::failed(service, service, hint)
+        }
+    }
+}
+
+async fn check_insight(insight: &impl InsightApi) -> ConnectionCheck {
+    let service = format!("Insight {}", insight.uri());
+    match insight.status().await {
+        Ok(status) if status.is_success() => ConnectionCheck::ok("Insight", service, "reachable"),
+        Ok(status) => ConnectionCheck::failed(
+            "Insight",
+            service,
+            format!(
+                "Responded with {} — check that EXPLORER_INSIGHT_API_URL ends with the API path \
+                 (e.g. /insight-api)",
+                status
+            ),
+        ),
+        Err(e) => ConnectionCheck::failed(
+            "Insight",
+            service,
+            format!(
+                "Unreachable ({}) — only needed as a fallback when Core RPC can't list UTXOs",
+                e
+            ),
+        ),
+    }
+}
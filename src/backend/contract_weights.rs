@@ -0,0 +1,107 @@
+//! Shares of the document operations' load going to each contract.
+//!
+//! Document operations each have their own frequency, so with many start
+//! contracts the load is spread by how many operations each contract got
+//! rather than by how busy it should be. Weights set the share of the
+//! document load a contract takes, e.g. 8 and 2 send 80% of it to the first
+//! contract. The total document load stays the same, the frequencies of the
+//! operations of each weighted contract are scaled so their contract gets its
+//! share of it.
+
+use std::collections::BTreeMap;
+
+use dpp::{
+    data_contract::accessors::v0::DataContractV0Getters, platform_value::string_encoding::Encoding,
+};
+use strategy_tests::{operations::OperationType, Strategy};
+
+use super::dry_run::expected_per_block;
+
+/// Weight of each contract by its Base58 ID, as contract aliases are keyed
+pub(crate) type ContractWeights = BTreeMap<String, f64>;
+
+/// Frequency multiplier of each operation of the strategy, in order. Only
+/// document operations on weighted contracts get one other than 1.
+pub(crate) fn operation_multipliers(strategy: &Strategy, weights: &ContractWeights) -> Vec<f64> {
+    let contracts: Vec<Option<String>> = strategy
+        .operations
+        .iter()
+        .map(|operation| match &operation.op_type {
+            OperationType::Document(op) => Some(op.contract.id().to_string(Encoding::Base58))
+                .filter(|contract| weights.contains_key(contract)),
+            _ => None,
+        })
+        .collect();
+
+    let mut loads: BTreeMap<&str, f64> = BTreeMap::new();
+    for (operation, contract) in strategy.operations.iter().zip(&contracts) {
+        if let Some(contract) = contract {
+            *loads.entry(contract).or_default() += expected_per_block(&operation.frequency);
+        }
+    }
+    // Contracts without load can't be given a share of it
+    loads.retain(|_, load| *load > 0.0);
+    let total_load: f64 = loads.values().sum();
+    let total_weight: f64 = loads.keys().map(|contract| weights[*contract]).sum();
+
+    contracts
+        .iter()
+        .map(|contract| {
+            match contract
+                .as_deref()
+                .and_then(|contract| loads.get(contract).map(|load| (contract, load)))
+            {
+                Some((contract, load)) if total_weight > 0.0 => {
+                    weights[contract] / total_weight * total_load / load
+                }
+                _ => 1.0,
+            }
+        })
+        .collect()
+}
+
+/// Parses weights written one contract per line as `contract: weight`, text
+/// after a `#` is ignored.
+pub(crate) fn parse_weights(input: &str) -> Result<ContractWeights, String> {
+    input
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let (contract, weight) = line
+                .rsplit_once(':')
+                .ok_or_else(|| format!("Expected `contract: weight`, got `{}`", line))?;
+            let weight = weight
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|weight| *weight >= 0.0)
+                .ok_or_else(|| format!("Invalid weight `{}`", weight.trim()))?;
+            Ok((contract.trim().to_owned(), weight))
+        })
+        .collect()
+}
+
+/// Text to edit the weights of `contracts`, given with their display names,
+/// starting from the current ones.
+pub(crate) fn weights_template(
+    contracts: &[(String, String)],
+    weights: &ContractWeights,
+) -> String {
+    let mut template = "# Share of the document operations' load per contract, e.g. 8 and 2 \
+                        send 80% of it to the first one.\n# Contracts left out keep their \
+                        operations' frequencies, remove all lines to weigh none.\n"
+        .to_owned();
+    for (contract, display_name) in contracts {
+        let line = match weights.get(contract) {
+            Some(weight) => format!("{}: {}", contract, weight),
+            None => format!("# {}: 1", contract),
+        };
+        if display_name != contract {
+            template.push_str(&format!("{}  # {}\n", line, display_name));
+        } else {
+            template.push_str(&format!("{}\n", line));
+        }
+    }
+    template
+}
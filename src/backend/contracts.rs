@@ -1,25 +1,46 @@
 //! Contracts backend.
-use std::sync::Arc;
+use std::{collections::HashSet, fmt, sync::Arc};
 
 use dash_sdk::{
-    platform::{DocumentQuery, Fetch},
+    platform::{
+        transition::{broadcast::BroadcastStateTransition, put_contract::PutContract},
+        DocumentQuery, Fetch, FetchMany,
+    },
     Sdk,
 };
 use dpp::system_data_contracts::withdrawals_contract;
 use dpp::{
-    data_contract::accessors::v0::DataContractV0Getters,
+    data_contract::{
+        accessors::v0::{DataContractV0Getters, DataContractV0Setters},
+        conversion::value::v0::DataContractValueConversionMethodsV0,
+        document_type::accessors::DocumentTypeV0Getters,
+    },
     document::{Document, DocumentV0Getters},
+    identity::{accessors::IdentityGettersV0, KeyType, Purpose, SecurityLevel},
     platform_value::{string_encoding::Encoding, Value},
-    prelude::{DataContract, Identifier},
+    prelude::{DataContract, Identifier, Identity},
+    state_transition::batch_transition::{
+        methods::v0::DocumentsBatchTransitionMethodsV0, BatchTransition,
+    },
     system_data_contracts::{dashpay_contract, dpns_contract},
+    version::PlatformVersion,
 };
 use drive::query::{WhereClause, WhereOperator};
+use itertools::Itertools;
+use serde_json::Value as JsonValue;
+use simple_signer::signer::SimpleSigner;
 use tokio::sync::Mutex;
 
 use super::{
-    as_json_string, state::KnownContractsMap, AppState, AppStateUpdate, BackendEvent, Task,
+    as_json_string, rate_limiter::RateLimiter, state::KnownContractsMap, AppState, AppStateUpdate,
+    BackendEvent, Task,
 };
 
+/// Documents fetched at once when deleting the loaded identity's documents
+const DELETE_PAGE_SIZE: u32 = 100;
+/// Deletes between progress logs
+const DELETE_PROGRESS_INTERVAL: u64 = 10;
+
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) enum ContractTask {
     FetchDashpayContract,
@@ -28,9 +49,28 @@ pub(crate) enum ContractTask {
     RemoveContract(String),
     FetchContract(String),
     ClearKnownContracts,
+    /// Fetches all known contracts again, replacing the stored copies
+    RefreshKnownContracts,
+    ValidateContractJson(String),
+    RegisterContractJson(String),
+    /// Compares a known contract with its current version on platform
+    DiffContract(String),
+    /// Deletes the documents of a known contract owned by the loaded identity,
+    /// at most `rate` per second (0 for no limit)
+    DeleteOwnDocuments {
+        contract_name: String,
+        rate: f64,
+    },
 }
 
 impl AppState {
+    /// Asks the deletion of own documents going on to stop before its next
+    /// document, it then reports what was deleted. Returns `false` if none is
+    /// going on.
+    pub fn cancel_document_deletion(&self) -> bool {
+        self.deletion_cancel.send(true).is_ok()
+    }
+
     pub(super) async fn run_contract_task<'s>(
         &self,
         sdk: &Sdk,
@@ -166,6 +206,98 @@ impl AppState {
                     },
                 }
             }
+            ContractTask::RefreshKnownContracts => {
+                let mut contracts_lock = known_contracts.lock().await;
+                let mut refreshed = 0;
+                let mut errors = Vec::new();
+                for (name, contract) in contracts_lock.iter_mut() {
+                    match DataContract::fetch(sdk, contract.id()).await {
+                        Ok(Some(platform_contract)) => {
+                            *contract = platform_contract;
+                            refreshed += 1;
+                        }
+                        Ok(None) => errors.push(format!("{}: no longer on platform", name)),
+                        Err(e) => errors.push(format!("{}: {}", name, e)),
+                    }
+                }
+
+                let mut summary = format!("Refreshed {} known contracts", refreshed);
+                if !errors.is_empty() {
+                    summary.push_str(&format!(
+                        ", kept the stored copy of:\n{}",
+                        errors.join("\n")
+                    ));
+                }
+                BackendEvent::TaskCompletedStateChange {
+                    task: Task::Contract(task),
+                    execution_result: Ok(summary.into()),
+                    app_state_update: AppStateUpdate::KnownContracts(contracts_lock),
+                }
+            }
+            ContractTask::ValidateContractJson(ref json) => {
+                let execution_result = contract_from_json(json, sdk.version()).map(|contract| {
+                    format!(
+                        "Contract is valid.\n\nDocument types: {}",
+                        contract.document_types().keys().join(", ")
+                    )
+                    .into()
+                });
+
+                BackendEvent::TaskCompleted {
+                    task: Task::Contract(task),
+                    execution_result,
+                }
+            }
+            ContractTask::RegisterContractJson(ref json) => {
+                match self.register_contract_from_json(sdk, json).await {
+                    Ok(contract) => {
+                        let contract_id = contract.id().to_string(Encoding::Base58);
+                        let mut contracts_lock = known_contracts.lock().await;
+                        contracts_lock.insert(contract_id.clone(), contract);
+
+                        BackendEvent::TaskCompletedStateChange {
+                            task: Task::Contract(task),
+                            execution_result: Ok(
+                                format!("Registered contract {}", contract_id).into()
+                            ),
+                            app_state_update: AppStateUpdate::KnownContracts(contracts_lock),
+                        }
+                    }
+                    Err(e) => BackendEvent::TaskCompleted {
+                        task: Task::Contract(task),
+                        execution_result: Err(e),
+                    },
+                }
+            }
+            ContractTask::DiffContract(ref contract_name) => {
+                let local_contract = known_contracts.lock().await.get(contract_name).cloned();
+                let execution_result = diff_against_platform(sdk, local_contract)
+                    .await
+                    .map(Into::into);
+
+                BackendEvent::TaskCompleted {
+                    task: Task::Contract(task),
+                    execution_result,
+                }
+            }
+            ContractTask::DeleteOwnDocuments {
+                ref contract_name,
+                rate,
+            } => {
+                let contract = known_contracts.lock().await.get(contract_name).cloned();
+                let execution_result = match contract {
+                    Some(contract) => self
+                        .delete_own_documents(sdk, contract, rate)
+                        .await
+                        .map(Into::into),
+                    None => Err("No known contract with that name".to_owned()),
+                };
+
+                BackendEvent::TaskCompleted {
+                    task: Task::Contract(task),
+                    execution_result,
+                }
+            }
             ContractTask::ClearKnownContracts => {
                 let mut known_contracts = self.known_contracts.lock().await;
                 known_contracts.clear();
@@ -177,6 +309,323 @@ impl AppState {
             }
         }
     }
+
+    /// Registers a contract from its JSON definition, owned by the loaded
+    /// identity.
+    async fn register_contract_from_json(
+        &self,
+        sdk: &Sdk,
+        json: &str,
+    ) -> Result<DataContract, String> {
+        let mut contract = contract_from_json(json, sdk.version())?;
+
+        let loaded_identity_lock = self.loaded_identity.lock().await;
+        let Some(identity) = loaded_identity_lock.as_ref() else {
+            return Err("No identity loaded".to_owned());
+        };
+        let Some(public_key) = identity.get_first_public_key_matching(
+            Purpose::AUTHENTICATION,
+            HashSet::from([SecurityLevel::CRITICAL, SecurityLevel::HIGH]),
+            HashSet::from([KeyType::ECDSA_SECP256K1, KeyType::BLS12_381]),
+            false,
+        ) else {
+            return Err("Loaded identity has no critical or high authentication key".to_owned());
+        };
+
+        let identity_private_keys_lock = self.known_identities_private_keys.lock().await;
+        let mut signer = SimpleSigner::default();
+        let Identity::V0(identity_v0) = identity;
+        for (key_id, public_key) in &identity_v0.public_keys {
+            if let Some(private_key_bytes) =
+                identity_private_keys_lock.get(&(identity_v0.id, *key_id))
+            {
+                signer.private_keys.insert(
+                    public_key.clone(),
+                    private_key_bytes
+                        .clone()
+                        .try_into()
+                        .expect("Expected private key to be 32 bytes"),
+                );
+            }
+        }
+        drop(identity_private_keys_lock);
+
+        // Contract id is derived from the owner and the identity nonce on registration
+        contract.set_owner_id(identity.id());
+
+        contract
+            .put_to_platform_and_wait_for_response(sdk, public_key.clone(), &signer, None)
+            .await
+            .map_err(|e| format!("Failed to register contract: {}", e))
+    }
+
+    /// Deletes the documents of every deletable type of the contract owned by
+    /// the loaded identity, one at a time. Types without an index on
+    /// `$ownerId` can't be queried by owner and are skipped. Stops before the
+    /// next document once [AppState::cancel_document_deletion] is called.
+    async fn delete_own_documents(
+        &self,
+        sdk: &Sdk,
+        contract: DataContract,
+        rate: f64,
+    ) -> Result<String, String> {
+        let Some(identity) = self.loaded_identity.lock().await.clone() else {
+            return Err("No identity loaded".to_owned());
+        };
+        let Some(public_key) = identity
+            .get_first_public_key_matching(
+                Purpose::AUTHENTICATION,
+                HashSet::from([SecurityLevel::CRITICAL, SecurityLevel::HIGH]),
+                HashSet::from([KeyType::ECDSA_SECP256K1, KeyType::BLS12_381]),
+                false,
+            )
+            .cloned()
+        else {
+            return Err("Loaded identity has no critical or high authentication key".to_owned());
+        };
+        let signer = self.identity_signer(&identity).await;
+        let rate_limiter = RateLimiter::new(rate);
+        self.deletion_cancel.send_replace(false);
+        let mut cancel = self.deletion_cancel.subscribe();
+        let mut cancelled = false;
+
+        let mut identity_contract_nonce = sdk
+            .get_identity_contract_nonce(identity.id(), contract.id(), true, None)
+            .await
+            .map_err(|e| format!("Failed to fetch the identity contract nonce: {}", e))?;
+
+        let contract = Arc::new(contract);
+        let mut deleted = 0;
+        let mut summary = Vec::new();
+        for (document_type_name, document_type) in contract.document_types() {
+            if !document_type.documents_can_be_deleted() {
+                summary.push(format!(
+                    "{}: documents can't be deleted",
+                    document_type_name
+                ));
+                continue;
+            }
+            let query = DocumentQuery {
+                data_contract: Arc::clone(&contract),
+                document_type_name: document_type_name.clone(),
+                where_clauses: vec![WhereClause {
+                    field: "$ownerId".to_string(),
+                    operator: WhereOperator::Equal,
+                    value: Value::Identifier(identity.id().to_buffer()),
+                }],
+                order_by_clauses: vec![],
+                limit: DELETE_PAGE_SIZE,
+                start: None,
+            };
+
+            let mut type_deleted = 0;
+            let mut type_failed = 0;
+            // Deleted documents drop out of the query, so the first page is
+            // fetched again until it's empty or nothing of it could be deleted
+            loop {
+                let documents = match Document::fetch_many(sdk, query.clone()).await {
+                    Ok(documents) => documents,
+                    Err(e) => {
+                        summary.push(format!(
+                            "{}: can't query by owner: {}",
+                            document_type_name, e
+                        ));
+                        break;
+                    }
+                };
+                let page: Vec<Document> = documents.into_values().flatten().collect();
+                if page.is_empty() {
+                    break;
+                }
+                let mut page_deleted = 0;
+                for document in page {
+                    if let Some(rate_limiter) = &rate_limiter {
+                        tokio::select! {
+                            _ = rate_limiter.acquire() => {}
+                            _ = cancel.wait_for(|cancelled| *cancelled) => {}
+                        }
+                    }
+                    if *cancel.borrow() {
+                        cancelled = true;
+                        break;
+                    }
+                    let document_id = document.id();
+                    let result = BatchTransition::new_document_deletion_transition_from_document(
+                        document,
+                        document_type.as_ref(),
+                        &public_key,
+                        identity_contract_nonce,
+                        0,
+                        None,
+                        &signer,
+                        sdk.version(),
+                        None,
+                        None,
+                        None,
+                    )
+                    .map_err(|e| e.to_string());
+                    identity_contract_nonce += 1;
+                    let result = match result {
+                        Ok(transition) => transition
+                            .broadcast_and_wait(sdk, None)
+                            .await
+                            .map(|_| ())
+                            .map_err(|e| e.to_string()),
+                        Err(e) => Err(e),
+                    };
+                    match result {
+                        Ok(()) => {
+                            page_deleted += 1;
+                            deleted += 1;
+                            if deleted % DELETE_PROGRESS_INTERVAL == 0 {
+                                tracing::info!("Deleted {} documents so far", deleted);
+                            }
+                        }
+                        Err(e) => {
+                            type_failed += 1;
+                            tracing::debug!(
+                                "Failed to delete {} document {}: {}",
+                                document_type_name,
+                                document_id.to_string(Encoding::Base58),
+                                e
+                            );
+                            // A failed transition may not have used its nonce
+                            if let Ok(nonce) = sdk
+                                .get_identity_contract_nonce(
+                                    identity.id(),
+                                    contract.id(),
+                                    true,
+                                    None,
+                                )
+                                .await
+                            {
+                                identity_contract_nonce = nonce;
+                            }
+                        }
+                    }
+                }
+                type_deleted += page_deleted;
+                if page_deleted == 0 || cancelled {
+                    break;
+                }
+            }
+            if type_deleted > 0 || type_failed > 0 {
+                summary.push(format!(
+                    "{}: {} deleted, {} failed",
+                    document_type_name, type_deleted, type_failed
+                ));
+            }
+            if cancelled {
+                break;
+            }
+        }
+
+        tracing::info!("Deleted {} documents owned by the loaded identity", deleted);
+        Ok(format!(
+            "{}Deleted {} documents owned by the loaded identity\n\n{}",
+            if cancelled {
+                "Cancelled, documents left are kept. "
+            } else {
+                ""
+            },
+            deleted,
+            summary.join("\n")
+        ))
+    }
+}
+
+/// Fetches the on-chain version of a locally stored contract and lists the
+/// fields that differ between the two.
+async fn diff_against_platform(
+    sdk: &Sdk,
+    local_contract: Option<DataContract>,
+) -> Result<String, String> {
+    let local_contract = local_contract.ok_or("No known contract with that name")?;
+    let platform_contract = DataContract::fetch(sdk, local_contract.id())
+        .await
+        .map_err(|e| format!("Failed to fetch the contract: {}", e))?
+        .ok_or("The contract doesn't exist on platform")?;
+
+    let to_json = |contract: &DataContract| {
+        serde_json::to_value(contract).map_err(|e| format!("Cannot serialize contract: {}", e))
+    };
+    let mut differences = Vec::new();
+    json_diff(
+        "",
+        &to_json(&local_contract)?,
+        &to_json(&platform_contract)?,
+        &mut differences,
+    );
+
+    if differences.is_empty() {
+        Ok(format!(
+            "Local copy matches platform (version {})",
+            platform_contract.version()
+        ))
+    } else {
+        Ok(format!(
+            "Local version {}, platform version {}. Fetch the contract again to update the local \
+             copy.\n\n{}",
+            local_contract.version(),
+            platform_contract.version(),
+            differences.join("\n")
+        ))
+    }
+}
+
+/// Collects the paths where two JSON values differ, as `local -> platform`.
+fn json_diff(path: &str, local: &JsonValue, platform: &JsonValue, differences: &mut Vec<String>) {
+    let child_path = |key: &dyn fmt::Display| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", path, key)
+        }
+    };
+    match (local, platform) {
+        (JsonValue::Object(local_map), JsonValue::Object(platform_map)) => {
+            for key in local_map.keys().chain(platform_map.keys()).unique() {
+                match (local_map.get(key), platform_map.get(key)) {
+                    (Some(local), Some(platform)) => {
+                        json_diff(&child_path(key), local, platform, differences)
+                    }
+                    (Some(local), None) => differences.push(format!(
+                        "{}: only in local copy: {}",
+                        child_path(key),
+                        local
+                    )),
+                    (None, Some(platform)) => differences.push(format!(
+                        "{}: only on platform: {}",
+                        child_path(key),
+                        platform
+                    )),
+                    (None, None) => {}
+                }
+            }
+        }
+        (JsonValue::Array(local_items), JsonValue::Array(platform_items))
+            if local_items.len() == platform_items.len() =>
+        {
+            for (index, (local, platform)) in local_items.iter().zip(platform_items).enumerate() {
+                json_diff(&child_path(&index), local, platform, differences);
+            }
+        }
+        (local, platform) if local != platform => {
+            differences.push(format!("{}: {} -> {}", path, local, platform))
+        }
+        _ => {}
+    }
+}
+
+/// Parses and validates a contract JSON with DPP.
+pub(crate) fn contract_from_json(
+    json: &str,
+    platform_version: &PlatformVersion,
+) -> Result<DataContract, String> {
+    let value: Value = serde_json::from_str(json).map_err(|e| format!("Invalid JSON: {}", e))?;
+
+    DataContract::from_value(value, true, platform_version)
+        .map_err(|e| format!("Invalid contract: {}", e))
 }
 
 pub async fn get_dpns_name(sdk: &Sdk, id: &Identifier) -> Option<String> {
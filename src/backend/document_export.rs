@@ -0,0 +1,132 @@
+//! Export of document query results to files.
+//!
+//! Documents are flattened into one level of fields, nested objects giving
+//! dotted names (`records.identity`) and arrays kept as JSON strings, so a
+//! result set fits the rows and columns of a CSV file.
+
+use dpp::document::Document;
+use serde_json::{Map, Value};
+
+pub(crate) const EXPORTS_DIR: &str = "supporting_files/document_exports";
+
+#[derive(Debug, Clone, Copy, PartialEq, strum::Display)]
+pub(crate) enum DocumentExportFormat {
+    #[strum(to_string = "JSON")]
+    Json,
+    #[strum(to_string = "CSV")]
+    Csv,
+}
+
+impl DocumentExportFormat {
+    pub(crate) fn extension(&self) -> &'static str {
+        match self {
+            DocumentExportFormat::Json => "json",
+            DocumentExportFormat::Csv => "csv",
+        }
+    }
+}
+
+/// Serializes documents, keeping only `fields` if any are given. A JSON export
+/// of all fields keeps the documents as they are, otherwise they are
+/// flattened.
+pub(crate) fn export_documents(
+    documents: &[Document],
+    format: DocumentExportFormat,
+    fields: &[String],
+) -> Result<String, String> {
+    if format == DocumentExportFormat::Json && fields.is_empty() {
+        return serde_json::to_string_pretty(documents)
+            .map_err(|e| format!("Cannot serialize documents: {}", e));
+    }
+
+    let rows = documents
+        .iter()
+        .map(|document| {
+            let value = serde_json::to_value(document)
+                .map_err(|e| format!("Cannot serialize document: {}", e))?;
+            let mut row = Map::new();
+            flatten("", value, &mut row);
+            Ok(row)
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    // Columns in order of first appearance unless chosen
+    let columns: Vec<String> = if fields.is_empty() {
+        let mut columns = Vec::new();
+        for key in rows.iter().flat_map(|row| row.keys()) {
+            if !columns.contains(key) {
+                columns.push(key.clone());
+            }
+        }
+        columns
+    } else {
+        fields.to_vec()
+    };
+
+    match format {
+        DocumentExportFormat::Json => {
+            let rows: Vec<Map<String, Value>> = rows
+                .into_iter()
+                .map(|mut row| {
+                    columns
+                        .iter()
+                        .map(|column| (column.clone(), row.remove(column).unwrap_or(Value::Null)))
+                        .collect()
+                })
+                .collect();
+            serde_json::to_string_pretty(&rows)
+                .map_err(|e| format!("Cannot serialize documents: {}", e))
+        }
+        DocumentExportFormat::Csv => {
+            let mut csv = csv_line(columns.iter().cloned());
+            for row in rows {
+                csv.push_str(&csv_line(columns.iter().map(
+                    |column| match row.get(column) {
+                        None | Some(Value::Null) => String::new(),
+                        Some(Value::String(value)) => value.clone(),
+                        Some(value) => value.to_string(),
+                    },
+                )));
+            }
+            Ok(csv)
+        }
+    }
+}
+
+fn flatten(prefix: &str, value: Value, row: &mut Map<String, Value>) {
+    match value {
+        Value::Object(fields) => {
+            for (key, value) in fields {
+                let name = if prefix.is_empty() {
+                    key
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten(&name, value, row);
+            }
+        }
+        Value::Array(_) => {
+            row.insert(prefix.to_owned(), Value::String(value.to_string()));
+        }
+        value => {
+            row.insert(prefix.to_owned(), value);
+        }
+    }
+}
+
+/// Joins fields into a CSV line, quoting the ones containing a separator, a
+/// quote or a line break.
+fn csv_line(fields: impl Iterator<Item = String>) -> String {
+    let mut line = fields
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    line.push('\n');
+    line
+}
@@ -0,0 +1,501 @@
+//! Generators of the values of documents created by strategy runs.
+//!
+//! Document inserts fill their documents with random values of each
+//! property's type, which exercises storage but says little about how
+//! Platform handles the values it meets in practice. A generator set for an
+//! insert operation's contract and document type replaces the values of the
+//! documents the run creates for them, before the batch is signed again with
+//! the same key:
+//! - realistic: names, addresses, emails and the like picked by property name
+//! - boundary: the smallest and largest values and sizes the property allows
+//! - unicode stress: strings of combining marks, bidirectional text, emoji
+//!   sequences and invisible characters
+//!
+//! Properties a generator has nothing better for, such as identifiers and
+//! nested objects, keep their random values.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bincode::{Decode, Encode};
+use dash_sdk::platform::DataContract;
+use dpp::{
+    data_contract::{
+        accessors::v0::DataContractV0Getters,
+        document_type::{
+            accessors::DocumentTypeV0Getters,
+            property::{ByteArrayPropertySizes, StringPropertySizes},
+            DocumentPropertyType, DocumentTypeRef,
+        },
+    },
+    identity::{
+        accessors::IdentityGettersV0,
+        identity_public_key::accessors::v0::IdentityPublicKeyGettersV0, Identity,
+    },
+    platform_value::{string_encoding::Encoding, Identifier, Value},
+    state_transition::{
+        batch_transition::{
+            batched_transition::document_transition::DocumentTransition,
+            document_base_transition::v0::v0_methods::DocumentBaseTransitionV0Methods,
+            BatchTransition, DocumentCreateTransition,
+        },
+        StateTransition,
+    },
+};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+use simple_signer::signer::SimpleSigner;
+
+/// Length of generated strings whose property sets no maximum
+const DEFAULT_MAX_LENGTH: u16 = 255;
+/// Milliseconds in a year, the span of realistic dates before now
+const YEAR_MS: u64 = 365 * 24 * 3_600_000;
+/// Last millisecond of the year 9999
+const MAX_DATE_MS: u64 = 253_402_300_799_999;
+
+/// Values of the properties of generated documents.
+pub(crate) trait DocumentGenerator {
+    /// Value of the property `name`, `None` keeps its random value.
+    fn value(
+        &self,
+        name: &str,
+        property_type: &DocumentPropertyType,
+        rng: &mut StdRng,
+    ) -> Option<Value>;
+
+    /// Replaces the values of the properties a document was filled with.
+    fn fill(
+        &self,
+        document_type: DocumentTypeRef,
+        data: &mut BTreeMap<String, Value>,
+        rng: &mut StdRng,
+    ) {
+        for (name, value) in data.iter_mut() {
+            let Some(property) = document_type.properties().get(name) else {
+                continue;
+            };
+            if let Some(generated) = self.value(name, &property.property_type, rng) {
+                *value = generated;
+            }
+        }
+    }
+}
+
+/// Generator of an insert operation, stored with the strategy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Encode, Decode)]
+pub enum DocumentGeneratorKind {
+    /// The insert operation's own random fill
+    #[default]
+    Random,
+    Realistic,
+    Boundary,
+    UnicodeStress,
+}
+
+impl DocumentGeneratorKind {
+    pub(crate) const ALL: [DocumentGeneratorKind; 4] = [
+        DocumentGeneratorKind::Random,
+        DocumentGeneratorKind::Realistic,
+        DocumentGeneratorKind::Boundary,
+        DocumentGeneratorKind::UnicodeStress,
+    ];
+
+    /// `None` for the random fill, which needs no generator.
+    pub(crate) fn generator(&self) -> Option<&'static dyn DocumentGenerator> {
+        match self {
+            DocumentGeneratorKind::Random => None,
+            DocumentGeneratorKind::Realistic => Some(&RealisticGenerator),
+            DocumentGeneratorKind::Boundary => Some(&BoundaryGenerator),
+            DocumentGeneratorKind::UnicodeStress => Some(&UnicodeStressGenerator),
+        }
+    }
+}
+
+impl Display for DocumentGeneratorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DocumentGeneratorKind::Random => "Random",
+            DocumentGeneratorKind::Realistic => "Realistic",
+            DocumentGeneratorKind::Boundary => "Boundary values",
+            DocumentGeneratorKind::UnicodeStress => "Unicode stress",
+        })
+    }
+}
+
+/// Generators of a strategy by Base58 contract ID and document type name.
+/// Insert operations on the same document type share their generator, their
+/// documents can't be told apart once created.
+pub(crate) type DocumentGenerators = BTreeMap<(String, String), DocumentGeneratorKind>;
+
+/// Regenerates the data of the document creates of a batch transition whose
+/// contract and document type have a generator, and signs the batch again with
+/// the key of its owner among `identities`. Returns whether the transition
+/// was changed, it's left as is if it can't be signed again.
+pub(crate) fn regenerate_documents(
+    transition: &mut StateTransition,
+    generators: &DocumentGenerators,
+    known_contracts: &BTreeMap<String, DataContract>,
+    identities: &[Identity],
+    signer: &SimpleSigner,
+    rng: &mut StdRng,
+) -> bool {
+    let StateTransition::Batch(BatchTransition::V0(batch)) = &*transition else {
+        return false;
+    };
+    let mut batch = batch.clone();
+
+    let mut regenerated = false;
+    for document_transition in batch.transitions.iter_mut() {
+        let DocumentTransition::Create(DocumentCreateTransition::V0(create_tx)) =
+            document_transition
+        else {
+            continue;
+        };
+        let contract_id = create_tx
+            .base
+            .data_contract_id()
+            .to_string(Encoding::Base58);
+        let document_type_name = create_tx.base.document_type_name().clone();
+        let Some(generator) = generators
+            .get(&(contract_id.clone(), document_type_name.clone()))
+            .and_then(|kind| kind.generator())
+        else {
+            continue;
+        };
+        let Some(document_type) = known_contracts
+            .get(&contract_id)
+            .and_then(|contract| contract.document_type_for_name(&document_type_name).ok())
+        else {
+            continue;
+        };
+        generator.fill(document_type, &mut create_tx.data, rng);
+        regenerated = true;
+    }
+    if !regenerated {
+        return false;
+    }
+
+    let Some(public_key) = identities
+        .iter()
+        .find(|identity| identity.id() == batch.owner_id)
+        .and_then(|identity| identity.public_keys().get(&batch.signature_public_key_id))
+        .cloned()
+    else {
+        return false;
+    };
+    let security_level = public_key.security_level();
+
+    let mut regenerated_transition = StateTransition::Batch(BatchTransition::V0(batch));
+    if regenerated_transition
+        .sign_external(
+            &public_key,
+            signer,
+            Some(move |_: Identifier, _: String| Ok(security_level)),
+        )
+        .is_err()
+    {
+        return false;
+    }
+    *transition = regenerated_transition;
+    true
+}
+
+/// Bounds of generated string lengths, in characters.
+fn length_bounds(sizes: &StringPropertySizes) -> (usize, usize) {
+    let min = sizes.min_length.unwrap_or(0) as usize;
+    let max = sizes.max_length.unwrap_or(DEFAULT_MAX_LENGTH) as usize;
+    (min, max.max(min))
+}
+
+/// `text` cut or padded to fit `sizes`, counting characters.
+fn fit_length(text: String, sizes: &StringPropertySizes, padding: char) -> String {
+    let (min, max) = length_bounds(sizes);
+    let mut text: String = text.chars().take(max).collect();
+    let length = text.chars().count();
+    if length < min {
+        text.extend(std::iter::repeat(padding).take(min - length));
+    }
+    text
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+fn pick<'a>(values: &[&'a str], rng: &mut StdRng) -> &'a str {
+    values.choose(rng).copied().unwrap_or_default()
+}
+
+/// Faker-style values picked by property name.
+pub(crate) struct RealisticGenerator;
+
+const FIRST_NAMES: [&str; 12] = [
+    "Alice", "Bruno", "Chen", "Dalia", "Emeka", "Fatima", "Gustavo", "Hana", "Ivan", "Jasmine",
+    "Kofi", "Lucía",
+];
+const LAST_NAMES: [&str; 10] = [
+    "Anderson", "Baptiste", "Dubois", "García", "Kowalski", "Nakamura", "Okafor", "Petrov",
+    "Silva", "Wang",
+];
+const STREETS: [&str; 8] = [
+    "Main Street",
+    "Oak Avenue",
+    "Harbour Road",
+    "Rue de la Paix",
+    "Calle Mayor",
+    "Elm Drive",
+    "Station Lane",
+    "Park Boulevard",
+];
+const CITIES: [&str; 10] = [
+    "Amsterdam",
+    "Buenos Aires",
+    "Cape Town",
+    "Denver",
+    "Hanoi",
+    "Lagos",
+    "Lisbon",
+    "Osaka",
+    "Toronto",
+    "Warsaw",
+];
+const COUNTRIES: [&str; 8] = [
+    "Argentina",
+    "Canada",
+    "Japan",
+    "Nigeria",
+    "Poland",
+    "Portugal",
+    "South Africa",
+    "Vietnam",
+];
+const DOMAINS: [&str; 4] = ["example.com", "example.org", "mail.example", "test.example"];
+const WORDS: [&str; 16] = [
+    "fast", "payment", "coffee", "market", "friendly", "blue", "garden", "river", "morning",
+    "project", "quiet", "street", "ticket", "village", "warm", "yellow",
+];
+
+impl RealisticGenerator {
+    fn text(name: &str, rng: &mut StdRng) -> String {
+        let name = name.to_lowercase();
+        let first = pick(&FIRST_NAMES, rng);
+        let last = pick(&LAST_NAMES, rng);
+        if name.contains("email") || name.contains("mail") {
+            let domain = pick(&DOMAINS, rng);
+            format!(
+                "{}.{}@{}",
+                first.to_lowercase(),
+                last.to_lowercase(),
+                domain
+            )
+        } else if name.contains("first") || name.contains("given") {
+            first.to_owned()
+        } else if name.contains("last") || name.contains("surname") || name.contains("family") {
+            last.to_owned()
+        } else if name.contains("user") || name.contains("handle") || name.contains("nick") {
+            format!("{}_{}", first.to_lowercase(), rng.gen_range(1..1000))
+        } else if name.contains("name") || name.contains("author") || name.contains("owner") {
+            format!("{} {}", first, last)
+        } else if name.contains("street") || name.contains("address") {
+            let street = pick(&STREETS, rng);
+            format!("{} {}", rng.gen_range(1..300), street)
+        } else if name.contains("city") || name.contains("town") {
+            pick(&CITIES, rng).to_owned()
+        } else if name.contains("country") {
+            pick(&COUNTRIES, rng).to_owned()
+        } else if name.contains("phone") || name.contains("mobile") {
+            format!(
+                "+{} {} {:04}",
+                rng.gen_range(1..99),
+                rng.gen_range(100..999),
+                rng.gen_range(0..10000)
+            )
+        } else if name.contains("url")
+            || name.contains("link")
+            || name.contains("website")
+            || name.contains("avatar")
+        {
+            let domain = pick(&DOMAINS, rng);
+            let word = pick(&WORDS, rng);
+            format!("https://{}/{}", domain, word)
+        } else if name.contains("description")
+            || name.contains("bio")
+            || name.contains("message")
+            || name.contains("text")
+            || name.contains("content")
+            || name.contains("comment")
+        {
+            let words: Vec<&str> = (0..rng.gen_range(4..12))
+                .map(|_| pick(&WORDS, rng))
+                .collect();
+            let mut sentence = words.join(" ");
+            if let Some(first_letter) = sentence.get_mut(0..1) {
+                first_letter.make_ascii_uppercase();
+            }
+            sentence.push('.');
+            sentence
+        } else {
+            pick(&WORDS, rng).to_owned()
+        }
+    }
+
+    fn number(name: &str, rng: &mut StdRng) -> u8 {
+        let name = name.to_lowercase();
+        if name.contains("age") {
+            rng.gen_range(18..=90)
+        } else if name.contains("rating") || name.contains("score") || name.contains("stars") {
+            rng.gen_range(1..=5)
+        } else {
+            rng.gen_range(0..=100)
+        }
+    }
+}
+
+impl DocumentGenerator for RealisticGenerator {
+    fn value(
+        &self,
+        name: &str,
+        property_type: &DocumentPropertyType,
+        rng: &mut StdRng,
+    ) -> Option<Value> {
+        let number = Self::number(name, rng);
+        match property_type {
+            DocumentPropertyType::String(sizes) => {
+                Some(Value::Text(fit_length(Self::text(name, rng), sizes, '.')))
+            }
+            DocumentPropertyType::U128 => Some(Value::U128(number.into())),
+            DocumentPropertyType::I128 => Some(Value::I128(number.into())),
+            DocumentPropertyType::U64 => Some(Value::U64(number.into())),
+            DocumentPropertyType::I64 => Some(Value::I64(number.into())),
+            DocumentPropertyType::U32 => Some(Value::U32(number.into())),
+            DocumentPropertyType::I32 => Some(Value::I32(number.into())),
+            DocumentPropertyType::U16 => Some(Value::U16(number.into())),
+            DocumentPropertyType::I16 => Some(Value::I16(number.into())),
+            DocumentPropertyType::U8 => Some(Value::U8(number)),
+            DocumentPropertyType::I8 => Some(Value::I8(number.min(i8::MAX as u8) as i8)),
+            DocumentPropertyType::F64 => Some(Value::Float(
+                (rng.gen_range(0.0..1000.0f64) * 100.0).round() / 100.0,
+            )),
+            DocumentPropertyType::Date => Some(Value::U64(
+                now_ms().saturating_sub(rng.gen_range(0..YEAR_MS)),
+            )),
+            DocumentPropertyType::Boolean => Some(Value::Bool(rng.gen())),
+            DocumentPropertyType::ByteArray(_)
+            | DocumentPropertyType::Identifier
+            | DocumentPropertyType::Object(_)
+            | DocumentPropertyType::Array(_)
+            | DocumentPropertyType::VariableTypeArray(_) => None,
+        }
+    }
+}
+
+/// Smallest and largest values of each property's type and sizes.
+pub(crate) struct BoundaryGenerator;
+
+impl BoundaryGenerator {
+    fn bytes(sizes: &ByteArrayPropertySizes, rng: &mut StdRng) -> Vec<u8> {
+        let min = sizes.min_size.unwrap_or(0) as usize;
+        let max = (sizes.max_size.unwrap_or(DEFAULT_MAX_LENGTH) as usize).max(min);
+        let size = if rng.gen() { min } else { max };
+        vec![if rng.gen() { u8::MIN } else { u8::MAX }; size]
+    }
+}
+
+impl DocumentGenerator for BoundaryGenerator {
+    fn value(
+        &self,
+        _name: &str,
+        property_type: &DocumentPropertyType,
+        rng: &mut StdRng,
+    ) -> Option<Value> {
+        macro_rules! edge {
+            ($variant:ident, $type:ty) => {
+                Some(Value::$variant(
+                    *[<$type>::MIN, 0, <$type>::MAX].choose(rng).unwrap(),
+                ))
+            };
+        }
+        match property_type {
+            DocumentPropertyType::U128 => edge!(U128, u128),
+            DocumentPropertyType::I128 => edge!(I128, i128),
+            DocumentPropertyType::U64 => edge!(U64, u64),
+            DocumentPropertyType::I64 => edge!(I64, i64),
+            DocumentPropertyType::U32 => edge!(U32, u32),
+            DocumentPropertyType::I32 => edge!(I32, i32),
+            DocumentPropertyType::U16 => edge!(U16, u16),
+            DocumentPropertyType::I16 => edge!(I16, i16),
+            DocumentPropertyType::U8 => edge!(U8, u8),
+            DocumentPropertyType::I8 => edge!(I8, i8),
+            DocumentPropertyType::F64 => Some(Value::Float(
+                *[f64::MIN, -0.0, 0.0, f64::MIN_POSITIVE, f64::MAX]
+                    .choose(rng)
+                    .unwrap(),
+            )),
+            DocumentPropertyType::Date => Some(Value::U64(*[0, MAX_DATE_MS].choose(rng).unwrap())),
+            DocumentPropertyType::String(sizes) => {
+                let (min, max) = length_bounds(sizes);
+                let length = if rng.gen() { min } else { max };
+                Some(Value::Text("z".repeat(length)))
+            }
+            DocumentPropertyType::ByteArray(sizes) => Some(Value::Bytes(Self::bytes(sizes, rng))),
+            DocumentPropertyType::Boolean
+            | DocumentPropertyType::Identifier
+            | DocumentPropertyType::Object(_)
+            | DocumentPropertyType::Array(_)
+            | DocumentPropertyType::VariableTypeArray(_) => None,
+        }
+    }
+}
+
+/// Strings mixing the characters text handling most often gets wrong.
+pub(crate) struct UnicodeStressGenerator;
+
+const UNICODE_PIECES: [&str; 14] = [
+    // Precomposed and decomposed forms of the same letter
+    "\u{e9}",
+    "e\u{301}",
+    // Stacked combining marks
+    "a\u{300}\u{301}\u{302}\u{303}\u{304}",
+    // Right to left text and overrides
+    "\u{5e9}\u{5dc}\u{5d5}\u{5dd}",
+    "\u{645}\u{631}\u{62d}\u{628}\u{627}",
+    "\u{202e}txet\u{202c}",
+    // Emoji with skin tones and joiner sequences
+    "\u{1f44d}\u{1f3fd}",
+    "\u{1f469}\u{200d}\u{1f469}\u{200d}\u{1f467}",
+    "\u{1f1fa}\u{1f1f3}",
+    // Invisible characters
+    "\u{200b}",
+    "\u{feff}",
+    // Characters outside the Basic Multilingual Plane and wide ones
+    "\u{1d54f}\u{1d560}",
+    "\u{6f22}\u{5b57}",
+    // Replacement character
+    "\u{fffd}",
+];
+
+impl DocumentGenerator for UnicodeStressGenerator {
+    fn value(
+        &self,
+        _name: &str,
+        property_type: &DocumentPropertyType,
+        rng: &mut StdRng,
+    ) -> Option<Value> {
+        let DocumentPropertyType::String(sizes) = property_type else {
+            return None;
+        };
+        let (min, max) = length_bounds(sizes);
+        let target = rng.gen_range(min..=max);
+        let mut text = String::new();
+        while text.chars().count() < target {
+            text.push_str(UNICODE_PIECES.choose(rng).unwrap());
+        }
+        // Cutting may split a sequence, which is as much a part of the test
+        Some(Value::Text(fit_length(text, sizes, '\u{e9}')))
+    }
+}
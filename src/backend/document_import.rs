@@ -0,0 +1,177 @@
+//! Documents created from JSON files.
+//!
+//! A file holds one JSON object with the document's own properties, system
+//! fields such as `$id` or `$createdAt` are set when the document is created.
+//! Every property is checked against the document type before anything is
+//! broadcast: unknown and missing required properties and values of the wrong
+//! kind are all reported at once. Identifiers are written as Base58 strings
+//! and byte arrays as Base64 strings.
+//!
+//! Payload files used by document insert operations of strategies hold one
+//! such object per document type name instead, properties left out being
+//! filled with random values.
+
+use std::{collections::BTreeMap, fs};
+
+use dpp::{
+    data_contract::document_type::{
+        accessors::DocumentTypeV0Getters, DocumentPropertyType, DocumentType,
+    },
+    platform_value::{
+        string_encoding::{self, Encoding},
+        Identifier, Value,
+    },
+};
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+/// Reads the properties of a new document from a JSON file, checked against
+/// the document type.
+pub(crate) fn read_document_properties(
+    path: &str,
+    document_type: &DocumentType,
+) -> Result<BTreeMap<String, Value>, String> {
+    let json = fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path, e))?;
+    let JsonValue::Object(object) =
+        serde_json::from_str(&json).map_err(|e| format!("Invalid JSON in {}: {}", path, e))?
+    else {
+        return Err(format!("{} must hold a single JSON object", path));
+    };
+
+    let (properties, mut errors) = convert_properties(object, document_type);
+    for required in document_type.required_fields() {
+        if !required.starts_with('$') && !properties.contains_key(required) {
+            errors.push(format!("{}: required property is missing", required));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(properties)
+    } else {
+        Err(format!(
+            "{} doesn't match document type {}:\n{}",
+            path,
+            document_type.name(),
+            errors.join("\n")
+        ))
+    }
+}
+
+/// Reads a payload file, a JSON object of property values by document type
+/// name.
+pub(crate) fn read_document_payloads(
+    path: &str,
+) -> Result<BTreeMap<String, JsonMap<String, JsonValue>>, String> {
+    let json = fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path, e))?;
+    let JsonValue::Object(object) =
+        serde_json::from_str(&json).map_err(|e| format!("Invalid JSON in {}: {}", path, e))?
+    else {
+        return Err(format!(
+            "{} must hold a JSON object of values by document type",
+            path
+        ));
+    };
+    object
+        .into_iter()
+        .map(|(document_type, values)| match values {
+            JsonValue::Object(values) => Ok((document_type, values)),
+            _ => Err(format!(
+                "{}: values of document type {} must be a JSON object",
+                path, document_type
+            )),
+        })
+        .collect()
+}
+
+/// Converts the payload of a document type, properties it doesn't set being
+/// left to random values.
+pub(crate) fn payload_properties(
+    payload: &JsonMap<String, JsonValue>,
+    document_type: &DocumentType,
+) -> Result<BTreeMap<String, Value>, String> {
+    let (properties, errors) = convert_properties(payload.clone(), document_type);
+    if errors.is_empty() {
+        Ok(properties)
+    } else {
+        Err(format!(
+            "Payload doesn't match document type {}:\n{}",
+            document_type.name(),
+            errors.join("\n")
+        ))
+    }
+}
+
+/// Converts properties to the document type, with an error for every one that
+/// doesn't fit.
+fn convert_properties(
+    object: JsonMap<String, JsonValue>,
+    document_type: &DocumentType,
+) -> (BTreeMap<String, Value>, Vec<String>) {
+    let mut errors = Vec::new();
+    let mut properties = BTreeMap::new();
+    for (name, json_value) in object {
+        match document_type.properties().get(&name) {
+            Some(property) => match convert_value(&property.property_type, json_value) {
+                Ok(value) => {
+                    properties.insert(name, value);
+                }
+                Err(e) => errors.push(format!("{}: {}", name, e)),
+            },
+            None => errors.push(format!(
+                "{}: not a property of document type {}",
+                name,
+                document_type.name()
+            )),
+        }
+    }
+    (properties, errors)
+}
+
+fn convert_value(
+    property_type: &DocumentPropertyType,
+    json_value: JsonValue,
+) -> Result<Value, String> {
+    match property_type {
+        DocumentPropertyType::U128
+        | DocumentPropertyType::U64
+        | DocumentPropertyType::U32
+        | DocumentPropertyType::U16
+        | DocumentPropertyType::U8
+        | DocumentPropertyType::Date => json_value
+            .as_u64()
+            .map(Value::U64)
+            .ok_or_else(|| "expected a non-negative integer".to_owned()),
+        DocumentPropertyType::I128
+        | DocumentPropertyType::I64
+        | DocumentPropertyType::I32
+        | DocumentPropertyType::I16
+        | DocumentPropertyType::I8 => json_value
+            .as_i64()
+            .map(Value::I64)
+            .ok_or_else(|| "expected an integer".to_owned()),
+        DocumentPropertyType::F64 => json_value
+            .as_f64()
+            .map(Value::Float)
+            .ok_or_else(|| "expected a number".to_owned()),
+        DocumentPropertyType::String(_) => match json_value {
+            JsonValue::String(text) => Ok(Value::Text(text)),
+            _ => Err("expected a string".to_owned()),
+        },
+        DocumentPropertyType::Boolean => json_value
+            .as_bool()
+            .map(Value::Bool)
+            .ok_or_else(|| "expected true or false".to_owned()),
+        DocumentPropertyType::Identifier => json_value
+            .as_str()
+            .and_then(|id| Identifier::from_string(id, Encoding::Base58).ok())
+            .map(|id| Value::Identifier(id.to_buffer()))
+            .ok_or_else(|| "expected a Base58 identifier".to_owned()),
+        DocumentPropertyType::ByteArray(_) => json_value
+            .as_str()
+            .and_then(|bytes| string_encoding::decode(bytes, Encoding::Base64).ok())
+            .map(Value::Bytes)
+            .ok_or_else(|| "expected a Base64 string".to_owned()),
+        DocumentPropertyType::Object(_)
+        | DocumentPropertyType::Array(_)
+        | DocumentPropertyType::VariableTypeArray(_) => Ok(json_value.into()),
+    }
+}
@@ -0,0 +1,181 @@
+//! Field statistics of a sample of documents of a document type.
+//!
+//! Strategies create documents with random values of a given fill type and
+//! size, which only loads the network realistically if they resemble what the
+//! contract is actually used for. The statistics tell how often each property
+//! is set in existing documents, how long its values are and how large the
+//! documents are once encoded.
+
+use std::fmt::{self, Display};
+
+use dpp::{
+    data_contract::document_type::{accessors::DocumentTypeV0Getters, DocumentTypeRef},
+    document::{
+        serialization_traits::DocumentPlatformConversionMethodsV0, Document, DocumentV0Getters,
+    },
+    platform_value::Value,
+    version::PlatformVersion,
+};
+
+use crate::format::{format_count, format_decimal};
+
+/// Documents fetched for the statistics, a single page of a query
+pub(crate) const SAMPLE_SIZE: u32 = 100;
+
+#[derive(Debug, Clone, Default)]
+pub struct FieldStats {
+    pub name: String,
+    /// Documents of the sample with the property set
+    pub present: u64,
+    /// Lengths of the text, bytes, array and map values of the property
+    pub lengths: Lengths,
+}
+
+/// Minimum, maximum and total of a set of lengths.
+#[derive(Debug, Clone, Default)]
+pub struct Lengths {
+    pub count: u64,
+    pub min: usize,
+    pub max: usize,
+    pub total: usize,
+}
+
+impl Lengths {
+    fn add(&mut self, length: usize) {
+        if self.count == 0 || length < self.min {
+            self.min = length;
+        }
+        self.max = self.max.max(length);
+        self.total += length;
+        self.count += 1;
+    }
+
+    fn average(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.total as f64 / self.count as f64
+        }
+    }
+}
+
+impl Display for Lengths {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.count == 0 {
+            write!(f, "-")
+        } else {
+            write!(
+                f,
+                "{}/{}/{}",
+                self.min,
+                format_decimal(self.average(), 1),
+                self.max
+            )
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DocumentTypeStats {
+    pub document_type_name: String,
+    pub sample_size: u64,
+    /// Properties of the document type first, in their order, then the ones
+    /// only found in documents
+    pub fields: Vec<FieldStats>,
+    /// Encoded sizes of the documents in bytes
+    pub sizes: Lengths,
+}
+
+impl DocumentTypeStats {
+    pub(crate) fn compute<'a>(
+        document_type: DocumentTypeRef,
+        documents: impl IntoIterator<Item = &'a Document>,
+        platform_version: &PlatformVersion,
+    ) -> Self {
+        let mut stats = DocumentTypeStats {
+            document_type_name: document_type.name().clone(),
+            fields: document_type
+                .properties()
+                .keys()
+                .map(|name| FieldStats {
+                    name: name.clone(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+
+        for document in documents {
+            stats.sample_size += 1;
+            match document.serialize(document_type, platform_version) {
+                Ok(bytes) => stats.sizes.add(bytes.len()),
+                Err(e) => tracing::warn!("Cannot encode document {}: {}", document.id(), e),
+            }
+            for (name, value) in document.properties() {
+                let index = match stats.fields.iter().position(|field| &field.name == name) {
+                    Some(index) => index,
+                    None => {
+                        stats.fields.push(FieldStats {
+                            name: name.clone(),
+                            ..Default::default()
+                        });
+                        stats.fields.len() - 1
+                    }
+                };
+                let field = &mut stats.fields[index];
+                if value.is_null() {
+                    continue;
+                }
+                field.present += 1;
+                if let Some(length) = value_length(value) {
+                    field.lengths.add(length);
+                }
+            }
+        }
+        stats
+    }
+}
+
+/// Length of a value whose size varies: characters of text, bytes, or items
+/// of arrays and maps.
+fn value_length(value: &Value) -> Option<usize> {
+    match value {
+        Value::Text(text) => Some(text.chars().count()),
+        Value::Bytes(bytes) => Some(bytes.len()),
+        Value::Array(items) => Some(items.len()),
+        Value::Map(entries) => Some(entries.len()),
+        _ => None,
+    }
+}
+
+impl Display for DocumentTypeStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.sample_size == 0 {
+            return write!(f, "No documents of type {} found", self.document_type_name);
+        }
+        write!(
+            f,
+            "Sample of {} documents of type {}",
+            format_count(self.sample_size),
+            self.document_type_name
+        )?;
+        if self.sizes.count > 0 {
+            write!(f, "\nEncoded size in bytes (min/avg/max): {}", self.sizes)?;
+        }
+        write!(
+            f,
+            "\n\n{:<32}{:>8}  {}",
+            "Field", "Set in", "Length (min/avg/max)"
+        )?;
+        for field in &self.fields {
+            write!(
+                f,
+                "\n{:<32}{:>7}%  {}",
+                field.name,
+                format_decimal(field.present as f64 * 100.0 / self.sample_size as f64, 0),
+                field.lengths
+            )?;
+        }
+        Ok(())
+    }
+}
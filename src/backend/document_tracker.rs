@@ -0,0 +1,105 @@
+//! Documents created by a strategy run, for its delete operations to target.
+//!
+//! By default delete operations pick among every document of their type found
+//! in the local Drive, which holds the documents of earlier runs too, so how
+//! many deletes a block gets and which documents they hit varies. With another
+//! [DeletePolicy] they target the documents the run itself created instead,
+//! each handed out once, so every delete names a document Platform has a proof
+//! for and a run deletes as many documents as it created at most.
+//!
+//! Replace and transfer operations draw from the same documents. A replaced
+//! or transferred document is tracked again at its new revision, so it can be
+//! targeted again later.
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::Mutex,
+};
+
+use dpp::{document::Document, platform_value::Identifier};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, strum::Display, strum::EnumString)]
+#[strum(serialize_all = "kebab-case")]
+pub enum DeletePolicy {
+    /// Any document of the type in the local Drive
+    #[default]
+    LocalDrive,
+    /// The earliest created document still tracked
+    Oldest,
+    /// The latest created document still tracked
+    Newest,
+    /// Any document still tracked
+    Random,
+}
+
+impl DeletePolicy {
+    pub const ALL: [DeletePolicy; 4] = [
+        DeletePolicy::LocalDrive,
+        DeletePolicy::Oldest,
+        DeletePolicy::Newest,
+        DeletePolicy::Random,
+    ];
+}
+
+/// Documents created during a run by contract and document type, oldest
+/// first.
+#[derive(Debug)]
+pub(crate) struct DocumentTracker {
+    documents: Mutex<BTreeMap<(Identifier, String), VecDeque<Document>>>,
+    /// Picks documents for [DeletePolicy::Random]
+    rng: Mutex<StdRng>,
+}
+
+impl DocumentTracker {
+    /// Tracker picking random documents in the same order for the same seed.
+    pub(crate) fn new(seed: u64) -> Self {
+        DocumentTracker {
+            documents: Mutex::default(),
+            rng: Mutex::new(StdRng::seed_from_u64(seed)),
+        }
+    }
+
+    pub(crate) fn record_created(
+        &self,
+        contract_id: Identifier,
+        document_type_name: &str,
+        document: Document,
+    ) {
+        self.documents
+            .lock()
+            .expect("document tracker lock")
+            .entry((contract_id, document_type_name.to_owned()))
+            .or_default()
+            .push_back(document);
+    }
+
+    /// Takes a tracked document of the type according to the policy, `None`
+    /// if there is none left or the policy doesn't use tracked documents.
+    pub(crate) fn take(
+        &self,
+        policy: DeletePolicy,
+        contract_id: Identifier,
+        document_type_name: &str,
+    ) -> Option<Document> {
+        let mut documents = self.documents.lock().expect("document tracker lock");
+        let tracked = documents.get_mut(&(contract_id, document_type_name.to_owned()))?;
+        match policy {
+            DeletePolicy::LocalDrive => None,
+            DeletePolicy::Oldest => tracked.pop_front(),
+            DeletePolicy::Newest => tracked.pop_back(),
+            DeletePolicy::Random => {
+                if tracked.is_empty() {
+                    None
+                } else {
+                    let index = self
+                        .rng
+                        .lock()
+                        .expect("document tracker lock")
+                        .gen_range(0..tracked.len());
+                    tracked.remove(index)
+                }
+            }
+        }
+    }
+}
@@ -3,7 +3,7 @@ use dash_sdk::platform::transition::vote::PutVote;
 use std::{
     collections::{BTreeMap, HashMap, HashSet},
     fmt::format,
-    iter,
+    fs, iter,
     sync::Arc,
     time::{SystemTime, UNIX_EPOCH},
 };
@@ -12,7 +12,8 @@ use dash_sdk::{
     platform::{
         transition::{
             purchase_document::PurchaseDocument, put_document::PutDocument,
-            transfer_document::TransferDocument, update_price_of_document::UpdatePriceOfDocument,
+            put_settings::PutSettings, transfer_document::TransferDocument,
+            update_price_of_document::UpdatePriceOfDocument,
         },
         DocumentQuery, FetchMany,
     },
@@ -65,9 +66,16 @@ use drive_proof_verifier::types::ContestedResource;
 
 use futures::{stream::FuturesUnordered, Future, StreamExt};
 use rand::{prelude::StdRng, Rng, SeedableRng};
+use rs_dapi_client::RequestSettings;
 use simple_signer::signer::SimpleSigner;
 
-use super::{state::IdentityPrivateKeysMap, AppStateUpdate, CompletedTaskPayload};
+use super::{
+    document_export::{self, DocumentExportFormat},
+    document_import,
+    document_stats::{self, DocumentTypeStats},
+    state::IdentityPrivateKeysMap,
+    AppStateUpdate, CompletedTaskPayload,
+};
 use crate::{
     backend::{error::Error, AppState, BackendEvent, Task},
     config::Config,
@@ -80,6 +88,8 @@ pub(crate) enum DocumentTask {
         data_contract_name: String,
         document_type_name: String,
         count: u16,
+        /// Fee increase in percent of the base fee
+        user_fee_increase: u16,
     },
     QueryContestedResources(DataContract, DocumentType),
     QueryDocumentsAndContestedResources {
@@ -94,6 +104,15 @@ pub(crate) enum DocumentTask {
         document_type_name: String,
         properties: HashMap<String, String>,
     },
+    /// Creates a document with the properties of a JSON file, checked against
+    /// the document type first
+    BroadcastDocumentFromJson {
+        data_contract_name: String,
+        document_type_name: String,
+        path: String,
+        /// Fee increase in percent of the base fee
+        user_fee_increase: u16,
+    },
     PurchaseDocument {
         data_contract: DataContract,
         document_type: DocumentType,
@@ -111,6 +130,19 @@ pub(crate) enum DocumentTask {
         document_type: DocumentType,
         document: Document,
     },
+    /// Writes documents to a file in [document_export::EXPORTS_DIR], keeping
+    /// only `fields` unless empty
+    ExportDocuments {
+        document_type_name: String,
+        documents: Vec<Document>,
+        format: DocumentExportFormat,
+        fields: Vec<String>,
+    },
+    /// Field statistics of a sample of the document type's documents
+    DocumentTypeStats {
+        data_contract: DataContract,
+        document_type: DocumentType,
+    },
 }
 
 impl AppState {
@@ -134,6 +166,7 @@ impl AppState {
                 data_contract_name,
                 document_type_name,
                 count,
+                user_fee_increase,
             } => {
                 let broadcast_stats = {
                     let data_contracts_lock = self.known_contracts.lock().await;
@@ -168,6 +201,7 @@ impl AppState {
                         data_contract,
                         &document_type,
                         *count,
+                        *user_fee_increase,
                     )
                     .await
                 };
@@ -259,111 +293,12 @@ impl AppState {
                     }
                 };
 
-                // Get signer from loaded identity
-                let mut signer = SimpleSigner::default();
-                let Identity::V0(identity_v0) = loaded_identity;
-                let identity_private_keys_lock = self.known_identities_private_keys.lock().await;
-                for (key_id, public_key) in &identity_v0.public_keys {
-                    let identity_key_tuple = (identity_v0.id, *key_id);
-                    if let Some(private_key_bytes) =
-                        identity_private_keys_lock.get(&identity_key_tuple)
-                    {
-                        signer.private_keys.insert(
-                            public_key.clone(),
-                            private_key_bytes
-                                .clone()
-                                .try_into()
-                                .expect("Expected private key to be 32 bytes"),
-                        );
-                    }
-                }
-                drop(identity_private_keys_lock);
+                let signer = self.identity_signer(loaded_identity).await;
 
                 // Get the state transition entropy
                 let mut rng = StdRng::from_entropy();
                 let document_state_transition_entropy: [u8; 32] = rng.gen();
 
-                // Now gather all the parameters to create a document
-                // For this I am copying the logic from DocumentTypeV0::random_document_with_params()
-
-                let id = Document::generate_document_id_v0(
-                    &data_contract.id(),
-                    &loaded_identity.id(),
-                    &document_type_name.as_str(),
-                    document_state_transition_entropy.as_slice(),
-                );
-
-                let document_type_v0 = match document_type {
-                    DocumentType::V0(document_type_v0) => document_type_v0,
-                    DocumentType::V1(_) => {
-                        return BackendEvent::TaskCompleted {
-                            task: Task::Document(task),
-                            execution_result: Err("Unsupported DocumentType version".to_string()),
-                        };
-                    }
-                };
-                let revision = if document_type_v0.requires_revision() {
-                    Some(INITIAL_REVISION)
-                } else {
-                    None
-                };
-
-                let created_at = if document_type_v0.required_fields().contains(CREATED_AT) {
-                    let now = SystemTime::now();
-                    let duration_since_epoch =
-                        now.duration_since(UNIX_EPOCH).expect("Time went backwards");
-                    let milliseconds = duration_since_epoch.as_millis() as u64;
-                    Some(milliseconds)
-                } else {
-                    None
-                };
-
-                let updated_at = if document_type_v0.required_fields().contains(UPDATED_AT) {
-                    let now = SystemTime::now();
-                    let duration_since_epoch =
-                        now.duration_since(UNIX_EPOCH).expect("Time went backwards");
-                    let milliseconds = duration_since_epoch.as_millis() as u64;
-                    Some(milliseconds)
-                } else {
-                    None
-                };
-
-                let created_at_block_height = if document_type_v0
-                    .required_fields()
-                    .contains(CREATED_AT_BLOCK_HEIGHT)
-                {
-                    Some(0)
-                } else {
-                    None
-                };
-
-                let updated_at_block_height = if document_type_v0
-                    .required_fields()
-                    .contains(UPDATED_AT_BLOCK_HEIGHT)
-                {
-                    Some(0)
-                } else {
-                    None
-                };
-
-                let created_at_core_block_height = if document_type_v0
-                    .required_fields()
-                    .contains(CREATED_AT_CORE_BLOCK_HEIGHT)
-                {
-                    Some(0)
-                } else {
-                    None
-                };
-
-                let updated_at_core_block_height = if document_type_v0
-                    .required_fields()
-                    .contains(UPDATED_AT_CORE_BLOCK_HEIGHT)
-                {
-                    Some(0)
-                } else {
-                    None
-                };
-
                 let mut properties: BTreeMap<String, Value> = BTreeMap::new();
                 let _ = properties_strings.iter().map(|(k, v)| {
                     // If the value is meant to be a number, try to convert it and insert, otherwise insert as a string
@@ -375,21 +310,21 @@ impl AppState {
                     }
                 });
 
-                let document: Document = Document::V0(DocumentV0 {
-                    id,
-                    properties: properties.clone(),
-                    owner_id: loaded_identity.id(),
-                    revision,
-                    created_at,
-                    updated_at,
-                    transferred_at: None,
-                    created_at_block_height,
-                    updated_at_block_height,
-                    transferred_at_block_height: None,
-                    created_at_core_block_height,
-                    updated_at_core_block_height,
-                    transferred_at_core_block_height: None,
-                });
+                let document = match new_document(
+                    document_type,
+                    data_contract.id(),
+                    loaded_identity.id(),
+                    properties,
+                    &document_state_transition_entropy,
+                ) {
+                    Ok(document) => document,
+                    Err(e) => {
+                        return BackendEvent::TaskCompleted {
+                            task: Task::Document(task),
+                            execution_result: Err(e),
+                        };
+                    }
+                };
 
                 tracing::info!("Document: {:?}", document);
 
@@ -419,6 +354,27 @@ impl AppState {
                 };
                 BackendEvent::None
             }
+            DocumentTask::BroadcastDocumentFromJson {
+                data_contract_name,
+                document_type_name,
+                path,
+                user_fee_increase,
+            } => {
+                let execution_result = self
+                    .broadcast_document_from_json(
+                        sdk,
+                        data_contract_name,
+                        document_type_name,
+                        path,
+                        *user_fee_increase,
+                    )
+                    .await
+                    .map(Into::into);
+                BackendEvent::TaskCompleted {
+                    task: Task::Document(task),
+                    execution_result,
+                }
+            }
             DocumentTask::PurchaseDocument {
                 data_contract,
                 document_type,
@@ -783,6 +739,65 @@ impl AppState {
                     }
                 }
             }
+            DocumentTask::ExportDocuments {
+                document_type_name,
+                documents,
+                format,
+                fields,
+            } => {
+                let path = format!(
+                    "{}/{}_{}.{}",
+                    document_export::EXPORTS_DIR,
+                    document_type_name,
+                    Utc::now().format("%Y%m%d%H%M%S"),
+                    format.extension()
+                );
+                let execution_result =
+                    document_export::export_documents(documents, *format, fields)
+                        .and_then(|contents| {
+                            fs::create_dir_all(document_export::EXPORTS_DIR)
+                                .and_then(|_| fs::write(&path, contents))
+                                .map_err(|e| format!("Failed to write {}: {}", path, e))
+                        })
+                        .map(|_| {
+                            format!("Exported {} documents to {}", documents.len(), path).into()
+                        });
+
+                BackendEvent::TaskCompleted {
+                    task: Task::Document(task.clone()),
+                    execution_result,
+                }
+            }
+            DocumentTask::DocumentTypeStats {
+                data_contract,
+                document_type,
+            } => {
+                let query = DocumentQuery {
+                    data_contract: Arc::new(data_contract.clone()),
+                    document_type_name: document_type.name().clone(),
+                    where_clauses: vec![],
+                    order_by_clauses: vec![],
+                    limit: document_stats::SAMPLE_SIZE,
+                    start: None,
+                };
+                let execution_result = Document::fetch_many(sdk, query)
+                    .await
+                    .map(|documents| {
+                        DocumentTypeStats::compute(
+                            document_type.as_ref(),
+                            documents.values().flatten(),
+                            sdk.version(),
+                        )
+                        .to_string()
+                        .into()
+                    })
+                    .map_err(|e| format!("Failed to fetch documents: {}", e));
+
+                BackendEvent::TaskCompleted {
+                    task: Task::Document(task.clone()),
+                    execution_result,
+                }
+            }
             DocumentTask::QueryDocumentsAndContestedResources {
                 document_query,
                 data_contract,
@@ -1053,6 +1068,158 @@ impl AppState {
     }
 }
 
+impl AppState {
+    async fn broadcast_document_from_json(
+        &self,
+        sdk: &Sdk,
+        data_contract_name: &str,
+        document_type_name: &str,
+        path: &str,
+        user_fee_increase: u16,
+    ) -> Result<String, String> {
+        let data_contract = self
+            .known_contracts
+            .lock()
+            .await
+            .get(data_contract_name)
+            .cloned()
+            .ok_or_else(|| "Data contract not found in TUI known contracts".to_owned())?;
+        let document_type = data_contract
+            .document_type_cloned_for_name(document_type_name)
+            .map_err(|_| "Document type name not found in data contract".to_owned())?;
+        let properties = document_import::read_document_properties(path, &document_type)?;
+
+        let loaded_identity = self
+            .loaded_identity
+            .lock()
+            .await
+            .clone()
+            .ok_or_else(|| "No identity loaded".to_owned())?;
+        let identity_public_key = loaded_identity
+            .get_first_public_key_matching(
+                Purpose::AUTHENTICATION,
+                HashSet::from([document_type.security_level_requirement()]),
+                HashSet::from([KeyType::ECDSA_SECP256K1, KeyType::BLS12_381]),
+                false,
+            )
+            .cloned()
+            .ok_or_else(|| {
+                "Loaded identity does not have a public key matching the criteria for document \
+                 broadcasts"
+                    .to_owned()
+            })?;
+        let signer = self.identity_signer(&loaded_identity).await;
+
+        let entropy: [u8; 32] = StdRng::from_entropy().gen();
+        let document = new_document(
+            &document_type,
+            data_contract.id(),
+            loaded_identity.id(),
+            properties,
+            &entropy,
+        )?;
+        let document = document
+            .put_to_platform_and_wait_for_response(
+                sdk,
+                document_type,
+                entropy,
+                identity_public_key,
+                &signer,
+                put_settings(user_fee_increase),
+            )
+            .await
+            .map_err(|e| format!("Failed to broadcast document: {}", e))?;
+        Ok(format!(
+            "Successfully broadcasted document with id {}",
+            document.id().to_string(Encoding::Base58)
+        ))
+    }
+
+    /// Signer with the private keys known for the identity.
+    pub(super) async fn identity_signer(&self, identity: &Identity) -> SimpleSigner {
+        let mut signer = SimpleSigner::default();
+        let Identity::V0(identity_v0) = identity;
+        let identity_private_keys_lock = self.known_identities_private_keys.lock().await;
+        for (key_id, public_key) in &identity_v0.public_keys {
+            let identity_key_tuple = (identity_v0.id, *key_id);
+            if let Some(private_key_bytes) = identity_private_keys_lock.get(&identity_key_tuple) {
+                signer.private_keys.insert(
+                    public_key.clone(),
+                    private_key_bytes
+                        .clone()
+                        .try_into()
+                        .expect("Expected private key to be 32 bytes"),
+                );
+            }
+        }
+        signer
+    }
+}
+
+/// Settings of a broadcast with the fee increased by `user_fee_increase`
+/// percent, `None` for the defaults.
+fn put_settings(user_fee_increase: u16) -> Option<PutSettings> {
+    (user_fee_increase > 0).then(|| PutSettings {
+        request_settings: RequestSettings::default(),
+        identity_nonce_stale_time_s: None,
+        user_fee_increase: Some(user_fee_increase),
+        wait_timeout: None,
+    })
+}
+
+/// New document of the type owned by `owner_id`, with the system fields the
+/// type requires set.
+///
+/// Copies the logic from DocumentTypeV0::random_document_with_params().
+fn new_document(
+    document_type: &DocumentType,
+    data_contract_id: Identifier,
+    owner_id: Identifier,
+    properties: BTreeMap<String, Value>,
+    entropy: &[u8; 32],
+) -> Result<Document, String> {
+    let DocumentType::V0(document_type_v0) = document_type else {
+        return Err("Unsupported DocumentType version".to_string());
+    };
+    let id = Document::generate_document_id_v0(
+        &data_contract_id,
+        &owner_id,
+        document_type_v0.name().as_str(),
+        entropy.as_slice(),
+    );
+    let revision = if document_type_v0.requires_revision() {
+        Some(INITIAL_REVISION)
+    } else {
+        None
+    };
+
+    let now_ms = || {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_millis() as u64
+    };
+    let required_fields = document_type_v0.required_fields();
+    let required_time = |field: &str| required_fields.contains(field).then(now_ms);
+    let required_height = |field: &str| required_fields.contains(field).then_some(0);
+
+    Ok(Document::V0(DocumentV0 {
+        id,
+        properties,
+        owner_id,
+        revision,
+        created_at: required_time(CREATED_AT),
+        updated_at: required_time(UPDATED_AT),
+        transferred_at: None,
+        created_at_block_height: required_height(CREATED_AT_BLOCK_HEIGHT),
+        updated_at_block_height: required_height(UPDATED_AT_BLOCK_HEIGHT),
+        transferred_at_block_height: None,
+        created_at_core_block_height: required_height(CREATED_AT_CORE_BLOCK_HEIGHT),
+        updated_at_core_block_height: required_height(UPDATED_AT_CORE_BLOCK_HEIGHT),
+        transferred_at_core_block_height: None,
+    }))
+}
+
 struct BroadcastRandomDocumentsStats {
     total: u16,
     completed: u16,
@@ -1082,6 +1249,7 @@ async fn broadcast_random_documents<'s>(
     data_contract: &DataContract,
     document_type: &DocumentType,
     count: u16,
+    user_fee_increase: u16,
 ) -> Result<BroadcastRandomDocumentsStats, Error> {
     let mut std_rng = StdRng::from_entropy();
 
@@ -1129,6 +1297,7 @@ async fn broadcast_random_documents<'s>(
         signer: &'a SimpleSigner,
         identity_public_key: &'a IdentityPublicKey,
         data_contract: Arc<DataContract>,
+        user_fee_increase: u16,
     ) -> impl Future<Output = Result<(), String>> + 'a {
         let document_state_transition_entropy: [u8; 32] = rng.gen();
         let time_ms = SystemTime::now()
@@ -1158,7 +1327,7 @@ async fn broadcast_random_documents<'s>(
                     document_state_transition_entropy,
                     identity_public_key.clone(),
                     signer,
-                    None,
+                    put_settings(user_fee_increase),
                 )
                 .await
                 .map(|_| ())
@@ -1175,6 +1344,7 @@ async fn broadcast_random_documents<'s>(
             &signer,
             identity_public_key,
             Arc::clone(&data_contract),
+            user_fee_increase,
         )
     })
     .take(count as usize)
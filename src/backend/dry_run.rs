@@ -0,0 +1,351 @@
+//! Strategy dry runs, walking the blocks of a run without broadcasting.
+//!
+//! A run finds out it lacks UTXOs or credits only once it starts funding
+//! asset locks. A dry run generates the transitions of every loop like the run
+//! would, on a copy of the strategy and of the known contracts, so what a run
+//! needs is known beforehand. Identity creates and top ups get placeholder
+//! asset lock proofs, which are never broadcast, and the proofs they use are
+//! what the run has to fund.
+
+use std::{collections::BTreeMap, fmt};
+
+use dpp::{
+    block::block_info::BlockInfo,
+    dashcore::{hashes::Hash, OutPoint, PrivateKey, Txid},
+    data_contract::accessors::v0::DataContractV0Getters,
+    document::Document,
+    fee::Credits,
+    identity::state_transition::asset_lock_proof::{chain::ChainAssetLockProof, AssetLockProof},
+    platform_value::{string_encoding::Encoding, Identifier},
+    serialization::PlatformSerializable,
+    state_transition::{
+        batch_transition::{
+            batched_transition::document_transition::DocumentTransition,
+            document_base_transition::v0::v0_methods::DocumentBaseTransitionV0Methods,
+            document_create_transition::v0::DocumentFromCreateTransitionV0, BatchTransition,
+            DocumentCreateTransition,
+        },
+        StateTransition, StateTransitionLike,
+    },
+    version::PlatformVersion,
+};
+use strategy_tests::{frequency::Frequency, operations::OperationType, Strategy};
+
+use super::state::KnownContractsMap;
+use crate::{
+    format::{format_count, format_rate, format_secs},
+    units::{self, CREDITS_PER_DUFF},
+};
+
+/// Duffs each asset lock transaction is given on top of the funded balance
+/// for its fee
+const ASSET_LOCK_FEE_ALLOWANCE: u64 = 30_000;
+/// Credits Drive charges per byte stored, processing fees come on top
+const STORAGE_CREDITS_PER_BYTE: Credits = 27_000;
+/// Rough fee of a transition storing a small document, processing included,
+/// for estimates made without generating the transitions
+const TRANSITION_FEE_ESTIMATE: Credits = 20_000_000;
+
+/// Asset lock proofs a run of `duration` blocks is expected to use, going by
+/// the lower end of the operation frequencies.
+pub(crate) fn estimated_asset_lock_proofs(strategy: &Strategy, duration: u64) -> u64 {
+    let num_start_identities = strategy.start_identities.number_of_identities as u64;
+    let num_identity_inserts = (strategy
+        .identity_inserts
+        .frequency
+        .times_per_block_range
+        .start as f64
+        * duration as f64
+        * strategy
+            .identity_inserts
+            .frequency
+            .chance_per_block
+            .unwrap_or(1.0)) as u64;
+    let mut num_top_ups: u64 = 0;
+    for operation in &strategy.operations {
+        if matches!(operation.op_type, OperationType::IdentityTopUp(_)) {
+            num_top_ups += (operation.frequency.times_per_block_range.start as f64
+                * duration as f64
+                * operation.frequency.chance_per_block.unwrap_or(1.0))
+                as u64;
+        }
+    }
+    num_start_identities + num_identity_inserts + num_top_ups
+}
+
+/// Credits the wallet spends on a single asset lock of the strategy.
+pub(crate) fn asset_lock_cost(strategy: &Strategy) -> Credits {
+    (strategy.start_identities.starting_balances + ASSET_LOCK_FEE_ALLOWANCE) * CREDITS_PER_DUFF
+}
+
+/// Asset lock proofs a run of `loops` loops can't use more of, each loop
+/// using the upper end of the operation frequencies.
+pub(crate) fn max_asset_lock_proofs(strategy: &Strategy, loops: u64) -> u64 {
+    let per_loop = strategy
+        .identity_inserts
+        .frequency
+        .times_per_block_range
+        .end as u64
+        + strategy
+            .operations
+            .iter()
+            .filter(|operation| matches!(operation.op_type, OperationType::IdentityTopUp(_)))
+            .map(|operation| operation.frequency.times_per_block_range.end as u64)
+            .sum::<u64>();
+    strategy.start_identities.number_of_identities as u64 + per_loop * loops
+}
+
+/// Transitions a frequency is expected to produce each block, on average.
+pub(crate) fn expected_per_block(frequency: &Frequency) -> f64 {
+    let range = &frequency.times_per_block_range;
+    let times = if range.end <= range.start {
+        range.end as f64
+    } else {
+        (range.start + range.end - 1) as f64 / 2.0
+    };
+    times * frequency.chance_per_block.unwrap_or(1.0)
+}
+
+/// What a run of the strategy is expected to need, computed from its
+/// frequencies alone, without the cost of a dry run.
+#[derive(Debug)]
+pub(crate) struct StrategySummary {
+    pub(crate) duration: u64,
+    pub(crate) seconds_per_loop: u64,
+    pub(crate) transitions_per_second: f64,
+    pub(crate) transitions: u64,
+    pub(crate) asset_lock_proofs: u64,
+    pub(crate) asset_lock_credits: Credits,
+}
+
+impl StrategySummary {
+    pub(crate) fn new(strategy: &Strategy, duration: u64, seconds_per_loop: u64) -> Self {
+        let seconds_per_loop = seconds_per_loop.max(1);
+        let loops = duration.div_ceil(seconds_per_loop);
+        let per_loop = expected_per_block(&strategy.identity_inserts.frequency)
+            + strategy
+                .operations
+                .iter()
+                .map(|operation| expected_per_block(&operation.frequency))
+                .sum::<f64>();
+        let asset_lock_proofs = estimated_asset_lock_proofs(strategy, loops);
+        StrategySummary {
+            duration,
+            seconds_per_loop,
+            transitions_per_second: per_loop / seconds_per_loop as f64,
+            transitions: (per_loop * loops as f64) as u64
+                + strategy.start_identities.number_of_identities as u64
+                + strategy.start_contracts.len() as u64,
+            asset_lock_proofs,
+            asset_lock_credits: asset_lock_proofs * asset_lock_cost(strategy),
+        }
+    }
+
+    fn fee_credits(&self) -> Credits {
+        self.transitions * TRANSITION_FEE_ESTIMATE
+    }
+}
+
+impl fmt::Display for StrategySummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Expected for a {} run, {} per loop:",
+            format_secs(self.duration),
+            format_secs(self.seconds_per_loop)
+        )?;
+        writeln!(
+            f,
+            "Transitions: {}, about {} in total",
+            format_rate(self.transitions_per_second),
+            format_count(self.transitions)
+        )?;
+        writeln!(
+            f,
+            "Asset lock proofs: {}",
+            format_count(self.asset_lock_proofs)
+        )?;
+        writeln!(
+            f,
+            "Estimated cost: {} (asset locks {}, fees {})",
+            units::format_credits(self.asset_lock_credits + self.fee_credits()),
+            units::format_credits(self.asset_lock_credits),
+            units::format_credits(self.fee_credits())
+        )?;
+        write!(
+            f,
+            "Required wallet balance: {}",
+            units::format_credits(self.asset_lock_credits)
+        )
+    }
+}
+
+/// Asset lock proofs for transitions that are never broadcast, all signed with
+/// the same key.
+pub(crate) fn placeholder_asset_lock_proofs(
+    count: u64,
+    private_key: PrivateKey,
+) -> Vec<(AssetLockProof, PrivateKey)> {
+    (0..count)
+        .map(|index| {
+            (
+                AssetLockProof::Chain(ChainAssetLockProof {
+                    core_chain_locked_height: 0,
+                    out_point: OutPoint::new(Txid::all_zeros(), index as u32),
+                }),
+                private_key,
+            )
+        })
+        .collect()
+}
+
+/// Documents created by a batch transition with their contract id and
+/// document type name.
+pub(crate) fn created_documents(
+    transition: &StateTransition,
+    known_contracts: &KnownContractsMap,
+    block_info: &BlockInfo,
+    platform_version: &PlatformVersion,
+) -> Vec<(Identifier, String, Document)> {
+    let StateTransition::Batch(BatchTransition::V0(batch_transition_v0)) = transition else {
+        return Vec::new();
+    };
+    batch_transition_v0
+        .transitions
+        .iter()
+        .filter_map(|document_transition| {
+            let DocumentTransition::Create(DocumentCreateTransition::V0(create_transition_v0)) =
+                document_transition
+            else {
+                return None;
+            };
+            let contract_id = create_transition_v0.base.data_contract_id();
+            let document_type_name = create_transition_v0.base.document_type_name();
+            let document_type = known_contracts
+                .get(&contract_id.to_string(Encoding::Base58))?
+                .document_type_for_name(document_type_name)
+                .ok()?;
+            let document = Document::try_from_create_transition_v0(
+                create_transition_v0,
+                transition.owner_id(),
+                block_info,
+                &document_type,
+                platform_version,
+            )
+            .ok()?;
+            Some((contract_id, document_type_name.to_string(), document))
+        })
+        .collect()
+}
+
+/// What a run of the strategy would produce and need.
+#[derive(Debug, Default)]
+pub(crate) struct DryRunReport {
+    pub(crate) loops: u64,
+    /// Seed the transitions were generated with
+    pub(crate) seed: u64,
+    /// Transitions by type name
+    pub(crate) transitions: BTreeMap<String, u64>,
+    pub(crate) asset_lock_proofs: u64,
+    /// L1 transactions funding the asset locks, each taking a UTXO
+    pub(crate) asset_lock_transactions: u64,
+    pub(crate) available_utxos: usize,
+    pub(crate) asset_lock_credits: Credits,
+    /// Bytes of the transitions storing documents and contracts
+    pub(crate) stored_bytes: u64,
+}
+
+impl DryRunReport {
+    /// Counts a transition the run would broadcast.
+    pub(crate) fn add_transition(&mut self, transition: &StateTransition) {
+        *self
+            .transitions
+            .entry(transition.name().to_string())
+            .or_default() += 1;
+        if stores_data(transition) {
+            self.stored_bytes += transition
+                .serialize_to_bytes()
+                .map(|bytes| bytes.len() as u64)
+                .unwrap_or_default();
+        }
+    }
+
+    fn storage_credits(&self) -> Credits {
+        self.stored_bytes * STORAGE_CREDITS_PER_BYTE
+    }
+}
+
+/// Whether the transition stores documents or a contract, paying for its bytes.
+fn stores_data(transition: &StateTransition) -> bool {
+    matches!(
+        transition,
+        StateTransition::Batch(_)
+            | StateTransition::DataContractCreate(_)
+            | StateTransition::DataContractUpdate(_)
+    )
+}
+
+/// Rough fee of a transition of `size` serialized bytes: the storage of its
+/// bytes if it stores data, at least the usual fee of a transition, raised by
+/// its user fee increase.
+pub(crate) fn estimated_transition_fee(transition: &StateTransition, size: usize) -> Credits {
+    let fee = if stores_data(transition) {
+        TRANSITION_FEE_ESTIMATE.max(size as Credits * STORAGE_CREDITS_PER_BYTE)
+    } else {
+        TRANSITION_FEE_ESTIMATE
+    };
+    fee + fee * transition.user_fee_increase() as Credits / 100
+}
+
+impl fmt::Display for DryRunReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Dry run of {} loops, nothing was broadcast",
+            format_count(self.loops)
+        )?;
+        writeln!(f, "Seed: {}", self.seed)?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "Transitions: {}",
+            format_count(self.transitions.values().sum::<u64>())
+        )?;
+        for (name, count) in &self.transitions {
+            writeln!(f, "  {}: {}", name, format_count(*count))?;
+        }
+        writeln!(f)?;
+        writeln!(
+            f,
+            "Asset lock proofs: {} in {} L1 transactions",
+            format_count(self.asset_lock_proofs),
+            format_count(self.asset_lock_transactions)
+        )?;
+        let utxos_status = if self.available_utxos < self.asset_lock_transactions as usize {
+            "not enough, go to the Wallet screen and create more"
+        } else {
+            "enough"
+        };
+        writeln!(
+            f,
+            "UTXOs: {} needed, {} available ({})",
+            self.asset_lock_transactions, self.available_utxos, utxos_status
+        )?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "Estimated cost: {}",
+            units::format_credits(self.asset_lock_credits + self.storage_credits())
+        )?;
+        writeln!(
+            f,
+            "  Asset locks: {}",
+            units::format_credits(self.asset_lock_credits)
+        )?;
+        write!(
+            f,
+            "  Storage of documents and contracts: at least {} (processing fees not included)",
+            units::format_credits(self.storage_credits())
+        )
+    }
+}
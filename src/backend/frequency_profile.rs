@@ -0,0 +1,190 @@
+//! Hour of day variation for strategy operation frequencies.
+//!
+//! A strategy's operations have a constant frequency, so a run produces the
+//! same load every block. A profile scales those frequencies by the hour (UTC)
+//! of the block being prepared, which makes runs bursty instead.
+
+use std::fmt::{self, Display};
+
+use bincode::{Decode, Encode};
+use strategy_tests::frequency::Frequency;
+
+pub(crate) const HOURS: usize = 24;
+
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub enum FrequencyProfile {
+    /// Quiet at night, most of the load between 9:00 and 18:00
+    BusinessHours,
+    /// Low during the day, peak between 18:00 and 23:00
+    EveningPeak,
+    /// Alternating idle and triple load hours
+    Bursty,
+    /// One multiplier per hour
+    Custom(Vec<f64>),
+}
+
+impl FrequencyProfile {
+    pub(crate) const PRESETS: [FrequencyProfile; 3] = [
+        FrequencyProfile::BusinessHours,
+        FrequencyProfile::EveningPeak,
+        FrequencyProfile::Bursty,
+    ];
+
+    /// Frequency multiplier for the hour of day.
+    pub(crate) fn multiplier(&self, hour: usize) -> f64 {
+        let hour = hour % HOURS;
+        match self {
+            FrequencyProfile::BusinessHours => match hour {
+                9..=17 => 1.5,
+                7..=8 | 18..=20 => 0.75,
+                _ => 0.1,
+            },
+            FrequencyProfile::EveningPeak => match hour {
+                18..=22 => 2.0,
+                9..=17 => 0.75,
+                _ => 0.25,
+            },
+            FrequencyProfile::Bursty => {
+                if hour % 2 == 0 {
+                    3.0
+                } else {
+                    0.0
+                }
+            }
+            FrequencyProfile::Custom(multipliers) => multipliers.get(hour).copied().unwrap_or(1.0),
+        }
+    }
+
+    /// Parses a custom profile written one multiplier per line, either as
+    /// `value` or `hour: value`, lines starting with `#` are ignored.
+    pub(crate) fn parse_custom(input: &str) -> Result<FrequencyProfile, String> {
+        let multipliers = input
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                let value = line
+                    .rsplit_once(':')
+                    .map_or(line, |(_, value)| value)
+                    .trim();
+                value
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|m| m.is_finite() && *m >= 0.0)
+                    .ok_or_else(|| format!("Invalid multiplier `{}`", line))
+            })
+            .collect::<Result<Vec<f64>, String>>()?;
+
+        if multipliers.len() != HOURS {
+            return Err(format!(
+                "Expected {} multipliers, one per hour, got {}",
+                HOURS,
+                multipliers.len()
+            ));
+        }
+
+        Ok(FrequencyProfile::Custom(multipliers))
+    }
+
+    /// Text to edit a custom profile, starting from this profile's values.
+    pub(crate) fn custom_template(&self) -> String {
+        let mut template = "# Frequency multiplier per hour (UTC), 1 keeps the configured \
+                            frequency\n"
+            .to_owned();
+        for hour in 0..HOURS {
+            template.push_str(&format!("{:02}: {}\n", hour, self.multiplier(hour)));
+        }
+        template
+    }
+}
+
+impl Display for FrequencyProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrequencyProfile::BusinessHours => write!(f, "Business hours"),
+            FrequencyProfile::EveningPeak => write!(f, "Evening peak"),
+            FrequencyProfile::Bursty => write!(f, "Bursty"),
+            FrequencyProfile::Custom(multipliers) => write!(
+                f,
+                "Custom [{}]",
+                multipliers
+                    .iter()
+                    .map(|m| m.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+/// Scales a base frequency: multipliers below one lower the chance per block,
+/// multipliers above one widen the times per block range.
+pub(crate) fn scale_frequency(base: &Frequency, multiplier: f64) -> Frequency {
+    if multiplier <= 1.0 {
+        Frequency {
+            times_per_block_range: base.times_per_block_range.clone(),
+            chance_per_block: Some(
+                (base.chance_per_block.unwrap_or(1.0) * multiplier).clamp(0.0, 1.0),
+            ),
+        }
+    } else {
+        let scale = |times: u16| (times as f64 * multiplier).round() as u16;
+        let start = scale(base.times_per_block_range.start);
+        let end = scale(base.times_per_block_range.end.saturating_sub(1)).saturating_add(1);
+        Frequency {
+            times_per_block_range: start..end.max(start + 1),
+            chance_per_block: base.chance_per_block,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hours(value: &str) -> Vec<String> {
+        (0..HOURS)
+            .map(|hour| format!("{:02}: {}", hour, value))
+            .collect()
+    }
+
+    #[test]
+    fn parses_its_template() {
+        let profile = FrequencyProfile::BusinessHours;
+        assert_eq!(
+            FrequencyProfile::parse_custom(&profile.custom_template()),
+            Ok(FrequencyProfile::Custom(
+                (0..HOURS).map(|hour| profile.multiplier(hour)).collect()
+            ))
+        );
+    }
+
+    #[test]
+    fn parses_bare_and_decimal_multipliers() {
+        let input = "# comment\n\n".to_owned() + &["0.25"; HOURS].join("\n");
+        assert_eq!(
+            FrequencyProfile::parse_custom(&input),
+            Ok(FrequencyProfile::Custom(vec![0.25; HOURS]))
+        );
+    }
+
+    #[test]
+    fn refuses_negative_and_invalid_multipliers() {
+        for value in ["-1", "", "x", "NaN", "inf"] {
+            assert!(
+                FrequencyProfile::parse_custom(&hours(value).join("\n")).is_err(),
+                "accepted `{}`",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn needs_one_multiplier_per_hour() {
+        assert!(FrequencyProfile::parse_custom("").is_err());
+        assert!(FrequencyProfile::parse_custom(&hours("1")[1..].join("\n")).is_err());
+        let mut too_many = hours("1");
+        too_many.push("1".to_owned());
+        assert!(FrequencyProfile::parse_custom(&too_many.join("\n")).is_err());
+    }
+}
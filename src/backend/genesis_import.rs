@@ -0,0 +1,186 @@
+//! Contracts and identities of a freshly set up local network.
+//!
+//! A local devnet registers its system contracts and a few funded identities
+//! while it starts, exploring it otherwise begins with fetching each of them by
+//! ID and adding their keys one identity at a time. A genesis dump lists them
+//! in one JSON file:
+//!
+//! ```json
+//! {
+//!   "contracts": [{ "id": "<Base58 ID>", "name": "dpns" }],
+//!   "identities": [{ "id": "<Base58 ID>", "privateKeys": ["<hex or WIF>"] }]
+//! }
+//! ```
+//!
+//! Contracts and identities are fetched from the network, so the dump only
+//! needs their IDs. Contracts without a name are known by their ID. Private
+//! keys are matched to the identity's public keys, a key matching none is
+//! reported and left out. Entries that fail don't stop the rest.
+
+use std::fs;
+
+use dash_sdk::{platform::Fetch, Sdk};
+use dpp::{
+    dashcore::{hashes::Hash, key::Secp256k1, PrivateKey},
+    identity::{
+        accessors::IdentityGettersV0,
+        identity_public_key::accessors::v0::IdentityPublicKeyGettersV0, KeyID,
+    },
+    platform_value::string_encoding::Encoding,
+    prelude::{DataContract, Identifier, Identity},
+};
+use serde::Deserialize;
+
+use super::{identities::private_key_from_string, key_reuse, AppState};
+
+pub(crate) const GENESIS_DUMP_PATH: &str = "supporting_files/genesis_dump.json";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct GenesisDump {
+    contracts: Vec<GenesisContract>,
+    identities: Vec<GenesisIdentity>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GenesisContract {
+    id: String,
+    #[serde(default)]
+    name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GenesisIdentity {
+    id: String,
+    #[serde(default)]
+    private_keys: Vec<String>,
+}
+
+impl AppState {
+    /// Adds the contracts and identities of a genesis dump to the known ones,
+    /// returning a summary of what was imported and what failed.
+    pub(crate) async fn import_genesis_dump(
+        &self,
+        sdk: &Sdk,
+        path: &str,
+    ) -> Result<String, String> {
+        let json = fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path, e))?;
+        let dump: GenesisDump = serde_json::from_str(&json)
+            .map_err(|e| format!("Invalid genesis dump in {}: {}", path, e))?;
+
+        let mut errors = Vec::new();
+
+        let mut imported_contracts = 0;
+        for contract in &dump.contracts {
+            match fetch_contract(sdk, &contract.id).await {
+                Ok(data_contract) => {
+                    let name = contract.name.clone().unwrap_or_else(|| contract.id.clone());
+                    self.known_contracts
+                        .lock()
+                        .await
+                        .insert(name, data_contract);
+                    imported_contracts += 1;
+                }
+                Err(e) => errors.push(format!("Contract {}: {}", contract.id, e)),
+            }
+        }
+
+        let mut imported_identities = 0;
+        let mut imported_keys = 0;
+        let mut reused_keys = Vec::new();
+        for genesis_identity in &dump.identities {
+            let identity = match fetch_identity(sdk, &genesis_identity.id).await {
+                Ok(identity) => identity,
+                Err(e) => {
+                    errors.push(format!("Identity {}: {}", genesis_identity.id, e));
+                    continue;
+                }
+            };
+
+            let mut private_keys = self.known_identities_private_keys.lock().await;
+            for (index, private_key) in genesis_identity.private_keys.iter().enumerate() {
+                let key_id = private_key_from_string(private_key)
+                    .map_err(|e| e.to_string())
+                    .and_then(|private_key| {
+                        identity_key_id(&identity, &private_key)
+                            .map(|key_id| (key_id, private_key))
+                            .ok_or_else(|| "matches none of its public keys".to_owned())
+                    });
+                match key_id {
+                    Ok((key_id, private_key)) => {
+                        private_keys
+                            .insert((identity.id(), key_id), private_key.to_bytes().to_vec());
+                        imported_keys += 1;
+                    }
+                    Err(e) => errors.push(format!(
+                        "Identity {} private key #{}: {}",
+                        genesis_identity.id,
+                        index + 1,
+                        e
+                    )),
+                }
+            }
+            drop(private_keys);
+
+            reused_keys.extend(
+                self.key_reuse_warnings(&identity)
+                    .await
+                    .into_iter()
+                    .map(|warning| format!("Identity {}: {}", genesis_identity.id, warning)),
+            );
+            self.known_identities
+                .lock()
+                .await
+                .insert(identity.id(), identity);
+            imported_identities += 1;
+        }
+
+        let mut summary = format!(
+            "Imported {} of {} contracts and {} of {} identities with {} private keys from {}",
+            imported_contracts,
+            dump.contracts.len(),
+            imported_identities,
+            dump.identities.len(),
+            imported_keys,
+            path
+        );
+        if !errors.is_empty() {
+            summary.push_str(&format!(", skipped:\n{}", errors.join("\n")));
+        }
+        Ok(key_reuse::with_warnings(&summary, &reused_keys))
+    }
+}
+
+async fn fetch_contract(sdk: &Sdk, id: &str) -> Result<DataContract, String> {
+    let id = Identifier::from_string(id, Encoding::Base58).map_err(|e| e.to_string())?;
+    DataContract::fetch(sdk, id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "not found on the network".to_owned())
+}
+
+async fn fetch_identity(sdk: &Sdk, id: &str) -> Result<Identity, String> {
+    let id = Identifier::from_string(id, Encoding::Base58).map_err(|e| e.to_string())?;
+    Identity::fetch(sdk, id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "not found on the network".to_owned())
+}
+
+/// ID of the identity's public key the private key belongs to.
+fn identity_key_id(identity: &Identity, private_key: &PrivateKey) -> Option<KeyID> {
+    let public_key_hash = private_key
+        .public_key(&Secp256k1::new())
+        .pubkey_hash()
+        .to_byte_array();
+    identity
+        .public_keys()
+        .iter()
+        .find(|(_, public_key)| {
+            public_key
+                .public_key_hash()
+                .is_ok_and(|hash| hash == public_key_hash)
+        })
+        .map(|(key_id, _)| *key_id)
+}
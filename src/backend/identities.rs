@@ -8,8 +8,8 @@ use dpp::state_transition::batch_transition::BatchTransition;
 use std::io::Write;
 use std::str::FromStr;
 use std::{
-    collections::{BTreeMap, HashSet},
-    fs::OpenOptions,
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fs::{self, OpenOptions},
     time::Duration,
 };
 
@@ -30,16 +30,17 @@ use dash_sdk::{
             put_settings::PutSettings, top_up_identity::TopUpIdentity,
             withdraw_from_identity::WithdrawFromIdentity,
         },
+        types::identity::PublicKeyHash,
         Fetch,
     },
     Sdk,
 };
 use dpp::{
-    dashcore::{self, key::Secp256k1},
+    dashcore::{self, bip32::ExtendedPrivKey, key::Secp256k1, secp256k1::SecretKey},
     identity::SecurityLevel,
 };
 use dpp::{
-    dashcore::{psbt::serialize::Serialize, Address, PrivateKey, Transaction},
+    dashcore::{psbt::serialize::Serialize, Address, OutPoint, PrivateKey, Transaction, Txid},
     data_contract::{
         accessors::v0::DataContractV0Getters,
         document_type::random_document::{
@@ -51,6 +52,7 @@ use dpp::{
     identity::{
         accessors::{IdentityGettersV0, IdentitySettersV0},
         identity_public_key::{accessors::v0::IdentityPublicKeyGettersV0, v0::IdentityPublicKeyV0},
+        state_transition::asset_lock_proof::{chain::ChainAssetLockProof, InstantAssetLockProof},
         KeyType, PartialIdentity, Purpose as KeyPurpose, SecurityLevel as KeySecurityLevel,
     },
     platform_value::{string_encoding::Encoding, Bytes32, Identifier},
@@ -82,21 +84,30 @@ use simple_signer::signer::SimpleSigner;
 use tokio::sync::{MappedMutexGuard, MutexGuard};
 
 use super::{
-    insight::InsightError, set_clipboard, state::IdentityPrivateKeysMap, wallet::WalletError,
+    clients::PlatformApi,
+    insight::InsightError,
+    key_reuse::{self, KnownKeys},
+    key_vault, set_clipboard,
+    state::{IdentityPrivateKeysMap, QueuedRegistration},
+    wallet::{self, WalletError},
     AppStateUpdate, CompletedTaskPayload, Wallet,
 };
 use crate::backend::wallet::WalletError::Custom;
 use crate::backend::{error::Error, stringify_result_keep_item, AppState, BackendEvent, Task};
 use crate::config::Config;
+use crate::units;
+
+/// Retries of a queued registration after which only manual retries attempt it
+pub const MAX_REGISTRATION_RETRIES: u32 = 5;
 
 pub(super) async fn fetch_identity_by_b58_id(
-    sdk: &Sdk,
+    platform: &impl PlatformApi,
     base58_id: &str,
 ) -> Result<(Option<Identity>, String), String> {
     let id_bytes = Identifier::from_string(base58_id, Encoding::Base58)
         .map_err(|_| "Can't parse identifier as base58 string".to_owned())?;
 
-    let fetch_result = Identity::fetch(sdk, id_bytes).await;
+    let fetch_result = platform.fetch_identity(id_bytes).await;
     stringify_result_keep_item(fetch_result)
 }
 
@@ -105,6 +116,11 @@ pub enum IdentityTask {
     RegisterIdentity(u64),
     LoadKnownIdentity(Identifier),
     ContinueRegisteringIdentity,
+    /// Retries the queued registrations, the ones retried
+    /// [MAX_REGISTRATION_RETRIES] times already only if `include_given_up`
+    RetryQueuedRegistrations {
+        include_given_up: bool,
+    },
     TopUpIdentity(u64),
     WithdrawFromIdentity(u64),
     Refresh,
@@ -117,12 +133,25 @@ pub enum IdentityTask {
     },
     ClearLoadedIdentity,
     ClearRegistrationOfIdentityInProgress,
-    TransferCredits(String, f64),
+    /// Recipient Base58 ID and amount in credits
+    TransferCredits(String, u64),
     LoadMasternodeIdentity(String, String),
     LoadIdentityById(String),
     AddPrivateKeys(Vec<String>),
     ForgetIdentity(Identifier),
+    /// Adds a known identity to the ones signing strategy runs, or removes it
+    ToggleStrategySigning(Identifier),
     RegisterDPNSName(Identity, String),
+    /// Writes known identities' private keys to the vault file, encrypted with
+    /// the passphrase
+    ExportKeyVault {
+        passphrase: String,
+        /// Replace an existing vault file
+        overwrite: bool,
+    },
+    /// Adds private keys from the vault file to the known ones, decrypted
+    /// with the passphrase
+    ImportKeyVault(String),
 
     // Withdrawals Testing Tasks
     WithdrawToNoAddress,
@@ -136,13 +165,18 @@ impl AppState {
         match task {
             IdentityTask::RegisterIdentity(amount) => {
                 let result = self.register_new_identity(sdk, amount).await;
+                let queued = match result {
+                    Ok(_) => None,
+                    Err(_) => self.queue_registration_in_progress().await,
+                };
                 let execution_result = result
                     .as_ref()
                     .map(|_| "Executed successfully.\n\nPrivate keys were logged to supporting_files/new_identity_private_keys.log\n\nIt's recommended that you copy this file to a safe place so you don't lost your funds.".into())
-                    .map_err(|e| e.to_string());
-                let app_state_update = match result {
-                    Ok(identity) => AppStateUpdate::LoadedIdentity(identity),
-                    Err(_) => AppStateUpdate::IdentityRegistrationProgressed,
+                    .map_err(|e| registration_error_message(e, queued));
+                let app_state_update = match (result, queued) {
+                    (Ok(identity), _) => AppStateUpdate::LoadedIdentity(identity),
+                    (Err(_), Some(queued)) => AppStateUpdate::IdentityRegistrationQueue(queued),
+                    (Err(_), None) => AppStateUpdate::IdentityRegistrationProgressed,
                 };
 
                 BackendEvent::TaskCompletedStateChange {
@@ -189,13 +223,18 @@ impl AppState {
             }
             IdentityTask::ContinueRegisteringIdentity => {
                 let result = self.register_new_identity(sdk, 0).await;
+                let queued = match result {
+                    Ok(_) => None,
+                    Err(_) => self.queue_registration_in_progress().await,
+                };
                 let execution_result = result
                     .as_ref()
                     .map(|_| "Executed successfully".into())
-                    .map_err(|e| e.to_string());
-                let app_state_update = match result {
-                    Ok(identity) => AppStateUpdate::LoadedIdentity(identity),
-                    Err(_) => AppStateUpdate::IdentityRegistrationProgressed,
+                    .map_err(|e| registration_error_message(e, queued));
+                let app_state_update = match (result, queued) {
+                    (Ok(identity), _) => AppStateUpdate::LoadedIdentity(identity),
+                    (Err(_), Some(queued)) => AppStateUpdate::IdentityRegistrationQueue(queued),
+                    (Err(_), None) => AppStateUpdate::IdentityRegistrationProgressed,
                 };
 
                 BackendEvent::TaskCompletedStateChange {
@@ -204,6 +243,98 @@ impl AppState {
                     app_state_update,
                 }
             }
+            IdentityTask::RetryQueuedRegistrations { include_given_up } => {
+                // Entries stay queued until they succeed, so a retry stopped midway
+                // loses nothing
+                let queue = self.identity_registration_queue.lock().await.clone();
+
+                // Retries happen in the background, they shouldn't replace the loaded identity
+                let previously_loaded_identity = self.loaded_identity.lock().await.clone();
+
+                let mut attempted = 0;
+                let mut registered = 0;
+                let mut given_up = 0;
+                let mut errors = Vec::new();
+                for (registration, retries) in queue {
+                    if retries >= MAX_REGISTRATION_RETRIES && !include_given_up {
+                        given_up += 1;
+                        continue;
+                    }
+                    let txid = registration.0.txid();
+                    {
+                        let mut in_creation = self
+                            .identity_asset_lock_private_key_in_creation
+                            .lock()
+                            .await;
+                        if in_creation.is_some() {
+                            // Another registration is in progress, this one stays queued
+                            continue;
+                        }
+                        in_creation.replace(registration);
+                    }
+                    // Counted before the attempt, so one that never finishes counts too
+                    self.update_queued_registration(txid, |(_, retries)| *retries += 1)
+                        .await;
+                    attempted += 1;
+
+                    match self.register_new_identity(sdk, 0).await {
+                        Ok(identity) => {
+                            tracing::info!(
+                                "Registered queued identity {}",
+                                identity.id().to_string(Encoding::Base58)
+                            );
+                            registered += 1;
+                            self.identity_registration_queue
+                                .lock()
+                                .await
+                                .retain(|(registration, _)| registration.0.txid() != txid);
+                        }
+                        Err(e) => {
+                            // Progress made, like a retrieved proof, is kept with the entry
+                            let progressed = self
+                                .identity_asset_lock_private_key_in_creation
+                                .lock()
+                                .await
+                                .take();
+                            if let Some(progressed) = progressed {
+                                self.update_queued_registration(txid, |(registration, _)| {
+                                    *registration = progressed
+                                })
+                                .await;
+                            }
+                            errors.push(e.to_string());
+                        }
+                    }
+                }
+
+                if registered > 0 {
+                    *self.loaded_identity.lock().await = previously_loaded_identity;
+                }
+
+                let queued = self.identity_registration_queue.lock().await.len();
+                let mut summary = format!(
+                    "Registered {} of {} queued identities retried, {} still queued",
+                    registered, attempted, queued
+                );
+                if given_up > 0 {
+                    summary.push_str(&format!(
+                        ", {} given up on after {} retries are only retried from the identities \
+                         screen",
+                        given_up, MAX_REGISTRATION_RETRIES
+                    ));
+                }
+                let execution_result = if errors.is_empty() {
+                    Ok(summary.into())
+                } else {
+                    Err(format!("{}\n\n{}", summary, errors.join("\n")))
+                };
+
+                BackendEvent::TaskCompletedStateChange {
+                    task: Task::Identity(task),
+                    execution_result,
+                    app_state_update: AppStateUpdate::IdentityRegistrationQueue(queued),
+                }
+            }
             IdentityTask::ClearRegistrationOfIdentityInProgress => {
                 let mut loaded_identity_asset_lock_private_key_in_creation = self
                     .identity_asset_lock_private_key_in_creation
@@ -264,6 +395,16 @@ impl AppState {
                 }
             }
             IdentityTask::TopUpIdentity(amount) => {
+                let allowed = amount
+                    .checked_mul(units::CREDITS_PER_DUFF)
+                    .ok_or_else(|| "The top up amount is too large".to_owned())
+                    .and_then(|credits| Config::load().check_top_up_allowed(credits));
+                if let Err(e) = allowed {
+                    return BackendEvent::TaskCompleted {
+                        task: Task::Identity(task),
+                        execution_result: Err(e),
+                    };
+                }
                 let result = self.top_up_identity(sdk, amount).await;
                 let execution_result = result
                     .as_ref()
@@ -283,13 +424,13 @@ impl AppState {
             }
             IdentityTask::WithdrawFromIdentity(amount) => {
                 let result = self.withdraw_from_identity(sdk, amount).await;
-                let amount_in_dash = amount as f64 / 100_000_000_000.00;
                 let execution_result = result
                     .as_ref()
                     .map(|identity_and_address_string| {
                         format!(
-                            "Successfully withdrew {} Dash from loaded identity to address {}.",
-                            amount_in_dash, identity_and_address_string.1
+                            "Successfully withdrew {} from loaded identity to address {}.",
+                            units::format_credits(amount),
+                            identity_and_address_string.1
                         )
                         .into()
                     })
@@ -310,14 +451,14 @@ impl AppState {
             }
             IdentityTask::WithdrawToNoAddress => {
                 let amount = 200_000; // credits
-                let amount_in_dash = amount as f64 / 100_000_000_000.00;
                 let result = self.withdraw_from_identity_to_no_address(sdk, amount).await;
                 let execution_result = result
                     .as_ref()
                     .map(|identity_and_address_string| {
                         format!(
-                            "Successfully withdrew {} Dash from loaded identity to address {}.",
-                            amount_in_dash, identity_and_address_string.1
+                            "Successfully withdrew {} from loaded identity to address {}.",
+                            units::format_credits(amount),
+                            identity_and_address_string.1
                         )
                         .into()
                     })
@@ -338,7 +479,6 @@ impl AppState {
             }
             IdentityTask::SelectKeyTypeWithdrawal(ref key_type_string) => {
                 let amount = 200_000; // credits
-                let amount_in_dash = amount as f64 / 100_000_000_000.00;
                 let key_type = match key_type_string.as_str() {
                     "ECDSA_SECP256K1" => KeyType::ECDSA_SECP256K1,
                     "BLS12_381" => KeyType::BLS12_381,
@@ -354,8 +494,9 @@ impl AppState {
                     .as_ref()
                     .map(|identity_and_address_string| {
                         format!(
-                            "Successfully withdrew {} Dash from loaded identity to address {}.",
-                            amount_in_dash, identity_and_address_string.1
+                            "Successfully withdrew {} from loaded identity to address {}.",
+                            units::format_credits(amount),
+                            identity_and_address_string.1
                         )
                         .into()
                     })
@@ -376,7 +517,6 @@ impl AppState {
             }
             IdentityTask::WithdrawToOtherWalletAddress => {
                 let amount = 200_000; // credits
-                let amount_in_dash = amount as f64 / 100_000_000_000.00;
                 let result = self
                     .withdraw_from_identity_to_other_wallet_address(sdk, amount)
                     .await;
@@ -384,8 +524,9 @@ impl AppState {
                     .as_ref()
                     .map(|identity_and_address_string| {
                         format!(
-                            "Successfully withdrew {} Dash from loaded identity to address {}.",
-                            amount_in_dash, identity_and_address_string.1
+                            "Successfully withdrew {} from loaded identity to address {}.",
+                            units::format_credits(amount),
+                            identity_and_address_string.1
                         )
                         .into()
                     })
@@ -406,14 +547,14 @@ impl AppState {
             }
             IdentityTask::WithdrawWithMasterKey => {
                 let amount = 200_000; // credits
-                let amount_in_dash = amount as f64 / 100_000_000_000.00;
                 let result = self.withdraw_with_master_key(sdk, amount).await;
                 let execution_result = result
                     .as_ref()
                     .map(|identity_and_address_string| {
                         format!(
-                            "Successfully withdrew {} Dash from loaded identity to address {}.",
-                            amount_in_dash, identity_and_address_string.1
+                            "Successfully withdrew {} from loaded identity to address {}.",
+                            units::format_credits(amount),
+                            identity_and_address_string.1
                         )
                         .into()
                     })
@@ -481,13 +622,24 @@ impl AppState {
                 )
                 .await
                 {
-                    Ok(app_state_update) => BackendEvent::TaskCompletedStateChange {
-                        task: Task::Identity(task),
-                        execution_result: Ok(CompletedTaskPayload::String(
-                            "Successfully added a key to the identity".to_owned(),
-                        )),
-                        app_state_update,
-                    },
+                    Ok(app_state_update) => {
+                        let warnings = match &app_state_update {
+                            AppStateUpdate::LoadedIdentity(identity) => {
+                                self.key_reuse_warnings(identity).await
+                            }
+                            _ => Vec::new(),
+                        };
+                        BackendEvent::TaskCompletedStateChange {
+                            task: Task::Identity(task),
+                            execution_result: Ok(CompletedTaskPayload::String(
+                                key_reuse::with_warnings(
+                                    "Successfully added a key to the identity",
+                                    &warnings,
+                                ),
+                            )),
+                            app_state_update,
+                        }
+                    }
                     Err(e) => BackendEvent::TaskCompleted {
                         task: Task::Identity(task),
                         execution_result: Err(e),
@@ -509,7 +661,7 @@ impl AppState {
                 let mut transfer_transition =
                     IdentityCreditTransferTransition::default_versioned(sdk.version())
                         .expect("Expected to create a default credit transfer transition");
-                transfer_transition.set_amount((amount * 100_000_000_000.0) as u64);
+                transfer_transition.set_amount(amount);
                 transfer_transition.set_recipient_id(recipient_id);
                 let loaded_identity = self.loaded_identity.lock().await;
                 if let Some(identity) = loaded_identity.as_ref() {
@@ -664,6 +816,8 @@ impl AppState {
                                 }
                             };
 
+                            let warnings = self.key_reuse_warnings(&evonode_identity).await;
+
                             // Insert private key into the state for later use
                             let mut identity_private_keys =
                                 self.known_identities_private_keys.lock().await;
@@ -693,7 +847,7 @@ impl AppState {
                             BackendEvent::TaskCompletedStateChange {
                                 task: Task::Identity(task),
                                 execution_result: Ok(CompletedTaskPayload::String(
-                                    "Loaded Evonode Identity".to_string(),
+                                    key_reuse::with_warnings("Loaded Evonode Identity", &warnings),
                                 )),
                                 app_state_update: AppStateUpdate::LoadedEvonodeIdentity(
                                     MutexGuard::map(loaded_identity, |x| {
@@ -774,6 +928,14 @@ impl AppState {
                     };
                 }
 
+                let identity = self
+                    .loaded_identity
+                    .lock()
+                    .await
+                    .clone()
+                    .expect("identity was set above");
+                let warnings = self.key_reuse_warnings(&identity).await;
+
                 let loaded_identity_lock = self.loaded_identity.lock().await;
                 let loaded_identity_update = MutexGuard::map(loaded_identity_lock, |opt| {
                     opt.as_mut().expect("identity was set above")
@@ -781,7 +943,9 @@ impl AppState {
 
                 BackendEvent::TaskCompletedStateChange {
                     task: Task::Identity(task),
-                    execution_result: Ok("Added identity".into()),
+                    execution_result: Ok(
+                        key_reuse::with_warnings("Added identity", &warnings).into()
+                    ),
                     app_state_update: AppStateUpdate::LoadedIdentity(loaded_identity_update),
                 }
             }
@@ -802,6 +966,7 @@ impl AppState {
                 let identity_fetch_result = Identity::fetch_by_identifier(sdk, identity_id).await;
                 match identity_fetch_result {
                     Ok(Some(identity)) => {
+                        let warnings = self.key_reuse_warnings(&identity).await;
                         let mut loaded_identity_lock = self.loaded_identity.lock().await;
                         loaded_identity_lock.replace(identity.clone());
                         let loaded_identity_update = MutexGuard::map(loaded_identity_lock, |opt| {
@@ -811,7 +976,11 @@ impl AppState {
                         known_identities_lock.insert(identity_id, identity);
                         BackendEvent::TaskCompletedStateChange {
                             task: Task::Identity(task),
-                            execution_result: Ok("Loaded identity from base58 id".into()),
+                            execution_result: Ok(key_reuse::with_warnings(
+                                "Loaded identity from base58 id",
+                                &warnings,
+                            )
+                            .into()),
                             app_state_update: AppStateUpdate::LoadedIdentity(
                                 loaded_identity_update,
                             ),
@@ -843,6 +1012,10 @@ impl AppState {
                 for key in keys_to_remove {
                     known_identities_private_keys.remove(&key);
                 }
+                self.strategy_signing_identities
+                    .lock()
+                    .await
+                    .remove(&identifier);
 
                 // If this is the loaded identity, remove it from there
                 let mut loaded_identity = self.loaded_identity.lock().await;
@@ -862,6 +1035,138 @@ impl AppState {
                     app_state_update: AppStateUpdate::ForgotIdentity,
                 }
             }
+            IdentityTask::ToggleStrategySigning(identifier) => {
+                let key_count = self
+                    .known_identities_private_keys
+                    .lock()
+                    .await
+                    .keys()
+                    .filter(|(id, _)| *id == identifier)
+                    .count();
+                let mut signing_identities = self.strategy_signing_identities.lock().await;
+                let message = if signing_identities.remove(&identifier) {
+                    format!(
+                        "Identity {} no longer signs strategy runs",
+                        identifier.to_string(Encoding::Base58)
+                    )
+                } else if key_count == 0 {
+                    return BackendEvent::TaskCompleted {
+                        task: Task::Identity(task),
+                        execution_result: Err(
+                            "No private keys known for the identity, add them first".to_owned(),
+                        ),
+                    };
+                } else {
+                    signing_identities.insert(identifier);
+                    format!(
+                        "Identity {} signs strategy runs with its {} known private keys",
+                        identifier.to_string(Encoding::Base58),
+                        key_count
+                    )
+                };
+
+                BackendEvent::TaskCompleted {
+                    task: Task::Identity(task),
+                    execution_result: Ok(message.into()),
+                }
+            }
+            IdentityTask::ExportKeyVault {
+                ref passphrase,
+                overwrite,
+            } => {
+                let private_keys = self.known_identities_private_keys.lock().await;
+                let result = key_vault::export_vault(&private_keys, passphrase)
+                    .and_then(|vault| key_vault::write_vault(&vault, overwrite));
+
+                BackendEvent::TaskCompleted {
+                    task: Task::Identity(task.clone()),
+                    execution_result: result.map(|_| {
+                        format!(
+                            "Exported {} private keys to {}, encrypted",
+                            private_keys.len(),
+                            key_vault::VAULT_FILE_PATH,
+                        )
+                        .into()
+                    }),
+                }
+            }
+            IdentityTask::ImportKeyVault(ref passphrase) => {
+                let imported = fs::read_to_string(key_vault::VAULT_FILE_PATH)
+                    .map_err(|e| format!("Failed to read {}: {}", key_vault::VAULT_FILE_PATH, e))
+                    .and_then(|contents| key_vault::import_vault(&contents, passphrase));
+                let imported = match imported {
+                    Ok(imported) => imported,
+                    Err(e) => {
+                        return BackendEvent::TaskCompleted {
+                            task: Task::Identity(task.clone()),
+                            execution_result: Err(e),
+                        }
+                    }
+                };
+
+                let known_identities = self.known_identities.lock().await;
+                let identity_ids: BTreeSet<Identifier> = imported
+                    .keys()
+                    .map(|(identity_id, _)| *identity_id)
+                    .collect();
+                let unknown_identities = identity_ids
+                    .iter()
+                    .filter(|identity_id| !known_identities.contains_key(identity_id))
+                    .count();
+                drop(known_identities);
+
+                // Keys already known with other bytes are kept, the vault may be stale
+                let mut private_keys = self.known_identities_private_keys.lock().await;
+                let mut imported_keys = 0;
+                let mut already_known = 0;
+                let mut conflicts = Vec::new();
+                for (key, private_key) in imported {
+                    match private_keys.get(&key) {
+                        None => {
+                            private_keys.insert(key, private_key);
+                            imported_keys += 1;
+                        }
+                        Some(known) if *known == private_key => already_known += 1,
+                        Some(_) => conflicts.push(key),
+                    }
+                }
+
+                let mut message = format!(
+                    "Imported {} private keys of {} identities",
+                    imported_keys,
+                    identity_ids.len()
+                );
+                if already_known > 0 {
+                    message.push_str(&format!(", {} were already known", already_known));
+                }
+                if unknown_identities > 0 {
+                    message.push_str(&format!(
+                        ", {} of them are not known yet, load them by ID to use the keys",
+                        unknown_identities
+                    ));
+                }
+                if !conflicts.is_empty() {
+                    message.push_str(&format!(
+                        "\n\n{} keys differ from the known ones and were not imported:\n{}",
+                        conflicts.len(),
+                        conflicts
+                            .iter()
+                            .map(|(identity_id, key_id)| format!(
+                                "{} key {}",
+                                identity_id.to_string(Encoding::Base58),
+                                key_id
+                            ))
+                            .collect::<Vec<_>>()
+                            .join("\n")
+                    ));
+                }
+
+                BackendEvent::TaskCompletedStateChange {
+                    task: Task::Identity(task.clone()),
+                    execution_result: Ok(message.into()),
+                    app_state_update: AppStateUpdate::IdentityPrivateKeys(private_keys),
+                }
+            }
         }
     }
 
@@ -1074,6 +1379,7 @@ impl AppState {
         &'s self,
         sdk: &Sdk,
     ) -> Result<MappedMutexGuard<'s, BTreeMap<Identifier, Identity>>, Error> {
+        let mut loaded_identity = self.loaded_identity.lock().await;
         let mut known_identities = self.known_identities.lock().await;
 
         let mut identities_to_refresh = Vec::new();
@@ -1085,6 +1391,13 @@ impl AppState {
 
         for identity_id in identities_to_refresh {
             if let Some(refreshed_identity) = Identity::fetch(sdk, identity_id).await? {
+                // Don't keep a stale copy of the loaded identity either
+                if let Some(loaded) = loaded_identity
+                    .as_mut()
+                    .filter(|loaded| loaded.id() == identity_id)
+                {
+                    *loaded = refreshed_identity.clone();
+                }
                 known_identities
                     .entry(identity_id)
                     .and_modify(|id| *id = refreshed_identity.clone())
@@ -1122,6 +1435,35 @@ impl AppState {
         Ok(())
     }
 
+    /// Moves the registration in progress to the retry queue so a new one can
+    /// be started, returns the queue length if there was one to move.
+    async fn queue_registration_in_progress(&self) -> Option<usize> {
+        let registration = self
+            .identity_asset_lock_private_key_in_creation
+            .lock()
+            .await
+            .take()?;
+        let mut queue = self.identity_registration_queue.lock().await;
+        queue.push((registration, 0));
+        Some(queue.len())
+    }
+
+    /// Updates the queued registration of the asset lock transaction `txid`,
+    /// if it's still queued.
+    async fn update_queued_registration(
+        &self,
+        txid: Txid,
+        update: impl FnOnce(&mut QueuedRegistration),
+    ) {
+        let mut queue = self.identity_registration_queue.lock().await;
+        if let Some(queued) = queue
+            .iter_mut()
+            .find(|(registration, _)| registration.0.txid() == txid)
+        {
+            update(queued);
+        }
+    }
+
     pub(crate) async fn register_new_identity<'s>(
         &'s self,
         sdk: &Sdk,
@@ -1212,61 +1554,47 @@ impl AppState {
             if let Some(identity_info) = maybe_identity_info {
                 identity_info.clone()
             } else {
-                let mut std_rng = StdRng::from_entropy();
-
-                // call returns arrays
-                let (mut identity, raw_keys): (Identity, BTreeMap<IdentityPublicKey, [u8; 32]>) =
-                    Identity::random_identity_with_main_keys_with_private_key(
-                        2,
-                        &mut std_rng,
-                        sdk.version(),
-                    )?;
-
-                // convert each [u8; 32] → Vec<u8>
-                let mut keys: BTreeMap<IdentityPublicKey, Vec<u8>> =
-                    raw_keys.into_iter().map(|(k, v)| (k, v.to_vec())).collect();
-
-                // Add a critical key
-                let (critical_key, critical_private_key) =
-                    IdentityPublicKey::random_ecdsa_critical_level_authentication_key(
-                        2,
-                        None,
-                        sdk.version(),
-                    )?;
-                identity.add_public_key(critical_key.clone());
-                keys.insert(
-                    critical_key,
-                    critical_private_key
-                        .clone()
-                        .try_into()
-                        .expect("Expected private key to be 32 bytes"),
-                );
-
-                // Add a key for transfers
-                let (transfer_key, transfer_private_key) =
-                    IdentityPublicKey::random_key_with_known_attributes(
-                        3,
-                        &mut std_rng,
-                        KeyPurpose::TRANSFER,
-                        KeySecurityLevel::CRITICAL,
-                        KeyType::ECDSA_SECP256K1,
-                        None,
-                        sdk.version(),
-                    )?;
-                identity.add_public_key(transfer_key.clone());
-                keys.insert(
-                    transfer_key,
-                    transfer_private_key
-                        .clone()
-                        .try_into()
-                        .expect("Expected private key to be 32 bytes"),
-                );
-
-                identity.set_id(
-                    asset_lock_proof
-                        .create_identifier()
-                        .expect("expected to create an identifier"),
-                );
+                let identity_id = asset_lock_proof
+                    .create_identifier()
+                    .expect("expected to create an identifier");
+
+                // Keys of HD wallets are derived along DIP-13 paths, so the identity can be
+                // recovered from the mnemonic. The index is reserved while the wallet is
+                // locked, registrations going on at the same time take different ones.
+                let keys = match wallet.identity_master_key()? {
+                    Some(master_key) => {
+                        let identity_index = unused_identity_index(
+                            sdk,
+                            &master_key,
+                            &mut *self.reserved_identity_keys.lock().await,
+                        )
+                        .await?;
+                        tracing::info!(
+                            "Registering identity {} with wallet identity index {}",
+                            identity_id.to_string(Encoding::Base58),
+                            identity_index
+                        );
+                        new_identity_keys(|key_index| {
+                            Ok(wallet::identity_authentication_key(
+                                &master_key,
+                                identity_index,
+                                key_index,
+                            )?)
+                        })?
+                    }
+                    None => {
+                        let mut rng = StdRng::from_entropy();
+                        let network = Config::load().core_network();
+                        new_identity_keys(|_| {
+                            Ok(PrivateKey::new(SecretKey::new(&mut rng), network))
+                        })?
+                    }
+                };
+                let identity = Identity::new_with_id_and_keys(
+                    identity_id,
+                    keys.keys().map(|key| (key.id(), key.clone())).collect(),
+                    sdk.version(),
+                )?;
 
                 identity_asset_lock_private_key_in_creation.replace((
                     asset_lock_transaction.clone(),
@@ -1843,6 +2171,9 @@ impl AppState {
         ))
     }
 
+    /// Broadcasts an asset lock and waits for its proof. The lock stream follows
+    /// the address the transaction sends its change to, `address` is only used
+    /// for a transaction without change.
     pub(crate) async fn broadcast_and_retrieve_asset_lock(
         sdk: &Sdk,
         asset_lock_transaction: &Transaction,
@@ -1854,6 +2185,13 @@ impl AppState {
         )
         .entered();
 
+        let network = Config::load().core_network();
+        let address = &asset_lock_transaction
+            .output
+            .iter()
+            .find_map(|output| Address::from_script(&output.script_pubkey, network).ok())
+            .unwrap_or_else(|| address.clone());
+
         let block_hash = sdk
             .execute(GetBlockchainStatusRequest {}, RequestSettings::default())
             .await?
@@ -1929,6 +2267,24 @@ impl AppState {
         .await
     }
 
+    /// Proof of another credit output of the asset lock transaction a proof is
+    /// for, to use each output of a batched asset lock.
+    pub(crate) fn asset_lock_proof_for_output(
+        asset_lock_proof: &AssetLockProof,
+        output_index: u32,
+    ) -> AssetLockProof {
+        match asset_lock_proof {
+            AssetLockProof::Instant(proof) => AssetLockProof::Instant(InstantAssetLockProof {
+                output_index,
+                ..proof.clone()
+            }),
+            AssetLockProof::Chain(proof) => AssetLockProof::Chain(ChainAssetLockProof {
+                out_point: OutPoint::new(proof.out_point.txid, output_index),
+                ..proof.clone()
+            }),
+        }
+    }
+
     pub async fn _retrieve_asset_lock_proof(
         sdk: &Sdk,
         wallet: &mut Wallet,
@@ -1964,40 +2320,38 @@ impl AppState {
     ) -> Result<(), WalletError> {
         let private_keys = private_keys_as_strings
             .iter()
-            .map(|private_key| match private_key.len() {
-                64 => {
-                    // hex
-                    let bytes = match hex::decode(private_key) {
-                        Ok(bytes) => bytes,
-                        Err(_) => {
-                            return Err(WalletError::Custom("Failed to decode hex".to_string()))
-                        }
-                    };
-                    let network = Config::load().core_network();
-                    match PrivateKey::from_slice(bytes.as_slice(), network) {
-                        Ok(key) => Ok(key),
-                        Err(_) => {
-                            return Err(WalletError::Custom("Expected private key".to_string()))
-                        }
-                    }
-                }
-                51 | 52 => {
-                    // wif
-                    match PrivateKey::from_wif(private_key) {
-                        Ok(key) => Ok(key),
-                        Err(_) => return Err(WalletError::Custom("Expected WIF key".to_string())),
-                    }
-                }
-                _ => {
-                    return Err(WalletError::Custom(
-                        "Private key can't be decoded from hex or wif".to_string(),
-                    ));
-                }
-            })
+            .map(|private_key| private_key_from_string(private_key))
             .collect::<Result<Vec<_>, _>>()?;
         Self::add_identity_with_private_keys(&self, identity_id, private_keys, sdk).await
     }
 
+    /// Warnings for the keys of an identity also used by another known
+    /// identity or by a wallet, logged as well.
+    pub(crate) async fn key_reuse_warnings(&self, identity: &Identity) -> Vec<String> {
+        let mut known_keys = KnownKeys::default();
+        for known_identity in self.known_identities.lock().await.values() {
+            known_keys.add_identity(known_identity);
+        }
+        if let Some(wallet) = self.loaded_wallet.lock().await.as_ref() {
+            known_keys.add_wallet("the loaded wallet", wallet);
+        }
+        if let Some(wallet) = self.sweep_wallet.lock().await.as_ref() {
+            known_keys.add_wallet("the sweep wallet", wallet);
+        }
+        for (name, wallet) in self.stored_wallets.lock().await.iter() {
+            known_keys.add_wallet(&format!("wallet {}", name), wallet);
+        }
+        let warnings = known_keys.reused_by(identity);
+        for warning in &warnings {
+            tracing::warn!(
+                "Identity {}: {}",
+                identity.id().to_string(Encoding::Base58),
+                warning
+            );
+        }
+        warnings
+    }
+
     pub async fn add_identity_with_private_keys<'s>(
         &self,
         identity_id: &Identifier,
@@ -2054,6 +2408,118 @@ impl AppState {
     }
 }
 
+/// Purpose and security level of the keys of new identities, by key ID
+const NEW_IDENTITY_KEYS: [(KeyPurpose, KeySecurityLevel); 4] = [
+    (KeyPurpose::AUTHENTICATION, KeySecurityLevel::MASTER),
+    (KeyPurpose::AUTHENTICATION, KeySecurityLevel::HIGH),
+    (KeyPurpose::AUTHENTICATION, KeySecurityLevel::CRITICAL),
+    (KeyPurpose::TRANSFER, KeySecurityLevel::CRITICAL),
+];
+
+/// Keys of a new identity, see [NEW_IDENTITY_KEYS], with the private key of
+/// each key ID. Key IDs of keys derived from the wallet are the key indexes of
+/// their derivation paths.
+fn new_identity_keys(
+    mut private_key: impl FnMut(u32) -> Result<PrivateKey, Error>,
+) -> Result<BTreeMap<IdentityPublicKey, Vec<u8>>, Error> {
+    let secp = Secp256k1::new();
+    NEW_IDENTITY_KEYS
+        .iter()
+        .enumerate()
+        .map(|(key_id, (purpose, security_level))| {
+            let private_key = private_key(key_id as u32)?;
+            let public_key: IdentityPublicKey = IdentityPublicKeyV0 {
+                id: key_id as KeyID,
+                purpose: *purpose,
+                security_level: *security_level,
+                contract_bounds: None,
+                key_type: KeyType::ECDSA_SECP256K1,
+                read_only: false,
+                data: private_key.public_key(&secp).to_bytes().into(),
+                disabled_at: None,
+            }
+            .into();
+            Ok((public_key, private_key.inner.secret_bytes().to_vec()))
+        })
+        .collect()
+}
+
+/// Used identity indexes looked up on Platform before giving up on finding an
+/// unused one, each lookup is a Platform query. Indexes reserved by
+/// registrations going on aren't looked up.
+const IDENTITY_INDEX_GAP_LIMIT: u32 = 100;
+
+/// First identity index of the wallet whose master key no identity on Platform
+/// has and no other registration was handed, identities registered by the
+/// wallet before take the lower ones. The index is reserved in `reserved`, the
+/// identity may not be found on Platform yet when the next registration looks.
+async fn unused_identity_index(
+    sdk: &Sdk,
+    master_key: &ExtendedPrivKey,
+    reserved: &mut BTreeSet<[u8; 20]>,
+) -> Result<u32, Error> {
+    let secp = Secp256k1::new();
+    let mut looked_up = 0;
+    for identity_index in 0..u32::MAX {
+        let identity_master_key =
+            wallet::identity_authentication_key(master_key, identity_index, 0)?;
+        let public_key_hash = identity_master_key
+            .public_key(&secp)
+            .pubkey_hash()
+            .to_byte_array();
+        if reserved.contains(&public_key_hash) {
+            continue;
+        }
+        if looked_up == IDENTITY_INDEX_GAP_LIMIT {
+            return Err(Error::IdentityRegistrationError(format!(
+                "The wallet's identities use the {} identity indexes looked up, up to index {}, \
+                 register with another wallet",
+                IDENTITY_INDEX_GAP_LIMIT,
+                identity_index - 1
+            )));
+        }
+        looked_up += 1;
+        if Identity::fetch(sdk, PublicKeyHash(public_key_hash))
+            .await?
+            .is_none()
+        {
+            reserved.insert(public_key_hash);
+            return Ok(identity_index);
+        }
+    }
+    Err(Error::IdentityRegistrationError(
+        "The wallet has no unused identity index left".to_string(),
+    ))
+}
+
+/// Decodes a private key given in hex or WIF.
+pub(crate) fn private_key_from_string(private_key: &str) -> Result<PrivateKey, WalletError> {
+    match private_key.len() {
+        64 => {
+            // hex
+            let bytes = match hex::decode(private_key) {
+                Ok(bytes) => bytes,
+                Err(_) => return Err(WalletError::Custom("Failed to decode hex".to_string())),
+            };
+            let network = Config::load().core_network();
+            match PrivateKey::from_slice(bytes.as_slice(), network) {
+                Ok(key) => Ok(key),
+                Err(_) => Err(WalletError::Custom("Expected private key".to_string())),
+            }
+        }
+        51 | 52 => {
+            // wif
+            match PrivateKey::from_wif(private_key) {
+                Ok(key) => Ok(key),
+                Err(_) => Err(WalletError::Custom("Expected WIF key".to_string())),
+            }
+        }
+        _ => Err(WalletError::Custom(
+            "Private key can't be decoded from hex or wif".to_string(),
+        )),
+    }
+}
+
 async fn add_identity_key<'a>(
     sdk: &Sdk,
     mut loaded_identity: MappedMutexGuard<'a, Identity>,
@@ -2188,3 +2654,14 @@ fn log_identity_keys(
 
     Ok(())
 }
+
+fn registration_error_message(error: &Error, queued: Option<usize>) -> String {
+    match queued {
+        Some(queued) => format!(
+            "{}\n\nThe registration was queued and will be retried with the same asset lock \
+             ({} queued)",
+            error, queued
+        ),
+        None => error.to_string(),
+    }
+}
@@ -3,7 +3,10 @@ use std::collections::HashMap;
 use dapi_grpc::tonic::transport::Uri;
 use dpp::dashcore::{Address, OutPoint, ScriptBuf, TxOut, Txid};
 
+use super::clients::{InsightApi, UtxosWithConfirmations};
+
 const ADDRESS_UTXO_PATH: &str = "addrs/utxo";
+const STATUS_PATH: &str = "status";
 
 #[derive(Debug, thiserror::Error)]
 #[error("insight error: {0}")]
@@ -16,9 +19,26 @@ impl InsightAPIClient {
     pub fn new(uri: Uri) -> Self {
         Self(uri)
     }
+}
+
+impl InsightApi for InsightAPIClient {
+    fn uri(&self) -> &Uri {
+        &self.0
+    }
+
+    async fn status(&self) -> Result<reqwest::StatusCode, InsightError> {
+        let url = format!("{}/{}", self.0, STATUS_PATH);
+
+        reqwest::Client::new()
+            .get(&url)
+            .timeout(std::time::Duration::from_secs(5))
+            .send()
+            .await
+            .map(|resp| resp.status())
+            .map_err(|e| InsightError(e.to_string()))
+    }
 
-    /// Fetches the unspent transaction outputs (UTXOs) with amounts for the
-    /// specified addresses.
+    /// Posts the addresses to the `addrs/utxo` endpoint.
     ///
     /// # Arguments
     ///
@@ -28,8 +48,9 @@ impl InsightAPIClient {
     /// # Returns
     ///
     /// A `Result` containing either:
-    /// - A `HashMap<OutPoint, TxOut>` where each `OutPoint` is a reference to a
-    ///   UTXO and `TxOut` contains its details, if the operation is successful.
+    /// - A `HashMap<OutPoint, (TxOut, Option<u32>)>` where each `OutPoint` is a
+    ///   reference to a UTXO, `TxOut` contains its details and the number of
+    ///   its confirmations, if Insight reports them, if the operation is successful.
     /// - An `InsightError` if the request fails or the response cannot be
     ///   parsed.
     ///
@@ -42,10 +63,10 @@ impl InsightAPIClient {
     /// - Missing fields in the JSON response (`txid`, `vout`, `satoshis`,
     ///   `scriptPubKey`).
     /// - Invalid formats for `txid` or `scriptPubKey`.
-    pub async fn utxos_with_amount_for_addresses(
+    async fn utxos_with_amount_for_addresses(
         &self,
         addresses: &[&Address],
-    ) -> Result<HashMap<OutPoint, TxOut>, InsightError> {
+    ) -> Result<UtxosWithConfirmations, InsightError> {
         let url = format!("{}/{}", self.0, ADDRESS_UTXO_PATH);
 
         let addr_str = addresses
@@ -101,13 +122,20 @@ impl InsightAPIClient {
                 .ok_or_else(|| InsightError("Missing scriptPubKey".into()))?;
             let script = ScriptBuf::from_hex(script_buf_str)
                 .map_err(|_| InsightError("Invalid scriptPubKey format".into()))?;
+            let confirmations = utxo
+                .get("confirmations")
+                .and_then(|v| v.as_u64())
+                .map(|confirmations| confirmations as u32);
 
             utxos.insert(
                 OutPoint { txid, vout },
-                TxOut {
-                    value,
-                    script_pubkey: script,
-                },
+                (
+                    TxOut {
+                        value,
+                        script_pubkey: script,
+                    },
+                    confirmations,
+                ),
             );
         }
 
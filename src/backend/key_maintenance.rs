@@ -0,0 +1,213 @@
+//! Identity key maintenance operations for strategy runs.
+//!
+//! The strategy's own identity update operations only add or disable a number
+//! of keys picked by the strategy library. A key maintenance operation chooses
+//! the purpose, security level and type of the keys it adds, the purpose of the
+//! keys it disables, and can rotate the master key. `Strategy` has no place for
+//! them, so like frequency profiles they are kept per strategy aside and their
+//! transitions are added to each block's during a run.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+};
+
+use bincode::{Decode, Encode};
+use dpp::{
+    identity::{
+        accessors::{IdentityGettersV0, IdentitySettersV0},
+        identity_public_key::{
+            accessors::v0::{IdentityPublicKeyGettersV0, IdentityPublicKeySettersV0},
+            v0::IdentityPublicKeyV0,
+        },
+        KeyID, KeyType, Purpose, SecurityLevel,
+    },
+    platform_value::Identifier,
+    prelude::{Identity, IdentityPublicKey},
+    state_transition::{
+        identity_update_transition::{
+            methods::IdentityUpdateTransitionMethodsV0, v0::IdentityUpdateTransitionV0,
+        },
+        public_key_in_creation::v0::IdentityPublicKeyInCreationV0,
+        StateTransition,
+    },
+    version::PlatformVersion,
+};
+use rand::{rngs::StdRng, seq::SliceRandom};
+use simple_signer::signer::SimpleSigner;
+
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct KeyMaintenanceOp {
+    pub add_count: u16,
+    pub add_purpose: Purpose,
+    pub add_security_level: SecurityLevel,
+    pub add_key_type: KeyType,
+    pub disable_count: u16,
+    pub disable_purpose: Purpose,
+    /// Adds a new master key and disables the current one
+    pub rotate_master: bool,
+    pub chance_per_block: f64,
+}
+
+impl KeyMaintenanceOp {
+    /// Keys added, disabled and rotated by the operation.
+    pub fn changes(&self) -> String {
+        let mut parts = Vec::new();
+        if self.add_count > 0 {
+            parts.push(format!(
+                "add {} {:?}/{:?}/{:?}",
+                self.add_count, self.add_purpose, self.add_security_level, self.add_key_type
+            ));
+        }
+        if self.disable_count > 0 {
+            parts.push(format!(
+                "disable {} {:?}",
+                self.disable_count, self.disable_purpose
+            ));
+        }
+        if self.rotate_master {
+            parts.push("rotate master key".to_owned());
+        }
+        parts.join(", ")
+    }
+}
+
+impl Display for KeyMaintenanceOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "IdentityUpdateKeys({}); chance per block: {}",
+            self.changes(),
+            self.chance_per_block
+        )
+    }
+}
+
+/// Security level keys of a purpose are required to have, `None` if any
+/// non-master level will do.
+pub(crate) fn forced_security_level(purpose: Purpose) -> Option<SecurityLevel> {
+    match purpose {
+        Purpose::TRANSFER => Some(SecurityLevel::CRITICAL),
+        Purpose::ENCRYPTION | Purpose::DECRYPTION => Some(SecurityLevel::MEDIUM),
+        _ => None,
+    }
+}
+
+/// Builds the identity update transition of an operation and applies it to the
+/// local copy of the identity, new private keys are added to the signer.
+///
+/// At least one key of the disabled purpose stays enabled so the other
+/// operations of the strategy can still sign with it.
+pub(crate) fn key_maintenance_transition(
+    op: &KeyMaintenanceOp,
+    identity: &mut Identity,
+    identity_nonce_counter: &mut BTreeMap<Identifier, u64>,
+    signer: &mut SimpleSigner,
+    rng: &mut StdRng,
+    block_time_ms: u64,
+    platform_version: &PlatformVersion,
+) -> Result<StateTransition, String> {
+    let (master_key_id, _) = identity
+        .public_keys()
+        .iter()
+        .find(|(_, key)| {
+            key.security_level() == SecurityLevel::MASTER && key.disabled_at().is_none()
+        })
+        .ok_or_else(|| "No enabled master key found for identity".to_owned())?;
+    let master_key_id = *master_key_id;
+
+    let mut next_key_id = identity.get_public_key_max_id() + 1;
+    let mut new_key = |purpose, security_level, key_type| -> Result<IdentityPublicKey, String> {
+        let (public_key, private_key) =
+            KeyType::random_public_and_private_key_data(&key_type, rng, platform_version)
+                .map_err(|e| format!("Cannot generate key pair: {e}"))?;
+        let key: IdentityPublicKey = IdentityPublicKeyV0 {
+            id: next_key_id,
+            purpose,
+            security_level,
+            contract_bounds: None,
+            key_type,
+            read_only: false,
+            data: public_key.into(),
+            disabled_at: None,
+        }
+        .into();
+        next_key_id += 1;
+        signer.add_key(
+            key.clone(),
+            private_key
+                .try_into()
+                .expect("Expected private key to be 32 bytes"),
+        );
+        Ok(key)
+    };
+
+    let mut add_keys = Vec::new();
+    for _ in 0..op.add_count {
+        add_keys.push(new_key(
+            op.add_purpose,
+            op.add_security_level,
+            op.add_key_type,
+        )?);
+    }
+    if op.rotate_master {
+        add_keys.push(new_key(
+            Purpose::AUTHENTICATION,
+            SecurityLevel::MASTER,
+            KeyType::ECDSA_SECP256K1,
+        )?);
+    }
+
+    let mut disable_candidates: Vec<KeyID> = identity
+        .public_keys()
+        .iter()
+        .filter(|(_, key)| {
+            key.purpose() == op.disable_purpose
+                && key.security_level() != SecurityLevel::MASTER
+                && key.disabled_at().is_none()
+        })
+        .map(|(id, _)| *id)
+        .collect();
+    disable_candidates.shuffle(rng);
+    let disable_count = (op.disable_count as usize).min(disable_candidates.len().saturating_sub(1));
+    let mut disable_keys: Vec<KeyID> = disable_candidates[..disable_count].to_vec();
+    if op.rotate_master {
+        disable_keys.push(master_key_id);
+    }
+
+    if add_keys.is_empty() && disable_keys.is_empty() {
+        return Err("Nothing to add or disable".to_owned());
+    }
+
+    let nonce = identity_nonce_counter.entry(identity.id()).or_default();
+    *nonce += 1;
+
+    identity.bump_revision();
+    let transition = IdentityUpdateTransitionV0::try_from_identity_with_signer(
+        identity,
+        &master_key_id,
+        add_keys
+            .iter()
+            .map(|key| Into::<IdentityPublicKeyInCreationV0>::into(key.clone()).into())
+            .collect(),
+        disable_keys.clone(),
+        *nonce,
+        0,
+        &*signer,
+        platform_version,
+        None,
+    )
+    .map_err(|e| format!("Unable to create identity update transition: {e}"))?;
+
+    // Mirror the update so later blocks pick keys from the updated identity
+    for key in add_keys {
+        identity.add_public_key(key);
+    }
+    for key_id in disable_keys {
+        if let Some(key) = identity.public_keys_mut().get_mut(&key_id) {
+            key.set_disabled_at(block_time_ms);
+        }
+    }
+
+    Ok(transition)
+}
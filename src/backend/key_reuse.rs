@@ -0,0 +1,106 @@
+//! Public keys shared between known identities, or with a wallet.
+//!
+//! Signers of strategy runs look private keys up by public key, so a key used
+//! by two identities signs for whichever of them was added last, and the
+//! other one's transitions fail with signature errors that don't point at the
+//! key. Identities are checked against the known ones and the wallets when
+//! they're loaded, imported or given keys, and the reused keys are reported.
+
+use std::fmt::{self, Display};
+
+use dpp::{
+    dashcore::hashes::Hash,
+    identity::{accessors::IdentityGettersV0, hash::IdentityPublicKeyHashMethodsV0, KeyID},
+    platform_value::string_encoding::Encoding,
+    prelude::{Identifier, Identity},
+};
+
+use super::Wallet;
+
+#[derive(Debug, Clone, PartialEq)]
+enum KeyOwner {
+    Identity(Identifier, KeyID),
+    Wallet(String),
+}
+
+impl Display for KeyOwner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyOwner::Identity(identity_id, key_id) => write!(
+                f,
+                "key {} of identity {}",
+                key_id,
+                identity_id.to_string(Encoding::Base58)
+            ),
+            KeyOwner::Wallet(name) => write!(f, "a key of {}", name),
+        }
+    }
+}
+
+/// Hashes of the public keys of identities and wallets, to find the ones
+/// another identity uses too.
+#[derive(Debug, Default)]
+pub(crate) struct KnownKeys(Vec<(KeyOwner, [u8; 20])>);
+
+impl KnownKeys {
+    pub(crate) fn add_identity(&mut self, identity: &Identity) {
+        for (key_id, key) in identity.public_keys() {
+            if let Ok(hash) = key.public_key_hash() {
+                self.0
+                    .push((KeyOwner::Identity(identity.id(), *key_id), hash));
+            }
+        }
+    }
+
+    /// Adds the keys of a wallet, its change keys included, described by
+    /// `name` in warnings.
+    pub(crate) fn add_wallet(&mut self, name: &str, wallet: &Wallet) {
+        // Keys of Core wallets stay in dashd
+        let Wallet::SingleKeyWallet(wallet) = wallet else {
+            return;
+        };
+        let public_keys = std::iter::once(&wallet.public_key)
+            .chain(wallet.change_keys.iter().map(|key| &key.public_key));
+        for public_key in public_keys {
+            self.0.push((
+                KeyOwner::Wallet(name.to_owned()),
+                public_key.pubkey_hash().to_byte_array(),
+            ));
+        }
+    }
+
+    /// Warnings for the keys of an identity also used by another identity or
+    /// a wallet.
+    pub(crate) fn reused_by(&self, identity: &Identity) -> Vec<String> {
+        let mut warnings = Vec::new();
+        for (key_id, key) in identity.public_keys() {
+            let Ok(hash) = key.public_key_hash() else {
+                continue;
+            };
+            for (owner, _) in self.0.iter().filter(|(owner, known_hash)| {
+                *known_hash == hash
+                    && !matches!(owner, KeyOwner::Identity(id, _) if *id == identity.id())
+            }) {
+                warnings.push(format!("Key {} is also {}", key_id, owner));
+            }
+        }
+        warnings
+    }
+}
+
+/// Execution result message followed by the key reuse warnings, if any.
+pub(crate) fn with_warnings(message: &str, warnings: &[String]) -> String {
+    if warnings.is_empty() {
+        message.to_owned()
+    } else {
+        format!(
+            "{}\n\nWarning, reused keys make strategy runs fail to sign:\n{}",
+            message,
+            warnings
+                .iter()
+                .map(|warning| format!(" - {}", warning))
+                .collect::<Vec<_>>()
+                .join("\n")
+        )
+    }
+}
@@ -0,0 +1,239 @@
+//! File vault for known identities' private keys.
+//!
+//! Private keys otherwise live only in the application state file, which is
+//! tied to a network and not meant to be shared. A vault is a JSON file with the
+//! keys that can be moved between machines, encrypted with a passphrase
+//! (AES-256-GCM, key derived with PBKDF2-HMAC-SHA256).
+
+use std::{fs::OpenOptions, io::Write, path::Path};
+
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Nonce,
+};
+use dpp::platform_value::string_encoding::Encoding;
+use dpp::prelude::Identifier;
+use rand::Rng;
+use serde_json::{json, Value};
+use sha2::Sha256;
+
+use super::state::IdentityPrivateKeysMap;
+use crate::config::Config;
+
+pub(crate) const VAULT_FILE_PATH: &str = "supporting_files/key_vault.json";
+
+const VAULT_VERSION: u64 = 1;
+const PBKDF2_ROUNDS: u32 = 600_000;
+
+/// Serializes the keys into a vault encrypted with the passphrase, which
+/// can't be empty.
+///
+/// ```json
+/// {
+///   "version": 1,
+///   "network": "testnet",
+///   "keys": [
+///     { "identity_id": "<base58>", "key_id": 0, "private_key": "<hex>" }
+///   ]
+/// }
+/// ```
+///
+/// The vault keeps `version` and `network` readable and replaces `keys` with
+/// hex `salt`, `nonce` and `ciphertext` of the `keys` array.
+pub(crate) fn export_vault(
+    keys: &IdentityPrivateKeysMap,
+    passphrase: &str,
+) -> Result<String, String> {
+    encrypt_vault(keys, passphrase, &Config::load().network)
+}
+
+/// Reads keys back from a vault written by [export_vault].
+pub(crate) fn import_vault(
+    contents: &str,
+    passphrase: &str,
+) -> Result<IdentityPrivateKeysMap, String> {
+    decrypt_vault(contents, passphrase, &Config::load().network)
+}
+
+fn encrypt_vault(
+    keys: &IdentityPrivateKeysMap,
+    passphrase: &str,
+    network: &str,
+) -> Result<String, String> {
+    if passphrase.is_empty() {
+        return Err("The vault passphrase can't be empty".to_owned());
+    }
+
+    let keys_json = Value::Array(
+        keys.iter()
+            .map(|((identity_id, key_id), private_key)| {
+                json!({
+                    "identity_id": identity_id.to_string(Encoding::Base58),
+                    "key_id": key_id,
+                    "private_key": hex::encode(private_key),
+                })
+            })
+            .collect(),
+    );
+
+    let mut vault = json!({
+        "version": VAULT_VERSION,
+        "network": network,
+    });
+
+    let mut rng = rand::thread_rng();
+    let salt: [u8; 16] = rng.gen();
+    let nonce: [u8; 12] = rng.gen();
+    let ciphertext = vault_cipher(passphrase, &salt)
+        .encrypt(Nonce::from_slice(&nonce), keys_json.to_string().as_bytes())
+        .map_err(|_| "Failed to encrypt the vault".to_owned())?;
+    vault["salt"] = hex::encode(salt).into();
+    vault["nonce"] = hex::encode(nonce).into();
+    vault["ciphertext"] = hex::encode(ciphertext).into();
+
+    Ok(serde_json::to_string_pretty(&vault).expect("json values are serializable"))
+}
+
+fn decrypt_vault(
+    contents: &str,
+    passphrase: &str,
+    network: &str,
+) -> Result<IdentityPrivateKeysMap, String> {
+    let vault: Value =
+        serde_json::from_str(contents).map_err(|e| format!("Invalid vault file: {}", e))?;
+
+    if vault["version"].as_u64() != Some(VAULT_VERSION) {
+        return Err(format!("Unsupported vault version {}", vault["version"]));
+    }
+    if vault["network"].as_str() != Some(network) {
+        return Err(format!(
+            "The vault was exported on {}, not {}",
+            vault["network"], network
+        ));
+    }
+
+    let salt = hex_field(&vault, "salt")?;
+    let nonce = hex_field(&vault, "nonce")?;
+    if nonce.len() != 12 {
+        return Err("Invalid vault nonce".to_owned());
+    }
+    let plaintext = vault_cipher(passphrase, &salt)
+        .decrypt(
+            Nonce::from_slice(&nonce),
+            hex_field(&vault, "ciphertext")?.as_slice(),
+        )
+        .map_err(|_| "Wrong passphrase or corrupted vault".to_owned())?;
+    let keys_json: Value =
+        serde_json::from_slice(&plaintext).map_err(|e| format!("Invalid vault keys: {}", e))?;
+
+    keys_json
+        .as_array()
+        .ok_or("The vault has no keys")?
+        .iter()
+        .map(|entry| {
+            let identity_id = entry["identity_id"]
+                .as_str()
+                .and_then(|id| Identifier::from_string(id, Encoding::Base58).ok())
+                .ok_or_else(|| format!("Invalid identity id in vault entry {}", entry))?;
+            let key_id = entry["key_id"]
+                .as_u64()
+                .and_then(|id| u32::try_from(id).ok())
+                .ok_or_else(|| format!("Invalid key id in vault entry {}", entry))?;
+            let private_key = hex_field(entry, "private_key")?;
+            if private_key.len() != 32 {
+                return Err(format!("Invalid private key in vault entry {}", entry));
+            }
+            Ok(((identity_id, key_id), private_key))
+        })
+        .collect()
+}
+
+/// Writes the vault file readable by its owner only. An existing vault is only
+/// replaced if `overwrite` is set.
+pub(crate) fn write_vault(vault: &str, overwrite: bool) -> Result<(), String> {
+    let path = Path::new(VAULT_FILE_PATH);
+    if path.exists() && !overwrite {
+        return Err(format!(
+            "{} already exists, export again and confirm replacing it",
+            VAULT_FILE_PATH
+        ));
+    }
+    write_private_file(path, vault).map_err(|e| format!("Failed to write the vault: {}", e))
+}
+
+/// Writes a file holding private keys, readable by its owner only.
+pub(crate) fn write_private_file(path: &Path, contents: &str) -> std::io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+        options.mode(0o600);
+        // The mode only applies to new files
+        if path.exists() {
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(contents.as_bytes()))
+}
+
+fn vault_cipher(passphrase: &str, salt: &[u8]) -> Aes256Gcm {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, PBKDF2_ROUNDS, &mut key);
+    Aes256Gcm::new_from_slice(&key).expect("key is 32 bytes")
+}
+
+fn hex_field(value: &Value, field: &str) -> Result<Vec<u8>, String> {
+    value[field]
+        .as_str()
+        .and_then(|hex_string| hex::decode(hex_string).ok())
+        .ok_or_else(|| format!("Invalid vault field `{}`", field))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys() -> IdentityPrivateKeysMap {
+        [
+            ((Identifier::new([1; 32]), 0), vec![2; 32]),
+            ((Identifier::new([1; 32]), 1), vec![3; 32]),
+            ((Identifier::new([4; 32]), 0), vec![5; 32]),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn round_trips_keys() {
+        let vault = encrypt_vault(&keys(), "passphrase", "testnet").unwrap();
+        assert_eq!(decrypt_vault(&vault, "passphrase", "testnet"), Ok(keys()));
+    }
+
+    #[test]
+    fn refuses_wrong_passphrase_and_tampered_ciphertext() {
+        let vault = encrypt_vault(&keys(), "passphrase", "testnet").unwrap();
+        assert!(decrypt_vault(&vault, "passphrase!", "testnet").is_err());
+
+        let mut tampered: Value = serde_json::from_str(&vault).unwrap();
+        let mut ciphertext = hex_field(&tampered, "ciphertext").unwrap();
+        ciphertext[0] ^= 1;
+        tampered["ciphertext"] = hex::encode(ciphertext).into();
+        assert!(decrypt_vault(&tampered.to_string(), "passphrase", "testnet").is_err());
+    }
+
+    #[test]
+    fn refuses_vaults_of_another_network() {
+        let vault = encrypt_vault(&keys(), "passphrase", "testnet").unwrap();
+        let error = decrypt_vault(&vault, "passphrase", "dash").unwrap_err();
+        assert!(error.contains("exported on"));
+    }
+
+    #[test]
+    fn refuses_empty_passphrase() {
+        assert!(encrypt_vault(&keys(), "", "testnet").is_err());
+    }
+}
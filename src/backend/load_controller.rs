@@ -0,0 +1,104 @@
+//! Closed-loop load control for strategy runs.
+//!
+//! With a latency target the run no longer offers a fixed load: every stats
+//! window the controller compares the 95th percentile of inclusion latency to
+//! the target, raising the load while it holds and cutting it back once it
+//! doesn't. The best throughput seen while under target is the run's
+//! maximum sustainable throughput.
+
+use std::{
+    fmt::{self, Display},
+    time::Duration,
+};
+
+/// Completed waits a window needs before its latency is trusted
+const MIN_WINDOW_SAMPLES: u64 = 10;
+/// Load multiplier applied while latency is under target
+const INCREASE_FACTOR: f64 = 1.2;
+/// Load multiplier applied once latency goes over target
+const DECREASE_FACTOR: f64 = 0.7;
+const MIN_SCALE: f64 = 0.05;
+const MAX_SCALE: f64 = 50.0;
+
+/// Outcome of a latency targeted run.
+#[derive(Debug, Clone)]
+pub struct LoadControlReport {
+    pub target_p95: Duration,
+    /// Best inclusion rate of a window meeting the target, `None` if none did
+    pub max_sustainable_tps: Option<f64>,
+    /// Load multiplier of the configured frequencies when the run ended
+    pub final_scale: f64,
+}
+
+impl Display for LoadControlReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.max_sustainable_tps {
+            Some(tps) => write!(
+                f,
+                "Max sustainable throughput with p95 latency under {}s: {:.2} tx/s",
+                self.target_p95.as_secs(),
+                tps
+            )?,
+            None => write!(
+                f,
+                "p95 latency never stayed under {}s, no sustainable throughput found",
+                self.target_p95.as_secs()
+            )?,
+        }
+        write!(f, " (final load {:.2}x)", self.final_scale)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct LoadController {
+    target_p95: Duration,
+    scale: f64,
+    max_sustainable_tps: Option<f64>,
+}
+
+impl LoadController {
+    pub(crate) fn new(target_p95: Duration) -> Self {
+        LoadController {
+            target_p95,
+            scale: 1.0,
+            max_sustainable_tps: None,
+        }
+    }
+
+    /// Multiplier for the configured operation frequencies.
+    pub(crate) fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// Adjusts the load after a window where `included` transitions completed
+    /// their wait with the given 95th percentile latency.
+    pub(crate) fn observe_window(&mut self, p95: Duration, included: u64, window: Duration) {
+        if included < MIN_WINDOW_SAMPLES || window.is_zero() {
+            return;
+        }
+        let tps = included as f64 / window.as_secs_f64();
+        let previous_scale = self.scale;
+        if p95 <= self.target_p95 {
+            self.max_sustainable_tps = Some(self.max_sustainable_tps.unwrap_or(0.0).max(tps));
+            self.scale = (self.scale * INCREASE_FACTOR).min(MAX_SCALE);
+        } else {
+            self.scale = (self.scale * DECREASE_FACTOR).max(MIN_SCALE);
+        }
+        tracing::info!(
+            "Load control: p95 {}s for a {}s target at {:.2} tx/s, load {:.2}x -> {:.2}x",
+            p95.as_secs(),
+            self.target_p95.as_secs(),
+            tps,
+            previous_scale,
+            self.scale
+        );
+    }
+
+    pub(crate) fn finish(self) -> LoadControlReport {
+        LoadControlReport {
+            target_p95: self.target_p95,
+            max_sustainable_tps: self.max_sustainable_tps,
+            final_scale: self.scale,
+        }
+    }
+}
@@ -0,0 +1,284 @@
+//! Load shapes for strategy runs.
+//!
+//! A run offers the load of the configured operation frequencies from its
+//! first loop to its last. A profile scales those frequencies by the time
+//! since the load started instead, to find where the network starts to lag on
+//! a ramp or how it recovers from a spike. Each profile splits the run in
+//! phases, reported separately once the run is over.
+//!
+//! Profiles are written as text, both in the run form and on the command
+//! line:
+//! - `constant`: the configured load for the whole run
+//! - `ramp:<secs>`: from no load to the configured one over the first seconds
+//! - `step:<secs>:<steps>`: the configured load reached in equal steps, one
+//!   every `secs` seconds
+//! - `spike:<start secs>:<length secs>:<multiplier>`: the configured load,
+//!   multiplied during the spike
+
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+    time::Duration,
+};
+
+use crate::format::{format_count, format_duration, format_rate};
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum LoadProfile {
+    #[default]
+    Constant,
+    LinearRamp {
+        ramp: Duration,
+    },
+    Step {
+        interval: Duration,
+        steps: u32,
+    },
+    Spike {
+        start: Duration,
+        length: Duration,
+        multiplier: f64,
+    },
+}
+
+impl LoadProfile {
+    /// Frequency multiplier `elapsed` into the load.
+    pub(crate) fn multiplier(&self, elapsed: Duration) -> f64 {
+        match self {
+            LoadProfile::Constant => 1.0,
+            LoadProfile::LinearRamp { ramp } => {
+                (elapsed.as_secs_f64() / ramp.as_secs_f64()).min(1.0)
+            }
+            LoadProfile::Step { interval, steps } => {
+                let step = (elapsed.as_secs() / interval.as_secs()).min(*steps as u64 - 1) + 1;
+                step as f64 / *steps as f64
+            }
+            LoadProfile::Spike {
+                start,
+                length,
+                multiplier,
+            } => {
+                if elapsed >= *start && elapsed < *start + *length {
+                    *multiplier
+                } else {
+                    1.0
+                }
+            }
+        }
+    }
+
+    /// Names of the phases with the time into the load each one starts at.
+    fn phases(&self) -> Vec<(String, Duration)> {
+        match self {
+            LoadProfile::Constant => vec![("constant load".to_owned(), Duration::ZERO)],
+            LoadProfile::LinearRamp { ramp } => vec![
+                ("ramp".to_owned(), Duration::ZERO),
+                ("full load".to_owned(), *ramp),
+            ],
+            LoadProfile::Step { interval, steps } => (0..*steps)
+                .map(|step| {
+                    (
+                        format!(
+                            "step {} ({:.0}% load)",
+                            step + 1,
+                            (step + 1) as f64 * 100.0 / *steps as f64
+                        ),
+                        *interval * step,
+                    )
+                })
+                .collect(),
+            LoadProfile::Spike { start, length, .. } => vec![
+                ("before spike".to_owned(), Duration::ZERO),
+                ("spike".to_owned(), *start),
+                ("after spike".to_owned(), *start + *length),
+            ],
+        }
+    }
+
+    fn phase_index(&self, elapsed: Duration) -> usize {
+        self.phases()
+            .iter()
+            .rposition(|(_, start)| elapsed >= *start)
+            .unwrap_or(0)
+    }
+}
+
+impl FromStr for LoadProfile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.trim().split(':').map(str::trim).collect();
+        let secs = |value: &str| {
+            value
+                .parse::<u64>()
+                .ok()
+                .filter(|secs| *secs > 0)
+                .map(Duration::from_secs)
+                .ok_or_else(|| format!("Expected a positive number of seconds, got `{}`", value))
+        };
+        match parts.as_slice() {
+            ["constant"] => Ok(LoadProfile::Constant),
+            ["ramp", ramp] => Ok(LoadProfile::LinearRamp { ramp: secs(ramp)? }),
+            ["step", interval, steps] => Ok(LoadProfile::Step {
+                interval: secs(interval)?,
+                steps: steps
+                    .parse()
+                    .ok()
+                    .filter(|steps| *steps > 0)
+                    .ok_or_else(|| {
+                        format!("Expected a positive number of steps, got `{}`", steps)
+                    })?,
+            }),
+            ["spike", start, length, multiplier] => Ok(LoadProfile::Spike {
+                start: start
+                    .parse()
+                    .map(Duration::from_secs)
+                    .map_err(|_| format!("Expected a number of seconds, got `{}`", start))?,
+                length: secs(length)?,
+                multiplier: multiplier
+                    .parse()
+                    .ok()
+                    .filter(|multiplier: &f64| *multiplier >= 0.0)
+                    .ok_or_else(|| format!("Expected a multiplier, got `{}`", multiplier))?,
+            }),
+            _ => Err(format!(
+                "Unknown load profile `{}`, expected constant, ramp:<secs>, \
+                 step:<secs>:<steps> or spike:<start secs>:<length secs>:<multiplier>",
+                s
+            )),
+        }
+    }
+}
+
+impl Display for LoadProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadProfile::Constant => write!(f, "constant"),
+            LoadProfile::LinearRamp { ramp } => write!(f, "ramp:{}", ramp.as_secs()),
+            LoadProfile::Step { interval, steps } => {
+                write!(f, "step:{}:{}", interval.as_secs(), steps)
+            }
+            LoadProfile::Spike {
+                start,
+                length,
+                multiplier,
+            } => write!(
+                f,
+                "spike:{}:{}:{}",
+                start.as_secs(),
+                length.as_secs(),
+                multiplier
+            ),
+        }
+    }
+}
+
+/// Run counters at some point of the run.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct PhaseCounters {
+    pub(crate) attempted: u64,
+    pub(crate) broadcast_successful: u64,
+    pub(crate) broadcast_failed: u64,
+    pub(crate) included: u64,
+}
+
+/// Numbers of one phase of a profiled run.
+#[derive(Debug, Clone)]
+pub struct LoadPhaseReport {
+    pub name: String,
+    pub duration: Duration,
+    pub attempted: u64,
+    pub broadcast_successful: u64,
+    pub broadcast_failed: u64,
+    /// Waits completed during the phase, some for transitions of the phase
+    /// before
+    pub included: u64,
+}
+
+impl Display for LoadPhaseReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let secs = self.duration.as_secs_f64();
+        let per_sec = |count: u64| {
+            if secs > 0.0 {
+                count as f64 / secs
+            } else {
+                0.0
+            }
+        };
+        write!(
+            f,
+            "{} ({}): {} attempted ({}), broadcast {} ok / {} failed, {} included ({})",
+            self.name,
+            format_duration(self.duration),
+            format_count(self.attempted),
+            format_rate(per_sec(self.attempted)),
+            format_count(self.broadcast_successful),
+            format_count(self.broadcast_failed),
+            format_count(self.included),
+            format_rate(per_sec(self.included))
+        )
+    }
+}
+
+/// Splits the run counters by the phases of a profile.
+#[derive(Debug)]
+pub(crate) struct PhaseTracker {
+    profile: LoadProfile,
+    current: usize,
+    started_at: Duration,
+    counters_at_start: PhaseCounters,
+    reports: Vec<LoadPhaseReport>,
+}
+
+impl PhaseTracker {
+    /// Tracker for a load starting with the given counters.
+    pub(crate) fn new(profile: LoadProfile, counters: PhaseCounters) -> Self {
+        PhaseTracker {
+            profile,
+            current: 0,
+            started_at: Duration::ZERO,
+            counters_at_start: counters,
+            reports: Vec::new(),
+        }
+    }
+
+    /// Closes the phases the run moved past, to be called at the start of
+    /// each loop with the time into the load and the counters so far.
+    pub(crate) fn observe(&mut self, elapsed: Duration, counters: PhaseCounters) {
+        let phase = self.profile.phase_index(elapsed);
+        if phase != self.current {
+            self.close_phase(elapsed, counters);
+            self.current = phase;
+        }
+    }
+
+    fn close_phase(&mut self, elapsed: Duration, counters: PhaseCounters) {
+        let start = self.counters_at_start;
+        let duration = elapsed.saturating_sub(self.started_at);
+        // A phase starting at the same time as the next one never ran
+        if duration.is_zero() && counters.attempted == start.attempted {
+            return;
+        }
+        self.reports.push(LoadPhaseReport {
+            name: self.profile.phases()[self.current].0.clone(),
+            duration,
+            attempted: counters.attempted - start.attempted,
+            broadcast_successful: counters.broadcast_successful - start.broadcast_successful,
+            broadcast_failed: counters.broadcast_failed - start.broadcast_failed,
+            included: counters.included - start.included,
+        });
+        self.started_at = elapsed;
+        self.counters_at_start = counters;
+    }
+
+    /// Reports of the phases the run went through, the last one closed at
+    /// the end of the run.
+    pub(crate) fn finish(
+        mut self,
+        elapsed: Duration,
+        counters: PhaseCounters,
+    ) -> Vec<LoadPhaseReport> {
+        self.close_phase(elapsed, counters);
+        self.reports
+    }
+}
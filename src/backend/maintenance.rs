@@ -0,0 +1,284 @@
+//! Size of the persisted state and its pruning.
+//!
+//! Everything ever loaded stays in the state file and every strategy run adds
+//! reports next to it, so both only grow. Sizes of the persisted collections
+//! are measured by encoding them the way the state file does, and two pruning
+//! actions drop what is least likely to be needed again: old run reports and
+//! identities that can neither pay for nor sign anything.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Display},
+    fs,
+    path::Path,
+};
+
+use chrono::{NaiveDateTime, Utc};
+use dpp::{
+    identity::accessors::IdentityGettersV0,
+    prelude::{Identifier, Identity},
+    serialization::PlatformSerializableWithPlatformVersion,
+    version::PlatformVersion,
+};
+use tokio::sync::MutexGuard;
+use walkdir::WalkDir;
+
+use super::{
+    run_report, state::IdentityPrivateKeysMap, AppState, AppStateUpdate, BackendEvent, Task,
+};
+use crate::config::Config;
+
+/// Report files are named after the time they were written
+const REPORT_TIME_FORMAT: &str = "%Y%m%d%H%M%S";
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum MaintenanceTask {
+    MeasureStorage,
+    /// Deletes run and upgrade window reports older than this many days,
+    /// baselines are kept
+    PruneRunReports(u32),
+    /// Forgets known identities with a zero balance and no private keys
+    PruneEmptyIdentities,
+}
+
+/// Count and encoded size of a persisted collection.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct StoredItems {
+    pub count: usize,
+    pub bytes: u64,
+}
+
+impl Display for StoredItems {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.count, display_size(self.bytes))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct StorageReport {
+    /// Size of the state file as last saved, `None` if there is none yet
+    pub state_file_bytes: Option<u64>,
+    pub identities: StoredItems,
+    pub identity_private_keys: usize,
+    pub contracts: StoredItems,
+    pub strategies: StoredItems,
+    pub run_reports: StoredItems,
+    /// Identities [MaintenanceTask::PruneEmptyIdentities] would forget
+    pub empty_identities: usize,
+}
+
+impl Display for StorageReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state_file = self
+            .state_file_bytes
+            .map(display_size)
+            .unwrap_or_else(|| "not saved yet".to_owned());
+        write!(
+            f,
+            "State file: {}\n\nKnown identities: {}\nIdentity private keys: {}\nKnown contracts: \
+             {}\nStrategies: {}\nRun reports: {}\n\nIdentities with no balance and no keys: {}",
+            state_file,
+            self.identities,
+            self.identity_private_keys,
+            self.contracts,
+            self.strategies,
+            self.run_reports,
+            self.empty_identities
+        )
+    }
+}
+
+fn display_size(bytes: u64) -> String {
+    match bytes {
+        0..=1023 => format!("{} B", bytes),
+        1024..=1_048_575 => format!("{:.1} KiB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MiB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+impl AppState {
+    pub(super) async fn run_maintenance_task(
+        &self,
+        config: &Config,
+        platform_version: &PlatformVersion,
+        task: MaintenanceTask,
+    ) -> BackendEvent {
+        match task {
+            MaintenanceTask::MeasureStorage => BackendEvent::TaskCompleted {
+                task: Task::Maintenance(task),
+                execution_result: Ok(self
+                    .measure_storage(config, platform_version)
+                    .await
+                    .to_string()
+                    .into()),
+            },
+            MaintenanceTask::PruneRunReports(days) => {
+                let execution_result = prune_run_reports(days).map(|(files, bytes)| {
+                    format!(
+                        "Deleted {} reports older than {} days, {} freed",
+                        files,
+                        days,
+                        display_size(bytes)
+                    )
+                    .into()
+                });
+                BackendEvent::TaskCompleted {
+                    task: Task::Maintenance(task),
+                    execution_result,
+                }
+            }
+            MaintenanceTask::PruneEmptyIdentities => {
+                let loaded_identity = self.loaded_identity.lock().await;
+                let mut known_identities = self.known_identities.lock().await;
+                let mut known_names = self.known_identities_names.lock().await;
+                let empty = empty_identities(
+                    &known_identities,
+                    &*self.known_identities_private_keys.lock().await,
+                );
+
+                let mut pruned = 0;
+                for id in empty {
+                    // The loaded identity stays even if it can't do anything yet
+                    if loaded_identity.as_ref().map(|identity| identity.id()) == Some(id) {
+                        continue;
+                    }
+                    known_identities.remove(&id);
+                    known_names.remove(&id);
+                    pruned += 1;
+                }
+                drop(known_names);
+                drop(loaded_identity);
+
+                BackendEvent::TaskCompletedStateChange {
+                    task: Task::Maintenance(task),
+                    execution_result: Ok(format!(
+                        "Forgot {} identities with no balance and no keys",
+                        pruned
+                    )
+                    .into()),
+                    app_state_update: AppStateUpdate::KnownIdentities(MutexGuard::map(
+                        known_identities,
+                        |identities| identities,
+                    )),
+                }
+            }
+        }
+    }
+
+    async fn measure_storage(
+        &self,
+        config: &Config,
+        platform_version: &PlatformVersion,
+    ) -> StorageReport {
+        let bincode_config = bincode::config::standard()
+            .with_big_endian()
+            .with_no_limit();
+
+        let known_identities = self.known_identities.lock().await;
+        let private_keys = self.known_identities_private_keys.lock().await;
+        let identities = StoredItems {
+            count: known_identities.len(),
+            bytes: known_identities
+                .values()
+                .filter_map(|identity| bincode::encode_to_vec(identity, bincode_config).ok())
+                .map(|bytes| bytes.len() as u64)
+                .sum(),
+        };
+        let empty_identities = empty_identities(&known_identities, &private_keys).len();
+        let identity_private_keys = private_keys.len();
+        drop(private_keys);
+        drop(known_identities);
+
+        let known_contracts = self.known_contracts.lock().await;
+        let contracts = StoredItems {
+            count: known_contracts.len(),
+            bytes: known_contracts
+                .values()
+                .filter_map(|contract| {
+                    contract
+                        .serialize_to_bytes_with_platform_version(platform_version)
+                        .ok()
+                })
+                .map(|bytes| bytes.len() as u64)
+                .sum(),
+        };
+        drop(known_contracts);
+
+        let available_strategies = self.available_strategies.lock().await;
+        let strategies = StoredItems {
+            count: available_strategies.len(),
+            bytes: available_strategies
+                .values()
+                .filter_map(|strategy| {
+                    strategy
+                        .serialize_to_bytes_with_platform_version(platform_version)
+                        .ok()
+                })
+                .map(|bytes| bytes.len() as u64)
+                .sum(),
+        };
+        drop(available_strategies);
+
+        let mut run_reports = StoredItems::default();
+        for entry in report_files() {
+            run_reports.count += 1;
+            run_reports.bytes += entry.metadata().map(|m| m.len()).unwrap_or_default();
+        }
+
+        StorageReport {
+            state_file_bytes: fs::metadata(config.state_file_path()).ok().map(|m| m.len()),
+            identities,
+            identity_private_keys,
+            contracts,
+            strategies,
+            run_reports,
+            empty_identities,
+        }
+    }
+}
+
+/// Known identities with a zero balance and none of their private keys known.
+fn empty_identities(
+    known_identities: &BTreeMap<Identifier, Identity>,
+    private_keys: &IdentityPrivateKeysMap,
+) -> Vec<Identifier> {
+    let with_keys: BTreeSet<Identifier> = private_keys.keys().map(|(id, _)| *id).collect();
+    known_identities
+        .iter()
+        .filter(|(id, identity)| identity.balance() == 0 && !with_keys.contains(id))
+        .map(|(id, _)| *id)
+        .collect()
+}
+
+/// Run and upgrade window reports of all strategies, baselines aside.
+fn report_files() -> impl Iterator<Item = walkdir::DirEntry> {
+    WalkDir::new(run_report::REPORTS_DIR)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file() && report_time(entry.path()).is_some())
+}
+
+fn report_time(path: &Path) -> Option<NaiveDateTime> {
+    if path.extension()? != "json" {
+        return None;
+    }
+    NaiveDateTime::parse_from_str(path.file_stem()?.to_str()?, REPORT_TIME_FORMAT).ok()
+}
+
+/// Deletes reports older than the number of days, returning how many files
+/// and bytes were removed.
+fn prune_run_reports(days: u32) -> Result<(usize, u64), String> {
+    let cutoff = Utc::now().naive_utc() - chrono::Duration::days(days as i64);
+    let mut deleted = 0;
+    let mut freed = 0;
+    for entry in report_files() {
+        if report_time(entry.path()).is_some_and(|time| time < cutoff) {
+            let bytes = entry.metadata().map(|m| m.len()).unwrap_or_default();
+            fs::remove_file(entry.path())
+                .map_err(|e| format!("Failed to delete {}: {}", entry.path().display(), e))?;
+            deleted += 1;
+            freed += bytes;
+        }
+    }
+    Ok((deleted, freed))
+}
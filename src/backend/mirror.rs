@@ -0,0 +1,171 @@
+//! Mirroring of strategy run traffic to a second network.
+//!
+//! An experimental way to check a release candidate against the network it
+//! would upgrade: every transition a run broadcasts is broadcast as is to a
+//! shadow network as well, and the two networks' acceptance is compared.
+//! Transitions are signed for the primary network's state, so the shadow
+//! network only gives comparable results with the same identities, contracts
+//! and nonces, e.g. a devnet restored from the same snapshot.
+
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use dash_sdk::{platform::transition::broadcast_request::BroadcastRequestForStateTransition, Sdk};
+use dpp::{state_transition::StateTransition, version::PlatformVersion};
+use rs_dapi_client::{AddressList, DapiRequestExecutor, RequestSettings};
+use tokio::sync::Mutex;
+
+use super::connections;
+use crate::config::Config;
+
+/// Divergent transitions kept for the report, the rest are only counted
+const MAX_KEPT_DIVERGENCES: usize = 20;
+
+/// A transition one network accepted and the other rejected.
+#[derive(Debug, Clone)]
+pub struct Divergence {
+    pub transition_id: String,
+    pub transition_name: String,
+    pub primary_error: Option<String>,
+    pub mirror_error: Option<String>,
+}
+
+impl Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let outcome = |error: &Option<String>| match error {
+            Some(error) => format!("rejected ({})", error),
+            None => "accepted".to_owned(),
+        };
+        write!(
+            f,
+            "{} {}: primary {}, mirror {}",
+            self.transition_name,
+            self.transition_id,
+            outcome(&self.primary_error),
+            outcome(&self.mirror_error)
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MirrorReport {
+    pub mirrored: u64,
+    pub both_accepted: u64,
+    pub both_rejected: u64,
+    pub divergent: u64,
+    /// First divergent transitions
+    pub divergences: Vec<Divergence>,
+}
+
+impl Display for MirrorReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Mirrored transitions: {}, accepted by both: {}, rejected by both: {}, divergent: {}",
+            self.mirrored, self.both_accepted, self.both_rejected, self.divergent
+        )?;
+        for divergence in &self.divergences {
+            write!(f, "\n - {}", divergence)?;
+        }
+        if self.divergent > self.divergences.len() as u64 {
+            write!(
+                f,
+                "\n - and {} more",
+                self.divergent - self.divergences.len() as u64
+            )?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) struct TransitionMirror {
+    sdk: Sdk,
+    mirrored: AtomicU64,
+    both_accepted: AtomicU64,
+    both_rejected: AtomicU64,
+    divergent: AtomicU64,
+    divergences: Mutex<Vec<Divergence>>,
+}
+
+impl TransitionMirror {
+    /// Mirror to the configured mirror DAPI addresses.
+    pub(crate) fn new(
+        config: &Config,
+        platform_version: &'static PlatformVersion,
+    ) -> Result<Self, String> {
+        let addresses = config
+            .mirror_dapi_addresses
+            .as_deref()
+            .filter(|addresses| !addresses.is_empty())
+            .ok_or_else(|| {
+                "Set EXPLORER_MIRROR_DAPI_ADDRESSES to mirror transitions to another network"
+                    .to_owned()
+            })?;
+        let address_list = AddressList::from_str(addresses)
+            .map_err(|e| format!("Invalid mirror DAPI addresses: {}", e))?;
+        Ok(TransitionMirror {
+            sdk: connections::build_sdk(config, address_list, platform_version)?,
+            mirrored: AtomicU64::new(0),
+            both_accepted: AtomicU64::new(0),
+            both_rejected: AtomicU64::new(0),
+            divergent: AtomicU64::new(0),
+            divergences: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Broadcasts a transition to the mirror network and compares the outcome
+    /// with the primary network's.
+    pub(crate) async fn mirror<T, E: Display>(
+        &self,
+        transition: &StateTransition,
+        transition_id: &str,
+        primary_result: &Result<T, E>,
+        request_settings: RequestSettings,
+    ) {
+        let mirror_result = match transition.broadcast_request_for_state_transition() {
+            Ok(request) => request
+                .execute(&self.sdk, request_settings)
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        self.mirrored.fetch_add(1, Ordering::SeqCst);
+
+        match (primary_result, mirror_result) {
+            (Ok(_), Ok(())) => {
+                self.both_accepted.fetch_add(1, Ordering::SeqCst);
+            }
+            (Err(_), Err(_)) => {
+                self.both_rejected.fetch_add(1, Ordering::SeqCst);
+            }
+            (primary_result, mirror_result) => {
+                let divergence = Divergence {
+                    transition_id: transition_id.to_owned(),
+                    transition_name: transition.name().to_string(),
+                    primary_error: primary_result.as_ref().err().map(ToString::to_string),
+                    mirror_error: mirror_result.err(),
+                };
+                tracing::warn!("Mirror divergence: {}", divergence);
+                self.divergent.fetch_add(1, Ordering::SeqCst);
+                let mut divergences = self.divergences.lock().await;
+                if divergences.len() < MAX_KEPT_DIVERGENCES {
+                    divergences.push(divergence);
+                }
+            }
+        }
+    }
+
+    pub(crate) async fn report(&self) -> MirrorReport {
+        MirrorReport {
+            mirrored: self.mirrored.load(Ordering::SeqCst),
+            both_accepted: self.both_accepted.load(Ordering::SeqCst),
+            both_rejected: self.both_rejected.load(Ordering::SeqCst),
+            divergent: self.divergent.load(Ordering::SeqCst),
+            divergences: self.divergences.lock().await.clone(),
+        }
+    }
+}
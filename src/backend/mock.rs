@@ -0,0 +1,275 @@
+//! Backend with canned data for the `--demo` mode.
+//!
+//! Screens only see the application state and the results of tasks, so a
+//! state filled with a wallet, an identity, contracts and a strategy is enough
+//! to walk through the UI without Platform, Core or Insight. Connection checks,
+//! identity lookups and wallet refreshes go to mock clients answering with the
+//! same data, other tasks that would need the network complete with an error
+//! saying so. The state is never saved so the real one is left alone.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::DerefMut,
+    path::Path,
+};
+
+use dapi_grpc::tonic::transport::Uri;
+use dpp::{
+    dashcore::{
+        hashes::Hash, secp256k1::Secp256k1, Address, Network, OutPoint, PrivateKey, TxOut, Txid,
+    },
+    identity::accessors::{IdentityGettersV0, IdentitySettersV0},
+    prelude::{Identifier, Identity, IdentityPublicKey},
+    util::deserializer::ProtocolVersion,
+    version::PlatformVersion,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use strategy_tests::Strategy;
+use tokio::sync::MutexGuard;
+
+use super::{
+    clients::{CoreApi, InsightApi, PlatformApi, UtxosWithConfirmations},
+    connections::{self, check_loaded},
+    identities,
+    insight::InsightError,
+    wallet::{SingleKeyWallet, WalletTask},
+    AppState, AppStateUpdate, BackendEvent, CompletedTaskPayload, Task, TaskBackend, Wallet,
+};
+use crate::config::Config;
+
+const DEMO_STRATEGY: &str = "demo";
+/// Balance of the demo wallet in duffs
+const DEMO_WALLET_BALANCE: u64 = 10 * 100_000_000;
+/// Balance of the demo identity in credits
+const DEMO_IDENTITY_BALANCE: u64 = 12_345_678_000;
+/// Confirmations of the demo wallet's UTXO
+const DEMO_UTXO_CONFIRMATIONS: u32 = 6;
+/// Height of the chain the mock Core reports
+const DEMO_BLOCK_COUNT: u64 = 1_000;
+
+/// Platform knowing the demo identity, at the latest protocol version.
+pub struct MockPlatform {
+    identities: BTreeMap<Identifier, Identity>,
+}
+
+impl PlatformApi for MockPlatform {
+    async fn current_protocol_version(&self) -> Result<ProtocolVersion, String> {
+        Ok(PlatformVersion::latest().protocol_version)
+    }
+
+    async fn fetch_identity(&self, id: Identifier) -> Result<Option<Identity>, String> {
+        Ok(self.identities.get(&id).cloned())
+    }
+
+    fn banned_addresses(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// Core listing the demo wallet's UTXO.
+pub struct MockCore {
+    utxos: UtxosWithConfirmations,
+}
+
+impl CoreApi for MockCore {
+    fn block_count(&self) -> Result<u64, String> {
+        Ok(DEMO_BLOCK_COUNT)
+    }
+
+    fn unspent_outputs(&self, addresses: &[&Address]) -> Result<UtxosWithConfirmations, String> {
+        let script_pubkeys: Vec<_> = addresses
+            .iter()
+            .map(|address| address.script_pubkey())
+            .collect();
+        Ok(self
+            .utxos
+            .iter()
+            .filter(|(_, (tx_out, _))| script_pubkeys.contains(&tx_out.script_pubkey))
+            .map(|(outpoint, utxo)| (*outpoint, utxo.clone()))
+            .collect())
+    }
+}
+
+/// Insight that is reachable but knows no UTXOs, it's only asked when Core
+/// fails.
+pub struct MockInsight {
+    uri: Uri,
+}
+
+impl InsightApi for MockInsight {
+    fn uri(&self) -> &Uri {
+        &self.uri
+    }
+
+    async fn status(&self) -> Result<reqwest::StatusCode, InsightError> {
+        Ok(reqwest::StatusCode::OK)
+    }
+
+    async fn utxos_with_amount_for_addresses(
+        &self,
+        _addresses: &[&Address],
+    ) -> Result<UtxosWithConfirmations, InsightError> {
+        Ok(HashMap::new())
+    }
+}
+
+pub struct MockBackend {
+    app_state: AppState,
+    config: Option<Config>,
+    platform: MockPlatform,
+    core: MockCore,
+    insight: MockInsight,
+}
+
+impl MockBackend {
+    pub fn new() -> Self {
+        let config = Config::load();
+        MockBackend {
+            config: Some(config.clone()),
+            ..Self::with_network(config.core_network())
+        }
+    }
+
+    /// Demo backend with the wallet on `network`, without reading the
+    /// configuration.
+    pub fn with_network(network: Network) -> Self {
+        Self::with_state(AppState::default(), network)
+    }
+
+    /// Demo backend with its local Drive at `drive_path` instead of the
+    /// application's one, so several can be open at once.
+    pub(crate) fn with_drive_path(network: Network, drive_path: impl AsRef<Path>) -> Self {
+        Self::with_state(AppState::with_drive_path(drive_path), network)
+    }
+
+    fn with_state(mut app_state: AppState, network: Network) -> Self {
+        let platform_version = PlatformVersion::latest();
+
+        let private_key =
+            PrivateKey::from_slice(&[1; 32], network).expect("expected a private key");
+        let public_key = private_key.public_key(&Secp256k1::new());
+        let address = Address::p2pkh(&public_key, network);
+        let outpoint = OutPoint::new(Txid::all_zeros(), 0);
+        let tx_out = TxOut {
+            value: DEMO_WALLET_BALANCE,
+            script_pubkey: address.script_pubkey(),
+        };
+        *app_state.loaded_wallet.get_mut() = Some(Wallet::SingleKeyWallet(SingleKeyWallet {
+            private_key,
+            public_key,
+            address,
+            utxos: HashMap::from([(outpoint, tx_out.clone())]),
+            utxo_confirmations: HashMap::from([(outpoint, DEMO_UTXO_CONFIRMATIONS)]),
+            frozen_utxos: Default::default(),
+            change_keys: Vec::new(),
+            next_change_index: 0,
+            hd_seed: None,
+        }));
+
+        let (mut identity, _): (Identity, BTreeMap<IdentityPublicKey, [u8; 32]>) =
+            Identity::random_identity_with_main_keys_with_private_key(
+                3,
+                &mut StdRng::seed_from_u64(0),
+                platform_version,
+            )
+            .expect("expected a random identity");
+        identity.set_balance(DEMO_IDENTITY_BALANCE);
+        app_state
+            .known_identities
+            .get_mut()
+            .insert(identity.id(), identity.clone());
+        *app_state.loaded_identity.get_mut() = Some(identity);
+
+        let supporting_contracts = app_state.supporting_contracts.get_mut().clone();
+        *app_state.known_contracts.get_mut() = supporting_contracts;
+
+        app_state
+            .available_strategies
+            .get_mut()
+            .insert(DEMO_STRATEGY.to_owned(), Strategy::default());
+        app_state
+            .available_strategies_contract_names
+            .get_mut()
+            .insert(DEMO_STRATEGY.to_owned(), Vec::new());
+        app_state.publish_snapshot();
+
+        let platform = MockPlatform {
+            identities: app_state.known_identities.get_mut().clone(),
+        };
+        let core = MockCore {
+            utxos: HashMap::from([(outpoint, (tx_out, Some(DEMO_UTXO_CONFIRMATIONS)))]),
+        };
+        let insight = MockInsight {
+            uri: Uri::from_static("http://127.0.0.1:3001/insight-api"),
+        };
+
+        MockBackend {
+            app_state,
+            config: None,
+            platform,
+            core,
+            insight,
+        }
+    }
+}
+
+impl TaskBackend for MockBackend {
+    fn state(&self) -> &AppState {
+        &self.app_state
+    }
+
+    async fn run_task(&self, task: Task) -> BackendEvent {
+        let execution_result = match task {
+            Task::TestConnections => match &self.config {
+                Some(config) => {
+                    // There are no DAPI addresses to reach, the mock Platform
+                    // stands in for them
+                    let mut checks = connections::check_connections(
+                        &self.platform,
+                        "",
+                        &self.core,
+                        &self.insight,
+                        config,
+                    )
+                    .await;
+                    checks.extend(check_loaded(true, true));
+                    Ok(CompletedTaskPayload::ConnectionChecks(checks))
+                }
+                None => Err("No configuration was loaded to check connections with".to_owned()),
+            },
+            Task::FetchIdentityById(ref base58_id, _) => {
+                identities::fetch_identity_by_b58_id(&self.platform, base58_id)
+                    .await
+                    .map(|(_, result_info)| CompletedTaskPayload::String(result_info))
+            }
+            Task::Wallet(WalletTask::Refresh) => {
+                let mut wallet_guard = self.app_state.loaded_wallet.lock().await;
+                let Some(wallet) = wallet_guard.deref_mut() else {
+                    return BackendEvent::TaskCompleted {
+                        task,
+                        execution_result: Err("No wallet loaded".to_owned()),
+                    };
+                };
+                if let Err(e) = wallet.reload_utxos(&self.insight, &self.core).await {
+                    return BackendEvent::TaskCompleted {
+                        task,
+                        execution_result: Err(e),
+                    };
+                }
+                return BackendEvent::TaskCompletedStateChange {
+                    task,
+                    execution_result: Ok("Refreshed wallet".into()),
+                    app_state_update: AppStateUpdate::LoadedWallet(MutexGuard::map(
+                        wallet_guard,
+                        |wallet| wallet.as_mut().expect("wallet was checked above"),
+                    )),
+                };
+            }
+            _ => Err("Not available in demo mode, there is no network".to_owned()),
+        };
+        BackendEvent::TaskCompleted {
+            task,
+            execution_result,
+        }
+    }
+}
@@ -0,0 +1,162 @@
+//! Results of a strategy run by type of transition.
+//!
+//! Run totals mix cheap document batches with identity top ups waiting on
+//! asset locks and contract creations, so a slow or failing type hides in
+//! the overall rate. Broadcasts, waits and wait times of the measured part of
+//! the run are counted for each transition type as well, and reported next
+//! to the totals.
+
+use std::{
+    fmt::{self, Display},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+use dashmap::DashMap;
+use hdrhistogram::Histogram;
+
+use crate::format::{format_count, format_rate};
+
+#[derive(Debug)]
+struct TypeCounters {
+    attempted: AtomicU64,
+    broadcast_successful: AtomicU64,
+    broadcast_failed: AtomicU64,
+    included: AtomicU64,
+    rejected: AtomicU64,
+    wait_failed: AtomicU64,
+    /// Wait times of included transitions, in milliseconds
+    wait_times: Mutex<Histogram<u64>>,
+}
+
+impl Default for TypeCounters {
+    fn default() -> Self {
+        TypeCounters {
+            attempted: AtomicU64::new(0),
+            broadcast_successful: AtomicU64::new(0),
+            broadcast_failed: AtomicU64::new(0),
+            included: AtomicU64::new(0),
+            rejected: AtomicU64::new(0),
+            wait_failed: AtomicU64::new(0),
+            wait_times: Mutex::new(Histogram::new(3).expect("valid histogram precision")),
+        }
+    }
+}
+
+/// Outcome of the wait for a transition.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum WaitOutcome {
+    Included(Duration),
+    Rejected,
+    Failed,
+}
+
+/// Counters of a run by transition type, shared between its broadcasts and
+/// waits.
+#[derive(Debug, Default)]
+pub(crate) struct OperationMetrics(DashMap<String, TypeCounters>);
+
+impl OperationMetrics {
+    pub(crate) fn record_broadcast(&self, transition_type: &str, success: bool) {
+        let counters = self.0.entry(transition_type.to_owned()).or_default();
+        counters.attempted.fetch_add(1, Ordering::SeqCst);
+        if success {
+            counters.broadcast_successful.fetch_add(1, Ordering::SeqCst);
+        } else {
+            counters.broadcast_failed.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    pub(crate) fn record_wait(&self, transition_type: &str, outcome: WaitOutcome) {
+        let counters = self.0.entry(transition_type.to_owned()).or_default();
+        match outcome {
+            WaitOutcome::Included(wait_time) => {
+                counters.included.fetch_add(1, Ordering::SeqCst);
+                let _ = counters
+                    .wait_times
+                    .lock()
+                    .expect("wait times lock")
+                    .record(wait_time.as_millis() as u64);
+            }
+            WaitOutcome::Rejected => {
+                counters.rejected.fetch_add(1, Ordering::SeqCst);
+            }
+            WaitOutcome::Failed => {
+                counters.wait_failed.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+    }
+
+    /// Report of every transition type, over the measured time of the run.
+    pub(crate) fn reports(&self, measured_time: Duration) -> Vec<OperationTypeReport> {
+        let secs = measured_time.as_secs_f64().max(1.0);
+        let mut reports: Vec<_> = self
+            .0
+            .iter()
+            .map(|entry| {
+                let counters = entry.value();
+                let wait_times = counters.wait_times.lock().expect("wait times lock");
+                let attempted = counters.attempted.load(Ordering::SeqCst);
+                let included = counters.included.load(Ordering::SeqCst);
+                OperationTypeReport {
+                    transition_type: entry.key().clone(),
+                    attempted,
+                    rate: attempted as f64 / secs,
+                    broadcast_successful: counters.broadcast_successful.load(Ordering::SeqCst),
+                    broadcast_failed: counters.broadcast_failed.load(Ordering::SeqCst),
+                    included,
+                    included_rate: included as f64 / secs,
+                    rejected: counters.rejected.load(Ordering::SeqCst),
+                    wait_failed: counters.wait_failed.load(Ordering::SeqCst),
+                    wait_percentiles: [0.50, 0.90, 0.95]
+                        .map(|quantile| wait_times.value_at_quantile(quantile) as f64 / 1000.0),
+                }
+            })
+            .collect();
+        reports.sort_by(|a, b| a.transition_type.cmp(&b.transition_type));
+        reports
+    }
+}
+
+/// Numbers of one transition type of a run.
+#[derive(Debug, Clone)]
+pub struct OperationTypeReport {
+    pub transition_type: String,
+    pub attempted: u64,
+    /// Attempted transitions per second
+    pub rate: f64,
+    pub broadcast_successful: u64,
+    pub broadcast_failed: u64,
+    pub included: u64,
+    /// Included transitions per second
+    pub included_rate: f64,
+    pub rejected: u64,
+    pub wait_failed: u64,
+    /// 50th, 90th and 95th percentiles of the wait for inclusion, in seconds
+    pub wait_percentiles: [f64; 3],
+}
+
+impl Display for OperationTypeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} attempted ({}), broadcast {} ok / {} failed, {} included ({}), {} rejected, \
+             {} wait errors, wait p50/p90/p95 {:.2}s/{:.2}s/{:.2}s",
+            self.transition_type,
+            format_count(self.attempted),
+            format_rate(self.rate),
+            format_count(self.broadcast_successful),
+            format_count(self.broadcast_failed),
+            format_count(self.included),
+            format_rate(self.included_rate),
+            format_count(self.rejected),
+            format_count(self.wait_failed),
+            self.wait_percentiles[0],
+            self.wait_percentiles[1],
+            self.wait_percentiles[2]
+        )
+    }
+}
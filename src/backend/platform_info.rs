@@ -1,4 +1,5 @@
 use crate::backend::{as_json_string, BackendEvent, Task};
+use crate::units;
 use chrono::{prelude::*, LocalResult};
 use chrono_humanize::{Accuracy, HumanTime, Tense};
 use dapi_grpc::platform::v0::{Proof, ResponseMetadata};
@@ -33,7 +34,6 @@ use dpp::{
         epoch::EpochIndex,
         extended_epoch_info::{v0::ExtendedEpochInfoV0Getters, ExtendedEpochInfo},
     },
-    dash_to_credits,
     version::ProtocolVersionVoteCount,
 };
 use drive::drive::credit_pools::epochs::epoch_key_constants::KEY_START_BLOCK_CORE_HEIGHT;
@@ -69,6 +69,17 @@ pub(crate) enum PlatformInfoTask {
     FetchManyEpochInfo(u16, u32), // second is count
 }
 
+/// Usual length of an epoch on the network, in milliseconds.
+pub(crate) fn estimated_epoch_duration_ms(network: Network) -> u64 {
+    match network {
+        Network::Dash => 788_400_000,
+        Network::Testnet => 3_600_000,
+        Network::Devnet => 3_600_000,
+        Network::Regtest => 1_200_000,
+        _ => 3_600_000,
+    }
+}
+
 fn format_extended_epoch_info(
     epoch_info: ExtendedEpochInfo,
     metadata: ResponseMetadata,
@@ -121,13 +132,7 @@ fn format_extended_epoch_info(
         String::default()
     };
 
-    let epoch_estimated_time = match network {
-        Network::Dash => 788_400_000,
-        Network::Testnet => 3_600_000,
-        Network::Devnet => 3_600_000,
-        Network::Regtest => 1_200_000,
-        _ => 3_600_000,
-    };
+    let epoch_estimated_time = estimated_epoch_duration_ms(network);
 
     let readable_epoch_end_time = match Utc
         .timestamp_millis_opt(epoch_info.first_block_time() as i64 + epoch_estimated_time as i64)
@@ -306,9 +311,9 @@ fn format_withdrawal_documents_to_bare_info(
             let address =
                 Address::from_script(&output_script, network).expect("expected an address");
             format!(
-                "{}: {:.8} Dash for {} towards {} ({})",
+                "{}: {} for {} towards {} ({})",
                 local_datetime,
-                amount as f64 / (dash_to_credits!(1) as f64),
+                units::format_credits(amount),
                 owner_id,
                 address,
                 status,
@@ -318,25 +323,26 @@ fn format_withdrawal_documents_to_bare_info(
     let mut result = String::new();
 
     result.push_str(&format!(
-        "total amount {} Dash\n",
-        total_amount as f64 / (dash_to_credits!(1) as f64)
+        "total amount {}\n",
+        units::format_credits(total_amount)
     ));
     result.push_str(&format!("amounts {{\n    {}\n}}\n", amounts));
     let daily_withdrawal_limit =
         daily_withdrawal_limit(total_credits_on_platform, PlatformVersion::latest())
             .expect("expected to get daily withdrawal limit");
     result.push_str(&format!(
-        "withdrew {} Dash in last 24 hours\n",
-        recent_withdrawal_amounts as f64 / (dash_to_credits!(1) as f64)
+        "withdrew {} in last 24 hours\n",
+        units::format_credits(recent_withdrawal_amounts as Credits)
     ));
     result.push_str(&format!(
-        "daily withdrawal limit is {} Dash\n",
-        daily_withdrawal_limit as f64 / (dash_to_credits!(1) as f64)
+        "daily withdrawal limit is {}\n",
+        units::format_credits(daily_withdrawal_limit)
     ));
     result.push_str(&format!(
-        "currently can withdraw {} Dash\n",
-        daily_withdrawal_limit.saturating_sub(recent_withdrawal_amounts as Credits) as f64
-            / (dash_to_credits!(1) as f64)
+        "currently can withdraw {}\n",
+        units::format_credits(
+            daily_withdrawal_limit.saturating_sub(recent_withdrawal_amounts as Credits)
+        )
     ));
 
     result
@@ -391,9 +397,9 @@ fn format_completed_withdrawal_documents_to_bare_info(
             let address =
                 Address::from_script(&output_script, network).expect("expected an address");
             format!(
-                "{} {:.8} Dash for {} towards {} ({} at {})",
+                "{} {} for {} towards {} ({} at {})",
                 transaction_index,
-                amount as f64 / (dash_to_credits!(1) as f64),
+                units::format_credits(amount),
                 owner_id,
                 address,
                 status,
@@ -404,25 +410,26 @@ fn format_completed_withdrawal_documents_to_bare_info(
     let mut result = String::new();
 
     result.push_str(&format!(
-        "total amount {} Dash\n",
-        total_amount as f64 / (dash_to_credits!(1) as f64)
+        "total amount {}\n",
+        units::format_credits(total_amount)
     ));
     result.push_str(&format!("amounts {{\n    {}\n}}\n", amounts));
     let daily_withdrawal_limit =
         daily_withdrawal_limit(total_credits_on_platform, PlatformVersion::latest())
             .expect("expected to get daily withdrawal limit");
     result.push_str(&format!(
-        "withdrew {} Dash in last 24 hours\n",
-        recent_withdrawal_amounts as f64 / (dash_to_credits!(1) as f64)
+        "withdrew {} in last 24 hours\n",
+        units::format_credits(recent_withdrawal_amounts as Credits)
     ));
     result.push_str(&format!(
-        "daily withdrawal limit is {} Dash\n",
-        daily_withdrawal_limit as f64 / (dash_to_credits!(1) as f64)
+        "daily withdrawal limit is {}\n",
+        units::format_credits(daily_withdrawal_limit)
     ));
     result.push_str(&format!(
-        "currently can withdraw {} Dash\n",
-        daily_withdrawal_limit.saturating_sub(recent_withdrawal_amounts as Credits) as f64
-            / (dash_to_credits!(1) as f64)
+        "currently can withdraw {}\n",
+        units::format_credits(
+            daily_withdrawal_limit.saturating_sub(recent_withdrawal_amounts as Credits)
+        )
     ));
 
     result
@@ -535,8 +542,6 @@ fn format_total_credits_on_platform(
         LocalResult::Ambiguous(..) => String::new(),
     };
 
-    let dash_amount = total_credits_on_platform.0 as f64 * 10f64.powf(-11.0);
-
     let extra_start_info = if epoch.index == 0 {
         format!("activation height: {}\n", request_activation_core_height())
     } else {
@@ -544,7 +549,7 @@ fn format_total_credits_on_platform(
     };
 
     format!(
-        "current height: {}\ncurrent epoch start core height: {}\n{}current core height: {}\nsubsidy interval: {}\ncurrent block time: {} ({})\ntotal_credits_on_platform {} ({:.4} Dash)\n\nproof: {}",
+        "current height: {}\ncurrent epoch start core height: {}\n{}current core height: {}\nsubsidy interval: {}\ncurrent block time: {} ({})\ntotal credits on platform: {}\n\nproof: {}",
         metadata.height,
         start_core_height,
         extra_start_info,
@@ -552,8 +557,7 @@ fn format_total_credits_on_platform(
         network.core_subsidy_halving_interval(),
         metadata.time_ms,
         readable_block_time,
-        total_credits_on_platform.0,
-        dash_amount,
+        units::format_credits(total_credits_on_platform.0),
         prettify_proof(&proof)
     )
 }
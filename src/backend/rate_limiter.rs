@@ -0,0 +1,67 @@
+//! Broadcast pacing for strategy runs with a target rate.
+//!
+//! Without a target a run broadcasts whatever a loop generates as soon as it
+//! is generated, so throughput follows the operation frequencies and how long
+//! generation takes. With one every broadcast takes a token from a bucket
+//! refilled at the target rate. The loop then no longer sleeps out the rest of
+//! its seconds, which would only leave tokens unused, so generation keeps up
+//! with the rate as long as the operation frequencies allow it.
+
+use std::time::Duration;
+
+use tokio::{sync::Mutex, time::Instant};
+
+/// Seconds of tokens the bucket holds at most, the burst after a slow loop
+const BURST_SECS: f64 = 1.0;
+
+#[derive(Debug)]
+struct Bucket {
+    /// Negative while broadcasts are waiting for tokens
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    rate: f64,
+    bucket: Mutex<Bucket>,
+}
+
+impl RateLimiter {
+    /// Limiter for `rate` broadcasts per second, `None` for a rate that isn't
+    /// positive.
+    pub(crate) fn new(rate: f64) -> Option<Self> {
+        (rate > 0.0).then(|| RateLimiter {
+            rate,
+            bucket: Mutex::new(Bucket {
+                tokens: 0.0,
+                refilled_at: Instant::now(),
+            }),
+        })
+    }
+
+    pub(crate) fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    fn refill(&self, bucket: &mut Bucket) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.rate * BURST_SECS);
+        bucket.refilled_at = now;
+    }
+
+    /// Waits for a token. Tokens are handed out in the order they are asked
+    /// for, a caller reserves its own before sleeping until it is refilled.
+    pub(crate) async fn acquire(&self) {
+        let wait = {
+            let mut bucket = self.bucket.lock().await;
+            self.refill(&mut bucket);
+            bucket.tokens -= 1.0;
+            (bucket.tokens < 0.0).then(|| Duration::from_secs_f64(-bucket.tokens / self.rate))
+        };
+        if let Some(wait) = wait {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
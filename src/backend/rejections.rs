@@ -0,0 +1,114 @@
+//! Categories of the errors Platform rejects transitions with.
+//!
+//! The wait result of a rejected transition carries the consensus error that
+//! rejected it, serialized. Decoded, errors are counted by what usually has to
+//! change for such transitions to pass: nonces, signatures or fees, and the
+//! other consensus errors by their code. Errors that don't decode are counted
+//! by the code of the response.
+//!
+//! Transitions refused on broadcast carry the consensus error in the gRPC
+//! response metadata instead.
+
+use std::{
+    fmt::{self, Display},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use dapi_grpc::platform::v0::StateTransitionBroadcastError;
+use dashmap::DashMap;
+use dpp::{
+    consensus::{basic::BasicError, state::state_error::StateError, ConsensusError},
+    errors::consensus::codes::ErrorWithCode,
+    serialization::PlatformDeserializable,
+};
+use rs_dapi_client::{transport::TransportError, DapiClientError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum RejectionCategory {
+    Nonce,
+    Signature,
+    Fee,
+    /// Any other consensus error, by code
+    Consensus(u32),
+    /// Error that doesn't decode, by response code
+    Undecoded(u32),
+}
+
+/// Metadata of a refused broadcast holding its serialized consensus error
+const CONSENSUS_ERROR_METADATA_KEY: &str = "dash-serialized-consensus-error-bin";
+
+impl Display for RejectionCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RejectionCategory::Nonce => write!(f, "nonce"),
+            RejectionCategory::Signature => write!(f, "signature"),
+            RejectionCategory::Fee => write!(f, "fee"),
+            RejectionCategory::Consensus(code) => write!(f, "consensus error {}", code),
+            RejectionCategory::Undecoded(code) => write!(f, "undecoded, response code {}", code),
+        }
+    }
+}
+
+/// Category of a rejection along with its message.
+pub(crate) fn categorize(error: &StateTransitionBroadcastError) -> (RejectionCategory, String) {
+    let Ok(consensus_error) = ConsensusError::deserialize_from_bytes(&error.data) else {
+        return (
+            RejectionCategory::Undecoded(error.code),
+            error.message.clone(),
+        );
+    };
+    let category = match &consensus_error {
+        consensus_error if is_nonce_error(consensus_error) => RejectionCategory::Nonce,
+        ConsensusError::SignatureError(_) => RejectionCategory::Signature,
+        ConsensusError::FeeError(_) => RejectionCategory::Fee,
+        _ => RejectionCategory::Consensus(consensus_error.code()),
+    };
+    (category, consensus_error.to_string())
+}
+
+/// Consensus error a broadcast was refused with, if the response carries one.
+pub(crate) fn broadcast_consensus_error(error: &DapiClientError) -> Option<ConsensusError> {
+    let DapiClientError::Transport(TransportError::Grpc(status), ..) = error else {
+        return None;
+    };
+    let bytes = status
+        .metadata()
+        .get_bin(CONSENSUS_ERROR_METADATA_KEY)?
+        .to_bytes()
+        .ok()?;
+    ConsensusError::deserialize_from_bytes(&bytes).ok()
+}
+
+/// Whether the error is about the identity or identity contract nonce of the
+/// transition, which a nonce fetched from Platform may fix.
+pub(crate) fn is_nonce_error(error: &ConsensusError) -> bool {
+    matches!(
+        error,
+        ConsensusError::StateError(StateError::InvalidIdentityNonceError(_))
+            | ConsensusError::BasicError(BasicError::NonceOutOfBoundsError(_))
+    )
+}
+
+/// Rejections of a run by category, shared between its waits.
+#[derive(Debug, Default)]
+pub(crate) struct RejectionCounters(DashMap<RejectionCategory, AtomicU64>);
+
+impl RejectionCounters {
+    pub(crate) fn record(&self, category: RejectionCategory) {
+        self.0
+            .entry(category)
+            .or_insert_with(|| AtomicU64::new(0))
+            .fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Counts so far, by category.
+    pub(crate) fn counts(&self) -> Vec<(RejectionCategory, u64)> {
+        let mut counts: Vec<_> = self
+            .0
+            .iter()
+            .map(|entry| (*entry.key(), entry.value().load(Ordering::SeqCst)))
+            .collect();
+        counts.sort();
+        counts
+    }
+}
@@ -0,0 +1,146 @@
+//! Top ups of identities running out of credits during a strategy run from a
+//! reserve identity.
+//!
+//! An asset lock top up waits for an L1 transaction and its proof and spends a
+//! UTXO of the loaded wallet each time. A credit transfer from an identity
+//! funded beforehand is a single Platform transition instead, so topped up
+//! identities are back to broadcasting a block later. The reserve needs a
+//! transfer key with a known private key.
+
+use std::collections::HashSet;
+
+use dash_sdk::{platform::transition::broadcast::BroadcastStateTransition, Sdk};
+use dpp::{
+    fee::Credits,
+    identity::{
+        accessors::IdentityGettersV0,
+        identity_public_key::accessors::v0::IdentityPublicKeyGettersV0, KeyType, Purpose,
+        SecurityLevel,
+    },
+    platform_value::{string_encoding::Encoding, Identifier},
+    prelude::IdentityPublicKey,
+    state_transition::{
+        identity_credit_transfer_transition::{
+            accessors::IdentityCreditTransferTransitionAccessorsV0,
+            IdentityCreditTransferTransition,
+        },
+        proof_result::StateTransitionProofResult,
+        StateTransition,
+    },
+    ProtocolError,
+};
+use simple_signer::signer::SimpleSigner;
+use tokio::sync::Mutex;
+
+use super::AppState;
+
+/// Identity funding the top ups of a run and how much each one transfers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReserveTopUp {
+    pub identity_id: Identifier,
+    pub credits: Credits,
+}
+
+pub(crate) struct ReserveFunder {
+    identity_id: Identifier,
+    credits: Credits,
+    transfer_key: IdentityPublicKey,
+    signer: SimpleSigner,
+    /// Held while a transfer is made, so each one gets the next nonce
+    transferring: Mutex<()>,
+}
+
+impl ReserveFunder {
+    /// Checks the reserve is a known identity with a usable transfer key.
+    pub(crate) async fn new(app_state: &AppState, top_up: &ReserveTopUp) -> Result<Self, String> {
+        let b58_id = top_up.identity_id.to_string(Encoding::Base58);
+        if top_up.credits == 0 {
+            return Err("Top ups from a reserve identity need an amount".to_owned());
+        }
+        let known_identities = app_state.known_identities.lock().await;
+        let identity = known_identities
+            .get(&top_up.identity_id)
+            .ok_or_else(|| format!("Reserve identity {} is not known", b58_id))?;
+        if identity.balance() < top_up.credits {
+            return Err(format!(
+                "Reserve identity {} can't afford a single top up",
+                b58_id
+            ));
+        }
+        let transfer_key = identity
+            .get_first_public_key_matching(
+                Purpose::TRANSFER,
+                SecurityLevel::full_range().into(),
+                HashSet::from([KeyType::ECDSA_SECP256K1, KeyType::BLS12_381]),
+                false,
+            )
+            .ok_or_else(|| format!("Reserve identity {} has no transfer key", b58_id))?
+            .clone();
+        let private_key = app_state
+            .known_identities_private_keys
+            .lock()
+            .await
+            .get(&(top_up.identity_id, transfer_key.id()))
+            .cloned()
+            .ok_or_else(|| {
+                format!(
+                    "The private transfer key of reserve identity {} is not known",
+                    b58_id
+                )
+            })?;
+
+        let mut signer = SimpleSigner::default();
+        signer.add_key(
+            transfer_key.clone(),
+            private_key
+                .try_into()
+                .map_err(|_| "Expected private key to be 32 bytes".to_owned())?,
+        );
+        Ok(ReserveFunder {
+            identity_id: top_up.identity_id,
+            credits: top_up.credits,
+            transfer_key,
+            signer,
+            transferring: Mutex::new(()),
+        })
+    }
+
+    pub(crate) fn identity_id(&self) -> Identifier {
+        self.identity_id
+    }
+
+    pub(crate) fn credits(&self) -> Credits {
+        self.credits
+    }
+
+    /// Transfers the top up amount to the identity and waits for the result.
+    pub(crate) async fn top_up(&self, sdk: &Sdk, recipient: Identifier) -> Result<(), String> {
+        let _transferring = self.transferring.lock().await;
+        let nonce = sdk
+            .get_identity_nonce(self.identity_id, true, None)
+            .await
+            .map_err(|e| format!("Failed to get the reserve's nonce: {}", e))?;
+
+        let mut transfer_transition =
+            IdentityCreditTransferTransition::default_versioned(sdk.version())
+                .map_err(|e| e.to_string())?;
+        transfer_transition.set_identity_id(self.identity_id);
+        transfer_transition.set_recipient_id(recipient);
+        transfer_transition.set_amount(self.credits);
+        transfer_transition.set_nonce(nonce);
+
+        let mut transition = StateTransition::IdentityCreditTransfer(transfer_transition);
+        transition
+            .sign_external(
+                &self.transfer_key,
+                &self.signer,
+                None::<fn(Identifier, String) -> Result<SecurityLevel, ProtocolError>>,
+            )
+            .map_err(|e| e.to_string())?;
+        transition
+            .broadcast_and_wait(sdk, None)
+            .await
+            .map(|_: StateTransitionProofResult| ())
+            .map_err(|e| e.to_string())
+    }
+}
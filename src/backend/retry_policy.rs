@@ -0,0 +1,74 @@
+//! Timeouts and retries of strategy runs.
+//!
+//! A local network answers within a second while a busy testnet node may take
+//! most of a minute, no single set of timeouts suits both. The policy a
+//! strategy last ran with is stored along with it and used by its next runs,
+//! scheduled and chained ones included.
+
+use std::{
+    cmp::min,
+    fmt::{self, Display},
+    time::Duration,
+};
+
+use bincode::{Decode, Encode};
+
+use crate::format::format_duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Encode, Decode)]
+pub struct RetryPolicy {
+    /// Seconds to wait for the result of a broadcast transition
+    pub wait_timeout_secs: u64,
+    /// Retries of the wait for a transition result
+    pub wait_retries: usize,
+    /// Milliseconds to connect to a DAPI node when broadcasting during the
+    /// load, at most half a loop
+    pub broadcast_connect_timeout_ms: u64,
+    /// Milliseconds a broadcast during the load may take, at most half a loop
+    pub broadcast_timeout_ms: u64,
+    /// Retries of a broadcast during the load, on another node
+    pub broadcast_retries: usize,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            wait_timeout_secs: 75,
+            wait_retries: 5,
+            broadcast_connect_timeout_ms: 3000,
+            broadcast_timeout_ms: 3000,
+            broadcast_retries: 0,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub(crate) fn wait_timeout(&self) -> Duration {
+        Duration::from_secs(self.wait_timeout_secs)
+    }
+
+    /// Connect and request timeouts of broadcasts during the load, so a loop
+    /// isn't held up by a slow node.
+    pub(crate) fn broadcast_timeouts(&self, seconds_per_loop: u64) -> (Duration, Duration) {
+        let half_loop = seconds_per_loop * 1000 / 2;
+        (
+            Duration::from_millis(min(half_loop, self.broadcast_connect_timeout_ms)),
+            Duration::from_millis(min(half_loop, self.broadcast_timeout_ms)),
+        )
+    }
+}
+
+impl Display for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "waits of {} with {} retries, broadcasts connecting within {} and taking up to {} \
+             with {} retries",
+            format_duration(self.wait_timeout()),
+            self.wait_retries,
+            format_duration(Duration::from_millis(self.broadcast_connect_timeout_ms)),
+            format_duration(Duration::from_millis(self.broadcast_timeout_ms)),
+            self.broadcast_retries
+        )
+    }
+}
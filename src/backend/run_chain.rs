@@ -0,0 +1,70 @@
+//! Chains of strategy runs.
+//!
+//! A chain runs strategies one after the other, e.g. one registering
+//! identities and contracts and the next one loading them with documents.
+//! Contracts created by a run are known contracts from then on already, the
+//! identities it creates are handed over to the next run of the chain instead
+//! of getting their funds withdrawn, along with their private keys. The last
+//! run of the chain, or the first one to stop early, withdraws from all of
+//! them as a single run would.
+
+use std::fmt::{self, Display};
+
+use dpp::prelude::Identity;
+use simple_signer::signer::SimpleSigner;
+
+/// Handover from a run of a chain to the next one.
+#[derive(Debug, Default)]
+pub(crate) struct ChainCarry {
+    /// Identities created by the earlier runs, with funds left
+    pub(crate) identities: Vec<Identity>,
+    /// Private keys of these identities
+    pub(crate) signer: SimpleSigner,
+    /// Whether the run reading the carry passes its identities on rather than
+    /// withdrawing from them
+    pub(crate) pass_on: bool,
+}
+
+/// Outcome of each run of a chain, in order.
+#[derive(Debug, Clone, Default)]
+pub struct ChainReport {
+    pub links: Vec<ChainLinkOutcome>,
+    /// Strategies left out after a run stopped the chain
+    pub skipped: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ChainLinkOutcome {
+    pub strategy_name: String,
+    pub success: bool,
+    pub outcome: String,
+}
+
+impl Display for ChainReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Chain of {} strategies:",
+            self.links.len() + self.skipped.len()
+        )?;
+        for (index, link) in self.links.iter().enumerate() {
+            write!(
+                f,
+                "\n{}. {}: {}{}",
+                index + 1,
+                link.strategy_name,
+                link.outcome,
+                if link.success { "" } else { ", chain stopped" }
+            )?;
+        }
+        for (index, strategy_name) in self.skipped.iter().enumerate() {
+            write!(
+                f,
+                "\n{}. {}: not run",
+                self.links.len() + index + 1,
+                strategy_name
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+//! Run parameters a strategy was last started with from the run form.
+//!
+//! Benchmarks compare runs of a strategy, which only holds up if they load the
+//! network the same way. The duration, pace, proof verification and reserve
+//! top ups of the last run started from the form are stored with the strategy
+//! and fill the form of its next run, in this session or a later one.
+
+use bincode::{Decode, Encode};
+use dpp::{fee::Credits, prelude::Identifier};
+
+use super::reserve_top_up::ReserveTopUp;
+
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct RunDefaults {
+    pub duration_secs: u64,
+    pub seconds_per_loop: u64,
+    pub verify_proofs: bool,
+    /// Broadcasts per second the run was paced to, if any
+    pub target_rate: Option<f64>,
+    /// Identity the top ups were transferred from, if not from asset locks
+    pub reserve_identity: Option<Identifier>,
+    /// Credits of each top up from the reserve
+    pub reserve_credits: Credits,
+}
+
+impl RunDefaults {
+    pub(crate) fn new(
+        duration_secs: u64,
+        seconds_per_loop: u64,
+        verify_proofs: bool,
+        target_rate: Option<f64>,
+        reserve_top_up: Option<&ReserveTopUp>,
+    ) -> Self {
+        RunDefaults {
+            duration_secs,
+            seconds_per_loop,
+            verify_proofs,
+            target_rate,
+            reserve_identity: reserve_top_up.map(|reserve| reserve.identity_id),
+            reserve_credits: reserve_top_up
+                .map(|reserve| reserve.credits)
+                .unwrap_or_default(),
+        }
+    }
+}
@@ -0,0 +1,233 @@
+//! History of strategy runs kept in the app state.
+//!
+//! Run reports are written per strategy and only their headline numbers are
+//! compared to a baseline. The history keeps every completed run, whatever the
+//! strategy, with how it was started, the protocol version of the network it
+//! ran against and its mix of errors, so two runs can be put side by side to
+//! tell a regression between platform versions from a change of parameters.
+//! The latest [MAX_RUNS] runs are kept at most.
+
+use std::fmt::{self, Display};
+
+use bincode::{Decode, Encode};
+
+use super::run_schedule::format_time;
+use crate::format::{format_count, format_decimal, format_rate, format_secs};
+
+const MAX_RUNS: usize = 200;
+
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct RunRecord {
+    pub id: u64,
+    pub strategy_name: String,
+    /// Unix time in seconds the run finished at
+    pub finished_at: u64,
+    /// Protocol version of the network during the run
+    pub protocol_version: u32,
+    /// Seconds of load asked for, after the init loops
+    pub duration_secs: u64,
+    pub seconds_per_loop: u64,
+    pub verify_proofs: bool,
+    pub user_fee_increase: u16,
+    /// Seed of the run's random choices
+    pub seed: u64,
+    pub pinned_address: Option<String>,
+    /// Seconds the load actually ran
+    pub run_time_secs: u64,
+    pub transition_count: u64,
+    pub success_count: u64,
+    /// Attempted rate in transitions per second
+    pub rate: f32,
+    /// Successful rate in transitions per second
+    pub success_rate: f32,
+    pub success_percent: u64,
+    /// Wait times percentiles in seconds: 50%, 90% and 95%
+    pub wait_percentiles: [f64; 3],
+    /// Count of each kind of broadcast error and rejection
+    pub errors: Vec<(String, u64)>,
+    pub halt_reason: Option<String>,
+}
+
+impl RunRecord {
+    /// Errors of each kind as a share of the attempted transitions.
+    pub fn error_percent(&self, count: u64) -> f64 {
+        if self.transition_count == 0 {
+            0.0
+        } else {
+            count as f64 * 100.0 / self.transition_count as f64
+        }
+    }
+
+    fn to_short(&self) -> String {
+        format!("#{} {}", self.id, format_time(self.finished_at))
+    }
+}
+
+impl Display for RunRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "#{} {} at {}, v{}: {}, {}%",
+            self.id,
+            self.strategy_name,
+            format_time(self.finished_at),
+            self.protocol_version,
+            format_rate(self.success_rate.into()),
+            self.success_percent
+        )
+    }
+}
+
+/// Completed strategy runs, oldest first.
+#[derive(Debug, Clone, Default, PartialEq, Encode, Decode)]
+pub struct RunHistory {
+    pub runs: Vec<RunRecord>,
+    next_id: u64,
+}
+
+impl RunHistory {
+    /// Adds a run, returning its ID.
+    pub(crate) fn record(&mut self, mut record: RunRecord) -> u64 {
+        self.next_id += 1;
+        record.id = self.next_id;
+        self.runs.push(record);
+        if self.runs.len() > MAX_RUNS {
+            self.runs.remove(0);
+        }
+        self.next_id
+    }
+
+    pub fn get(&self, id: u64) -> Option<&RunRecord> {
+        self.runs.iter().find(|record| record.id == id)
+    }
+}
+
+/// Both runs' parameters and results line by line, with the change from the
+/// first run to the second one.
+pub fn compare(before: &RunRecord, after: &RunRecord) -> String {
+    let mut lines = vec![
+        format!(
+            "{:<24}{:<28}{:<28}",
+            "",
+            before.to_short(),
+            after.to_short()
+        ),
+        row("Strategy", &before.strategy_name, &after.strategy_name),
+        row(
+            "Protocol version",
+            before.protocol_version,
+            after.protocol_version,
+        ),
+        row(
+            "Duration",
+            format_secs(before.duration_secs),
+            format_secs(after.duration_secs),
+        ),
+        row(
+            "Seconds per loop",
+            before.seconds_per_loop,
+            after.seconds_per_loop,
+        ),
+        row("Verify proofs", before.verify_proofs, after.verify_proofs),
+        row(
+            "Fee increase (%)",
+            before.user_fee_increase,
+            after.user_fee_increase,
+        ),
+        row("Seed", before.seed, after.seed),
+        row(
+            "Pinned address",
+            before.pinned_address.as_deref().unwrap_or("-"),
+            after.pinned_address.as_deref().unwrap_or("-"),
+        ),
+        row(
+            "Transitions",
+            format_count(before.transition_count),
+            format_count(after.transition_count),
+        ),
+        change_row("Attempted tx/s", before.rate as f64, after.rate as f64, ""),
+        change_row(
+            "Successful tx/s",
+            before.success_rate as f64,
+            after.success_rate as f64,
+            "",
+        ),
+        change_row(
+            "Success %",
+            before.success_percent as f64,
+            after.success_percent as f64,
+            " points",
+        ),
+    ];
+    for (label, index) in [
+        ("Wait p50 (s)", 0),
+        ("Wait p90 (s)", 1),
+        ("Wait p95 (s)", 2),
+    ] {
+        lines.push(change_row(
+            label,
+            before.wait_percentiles[index],
+            after.wait_percentiles[index],
+            "",
+        ));
+    }
+
+    lines.push("Errors, % of attempted:".to_owned());
+    let mut kinds: Vec<&String> = before
+        .errors
+        .iter()
+        .chain(after.errors.iter())
+        .map(|(kind, _)| kind)
+        .collect();
+    kinds.sort();
+    kinds.dedup();
+    if kinds.is_empty() {
+        lines.push("  none".to_owned());
+    }
+    for kind in kinds {
+        let count_of = |record: &RunRecord| {
+            record
+                .errors
+                .iter()
+                .find(|(k, _)| k == kind)
+                .map(|(_, count)| *count)
+                .unwrap_or_default()
+        };
+        let (before_count, after_count) = (count_of(before), count_of(after));
+        lines.push(change_row(
+            &format!("  {}", kind),
+            before.error_percent(before_count),
+            after.error_percent(after_count),
+            " points",
+        ));
+    }
+
+    if before.halt_reason.is_some() || after.halt_reason.is_some() {
+        lines.push(row(
+            "Stopped early",
+            before.halt_reason.as_deref().unwrap_or("-"),
+            after.halt_reason.as_deref().unwrap_or("-"),
+        ));
+    }
+    lines.join("\n")
+}
+
+fn row(label: &str, before: impl Display, after: impl Display) -> String {
+    format!(
+        "{:<24}{:<28}{:<28}",
+        label,
+        before.to_string(),
+        after.to_string()
+    )
+}
+
+fn change_row(label: &str, before: f64, after: f64, unit: &str) -> String {
+    format!(
+        "{:<24}{:<28}{:<28}{:+.2}{}",
+        label,
+        format_decimal(before, 2),
+        format_decimal(after, 2),
+        after - before,
+        unit
+    )
+}
@@ -0,0 +1,153 @@
+//! Live progress of a strategy run.
+//!
+//! The running instance writes a snapshot next to its state file every second,
+//! so another instance started in follow mode can display it without access to
+//! the backend. Result counters are refreshed every 10 loops, the depth of each
+//! stage of the run on every snapshot. With several runs going on, the file
+//! follows the one started first.
+
+use std::{
+    fmt::{self, Display},
+    fs,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::format::{format_count, format_secs};
+
+/// Width in characters of a stage gauge
+const GAUGE_WIDTH: usize = 20;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunProgress {
+    pub strategy_name: String,
+    pub elapsed_secs: u64,
+    pub duration_secs: u64,
+    pub transitions_attempted: u64,
+    pub broadcast_successful: u64,
+    pub broadcast_failed: u64,
+    pub broadcast_ongoing: u64,
+    pub wait_successful: u64,
+    pub wait_failed: u64,
+    pub wait_ongoing: u64,
+    pub wait_unknown: u64,
+    /// Wait times percentiles in seconds: 50%, 90% and 95%
+    pub wait_percentiles: [f64; 3],
+    /// Transitions prepared by the current loop and not broadcast yet
+    pub queued_broadcasts: u64,
+    /// Whether the current loop is still generating its transitions
+    pub generating: bool,
+    pub finished: bool,
+    /// Unix time in seconds of the snapshot
+    pub updated_at: u64,
+}
+
+impl RunProgress {
+    /// Writes the snapshot, through a temporary file so followers never read
+    /// a partial one. Failures are only logged, a run shouldn't stop for them.
+    pub(crate) fn write(mut self, path: &Path) {
+        self.stamp();
+        let json = serde_json::to_string_pretty(&self).expect("progress is serializable");
+        let tmp_path = path.with_extension("tmp");
+        if let Err(e) = fs::write(&tmp_path, json).and_then(|_| fs::rename(&tmp_path, path)) {
+            tracing::warn!("Failed to write run progress to {}: {}", path.display(), e);
+        }
+    }
+
+    /// Dates the snapshot now.
+    pub(crate) fn stamp(&mut self) {
+        self.updated_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_secs())
+            .unwrap_or_default();
+    }
+
+    pub fn read(path: &Path) -> Result<RunProgress, String> {
+        let json = fs::read_to_string(path)
+            .map_err(|e| format!("No run progress at {}: {}", path.display(), e))?;
+        serde_json::from_str(&json).map_err(|e| format!("Invalid run progress: {}", e))
+    }
+
+    pub fn updated_secs_ago(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_secs().saturating_sub(self.updated_at))
+            .unwrap_or_default()
+    }
+
+    /// Gauges of the transitions in each stage of the run, scaled to the
+    /// fullest one, with the stage that is likely holding the run back.
+    pub fn stage_gauges(&self) -> String {
+        let stages = [
+            ("Queued", self.queued_broadcasts),
+            ("Broadcasting", self.broadcast_ongoing),
+            ("Waiting", self.wait_ongoing),
+        ];
+        let max = stages
+            .iter()
+            .map(|(_, depth)| *depth)
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let mut gauges = format!(
+            "Generating transitions: {}\n",
+            if self.generating { "yes" } else { "no" }
+        );
+        for (stage, depth) in stages {
+            let filled = (depth as usize * GAUGE_WIDTH).div_ceil(max as usize);
+            gauges.push_str(&format!(
+                "{:<13}[{}{}] {}\n",
+                stage,
+                "#".repeat(filled),
+                " ".repeat(GAUGE_WIDTH - filled),
+                depth
+            ));
+        }
+        let bottleneck = if self.finished {
+            "none, the run is finished"
+        } else if self.generating && self.queued_broadcasts + self.broadcast_ongoing == 0 {
+            "transition generation"
+        } else if self.queued_broadcasts + self.broadcast_ongoing > self.wait_ongoing {
+            "broadcast"
+        } else if self.wait_ongoing > 0 {
+            "waiting for results"
+        } else {
+            "none, idle until the next loop"
+        };
+        gauges.push_str(&format!("Bottleneck: {}", bottleneck));
+        gauges
+    }
+}
+
+impl Display for RunProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = if self.finished { "finished" } else { "running" };
+        write!(
+            f,
+            "Strategy '{}' {}\n\nElapsed: {} of {}\nState transitions attempted: {}\n\
+             Broadcast results: {} successful, {} failed, {} ongoing\n\
+             Wait results: {} successful, {} failed, {} ongoing, {} unknown\n\
+             Wait times (s): 50% - {} 90% - {} 95% - {}\n\n{}\n\nUpdated {}s ago",
+            self.strategy_name,
+            status,
+            format_secs(self.elapsed_secs),
+            format_secs(self.duration_secs),
+            format_count(self.transitions_attempted),
+            format_count(self.broadcast_successful),
+            format_count(self.broadcast_failed),
+            format_count(self.broadcast_ongoing),
+            format_count(self.wait_successful),
+            format_count(self.wait_failed),
+            format_count(self.wait_ongoing),
+            format_count(self.wait_unknown),
+            self.wait_percentiles[0],
+            self.wait_percentiles[1],
+            self.wait_percentiles[2],
+            self.stage_gauges(),
+            self.updated_secs_ago()
+        )
+    }
+}
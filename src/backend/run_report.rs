@@ -0,0 +1,296 @@
+//! Persisted strategy run reports and comparison against a baseline run.
+//!
+//! Every completed run writes its headline numbers to
+//! `supporting_files/run_reports/<strategy>/<time>.json`. One of those reports
+//! can be marked as the strategy's baseline, with tolerances, and later runs
+//! flag the numbers that got worse beyond them.
+//!
+//! With `EXPLORER_REPORT_UPLOAD_URL` set, reports are also POSTed there once
+//! written, so the results of tests distributed over many explorers end up on
+//! a shared server.
+
+use std::{
+    fmt::{self, Display},
+    fs,
+    path::PathBuf,
+    time::Duration,
+};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use super::traffic::TrafficReport;
+use crate::config::Config;
+
+pub(crate) const REPORTS_DIR: &str = "supporting_files/run_reports";
+const BASELINE_FILE: &str = "baseline.json";
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunReport {
+    pub strategy_name: String,
+    pub run_time_secs: u64,
+    pub transitions_attempted: u64,
+    /// Attempted rate in transitions per second
+    pub rate: f32,
+    /// Successful rate in transitions per second
+    pub success_rate: f32,
+    pub success_percent: u64,
+    /// Wait times percentiles in seconds: 50%, 90% and 95%
+    pub wait_percentiles: [f64; 3],
+    /// DAPI address the run was pinned to, if any
+    #[serde(default)]
+    pub pinned_address: Option<String>,
+    /// Fee increase of the run's transitions, in percent of the base fee
+    #[serde(default)]
+    pub user_fee_increase: u16,
+    /// Bytes sent and received over the run, by kind of request
+    #[serde(default)]
+    pub traffic: TrafficReport,
+    /// Aliases of the contracts the run created or reused, as "alias: ID"
+    #[serde(default)]
+    pub contract_aliases: Vec<String>,
+}
+
+/// How much worse than the baseline a run may get before it's flagged.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tolerances {
+    /// Allowed increase of the 95th percentile wait time, in percent
+    pub p95_increase_percent: f64,
+    /// Allowed decrease of the successful rate, in percent
+    pub success_rate_drop_percent: f64,
+    /// Allowed decrease of the success percentage, in points
+    pub success_percent_drop: u64,
+}
+
+impl Default for Tolerances {
+    fn default() -> Self {
+        Tolerances {
+            p95_increase_percent: 20.0,
+            success_rate_drop_percent: 10.0,
+            success_percent_drop: 5,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Baseline {
+    report_file: String,
+    report: RunReport,
+    tolerances: Tolerances,
+}
+
+/// A run compared to its strategy's baseline.
+#[derive(Debug, Clone)]
+pub struct BaselineComparison {
+    pub baseline_file: String,
+    pub changes: Vec<String>,
+    pub regressions: Vec<String>,
+}
+
+impl Display for BaselineComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Compared to baseline {}:", self.baseline_file)?;
+        for change in &self.changes {
+            write!(f, "\n - {}", change)?;
+        }
+        if self.regressions.is_empty() {
+            write!(f, "\nNo regressions")
+        } else {
+            for regression in &self.regressions {
+                write!(f, "\nREGRESSION: {}", regression)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+pub(crate) fn strategy_dir(strategy_name: &str) -> PathBuf {
+    PathBuf::from(REPORTS_DIR).join(strategy_name)
+}
+
+impl RunReport {
+    /// Writes the report in its strategy's directory, returning the file name.
+    pub(crate) fn save(&self) -> Result<String, String> {
+        let dir = strategy_dir(&self.strategy_name);
+        let file_name = format!("{}.json", Utc::now().format("%Y%m%d%H%M%S"));
+        let json = serde_json::to_string_pretty(self).expect("report is serializable");
+        fs::create_dir_all(&dir)
+            .and_then(|_| fs::write(dir.join(&file_name), json))
+            .map_err(|e| format!("Failed to write run report to {}: {}", dir.display(), e))?;
+        Ok(file_name)
+    }
+
+    /// POSTs the report to the configured results server, if any, along with
+    /// the network it ran against and its file name. Returns the URL it was
+    /// sent to.
+    pub(crate) async fn upload(
+        &self,
+        config: &Config,
+        file_name: &str,
+    ) -> Option<Result<String, String>> {
+        let url = config.report_upload_url.as_ref()?;
+        let body = serde_json::json!({
+            "network": config.network,
+            "report_file": file_name,
+            "report": self,
+        });
+        let mut request = reqwest::Client::new()
+            .post(url)
+            .timeout(UPLOAD_TIMEOUT)
+            .json(&body);
+        if let Some(token) = &config.report_upload_token {
+            request = request.bearer_auth(token);
+        }
+        let result = match request.send().await {
+            Ok(response) if response.status().is_success() => Ok(url.clone()),
+            Ok(response) => Err(format!(
+                "Results server {} refused run report {}: {}",
+                url,
+                file_name,
+                response.status()
+            )),
+            Err(e) => Err(format!(
+                "Failed to upload run report {} to {}: {}",
+                file_name, url, e
+            )),
+        };
+        Some(result)
+    }
+}
+
+/// Report file names of a strategy, newest first.
+pub(crate) fn list_reports(strategy_name: &str) -> Vec<String> {
+    let mut reports: Vec<String> = fs::read_dir(strategy_dir(strategy_name))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| name.ends_with(".json") && name != BASELINE_FILE)
+        .collect();
+    reports.sort_unstable_by(|a, b| b.cmp(a));
+    reports
+}
+
+pub(crate) fn read_report(strategy_name: &str, report_file: &str) -> Result<RunReport, String> {
+    fs::read_to_string(strategy_dir(strategy_name).join(report_file))
+        .map_err(|e| format!("Cannot read run report {}: {}", report_file, e))
+        .and_then(|json| {
+            serde_json::from_str(&json).map_err(|e| format!("Invalid run report: {}", e))
+        })
+}
+
+/// Marks a stored report as the strategy's baseline.
+pub(crate) fn set_baseline(
+    strategy_name: &str,
+    report_file: &str,
+    tolerances: &Tolerances,
+) -> Result<String, String> {
+    let dir = strategy_dir(strategy_name);
+    let report = read_report(strategy_name, report_file)?;
+    let baseline = Baseline {
+        report_file: report_file.to_owned(),
+        report,
+        tolerances: tolerances.clone(),
+    };
+    let json = serde_json::to_string_pretty(&baseline).expect("baseline is serializable");
+    fs::write(dir.join(BASELINE_FILE), json)
+        .map_err(|e| format!("Failed to write baseline: {}", e))?;
+    Ok(format!(
+        "Run {} is now the baseline of strategy {}",
+        report_file, strategy_name
+    ))
+}
+
+/// Compares a report to its strategy's baseline, `None` if there is none.
+pub(crate) fn compare_to_baseline(report: &RunReport) -> Option<BaselineComparison> {
+    let json = fs::read_to_string(strategy_dir(&report.strategy_name).join(BASELINE_FILE)).ok()?;
+    let baseline: Baseline = serde_json::from_str(&json)
+        .map_err(|e| tracing::warn!("Invalid baseline of {}: {}", report.strategy_name, e))
+        .ok()?;
+    let tolerances = &baseline.tolerances;
+    let mut changes = Vec::new();
+    let mut regressions = Vec::new();
+
+    let p95 = report.wait_percentiles[2];
+    let baseline_p95 = baseline.report.wait_percentiles[2];
+    let p95_change = percent_change(baseline_p95, p95);
+    changes.push(format!(
+        "p95 wait time: {}s (baseline {}s, {:+.0}%)",
+        p95, baseline_p95, p95_change
+    ));
+    if p95_change > tolerances.p95_increase_percent {
+        regressions.push(format!(
+            "p95 wait time up {:.0}%, above the {:.0}% tolerance",
+            p95_change, tolerances.p95_increase_percent
+        ));
+    }
+
+    let rate_change = percent_change(
+        baseline.report.success_rate as f64,
+        report.success_rate as f64,
+    );
+    changes.push(format!(
+        "Successful rate: {:.2} tx/s (baseline {:.2} tx/s, {:+.0}%)",
+        report.success_rate, baseline.report.success_rate, rate_change
+    ));
+    if -rate_change > tolerances.success_rate_drop_percent {
+        regressions.push(format!(
+            "successful rate down {:.0}%, above the {:.0}% tolerance",
+            -rate_change, tolerances.success_rate_drop_percent
+        ));
+    }
+
+    let success_drop = baseline
+        .report
+        .success_percent
+        .saturating_sub(report.success_percent);
+    changes.push(format!(
+        "Success percentage: {}% (baseline {}%)",
+        report.success_percent, baseline.report.success_percent
+    ));
+    if success_drop > tolerances.success_percent_drop {
+        regressions.push(format!(
+            "success percentage down {} points, above the {} points tolerance",
+            success_drop, tolerances.success_percent_drop
+        ));
+    }
+
+    // Numbers of a single node and of the whole network don't compare well
+    if report.pinned_address != baseline.report.pinned_address {
+        let pinning = |address: &Option<String>| {
+            address
+                .as_deref()
+                .map_or("all addresses".to_owned(), |address| {
+                    format!("pinned to {}", address)
+                })
+        };
+        changes.push(format!(
+            "DAPI: {} (baseline {})",
+            pinning(&report.pinned_address),
+            pinning(&baseline.report.pinned_address)
+        ));
+    }
+
+    // Fee bumped transitions may be included faster than the baseline's
+    if report.user_fee_increase != baseline.report.user_fee_increase {
+        changes.push(format!(
+            "User fee increase: {}% (baseline {}%)",
+            report.user_fee_increase, baseline.report.user_fee_increase
+        ));
+    }
+
+    Some(BaselineComparison {
+        baseline_file: baseline.report_file,
+        changes,
+        regressions,
+    })
+}
+
+fn percent_change(baseline: f64, value: f64) -> f64 {
+    if baseline == 0.0 {
+        0.0
+    } else {
+        (value - baseline) / baseline * 100.0
+    }
+}
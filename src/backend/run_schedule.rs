@@ -0,0 +1,183 @@
+//! Strategy runs started at a set time, once or repeatedly.
+//!
+//! A schedule names a strategy and how to run it, the time of its next run
+//! and, for repeated runs such as nightly soak tests, the hours between runs.
+//! The UI checks for due schedules every so often and starts one only when no
+//! other task is running. A schedule is moved to its next run as soon as a
+//! run of it starts, so a run that fails or is interrupted isn't started
+//! again right away. Runs missed while the application was closed are
+//! skipped: a repeated schedule continues with its next run in the future
+//! and a single one runs once, late.
+//!
+//! The outcome of every scheduled run is kept in a history, the latest
+//! [MAX_HISTORY] runs at most.
+
+use std::fmt::{self, Display};
+
+use bincode::{Decode, Encode};
+use chrono::{DateTime, Duration as ChronoDuration, NaiveTime, TimeZone, Utc};
+use dpp::fee::Credits;
+
+use super::AppState;
+
+const MAX_HISTORY: usize = 100;
+const SECS_PER_HOUR: u64 = 3600;
+
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct RunSchedule {
+    pub id: u64,
+    pub strategy_name: String,
+    /// Seconds of load after the init loops
+    pub duration_secs: u64,
+    pub seconds_per_loop: u64,
+    pub verify_proofs: bool,
+    pub spend_cap: Option<Credits>,
+    /// Unix time in seconds of the next run
+    pub next_run: u64,
+    /// Hours between runs, `None` for a single run
+    pub repeat_hours: Option<u64>,
+}
+
+impl Display for RunSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "#{} {} at {}",
+            self.id,
+            self.strategy_name,
+            format_time(self.next_run)
+        )?;
+        if let Some(hours) = self.repeat_hours {
+            write!(f, ", every {}h", hours)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct ScheduledRunRecord {
+    pub schedule_id: u64,
+    pub strategy_name: String,
+    pub started_at: u64,
+    pub finished_at: u64,
+    pub success: bool,
+    /// Summary of the run or why it failed
+    pub outcome: String,
+}
+
+impl Display for ScheduledRunRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "#{} {} at {} ({}s): {}",
+            self.schedule_id,
+            self.strategy_name,
+            format_time(self.started_at),
+            self.finished_at.saturating_sub(self.started_at),
+            self.outcome
+        )
+    }
+}
+
+/// Pending schedules and the runs made of them, oldest first.
+#[derive(Debug, Clone, Default, PartialEq, Encode, Decode)]
+pub struct RunSchedules {
+    pub pending: Vec<RunSchedule>,
+    pub history: Vec<ScheduledRunRecord>,
+    next_id: u64,
+}
+
+impl RunSchedules {
+    /// Adds a schedule, returning its ID.
+    pub(crate) fn add(&mut self, mut schedule: RunSchedule) -> u64 {
+        self.next_id += 1;
+        schedule.id = self.next_id;
+        self.pending.push(schedule);
+        self.next_id
+    }
+
+    pub(crate) fn remove(&mut self, id: u64) -> Option<RunSchedule> {
+        let index = self.pending.iter().position(|schedule| schedule.id == id)?;
+        Some(self.pending.remove(index))
+    }
+
+    /// ID of the schedule due the longest at `now`, if any.
+    pub(crate) fn due(&self, now: u64) -> Option<u64> {
+        self.pending
+            .iter()
+            .filter(|schedule| schedule.next_run <= now)
+            .min_by_key(|schedule| schedule.next_run)
+            .map(|schedule| schedule.id)
+    }
+
+    /// Takes a schedule for a run starting at `now`: a repeated one is moved
+    /// to its next run in the future, a single one is removed.
+    pub(crate) fn start(&mut self, id: u64, now: u64) -> Option<RunSchedule> {
+        let index = self.pending.iter().position(|schedule| schedule.id == id)?;
+        let schedule = self.pending[index].clone();
+        match schedule.repeat_hours {
+            Some(hours) => {
+                let interval = hours.max(1) * SECS_PER_HOUR;
+                let next_run = &mut self.pending[index].next_run;
+                while *next_run <= now {
+                    *next_run += interval;
+                }
+            }
+            None => {
+                self.pending.remove(index);
+            }
+        }
+        Some(schedule)
+    }
+
+    pub(crate) fn record(&mut self, record: ScheduledRunRecord) {
+        self.history.push(record);
+        if self.history.len() > MAX_HISTORY {
+            self.history.remove(0);
+        }
+    }
+}
+
+impl AppState {
+    /// Schedule due the longest, to be run once no other task is running.
+    pub async fn due_run_schedule(&self) -> Option<u64> {
+        self.run_schedules.lock().await.due(now_secs())
+    }
+}
+
+pub(crate) fn now_secs() -> u64 {
+    Utc::now().timestamp() as u64
+}
+
+/// Parses when a schedule first runs: `now`, `+<minutes>` or `HH:MM` for the
+/// next time of day it is in UTC.
+pub(crate) fn parse_start_time(input: &str, now: u64) -> Result<u64, String> {
+    let input = input.trim();
+    if input == "now" {
+        return Ok(now);
+    }
+    if let Some(minutes) = input.strip_prefix('+') {
+        return minutes
+            .trim()
+            .parse::<u64>()
+            .map(|minutes| now + minutes * 60)
+            .map_err(|_| format!("Expected minutes after +, got `{}`", minutes));
+    }
+    let time = NaiveTime::parse_from_str(input, "%H:%M")
+        .map_err(|_| "Expected now, +<minutes> or HH:MM (UTC)".to_owned())?;
+    let now_time = Utc
+        .timestamp_opt(now as i64, 0)
+        .single()
+        .ok_or("Invalid current time")?;
+    let mut start = now_time.date_naive().and_time(time).and_utc();
+    if start <= now_time {
+        start += ChronoDuration::days(1);
+    }
+    Ok(start.timestamp() as u64)
+}
+
+pub(crate) fn format_time(secs: u64) -> String {
+    DateTime::<Utc>::from_timestamp(secs as i64, 0)
+        .map(|time| time.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_else(|| secs.to_string())
+}
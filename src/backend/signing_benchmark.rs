@@ -0,0 +1,222 @@
+//! Client-side ceiling of the transition rate.
+//!
+//! Every transition of a run is signed and serialized on this machine before
+//! it's broadcast. The benchmark times both for each key type identities can
+//! sign with: the rate they allow is the most a run can reach however fast
+//! the network is. A run well below the ceiling of its keys is held back by
+//! the network, one close to it by the client.
+//!
+//! A credit transfer stands in for every transition, signing time barely
+//! depends on what's signed since only its hash is.
+
+use std::{
+    fmt::{self, Display},
+    time::{Duration, Instant},
+};
+
+use dpp::{
+    identity::{signer::Signer, KeyType, Purpose, SecurityLevel},
+    platform_value::{BinaryData, Identifier},
+    prelude::IdentityPublicKey,
+    serialization::{PlatformSerializable, Signable},
+    state_transition::{
+        identity_credit_transfer_transition::{
+            v0::IdentityCreditTransferTransitionV0, IdentityCreditTransferTransition,
+        },
+        StateTransition,
+    },
+    version::PlatformVersion,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use simple_signer::signer::SimpleSigner;
+
+use super::run_history::RunRecord;
+use crate::format::format_rate;
+
+/// Time spent signing, then serializing, with each key type
+const MEASURE_TIME: Duration = Duration::from_millis(500);
+
+/// Key types identities sign transitions with
+const KEY_TYPES: [KeyType; 4] = [
+    KeyType::ECDSA_SECP256K1,
+    KeyType::ECDSA_HASH160,
+    KeyType::BLS12_381,
+    KeyType::EDDSA_25519_HASH160,
+];
+
+/// Share of the ceiling above which a run is taken as held back by the client
+const CLIENT_BOUND_SHARE: f64 = 0.8;
+
+/// Throughput of a key type on this machine.
+#[derive(Debug, Clone)]
+pub(crate) struct KeyTypeThroughput {
+    pub(crate) key_type: KeyType,
+    pub(crate) signatures_per_sec: f64,
+    pub(crate) serializations_per_sec: f64,
+    /// Bytes of the signed transition
+    pub(crate) transition_size: usize,
+}
+
+impl KeyTypeThroughput {
+    /// Transitions per second a single thread signs and serializes.
+    pub(crate) fn ceiling(&self) -> f64 {
+        1.0 / (1.0 / self.signatures_per_sec + 1.0 / self.serializations_per_sec)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct SigningBenchmark {
+    pub(crate) key_types: Vec<Result<KeyTypeThroughput, String>>,
+    /// Threads this machine runs at once
+    pub(crate) threads: usize,
+}
+
+impl SigningBenchmark {
+    /// Ceiling of the keys strategy runs sign most transitions with.
+    fn run_ceiling(&self) -> Option<f64> {
+        self.key_types
+            .iter()
+            .flatten()
+            .find(|throughput| throughput.key_type == KeyType::ECDSA_SECP256K1)
+            .map(KeyTypeThroughput::ceiling)
+    }
+
+    /// Whether the rate of `run` was bound by the client or the network.
+    pub(crate) fn verdict(&self, run: &RunRecord) -> String {
+        let Some(ceiling) = self.run_ceiling() else {
+            return "No ECDSA ceiling to compare the last run with".to_owned();
+        };
+        let share = run.rate as f64 / ceiling;
+        let bound = if share >= CLIENT_BOUND_SHARE {
+            "the client: signing and serializing take most of each loop, a faster machine or \
+             lighter keys would raise it"
+        } else {
+            "the network: the client could sign and serialize faster than it broadcast"
+        };
+        format!(
+            "Last run #{} of {} attempted {}, {:.0}% of the ECDSA ceiling. Its rate was bound \
+             by {}.",
+            run.id,
+            run.strategy_name,
+            format_rate(run.rate.into()),
+            share * 100.0,
+            bound
+        )
+    }
+}
+
+impl Display for SigningBenchmark {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Client-side ceiling per key type, on one of {} threads:\n",
+            self.threads
+        )?;
+        writeln!(
+            f,
+            "{:<22}{:>14}{:>16}{:>8}{:>14}",
+            "Key type", "Signatures/s", "Serializing/s", "Bytes", "Ceiling"
+        )?;
+        for result in &self.key_types {
+            match result {
+                Ok(throughput) => writeln!(
+                    f,
+                    "{:<22}{:>14.0}{:>16.0}{:>8}{:>14}",
+                    format!("{:?}", throughput.key_type),
+                    throughput.signatures_per_sec,
+                    throughput.serializations_per_sec,
+                    throughput.transition_size,
+                    format_rate(throughput.ceiling())
+                )?,
+                Err(e) => writeln!(f, "{}", e)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Times signing and serializing a transition with each key type. Runs for
+/// about a second per key type and keeps the thread busy meanwhile.
+pub(crate) fn run_benchmark(platform_version: &PlatformVersion) -> SigningBenchmark {
+    let mut rng = StdRng::from_entropy();
+    let key_types = KEY_TYPES
+        .iter()
+        .map(|key_type| measure(*key_type, &mut rng, platform_version))
+        .collect();
+    SigningBenchmark {
+        key_types,
+        threads: std::thread::available_parallelism().map_or(1, |threads| threads.get()),
+    }
+}
+
+fn measure(
+    key_type: KeyType,
+    rng: &mut StdRng,
+    platform_version: &PlatformVersion,
+) -> Result<KeyTypeThroughput, String> {
+    let error = |e: dpp::ProtocolError| format!("{:?}: {}", key_type, e);
+    let (public_key, private_key) = IdentityPublicKey::random_key_with_known_attributes(
+        0,
+        rng,
+        Purpose::AUTHENTICATION,
+        SecurityLevel::HIGH,
+        key_type,
+        None,
+        platform_version,
+    )
+    .map_err(error)?;
+    let mut signer = SimpleSigner::default();
+    signer.add_key(
+        public_key.clone(),
+        private_key
+            .try_into()
+            .map_err(|_| format!("{:?}: private key isn't 32 bytes", key_type))?,
+    );
+
+    let mut transition = StateTransition::IdentityCreditTransfer(
+        IdentityCreditTransferTransition::V0(IdentityCreditTransferTransitionV0 {
+            identity_id: Identifier::new(rng.gen()),
+            recipient_id: Identifier::new(rng.gen()),
+            amount: 0,
+            nonce: 0,
+            user_fee_increase: 0,
+            signature_public_key_id: 0,
+            signature: BinaryData::default(),
+        }),
+    );
+
+    // Each transition gets a new nonce so no two signed payloads are the same
+    let mut nonce = 0;
+    let mut signatures = 0u64;
+    let start = Instant::now();
+    while start.elapsed() < MEASURE_TIME {
+        nonce += 1;
+        if let StateTransition::IdentityCreditTransfer(IdentityCreditTransferTransition::V0(
+            transfer,
+        )) = &mut transition
+        {
+            transfer.nonce = nonce;
+        }
+        let signable = transition.signable_bytes().map_err(error)?;
+        let signature = signer.sign(&public_key, &signable).map_err(error)?;
+        transition.set_signature(signature);
+        signatures += 1;
+    }
+    let signatures_per_sec = signatures as f64 / start.elapsed().as_secs_f64();
+
+    let mut serializations = 0u64;
+    let mut transition_size = 0;
+    let start = Instant::now();
+    while start.elapsed() < MEASURE_TIME {
+        transition_size = transition.serialize_to_bytes().map_err(error)?.len();
+        serializations += 1;
+    }
+    let serializations_per_sec = serializations as f64 / start.elapsed().as_secs_f64();
+
+    Ok(KeyTypeThroughput {
+        key_type,
+        signatures_per_sec,
+        serializations_per_sec,
+        transition_size,
+    })
+}
@@ -0,0 +1,311 @@
+//! Read-only copy of the application state for screens.
+//!
+//! Backend tasks may hold [AppState] locks for as long as they run, a screen
+//! locking them to draw itself would wait for the task. The backend publishes
+//! a snapshot of the parts screens display after each task instead, reading it
+//! takes no lock a task could hold. Publishing doesn't wait for locks either:
+//! state a task or an event still holds keeps its previous copy, and the
+//! snapshot is marked stale for the UI loop to publish it again shortly.
+
+use std::{
+    cell::Cell,
+    collections::{BTreeMap, BTreeSet},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use dpp::{
+    data_contract::accessors::v0::DataContractV0Getters,
+    identity::KeyID,
+    platform_value::string_encoding::Encoding,
+    prelude::{DataContract, Identifier, Identity},
+};
+use strategy_tests::Strategy;
+use tokio::sync::{watch, Mutex};
+
+use super::{
+    contract_weights::ContractWeights,
+    document_generators::DocumentGenerators,
+    frequency_profile::FrequencyProfile,
+    key_maintenance::KeyMaintenanceOp,
+    retry_policy::RetryPolicy,
+    run_defaults::RunDefaults,
+    run_history::RunHistory,
+    run_schedule::RunSchedules,
+    state::{ContractAliases, StrategyContractNames},
+    AppState, Wallet,
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct AppStateSnapshot {
+    pub loaded_identity: Option<Identity>,
+    pub loaded_wallet: Option<Wallet>,
+    pub sweep_wallet_address: Option<String>,
+    pub loaded_wallet_name: Option<String>,
+    /// Wallets of the wallet manager other than the loaded one
+    pub stored_wallets: BTreeMap<String, Wallet>,
+    pub known_identities: BTreeMap<Identifier, Identity>,
+    pub known_identities_names: BTreeMap<Identifier, Vec<String>>,
+    /// Keys of known identities a private key is known for, not the keys
+    /// themselves
+    pub private_key_ids: BTreeSet<(Identifier, KeyID)>,
+    pub known_contracts: BTreeMap<String, DataContract>,
+    pub supporting_contracts: BTreeMap<String, DataContract>,
+    /// Known identities signing strategy runs along with the loaded one
+    pub strategy_signing_identities: BTreeSet<Identifier>,
+    pub run_schedules: RunSchedules,
+    pub run_history: RunHistory,
+    /// Names known and supporting contracts were loaded under, if not their ID
+    pub known_contract_names: BTreeMap<Identifier, String>,
+    pub available_strategies: BTreeMap<String, Strategy>,
+    pub strategy_contract_names: BTreeMap<String, StrategyContractNames>,
+    pub selected_strategy: Option<String>,
+    pub strategy_frequency_profiles: BTreeMap<String, FrequencyProfile>,
+    pub strategy_contract_aliases: BTreeMap<String, ContractAliases>,
+    pub strategy_key_maintenance: BTreeMap<String, Vec<KeyMaintenanceOp>>,
+    pub strategy_contract_weights: BTreeMap<String, ContractWeights>,
+    pub strategy_document_generators: BTreeMap<String, DocumentGenerators>,
+    /// Timeouts and retries strategies last ran with
+    pub strategy_retry_policies: BTreeMap<String, RetryPolicy>,
+    /// Run form parameters strategies were last started with
+    pub strategy_run_defaults: BTreeMap<String, RunDefaults>,
+    pub identity_registration_in_progress: bool,
+    pub identity_top_up_in_progress: bool,
+    pub queued_registrations: usize,
+}
+
+/// Latest published snapshot, shared between the backend and the screens.
+#[derive(Debug)]
+pub struct SnapshotPublisher {
+    sender: watch::Sender<Arc<AppStateSnapshot>>,
+    /// Whether the last snapshot kept a previous copy of some locked state
+    stale: AtomicBool,
+}
+
+impl Default for SnapshotPublisher {
+    fn default() -> Self {
+        SnapshotPublisher {
+            sender: watch::Sender::new(Arc::default()),
+            stale: AtomicBool::new(false),
+        }
+    }
+}
+
+impl AppState {
+    /// Latest published snapshot, never waits on a backend task.
+    pub fn snapshot(&self) -> Arc<AppStateSnapshot> {
+        self.snapshot.sender.borrow().clone()
+    }
+
+    /// Receiver of the snapshots published from now on, for screens showing
+    /// state that changes while they are open.
+    pub fn subscribe_snapshot(&self) -> watch::Receiver<Arc<AppStateSnapshot>> {
+        self.snapshot.sender.subscribe()
+    }
+
+    /// Copies the displayed state into a new snapshot, to be called once a
+    /// task changing the state is done and again once its event was handled.
+    pub fn publish_snapshot(&self) {
+        let previous = self.snapshot();
+        let stale = Cell::new(false);
+        let known_contracts = copy_or_previous(
+            &stale,
+            &self.known_contracts,
+            &previous.known_contracts,
+            Clone::clone,
+        );
+        let supporting_contracts = copy_or_previous(
+            &stale,
+            &self.supporting_contracts,
+            &previous.supporting_contracts,
+            Clone::clone,
+        );
+        let known_contract_names = known_contracts
+            .iter()
+            .chain(supporting_contracts.iter())
+            .filter(|(name, contract)| **name != contract.id().to_string(Encoding::Base58))
+            .map(|(name, contract)| (contract.id(), name.clone()))
+            .collect();
+
+        let snapshot = AppStateSnapshot {
+            loaded_identity: copy_or_previous(
+                &stale,
+                &self.loaded_identity,
+                &previous.loaded_identity,
+                Clone::clone,
+            ),
+            loaded_wallet: copy_or_previous(
+                &stale,
+                &self.loaded_wallet,
+                &previous.loaded_wallet,
+                Clone::clone,
+            ),
+            sweep_wallet_address: copy_or_previous(
+                &stale,
+                &self.sweep_wallet,
+                &previous.sweep_wallet_address,
+                |wallet| {
+                    wallet
+                        .as_ref()
+                        .map(|wallet| wallet.receive_address().to_string())
+                },
+            ),
+            loaded_wallet_name: copy_or_previous(
+                &stale,
+                &self.loaded_wallet_name,
+                &previous.loaded_wallet_name,
+                Clone::clone,
+            ),
+            stored_wallets: copy_or_previous(
+                &stale,
+                &self.stored_wallets,
+                &previous.stored_wallets,
+                Clone::clone,
+            ),
+            known_identities: copy_or_previous(
+                &stale,
+                &self.known_identities,
+                &previous.known_identities,
+                Clone::clone,
+            ),
+            known_identities_names: copy_or_previous(
+                &stale,
+                &self.known_identities_names,
+                &previous.known_identities_names,
+                Clone::clone,
+            ),
+            private_key_ids: copy_or_previous(
+                &stale,
+                &self.known_identities_private_keys,
+                &previous.private_key_ids,
+                |private_keys| private_keys.keys().copied().collect(),
+            ),
+            known_contracts,
+            supporting_contracts,
+            strategy_signing_identities: copy_or_previous(
+                &stale,
+                &self.strategy_signing_identities,
+                &previous.strategy_signing_identities,
+                Clone::clone,
+            ),
+            run_schedules: copy_or_previous(
+                &stale,
+                &self.run_schedules,
+                &previous.run_schedules,
+                Clone::clone,
+            ),
+            run_history: copy_or_previous(
+                &stale,
+                &self.run_history,
+                &previous.run_history,
+                Clone::clone,
+            ),
+            known_contract_names,
+            available_strategies: copy_or_previous(
+                &stale,
+                &self.available_strategies,
+                &previous.available_strategies,
+                Clone::clone,
+            ),
+            strategy_contract_names: copy_or_previous(
+                &stale,
+                &self.available_strategies_contract_names,
+                &previous.strategy_contract_names,
+                Clone::clone,
+            ),
+            selected_strategy: copy_or_previous(
+                &stale,
+                &self.selected_strategy,
+                &previous.selected_strategy,
+                Clone::clone,
+            ),
+            strategy_frequency_profiles: copy_or_previous(
+                &stale,
+                &self.strategy_frequency_profiles,
+                &previous.strategy_frequency_profiles,
+                Clone::clone,
+            ),
+            strategy_contract_aliases: copy_or_previous(
+                &stale,
+                &self.strategy_contract_aliases,
+                &previous.strategy_contract_aliases,
+                Clone::clone,
+            ),
+            strategy_key_maintenance: copy_or_previous(
+                &stale,
+                &self.strategy_key_maintenance,
+                &previous.strategy_key_maintenance,
+                Clone::clone,
+            ),
+            strategy_contract_weights: copy_or_previous(
+                &stale,
+                &self.strategy_contract_weights,
+                &previous.strategy_contract_weights,
+                Clone::clone,
+            ),
+            strategy_document_generators: copy_or_previous(
+                &stale,
+                &self.strategy_document_generators,
+                &previous.strategy_document_generators,
+                Clone::clone,
+            ),
+            strategy_retry_policies: copy_or_previous(
+                &stale,
+                &self.strategy_retry_policies,
+                &previous.strategy_retry_policies,
+                Clone::clone,
+            ),
+            strategy_run_defaults: copy_or_previous(
+                &stale,
+                &self.strategy_run_defaults,
+                &previous.strategy_run_defaults,
+                Clone::clone,
+            ),
+            identity_registration_in_progress: copy_or_previous(
+                &stale,
+                &self.identity_asset_lock_private_key_in_creation,
+                &previous.identity_registration_in_progress,
+                Option::is_some,
+            ),
+            identity_top_up_in_progress: copy_or_previous(
+                &stale,
+                &self.identity_asset_lock_private_key_in_top_up,
+                &previous.identity_top_up_in_progress,
+                Option::is_some,
+            ),
+            queued_registrations: copy_or_previous(
+                &stale,
+                &self.identity_registration_queue,
+                &previous.queued_registrations,
+                Vec::len,
+            ),
+        };
+        self.snapshot.sender.send_replace(Arc::new(snapshot));
+        self.snapshot.stale.store(stale.get(), Ordering::SeqCst);
+    }
+
+    /// Whether the latest snapshot kept a previous copy of state that was
+    /// locked, it should be published again once the lock is released.
+    pub fn snapshot_stale(&self) -> bool {
+        self.snapshot.stale.load(Ordering::SeqCst)
+    }
+}
+
+/// Copy of the state behind `mutex`, or its `previous` copy while the lock is
+/// held, in which case `stale` is set.
+fn copy_or_previous<T, U: Clone>(
+    stale: &Cell<bool>,
+    mutex: &Mutex<T>,
+    previous: &U,
+    copy: impl FnOnce(&T) -> U,
+) -> U {
+    match mutex.try_lock() {
+        Ok(state) => copy(&state),
+        Err(_) => {
+            stale.set(true);
+            previous.clone()
+        }
+    }
+}
@@ -2,12 +2,14 @@
 //! This kind of state does not include UI details and basically all about
 //! persistence required by backend.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
-use std::{collections::BTreeMap, fs};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+};
 
 use bincode::{Decode, Encode};
-use dash_sdk::dashcore_rpc::Client;
 use dpp::{
     dashcore::{
         psbt::serialize::{Deserialize, Serialize},
@@ -26,13 +28,33 @@ use dpp::{
 };
 use drive::drive::Drive;
 use strategy_tests::Strategy;
-use tokio::sync::Mutex;
+use tokio::sync::{watch, Mutex};
 use walkdir::{DirEntry, WalkDir};
 
-use super::wallet::{add_wallet_by_private_key_as_string, Wallet};
-use crate::{backend::insight::InsightAPIClient, config::Config};
+use super::{
+    activity_log::ActivityLog,
+    contract_weights::ContractWeights,
+    document_generators::DocumentGenerators,
+    frequency_profile::FrequencyProfile,
+    key_maintenance::KeyMaintenanceOp,
+    retry_policy::RetryPolicy,
+    run_chain::ChainCarry,
+    run_defaults::RunDefaults,
+    run_history::RunHistory,
+    run_schedule::RunSchedules,
+    snapshot::SnapshotPublisher,
+    strategy_runs::StrategyRuns,
+    transition_inspector::TransitionBuffer,
+    wallet::{add_wallet_by_private_key_as_string, load_wallet, Wallet},
+};
+use crate::{
+    backend::clients::{CoreApi, InsightApi},
+    config::Config,
+};
 
 const CURRENT_PROTOCOL_VERSION: ProtocolVersion = 1;
+/// Local Drive the state is kept along with
+const DRIVE_PATH: &str = "explorer.drive";
 
 const _USE_LOCAL: bool = false;
 
@@ -42,8 +64,20 @@ pub(super) type StrategiesMap = BTreeMap<String, Strategy>;
 pub(crate) type StrategyContractNames =
     Vec<(ContractFileName, Option<BTreeMap<u64, ContractFileName>>)>;
 pub(super) type KnownContractsMap = BTreeMap<String, DataContract>;
+/// <Contract ID, Alias>
+pub(crate) type ContractAliases = BTreeMap<Identifier, String>;
 /// <(Known identity ID, Public key ID), Private key bytes>
 pub type IdentityPrivateKeysMap = BTreeMap<(Identifier, KeyID), Vec<u8>>;
+/// Asset lock transaction, its one time private key, the asset lock proof once
+/// retrieved and the identity with its private keys once created
+pub(crate) type IdentityRegistration = (
+    Transaction,
+    PrivateKey,
+    Option<AssetLockProof>,
+    Option<(Identity, BTreeMap<IdentityPublicKey, Vec<u8>>)>,
+);
+/// A failed registration waiting to be retried and the retries made so far
+pub(crate) type QueuedRegistration = (IdentityRegistration, u32);
 
 // TODO: each state part should be in it's own mutex in case multiple backend
 // tasks are executed on different state parts,
@@ -54,6 +88,14 @@ pub struct AppState {
     pub loaded_identity: Mutex<Option<Identity>>,
     pub loaded_identity_pro_tx_hash: Mutex<Option<Identifier>>,
     pub loaded_wallet: Mutex<Option<Wallet>>,
+    /// Wallet receiving funds withdrawn at the end of strategy runs, when
+    /// selected for the run, so they stay apart from the funding wallet
+    pub sweep_wallet: Mutex<Option<Wallet>>,
+    /// Name of the loaded wallet in the wallet manager, `None` until it's
+    /// given one
+    pub loaded_wallet_name: Mutex<Option<String>>,
+    /// Wallets of the wallet manager other than the loaded one, by name
+    pub stored_wallets: Mutex<BTreeMap<String, Wallet>>,
     pub drive: Mutex<Drive>,
     pub known_identities: Mutex<BTreeMap<Identifier, Identity>>,
     pub known_identities_private_keys: Mutex<IdentityPrivateKeysMap>,
@@ -67,21 +109,69 @@ pub struct AppState {
     /// field serves as a double of strategies' `start_contracts`,
     /// but using file names
     pub available_strategies_contract_names: Mutex<BTreeMap<String, StrategyContractNames>>,
+    /// Hour of day frequency profiles of strategies, kept aside as
+    /// [Strategy] has no place for them
+    pub strategy_frequency_profiles: Mutex<BTreeMap<String, FrequencyProfile>>,
+    /// Timeouts and retries strategies last ran with, see [RetryPolicy]
+    pub strategy_retry_policies: Mutex<BTreeMap<String, RetryPolicy>>,
+    /// Run form parameters strategies were last started with, see [RunDefaults]
+    pub strategy_run_defaults: Mutex<BTreeMap<String, RunDefaults>>,
+    /// Human-friendly names of contracts used by strategies, by strategy name
+    /// and then by the ID of the loaded contract
+    pub strategy_contract_aliases: Mutex<BTreeMap<String, ContractAliases>>,
+    /// Identity key maintenance operations of strategies, kept aside like the
+    /// frequency profiles
+    pub strategy_key_maintenance: Mutex<BTreeMap<String, Vec<KeyMaintenanceOp>>>,
+    /// Shares of the document load given to the contracts of strategies, see
+    /// [ContractWeights]
+    pub strategy_contract_weights: Mutex<BTreeMap<String, ContractWeights>>,
+    /// Generators of the documents inserted by strategies, see
+    /// [DocumentGenerators]
+    pub strategy_document_generators: Mutex<BTreeMap<String, DocumentGenerators>>,
+    /// Known identities whose private keys sign strategy runs along with the
+    /// loaded identity's
+    pub strategy_signing_identities: Mutex<BTreeSet<Identifier>>,
+    /// Strategy runs started at a set time and the outcome of past ones
+    pub run_schedules: Mutex<RunSchedules>,
+    /// Completed strategy runs, for comparisons between them
+    pub run_history: Mutex<RunHistory>,
     pub selected_strategy: Mutex<Option<String>>,
-    pub identity_asset_lock_private_key_in_creation: Mutex<
-        Option<(
-            Transaction,
-            PrivateKey,
-            Option<AssetLockProof>,
-            Option<(Identity, BTreeMap<IdentityPublicKey, Vec<u8>>)>,
-        )>,
-    >,
+    pub identity_asset_lock_private_key_in_creation: Mutex<Option<IdentityRegistration>>,
+    /// Failed registrations kept to be retried, so funds locked for them are
+    /// not lost
+    pub identity_registration_queue: Mutex<Vec<QueuedRegistration>>,
     pub identity_asset_lock_private_key_in_top_up:
         Mutex<Option<(Transaction, PrivateKey, Option<AssetLockProof>)>>,
+    /// Copy of the displayed state published for screens, not persisted
+    pub(super) snapshot: SnapshotPublisher,
+    /// Strategy runs going on, not persisted
+    pub(super) strategy_runs: StrategyRuns,
+    /// Public key hashes of the identity master keys handed to registrations,
+    /// so no two take the same wallet identity index, not persisted
+    pub(super) reserved_identity_keys: Mutex<BTreeSet<[u8; 20]>>,
+    /// Set to ask a deletion of own documents to stop, see
+    /// [AppState::cancel_document_deletion]
+    pub(super) deletion_cancel: watch::Sender<bool>,
+    /// Identities handed from a run of a chain to the next one, by the
+    /// strategy of the run they're handed to, not persisted
+    pub(super) chain_carry: Mutex<BTreeMap<String, ChainCarry>>,
+    /// Run reports that couldn't be written to their file, lost on exit
+    pub(super) unsaved_reports: Mutex<Vec<String>>,
+    /// Tasks executed in this session, not persisted
+    pub(super) activity_log: ActivityLog,
+    /// Latest generated and failed transitions of strategy runs, not persisted
+    pub(super) transition_buffer: TransitionBuffer,
 }
 
 impl Default for AppState {
     fn default() -> Self {
+        AppState::with_drive_path(DRIVE_PATH)
+    }
+}
+
+impl AppState {
+    /// Empty state with its local Drive at `drive_path`.
+    pub(crate) fn with_drive_path(drive_path: impl AsRef<Path>) -> Self {
         let platform_version = PlatformVersion::get(CURRENT_PROTOCOL_VERSION).unwrap();
 
         // Helper function to check if a file is a JSON file
@@ -107,9 +197,8 @@ impl Default for AppState {
             }
         }
 
-        let (drive, _protocol_version) =
-            Drive::open("explorer.drive", None, Some(platform_version))
-                .expect("expected to open Drive successfully");
+        let (drive, _protocol_version) = Drive::open(drive_path, None, Some(platform_version))
+            .expect("expected to open Drive successfully");
 
         if drive
             .grove
@@ -126,11 +215,15 @@ impl Default for AppState {
                 .expect("expected to create root tree successfully");
         }
 
+        let strategy_runs = StrategyRuns::default();
         AppState {
             loaded_identity: None.into(),
             loaded_identity_pro_tx_hash: None.into(),
             known_identities_private_keys: Default::default(),
             loaded_wallet: Mutex::new(None),
+            sweep_wallet: Mutex::new(None),
+            loaded_wallet_name: Mutex::new(None),
+            stored_wallets: BTreeMap::new().into(),
             drive: Mutex::from(drive),
             known_contracts: BTreeMap::new().into(),
             supporting_contracts: supporting_contracts_raw.into(),
@@ -139,8 +232,27 @@ impl Default for AppState {
             available_strategies: BTreeMap::new().into(),
             selected_strategy: None.into(),
             identity_asset_lock_private_key_in_creation: None.into(),
+            identity_registration_queue: Vec::new().into(),
             identity_asset_lock_private_key_in_top_up: None.into(),
             available_strategies_contract_names: BTreeMap::new().into(),
+            strategy_frequency_profiles: BTreeMap::new().into(),
+            strategy_retry_policies: BTreeMap::new().into(),
+            strategy_run_defaults: BTreeMap::new().into(),
+            strategy_contract_aliases: BTreeMap::new().into(),
+            strategy_key_maintenance: BTreeMap::new().into(),
+            strategy_contract_weights: BTreeMap::new().into(),
+            strategy_document_generators: BTreeMap::new().into(),
+            strategy_signing_identities: BTreeSet::new().into(),
+            run_schedules: RunSchedules::default().into(),
+            run_history: RunHistory::default().into(),
+            snapshot: SnapshotPublisher::default(),
+            activity_log: ActivityLog::new(strategy_runs.clone()),
+            strategy_runs,
+            reserved_identity_keys: Mutex::new(BTreeSet::new()),
+            deletion_cancel: watch::Sender::new(false),
+            chain_carry: Mutex::new(BTreeMap::new()),
+            unsaved_reports: Mutex::new(Vec::new()),
+            transition_buffer: TransitionBuffer::default(),
         }
     }
 }
@@ -151,6 +263,9 @@ struct AppStateInSerializationFormat {
     pub loaded_identity_pro_tx_hash: Option<Identifier>,
     pub identity_private_keys: IdentityPrivateKeysMap,
     pub loaded_wallet: Option<Wallet>,
+    pub sweep_wallet: Option<Wallet>,
+    pub loaded_wallet_name: Option<String>,
+    pub stored_wallets: BTreeMap<String, Wallet>,
     pub known_identities: BTreeMap<Identifier, Identity>,
     pub known_identities_names: BTreeMap<Identifier, Vec<String>>,
     pub known_contracts: BTreeMap<String, Vec<u8>>,
@@ -158,6 +273,16 @@ struct AppStateInSerializationFormat {
     pub available_strategies: BTreeMap<String, Vec<u8>>,
     pub available_strategies_contract_names:
         BTreeMap<String, Vec<(ContractFileName, Option<BTreeMap<u64, ContractFileName>>)>>,
+    pub strategy_frequency_profiles: BTreeMap<String, FrequencyProfile>,
+    pub strategy_retry_policies: BTreeMap<String, RetryPolicy>,
+    pub strategy_run_defaults: BTreeMap<String, RunDefaults>,
+    pub strategy_contract_aliases: BTreeMap<String, ContractAliases>,
+    pub strategy_key_maintenance: BTreeMap<String, Vec<KeyMaintenanceOp>>,
+    pub strategy_contract_weights: BTreeMap<String, ContractWeights>,
+    pub strategy_document_generators: BTreeMap<String, DocumentGenerators>,
+    pub strategy_signing_identities: BTreeSet<Identifier>,
+    pub run_schedules: RunSchedules,
+    pub run_history: RunHistory,
     pub selected_strategy: Option<String>,
     pub identity_asset_lock_private_key_in_creation: Option<(
         Vec<u8>,
@@ -165,6 +290,13 @@ struct AppStateInSerializationFormat {
         Option<AssetLockProof>,
         Option<(Identity, BTreeMap<IdentityPublicKey, Vec<u8>>)>,
     )>,
+    pub identity_registration_queue: Vec<(
+        Vec<u8>,
+        [u8; 32],
+        Option<AssetLockProof>,
+        Option<(Identity, BTreeMap<IdentityPublicKey, Vec<u8>>)>,
+        u32,
+    )>,
     pub identity_asset_lock_private_key_in_top_up:
         Option<(Vec<u8>, [u8; 32], Option<AssetLockProof>)>,
 }
@@ -188,6 +320,9 @@ impl PlatformSerializableWithPlatformVersion for AppState {
             loaded_identity_pro_tx_hash,
             known_identities_private_keys: identity_private_keys,
             loaded_wallet,
+            sweep_wallet,
+            loaded_wallet_name,
+            stored_wallets,
             drive,
             known_identities,
             known_identities_names,
@@ -196,8 +331,27 @@ impl PlatformSerializableWithPlatformVersion for AppState {
             available_strategies,
             selected_strategy,
             identity_asset_lock_private_key_in_creation,
+            identity_registration_queue,
             available_strategies_contract_names,
+            strategy_frequency_profiles,
+            strategy_retry_policies,
+            strategy_run_defaults,
+            strategy_contract_aliases,
+            strategy_key_maintenance,
+            strategy_contract_weights,
+            strategy_document_generators,
+            strategy_signing_identities,
+            run_schedules,
+            run_history,
             identity_asset_lock_private_key_in_top_up,
+            snapshot: _,
+            strategy_runs: _,
+            reserved_identity_keys: _,
+            deletion_cancel: _,
+            chain_carry: _,
+            unsaved_reports: _,
+            activity_log: _,
+            transition_buffer: _,
         } = self;
 
         let known_contracts_in_serialization_format = known_contracts
@@ -245,6 +399,22 @@ impl PlatformSerializableWithPlatformVersion for AppState {
                     },
                 );
 
+        let identity_registration_queue = identity_registration_queue
+            .blocking_lock()
+            .iter()
+            .map(
+                |((transaction, private_key, asset_lock_proof, identity_info), retries)| {
+                    (
+                        transaction.serialize(),
+                        private_key.inner.secret_bytes(),
+                        asset_lock_proof.clone(),
+                        identity_info.clone(),
+                        *retries,
+                    )
+                },
+            )
+            .collect();
+
         let identity_asset_lock_private_key_in_top_up = identity_asset_lock_private_key_in_top_up
             .blocking_lock()
             .as_ref()
@@ -261,6 +431,9 @@ impl PlatformSerializableWithPlatformVersion for AppState {
             loaded_identity_pro_tx_hash: loaded_identity_pro_tx_hash.blocking_lock().clone(),
             identity_private_keys: identity_private_keys.blocking_lock().clone(),
             loaded_wallet: loaded_wallet.blocking_lock().clone(),
+            sweep_wallet: sweep_wallet.blocking_lock().clone(),
+            loaded_wallet_name: loaded_wallet_name.blocking_lock().clone(),
+            stored_wallets: stored_wallets.blocking_lock().clone(),
             known_identities: known_identities.blocking_lock().clone(),
             known_identities_names: known_identities_names.blocking_lock().clone(),
             known_contracts: known_contracts_in_serialization_format,
@@ -270,7 +443,18 @@ impl PlatformSerializableWithPlatformVersion for AppState {
             available_strategies_contract_names: available_strategies_contract_names
                 .blocking_lock()
                 .clone(),
+            strategy_frequency_profiles: strategy_frequency_profiles.blocking_lock().clone(),
+            strategy_retry_policies: strategy_retry_policies.blocking_lock().clone(),
+            strategy_run_defaults: strategy_run_defaults.blocking_lock().clone(),
+            strategy_contract_aliases: strategy_contract_aliases.blocking_lock().clone(),
+            strategy_key_maintenance: strategy_key_maintenance.blocking_lock().clone(),
+            strategy_contract_weights: strategy_contract_weights.blocking_lock().clone(),
+            strategy_document_generators: strategy_document_generators.blocking_lock().clone(),
+            strategy_signing_identities: strategy_signing_identities.blocking_lock().clone(),
+            run_schedules: run_schedules.blocking_lock().clone(),
+            run_history: run_history.blocking_lock().clone(),
             identity_asset_lock_private_key_in_creation,
+            identity_registration_queue,
             identity_asset_lock_private_key_in_top_up,
         };
 
@@ -308,6 +492,9 @@ impl PlatformDeserializableWithPotentialValidationFromVersionedStructure for App
             loaded_identity_pro_tx_hash,
             identity_private_keys,
             loaded_wallet,
+            sweep_wallet,
+            loaded_wallet_name,
+            stored_wallets,
             known_identities,
             known_identities_names,
             known_contracts,
@@ -315,7 +502,18 @@ impl PlatformDeserializableWithPotentialValidationFromVersionedStructure for App
             available_strategies,
             selected_strategy,
             available_strategies_contract_names,
+            strategy_frequency_profiles,
+            strategy_retry_policies,
+            strategy_run_defaults,
+            strategy_contract_aliases,
+            strategy_key_maintenance,
+            strategy_contract_weights,
+            strategy_document_generators,
+            strategy_signing_identities,
+            run_schedules,
+            run_history,
             identity_asset_lock_private_key_in_creation,
+            identity_registration_queue,
             identity_asset_lock_private_key_in_top_up,
         } = app_state;
 
@@ -389,6 +587,25 @@ impl PlatformDeserializableWithPotentialValidationFromVersionedStructure for App
                 },
             );
 
+        let identity_registration_queue = identity_registration_queue
+            .into_iter()
+            .map(
+                |(transaction, private_key, asset_lock_proof, identity_info, retries)| {
+                    (
+                        (
+                            Transaction::deserialize(&transaction)
+                                .expect("expected to deserialize transaction"),
+                            PrivateKey::from_slice(&private_key, network)
+                                .expect("expected private key"),
+                            asset_lock_proof,
+                            identity_info,
+                        ),
+                        retries,
+                    )
+                },
+            )
+            .collect::<Vec<_>>();
+
         let identity_asset_lock_private_key_in_top_up = identity_asset_lock_private_key_in_top_up
             .map(|(transaction, private_key, asset_lock_proof)| {
                 (
@@ -399,9 +616,8 @@ impl PlatformDeserializableWithPotentialValidationFromVersionedStructure for App
                 )
             });
 
-        let (drive, _protocol_version) =
-            Drive::open("explorer.drive", None, Some(platform_version))
-                .expect("expected to open Drive successfully");
+        let (drive, _protocol_version) = Drive::open(DRIVE_PATH, None, Some(platform_version))
+            .expect("expected to open Drive successfully");
 
         // Deserialize the wallet state and wrap it in Arc<Mutex<_>>
         let deserialized_wallet_state = loaded_wallet
@@ -411,11 +627,15 @@ impl PlatformDeserializableWithPotentialValidationFromVersionedStructure for App
                 Mutex::new(None)
             });
 
+        let strategy_runs = StrategyRuns::default();
         Ok(AppState {
             loaded_identity: loaded_identity.into(),
             loaded_identity_pro_tx_hash: loaded_identity_pro_tx_hash.into(),
             known_identities_private_keys: identity_private_keys.into(),
             loaded_wallet: deserialized_wallet_state,
+            sweep_wallet: sweep_wallet.into(),
+            loaded_wallet_name: loaded_wallet_name.into(),
+            stored_wallets: stored_wallets.into(),
             drive: drive.into(),
             known_identities: known_identities.into(),
             known_identities_names: known_identities_names.into(),
@@ -424,18 +644,37 @@ impl PlatformDeserializableWithPotentialValidationFromVersionedStructure for App
             available_strategies: available_strategies.into(),
             selected_strategy: selected_strategy.into(),
             available_strategies_contract_names: available_strategies_contract_names.into(),
+            strategy_frequency_profiles: strategy_frequency_profiles.into(),
+            strategy_retry_policies: strategy_retry_policies.into(),
+            strategy_run_defaults: strategy_run_defaults.into(),
+            strategy_contract_aliases: strategy_contract_aliases.into(),
+            strategy_key_maintenance: strategy_key_maintenance.into(),
+            strategy_contract_weights: strategy_contract_weights.into(),
+            strategy_document_generators: strategy_document_generators.into(),
+            strategy_signing_identities: strategy_signing_identities.into(),
+            run_schedules: run_schedules.into(),
+            run_history: run_history.into(),
             identity_asset_lock_private_key_in_creation:
                 identity_asset_lock_private_key_in_creation.into(),
+            identity_registration_queue: identity_registration_queue.into(),
             identity_asset_lock_private_key_in_top_up: identity_asset_lock_private_key_in_top_up
                 .into(),
+            snapshot: SnapshotPublisher::default(),
+            activity_log: ActivityLog::new(strategy_runs.clone()),
+            strategy_runs,
+            reserved_identity_keys: Mutex::new(BTreeSet::new()),
+            deletion_cancel: watch::Sender::new(false),
+            chain_carry: Mutex::new(BTreeMap::new()),
+            unsaved_reports: Mutex::new(Vec::new()),
+            transition_buffer: TransitionBuffer::default(),
         })
     }
 }
 
 impl AppState {
     pub async fn load(
-        insight: &InsightAPIClient,
-        core_client: &Client,
+        insight: &impl InsightApi,
+        core_client: &impl CoreApi,
         config: &Config,
     ) -> AppState {
         let path = config.state_file_path();
@@ -489,11 +728,28 @@ impl AppState {
             return state;
         };
 
-        // Load wallet by private key, overriding the state file
+        // Load wallet by private key, overriding the state file, the wallet it
+        // replaces is kept in the wallet manager
         if let Some(private_key) = &config.wallet_private_key {
-            let wallet_state = &app_state.loaded_wallet;
-            add_wallet_by_private_key_as_string(&wallet_state, private_key, insight, core_client)
-                .await;
+            let configured_wallet = Mutex::new(None);
+            add_wallet_by_private_key_as_string(
+                &configured_wallet,
+                private_key,
+                insight,
+                core_client,
+            )
+            .await;
+            if let Some(wallet) = configured_wallet.into_inner() {
+                let mut loaded_wallet = app_state.loaded_wallet.lock().await;
+                if loaded_wallet.as_ref().map(Wallet::receive_address)
+                    == Some(wallet.receive_address())
+                {
+                    *loaded_wallet = Some(wallet);
+                } else {
+                    drop(loaded_wallet);
+                    load_wallet(&app_state, None, wallet).await;
+                }
+            }
         }
 
         // Load supporting contracts
@@ -520,6 +776,25 @@ impl AppState {
         app_state
     }
 
+    /// What quitting now would lose: a strategy run going on, a task still
+    /// in flight or run reports that couldn't be saved. Empty if nothing.
+    pub async fn exit_warnings(&self, backend_idle: bool) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if self.strategy_runs.any_running() {
+            warnings.push(
+                "A strategy run is going on, its report and the withdrawals from the identities \
+                 it created would be lost"
+                    .to_owned(),
+            );
+        } else if !backend_idle {
+            warnings.push("A task is still in flight and would be cut short".to_owned());
+        }
+        for report in self.unsaved_reports.lock().await.iter() {
+            warnings.push(format!("Unsaved {}", report));
+        }
+        warnings
+    }
+
     /// Used in backend destructor, must not panic
     pub fn save(&self, config: &Config) {
         let platform_version = PlatformVersion::get(CURRENT_PROTOCOL_VERSION).unwrap();
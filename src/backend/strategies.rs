@@ -1,12 +1,14 @@
 //! Strategies management backend module.
 
 use std::{
-    cmp::min,
     collections::{BTreeMap, BTreeSet, VecDeque},
-    fs::File,
+    fmt::{self, Display},
+    fs::{self, File},
     io::Write,
+    path::Path,
+    str::FromStr,
     sync::{
-        atomic::{AtomicU64, AtomicUsize, Ordering},
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
         Arc,
     },
     time::{Duration, Instant, SystemTime, UNIX_EPOCH},
@@ -22,8 +24,8 @@ use dapi_grpc::{
         GetEpochsInfoRequest,
     },
     tonic::Code,
+    Message,
 };
-use dash_sdk::dashcore_rpc::Client;
 use dash_sdk::platform::transition::withdraw_from_identity::WithdrawFromIdentity;
 use dash_sdk::{
     platform::{transition::broadcast_request::BroadcastRequestForStateTransition, Fetch},
@@ -32,20 +34,25 @@ use dash_sdk::{
 use dashmap::DashMap;
 use dpp::{
     block::{block_info::BlockInfo, epoch::Epoch},
-    dashcore::{Address, PrivateKey, Transaction},
+    dashcore::{secp256k1::SecretKey, Address, PrivateKey, Transaction},
     data_contract::{
         accessors::v0::{DataContractV0Getters, DataContractV0Setters},
         created_data_contract::CreatedDataContract,
-        document_type::random_document::{DocumentFieldFillSize, DocumentFieldFillType},
+        document_type::{
+            accessors::DocumentTypeV0Getters,
+            random_document::{DocumentFieldFillSize, DocumentFieldFillType},
+        },
         DataContract,
     },
     data_contracts::{dashpay_contract, dpns_contract},
-    document::Document,
+    document::{Document, DocumentV0Getters, DocumentV0Setters},
     identity::{
-        accessors::IdentityGettersV0, state_transition::asset_lock_proof::AssetLockProof, Identity,
-        KeyType, PartialIdentity, Purpose, SecurityLevel,
+        accessors::IdentityGettersV0,
+        identity_public_key::accessors::v0::IdentityPublicKeyGettersV0,
+        state_transition::asset_lock_proof::AssetLockProof, Identity, KeyID, KeyType,
+        PartialIdentity, Purpose, SecurityLevel,
     },
-    platform_value::{string_encoding::Encoding, Identifier},
+    platform_value::{string_encoding::Encoding, Identifier, Value},
     serialization::{
         PlatformDeserializableWithPotentialValidationFromVersionedStructure,
         PlatformSerializableWithPlatformVersion,
@@ -55,7 +62,8 @@ use dpp::{
             batched_transition::document_transition::DocumentTransition,
             document_base_transition::v0::v0_methods::DocumentBaseTransitionV0Methods,
             document_create_transition::v0::DocumentFromCreateTransitionV0, BatchTransition,
-            DocumentCreateTransition, DocumentDeleteTransition,
+            DocumentCreateTransition, DocumentDeleteTransition, DocumentReplaceTransition,
+            DocumentTransferTransition,
         },
         data_contract_create_transition::accessors::DataContractCreateTransitionAccessorsV0,
         identity_topup_transition::{
@@ -63,6 +71,7 @@ use dpp::{
         },
         StateTransition, StateTransitionLike,
     },
+    version::PlatformVersion,
 };
 use dpp::{data_contracts::withdrawals_contract, fee::Credits};
 use drive::{
@@ -72,14 +81,15 @@ use drive::{
         Drive,
     },
     error::proof::ProofError,
-    query::DriveDocumentQuery,
+    query::{DriveDocumentQuery, WhereClause, WhereOperator},
     util::object_size_info::{DocumentInfo, OwnedDocumentInfo},
 };
 use futures::{future::join_all, stream::FuturesUnordered, FutureExt};
 use hdrhistogram::Histogram;
 use itertools::Itertools;
-use rand::{rngs::StdRng, SeedableRng};
-use rs_dapi_client::{DapiRequest, DapiRequestExecutor, RequestSettings};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rs_dapi_client::{AddressList, DapiRequest, DapiRequestExecutor, RequestSettings};
+use serde::Deserialize;
 use simple_signer::signer::SimpleSigner;
 use strategy_tests::{
     frequency::Frequency,
@@ -87,20 +97,203 @@ use strategy_tests::{
     IdentityInsertInfo, LocalDocumentQuery, StartIdentities, Strategy, StrategyConfig,
 };
 use tokio::sync::{oneshot, Mutex, MutexGuard, Semaphore};
+use tokio_util::sync::CancellationToken;
 
-use crate::backend::{wallet::SingleKeyWallet, Wallet};
+use crate::backend::wallet::MAX_ASSET_LOCKS_PER_TRANSACTION;
+use crate::config::Config;
+use crate::format::{format_count, format_duration, format_rate, format_secs};
+use crate::units::{self, CREDITS_PER_DUFF};
 
 use super::{
-    insight::InsightAPIClient,
-    state::{ContractFileName, KnownContractsMap},
+    backlog_monitor::{fetch_platform_block_height, BacklogMonitor},
+    clients::{CoreApi, InsightApi},
+    connections,
+    contract_weights::{self, ContractWeights},
+    document_generators::{self, DocumentGeneratorKind},
+    document_import,
+    document_tracker::{DeletePolicy, DocumentTracker},
+    dry_run::{self, DryRunReport},
+    frequency_profile::{scale_frequency, FrequencyProfile, HOURS},
+    key_maintenance::{key_maintenance_transition, KeyMaintenanceOp},
+    key_vault,
+    load_controller::LoadController,
+    load_profile::{LoadProfile, PhaseCounters, PhaseTracker},
+    mirror::TransitionMirror,
+    operation_metrics::{OperationMetrics, WaitOutcome},
+    rate_limiter::RateLimiter,
+    rejections::{self, RejectionCounters},
+    reserve_top_up::{ReserveFunder, ReserveTopUp},
+    retry_policy::RetryPolicy,
+    run_chain::{ChainCarry, ChainLinkOutcome, ChainReport},
+    run_defaults::RunDefaults,
+    run_history::RunRecord,
+    run_progress::RunProgress,
+    run_report::{self, RunReport, Tolerances},
+    run_schedule::{self, RunSchedule, ScheduledRunRecord},
+    signing_benchmark,
+    state::{ContractAliases, ContractFileName, KnownContractsMap},
+    strategy_file::{strategy_from_file, strategy_to_file, StrategyFileFormat},
+    strategy_fixture, strategy_lint, strategy_scaling,
+    strategy_templates::StrategyTemplate,
+    traffic::{RequestCategory, Traffic},
+    transition_trace::{self, TraceRecord, TraceResult, TransitionTrace},
+    upgrade_window::{self, UpgradeWindowReport, UpgradeWindowSettings, WindowStatus},
     AppState, AppStateUpdate, BackendEvent, StrategyCompletionResult, StrategyContractNames, Task,
 };
 
+/// How often the run progress is written with the current depth of each stage
+const PROGRESS_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+/// How often a loop held off by the in-flight cap checks for room again
+const IN_FLIGHT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Broadcasts a time mode loop has in flight at once by default
+pub const DEFAULT_MAX_CONCURRENT_BROADCASTS: usize = 64;
+
+/// Key types start identities get extra keys of. Hash keys hold no public key
+/// to verify a signature against, so the run couldn't sign with them.
+pub const START_IDENTITY_KEY_TYPES: [KeyType; 2] = [KeyType::ECDSA_SECP256K1, KeyType::BLS12_381];
+
+/// How a strategy run broadcasts state transitions and waits for their
+/// results, timeouts and retries are part of the [RetryPolicy].
+#[derive(Debug, PartialEq, Clone)]
+pub struct WaitSettings {
+    /// Number of outstanding waits after which the oldest ones are given up on
+    /// and counted as unknown, `0` for no limit. Applies to time mode loops.
+    pub max_outstanding: usize,
+    /// Number of broadcasts and waits in flight at which preparing new
+    /// transitions is held off until some are done, `0` for no limit. Applies
+    /// to time mode loops.
+    pub max_in_flight: usize,
+    /// Broadcasts of a loop running at once, the others queue until one is
+    /// done, `0` for no limit. Applies to time mode loops.
+    pub max_concurrent_broadcasts: usize,
+}
+
+impl Default for WaitSettings {
+    fn default() -> Self {
+        WaitSettings {
+            max_outstanding: 0,
+            max_in_flight: 0,
+            max_concurrent_broadcasts: DEFAULT_MAX_CONCURRENT_BROADCASTS,
+        }
+    }
+}
+
+/// An identity top up made during a run after a broadcast failed on an
+/// insufficient balance.
+#[derive(Debug, Clone)]
+pub(crate) struct TopUpRecord {
+    pub(crate) identity_id: Identifier,
+    pub(crate) credits: Credits,
+    pub(crate) funding: TopUpFunding,
+    pub(crate) success: bool,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum TopUpFunding {
+    /// Asset lock transaction ID
+    AssetLock(String),
+    /// Credit transfer from the run's reserve identity
+    Reserve(Identifier),
+}
+
+impl Display for TopUpRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let funding = match &self.funding {
+            TopUpFunding::AssetLock(tx_id) => format!("asset lock {}", tx_id),
+            TopUpFunding::Reserve(id) => {
+                format!("transfer from reserve {}", id.to_string(Encoding::Base58))
+            }
+        };
+        write!(
+            f,
+            "{}: {} credits, {}, {}",
+            self.identity_id.to_string(Encoding::Base58),
+            self.credits,
+            funding,
+            if self.success { "succeeded" } else { "failed" }
+        )
+    }
+}
+
+/// Options of a single strategy run.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct RunOptions {
+    pub wait: WaitSettings,
+    /// Timeouts and retries, stored with the strategy for its next runs.
+    /// `None` to use the ones stored.
+    pub retry_policy: Option<RetryPolicy>,
+    /// Store the duration, pace, proof verification and reserve top ups of
+    /// the run for the strategy's next run form
+    pub save_defaults: bool,
+    /// Withdraw the balances of identities created by the run to the sweep
+    /// wallet instead of the loaded (funding) wallet
+    pub withdraw_to_sweep_wallet: bool,
+    /// Maximum credits the run may spend, fees and top ups included. Required
+    /// on mainnet.
+    pub spend_cap: Option<Credits>,
+    /// 95th percentile inclusion latency the run keeps under by adjusting its
+    /// load, `None` to offer the configured load as is
+    pub latency_target: Option<Duration>,
+    /// Start of the run excluded from the reported rates and wait times
+    pub warm_up: Duration,
+    /// End of the run excluded from the reported rates and wait times
+    pub cool_down: Duration,
+    /// Asset locks funded by each L1 transaction while initializing, 0 or 1
+    /// for one transaction per asset lock
+    pub asset_locks_per_transaction: usize,
+    /// DAPI address all requests of the run go to, `None` to let the Sdk
+    /// rotate between the configured ones
+    pub pinned_address: Option<String>,
+    /// Also broadcast every transition to the configured mirror network and
+    /// compare the outcomes
+    pub mirror: bool,
+    /// Write a compressed trace line for every transition of the load
+    pub trace_transitions: bool,
+    /// Top up identities running out of credits with transfers from a reserve
+    /// identity rather than with asset locks
+    pub reserve_top_up: Option<ReserveTopUp>,
+    /// Documents delete operations target
+    pub delete_policy: DeletePolicy,
+    /// Let the Sdk ban DAPI addresses failing requests for a while, so the
+    /// run avoids nodes that are down
+    pub ban_failed_addresses: bool,
+    /// Broadcasts per second the run is paced to, `None` to broadcast
+    /// transitions as soon as they are generated
+    pub target_rate: Option<f64>,
+    /// Shape of the load over the run
+    pub load_profile: LoadProfile,
+    /// Fee increase of the transitions signed by identities of the run, in
+    /// percent of the base fee, to get them prioritized
+    pub user_fee_increase: u16,
+    /// Seed of the run's random choices, so the same transitions can be
+    /// replayed against another network. `None` draws one, which is logged.
+    pub seed: Option<u64>,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum StrategyTask {
     CreateStrategy(String),
+    CreateFromTemplate {
+        strategy_name: String,
+        template: StrategyTemplate,
+    },
     ImportStrategy(String),
     ExportStrategy(String),
+    /// Writes a strategy to a TOML or JSON file, by the path's extension
+    ExportStrategyFile {
+        strategy_name: String,
+        path: String,
+    },
+    /// Reads a strategy written by [StrategyTask::ExportStrategyFile], named
+    /// after the file
+    ImportStrategyFile(String),
+    /// Writes a strategy and a test loading it as a `strategy_tests` fixture
+    ExportStrategyFixture(String),
+    /// Writes the private keys of the strategy's signer held for the loaded
+    /// identity and the identities marked to sign strategy runs, readable by
+    /// the owner only
+    ExportSignerBundle(String),
     SelectStrategy(String),
     DeleteStrategy(String),
     CloneStrategy(String),
@@ -118,6 +311,18 @@ pub enum StrategyTask {
         balance: u64,
         add_transfer_key: bool,
     },
+    AddStartIdentityKeys {
+        strategy_name: String,
+        purpose: Purpose,
+        security_level: SecurityLevel,
+        key_type: KeyType,
+        count: u8,
+    },
+    ClearStartIdentityKeys(String),
+    ImportHardCodedStartIdentities {
+        strategy_name: String,
+        path: String,
+    },
     AddHardCodedStartIdentity {
         strategy_name: String,
         identity_id_str: String,
@@ -127,8 +332,89 @@ pub enum StrategyTask {
         strategy_name: String,
         operation: Operation,
     },
-    RegisterDocsToAllContracts(String, u16, DocumentFieldFillSize, DocumentFieldFillType),
-    RunStrategy(String, u64, u64, bool, u64),
+    /// Identity key maintenance operation, run alongside the strategy's own
+    AddKeyMaintenanceOperation {
+        strategy_name: String,
+        operation: KeyMaintenanceOp,
+    },
+    SetFrequencyProfile(String, Option<FrequencyProfile>),
+    /// Shares of the document load given to the strategy's contracts, empty
+    /// weights remove them
+    SetContractWeights(String, ContractWeights),
+    /// Generator of the documents inserted by the strategy for a contract, by
+    /// its Base58 ID, and document type. The random generator removes it
+    SetDocumentGenerator {
+        strategy_name: String,
+        contract_id: String,
+        document_type: String,
+        generator: DocumentGeneratorKind,
+    },
+    /// Scales the start identities, identity inserts and top ups of the
+    /// strategy down to the asset locks the loaded wallet can fund for a run
+    /// of `duration` seconds
+    ScaleToWallet {
+        strategy_name: String,
+        duration: u64,
+    },
+    /// Alias for a strategy contract by its name, an empty alias removes it
+    SetContractAlias {
+        strategy_name: String,
+        contract_name: String,
+        alias: String,
+    },
+    /// Adds document inserts to every start contract, with the values of a
+    /// payload file for the document types it has any for
+    RegisterDocsToAllContracts(
+        String,
+        u16,
+        DocumentFieldFillSize,
+        DocumentFieldFillType,
+        Option<String>,
+    ),
+    /// Runs a strategy. Runs of other strategies go on alongside it, each on
+    /// a copy of its strategy, signing with identities of its own and funding
+    /// its asset locks from UTXOs of the loaded wallet no other run takes, see
+    /// [super::strategy_runs].
+    RunStrategy(String, u64, u64, bool, u64, RunOptions),
+    /// Generates the transitions of a run without broadcasting them and
+    /// reports what the run would need
+    DryRun {
+        strategy_name: String,
+        duration: u64,
+        seconds_per_loop: u64,
+        run_options: RunOptions,
+    },
+    /// Times signing and serializing transitions with each key type on this
+    /// machine, against the rate of the last run
+    SigningBenchmark,
+    /// Waits for a protocol version upgrade and runs the strategy before and
+    /// after its estimated activation
+    RunAcrossUpgrade {
+        strategy_name: String,
+        seconds_per_loop: u64,
+        run_options: RunOptions,
+        upgrade_window: UpgradeWindowSettings,
+    },
+    /// Runs strategies one after the other, the identities created by a run
+    /// are available to the next one
+    RunChain {
+        strategy_names: Vec<String>,
+        duration: u64,
+        seconds_per_loop: u64,
+        verify_proofs: bool,
+        run_options: RunOptions,
+    },
+    /// Adds a schedule starting runs of a strategy at a set time
+    ScheduleRun(RunSchedule),
+    CancelSchedule(u64),
+    /// Runs a due schedule and records the outcome in the schedule history
+    RunScheduled(u64),
+    /// Marks a stored run report as the baseline later runs compare to
+    SetRunBaseline {
+        strategy_name: String,
+        report_file: String,
+        tolerances: Tolerances,
+    },
     RemoveLastContract(String),
     ClearContracts(String),
     ClearOperations(String),
@@ -137,14 +423,54 @@ pub enum StrategyTask {
     RemoveLastOperation(String),
 }
 
+impl StrategyTask {
+    /// Name of the strategy the task changes, a running strategy is left as
+    /// is until its run ends.
+    fn edited_strategy(&self) -> Option<&str> {
+        match self {
+            StrategyTask::DeleteStrategy(strategy_name)
+            | StrategyTask::SetStartContracts(strategy_name, _)
+            | StrategyTask::SetStartContractsRandom(strategy_name, _, _)
+            | StrategyTask::SetIdentityInserts { strategy_name, .. }
+            | StrategyTask::SetStartIdentities { strategy_name, .. }
+            | StrategyTask::AddStartIdentityKeys { strategy_name, .. }
+            | StrategyTask::ClearStartIdentityKeys(strategy_name)
+            | StrategyTask::ImportHardCodedStartIdentities { strategy_name, .. }
+            | StrategyTask::AddHardCodedStartIdentity { strategy_name, .. }
+            | StrategyTask::SetStartIdentitiesBalance(strategy_name, _)
+            | StrategyTask::AddOperation { strategy_name, .. }
+            | StrategyTask::AddKeyMaintenanceOperation { strategy_name, .. }
+            | StrategyTask::ScaleToWallet { strategy_name, .. }
+            | StrategyTask::RegisterDocsToAllContracts(strategy_name, ..)
+            | StrategyTask::RemoveLastContract(strategy_name)
+            | StrategyTask::ClearContracts(strategy_name)
+            | StrategyTask::ClearOperations(strategy_name)
+            | StrategyTask::RemoveIdentityInserts(strategy_name)
+            | StrategyTask::RemoveStartIdentities(strategy_name)
+            | StrategyTask::RemoveLastOperation(strategy_name) => Some(strategy_name),
+            _ => None,
+        }
+    }
+}
+
 impl AppState {
     pub async fn run_strategy_task<'s>(
         &'s self,
         sdk: &Sdk,
         task: StrategyTask,
-        insight: &'s InsightAPIClient,
-        core_client: &'s Client,
+        insight: &'s impl InsightApi,
+        core_client: &'s impl CoreApi,
     ) -> BackendEvent<'s> {
+        if let Some(strategy_name) = task.edited_strategy() {
+            if self.strategy_runs.is_running(strategy_name) {
+                return BackendEvent::StrategyError {
+                    error: format!(
+                        "Strategy {} is running, edit it once the run is over",
+                        strategy_name
+                    ),
+                };
+            }
+        }
         match task {
             StrategyTask::CreateStrategy(strategy_name) => {
                 let mut strategies_lock = self.available_strategies.lock().await;
@@ -165,6 +491,60 @@ impl AppState {
                     }),
                 ))
             }
+            StrategyTask::CreateFromTemplate {
+                strategy_name,
+                template,
+            } => {
+                let start_contract = match template.start_contract_name() {
+                    Some(contract_name) => {
+                        let contract = self
+                            .known_contracts
+                            .lock()
+                            .await
+                            .get(contract_name)
+                            .cloned()
+                            .or(self
+                                .supporting_contracts
+                                .lock()
+                                .await
+                                .get(contract_name)
+                                .cloned());
+                        if contract.is_none() {
+                            return BackendEvent::StrategyError {
+                                error: format!(
+                                    "Contract {} is missing from supporting_files/contract",
+                                    contract_name
+                                ),
+                            };
+                        }
+                        contract
+                    }
+                    None => None,
+                };
+                let (strategy, contract_names) = match template.build(start_contract, sdk.version())
+                {
+                    Ok(built) => built,
+                    Err(error) => return BackendEvent::StrategyError { error },
+                };
+
+                let mut strategies_lock = self.available_strategies.lock().await;
+                let mut contract_names_lock = self.available_strategies_contract_names.lock().await;
+                let mut selected_strategy_lock = self.selected_strategy.lock().await;
+
+                strategies_lock.insert(strategy_name.clone(), strategy);
+                *selected_strategy_lock = Some(strategy_name.clone());
+                contract_names_lock.insert(strategy_name.clone(), contract_names);
+
+                BackendEvent::AppStateUpdated(AppStateUpdate::SelectedStrategy(
+                    strategy_name.clone(),
+                    MutexGuard::map(strategies_lock, |strategies| {
+                        strategies.get_mut(&strategy_name).expect("strategy exists")
+                    }),
+                    MutexGuard::map(contract_names_lock, |names| {
+                        names.get_mut(&strategy_name).expect("inconsistent data")
+                    }),
+                ))
+            }
             StrategyTask::ImportStrategy(url) => {
                 match reqwest::get(&url).await {
                     Ok(response) => {
@@ -332,6 +712,215 @@ impl AppState {
                     }
                 }
             }
+            StrategyTask::ExportStrategyFixture(ref strategy_name) => {
+                let strategies_lock = self.available_strategies.lock().await;
+                let Some(strategy) = strategies_lock.get(strategy_name) else {
+                    return BackendEvent::StrategyError {
+                        error: format!("Strategy doesn't exist in app state."),
+                    };
+                };
+                match strategy_fixture::write_fixture(strategy_name, strategy, sdk.version()) {
+                    Ok(fixture) => {
+                        let mut message = format!(
+                            "Wrote the fixture {} and its test {}",
+                            fixture.strategy_path.display(),
+                            fixture.test_path.display()
+                        );
+                        if fixture.dropped_identities > 0 {
+                            message.push_str(&format!(
+                                "\n{} hard coded start identities without a registration \
+                                 transition were left out",
+                                fixture.dropped_identities
+                            ));
+                        }
+                        BackendEvent::TaskCompleted {
+                            task: Task::Strategy(task.clone()),
+                            execution_result: Ok(message.into()),
+                        }
+                    }
+                    Err(error) => BackendEvent::StrategyError { error },
+                }
+            }
+            StrategyTask::ExportStrategyFile {
+                ref strategy_name,
+                ref path,
+            } => {
+                let path = Path::new(path);
+                let format = match StrategyFileFormat::from_path(path) {
+                    Ok(format) => format,
+                    Err(error) => return BackendEvent::StrategyError { error },
+                };
+                let strategies_lock = self.available_strategies.lock().await;
+                let Some(strategy) = strategies_lock.get(strategy_name) else {
+                    return BackendEvent::StrategyError {
+                        error: format!("Strategy doesn't exist in app state."),
+                    };
+                };
+                let contract_names = self
+                    .available_strategies_contract_names
+                    .lock()
+                    .await
+                    .get(strategy_name)
+                    .cloned()
+                    .unwrap_or_default();
+                let known_contracts = self.known_contracts.lock().await;
+                let supporting_contracts = self.supporting_contracts.lock().await;
+
+                let execution_result = strategy_to_file(
+                    strategy,
+                    &contract_names,
+                    &known_contracts,
+                    |name| {
+                        known_contracts.contains_key(name)
+                            || supporting_contracts.contains_key(name)
+                    },
+                    format,
+                )
+                .and_then(|contents| {
+                    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+                        fs::create_dir_all(dir)
+                            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+                    }
+                    fs::write(path, contents)
+                        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+                })
+                .map(|_| {
+                    format!(
+                        "Exported strategy {} as {} to {}",
+                        strategy_name,
+                        format,
+                        path.display()
+                    )
+                    .into()
+                });
+                BackendEvent::TaskCompleted {
+                    task: Task::Strategy(task.clone()),
+                    execution_result,
+                }
+            }
+            StrategyTask::ImportStrategyFile(ref path) => {
+                let path = Path::new(path);
+                let Some(strategy_name) = path.file_stem().and_then(|s| s.to_str()) else {
+                    return BackendEvent::StrategyError {
+                        error: format!("Cannot name a strategy after {}", path.display()),
+                    };
+                };
+                let strategy_name = strategy_name.to_owned();
+                let imported = StrategyFileFormat::from_path(path).and_then(|format| {
+                    let contents = fs::read_to_string(path)
+                        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+                    Ok((format, contents))
+                });
+                let (format, contents) = match imported {
+                    Ok(imported) => imported,
+                    Err(error) => return BackendEvent::StrategyError { error },
+                };
+
+                let known_contracts = self.known_contracts.lock().await;
+                let supporting_contracts = self.supporting_contracts.lock().await;
+                let known_identities = self.known_identities.lock().await;
+                let imported = strategy_from_file(
+                    &contents,
+                    format,
+                    |name| {
+                        known_contracts
+                            .get(name)
+                            .or_else(|| supporting_contracts.get(name))
+                            .cloned()
+                    },
+                    &known_identities,
+                    sdk.version(),
+                );
+                drop(known_identities);
+                drop(supporting_contracts);
+                drop(known_contracts);
+                let (strategy, contract_names) = match imported {
+                    Ok(imported) => imported,
+                    Err(error) => {
+                        return BackendEvent::StrategyError {
+                            error: format!("Failed to import {}: {}", path.display(), error),
+                        }
+                    }
+                };
+
+                let mut strategies_lock = self.available_strategies.lock().await;
+                if strategies_lock.contains_key(&strategy_name) {
+                    return BackendEvent::StrategyError {
+                        error: format!(
+                            "A strategy named {} already exists, rename the file or delete the \
+                             strategy to import it",
+                            strategy_name
+                        ),
+                    };
+                }
+                let mut contract_names_lock = self.available_strategies_contract_names.lock().await;
+                let mut selected_strategy_lock = self.selected_strategy.lock().await;
+
+                strategies_lock.insert(strategy_name.clone(), strategy);
+                *selected_strategy_lock = Some(strategy_name.clone());
+                contract_names_lock.insert(strategy_name.clone(), contract_names);
+
+                BackendEvent::AppStateUpdated(AppStateUpdate::SelectedStrategy(
+                    strategy_name.clone(),
+                    MutexGuard::map(strategies_lock, |strategies| {
+                        strategies.get_mut(&strategy_name).expect("strategy exists")
+                    }),
+                    MutexGuard::map(contract_names_lock, |names| {
+                        names.get_mut(&strategy_name).expect("inconsistent data")
+                    }),
+                ))
+            }
+            StrategyTask::ExportSignerBundle(ref strategy_name) => {
+                let strategies_lock = self.available_strategies.lock().await;
+                let Some(signer) = strategies_lock
+                    .get(strategy_name)
+                    .and_then(|strategy| strategy.signer.as_ref())
+                else {
+                    return BackendEvent::TaskCompleted {
+                        task: Task::Strategy(task),
+                        execution_result: Err(
+                            "The strategy has no signer yet, run it at least once".to_owned(),
+                        ),
+                    };
+                };
+                let loaded_identity = self.loaded_identity.lock().await.clone();
+                let known_identities_lock = self.known_identities.lock().await;
+                // Only the keys of the loaded identity and of the identities marked to sign
+                // strategy runs, not those of the identities runs created
+                let mut selected_identities = self.strategy_signing_identities.lock().await.clone();
+                selected_identities.extend(loaded_identity.as_ref().map(|identity| identity.id()));
+                let bundle = signer_bundle_json(
+                    strategy_name,
+                    signer,
+                    known_identities_lock
+                        .values()
+                        .chain(loaded_identity.as_ref())
+                        .filter(|identity| selected_identities.contains(&identity.id())),
+                );
+
+                let file_name = format!(
+                    "supporting_files/signer_bundles/{}.json",
+                    file_name_safe(strategy_name)
+                );
+                let result = std::fs::create_dir_all("supporting_files/signer_bundles")
+                    .and_then(|_| key_vault::write_private_file(Path::new(&file_name), &bundle));
+
+                BackendEvent::TaskCompleted {
+                    task: Task::Strategy(task),
+                    execution_result: result
+                        .map(|_| format!("Exported signer bundle to {}", file_name).into())
+                        .map_err(|e| format!("Failed to write signer bundle: {}", e)),
+                }
+            }
+            StrategyTask::SetRunBaseline {
+                ref strategy_name,
+                ref report_file,
+                ref tolerances,
+            } => BackendEvent::TaskCompleted {
+                execution_result: run_report::set_baseline(strategy_name, report_file, tolerances)
+                    .map(Into::into),
+                task: Task::Strategy(task),
+            },
             StrategyTask::SelectStrategy(ref strategy_name) => {
                 let mut selected_strategy_lock = self.selected_strategy.lock().await;
                 let strategies_lock = self.available_strategies.lock().await;
@@ -363,6 +952,34 @@ impl AppState {
                 if strategies_lock.contains_key(&strategy_name) {
                     strategies_lock.remove(&strategy_name);
                     contract_names_lock.remove(&strategy_name);
+                    self.strategy_frequency_profiles
+                        .lock()
+                        .await
+                        .remove(&strategy_name);
+                    self.strategy_retry_policies
+                        .lock()
+                        .await
+                        .remove(&strategy_name);
+                    self.strategy_run_defaults
+                        .lock()
+                        .await
+                        .remove(&strategy_name);
+                    self.strategy_contract_aliases
+                        .lock()
+                        .await
+                        .remove(&strategy_name);
+                    self.strategy_key_maintenance
+                        .lock()
+                        .await
+                        .remove(&strategy_name);
+                    self.strategy_contract_weights
+                        .lock()
+                        .await
+                        .remove(&strategy_name);
+                    self.strategy_document_generators
+                        .lock()
+                        .await
+                        .remove(&strategy_name);
 
                     // If the deleted strategy was the selected one, unset the selected strategy
                     if let Some(selected) = selected_strategy_lock.as_ref() {
@@ -397,6 +1014,51 @@ impl AppState {
                         strategies_lock.insert(new_strategy_name.clone(), cloned_strategy);
                         contract_names_lock.insert(new_strategy_name.clone(), cloned_display_data);
 
+                        let mut profiles_lock = self.strategy_frequency_profiles.lock().await;
+                        if let Some(profile) = profiles_lock.get(selected_strategy_name).cloned() {
+                            profiles_lock.insert(new_strategy_name.clone(), profile);
+                        }
+                        drop(profiles_lock);
+
+                        let mut policies_lock = self.strategy_retry_policies.lock().await;
+                        if let Some(policy) = policies_lock.get(selected_strategy_name).copied() {
+                            policies_lock.insert(new_strategy_name.clone(), policy);
+                        }
+                        drop(policies_lock);
+
+                        let mut defaults_lock = self.strategy_run_defaults.lock().await;
+                        if let Some(defaults) = defaults_lock.get(selected_strategy_name).cloned() {
+                            defaults_lock.insert(new_strategy_name.clone(), defaults);
+                        }
+                        drop(defaults_lock);
+
+                        let mut aliases_lock = self.strategy_contract_aliases.lock().await;
+                        if let Some(aliases) = aliases_lock.get(selected_strategy_name).cloned() {
+                            aliases_lock.insert(new_strategy_name.clone(), aliases);
+                        }
+                        drop(aliases_lock);
+
+                        let mut key_maintenance_lock = self.strategy_key_maintenance.lock().await;
+                        if let Some(ops) = key_maintenance_lock.get(selected_strategy_name).cloned()
+                        {
+                            key_maintenance_lock.insert(new_strategy_name.clone(), ops);
+                        }
+                        drop(key_maintenance_lock);
+
+                        let mut weights_lock = self.strategy_contract_weights.lock().await;
+                        if let Some(weights) = weights_lock.get(selected_strategy_name).cloned() {
+                            weights_lock.insert(new_strategy_name.clone(), weights);
+                        }
+                        drop(weights_lock);
+
+                        let mut generators_lock = self.strategy_document_generators.lock().await;
+                        if let Some(generators) =
+                            generators_lock.get(selected_strategy_name).cloned()
+                        {
+                            generators_lock.insert(new_strategy_name.clone(), generators);
+                        }
+                        drop(generators_lock);
+
                         *selected_strategy_lock = Some(new_strategy_name.clone());
 
                         BackendEvent::AppStateUpdated(AppStateUpdate::SelectedStrategy(
@@ -541,8 +1203,8 @@ impl AppState {
                 }
             }
             StrategyTask::SetStartContractsRandom(
-                strategy_name,
-                selected_contract_name,
+                ref strategy_name,
+                ref selected_contract_name,
                 variants,
             ) => {
                 // Attain state locks
@@ -551,7 +1213,7 @@ impl AppState {
                 let mut supporting_contracts_lock = self.supporting_contracts.lock().await;
                 let mut contract_names_lock = self.available_strategies_contract_names.lock().await;
 
-                if let Some(strategy) = strategies_lock.get_mut(&strategy_name) {
+                if let Some(strategy) = strategies_lock.get_mut(strategy_name) {
                     let platform_version = sdk.version();
 
                     // Function to retrieve the contract from either known_contracts or
@@ -567,7 +1229,7 @@ impl AppState {
                     let mut fake_identity_nonce = 1;
 
                     // Add the contracts to the strategy start_contracts
-                    if let Some(mut data_contract) = get_contract(&selected_contract_name) {
+                    if let Some(mut data_contract) = get_contract(selected_contract_name) {
                         data_contract.set_version(1);
 
                         match CreatedDataContract::from_contract_and_identity_nonce(
@@ -632,9 +1294,19 @@ impl AppState {
                                     })
                                     .collect();
 
+                                add_random_variant_aliases(
+                                    self.strategy_contract_aliases
+                                        .lock()
+                                        .await
+                                        .entry(strategy_name.clone())
+                                        .or_default(),
+                                    selected_contract_name,
+                                    contract_variants.iter().map(|x| x.data_contract().id()),
+                                );
+
                                 // Add the new contracts to self.available_strategies_contract_names
                                 if let Some(existing_strategy_contracts) =
-                                    contract_names_lock.get_mut(&strategy_name)
+                                    contract_names_lock.get_mut(strategy_name)
                                 {
                                     existing_strategy_contracts.extend(contract_id_strings);
                                 } else {
@@ -659,15 +1331,23 @@ impl AppState {
                         };
                     }
 
-                    BackendEvent::AppStateUpdated(AppStateUpdate::SelectedStrategy(
-                        strategy_name.clone(),
-                        MutexGuard::map(strategies_lock, |strategies| {
-                            strategies.get_mut(&strategy_name).expect("strategy exists")
-                        }),
-                        MutexGuard::map(contract_names_lock, |names| {
-                            names.get_mut(&strategy_name).expect("inconsistent data")
-                        }),
-                    ))
+                    BackendEvent::TaskCompletedStateChange {
+                        task: Task::Strategy(task.clone()),
+                        execution_result: Ok(format!(
+                            "Added {} contracts based on {}",
+                            variants, selected_contract_name
+                        )
+                        .into()),
+                        app_state_update: AppStateUpdate::SelectedStrategy(
+                            strategy_name.clone(),
+                            MutexGuard::map(strategies_lock, |strategies| {
+                                strategies.get_mut(strategy_name).expect("strategy exists")
+                            }),
+                            MutexGuard::map(contract_names_lock, |names| {
+                                names.get_mut(strategy_name).expect("inconsistent data")
+                            }),
+                        ),
+                    }
                 } else {
                     BackendEvent::StrategyError {
                         error: format!("Strategy doesn't exist in app state."),
@@ -697,59 +1377,410 @@ impl AppState {
                     }
                 }
             }
-            StrategyTask::RegisterDocsToAllContracts(
-                strategy_name,
-                num_docs,
-                fill_size,
-                fill_type,
-            ) => {
-                let mut strategies_lock = self.available_strategies.lock().await;
-                if let Some(strategy) = strategies_lock.get_mut(&strategy_name) {
-                    for contract_with_updates in &strategy.start_contracts {
-                        let contract = &contract_with_updates.0;
-                        let document_types = contract.data_contract().document_types();
-                        let document_type = document_types.values().next().expect(
-                            "Expected to get a document type in RegisterDocsToAllContracts",
-                        );
-                        let action =
-                            DocumentAction::DocumentActionInsertRandom(fill_type, fill_size);
-                        let operation = Operation {
-                            op_type: OperationType::Document(DocumentOp {
-                                contract: contract.data_contract().clone(),
-                                document_type: document_type.clone(),
-                                action,
-                            }),
-                            frequency: Frequency {
-                                times_per_block_range: num_docs..num_docs + 1,
-                                chance_per_block: None,
-                            },
-                        };
-                        strategy.operations.push(operation);
-                    }
-                    BackendEvent::AppStateUpdated(AppStateUpdate::SelectedStrategy(
+            StrategyTask::AddKeyMaintenanceOperation {
+                ref strategy_name,
+                ref operation,
+            } => {
+                if !self
+                    .available_strategies
+                    .lock()
+                    .await
+                    .contains_key(strategy_name)
+                {
+                    return BackendEvent::StrategyError {
+                        error: format!("Strategy doesn't exist in app state."),
+                    };
+                }
+
+                let mut key_maintenance_lock = self.strategy_key_maintenance.lock().await;
+                key_maintenance_lock
+                    .entry(strategy_name.clone())
+                    .or_default()
+                    .push(operation.clone());
+
+                BackendEvent::TaskCompletedStateChange {
+                    task: Task::Strategy(task.clone()),
+                    execution_result: Ok(format!("Added {}", operation).into()),
+                    app_state_update: AppStateUpdate::StrategyKeyMaintenance(
                         strategy_name.clone(),
-                        MutexGuard::map(strategies_lock, |strategies| {
-                            strategies.get_mut(&strategy_name).expect("strategy exists")
+                        MutexGuard::map(key_maintenance_lock, |ops| {
+                            ops.get_mut(strategy_name).expect("operations inserted")
                         }),
-                        MutexGuard::map(
-                            self.available_strategies_contract_names.lock().await,
-                            |names| names.get_mut(&strategy_name).expect("inconsistent data"),
-                        ),
-                    ))
-                } else {
-                    BackendEvent::StrategyError {
-                        error: format!("Strategy doesn't exist in app state."),
-                    }
+                    ),
                 }
             }
-            StrategyTask::SetIdentityInserts {
-                strategy_name,
-                identity_inserts_frequency,
-                start_balance,
-            } => {
-                let mut strategies_lock = self.available_strategies.lock().await;
-                if let Some(strategy) = strategies_lock.get_mut(&strategy_name) {
-                    strategy.identity_inserts = IdentityInsertInfo {
+            StrategyTask::SetFrequencyProfile(ref strategy_name, ref profile) => {
+                if !self
+                    .available_strategies
+                    .lock()
+                    .await
+                    .contains_key(strategy_name)
+                {
+                    return BackendEvent::StrategyError {
+                        error: format!("Strategy doesn't exist in app state."),
+                    };
+                }
+
+                let mut profiles_lock = self.strategy_frequency_profiles.lock().await;
+                let message = if let Some(profile) = profile {
+                    profiles_lock.insert(strategy_name.clone(), profile.clone());
+                    format!("Hourly frequency profile set to {}", profile)
+                } else {
+                    profiles_lock.remove(strategy_name);
+                    "Hourly frequency profile removed, operations run at a constant frequency"
+                        .to_owned()
+                };
+
+                BackendEvent::TaskCompleted {
+                    task: Task::Strategy(task),
+                    execution_result: Ok(message.into()),
+                }
+            }
+            StrategyTask::SetContractWeights(ref strategy_name, ref weights) => {
+                let Some(strategy) = self
+                    .available_strategies
+                    .lock()
+                    .await
+                    .get(strategy_name)
+                    .cloned()
+                else {
+                    return BackendEvent::StrategyError {
+                        error: format!("Strategy doesn't exist in app state."),
+                    };
+                };
+                let document_contracts: BTreeSet<String> = strategy
+                    .operations
+                    .iter()
+                    .filter_map(|operation| match &operation.op_type {
+                        OperationType::Document(op) => {
+                            Some(op.contract.id().to_string(Encoding::Base58))
+                        }
+                        _ => None,
+                    })
+                    .collect();
+                let execution_result = if let Some(unknown) = weights
+                    .keys()
+                    .find(|contract| !document_contracts.contains(*contract))
+                {
+                    Err(format!(
+                        "Contract {} has no document operations in this strategy",
+                        unknown
+                    ))
+                } else {
+                    let mut weights_lock = self.strategy_contract_weights.lock().await;
+                    if weights.is_empty() {
+                        weights_lock.remove(strategy_name);
+                        Ok("Contract weights removed, document operations keep their \
+                            frequencies"
+                            .into())
+                    } else {
+                        weights_lock.insert(strategy_name.clone(), weights.clone());
+                        Ok(format!("Contract weights set for {} contracts", weights.len()).into())
+                    }
+                };
+
+                BackendEvent::TaskCompleted {
+                    task: Task::Strategy(task),
+                    execution_result,
+                }
+            }
+            StrategyTask::ScaleToWallet {
+                ref strategy_name,
+                duration,
+            } => {
+                let (spendable_balance, utxos) = match self.loaded_wallet.lock().await.as_ref() {
+                    Some(wallet) => (wallet.spendable_balance(), wallet.spendable_utxos().count()),
+                    None => {
+                        return BackendEvent::StrategyError {
+                            error: "No wallet loaded".to_string(),
+                        };
+                    }
+                };
+                let mut strategies_lock = self.available_strategies.lock().await;
+                let Some(strategy) = strategies_lock.get_mut(strategy_name) else {
+                    return BackendEvent::StrategyError {
+                        error: format!("Strategy doesn't exist in app state."),
+                    };
+                };
+                // Scaled again from the wallet as it is now, it may have changed
+                // since the plan was shown
+                let plan = match strategy_scaling::scale_to_wallet(
+                    strategy,
+                    duration,
+                    spendable_balance,
+                    utxos,
+                ) {
+                    Ok(plan) => plan,
+                    Err(error) => {
+                        return BackendEvent::TaskCompleted {
+                            task: Task::Strategy(task.clone()),
+                            execution_result: Err(error),
+                        };
+                    }
+                };
+                if plan.fits() {
+                    return BackendEvent::TaskCompleted {
+                        task: Task::Strategy(task.clone()),
+                        execution_result: Ok(plan.to_string().into()),
+                    };
+                }
+                *strategy = plan.strategy.clone();
+
+                BackendEvent::TaskCompletedStateChange {
+                    task: Task::Strategy(task.clone()),
+                    execution_result: Ok(plan.to_string().into()),
+                    app_state_update: AppStateUpdate::SelectedStrategy(
+                        strategy_name.clone(),
+                        MutexGuard::map(strategies_lock, |strategies| {
+                            strategies.get_mut(strategy_name).expect("strategy exists")
+                        }),
+                        MutexGuard::map(
+                            self.available_strategies_contract_names.lock().await,
+                            |names| names.get_mut(strategy_name).expect("inconsistent data"),
+                        ),
+                    ),
+                }
+            }
+            StrategyTask::SetDocumentGenerator {
+                ref strategy_name,
+                ref contract_id,
+                ref document_type,
+                generator,
+            } => {
+                let Some(strategy) = self
+                    .available_strategies
+                    .lock()
+                    .await
+                    .get(strategy_name)
+                    .cloned()
+                else {
+                    return BackendEvent::StrategyError {
+                        error: format!("Strategy doesn't exist in app state."),
+                    };
+                };
+                let has_inserts = strategy.operations.iter().any(|operation| {
+                    matches!(
+                        &operation.op_type,
+                        OperationType::Document(op)
+                            if matches!(
+                                op.action,
+                                DocumentAction::DocumentActionInsertRandom(..)
+                                    | DocumentAction::DocumentActionInsertSpecific(..)
+                            ) && op.contract.id().to_string(Encoding::Base58) == *contract_id
+                                && op.document_type.name() == document_type
+                    )
+                });
+                if !has_inserts {
+                    return BackendEvent::TaskCompleted {
+                        task: Task::Strategy(task.clone()),
+                        execution_result: Err(format!(
+                            "The strategy inserts no {} documents of contract {}",
+                            document_type, contract_id
+                        )),
+                    };
+                }
+
+                let mut generators_lock = self.strategy_document_generators.lock().await;
+                let generators = generators_lock.entry(strategy_name.clone()).or_default();
+                let key = (contract_id.clone(), document_type.clone());
+                if generator == DocumentGeneratorKind::Random {
+                    generators.remove(&key);
+                } else {
+                    generators.insert(key, generator);
+                }
+
+                BackendEvent::TaskCompletedStateChange {
+                    task: Task::Strategy(task.clone()),
+                    execution_result: Ok(format!(
+                        "{} documents generated with {}",
+                        document_type, generator
+                    )
+                    .into()),
+                    app_state_update: AppStateUpdate::StrategyDocumentGenerators(
+                        strategy_name.clone(),
+                        MutexGuard::map(generators_lock, |generators| {
+                            generators
+                                .get_mut(strategy_name)
+                                .expect("generators inserted")
+                        }),
+                    ),
+                }
+            }
+            StrategyTask::SetContractAlias {
+                ref strategy_name,
+                ref contract_name,
+                ref alias,
+            } => {
+                let is_strategy_contract = self
+                    .available_strategies_contract_names
+                    .lock()
+                    .await
+                    .get(strategy_name)
+                    .map(|names| names.iter().any(|(name, _)| name == contract_name));
+                match is_strategy_contract {
+                    None => {
+                        return BackendEvent::StrategyError {
+                            error: format!("Strategy doesn't exist in app state."),
+                        }
+                    }
+                    Some(false) => {
+                        return BackendEvent::TaskCompleted {
+                            task: Task::Strategy(task.clone()),
+                            execution_result: Err(format!(
+                                "Contract {} is not used by strategy {}",
+                                contract_name, strategy_name
+                            )),
+                        }
+                    }
+                    Some(true) => {}
+                }
+
+                // Aliases follow the contract ID, operations and runs refer to contracts by it
+                let contract_id = match Identifier::from_string(contract_name, Encoding::Base58) {
+                    Ok(contract_id) => Some(contract_id),
+                    Err(_) => self
+                        .known_contracts
+                        .lock()
+                        .await
+                        .get(contract_name)
+                        .or(self.supporting_contracts.lock().await.get(contract_name))
+                        .map(|contract| contract.id()),
+                };
+                let Some(contract_id) = contract_id else {
+                    return BackendEvent::TaskCompleted {
+                        task: Task::Strategy(task.clone()),
+                        execution_result: Err(format!(
+                            "Contract {} is not loaded, load it to give it an alias",
+                            contract_name
+                        )),
+                    };
+                };
+
+                let mut aliases_lock = self.strategy_contract_aliases.lock().await;
+                let aliases = aliases_lock.entry(strategy_name.clone()).or_default();
+                let alias = alias.trim();
+                let message = if alias.is_empty() {
+                    aliases.remove(&contract_id);
+                    format!("Removed alias of {}", contract_name)
+                } else {
+                    aliases.insert(contract_id, alias.to_owned());
+                    format!("{} is now shown as {}", contract_name, alias)
+                };
+
+                BackendEvent::TaskCompletedStateChange {
+                    task: Task::Strategy(task.clone()),
+                    execution_result: Ok(message.into()),
+                    app_state_update: AppStateUpdate::StrategyContractAliases(
+                        strategy_name.clone(),
+                        MutexGuard::map(aliases_lock, |aliases| {
+                            aliases.get_mut(strategy_name).expect("aliases inserted")
+                        }),
+                    ),
+                }
+            }
+            StrategyTask::RegisterDocsToAllContracts(
+                strategy_name,
+                num_docs,
+                fill_size,
+                fill_type,
+                payload_path,
+            ) => {
+                let payloads = match payload_path
+                    .as_deref()
+                    .map(document_import::read_document_payloads)
+                    .transpose()
+                {
+                    Ok(payloads) => payloads,
+                    Err(error) => return BackendEvent::StrategyError { error },
+                };
+                let mut strategies_lock = self.available_strategies.lock().await;
+                if let Some(strategy) = strategies_lock.get_mut(&strategy_name) {
+                    let mut operations = Vec::new();
+                    for contract_with_updates in &strategy.start_contracts {
+                        let contract = &contract_with_updates.0;
+                        let document_types = contract.data_contract().document_types();
+                        let mut inserts = Vec::new();
+                        for (name, document_type) in document_types {
+                            let Some(payload) =
+                                payloads.as_ref().and_then(|payloads| payloads.get(name))
+                            else {
+                                continue;
+                            };
+                            match document_import::payload_properties(payload, document_type) {
+                                Ok(values) => inserts.push((
+                                    document_type,
+                                    DocumentAction::DocumentActionInsertSpecific(
+                                        values, None, fill_type, fill_size,
+                                    ),
+                                )),
+                                Err(error) => return BackendEvent::StrategyError { error },
+                            }
+                        }
+                        // Random documents of the first type for contracts without payloads
+                        if inserts.is_empty() {
+                            let document_type = document_types.values().next().expect(
+                                "Expected to get a document type in RegisterDocsToAllContracts",
+                            );
+                            inserts.push((
+                                document_type,
+                                DocumentAction::DocumentActionInsertRandom(fill_type, fill_size),
+                            ));
+                        }
+                        for (document_type, action) in inserts {
+                            operations.push(Operation {
+                                op_type: OperationType::Document(DocumentOp {
+                                    contract: contract.data_contract().clone(),
+                                    document_type: document_type.clone(),
+                                    action,
+                                }),
+                                frequency: Frequency {
+                                    times_per_block_range: num_docs..num_docs + 1,
+                                    chance_per_block: None,
+                                },
+                            });
+                        }
+                    }
+                    if payloads.is_some()
+                        && !operations.iter().any(|operation| {
+                            matches!(
+                                &operation.op_type,
+                                OperationType::Document(DocumentOp {
+                                    action: DocumentAction::DocumentActionInsertSpecific(..),
+                                    ..
+                                })
+                            )
+                        })
+                    {
+                        return BackendEvent::StrategyError {
+                            error: "No start contract has a document type of the payload file"
+                                .to_owned(),
+                        };
+                    }
+                    strategy.operations.extend(operations);
+                    BackendEvent::AppStateUpdated(AppStateUpdate::SelectedStrategy(
+                        strategy_name.clone(),
+                        MutexGuard::map(strategies_lock, |strategies| {
+                            strategies.get_mut(&strategy_name).expect("strategy exists")
+                        }),
+                        MutexGuard::map(
+                            self.available_strategies_contract_names.lock().await,
+                            |names| names.get_mut(&strategy_name).expect("inconsistent data"),
+                        ),
+                    ))
+                } else {
+                    BackendEvent::StrategyError {
+                        error: format!("Strategy doesn't exist in app state."),
+                    }
+                }
+            }
+            StrategyTask::SetIdentityInserts {
+                strategy_name,
+                identity_inserts_frequency,
+                start_balance,
+            } => {
+                let mut strategies_lock = self.available_strategies.lock().await;
+                if let Some(strategy) = strategies_lock.get_mut(&strategy_name) {
+                    strategy.identity_inserts = IdentityInsertInfo {
                         frequency: identity_inserts_frequency,
                         start_keys: 3,
                         extra_keys: BTreeMap::new(),
@@ -780,12 +1811,12 @@ impl AppState {
             } => {
                 let mut strategies_lock = self.available_strategies.lock().await;
                 if let Some(strategy) = strategies_lock.get_mut(&strategy_name) {
-                    let mut extra_keys = BTreeMap::new();
+                    // Keep keys configured with AddStartIdentityKeys
+                    let mut extra_keys = strategy.start_identities.extra_keys.clone();
                     if add_transfer_key {
-                        extra_keys.insert(
-                            Purpose::TRANSFER,
-                            [(SecurityLevel::CRITICAL, vec![KeyType::ECDSA_SECP256K1])].into(),
-                        );
+                        extra_keys.entry(Purpose::TRANSFER).or_insert_with(|| {
+                            [(SecurityLevel::CRITICAL, vec![KeyType::ECDSA_SECP256K1])].into()
+                        });
                     }
                     strategy.start_identities = StartIdentities {
                         number_of_identities: count as u16,
@@ -810,6 +1841,117 @@ impl AppState {
                     }
                 }
             }
+            StrategyTask::AddStartIdentityKeys {
+                strategy_name,
+                purpose,
+                security_level,
+                key_type,
+                count,
+            } => {
+                let allowed = match purpose {
+                    Purpose::AUTHENTICATION => security_level != SecurityLevel::MASTER,
+                    Purpose::ENCRYPTION | Purpose::DECRYPTION => {
+                        security_level == SecurityLevel::MEDIUM
+                    }
+                    Purpose::TRANSFER => security_level == SecurityLevel::CRITICAL,
+                    _ => false,
+                };
+                if !allowed {
+                    return BackendEvent::StrategyError {
+                        error: format!(
+                            "{:?} keys with {:?} security level can't be added to start identities",
+                            purpose, security_level
+                        ),
+                    };
+                }
+                if !START_IDENTITY_KEY_TYPES.contains(&key_type) {
+                    return BackendEvent::StrategyError {
+                        error: format!(
+                            "{:?} keys can't sign, they can't be added to start identities",
+                            key_type
+                        ),
+                    };
+                }
+
+                let mut strategies_lock = self.available_strategies.lock().await;
+                if let Some(strategy) = strategies_lock.get_mut(&strategy_name) {
+                    strategy
+                        .start_identities
+                        .extra_keys
+                        .entry(purpose)
+                        .or_default()
+                        .entry(security_level)
+                        .or_default()
+                        .extend(std::iter::repeat(key_type).take(count as usize));
+                    BackendEvent::AppStateUpdated(AppStateUpdate::SelectedStrategy(
+                        strategy_name.clone(),
+                        MutexGuard::map(strategies_lock, |strategies| {
+                            strategies.get_mut(&strategy_name).expect("strategy exists")
+                        }),
+                        MutexGuard::map(
+                            self.available_strategies_contract_names.lock().await,
+                            |names| names.get_mut(&strategy_name).expect("inconsistent data"),
+                        ),
+                    ))
+                } else {
+                    BackendEvent::StrategyError {
+                        error: format!("Strategy doesn't exist in app state."),
+                    }
+                }
+            }
+            StrategyTask::ClearStartIdentityKeys(strategy_name) => {
+                let mut strategies_lock = self.available_strategies.lock().await;
+                if let Some(strategy) = strategies_lock.get_mut(&strategy_name) {
+                    strategy.start_identities.extra_keys.clear();
+                    BackendEvent::AppStateUpdated(AppStateUpdate::SelectedStrategy(
+                        strategy_name.clone(),
+                        MutexGuard::map(strategies_lock, |strategies| {
+                            strategies.get_mut(&strategy_name).expect("strategy exists")
+                        }),
+                        MutexGuard::map(
+                            self.available_strategies_contract_names.lock().await,
+                            |names| names.get_mut(&strategy_name).expect("inconsistent data"),
+                        ),
+                    ))
+                } else {
+                    BackendEvent::StrategyError {
+                        error: format!("Strategy doesn't exist in app state."),
+                    }
+                }
+            }
+            StrategyTask::ImportHardCodedStartIdentities {
+                ref strategy_name,
+                ref path,
+            } => match self
+                .import_hard_coded_start_identities(sdk, strategy_name, path)
+                .await
+            {
+                Ok(imported) => {
+                    let strategies_lock = self.available_strategies.lock().await;
+                    BackendEvent::TaskCompletedStateChange {
+                        task: Task::Strategy(task.clone()),
+                        execution_result: Ok(format!(
+                            "Imported {} hard-coded start identities from {}",
+                            imported, path
+                        )
+                        .into()),
+                        app_state_update: AppStateUpdate::SelectedStrategy(
+                            strategy_name.clone(),
+                            MutexGuard::map(strategies_lock, |strategies| {
+                                strategies.get_mut(strategy_name).expect("strategy exists")
+                            }),
+                            MutexGuard::map(
+                                self.available_strategies_contract_names.lock().await,
+                                |names| names.get_mut(strategy_name).expect("inconsistent data"),
+                            ),
+                        ),
+                    }
+                }
+                Err(e) => BackendEvent::TaskCompleted {
+                    task: Task::Strategy(task.clone()),
+                    execution_result: Err(e),
+                },
+            },
             StrategyTask::AddHardCodedStartIdentity {
                 strategy_name,
                 identity_id_str,
@@ -872,14 +2014,336 @@ impl AppState {
                     }
                 }
             }
+            StrategyTask::SigningBenchmark => {
+                // Blocks for a few seconds, on the strategy run thread like the runs it's
+                // compared with
+                let benchmark = signing_benchmark::run_benchmark(sdk.version());
+                let mut report = benchmark.to_string();
+                if let Some(run) = self.run_history.lock().await.runs.last() {
+                    report.push_str(&format!("\n{}", benchmark.verdict(run)));
+                }
+
+                BackendEvent::TaskCompleted {
+                    task: Task::Strategy(task),
+                    execution_result: Ok(report.into()),
+                }
+            }
+            StrategyTask::DryRun {
+                ref strategy_name,
+                duration,
+                seconds_per_loop,
+                ref run_options,
+            } => {
+                let Some(mut strategy) = self
+                    .available_strategies
+                    .lock()
+                    .await
+                    .get(strategy_name)
+                    .cloned()
+                else {
+                    return BackendEvent::StrategyError {
+                        error: "No known strategy with that name in app state".to_string(),
+                    };
+                };
+                if let Err(error) = strategy_lint::preflight(&strategy) {
+                    return BackendEvent::StrategyError { error };
+                }
+                let Some(loaded_identity) = self.loaded_identity.lock().await.clone() else {
+                    return BackendEvent::StrategyError {
+                        error: "No identity loaded".to_string(),
+                    };
+                };
+                let available_utxos = match self.loaded_wallet.lock().await.as_ref() {
+                    Some(wallet) => wallet.spendable_utxos().count(),
+                    None => {
+                        return BackendEvent::StrategyError {
+                            error: "No wallet loaded".to_string(),
+                        };
+                    }
+                };
+
+                // Same signer and identities the run starts with
+                let mut signer = self.strategy_signer(&loaded_identity).await;
+                let mut current_identities = vec![loaded_identity.clone()];
+                current_identities.extend(
+                    strategy
+                        .start_identities
+                        .hard_coded
+                        .iter()
+                        .filter(|(_, transition)| transition.is_none())
+                        .map(|(identity, _)| identity.clone()),
+                );
+                if !strategy
+                    .start_identities
+                    .hard_coded
+                    .iter()
+                    .any(|(identity, _)| identity.id() == loaded_identity.id())
+                {
+                    strategy
+                        .start_identities
+                        .hard_coded
+                        .push((loaded_identity.clone(), None));
+                }
+
+                // Loops 1 and 2 initialize, the rest run for the duration
+                let loops = 2 + duration.div_ceil(seconds_per_loop.max(1));
+                let max_asset_lock_proofs = dry_run::max_asset_lock_proofs(&strategy, loops);
+                let mut asset_lock_proofs = dry_run::placeholder_asset_lock_proofs(
+                    max_asset_lock_proofs,
+                    PrivateKey::new(
+                        SecretKey::new(&mut StdRng::from_entropy()),
+                        Config::load().core_network(),
+                    ),
+                );
+                // Generated like the run with the same seed would
+                let seed = run_seed(run_options);
+                let mut rng = StdRng::seed_from_u64(seed);
+                let mut known_contracts = self.known_contracts.lock().await.clone();
+                let mut identity_nonce_counter = BTreeMap::new();
+                let mut contract_nonce_counter = BTreeMap::new();
+                let mempool_document_counter = BTreeMap::new();
+                let mut block_info = BlockInfo {
+                    time_ms: SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .expect("time went backwards")
+                        .as_millis() as u64,
+                    height: 1,
+                    ..Default::default()
+                };
+                let strategy_config = StrategyConfig {
+                    start_block_height: block_info.height,
+                    number_of_blocks: duration,
+                };
+
+                let drive_lock = self.drive.lock().await;
+                let document_tracker = DocumentTracker::new(seed);
+                let mut document_query_callback = |query: LocalDocumentQuery| match query {
+                    LocalDocumentQuery::RandomDocumentQuery(random_query) => {
+                        if run_options.delete_policy != DeletePolicy::LocalDrive {
+                            return document_tracker
+                                .take(
+                                    run_options.delete_policy,
+                                    random_query.data_contract.id(),
+                                    random_query.document_type.name(),
+                                )
+                                .into_iter()
+                                .collect();
+                        }
+                        let drive_query = DriveDocumentQuery::any_item_query(
+                            random_query.data_contract,
+                            random_query.document_type.as_ref(),
+                        );
+                        match drive_lock.query_documents(drive_query, None, false, None, None) {
+                            Ok(QueryDocumentsOutcome::V0(outcome_v0)) => {
+                                outcome_v0.documents_owned()
+                            }
+                            Err(e) => {
+                                tracing::debug!(
+                                    "Error fetching documents using DriveQuery: {:?}",
+                                    e
+                                );
+                                vec![]
+                            }
+                        }
+                    }
+                };
+                let mut identity_fetch_callback =
+                    |identifier: Identifier, _keys_request: Option<IdentityKeysRequest>| {
+                        drive_lock
+                            .fetch_identity_with_balance(
+                                identifier.into_buffer(),
+                                None,
+                                sdk.version(),
+                            )
+                            .ok()
+                            .flatten()
+                            .unwrap_or_else(|| PartialIdentity {
+                                id: identifier,
+                                loaded_public_keys: BTreeMap::new(),
+                                balance: None,
+                                revision: None,
+                                not_found_public_keys: BTreeSet::new(),
+                            })
+                    };
+
+                let mut report = DryRunReport {
+                    loops,
+                    seed,
+                    available_utxos,
+                    ..Default::default()
+                };
+                let run_name: Arc<str> = Arc::from(strategy_name.as_str());
+                for loop_index in 1..=loops {
+                    let (transitions, _finalize_operations, mut new_identities) = strategy
+                        .state_transitions_for_block(
+                            &mut document_query_callback,
+                            &mut identity_fetch_callback,
+                            &mut asset_lock_proofs,
+                            &block_info,
+                            &mut current_identities,
+                            &mut known_contracts,
+                            &mut signer,
+                            &mut identity_nonce_counter,
+                            &mut contract_nonce_counter,
+                            &mempool_document_counter,
+                            &mut rng,
+                            &strategy_config,
+                            sdk.version(),
+                        );
+                    // The run only uses the identities created while initializing
+                    if loop_index < 3 {
+                        current_identities.append(&mut new_identities);
+                    }
+                    self.transition_buffer.record_generated(
+                        &run_name,
+                        &transitions,
+                        &current_identities,
+                    );
+
+                    for transition in &transitions {
+                        report.add_transition(transition);
+                        if let StateTransition::DataContractCreate(contract_create_transition) =
+                            transition
+                        {
+                            match DataContract::try_from_platform_versioned(
+                                contract_create_transition.data_contract().clone(),
+                                false,
+                                &mut vec![],
+                                sdk.version(),
+                            ) {
+                                Ok(contract) => {
+                                    known_contracts.insert(
+                                        contract.id().to_string(Encoding::Base58),
+                                        contract,
+                                    );
+                                }
+                                Err(e) => tracing::debug!(
+                                    "Failed to convert serialized contract to contract: {e}"
+                                ),
+                            }
+                        } else if run_options.delete_policy != DeletePolicy::LocalDrive {
+                            for (contract_id, document_type_name, document) in
+                                dry_run::created_documents(
+                                    transition,
+                                    &known_contracts,
+                                    &block_info,
+                                    sdk.version(),
+                                )
+                            {
+                                document_tracker.record_created(
+                                    contract_id,
+                                    &document_type_name,
+                                    document,
+                                );
+                            }
+                        }
+                    }
+
+                    block_info.height += 1;
+                    block_info.time_ms += seconds_per_loop * 1000;
+                }
+
+                report.asset_lock_proofs = max_asset_lock_proofs - asset_lock_proofs.len() as u64;
+                report.asset_lock_transactions = report.asset_lock_proofs.div_ceil(
+                    run_options
+                        .asset_locks_per_transaction
+                        .clamp(1, MAX_ASSET_LOCKS_PER_TRANSACTION) as u64,
+                );
+                report.asset_lock_credits =
+                    report.asset_lock_proofs * dry_run::asset_lock_cost(&strategy);
+
+                BackendEvent::TaskCompleted {
+                    task: Task::Strategy(task.clone()),
+                    execution_result: Ok(report.to_string().into()),
+                }
+            }
             StrategyTask::RunStrategy(
                 strategy_name,
                 duration,
                 seconds_per_loop,
                 verify_proofs,
                 top_up_amount,
+                run_options,
             ) => {
                 tracing::info!("-----Starting strategy '{}'-----", strategy_name);
+                let seed = run_seed(&run_options);
+                tracing::info!("Random choices of the run are seeded with {}", seed);
+
+                // Guard against burning real Dash by accident
+                let config = Config::load();
+                if let Err(e) = config.check_run_allowed(run_options.spend_cap) {
+                    return BackendEvent::StrategyError { error: e };
+                }
+                let retry_policy = {
+                    let mut policies = self.strategy_retry_policies.lock().await;
+                    match run_options.retry_policy {
+                        Some(policy) => {
+                            policies.insert(strategy_name.clone(), policy);
+                            policy
+                        }
+                        None => policies.get(&strategy_name).copied().unwrap_or_default(),
+                    }
+                };
+                tracing::info!("Retry policy: {}", retry_policy);
+                if run_options.save_defaults {
+                    self.strategy_run_defaults.lock().await.insert(
+                        strategy_name.clone(),
+                        RunDefaults::new(
+                            duration,
+                            seconds_per_loop,
+                            verify_proofs,
+                            run_options.target_rate,
+                            run_options.reserve_top_up.as_ref(),
+                        ),
+                    );
+                }
+                let run = match self.strategy_runs.start(&strategy_name) {
+                    Ok(run) => run,
+                    Err(error) => return BackendEvent::StrategyError { error },
+                };
+                let mut cancel = run.cancel_receiver();
+                let mut cancelled = false;
+                // A single address leaves the Sdk nothing to rotate to, so the
+                // node's own throughput limits show
+                let pinned_sdk;
+                let sdk = match &run_options.pinned_address {
+                    Some(address) => {
+                        pinned_sdk = match AddressList::from_str(address)
+                            .map_err(|e| format!("Invalid pinned address {}: {}", address, e))
+                            .and_then(|address_list| {
+                                connections::build_sdk(&config, address_list, sdk.version())
+                            }) {
+                            Ok(pinned_sdk) => pinned_sdk,
+                            Err(error) => return BackendEvent::StrategyError { error },
+                        };
+                        tracing::info!("Pinning all requests of the run to {}", address);
+                        &pinned_sdk
+                    }
+                    None => sdk,
+                };
+                let mirror = if run_options.mirror {
+                    match TransitionMirror::new(&config, sdk.version()) {
+                        Ok(mirror) => Some(Arc::new(mirror)),
+                        Err(error) => return BackendEvent::StrategyError { error },
+                    }
+                } else {
+                    None
+                };
+                let trace = if run_options.trace_transitions {
+                    match TransitionTrace::create(&strategy_name) {
+                        Ok(trace) => Some(Arc::new(trace)),
+                        Err(error) => return BackendEvent::StrategyError { error },
+                    }
+                } else {
+                    None
+                };
+                let reserve = match &run_options.reserve_top_up {
+                    Some(top_up) => match ReserveFunder::new(self, top_up).await {
+                        Ok(reserve) => Some(Arc::new(reserve)),
+                        Err(error) => return BackendEvent::StrategyError { error },
+                    },
+                    None => None,
+                };
                 let init_start_time = Instant::now(); // Start time of strategy initialization plus execution of first two blocks
                 let mut init_time = Duration::new(0, 0); // Will set this to the time it takes for all initialization plus the first two blocks to complete
 
@@ -896,9 +2360,10 @@ impl AppState {
                     }
                 };
 
-                // Refresh loaded_identity and get the current balance at strategy start
-                let mut loaded_identity_lock = match self.refresh_loaded_identity(&sdk).await {
-                    Ok(lock) => lock,
+                // Refresh loaded_identity and get the current balance at strategy start, the
+                // run goes on with its own copy so the state isn't locked meanwhile
+                let loaded_identity = match self.refresh_loaded_identity(&sdk).await {
+                    Ok(lock) => lock.clone(),
                     Err(e) => {
                         tracing::debug!("Failed to refresh loaded identity: {:?}", e);
                         return BackendEvent::StrategyError {
@@ -906,26 +2371,95 @@ impl AppState {
                         };
                     }
                 };
-                let initial_balance_identity = loaded_identity_lock.balance();
-
-                // Refresh UTXOs for the loaded wallet and get initial wallet balance
-                let mut loaded_wallet_lock = self.loaded_wallet.lock().await;
-                if let Some(ref mut wallet) = *loaded_wallet_lock {
-                    let _ = wallet.reload_utxos(insight, core_client).await;
+                let initial_balance_identity = loaded_identity.balance();
+
+                // Refresh UTXOs for the loaded wallet. Other runs going on broadcast
+                // transactions the wallet learns of only when they're confirmed, a reload
+                // would bring back the UTXOs they spent.
+                if !run.others_running() {
+                    if let Some(wallet) = self.loaded_wallet.lock().await.as_mut() {
+                        let _ = wallet.reload_utxos(insight, core_client).await;
+                    }
                 }
-                let initial_balance_wallet = loaded_wallet_lock.clone().unwrap().balance();
-                drop(loaded_wallet_lock);
+                // Duffs taken from the wallet by the transactions of the run
+                let wallet_spent = Arc::new(AtomicU64::new(0));
+
+                // Funds of the identities created by the run are withdrawn to this address
+                let withdrawal_address = if run_options.withdraw_to_sweep_wallet {
+                    match self.sweep_wallet.lock().await.as_ref() {
+                        Some(sweep_wallet) => sweep_wallet.receive_address(),
+                        None => {
+                            return BackendEvent::StrategyError {
+                                error: "Withdrawing to the sweep wallet was selected but no sweep \
+                                        wallet is set"
+                                    .to_string(),
+                            };
+                        }
+                    }
+                } else {
+                    self.loaded_wallet
+                        .lock()
+                        .await
+                        .as_ref()
+                        .expect("Expected a loaded wallet")
+                        .receive_address()
+                };
 
-                // Get a mutable strategy because we need to modify some properties of contracts on updates
-                let mut strategies_lock = self.available_strategies.lock().await;
-                if strategies_lock.get_mut(&strategy_name).is_none() {
+                // The run modifies some properties of contracts on updates, it works on a
+                // copy replacing the stored strategy once it's over. The stored one can't
+                // be edited while the run goes on.
+                let Some(mut run_strategy) = self
+                    .available_strategies
+                    .lock()
+                    .await
+                    .get(&strategy_name)
+                    .cloned()
+                else {
                     return BackendEvent::StrategyError {
                         error: "No known strategy with that name in app state".to_string(),
                     };
                 };
-                let strategy = strategies_lock
-                    .get_mut(&strategy_name)
-                    .expect("Expected to get a strategy with that name");
+                let strategy = &mut run_strategy;
+
+                if let Err(error) = strategy_lint::preflight(strategy) {
+                    return BackendEvent::StrategyError { error };
+                }
+
+                // Misconfigurations don't stop the run but are reported with it
+                let lint_warnings: Vec<String> = strategy_lint::lint_strategy(
+                    strategy,
+                    self.available_strategies_contract_names
+                        .lock()
+                        .await
+                        .get(&strategy_name)
+                        .map(|names| names.as_slice())
+                        .unwrap_or_default(),
+                )
+                .into_iter()
+                .chain(strategy_lint::lint_run(
+                    strategy,
+                    seconds_per_loop,
+                    &run_options.wait,
+                ))
+                .collect();
+                for warning in &lint_warnings {
+                    tracing::warn!("Strategy lint: {}", warning);
+                }
+
+                // Aliases of the strategy contracts, for the report. Start contracts may get new
+                // IDs when created, the aliases are carried over once they're known.
+                let mut contract_aliases = self
+                    .strategy_contract_aliases
+                    .lock()
+                    .await
+                    .get(&strategy_name)
+                    .cloned()
+                    .unwrap_or_default();
+                let start_contract_ids: Vec<Identifier> = strategy
+                    .start_contracts
+                    .iter()
+                    .map(|(contract, _)| contract.data_contract().id())
+                    .collect();
 
                 // Get block_info
                 // Get block info for the first block by sending a grpc request and looking at
@@ -977,36 +2511,60 @@ impl AppState {
                 }
                 initial_block_info.height += 1; // Add one because we'll be submitting to the next block
 
-                // Get signer from loaded_identity
-                // Convert loaded_identity to SimpleSigner
-                let identity_private_keys_lock = self.known_identities_private_keys.lock().await;
-                let mut signer = {
-                    let strategy_signer = strategy.signer.insert({
-                        let mut new_signer = SimpleSigner::default();
-                        let Identity::V0(identity_v0) = &*loaded_identity_lock;
-                        for (key_id, public_key) in &identity_v0.public_keys {
-                            let identity_key_tuple = (identity_v0.id, *key_id);
-                            if let Some(private_key_bytes) =
-                                identity_private_keys_lock.get(&identity_key_tuple)
-                            {
-                                new_signer.private_keys.insert(
-                                    public_key.clone(),
-                                    private_key_bytes
-                                        .clone()
-                                        .try_into()
-                                        .expect("Expected to convert private key bytes"),
-                                );
-                            }
-                        }
-                        new_signer
-                    });
-                    strategy_signer.clone()
-                };
-                drop(identity_private_keys_lock);
+                // Identities handed over by the previous run of a chain
+                let chain_carry = self.chain_carry.lock().await.remove(&strategy_name);
+                let pass_on = chain_carry.as_ref().is_some_and(|carry| carry.pass_on);
+
+                // Nonces of the identities signing transitions are counted by the run, no
+                // other run going on may sign with them
+                let signing_identity_ids =
+                    std::iter::once(loaded_identity.id())
+                        .chain(
+                            strategy
+                                .start_identities
+                                .hard_coded
+                                .iter()
+                                .filter(|(_, transition)| transition.is_none())
+                                .map(|(identity, _)| identity.id()),
+                        )
+                        .chain(chain_carry.iter().flat_map(|carry| {
+                            carry.identities.iter().map(|identity| identity.id())
+                        }));
+                if let Err(error) = run.reserve_identities(signing_identity_ids) {
+                    return BackendEvent::StrategyError { error };
+                }
+
+                // Signer with the keys of the loaded identity, of the identities marked
+                // to sign strategy runs and of the identities handed over
+                let mut run_signer = self.strategy_signer(&loaded_identity).await;
+                if let Some(carry) = &chain_carry {
+                    run_signer
+                        .private_keys
+                        .extend(carry.signer.private_keys.clone());
+                }
+                let mut signer = strategy.signer.insert(run_signer).clone();
+                for (identity, _) in strategy
+                    .start_identities
+                    .hard_coded
+                    .iter()
+                    .filter(|(_, transition)| transition.is_none())
+                {
+                    if !identity
+                        .public_keys()
+                        .values()
+                        .any(|public_key| signer.private_keys.contains_key(public_key))
+                    {
+                        tracing::warn!(
+                            "Hard coded start identity {} has no keys in the run signer, mark it \
+                             to sign strategy runs on the identities screen",
+                            identity.id()
+                        );
+                    }
+                }
 
                 // Set initial current_identities to loaded_identity
                 // During strategy execution, newly created identities will be added to current_identities
-                let mut loaded_identity_clone = loaded_identity_lock.clone();
+                let mut loaded_identity_clone = loaded_identity.clone();
                 let current_identities = Arc::new(Mutex::new(vec![loaded_identity_clone.clone()]));
 
                 // Add the hardcoded start identities that are already created to current_identities
@@ -1024,10 +2582,22 @@ impl AppState {
                     .collect();
 
                 {
-                    current_identities
-                        .lock()
-                        .await
-                        .extend(hard_coded_start_identities);
+                    let mut current_identities = current_identities.lock().await;
+                    current_identities.extend(hard_coded_start_identities);
+                    if let Some(carry) = chain_carry {
+                        tracing::info!(
+                            "{} identities handed over by the previous run of the chain",
+                            carry.identities.len()
+                        );
+                        for identity in carry.identities {
+                            if !current_identities
+                                .iter()
+                                .any(|current| current.id() == identity.id())
+                            {
+                                current_identities.push(identity);
+                            }
+                        }
+                    }
                 }
 
                 // Add the loaded identity to hardcoded start identities if it's not already present
@@ -1035,12 +2605,12 @@ impl AppState {
                     .start_identities
                     .hard_coded
                     .iter()
-                    .any(|(identity, _)| identity.id() == loaded_identity_lock.id())
+                    .any(|(identity, _)| identity.id() == loaded_identity.id())
                 {
                     strategy
                         .start_identities
                         .hard_coded
-                        .push((loaded_identity_lock.clone(), None));
+                        .push((loaded_identity.clone(), None));
                 }
 
                 // Set the nonce counters
@@ -1095,133 +2665,55 @@ impl AppState {
                 let mut contract_nonce_counter: BTreeMap<(Identifier, Identifier), u64> =
                     contract_results.into_iter().collect();
 
-                // Get a lock on the local drive for the following two callbacks
-                let drive_lock = self.drive.lock().await;
-
-                // Callback used to fetch documents from the local Drive instance
-                // Used for DocumentReplace and DocumentDelete transitions
-                let mut document_query_callback = |query: LocalDocumentQuery| {
-                    match query {
-                        LocalDocumentQuery::RandomDocumentQuery(random_query) => {
-                            let document_type = random_query.document_type;
-                            let data_contract = random_query.data_contract;
-
-                            // Construct a DriveQuery based on the document_type and
-                            // data_contract
-                            let drive_query = DriveDocumentQuery::any_item_query(
-                                data_contract,
-                                document_type.as_ref(),
-                            );
-
-                            // Query the Drive for documents
-                            match drive_lock.query_documents(drive_query, None, false, None, None) {
-                                Ok(outcome) => match outcome {
-                                    QueryDocumentsOutcome::V0(outcome_v0) => {
-                                        let documents = outcome_v0.documents_owned();
-                                        documents
-                                    }
-                                },
-                                Err(e) => {
-                                    tracing::debug!(
-                                        "Error fetching documents using DriveQuery: {:?}",
-                                        e
-                                    );
-                                    vec![]
-                                }
-                            }
-                        }
-                    }
-                };
-
-                // Callback used to fetch identities from the local Drive instance
-                // Is this ever used?
-                let mut identity_fetch_callback =
-                    |identifier: Identifier, _keys_request: Option<IdentityKeysRequest>| {
-                        // Convert Identifier to a byte array format expected by the Drive
-                        // method
-                        let identity_id_bytes = identifier.into_buffer();
-
-                        // Fetch identity information from the Drive
-                        match drive_lock.fetch_identity_with_balance(
-                            identity_id_bytes,
-                            None,
-                            sdk.version(),
-                        ) {
-                            Ok(maybe_partial_identity) => {
-                                let partial_identity =
-                                    maybe_partial_identity.unwrap_or_else(|| PartialIdentity {
-                                        id: identifier,
-                                        loaded_public_keys: BTreeMap::new(),
-                                        balance: None,
-                                        revision: None,
-                                        not_found_public_keys: BTreeSet::new(),
-                                    });
-                                tracing::trace!(
-                                    "Fetched identity info for identifier {}: {:?}",
-                                    identifier,
-                                    partial_identity
-                                );
-                                partial_identity
-                            }
-                            Err(e) => {
-                                tracing::debug!("Error fetching identity: {:?}", e);
-                                PartialIdentity {
-                                    id: identifier,
-                                    loaded_public_keys: BTreeMap::new(),
-                                    balance: None,
-                                    revision: None,
-                                    not_found_public_keys: BTreeSet::new(),
-                                }
-                            }
-                        }
-                    };
+                // Documents taken by replacements and deletions of the run
+                let document_tracker = DocumentTracker::new(seed);
 
                 // Create asset lock proofs for all the identity creates and top ups
-                let num_start_identities = strategy.start_identities.number_of_identities as u64;
-                let num_identity_inserts = (strategy
-                    .identity_inserts
-                    .frequency
-                    .times_per_block_range
-                    .start as f64
-                    * duration as f64
-                    * strategy
-                        .identity_inserts
-                        .frequency
-                        .chance_per_block
-                        .unwrap_or(1.0)) as u64;
-                let mut num_top_ups: u64 = 0;
-                for operation in &strategy.operations {
-                    if matches!(operation.op_type, OperationType::IdentityTopUp(_)) {
-                        num_top_ups += (operation.frequency.times_per_block_range.start as f64
-                            * duration as f64
-                            * operation.frequency.chance_per_block.unwrap_or(1.0))
-                            as u64;
+                let num_asset_lock_proofs_needed =
+                    dry_run::estimated_asset_lock_proofs(strategy, duration);
+                if let Some(spend_cap) = run_options.spend_cap {
+                    // Asset locks are funded up front, refuse to start if they alone break the cap
+                    let upfront_spend =
+                        num_asset_lock_proofs_needed * dry_run::asset_lock_cost(strategy);
+                    if upfront_spend > spend_cap {
+                        return BackendEvent::StrategyError {
+                            error: format!(
+                                "Funding {} asset locks needs {}, more than the spend cap of {}",
+                                num_asset_lock_proofs_needed,
+                                units::format_credits(upfront_spend),
+                                units::format_credits(spend_cap)
+                            ),
+                        };
                     }
                 }
-                let num_asset_lock_proofs_needed =
-                    num_start_identities + num_identity_inserts + num_top_ups;
                 let mut asset_lock_proofs: Vec<(AssetLockProof, PrivateKey)> = Vec::new();
+                // Several asset locks share an L1 transaction, one credit output each, which
+                // saves broadcasts and proof waits
+                let asset_locks_per_transaction = run_options
+                    .asset_locks_per_transaction
+                    .clamp(1, MAX_ASSET_LOCKS_PER_TRANSACTION)
+                    as u64;
+                let num_asset_lock_transactions =
+                    num_asset_lock_proofs_needed.div_ceil(asset_locks_per_transaction);
                 if num_asset_lock_proofs_needed > 0 {
+                    // A UTXO for each transaction is set aside from other runs going on
                     let wallet_lock = self.loaded_wallet.lock().await;
-                    let num_available_utxos = match wallet_lock
-                        .as_ref()
-                        .expect("No wallet loaded while getting asset lock proofs")
-                    {
-                        Wallet::SingleKeyWallet(SingleKeyWallet { utxos, .. }) => utxos.len(),
-                    };
-                    drop(wallet_lock);
-                    if num_available_utxos
-                        < num_asset_lock_proofs_needed
+                    let reserved = run.reserve_utxos(
+                        wallet_lock
+                            .as_ref()
+                            .expect("No wallet loaded while getting asset lock proofs"),
+                        num_asset_lock_transactions
                             .try_into()
-                            .expect("Couldn't convert num_asset_lock_proofs_needed into usize")
-                    {
-                        return BackendEvent::StrategyError {
-                            error: format!("Not enough UTXOs available in wallet. Available: {}. Need: {}. Go to Wallet screen and create more.", num_available_utxos, num_asset_lock_proofs_needed),
-                        };
+                            .expect("Couldn't convert num_asset_lock_transactions into usize"),
+                    );
+                    drop(wallet_lock);
+                    if let Err(error) = reserved {
+                        return BackendEvent::StrategyError { error };
                     }
                     tracing::info!(
-                        "Obtaining {} asset lock proofs for the strategy...",
-                        num_asset_lock_proofs_needed
+                        "Obtaining {} asset lock proofs for the strategy in {} transactions...",
+                        num_asset_lock_proofs_needed,
+                        num_asset_lock_transactions
                     );
                     let asset_lock_proof_time = Instant::now();
 
@@ -1229,24 +2721,48 @@ impl AppState {
                     let permits = Arc::new(Semaphore::new(20));
                     let starting_balance = strategy.start_identities.starting_balances;
                     let processed = Arc::new(AtomicUsize::new(0));
-                    let tasks: FuturesUnordered<_> = (0..num_asset_lock_proofs_needed)
-                    .map(|_| {
+                    let cancel = &cancel;
+                    let run = &run;
+                    let wallet_spent = &wallet_spent;
+                    let tasks: FuturesUnordered<_> = (0..num_asset_lock_transactions)
+                    .map(|transaction_index| {
                         let permits = Arc::clone(&permits);
                         let processed = Arc::clone(&processed);
+                        // The last transaction only funds what's left
+                        let batch_size = asset_locks_per_transaction.min(
+                            num_asset_lock_proofs_needed
+                                - transaction_index * asset_locks_per_transaction,
+                        ) as usize;
 
                         async move {
                             let _permit = permits.acquire_owned().await.ok()?;
+                            // Locks already broadcast are still waited for, their funds would be
+                            // stranded otherwise, but no new ones are funded once cancelled
+                            if *cancel.borrow() {
+                                return None;
+                            }
 
                             let mut wallet_lock = self.loaded_wallet.lock().await;
                             let wallet = wallet_lock.as_mut().expect("Wallet not loaded");
 
-                            let (asset_lock_transaction, asset_lock_proof_private_key) = wallet
-                                .asset_lock_transaction(None, starting_balance)
+                            let balance_before = wallet.balance();
+                            let (asset_lock_transaction, asset_lock_proof_private_keys) = wallet
+                                .without_utxos(&run.utxos_of_other_runs(), |wallet| {
+                                    wallet.batch_asset_lock_transaction(
+                                        None,
+                                        starting_balance,
+                                        batch_size,
+                                    )
+                                })
                                 .map_err(|e| {
                                     tracing::debug!("Error creating asset lock transaction: {:?}", e);
                                     e
                                 })
                                 .ok()?;
+                            wallet_spent.fetch_add(
+                                balance_before.saturating_sub(wallet.balance()),
+                                Ordering::SeqCst,
+                            );
 
                             let receive_address = wallet.receive_address();
                             drop(wallet_lock);
@@ -1255,7 +2771,19 @@ impl AppState {
 
                                 match AppState::broadcast_and_retrieve_asset_lock(sdk, &asset_lock_transaction, &receive_address).await {
                                     Ok(asset_lock_proof) => {
-                                        result = Ok((asset_lock_proof, asset_lock_proof_private_key));
+                                        result = Ok(asset_lock_proof_private_keys
+                                            .into_iter()
+                                            .enumerate()
+                                            .map(|(output_index, private_key)| {
+                                                (
+                                                    AppState::asset_lock_proof_for_output(
+                                                        &asset_lock_proof,
+                                                        output_index as u32,
+                                                    ),
+                                                    private_key,
+                                                )
+                                            })
+                                            .collect::<Vec<_>>());
                                     }
                                     Err(e) => {
                                         tracing::debug!(
@@ -1268,14 +2796,15 @@ impl AppState {
 
 
                             match result {
-                                Ok(asset_lock_proof) => {
-                                    let prev = processed.fetch_add(1, Ordering::SeqCst);
+                                Ok(batch_proofs) => {
+                                    let prev = processed.fetch_add(batch_proofs.len(), Ordering::SeqCst);
                                     tracing::trace!(
-                                        "Successfully obtained asset lock proof {} of {}",
+                                        "Successfully obtained asset lock proofs {} to {} of {}",
                                         prev + 1,
+                                        prev + batch_proofs.len(),
                                         num_asset_lock_proofs_needed
                                     );
-                                    Some(asset_lock_proof)
+                                    Some(batch_proofs)
                                 }
                                 Err(e) => {
                                     tracing::debug!(
@@ -1290,7 +2819,12 @@ impl AppState {
                     })
                     .collect();
 
-                    asset_lock_proofs = join_all(tasks).await.into_iter().flatten().collect();
+                    asset_lock_proofs = join_all(tasks)
+                        .await
+                        .into_iter()
+                        .flatten()
+                        .flatten()
+                        .collect();
 
                     tracing::info!(
                         "Took {} seconds to obtain {} asset lock proofs from {} required",
@@ -1298,10 +2832,93 @@ impl AppState {
                         asset_lock_proofs.len(),
                         num_asset_lock_proofs_needed
                     );
+                    if *cancel.borrow() {
+                        tracing::info!("Strategy run cancelled while funding asset locks");
+                    }
+                }
+
+                // Hourly frequency profile, applied on top of the configured operation frequencies
+                // which are restored once the run is over
+                let frequency_profile = self
+                    .strategy_frequency_profiles
+                    .lock()
+                    .await
+                    .get(&strategy_name)
+                    .cloned();
+                let key_maintenance_ops = self
+                    .strategy_key_maintenance
+                    .lock()
+                    .await
+                    .get(&strategy_name)
+                    .cloned()
+                    .unwrap_or_default();
+                let base_frequencies = strategy
+                    .operations
+                    .iter()
+                    .map(|operation| operation.frequency.clone())
+                    .collect::<Vec<_>>();
+                // Contract weights scale the document operations once for the whole run, the
+                // other multipliers apply on top of the weighted frequencies
+                let weighted_frequencies = match self
+                    .strategy_contract_weights
+                    .lock()
+                    .await
+                    .get(&strategy_name)
+                {
+                    Some(weights) => {
+                        let multipliers =
+                            contract_weights::operation_multipliers(strategy, weights);
+                        base_frequencies
+                            .iter()
+                            .zip(multipliers)
+                            .map(|(base, multiplier)| scale_frequency(base, multiplier))
+                            .collect::<Vec<_>>()
+                    }
+                    None => base_frequencies.clone(),
+                };
+                for (operation, weighted) in strategy
+                    .operations
+                    .iter_mut()
+                    .zip(weighted_frequencies.iter())
+                {
+                    operation.frequency = weighted.clone();
+                }
+                // Documents of the inserts with a generator get their values from it instead
+                // of the random fill
+                let document_generators = self
+                    .strategy_document_generators
+                    .lock()
+                    .await
+                    .get(&strategy_name)
+                    .cloned()
+                    .unwrap_or_default();
+                // Closed-loop control of the load against the latency target, from the
+                // wait times of each stats window
+                let mut load_controller = run_options.latency_target.map(LoadController::new);
+                // Broadcasts of the loops after the init ones are paced to the target rate
+                let rate_limiter = run_options
+                    .target_rate
+                    .and_then(RateLimiter::new)
+                    .map(Arc::new);
+                if let Some(rate_limiter) = &rate_limiter {
+                    tracing::info!("Pacing broadcasts to {:.2} tx/s", rate_limiter.rate());
                 }
+                // Load shape applied on top of the other multipliers, its phases are
+                // reported separately and start with the loops after the init ones
+                let load_profile = run_options.load_profile.clone();
+                let mut phase_tracker: Option<PhaseTracker> = None;
+                let window_hist = Arc::new(Mutex::new(Histogram::<u64>::new(3).unwrap()));
+                let mut window_start = Instant::now();
+                // Steady phase between the warm-up and the cool-down, the only part of the
+                // run the reported rates and wait times cover when either is set
+                let phases_excluded =
+                    !run_options.warm_up.is_zero() || !run_options.cool_down.is_zero();
+                let mut measured_time = Duration::ZERO;
+                let mut measured_attempted: u64 = 0;
+                let measured_broadcast_oks = Arc::new(AtomicU64::new(0));
 
                 // Some final initialization
-                let mut rng = StdRng::from_entropy(); // Will be passed to state_transitions_for_block
+                let mut rng = StdRng::seed_from_u64(seed); // Will be passed to state_transitions_for_block
                 let mut current_block_info = initial_block_info.clone(); // Used for transition creation and logging
                 let mut transition_count: u32 = 0; // Used for logging how many transitions we attempted
                 let mut success_count = Arc::new(AtomicU64::new(0)); // Used for logging how many transitions were successful
@@ -1309,24 +2926,93 @@ impl AppState {
                 let mut loop_index = 1; // Index of the loop iteration. Represents blocks for block mode and seconds for time mode
                 let mut new_identity_ids = Vec::new(); // Will capture the ids of identities added to current_identities
                 let mut new_contract_ids = Vec::new(); // Will capture the ids of newly created data contracts
+                let mut reused_contract_ids = Vec::new(); // Start contracts found on chain and not created again
                 let broadcast_oks = Arc::new(AtomicU64::new(0)); // Atomic counter for successful broadcasts
                 let broadcast_errs = Arc::new(AtomicU64::new(0)); // Atomic counter for failed broadcasts
                 let ongoing_broadcasts = Arc::new(AtomicU64::new(0)); // Atomic counter for ongoing broadcasts
                 let ongoing_waits = Arc::new(AtomicU64::new(0)); // Atomic counter for ongoing waits
                 let wait_oks = Arc::new(AtomicU64::new(0)); // Atomic counter for successful waits
                 let wait_errs = Arc::new(AtomicU64::new(0)); // Atomic counter for failed waits
+                let wait_unknowns = Arc::new(AtomicU64::new(0)); // Atomic counter for waits given up on
+                let mut pending_waits: VecDeque<oneshot::Sender<()>> = VecDeque::new(); // Used to give up on the oldest waits
+                let mut throttled_loops: u64 = 0; // Loops held off by the in-flight cap
+                let mut throttled_time = Duration::ZERO;
                 let mempool_document_counter =
                     Arc::new(Mutex::new(BTreeMap::<(Identifier, Identifier), u64>::new())); // Map to track how many documents an identity has in the mempool per contract
                 let hist = Arc::new(Mutex::new(Histogram::<u64>::new(3).unwrap()));
+                // Wait times since the last backlog check, to follow the latency trend
+                let recent_wait_seconds = Arc::new(AtomicU64::new(0));
+                let recent_wait_count = Arc::new(AtomicU64::new(0));
+                let mut backlog_monitor = BacklogMonitor::default();
+                // Snapshots for instances following the run
+                let progress_path = config.run_progress_file_path();
+                // Transitions prepared but not broadcast yet, and whether the loop is still
+                // generating them, sampled with the other stage counters for the gauge
+                let queued_broadcasts = Arc::new(AtomicU64::new(0));
+                let generating = Arc::new(AtomicBool::new(false));
+                let progress = Arc::new(Mutex::new(RunProgress {
+                    strategy_name: strategy_name.clone(),
+                    duration_secs: duration,
+                    ..Default::default()
+                }));
+                let sampler_token = CancellationToken::new();
+                let sampler_guard = sampler_token.clone().drop_guard();
+                let progress_sampler = tokio::spawn({
+                    let progress = progress.clone();
+                    let progress_path = progress_path.clone();
+                    let strategy_runs = self.strategy_runs.clone();
+                    let strategy_name = strategy_name.clone();
+                    let queued_broadcasts = queued_broadcasts.clone();
+                    let ongoing_broadcasts = ongoing_broadcasts.clone();
+                    let ongoing_waits = ongoing_waits.clone();
+                    let generating = generating.clone();
+                    async move {
+                        let mut interval = tokio::time::interval(PROGRESS_SAMPLE_INTERVAL);
+                        loop {
+                            tokio::select! {
+                                _ = sampler_token.cancelled() => break,
+                                _ = interval.tick() => {}
+                            }
+                            let mut snapshot = progress.lock().await.clone();
+                            snapshot.queued_broadcasts = queued_broadcasts.load(Ordering::SeqCst);
+                            snapshot.broadcast_ongoing = ongoing_broadcasts.load(Ordering::SeqCst);
+                            snapshot.wait_ongoing = ongoing_waits.load(Ordering::SeqCst);
+                            snapshot.generating = generating.load(Ordering::SeqCst);
+                            if strategy_runs.set_progress(&strategy_name, snapshot.clone()) {
+                                snapshot.write(&progress_path);
+                            }
+                        }
+                    }
+                });
 
                 // Broadcast error counters
                 let identity_nonce_error_count = Arc::new(AtomicU64::new(0));
+                // Document broadcasts that failed on a nonce and succeeded once retried with a
+                // fresh nonce
+                let nonce_recovery_count = Arc::new(AtomicU64::new(0));
+                // Identity contract nonces fetched for those retries, the last one used by
+                // (identity, contract), for the next loops to continue from
+                let refreshed_contract_nonces: Arc<Mutex<BTreeMap<(Identifier, Identifier), u64>>> =
+                    Default::default();
                 let insufficient_balance_error_count = Arc::new(AtomicU64::new(0));
+                // Identities topped up after running out of credits
+                let top_ups = Arc::new(Mutex::new(Vec::<TopUpRecord>::new()));
                 let local_rate_limit_error_count = Arc::new(AtomicU64::new(0));
                 let broadcast_connection_error_count = Arc::new(AtomicU64::new(0));
 
                 let broadcast_errors_per_code: Arc<DashMap<Code, AtomicU64>> = Default::default();
                 let wait_errors_per_code: Arc<DashMap<Code, AtomicU64>> = Default::default();
+                let rejections = Arc::new(RejectionCounters::default());
+                let operation_metrics = Arc::new(OperationMetrics::default());
+                // Generated and failed transitions are kept for the transition inspector
+                let transition_buffer = self.transition_buffer.clone();
+                let run_name: Arc<str> = Arc::from(strategy_name.as_str());
+                let traffic = Arc::new(Traffic::default());
+
+                // Credits spent so far (wallet asset locks and loaded identity balance), used
+                // to stop before the spend cap
+                let mut spent_credits: u64 = 0;
+                let mut halt_reason: Option<String> = None;
 
                 tracing::info!("Initialization complete. Starting strategy execution...");
                 tracing::info!("Progress will be logged every 10 batches of broadcasts.");
@@ -1334,6 +3020,40 @@ impl AppState {
                 // Now loop through the number of blocks or seconds the user asked for, preparing and processing state transitions
                 while load_start_time.elapsed().as_secs() < duration || loop_index <= 2 {
                     tracing::debug!("Start loop: {loop_index}");
+                    if *cancel.borrow() {
+                        tracing::info!("Strategy run cancelled at loop {}", loop_index);
+                        cancelled = true;
+                        break;
+                    }
+                    // Stop if the next loop would likely spend past the cap, assuming it
+                    // costs as much as the last one
+                    if let Some(spend_cap) = run_options.spend_cap {
+                        let reserve_spent: Credits = top_ups
+                            .lock()
+                            .await
+                            .iter()
+                            .filter(|top_up| {
+                                top_up.success && matches!(top_up.funding, TopUpFunding::Reserve(_))
+                            })
+                            .map(|top_up| top_up.credits)
+                            .sum();
+                        let now_spent = wallet_spent.load(Ordering::SeqCst) * CREDITS_PER_DUFF
+                            + initial_balance_identity
+                                .saturating_sub(loaded_identity_clone.balance())
+                            + reserve_spent;
+                        let last_loop_spend = now_spent.saturating_sub(spent_credits);
+                        spent_credits = now_spent;
+                        if next_loop_breaks_spend_cap(spend_cap, spent_credits, last_loop_spend) {
+                            let reason = format!(
+                                "Spend cap reached after {} spent at loop {}",
+                                units::format_credits(spent_credits),
+                                loop_index
+                            );
+                            tracing::warn!("{}", reason);
+                            halt_reason = Some(reason);
+                            break;
+                        }
+                    }
                     // Every 10 loops, log statistics
                     if loop_index % 10 == 0 || loop_index == 1 {
                         let stats_elapsed = load_start_time.elapsed().as_secs();
@@ -1345,12 +3065,43 @@ impl AppState {
                         let stats_broadcast_failed = broadcast_errs.load(Ordering::SeqCst);
                         let stats_wait_successful = wait_oks.load(Ordering::SeqCst);
                         let stats_wait_failed = wait_errs.load(Ordering::SeqCst);
+                        let stats_wait_unknown = wait_unknowns.load(Ordering::SeqCst);
                         let stats_ongoing_waits = ongoing_waits.load(Ordering::SeqCst);
                         let stats_ongoing_broadcasts = ongoing_broadcasts.load(Ordering::SeqCst);
                         let hist_lock = hist.lock().await;
                         let stats_p50 = hist_lock.value_at_quantile(0.50) as f64;
                         let stats_p90 = hist_lock.value_at_quantile(0.90) as f64;
                         let stats_p95 = hist_lock.value_at_quantile(0.95) as f64;
+                        drop(hist_lock);
+
+                        // Check whether the network keeps up, for the run report. Offsets
+                        // are from the load start, so skip the init loops.
+                        if loop_index > 2 {
+                            let stats_elapsed_duration = load_start_time.elapsed();
+                            if let Some(height) = fetch_platform_block_height(sdk, &traffic).await {
+                                backlog_monitor.observe_height(height, stats_elapsed_duration);
+                            }
+                            let recent_waits = recent_wait_count.swap(0, Ordering::SeqCst);
+                            let recent_wait_total = recent_wait_seconds.swap(0, Ordering::SeqCst);
+                            backlog_monitor.observe_wait_latency(
+                                recent_wait_total
+                                    .checked_div(recent_waits)
+                                    .map(Duration::from_secs),
+                                stats_elapsed_duration,
+                            );
+                        }
+                        if let Some(controller) = load_controller.as_mut() {
+                            let mut window_hist_lock = window_hist.lock().await;
+                            if loop_index > 2 {
+                                controller.observe_window(
+                                    Duration::from_secs(window_hist_lock.value_at_quantile(0.95)),
+                                    window_hist_lock.len(),
+                                    window_start.elapsed(),
+                                );
+                            }
+                            window_hist_lock.reset();
+                            window_start = Instant::now();
+                        }
 
                         let mut stats_broadcast_error_messages = Vec::new();
                         for entry in broadcast_errors_per_code.iter() {
@@ -1364,6 +3115,25 @@ impl AppState {
                             let count = entry.value().load(Ordering::SeqCst);
                             stats_wait_error_messages.push(format!("{:?} - {}", code, count));
                         }
+                        for (category, count) in rejections.counts() {
+                            stats_wait_error_messages
+                                .push(format!("rejected, {} - {}", category, count));
+                        }
+                        *progress.lock().await = RunProgress {
+                            strategy_name: strategy_name.clone(),
+                            elapsed_secs: stats_elapsed,
+                            duration_secs: duration,
+                            transitions_attempted: stats_attempted.into(),
+                            broadcast_successful: stats_broadcast_successful,
+                            broadcast_failed: stats_broadcast_failed,
+                            broadcast_ongoing: stats_ongoing_broadcasts,
+                            wait_successful: stats_wait_successful,
+                            wait_failed: stats_wait_failed,
+                            wait_ongoing: stats_ongoing_waits,
+                            wait_unknown: stats_wait_unknown,
+                            wait_percentiles: [stats_p50, stats_p90, stats_p95],
+                            ..Default::default()
+                        };
                         let broadcast_error_message = if !stats_broadcast_error_messages.is_empty()
                         {
                             format!("{}", stats_broadcast_error_messages.join(", "))
@@ -1376,25 +3146,170 @@ impl AppState {
                             String::new()
                         };
 
-                        tracing::info!("\n\n{} secs passed. {} broadcast ({} tx/s)\nBroadcast results: {} successful, {} failed, {} ongoing.\nWait results: {} successful, {} failed, {} ongoing.\nBroadcast errors: {}\nWait errors: {}\nWait times (s): 50% - {} 90% - {} 95% - {}\n", stats_elapsed, stats_attempted, stats_rate, stats_broadcast_successful, stats_broadcast_failed, stats_ongoing_broadcasts, stats_wait_successful, stats_wait_failed, stats_ongoing_waits, broadcast_error_message, wait_error_message, stats_p50, stats_p90, stats_p95);
+                        tracing::info!("\n\n{} secs passed. {} broadcast ({} tx/s)\nBroadcast results: {} successful, {} failed, {} ongoing.\nWait results: {} successful, {} failed, {} ongoing, {} unknown.\nBroadcast errors: {}\nWait errors: {}\nWait times (s): 50% - {} 90% - {} 95% - {}\n", stats_elapsed, stats_attempted, stats_rate, stats_broadcast_successful, stats_broadcast_failed, stats_ongoing_broadcasts, stats_wait_successful, stats_wait_failed, stats_ongoing_waits, stats_wait_unknown, broadcast_error_message, wait_error_message, stats_p50, stats_p90, stats_p95);
                     }
 
                     let loop_start_time = Instant::now();
+                    let load_elapsed = load_start_time.elapsed();
+                    let measured = loop_index > 2
+                        && load_elapsed >= run_options.warm_up
+                        && load_elapsed + run_options.cool_down < Duration::from_secs(duration);
+                    if loop_index > 2 && load_profile != LoadProfile::Constant {
+                        let counters = PhaseCounters {
+                            attempted: transition_count.into(),
+                            broadcast_successful: broadcast_oks.load(Ordering::SeqCst),
+                            broadcast_failed: broadcast_errs.load(Ordering::SeqCst),
+                            included: wait_oks.load(Ordering::SeqCst),
+                        };
+                        phase_tracker
+                            .get_or_insert_with(|| {
+                                PhaseTracker::new(load_profile.clone(), counters)
+                            })
+                            .observe(load_elapsed, counters);
+                    }
+                    // Every transition in flight holds a spawned task until it's done, past
+                    // the cap new ones are only prepared once earlier ones finish
+                    if loop_index > 2 && run_options.wait.max_in_flight > 0 {
+                        let in_flight = || {
+                            (queued_broadcasts.load(Ordering::SeqCst)
+                                + ongoing_broadcasts.load(Ordering::SeqCst)
+                                + ongoing_waits.load(Ordering::SeqCst))
+                                as usize
+                        };
+                        if in_flight() >= run_options.wait.max_in_flight {
+                            throttled_loops += 1;
+                            let throttle_start = Instant::now();
+                            while in_flight() >= run_options.wait.max_in_flight && !*cancel.borrow()
+                            {
+                                tokio::time::sleep(IN_FLIGHT_POLL_INTERVAL).await;
+                            }
+                            throttled_time += throttle_start.elapsed();
+                        }
+                    }
                     let broadcast_oks_clone = broadcast_oks.clone();
                     let broadcast_errs_clone = broadcast_errs.clone();
                     let wait_oks_clone = wait_oks.clone();
                     let wait_errs_clone = wait_errs.clone();
+                    let wait_unknowns_clone = wait_unknowns.clone();
                     let ongoing_waits_clone = ongoing_waits.clone();
                     let ongoing_broadcasts_clone = ongoing_broadcasts.clone();
                     let success_count_clone = success_count.clone();
                     let identity_nonce_error_count_clone = identity_nonce_error_count.clone();
+                    let nonce_recovery_count_clone = nonce_recovery_count.clone();
+                    let refreshed_contract_nonces_clone = refreshed_contract_nonces.clone();
                     let insufficient_balance_error_count_clone =
                         insufficient_balance_error_count.clone();
+                    let top_ups_clone = top_ups.clone();
                     let local_rate_limit_error_count_clone = local_rate_limit_error_count.clone();
                     let broadcast_connection_error_count_clone =
                         broadcast_connection_error_count.clone();
                     let broadcast_errors_per_code_clone = broadcast_errors_per_code.clone();
                     let wait_errors_per_code_clone = wait_errors_per_code.clone();
+                    let rejections_clone = rejections.clone();
+                    let trace_clone = trace.clone();
+                    let transition_buffer_clone = transition_buffer.clone();
+                    let operation_metrics_clone = operation_metrics.clone();
+                    let traffic_clone = traffic.clone();
+
+                    // Get a lock on the local drive for the following two callbacks, taken for each
+                    // loop so runs going on at the same time take turns
+                    let drive_lock = self.drive.lock().await;
+
+                    // Callback used to fetch documents from the local Drive instance
+                    // Used for DocumentReplace and DocumentDelete transitions
+                    let mut document_query_callback = |query: LocalDocumentQuery| {
+                        match query {
+                            LocalDocumentQuery::RandomDocumentQuery(random_query) => {
+                                let document_type = random_query.document_type;
+                                let data_contract = random_query.data_contract;
+
+                                // A single tracked document, or none if all were taken
+                                if run_options.delete_policy != DeletePolicy::LocalDrive {
+                                    return document_tracker
+                                        .take(
+                                            run_options.delete_policy,
+                                            data_contract.id(),
+                                            document_type.name(),
+                                        )
+                                        .into_iter()
+                                        .collect();
+                                }
+
+                                // Construct a DriveQuery based on the document_type and
+                                // data_contract
+                                let drive_query = DriveDocumentQuery::any_item_query(
+                                    data_contract,
+                                    document_type.as_ref(),
+                                );
+
+                                // Query the Drive for documents
+                                match drive_lock.query_documents(
+                                    drive_query,
+                                    None,
+                                    false,
+                                    None,
+                                    None,
+                                ) {
+                                    Ok(outcome) => match outcome {
+                                        QueryDocumentsOutcome::V0(outcome_v0) => {
+                                            let documents = outcome_v0.documents_owned();
+                                            documents
+                                        }
+                                    },
+                                    Err(e) => {
+                                        tracing::debug!(
+                                            "Error fetching documents using DriveQuery: {:?}",
+                                            e
+                                        );
+                                        vec![]
+                                    }
+                                }
+                            }
+                        }
+                    };
+
+                    // Callback used to fetch identities from the local Drive instance
+                    // Is this ever used?
+                    let mut identity_fetch_callback =
+                        |identifier: Identifier, _keys_request: Option<IdentityKeysRequest>| {
+                            // Convert Identifier to a byte array format expected by the Drive
+                            // method
+                            let identity_id_bytes = identifier.into_buffer();
+
+                            // Fetch identity information from the Drive
+                            match drive_lock.fetch_identity_with_balance(
+                                identity_id_bytes,
+                                None,
+                                sdk.version(),
+                            ) {
+                                Ok(maybe_partial_identity) => {
+                                    let partial_identity =
+                                        maybe_partial_identity.unwrap_or_else(|| PartialIdentity {
+                                            id: identifier,
+                                            loaded_public_keys: BTreeMap::new(),
+                                            balance: None,
+                                            revision: None,
+                                            not_found_public_keys: BTreeSet::new(),
+                                        });
+                                    tracing::trace!(
+                                        "Fetched identity info for identifier {}: {:?}",
+                                        identifier,
+                                        partial_identity
+                                    );
+                                    partial_identity
+                                }
+                                Err(e) => {
+                                    tracing::debug!("Error fetching identity: {:?}", e);
+                                    PartialIdentity {
+                                        id: identifier,
+                                        loaded_public_keys: BTreeMap::new(),
+                                        balance: None,
+                                        revision: None,
+                                        not_found_public_keys: BTreeSet::new(),
+                                    }
+                                }
+                            }
+                        };
 
                     // Need to pass self.known_contracts to state_transitions_for_block
                     let mut known_contracts_lock = self.known_contracts.lock().await;
@@ -1402,8 +3317,42 @@ impl AppState {
                     let mempool_document_counter_lock = mempool_document_counter.lock().await;
                     let mut current_identities_lock = current_identities.lock().await;
 
+                    if frequency_profile.is_some()
+                        || load_controller.is_some()
+                        || load_profile != LoadProfile::Constant
+                    {
+                        let hour = (current_block_info.time_ms / 3_600_000) as usize % HOURS;
+                        let multiplier = frequency_profile
+                            .as_ref()
+                            .map_or(1.0, |profile| profile.multiplier(hour))
+                            * load_controller
+                                .as_ref()
+                                .map_or(1.0, |controller| controller.scale())
+                            * if loop_index > 2 {
+                                load_profile.multiplier(load_elapsed)
+                            } else {
+                                1.0
+                            };
+                        for (operation, base) in strategy
+                            .operations
+                            .iter_mut()
+                            .zip(weighted_frequencies.iter())
+                        {
+                            operation.frequency = scale_frequency(base, multiplier);
+                        }
+                    }
+
+                    // Continue from nonces fetched for retries of earlier loops, unless the
+                    // run already went past them
+                    for (key, nonce) in std::mem::take(&mut *refreshed_contract_nonces.lock().await)
+                    {
+                        let counter = contract_nonce_counter.entry(key).or_insert(nonce);
+                        *counter = (*counter).max(nonce);
+                    }
+
                     // Get the state transitions for the block (or second)
-                    let (transitions, finalize_operations, mut new_identities) = strategy
+                    generating.store(true, Ordering::SeqCst);
+                    let (mut transitions, finalize_operations, mut new_identities) = strategy
                         .state_transitions_for_block(
                             &mut document_query_callback,
                             &mut identity_fetch_callback,
@@ -1422,19 +3371,88 @@ impl AppState {
                             },
                             sdk.version(),
                         );
+                    generating.store(false, Ordering::SeqCst);
 
                     drop(known_contracts_lock);
                     drop(mempool_document_counter_lock);
 
+                    if !document_generators.is_empty() {
+                        let known_contracts = self.known_contracts.lock().await;
+                        let mut regenerated = 0;
+                        for transition in transitions.iter_mut() {
+                            if document_generators::regenerate_documents(
+                                transition,
+                                &document_generators,
+                                &known_contracts,
+                                &current_identities_lock,
+                                &signer,
+                                &mut rng,
+                            ) {
+                                regenerated += 1;
+                            }
+                        }
+                        if regenerated > 0 {
+                            tracing::debug!(
+                                "Regenerated the documents of {} batch transitions",
+                                regenerated
+                            );
+                        }
+                    }
+
                     // Add the identities that will be created to current_identities.
                     // Only do this on init block because identity_inserts don't have transfer keys atm
                     // and if we have transfer txs, it will panic if it tries to use one of these identities.
                     // TO-DO: This should be moved to execution after we confirm they were registered.
                     if loop_index < 3 {
+                        for (id_before, (contract, _)) in
+                            start_contract_ids.iter().zip(&strategy.start_contracts)
+                        {
+                            if let Some(alias) = contract_aliases.get(id_before).cloned() {
+                                contract_aliases
+                                    .entry(contract.data_contract().id())
+                                    .or_insert(alias);
+                            }
+                        }
+
                         for identity in &new_identities {
                             new_identity_ids.push(identity.id().to_string(Encoding::Base58))
                         }
                         current_identities_lock.append(&mut new_identities);
+
+                        // Start contracts left on chain by an earlier run are reused instead of
+                        // paying for a create that fails as a duplicate. The identity nonce their
+                        // create took is left unused, Platform accepts the gap.
+                        let existing_contracts = existing_contracts(sdk, &transitions).await;
+                        if !existing_contracts.is_empty() {
+                            let mut known_contracts = self.known_contracts.lock().await;
+                            for contract in &existing_contracts {
+                                let id = contract.id().to_string(Encoding::Base58);
+                                tracing::info!("Start contract {} already exists, reusing it", id);
+                                known_contracts.insert(id.clone(), contract.clone());
+                                let result = drive_lock.apply_contract(
+                                    contract,
+                                    current_block_info,
+                                    true,
+                                    None,
+                                    None,
+                                    sdk.version(),
+                                );
+                                if let Err(e) = result {
+                                    tracing::debug!("Failed to add contract to local drive: {e}");
+                                }
+                                reused_contract_ids.push(id);
+                            }
+                            drop(known_contracts);
+                            transitions.retain(|transition| match transition {
+                                StateTransition::DataContractCreate(contract_create_transition) => {
+                                    let id = contract_create_transition.data_contract().id();
+                                    !existing_contracts
+                                        .iter()
+                                        .any(|contract| contract.id() == id)
+                                }
+                                _ => true,
+                            });
+                        }
                     }
 
                     // Extra transition type-specific processing
@@ -1519,6 +3537,16 @@ impl AppState {
                                                             let maybe_document = Document::try_from_create_transition_v0(document_create_transition_v0, transition.owner_id(), &current_block_info, &document_type, sdk.version());
                                                             match maybe_document {
                                                                 Ok(document) => {
+                                                                    if run_options.delete_policy
+                                                                        != DeletePolicy::LocalDrive
+                                                                    {
+                                                                        document_tracker
+                                                                            .record_created(
+                                                                                data_contract_id,
+                                                                                document_type_name,
+                                                                                document.clone(),
+                                                                            );
+                                                                    }
                                                                     let document_info = DocumentInfo::DocumentOwnedInfo((document, None));
                                                                     let owned_document_info =
                                                                         OwnedDocumentInfo {
@@ -1560,6 +3588,41 @@ impl AppState {
                                                 }
                                             }
                                         }
+                                        // Keep the local copy at the new revision and
+                                        // owner so later replaces and transfers build on it
+                                        DocumentTransition::Replace(_)
+                                        | DocumentTransition::Transfer(_) => {
+                                            let known_contracts = self.known_contracts.lock().await;
+                                            match update_local_document(
+                                                &drive_lock,
+                                                &known_contracts,
+                                                document_transition,
+                                                transition.owner_id(),
+                                                &current_block_info,
+                                                sdk.version(),
+                                            ) {
+                                                // The document was taken from the tracked ones
+                                                // to be updated, it can be targeted again
+                                                Ok((
+                                                    data_contract_id,
+                                                    document_type_name,
+                                                    document,
+                                                )) => {
+                                                    if run_options.delete_policy
+                                                        != DeletePolicy::LocalDrive
+                                                    {
+                                                        document_tracker.record_created(
+                                                            data_contract_id,
+                                                            &document_type_name,
+                                                            document,
+                                                        );
+                                                    }
+                                                }
+                                                Err(e) => tracing::debug!(
+                                                    "Failed to update document in local drive: {e}"
+                                                ),
+                                            }
+                                        }
                                         _ => {
                                             // nothing
                                         }
@@ -1572,6 +3635,8 @@ impl AppState {
                         };
                     }
 
+                    drop(drive_lock);
+
                     // Process each FinalizeBlockOperation, which so far is just adding keys to identities
                     for operation in finalize_operations {
                         match operation {
@@ -1588,15 +3653,53 @@ impl AppState {
                         }
                     }
 
-                    // Update the loaded_identity_clone and loaded_identity_lock with the latest state of the identity
+                    // Key maintenance operations, on identities already registered
+                    if loop_index > 2 && !current_identities_lock.is_empty() {
+                        for op in &key_maintenance_ops {
+                            if !rng.gen_bool(op.chance_per_block.clamp(0.0, 1.0)) {
+                                continue;
+                            }
+                            let index = rng.gen_range(0..current_identities_lock.len());
+                            match key_maintenance_transition(
+                                op,
+                                &mut current_identities_lock[index],
+                                &mut identity_nonce_counter,
+                                &mut signer,
+                                &mut rng,
+                                current_block_info.time_ms,
+                                sdk.version(),
+                            ) {
+                                Ok(transition) => transitions.push(transition),
+                                Err(e) => {
+                                    tracing::warn!("Skipped key maintenance operation: {}", e)
+                                }
+                            }
+                        }
+                    }
+
+                    // Update the loaded_identity_clone and the loaded identity of the state, unless
+                    // another one was loaded meanwhile, with the latest state of the identity
                     if let Some(modified_identity) = current_identities_lock
                         .iter()
                         .find(|identity| identity.id() == loaded_identity_clone.id())
                     {
                         loaded_identity_clone = modified_identity.clone();
-                        *loaded_identity_lock = modified_identity.clone();
+                        if let Some(state_identity) = self
+                            .loaded_identity
+                            .lock()
+                            .await
+                            .as_mut()
+                            .filter(|identity| identity.id() == modified_identity.id())
+                        {
+                            *state_identity = modified_identity.clone();
+                        }
                     }
 
+                    transition_buffer.record_generated(
+                        &run_name,
+                        &transitions,
+                        &current_identities_lock,
+                    );
                     drop(current_identities_lock);
 
                     // Now process the state transitions
@@ -1609,12 +3712,36 @@ impl AppState {
 
                         // A queue for the state transitions for the block (or second)
                         let st_queue: VecDeque<StateTransition> = transitions.clone().into();
+                        queued_broadcasts.fetch_add(st_queue.len() as u64, Ordering::SeqCst);
+                        if measured {
+                            measured_attempted += st_queue.len() as u64;
+                        }
 
                         // We will concurrently broadcast the state transitions, so collect the futures
                         let mut broadcast_futures = Vec::new();
+                        // Broadcasts past the concurrency limit queue for a permit
+                        let broadcast_permits =
+                            Semaphore::new(match run_options.wait.max_concurrent_broadcasts {
+                                0 => Semaphore::MAX_PERMITS,
+                                limit => limit,
+                            });
+                        let broadcast_permits = &broadcast_permits;
+                        // Used to sign again transitions retried with a fresh nonce
+                        let retry_signer = &signer;
 
                         for transition in st_queue.iter() {
                             transition_count += 1; // Used for logging how many transitions we attempted
+                            let transition = match run_options.user_fee_increase {
+                                0 => transition.clone(),
+                                user_fee_increase => with_user_fee_increase(
+                                    transition,
+                                    user_fee_increase,
+                                    &signer,
+                                    &current_identities,
+                                )
+                                .await
+                                .unwrap_or_else(|| transition.clone()),
+                            };
                             let transition_clone = transition.clone();
                             let transition_id = hex::encode(
                                 transition
@@ -1629,42 +3756,103 @@ impl AppState {
                             let broadcast_oks = broadcast_oks_clone.clone();
                             let broadcast_errs = broadcast_errs_clone.clone();
                             let ongoing_broadcasts = ongoing_broadcasts_clone.clone();
+                            let queued_broadcasts = queued_broadcasts.clone();
+                            let measured_broadcast_oks = measured_broadcast_oks.clone();
                             let success_count = success_count_clone.clone();
                             let identity_nonce_error_count =
                                 identity_nonce_error_count_clone.clone();
+                            let nonce_recovery_count = nonce_recovery_count_clone.clone();
+                            let refreshed_contract_nonces = refreshed_contract_nonces_clone.clone();
                             let insufficient_balance_error_count =
                                 insufficient_balance_error_count_clone.clone();
+                            let top_ups = top_ups_clone.clone();
                             let local_rate_limit_error_count =
                                 local_rate_limit_error_count_clone.clone();
                             let broadcast_connection_error_count =
                                 broadcast_connection_error_count_clone.clone();
                             let broadcast_errors_per_code = broadcast_errors_per_code_clone.clone();
-
-                            let mut request_settings = RequestSettings::default();
+                            let mirror = mirror.clone();
+                            let reserve = reserve.clone();
+                            let wallet_spent = wallet_spent.clone();
+                            let run = &run;
+                            let rate_limiter = rate_limiter.clone().filter(|_| loop_index > 2);
+                            let trace = trace_clone.clone().filter(|_| loop_index > 2);
+                            let transition_buffer = transition_buffer_clone.clone();
+                            let run_name = Arc::clone(&run_name);
+                            let operation_metrics = operation_metrics_clone.clone();
+                            let traffic = traffic_clone.clone();
+
+                            let mut request_settings = RequestSettings {
+                                ban_failed_address: Some(run_options.ban_failed_addresses),
+                                ..Default::default()
+                            };
                             // Time-based strategy body
                             if loop_index != 1 && loop_index != 2 {
                                 // time mode loading
-                                request_settings.connect_timeout = Some(Duration::from_millis(
-                                    min(seconds_per_loop * 1000 / 2, 3000),
-                                ));
-                                request_settings.timeout = Some(Duration::from_millis(min(
-                                    seconds_per_loop * 1000 / 2,
-                                    3000,
-                                )));
-                                request_settings.retries = Some(0);
+                                let (connect_timeout, timeout) =
+                                    retry_policy.broadcast_timeouts(seconds_per_loop);
+                                request_settings.connect_timeout = Some(connect_timeout);
+                                request_settings.timeout = Some(timeout);
+                                request_settings.retries = Some(retry_policy.broadcast_retries);
                             }
 
                             // Prepare futures for broadcasting transitions
                             let future = async move {
+                                if let Some(rate_limiter) = &rate_limiter {
+                                    rate_limiter.acquire().await;
+                                }
+                                let _permit = broadcast_permits
+                                    .acquire()
+                                    .await
+                                    .expect("broadcast permits are never closed");
+                                queued_broadcasts.fetch_sub(1, Ordering::SeqCst);
                                 match transition_clone.broadcast_request_for_state_transition() {
                                     Ok(broadcast_request) => {
                                         ongoing_broadcasts.fetch_add(1, Ordering::SeqCst);
+                                        let broadcast_at_ms = transition_trace::unix_time_ms();
+                                        let broadcast_start = Instant::now();
+                                        let request_size = broadcast_request.encoded_len();
                                         let broadcast_result = broadcast_request.execute(&sdk.clone(), request_settings).await;
+                                        traffic.record(RequestCategory::Broadcast, request_size, broadcast_result.as_ref().map_or(0, |response| response.inner.encoded_len()));
+
+                                        // A document broadcast rejected because of its identity contract nonce is
+                                        // retried once with a nonce fetched from Platform
+                                        let (transition_clone, broadcast_result) = match broadcast_result {
+                                            Err(e) if transition_clone.name() == "DocumentsBatch" && rejections::broadcast_consensus_error(&e.inner).is_some_and(|error| rejections::is_nonce_error(&error)) => {
+                                                match renonce_documents_batch(sdk, &transition_clone, retry_signer, &current_identities_clone, &refreshed_contract_nonces).await {
+                                                    Some(retried_transition) => {
+                                                        tracing::debug!("Retrying {} transition with a fresh nonce after: {:?}. ID: {}", transition_clone.name(), e, transition_id);
+                                                        let retried_result = match retried_transition.broadcast_request_for_state_transition() {
+                                                            Ok(request) => {
+                                                                let request_size = request.encoded_len();
+                                                                let result = request.execute(&sdk.clone(), request_settings).await;
+                                                                traffic.record(RequestCategory::Broadcast, request_size, result.as_ref().map_or(0, |response| response.inner.encoded_len()));
+                                                                result
+                                                            }
+                                                            Err(_) => Err(e),
+                                                        };
+                                                        if retried_result.is_ok() {
+                                                            nonce_recovery_count.fetch_add(1, Ordering::SeqCst);
+                                                        }
+                                                        (retried_transition, retried_result)
+                                                    }
+                                                    None => (transition_clone, Err(e)),
+                                                }
+                                            }
+                                            broadcast_result => (transition_clone, broadcast_result),
+                                        };
                                         ongoing_broadcasts.fetch_sub(1, Ordering::SeqCst);
+                                        if let Some(mirror) = &mirror {
+                                            mirror.mirror(&transition_clone, &transition_id, &broadcast_result, request_settings).await;
+                                        }
                                         match broadcast_result {
                                             Ok(_) => {
                                                 broadcast_oks.fetch_add(1, Ordering::SeqCst);
                                                 success_count.fetch_add(1, Ordering::SeqCst);
+                                                if measured {
+                                                    measured_broadcast_oks.fetch_add(1, Ordering::SeqCst);
+                                                    operation_metrics.record_broadcast(&transition_clone.name(), true);
+                                                }
                                                 let transition_owner_id = transition_clone.owner_id().to_string(Encoding::Base58);
                                                 if loop_index != 1 && loop_index != 2 {
                                                     tracing::trace!("Successfully broadcasted transition: {}. ID: {}. Owner ID: {:?}", transition_clone.name(), transition_id, transition_owner_id);
@@ -1675,6 +3863,8 @@ impl AppState {
                                                             match document_transition {
                                                                 DocumentTransition::Create(DocumentCreateTransition::V0(create_tx)) => create_tx.base.data_contract_id(),
                                                                 DocumentTransition::Delete(DocumentDeleteTransition::V0(delete_tx)) => delete_tx.base.data_contract_id(),
+                                                                DocumentTransition::Replace(DocumentReplaceTransition::V0(replace_tx)) => replace_tx.base.data_contract_id(),
+                                                                DocumentTransition::Transfer(DocumentTransferTransition::V0(transfer_tx)) => transfer_tx.base.data_contract_id(),
                                                                 _ => panic!("This should never happen")
                                                             }
                                                         ).collect_vec(),
@@ -1687,7 +3877,7 @@ impl AppState {
                                                         // tracing::trace!(" + Incremented identity {} tx counter for contract {}. Count: {}", transition_owner_id, contract_id.to_string(Encoding::Base58), count);
                                                     }
                                                 }
-                                                Ok((transition_clone, broadcast_result))
+                                                Ok((transition_clone, broadcast_result, (broadcast_at_ms, broadcast_start.elapsed())))
                                             },
                                             Err(e) => {
                                                 match e.inner {
@@ -1705,13 +3895,43 @@ impl AppState {
                                                     }
                                                 };
                                                 broadcast_errs.fetch_add(1, Ordering::SeqCst);
+                                                if measured {
+                                                    operation_metrics.record_broadcast(&transition_clone.name(), false);
+                                                }
                                                 tracing::error!("Error: Failed to broadcast {} transition: {:?}. ID: {}", transition_clone.name(), e, transition_id);
+                                                if let Some(trace) = &trace {
+                                                    trace.record(&TraceRecord {
+                                                        tx_id: transition_id.clone(),
+                                                        transition_type: transition_clone.name().to_owned(),
+                                                        owner_id: transition_clone.owner_id().to_string(Encoding::Base58),
+                                                        broadcast_at_ms,
+                                                        broadcast_ms: broadcast_start.elapsed().as_millis() as u64,
+                                                        wait_ms: None,
+                                                        result: TraceResult::BroadcastFailed,
+                                                        error: Some(e.to_string()),
+                                                    });
+                                                }
+                                                transition_buffer.record_failed(&run_name, &transition_clone, e.to_string());
                                                 if e.to_string().contains("Insufficient identity") {
                                                     insufficient_balance_error_count.fetch_add(1, Ordering::SeqCst);
                                                     // Top up. This logic works but it slows the broadcasting down slightly.
-                                                    if top_up_amount > 0 {
+                                                    // A transfer from the reserve is a single transition, unlike an asset lock.
+                                                    if let Some(reserve) = &reserve {
+                                                        let top_up_identity_id = transition_clone.owner_id();
+                                                        let result = reserve.top_up(sdk, top_up_identity_id).await;
+                                                        if let Err(e) = &result {
+                                                            tracing::debug!("Failed to top up identity from the reserve: {}", e);
+                                                        }
+                                                        top_ups.lock().await.push(TopUpRecord {
+                                                            identity_id: top_up_identity_id,
+                                                            credits: reserve.credits(),
+                                                            funding: TopUpFunding::Reserve(reserve.identity_id()),
+                                                            success: result.is_ok(),
+                                                        });
+                                                    } else if top_up_amount > 0 {
                                                         let current_identities = Arc::clone(&current_identities_clone);
                                                         let sdk_clone = sdk.clone();
+                                                        let traffic = traffic.clone();
                                                         let (tx, rx) = oneshot::channel();
 
                                                         // Lock the wallet and clone the necessary data before moving into the async block
@@ -1721,11 +3941,19 @@ impl AppState {
                                                         {
                                                             let mut wallet_lock = self.loaded_wallet.lock().await;
                                                             let wallet = wallet_lock.as_mut().unwrap();
-                                                            let (asset_lock_tx, asset_lock_proof_key) = wallet.asset_lock_transaction(None, top_up_amount).unwrap();
+                                                            let balance_before = wallet.balance();
+                                                            let (asset_lock_tx, asset_lock_proof_key) = wallet
+                                                                .without_utxos(&run.utxos_of_other_runs(), |wallet| {
+                                                                    wallet.asset_lock_transaction(None, top_up_amount)
+                                                                })
+                                                                .unwrap();
+                                                            wallet_spent.fetch_add(balance_before.saturating_sub(wallet.balance()), Ordering::SeqCst);
                                                             asset_lock_transaction = asset_lock_tx.clone();
                                                             asset_lock_proof_private_key = asset_lock_proof_key.clone();
                                                             wallet_receive_address = wallet.receive_address();
                                                         }
+                                                        let top_up_identity_id = transition_clone.owner_id();
+                                                        let top_up_tx_id = asset_lock_transaction.txid().to_string();
 
                                                         // Spawn a blocking task for the top-up process
                                                         tokio::task::spawn_blocking(move || {
@@ -1751,16 +3979,26 @@ impl AppState {
 
                                                                             let request = state_transition.broadcast_request_for_state_transition().expect("Expected to create broadcast request for top up");
 
-                                                                            match request
+                                                                            let request_size = request.encoded_len();
+                                                                            let top_up_result = request
                                                                                 .clone()
                                                                                 .execute(&sdk_clone, RequestSettings::default())
-                                                                                .await {
-                                                                                    Ok(_) => tracing::trace!("Successfully topped up identity"),
-                                                                                    Err(e) => tracing::debug!("Failed to top up identity: {:?}", e)
-                                                                                };
+                                                                                .await;
+                                                                            traffic.record(RequestCategory::Broadcast, request_size, top_up_result.as_ref().map_or(0, |response| response.inner.encoded_len()));
+                                                                            match top_up_result {
+                                                                                    Ok(_) => {
+                                                                                        tracing::trace!("Successfully topped up identity");
+                                                                                        true
+                                                                                    }
+                                                                                    Err(e) => {
+                                                                                        tracing::debug!("Failed to top up identity: {:?}", e);
+                                                                                        false
+                                                                                    }
+                                                                                }
                                                                         }
                                                                         Err(_) => {
                                                                             tracing::debug!("Failed to obtain asset lock proof for top up");
+                                                                            false
                                                                         }
                                                                     }
                                                                 })
@@ -1771,7 +4009,13 @@ impl AppState {
                                                         });
 
                                                         // Await the result of the blocking task
-                                                        let _ = rx.await;
+                                                        let success = rx.await.unwrap_or(false);
+                                                        top_ups.lock().await.push(TopUpRecord {
+                                                            identity_id: top_up_identity_id,
+                                                            credits: top_up_amount * 1000,
+                                                            funding: TopUpFunding::AssetLock(top_up_tx_id),
+                                                            success,
+                                                        });
 
                                                     }
                                                 } else if e.to_string().contains("invalid identity nonce") {
@@ -1801,19 +4045,21 @@ impl AppState {
 
                         // Now wait for results
 
-                        // If we're in block mode, or index 1 or 2 of time mode
+                        // If we're in block mode, or index 1 or 2 of time mode.
+                        // Registrations broadcast are waited for even if the run is cancelled
+                        // meanwhile, so the identities created are known and withdrawn from.
                         if loop_index == 1 || loop_index == 2 {
                             let request_settings = RequestSettings {
                                 connect_timeout: None,
-                                timeout: Some(Duration::from_secs(75)),
-                                retries: Some(5),
-                                ban_failed_address: Some(false),
+                                timeout: Some(retry_policy.wait_timeout()),
+                                retries: Some(retry_policy.wait_retries),
+                                ban_failed_address: Some(run_options.ban_failed_addresses),
                             };
 
                             let mut wait_futures = Vec::new();
                             for (tx_index, result) in broadcast_results.into_iter().enumerate() {
                                 match result {
-                                    Ok((transition, broadcast_result)) => {
+                                    Ok((transition, broadcast_result, _)) => {
                                         let transition_type = transition.name().to_owned();
                                         let transition_id = hex::encode(
                                             transition
@@ -1874,14 +4120,26 @@ impl AppState {
                                         };
                                         drop(known_contracts_lock);
 
+                                        let traffic = traffic_clone.clone();
                                         let wait_future = async move {
                                             let wait_result = match transition
                                                 .wait_for_state_transition_result_request()
                                             {
                                                 Ok(wait_request) => {
-                                                    wait_request
+                                                    let request_size = wait_request.encoded_len();
+                                                    let wait_result = wait_request
                                                         .execute(sdk, request_settings)
-                                                        .await
+                                                        .await;
+                                                    traffic.record(
+                                                        RequestCategory::Wait,
+                                                        request_size,
+                                                        wait_result
+                                                            .as_ref()
+                                                            .map_or(0, |response| {
+                                                                response.inner.encoded_len()
+                                                            }),
+                                                    );
+                                                    wait_result
                                                 }
                                                 Err(e) => {
                                                     tracing::debug!(
@@ -2003,22 +4261,33 @@ impl AppState {
                             // Time mode when index is greater than 2
                             let request_settings = RequestSettings {
                                 connect_timeout: None,
-                                timeout: Some(Duration::from_secs(75)),
-                                retries: Some(5),
-                                ban_failed_address: Some(false),
+                                timeout: Some(retry_policy.wait_timeout()),
+                                retries: Some(retry_policy.wait_retries),
+                                ban_failed_address: Some(run_options.ban_failed_addresses),
                             };
 
                             let sdk_clone = sdk.clone();
                             for (tx_index, result) in broadcast_results.into_iter().enumerate() {
                                 let wait_oks = wait_oks_clone.clone();
                                 let wait_errs = wait_errs_clone.clone();
+                                let wait_unknowns = wait_unknowns_clone.clone();
                                 let ongoing_waits = ongoing_waits_clone.clone();
                                 let wait_errors_per_code = wait_errors_per_code_clone.clone();
+                                let rejections = rejections_clone.clone();
+                                let trace = trace_clone.clone();
+                                let transition_buffer = transition_buffer_clone.clone();
+                                let run_name = Arc::clone(&run_name);
+                                let operation_metrics = operation_metrics_clone.clone();
+                                let traffic = traffic_clone.clone();
 
                                 let mempool_document_counter_clone =
                                     mempool_document_counter.clone();
                                 match result {
-                                    Ok((transition, _broadcast_result)) => {
+                                    Ok((
+                                        transition,
+                                        _broadcast_result,
+                                        (broadcast_at_ms, broadcast_time),
+                                    )) => {
                                         let transition_type = transition.name().to_owned();
                                         let transition_id = hex::encode(
                                             transition
@@ -2028,9 +4297,15 @@ impl AppState {
                                         .to_string()
                                         .reverse();
                                         let sdk_clone_inner = sdk_clone.clone();
+                                        let (give_up_sender, give_up) = oneshot::channel::<()>();
+                                        pending_waits.push_back(give_up_sender);
 
                                         tokio::spawn({
                                             let hist = Arc::clone(&hist);
+                                            let window_hist = Arc::clone(&window_hist);
+                                            let recent_wait_seconds =
+                                                Arc::clone(&recent_wait_seconds);
+                                            let recent_wait_count = Arc::clone(&recent_wait_count);
 
                                             async move {
                                                 if let Ok(wait_request) = transition
@@ -2038,15 +4313,106 @@ impl AppState {
                                                 {
                                                     ongoing_waits.fetch_add(1, Ordering::SeqCst);
                                                     let wait_start_time = Instant::now();
-                                                    match wait_request
-                                                        .execute(&sdk_clone_inner, request_settings)
-                                                        .await
-                                                    {
-                                                        Ok(wait_response) => {
+                                                    let request_size = wait_request.encoded_len();
+                                                    let wait_result = tokio::select! {
+                                                        result = wait_request.execute(&sdk_clone_inner, request_settings) => Some(result),
+                                                        Ok(()) = give_up => None,
+                                                    };
+                                                    // A wait given up on was still sent
+                                                    traffic.record(
+                                                        RequestCategory::Wait,
+                                                        request_size,
+                                                        match &wait_result {
+                                                            Some(Ok(response)) => {
+                                                                response.inner.encoded_len()
+                                                            }
+                                                            _ => 0,
+                                                        },
+                                                    );
+                                                    if let Some(trace) = &trace {
+                                                        let (result, error) = match &wait_result {
+                                                            None => (TraceResult::WaitGivenUp, None),
+                                                            Some(Ok(wait_response)) => match &wait_response.inner.version {
+                                                                Some(wait_for_state_transition_result_response::Version::V0(v0_response)) => match &v0_response.result {
+                                                                    Some(wait_for_state_transition_result_response_v0::Result::Error(e)) => {
+                                                                        let (category, message) = rejections::categorize(e);
+                                                                        (TraceResult::Rejected, Some(format!("{}: {}", category, message)))
+                                                                    }
+                                                                    _ => (TraceResult::Included, None),
+                                                                },
+                                                                None => (TraceResult::Included, None),
+                                                            },
+                                                            Some(Err(e)) => (TraceResult::WaitFailed, Some(e.to_string())),
+                                                        };
+                                                        trace.record(&TraceRecord {
+                                                            tx_id: transition_id.clone(),
+                                                            transition_type: transition_type
+                                                                .clone(),
+                                                            owner_id: transition
+                                                                .owner_id()
+                                                                .to_string(Encoding::Base58),
+                                                            broadcast_at_ms,
+                                                            broadcast_ms: broadcast_time.as_millis()
+                                                                as u64,
+                                                            wait_ms: Some(
+                                                                wait_start_time
+                                                                    .elapsed()
+                                                                    .as_millis()
+                                                                    as u64,
+                                                            ),
+                                                            result,
+                                                            error,
+                                                        });
+                                                    }
+                                                    match wait_result {
+                                                        None => {
+                                                            wait_unknowns
+                                                                .fetch_add(1, Ordering::SeqCst);
+                                                            ongoing_waits
+                                                                .fetch_sub(1, Ordering::SeqCst);
+                                                            tracing::debug!(" >>> Gave up waiting for transition, too many outstanding waits. ID: {}", transition_id);
+                                                            // The transition may still be processed, but don't hold its owner back
+                                                            let mut mempool_document_counter_lock =
+                                                                mempool_document_counter_clone
+                                                                    .lock()
+                                                                    .await;
+                                                            for contract_id in
+                                                                documents_batch_contract_ids(
+                                                                    &transition,
+                                                                )
+                                                            {
+                                                                if let Some(count) =
+                                                                    mempool_document_counter_lock
+                                                                        .get_mut(&(
+                                                                            transition.owner_id(),
+                                                                            contract_id,
+                                                                        ))
+                                                                {
+                                                                    *count =
+                                                                        count.saturating_sub(1);
+                                                                }
+                                                            }
+                                                        }
+                                                        Some(Ok(wait_response)) => {
                                                             let wait_time =
                                                                 wait_start_time.elapsed().as_secs();
-                                                            let mut hist_lock = hist.lock().await;
-                                                            hist_lock.record(wait_time).unwrap();
+                                                            if measured {
+                                                                hist.lock()
+                                                                    .await
+                                                                    .record(wait_time)
+                                                                    .unwrap();
+                                                            }
+                                                            window_hist
+                                                                .lock()
+                                                                .await
+                                                                .record(wait_time)
+                                                                .unwrap();
+                                                            recent_wait_seconds.fetch_add(
+                                                                wait_time,
+                                                                Ordering::SeqCst,
+                                                            );
+                                                            recent_wait_count
+                                                                .fetch_add(1, Ordering::SeqCst);
 
                                                             if let Some(wait_for_state_transition_result_response::Version::V0(v0_response)) = &wait_response.inner.version {
                                                             if let Some(wait_for_state_transition_result_response_v0::Result::Proof(_proof)) = &v0_response.result {
@@ -2054,6 +4420,9 @@ impl AppState {
                                                                 // Decrement the transitions counter
                                                                 wait_oks.fetch_add(1, Ordering::SeqCst);
                                                                 ongoing_waits.fetch_sub(1, Ordering::SeqCst);
+                                                                if measured {
+                                                                    operation_metrics.record_wait(&transition_type, WaitOutcome::Included(wait_start_time.elapsed()));
+                                                                }
                                                                 tracing::trace!(" >>> Transition was included in a block. ID: {}", transition_id);
                                                                 if transition_type == "DocumentsBatch" {
                                                                     let contract_ids = match transition.clone() {
@@ -2061,6 +4430,8 @@ impl AppState {
                                                                             match document_transition {
                                                                                 DocumentTransition::Create(DocumentCreateTransition::V0(create_tx)) => create_tx.base.data_contract_id(),
                                                                                 DocumentTransition::Delete(DocumentDeleteTransition::V0(delete_tx)) => delete_tx.base.data_contract_id(),
+                                                                                DocumentTransition::Replace(DocumentReplaceTransition::V0(replace_tx)) => replace_tx.base.data_contract_id(),
+                                                                                DocumentTransition::Transfer(DocumentTransferTransition::V0(transfer_tx)) => transfer_tx.base.data_contract_id(),
                                                                                 _ => panic!("This should never happen")
                                                                             }
                                                                         ).collect_vec(),
@@ -2076,13 +4447,21 @@ impl AppState {
                                                             } else if let Some(wait_for_state_transition_result_response_v0::Result::Error(e)) = &v0_response.result {
                                                                 wait_errs.fetch_add(1, Ordering::SeqCst);
                                                                 ongoing_waits.fetch_sub(1, Ordering::SeqCst);
-                                                                tracing::debug!(" >>> Transition failed in mempool with error: {:?}. ID: {}", e, transition_id);
+                                                                let (category, message) = rejections::categorize(e);
+                                                                rejections.record(category);
+                                                                if measured {
+                                                                    operation_metrics.record_wait(&transition_type, WaitOutcome::Rejected);
+                                                                }
+                                                                tracing::debug!(" >>> Transition rejected, {}: {}. ID: {}", category, message, transition_id);
+                                                                transition_buffer.record_failed(&run_name, &transition, format!("{}: {}", category, message));
                                                                 if transition_type == "DocumentsBatch" {
                                                                     let contract_ids = match transition.clone() {
                                                                         StateTransition::Batch(BatchTransition::V0(transition)) => transition.transitions.iter().map(|document_transition|
                                                                             match document_transition {
                                                                                 DocumentTransition::Create(DocumentCreateTransition::V0(create_tx)) => create_tx.base.data_contract_id(),
                                                                                 DocumentTransition::Delete(DocumentDeleteTransition::V0(delete_tx)) => delete_tx.base.data_contract_id(),
+                                                                                DocumentTransition::Replace(DocumentReplaceTransition::V0(replace_tx)) => replace_tx.base.data_contract_id(),
+                                                                                DocumentTransition::Transfer(DocumentTransferTransition::V0(transfer_tx)) => transfer_tx.base.data_contract_id(),
                                                                                 _ => panic!("This should never happen")
                                                                             }
                                                                         ).collect_vec(),
@@ -2098,15 +4477,24 @@ impl AppState {
                                                             } else {
                                                                 wait_oks.fetch_add(1, Ordering::SeqCst);
                                                                 ongoing_waits.fetch_sub(1, Ordering::SeqCst);
+                                                                if measured {
+                                                                    operation_metrics.record_wait(&transition_type, WaitOutcome::Included(wait_start_time.elapsed()));
+                                                                }
                                                                 tracing::trace!(" >>> Received empty response for transition with ID: {}", transition_id);
                                                             }
                                                         }
                                                         }
-                                                        Err(e) => {
+                                                        Some(Err(e)) => {
                                                             wait_errs
                                                                 .fetch_add(1, Ordering::SeqCst);
                                                             ongoing_waits
                                                                 .fetch_sub(1, Ordering::SeqCst);
+                                                            if measured {
+                                                                operation_metrics.record_wait(
+                                                                    &transition_type,
+                                                                    WaitOutcome::Failed,
+                                                                );
+                                                            }
                                                             match e.inner {
                                                                 rs_dapi_client::DapiClientError::Transport(ref e, ..) => {wait_errors_per_code
                                                                     .entry(match e {
@@ -2135,6 +4523,16 @@ impl AppState {
                                     }
                                 }
                             }
+
+                            // Give up on the oldest waits still in progress when there are too many
+                            if run_options.wait.max_outstanding > 0 {
+                                pending_waits.retain(|sender| !sender.is_closed());
+                                while pending_waits.len() > run_options.wait.max_outstanding {
+                                    if let Some(sender) = pending_waits.pop_front() {
+                                        let _ = sender.send(());
+                                    }
+                                }
+                            }
                         }
 
                         // Reset the load_start_time
@@ -2143,7 +4541,10 @@ impl AppState {
                             tracing::trace!(
                                 "Sleep 10 seconds to allow initialization transactions to process"
                             );
-                            tokio::time::sleep(Duration::from_secs(10)).await;
+                            tokio::select! {
+                                _ = tokio::time::sleep(Duration::from_secs(10)) => {}
+                                _ = cancel.wait_for(|cancelled| *cancelled) => {}
+                            }
                             load_start_time = Instant::now();
                         }
                     } else {
@@ -2164,11 +4565,21 @@ impl AppState {
                     current_block_info.time_ms = current_time_ms as u64;
                     loop_index += 1;
 
-                    // Make sure the loop doesn't iterate faster than once per seconds_per_loop in time mode
+                    // Make sure the loop doesn't iterate faster than once per seconds_per_loop in time mode.
+                    // With a target rate the broadcasts are paced already and sleeping would
+                    // leave tokens unused, unless the loop had nothing to broadcast.
                     let elapsed = loop_start_time.elapsed();
-                    if elapsed < Duration::from_secs(seconds_per_loop) {
+                    if elapsed < Duration::from_secs(seconds_per_loop)
+                        && (rate_limiter.is_none() || transitions.is_empty())
+                    {
                         let remaining_time = Duration::from_secs(seconds_per_loop) - elapsed;
-                        tokio::time::sleep(remaining_time).await;
+                        tokio::select! {
+                            _ = tokio::time::sleep(remaining_time) => {}
+                            _ = cancel.wait_for(|cancelled| *cancelled) => {}
+                        }
+                    }
+                    if measured {
+                        measured_time += loop_start_time.elapsed();
                     }
                 }
 
@@ -2191,6 +4602,35 @@ impl AppState {
                     duration
                 );
 
+                // Stop sampling so the last snapshot written is the final one
+                drop(sampler_guard);
+                let _ = progress_sampler.await;
+                let hist_lock = hist.lock().await;
+                let final_progress = RunProgress {
+                    strategy_name: strategy_name.clone(),
+                    elapsed_secs: load_execution_run_time.as_secs(),
+                    duration_secs: duration,
+                    transitions_attempted: transition_count.into(),
+                    broadcast_successful: broadcast_oks.load(Ordering::SeqCst),
+                    broadcast_failed: broadcast_errs.load(Ordering::SeqCst),
+                    broadcast_ongoing: ongoing_broadcasts.load(Ordering::SeqCst),
+                    wait_successful: wait_oks.load(Ordering::SeqCst),
+                    wait_failed: wait_errs.load(Ordering::SeqCst),
+                    wait_ongoing: ongoing_waits.load(Ordering::SeqCst),
+                    wait_unknown: wait_unknowns.load(Ordering::SeqCst),
+                    wait_percentiles: [0.50, 0.90, 0.95]
+                        .map(|quantile| hist_lock.value_at_quantile(quantile) as f64),
+                    finished: true,
+                    ..Default::default()
+                };
+                if self
+                    .strategy_runs
+                    .set_progress(&strategy_name, final_progress.clone())
+                {
+                    final_progress.write(&progress_path);
+                }
+                drop(hist_lock);
+
                 // Log all the newly created identities and contracts.
                 // Note these txs were not confirmed. They were just attempted at least.
                 tracing::info!(
@@ -2201,19 +4641,38 @@ impl AppState {
                     "Newly created contracts (attempted): {:?}",
                     new_contract_ids
                 );
+                if !reused_contract_ids.is_empty() {
+                    tracing::info!(
+                        "Start contracts reused from an earlier run: {:?}",
+                        reused_contract_ids
+                    );
+                }
 
                 // Withdraw all funds from newly created identities back to the wallet
                 let mut current_identities = current_identities.lock().await;
                 if current_identities.len() > 0 {
                     current_identities.remove(0); // Remove loaded identity from the vector
                 }
-                let wallet_lock = self
-                    .loaded_wallet
-                    .lock()
-                    .await
-                    .clone()
-                    .expect("Expected a loaded wallet while withdrawing");
-                tracing::info!("Withdrawing funds from newly created identities back to the loaded wallet (if they have transfer keys)...");
+                // Within a chain the identities go to the next run instead, unless this
+                // run stopped early and the chain ends with it
+                if pass_on && !cancelled && halt_reason.is_none() {
+                    tracing::info!(
+                        "Handing {} identities over to the next run of the chain",
+                        current_identities.len()
+                    );
+                    self.chain_carry.lock().await.insert(
+                        strategy_name.clone(),
+                        ChainCarry {
+                            identities: std::mem::take(&mut *current_identities),
+                            signer: signer.clone(),
+                            pass_on: false,
+                        },
+                    );
+                }
+                tracing::info!(
+                    "Withdrawing funds from newly created identities to {} (if they have transfer keys)...",
+                    withdrawal_address
+                );
                 let mut withdrawals_count = 0;
                 for identity in current_identities.clone() {
                     if identity
@@ -2228,7 +4687,7 @@ impl AppState {
                         let result = identity
                             .withdraw(
                                 sdk,
-                                Some(wallet_lock.receive_address()),
+                                Some(withdrawal_address.clone()),
                                 identity.balance() - 1_000_000, // not sure what this should be
                                 None,
                                 None,
@@ -2264,33 +4723,44 @@ impl AppState {
                     }
                 }
                 tracing::info!("Completed {} withdrawals.", withdrawals_count);
-                drop(wallet_lock);
 
-                // Refresh the identity at the end
-                drop(loaded_identity_lock);
-                let refresh_result = self.refresh_loaded_identity(&sdk).await;
+                // Refresh the identity at the end, the loaded one unless another identity
+                // was loaded meanwhile
+                let still_loaded = self
+                    .loaded_identity
+                    .lock()
+                    .await
+                    .as_ref()
+                    .is_some_and(|identity| identity.id() == loaded_identity.id());
+                let refresh_result = if still_loaded {
+                    self.refresh_loaded_identity(&sdk)
+                        .await
+                        .map(|refreshed_identity_lock| Some(refreshed_identity_lock.balance()))
+                        .map_err(|e| format!("{:?}", e))
+                } else {
+                    Identity::fetch(sdk, loaded_identity.id())
+                        .await
+                        .map(|refreshed_identity| {
+                            refreshed_identity.map(|identity| identity.balance())
+                        })
+                        .map_err(|e| format!("{:?}", e))
+                };
                 if let Err(ref e) = refresh_result {
                     tracing::warn!("Failed to refresh identity after running strategy: {:?}", e);
                 }
 
                 // Attempt to retrieve the final balance from the refreshed identity
-                let final_balance_identity = match refresh_result {
-                    Ok(refreshed_identity_lock) => {
-                        // Successfully refreshed, now access the balance
-                        refreshed_identity_lock.balance()
-                    }
-                    Err(_) => initial_balance_identity,
-                };
+                let final_balance_identity = refresh_result
+                    .ok()
+                    .flatten()
+                    .unwrap_or(initial_balance_identity);
 
                 let dash_spent_identity = (initial_balance_identity as f64
                     - final_balance_identity as f64)
                     / 100_000_000_000.0;
 
-                let loaded_wallet_lock = self.loaded_wallet.lock().await;
-                let final_balance_wallet = loaded_wallet_lock.clone().unwrap().balance();
-                let dash_spent_wallet = (initial_balance_wallet as f64
-                    - final_balance_wallet as f64)
-                    / 100_000_000_000.0;
+                let dash_spent_wallet =
+                    wallet_spent.load(Ordering::SeqCst) as f64 / 100_000_000_000.0;
 
                 // For time mode, success_count is just the number of broadcasts
                 success_count = broadcast_oks;
@@ -2324,6 +4794,12 @@ impl AppState {
                         as u64
                         / (load_run_time)) as f32
                 };
+                if phases_excluded {
+                    let measured_secs = measured_time.as_secs_f32().max(1.0);
+                    tps = measured_attempted as f32 / measured_secs;
+                    successful_tps =
+                        measured_broadcast_oks.load(Ordering::SeqCst) as f32 / measured_secs;
+                }
                 let mut success_percent = 0;
                 if success_count.load(Ordering::SeqCst)
                     > (strategy.start_contracts.len() as u64
@@ -2340,6 +4816,19 @@ impl AppState {
                         * 100.0) as u64
                 };
 
+                // Keep the run's signer so its keys can be exported afterwards
+                strategy.signer = Some(signer.clone());
+
+                for (operation, base) in strategy.operations.iter_mut().zip(base_frequencies) {
+                    operation.frequency = base;
+                }
+                // The run's copy replaces the stored strategy, a run that doesn't get here
+                // leaves it untouched
+                self.available_strategies
+                    .lock()
+                    .await
+                    .insert(strategy_name.clone(), strategy.clone());
+
                 // Clear self.supporting_contracts
                 let mut supporting_contracts_lock = self.supporting_contracts.lock().await;
                 supporting_contracts_lock.clear();
@@ -2347,24 +4836,216 @@ impl AppState {
                 // Time mode
                 tracing::info!(
                         "-----Strategy '{}' completed-----\n\nBroadcasts attempted: {}\nBroadcasts succeeded: {}\nNumber of loops: {}\nLoad run time: \
-                        {:?} seconds\nInit run time: {} seconds\nAttempted rate (approx): {} txs/s\nSuccessful rate: {} tx/s\nSuccess percentage: {}%\nDash spent (Loaded Identity): {}\nDash spent (Wallet): {}\nBroadcast nonce \
-                        errors: {}\nBroadcast balance errors: {}\nBroadcast rate limit errors: {}\nBroadcast connection errors: {}",
+                        {}\nInit run time: {}\nAttempted rate (approx): {}\nSuccessful rate: {}\nSuccess percentage: {}%\nDash spent (Loaded Identity): {}\nDash spent (Wallet): {}\nBroadcast nonce \
+                        errors: {}\nRecovered nonce errors: {}\nWaits given up: {}\nBroadcast balance errors: {}\nBroadcast rate limit errors: {}\nBroadcast connection errors: {}",
                         strategy_name,
-                        transition_count,
-                        success_count.load(Ordering::SeqCst),
-                        loop_index-3, // Minus 3 because we still incremented one at the end of the last loop, and don't count the first two blocks
-                        load_run_time,
-                        init_time.as_secs(),
-                        tps,
-                        successful_tps,
+                        format_count(transition_count.into()),
+                        format_count(success_count.load(Ordering::SeqCst)),
+                        format_count(loop_index-3), // Minus 3 because we still incremented one at the end of the last loop, and don't count the first two blocks
+                        format_secs(load_run_time),
+                        format_duration(init_time),
+                        format_rate(tps.into()),
+                        format_rate(successful_tps.into()),
                         success_percent,
                         dash_spent_identity,
                         dash_spent_wallet,
-                        identity_nonce_error_count.load(Ordering::SeqCst),
-                        insufficient_balance_error_count.load(Ordering::SeqCst),
-                        local_rate_limit_error_count.load(Ordering::SeqCst),
-                        broadcast_connection_error_count.load(Ordering::SeqCst)
+                        format_count(identity_nonce_error_count.load(Ordering::SeqCst)),
+                        format_count(nonce_recovery_count.load(Ordering::SeqCst)),
+                        format_count(wait_unknowns.load(Ordering::SeqCst)),
+                        format_count(insufficient_balance_error_count.load(Ordering::SeqCst)),
+                        format_count(local_rate_limit_error_count.load(Ordering::SeqCst)),
+                        format_count(broadcast_connection_error_count.load(Ordering::SeqCst))
+                    );
+                // A cut short run would only skew comparisons, so it gets no report
+                if cancelled {
+                    return BackendEvent::StrategyCompleted {
+                        strategy_name: strategy_name.clone(),
+                        result: StrategyCompletionResult::Cancelled {
+                            run_time: load_execution_run_time,
+                            transition_count: transition_count.into(),
+                            success_count: success_count.load(Ordering::SeqCst),
+                            dash_spent_identity,
+                            dash_spent_wallet,
+                        },
+                    };
+                }
+
+                let traffic = traffic.report(load_execution_run_time);
+                tracing::info!("{}", traffic);
+
+                // Aliases of the contracts the run created or reused, kept for their new IDs too
+                let run_contract_aliases: Vec<String> = new_contract_ids
+                    .iter()
+                    .chain(&reused_contract_ids)
+                    .filter_map(|id| {
+                        let alias = contract_aliases
+                            .get(&Identifier::from_string(id, Encoding::Base58).ok()?)?;
+                        Some(format!("{}: {}", alias, id))
+                    })
+                    .collect();
+                if !contract_aliases.is_empty() {
+                    let mut aliases_lock = self.strategy_contract_aliases.lock().await;
+                    let saved_aliases = aliases_lock.entry(strategy_name.clone()).or_default();
+                    for (contract_id, alias) in &contract_aliases {
+                        saved_aliases
+                            .entry(*contract_id)
+                            .or_insert_with(|| alias.clone());
+                    }
+                }
+
+                // Keep the run's numbers for later comparisons
+                let hist_lock = hist.lock().await;
+                let run_report = RunReport {
+                    strategy_name: strategy_name.clone(),
+                    run_time_secs: load_execution_run_time.as_secs(),
+                    transitions_attempted: transition_count.into(),
+                    rate: tps,
+                    success_rate: successful_tps,
+                    success_percent,
+                    wait_percentiles: [0.50, 0.90, 0.95]
+                        .map(|quantile| hist_lock.value_at_quantile(quantile) as f64),
+                    pinned_address: run_options.pinned_address.clone(),
+                    user_fee_increase: run_options.user_fee_increase,
+                    traffic: traffic.clone(),
+                    contract_aliases: run_contract_aliases.clone(),
+                };
+                drop(hist_lock);
+                match run_report.save() {
+                    Ok(file_name) => {
+                        tracing::info!("Saved run report {}", file_name);
+                        match run_report.upload(&config, &file_name).await {
+                            Some(Ok(url)) => tracing::info!("Uploaded run report to {}", url),
+                            Some(Err(e)) => tracing::warn!("{}", e),
+                            None => {}
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!("{}", e);
+                        self.unsaved_reports.lock().await.push(format!(
+                            "run report of {} ({} transitions, {}% succeeded)",
+                            strategy_name,
+                            format_count(run_report.transitions_attempted),
+                            run_report.success_percent
+                        ));
+                    }
+                }
+                let baseline_comparison = run_report::compare_to_baseline(&run_report);
+                if let Some(comparison) = &baseline_comparison {
+                    tracing::info!("{}", comparison);
+                }
+
+                let load_control = load_controller.map(LoadController::finish);
+                let load_phases = phase_tracker
+                    .map(|tracker| {
+                        tracker.finish(
+                            load_execution_run_time,
+                            PhaseCounters {
+                                attempted: transition_count.into(),
+                                broadcast_successful: broadcast_oks.load(Ordering::SeqCst),
+                                broadcast_failed: broadcast_errs.load(Ordering::SeqCst),
+                                included: wait_oks.load(Ordering::SeqCst),
+                            },
+                        )
+                    })
+                    .unwrap_or_default();
+                for phase in &load_phases {
+                    tracing::info!("Load phase {}", phase);
+                }
+                let rejections = rejections.counts();
+                for (category, count) in &rejections {
+                    tracing::info!("Transitions rejected, {}: {}", category, count);
+                }
+                let operation_types = operation_metrics.reports(if phases_excluded {
+                    measured_time
+                } else {
+                    load_execution_run_time
+                });
+                for report in &operation_types {
+                    tracing::info!("Transition type {}", report);
+                }
+                let mut errors: Vec<(String, u64)> = [
+                    ("broadcast nonce", &identity_nonce_error_count),
+                    ("broadcast balance", &insufficient_balance_error_count),
+                    ("broadcast rate limit", &local_rate_limit_error_count),
+                    ("broadcast connection", &broadcast_connection_error_count),
+                    ("wait given up", &wait_unknowns),
+                ]
+                .into_iter()
+                .map(|(kind, count)| (kind.to_owned(), count.load(Ordering::SeqCst)))
+                .collect();
+                errors.extend(
+                    rejections
+                        .iter()
+                        .map(|(category, count)| (format!("rejected, {}", category), *count)),
+                );
+                errors.retain(|(_, count)| *count > 0);
+                let run_id = self.run_history.lock().await.record(RunRecord {
+                    id: 0,
+                    strategy_name: strategy_name.clone(),
+                    finished_at: run_schedule::now_secs(),
+                    protocol_version: sdk.version().protocol_version,
+                    duration_secs: duration,
+                    seconds_per_loop,
+                    verify_proofs,
+                    user_fee_increase: run_options.user_fee_increase,
+                    seed,
+                    pinned_address: run_options.pinned_address.clone(),
+                    run_time_secs: run_report.run_time_secs,
+                    transition_count: transition_count.into(),
+                    success_count: success_count.load(Ordering::SeqCst) as u64,
+                    rate: tps,
+                    success_rate: successful_tps,
+                    success_percent,
+                    wait_percentiles: run_report.wait_percentiles,
+                    errors,
+                    halt_reason: halt_reason.clone(),
+                });
+                tracing::info!("Recorded run #{} in the run history", run_id);
+                let transition_trace = match trace.as_ref().map(|trace| trace.finish()) {
+                    Some(Ok(path)) => {
+                        tracing::info!("Saved transition trace {}", path.display());
+                        Some(path.display().to_string())
+                    }
+                    Some(Err(e)) => {
+                        tracing::warn!("{}", e);
+                        None
+                    }
+                    None => None,
+                };
+                let mirror_report = match &mirror {
+                    Some(mirror) => Some(mirror.report().await),
+                    None => None,
+                };
+                let banned_addresses = connections::banned_addresses(sdk);
+                if !banned_addresses.is_empty() {
+                    tracing::info!("DAPI addresses banned: {}", banned_addresses.join(", "));
+                }
+                if let Some(report) = &mirror_report {
+                    tracing::info!("{}", report);
+                }
+                if let Some(report) = &load_control {
+                    tracing::info!("{}", report);
+                }
+                if throttled_loops > 0 {
+                    tracing::info!(
+                        "In-flight cap of {} held off {} loops for {}",
+                        run_options.wait.max_in_flight,
+                        format_count(throttled_loops),
+                        format_duration(throttled_time)
                     );
+                }
+                let top_ups = top_ups.lock().await.clone();
+                if !top_ups.is_empty() {
+                    tracing::info!(
+                        "Identity top ups: {}, credits injected: {}",
+                        top_ups.len(),
+                        top_ups
+                            .iter()
+                            .filter(|top_up| top_up.success)
+                            .map(|top_up| top_up.credits)
+                            .sum::<Credits>()
+                    );
+                }
 
                 BackendEvent::StrategyCompleted {
                     strategy_name: strategy_name.clone(),
@@ -2381,7 +5062,305 @@ impl AppState {
                         init_time: init_time,
                         dash_spent_identity,
                         dash_spent_wallet,
+                        nonce_recoveries: nonce_recovery_count.load(Ordering::SeqCst),
+                        wait_unknowns: wait_unknowns.load(Ordering::SeqCst),
+                        halt_reason,
+                        backlog_incidents: backlog_monitor.finish(load_execution_run_time),
+                        lint_warnings,
+                        top_ups,
+                        load_control,
+                        load_profile,
+                        load_phases,
+                        reused_contracts: reused_contract_ids
+                            .iter()
+                            .map(|id| aliased_contract_id(id, &contract_aliases))
+                            .collect(),
+                        contract_aliases: run_contract_aliases,
+                        rejections,
+                        transition_trace,
+                        operation_types,
+                        measured_time: phases_excluded.then_some(measured_time),
+                        baseline_comparison,
+                        pinned_address: run_options.pinned_address,
+                        banned_addresses,
+                        mirror_report,
+                        seed,
+                        throttled_loops,
+                        throttled_time,
+                        traffic,
+                    },
+                }
+            }
+            StrategyTask::ScheduleRun(ref schedule) => {
+                if !self
+                    .available_strategies
+                    .lock()
+                    .await
+                    .contains_key(&schedule.strategy_name)
+                {
+                    return BackendEvent::StrategyError {
+                        error: "No known strategy with that name in app state".to_string(),
+                    };
+                }
+                let mut schedules = self.run_schedules.lock().await;
+                let id = schedules.add(schedule.clone());
+                BackendEvent::TaskCompleted {
+                    task: Task::Strategy(task.clone()),
+                    execution_result: Ok(format!(
+                        "Scheduled {} of '{}' as #{}, first at {}",
+                        if schedule.repeat_hours.is_some() {
+                            "runs"
+                        } else {
+                            "a run"
+                        },
+                        schedule.strategy_name,
+                        id,
+                        run_schedule::format_time(schedule.next_run)
+                    )
+                    .into()),
+                }
+            }
+            StrategyTask::CancelSchedule(id) => {
+                let execution_result = match self.run_schedules.lock().await.remove(id) {
+                    Some(schedule) => Ok(format!("Cancelled schedule {}", schedule).into()),
+                    None => Err(format!("No pending schedule #{}", id)),
+                };
+                BackendEvent::TaskCompleted {
+                    task: Task::Strategy(task.clone()),
+                    execution_result,
+                }
+            }
+            StrategyTask::RunScheduled(id) => {
+                let started_at = run_schedule::now_secs();
+                let Some(schedule) = self.run_schedules.lock().await.start(id, started_at) else {
+                    return BackendEvent::StrategyError {
+                        error: format!("No pending schedule #{}", id),
+                    };
+                };
+                tracing::info!("Starting scheduled run {}", schedule);
+
+                let run = StrategyTask::RunStrategy(
+                    schedule.strategy_name.clone(),
+                    schedule.duration_secs,
+                    schedule.seconds_per_loop,
+                    schedule.verify_proofs,
+                    0,
+                    RunOptions {
+                        spend_cap: schedule.spend_cap,
+                        ..Default::default()
                     },
+                );
+                let event = Box::pin(self.run_strategy_task(sdk, run, insight, core_client)).await;
+                let (success, outcome) = run_outcome(&event);
+                self.run_schedules.lock().await.record(ScheduledRunRecord {
+                    schedule_id: schedule.id,
+                    strategy_name: schedule.strategy_name,
+                    started_at,
+                    finished_at: run_schedule::now_secs(),
+                    success,
+                    outcome,
+                });
+                event
+            }
+            StrategyTask::RunChain {
+                ref strategy_names,
+                duration,
+                seconds_per_loop,
+                verify_proofs,
+                ref run_options,
+            } => {
+                {
+                    let strategies = self.available_strategies.lock().await;
+                    if let Some(missing) = strategy_names
+                        .iter()
+                        .find(|name| !strategies.contains_key(*name))
+                    {
+                        return BackendEvent::StrategyError {
+                            error: format!("Strategy {} doesn't exist", missing),
+                        };
+                    }
+                }
+
+                let mut report = ChainReport::default();
+                for (index, strategy_name) in strategy_names.iter().enumerate() {
+                    let last = index + 1 == strategy_names.len();
+                    // The identities the previous run handed over go to this one
+                    {
+                        let mut chain_carry = self.chain_carry.lock().await;
+                        let mut carry = index
+                            .checked_sub(1)
+                            .and_then(|previous| chain_carry.remove(&strategy_names[previous]))
+                            .unwrap_or_default();
+                        carry.pass_on = !last;
+                        chain_carry.insert(strategy_name.clone(), carry);
+                    }
+                    tracing::info!(
+                        "Running {} of the chain, {} of {}",
+                        strategy_name,
+                        index + 1,
+                        strategy_names.len()
+                    );
+
+                    let run = StrategyTask::RunStrategy(
+                        strategy_name.clone(),
+                        duration,
+                        seconds_per_loop,
+                        verify_proofs,
+                        0,
+                        run_options.clone(),
+                    );
+                    let event =
+                        Box::pin(self.run_strategy_task(sdk, run, insight, core_client)).await;
+                    let (success, outcome) = run_outcome(&event);
+                    report.links.push(ChainLinkOutcome {
+                        strategy_name: strategy_name.clone(),
+                        success,
+                        outcome,
+                    });
+                    if !success {
+                        report.skipped = strategy_names[index + 1..].to_vec();
+                        break;
+                    }
+                }
+
+                // A run failing before its wrap up leaves the identities handed to it
+                let last_carry = match report.links.last() {
+                    Some(link) => self.chain_carry.lock().await.remove(&link.strategy_name),
+                    None => None,
+                };
+                if let Some(carry) = last_carry {
+                    if !carry.identities.is_empty() {
+                        tracing::warn!(
+                            "Funds left in identities created by the chain: {:?}",
+                            carry
+                                .identities
+                                .iter()
+                                .map(|identity| identity.id().to_string(Encoding::Base58))
+                                .collect::<Vec<_>>()
+                        );
+                    }
+                }
+                BackendEvent::TaskCompleted {
+                    task: Task::Strategy(task.clone()),
+                    execution_result: Ok(report.to_string().into()),
+                }
+            }
+            StrategyTask::RunAcrossUpgrade {
+                ref strategy_name,
+                seconds_per_loop,
+                ref run_options,
+                ref upgrade_window,
+            } => {
+                // Registered as a run while waiting so it can be cancelled, the segments
+                // register themselves
+                let waiting = match self.strategy_runs.start(strategy_name) {
+                    Ok(waiting) => waiting,
+                    Err(error) => return BackendEvent::StrategyError { error },
+                };
+                let mut cancel = waiting.cancel_receiver();
+                let activation_ms = loop {
+                    match upgrade_window::check_window(sdk, upgrade_window).await {
+                        Ok(WindowStatus::Approaching(activation_ms)) => break activation_ms,
+                        Ok(WindowStatus::AlreadyActive) => {
+                            return BackendEvent::StrategyError {
+                                error: format!(
+                                    "Protocol version {} is already active",
+                                    upgrade_window.protocol_version
+                                ),
+                            }
+                        }
+                        Ok(WindowStatus::Waiting(status)) => {
+                            tracing::info!("Waiting for the upgrade window: {}", status)
+                        }
+                        Err(e) => tracing::warn!("{}", e),
+                    }
+                    tokio::select! {
+                        _ = tokio::time::sleep(upgrade_window::POLL_INTERVAL) => {}
+                        _ = cancel.changed() => {}
+                    }
+                    if *cancel.borrow() {
+                        return BackendEvent::TaskCompleted {
+                            task: Task::Strategy(task.clone()),
+                            execution_result: Err(
+                                "Cancelled while waiting for the upgrade window".to_owned()
+                            ),
+                        };
+                    }
+                };
+                drop(waiting);
+
+                let until_activation = Duration::from_millis(
+                    activation_ms.saturating_sub(
+                        SystemTime::now()
+                            .duration_since(UNIX_EPOCH)
+                            .expect("time went backwards")
+                            .as_millis() as u64,
+                    ),
+                );
+                let mut segments = Vec::new();
+                for duration in [until_activation, upgrade_window.post_activation] {
+                    let segment = StrategyTask::RunStrategy(
+                        strategy_name.clone(),
+                        duration.as_secs().max(1),
+                        seconds_per_loop,
+                        false,
+                        0,
+                        run_options.clone(),
+                    );
+                    let report =
+                        match Box::pin(self.run_strategy_task(sdk, segment, insight, core_client))
+                            .await
+                        {
+                            BackendEvent::StrategyCompleted {
+                                result: StrategyCompletionResult::Success { .. },
+                                ..
+                            } => run_report::list_reports(strategy_name)
+                                .first()
+                                .and_then(|file| run_report::read_report(strategy_name, file).ok()),
+                            BackendEvent::StrategyCompleted {
+                                result: StrategyCompletionResult::Cancelled { .. },
+                                ..
+                            } => {
+                                segments.push(None);
+                                break;
+                            }
+                            _ => None,
+                        };
+                    segments.push(report);
+                }
+                // A cancelled first segment leaves the second one out
+                segments.resize(2, None);
+
+                let activated = match upgrade_window::current_protocol_version(sdk).await {
+                    Ok(version) => version >= upgrade_window.protocol_version,
+                    Err(e) => {
+                        tracing::warn!("{}", e);
+                        false
+                    }
+                };
+                let post_activation = segments.pop().flatten();
+                let pre_activation = segments.pop().flatten();
+                let report = UpgradeWindowReport {
+                    strategy_name: strategy_name.clone(),
+                    protocol_version: upgrade_window.protocol_version,
+                    estimated_activation_ms: activation_ms,
+                    activated,
+                    pre_activation,
+                    post_activation,
+                };
+                match report.save() {
+                    Ok(file_name) => tracing::info!("Saved upgrade window report {}", file_name),
+                    Err(e) => {
+                        tracing::warn!("{}", e);
+                        self.unsaved_reports
+                            .lock()
+                            .await
+                            .push(format!("upgrade window report of {}", strategy_name));
+                    }
+                }
+                BackendEvent::TaskCompleted {
+                    task: Task::Strategy(task.clone()),
+                    execution_result: Ok(report.to_string().into()),
                 }
             }
             StrategyTask::RemoveLastContract(strategy_name) => {
@@ -2449,6 +5428,14 @@ impl AppState {
                 if let Some(strategy) = strategies_lock.get_mut(&strategy_name) {
                     // Clear operations for the strategy
                     strategy.operations.clear();
+                    self.strategy_key_maintenance
+                        .lock()
+                        .await
+                        .remove(&strategy_name);
+                    self.strategy_document_generators
+                        .lock()
+                        .await
+                        .remove(&strategy_name);
 
                     BackendEvent::AppStateUpdated(AppStateUpdate::SelectedStrategy(
                         strategy_name.clone(),
@@ -2573,6 +5560,172 @@ impl AppState {
 
         Ok(())
     }
+
+    /// Signer of a strategy run, holding the known private keys of the loaded
+    /// identity and of the identities marked to sign strategy runs.
+    async fn strategy_signer(&self, loaded_identity: &Identity) -> SimpleSigner {
+        let known_identities = self.known_identities.lock().await;
+        let signing_identities = self.strategy_signing_identities.lock().await;
+        let private_keys = self.known_identities_private_keys.lock().await;
+
+        let mut signer = SimpleSigner::default();
+        let identities = std::iter::once(loaded_identity).chain(
+            signing_identities
+                .iter()
+                .filter(|identity_id| **identity_id != loaded_identity.id())
+                .filter_map(|identity_id| known_identities.get(identity_id)),
+        );
+        for identity in identities {
+            for (key_id, public_key) in identity.public_keys() {
+                if let Some(private_key_bytes) = private_keys.get(&(identity.id(), *key_id)) {
+                    signer.private_keys.insert(
+                        public_key.clone(),
+                        private_key_bytes
+                            .clone()
+                            .try_into()
+                            .expect("Expected to convert private key bytes"),
+                    );
+                }
+            }
+        }
+        signer
+    }
+
+    /// Adds identities listed in a JSON file to the strategy's hard-coded start
+    /// identities, returns the number of identities added.
+    ///
+    /// The file holds a list where each entry is either a Base58 identity ID or
+    /// an object with private keys (hex or WIF) by key ID:
+    /// ```json
+    /// [
+    ///   "6Ev1Ac1vGbeDFVMDHwPNscsBoP5Uq1hQ1Sb5KmRjA3Ec",
+    ///   { "id": "BLkzmu7WXadqHJq3DmCQvxDhwd6pHsY5Aed4ccDb1Ydu", "private_keys": { "1": "<hex or WIF>" } }
+    /// ]
+    /// ```
+    /// Private keys must match the public key of their ID and are stored along
+    /// with other known identities' keys.
+    async fn import_hard_coded_start_identities(
+        &self,
+        sdk: &Sdk,
+        strategy_name: &str,
+        path: &str,
+    ) -> Result<usize, String> {
+        let content =
+            std::fs::read_to_string(path).map_err(|e| format!("Unable to read {}: {}", path, e))?;
+        let entries: Vec<HardCodedStartIdentityEntry> = serde_json::from_str(&content)
+            .map_err(|e| format!("Unable to parse {}: {}", path, e))?;
+
+        if !self
+            .available_strategies
+            .lock()
+            .await
+            .contains_key(strategy_name)
+        {
+            return Err("Strategy doesn't exist in app state.".to_owned());
+        }
+
+        let network = Config::load().core_network();
+        let mut identities = Vec::with_capacity(entries.len());
+        let mut private_keys = Vec::new();
+        for entry in entries {
+            let (id_str, entry_private_keys) = match entry {
+                HardCodedStartIdentityEntry::Id(id) => (id, BTreeMap::new()),
+                HardCodedStartIdentityEntry::WithKeys { id, private_keys } => (id, private_keys),
+            };
+            let identity_id = Identifier::from_string(&id_str, Encoding::Base58)
+                .map_err(|_| format!("{} is not a valid Base58 identity ID", id_str))?;
+
+            let known_identity = self
+                .known_identities
+                .lock()
+                .await
+                .get(&identity_id)
+                .cloned();
+            let identity = match known_identity {
+                Some(identity) => identity,
+                None => Identity::fetch(sdk, identity_id)
+                    .await
+                    .map_err(|e| format!("Unable to fetch identity {}: {}", id_str, e))?
+                    .ok_or_else(|| format!("Identity {} not found", id_str))?,
+            };
+
+            for (key_id, private_key) in entry_private_keys {
+                let Some(public_key) = identity.public_keys().get(&key_id) else {
+                    return Err(format!("Identity {} has no key {}", id_str, key_id));
+                };
+                let private_key_bytes = match hex::decode(&private_key) {
+                    Ok(bytes) if bytes.len() == 32 => bytes,
+                    _ => PrivateKey::from_wif(&private_key)
+                        .map(|key| key.inner.secret_bytes().to_vec())
+                        .map_err(|_| {
+                            format!(
+                                "Private key {} of identity {} is neither hex nor WIF",
+                                key_id, id_str
+                            )
+                        })?,
+                };
+                // A key that doesn't match would only fail once the run signs with it
+                let matches = <[u8; 32]>::try_from(private_key_bytes.as_slice())
+                    .ok()
+                    .and_then(|bytes| {
+                        public_key
+                            .key_type()
+                            .public_key_data_from_private_key_data(&bytes, network)
+                            .ok()
+                    })
+                    .is_some_and(|public_key_data| public_key_data == public_key.data().as_slice());
+                if !matches {
+                    return Err(format!(
+                        "Private key {} of identity {} doesn't match its public key",
+                        key_id, id_str
+                    ));
+                }
+                private_keys.push(((identity_id, key_id), private_key_bytes));
+            }
+
+            identities.push(identity);
+        }
+
+        self.known_identities_private_keys
+            .lock()
+            .await
+            .extend(private_keys);
+
+        let mut known_identities_lock = self.known_identities.lock().await;
+        let mut strategies_lock = self.available_strategies.lock().await;
+        let strategy = strategies_lock
+            .get_mut(strategy_name)
+            .ok_or("Strategy doesn't exist in app state.")?;
+
+        let mut imported = 0;
+        for identity in identities {
+            known_identities_lock.insert(identity.id(), identity.clone());
+            if !strategy
+                .start_identities
+                .hard_coded
+                .iter()
+                .any(|(hard_coded, _)| hard_coded.id() == identity.id())
+            {
+                strategy.start_identities.hard_coded.push((identity, None));
+                imported += 1;
+            }
+        }
+
+        Ok(imported)
+    }
+}
+
+/// Entry of a hard-coded start identities file, see
+/// [AppState::import_hard_coded_start_identities].
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum HardCodedStartIdentityEntry {
+    Id(String),
+    WithKeys {
+        id: String,
+        #[serde(default)]
+        private_keys: BTreeMap<KeyID, String>,
+    },
 }
 
 async fn try_broadcast_and_retrieve_asset_lock(
@@ -2608,3 +5761,435 @@ async fn try_broadcast_and_retrieve_asset_lock(
     }
     Err(())
 }
+
+/// Strategy name with anything but letters, digits, `-` and `_` replaced, so
+/// it can't lead out of the directory it's written to.
+fn file_name_safe(strategy_name: &str) -> String {
+    strategy_name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Serializes the signer's keys of `identities` for use by external tools,
+/// keys of other identities are left out.
+///
+/// The format is:
+/// ```json
+/// {
+///   "version": 1,
+///   "network": "testnet",
+///   "strategy": "my_strategy",
+///   "keys": [
+///     {
+///       "identity_id": "<base58 identity id>",
+///       "key_id": 0,
+///       "purpose": "AUTHENTICATION",
+///       "security_level": "MASTER",
+///       "key_type": "ECDSA_SECP256K1",
+///       "public_key": "<hex public key data>",
+///       "private_key": "<hex 32 bytes private key>"
+///     }
+///   ]
+/// }
+/// ```
+fn signer_bundle_json<'a>(
+    strategy_name: &str,
+    signer: &SimpleSigner,
+    identities: impl Iterator<Item = &'a Identity> + Clone,
+) -> String {
+    let keys = signer
+        .private_keys
+        .iter()
+        .filter_map(|(public_key, private_key)| {
+            let identity = identities.clone().find(|identity| {
+                identity
+                    .public_keys()
+                    .get(&public_key.id())
+                    .is_some_and(|key| key.data() == public_key.data())
+            })?;
+
+            Some(serde_json::json!({
+                "identity_id": identity.id().to_string(Encoding::Base58),
+                "key_id": public_key.id(),
+                "purpose": format!("{:?}", public_key.purpose()),
+                "security_level": format!("{:?}", public_key.security_level()),
+                "key_type": format!("{:?}", public_key.key_type()),
+                "public_key": hex::encode(public_key.data().as_slice()),
+                "private_key": hex::encode(private_key),
+            }))
+        })
+        .collect::<Vec<_>>();
+
+    serde_json::to_string_pretty(&serde_json::json!({
+        "version": 1,
+        "network": Config::load().network,
+        "strategy": strategy_name,
+        "keys": keys,
+    }))
+    .expect("json values are serializable")
+}
+
+/// Aliases contracts added by `SetStartContractsRandom` after the contract they
+/// are based on, so they don't show up as bare IDs. The first ID is the
+/// original contract, the following ones are its variants. Existing aliases are
+/// kept.
+pub(crate) fn add_random_variant_aliases(
+    aliases: &mut ContractAliases,
+    contract_name: &str,
+    contract_ids: impl IntoIterator<Item = Identifier>,
+) {
+    for (i, contract_id) in contract_ids.into_iter().enumerate() {
+        let alias = if i == 0 {
+            contract_name.to_owned()
+        } else {
+            format!("{}_variant_{}", contract_name, i - 1)
+        };
+        aliases.entry(contract_id).or_insert(alias);
+    }
+}
+
+/// Base58 contract ID with its alias in front, if it has one.
+fn aliased_contract_id(contract_id: &str, aliases: &ContractAliases) -> String {
+    let alias = Identifier::from_string(contract_id, Encoding::Base58)
+        .ok()
+        .and_then(|id| aliases.get(&id));
+    match alias {
+        Some(alias) => format!("{} ({})", alias, contract_id),
+        None => contract_id.to_owned(),
+    }
+}
+
+/// Contracts created by data contract create transitions that already exist on
+/// chain, as fetched from it.
+async fn existing_contracts(sdk: &Sdk, transitions: &[StateTransition]) -> Vec<DataContract> {
+    let mut existing = Vec::new();
+    for transition in transitions {
+        let StateTransition::DataContractCreate(contract_create_transition) = transition else {
+            continue;
+        };
+        let id = contract_create_transition.data_contract().id();
+        match DataContract::fetch(sdk, id).await {
+            Ok(Some(contract)) => existing.push(contract),
+            Ok(None) => {}
+            Err(e) => tracing::debug!(
+                "Failed to check whether contract {} exists: {}",
+                id.to_string(Encoding::Base58),
+                e
+            ),
+        }
+    }
+    existing
+}
+
+/// Applies a replace or transfer of the run to the document in the local
+/// Drive, returning its contract ID, document type name and the document as it
+/// is now.
+fn update_local_document(
+    drive: &Drive,
+    known_contracts: &KnownContractsMap,
+    document_transition: &DocumentTransition,
+    owner_id: Identifier,
+    block_info: &BlockInfo,
+    platform_version: &PlatformVersion,
+) -> Result<(Identifier, String, Document), String> {
+    let (base, revision) = match document_transition {
+        DocumentTransition::Replace(DocumentReplaceTransition::V0(replace_tx)) => {
+            (&replace_tx.base, replace_tx.revision)
+        }
+        DocumentTransition::Transfer(DocumentTransferTransition::V0(transfer_tx)) => {
+            (&transfer_tx.base, transfer_tx.revision)
+        }
+        _ => return Err("only replaces and transfers update documents".to_owned()),
+    };
+    let data_contract_id = base.data_contract_id();
+    let data_contract = known_contracts
+        .get(&data_contract_id.to_string(Encoding::Base58))
+        .ok_or_else(|| {
+            format!(
+                "data contract {} not found in known_contracts",
+                data_contract_id.to_string(Encoding::Base58)
+            )
+        })?;
+    let document_type_name = base.document_type_name();
+    let document_type = data_contract
+        .document_type_for_name(document_type_name)
+        .map_err(|e| e.to_string())?;
+    let mut query = DriveDocumentQuery::any_item_query(data_contract, document_type);
+    query.internal_clauses.primary_key_equal_clause = Some(WhereClause {
+        field: "$id".to_owned(),
+        operator: WhereOperator::Equal,
+        value: Value::Identifier(base.id().to_buffer()),
+    });
+    let QueryDocumentsOutcome::V0(outcome) = drive
+        .query_documents(query, None, false, None, None)
+        .map_err(|e| e.to_string())?;
+    let mut document = outcome
+        .documents_owned()
+        .into_iter()
+        .next()
+        .ok_or_else(|| "document not found".to_owned())?;
+    if document.owner_id() != owner_id {
+        return Err("document owned by another identity".to_owned());
+    }
+
+    document.set_revision(Some(revision));
+    document.set_updated_at(Some(block_info.time_ms));
+    match document_transition {
+        DocumentTransition::Replace(DocumentReplaceTransition::V0(replace_tx)) => {
+            document.set_properties(replace_tx.data.clone());
+        }
+        DocumentTransition::Transfer(DocumentTransferTransition::V0(transfer_tx)) => {
+            document.set_owner_id(transfer_tx.recipient_owner_id);
+            document.set_transferred_at(Some(block_info.time_ms));
+        }
+        _ => {}
+    }
+    let owned_document_info = OwnedDocumentInfo {
+        document_info: DocumentInfo::DocumentOwnedInfo((document.clone(), None)),
+        owner_id: Some(document.owner_id().to_buffer()),
+    };
+    drive
+        .add_document(
+            owned_document_info,
+            data_contract_id,
+            document_type_name,
+            true,
+            block_info,
+            true,
+            None,
+            platform_version,
+        )
+        .map_err(|e| e.to_string())?;
+    Ok((data_contract_id, document_type_name.clone(), document))
+}
+
+/// Contracts of the documents in a documents batch transition, empty for other
+/// transitions.
+fn documents_batch_contract_ids(transition: &StateTransition) -> Vec<Identifier> {
+    match transition {
+        StateTransition::Batch(BatchTransition::V0(batch)) => batch
+            .transitions
+            .iter()
+            .filter_map(|document_transition| match document_transition {
+                DocumentTransition::Create(DocumentCreateTransition::V0(create_tx)) => {
+                    Some(create_tx.base.data_contract_id())
+                }
+                DocumentTransition::Delete(DocumentDeleteTransition::V0(delete_tx)) => {
+                    Some(delete_tx.base.data_contract_id())
+                }
+                DocumentTransition::Replace(DocumentReplaceTransition::V0(replace_tx)) => {
+                    Some(replace_tx.base.data_contract_id())
+                }
+                DocumentTransition::Transfer(DocumentTransferTransition::V0(transfer_tx)) => {
+                    Some(transfer_tx.base.data_contract_id())
+                }
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Whether a run completed without stopping early, and its outcome in short.
+fn run_outcome(event: &BackendEvent) -> (bool, String) {
+    match event {
+        BackendEvent::StrategyCompleted {
+            result:
+                StrategyCompletionResult::Success {
+                    transition_count,
+                    success_percent,
+                    success_rate,
+                    halt_reason,
+                    ..
+                },
+            ..
+        } => (
+            halt_reason.is_none(),
+            format!(
+                "{} transitions, {}% succeeded, {}{}",
+                format_count(*transition_count),
+                success_percent,
+                format_rate((*success_rate).into()),
+                halt_reason
+                    .as_ref()
+                    .map(|reason| format!(", stopped early: {}", reason))
+                    .unwrap_or_default()
+            ),
+        ),
+        BackendEvent::StrategyCompleted {
+            result: StrategyCompletionResult::PartiallyCompleted { reason, .. },
+            ..
+        } => (false, format!("partially completed: {}", reason)),
+        BackendEvent::StrategyCompleted {
+            result: StrategyCompletionResult::Cancelled { .. },
+            ..
+        } => (false, "cancelled".to_owned()),
+        BackendEvent::StrategyError { error } => (false, error.clone()),
+        _ => (false, "ended without a result".to_owned()),
+    }
+}
+
+/// Whether a loop costing as much as the last one would spend past the spend
+/// cap, `spent` credits spent so far.
+fn next_loop_breaks_spend_cap(
+    spend_cap: Credits,
+    spent: Credits,
+    last_loop_spend: Credits,
+) -> bool {
+    spent.saturating_add(last_loop_spend) > spend_cap
+}
+
+/// Seed of a run's random choices, drawn at random unless one was given, to
+/// be logged so the run can still be replayed.
+fn run_seed(run_options: &RunOptions) -> u64 {
+    run_options.seed.unwrap_or_else(rand::random)
+}
+
+/// Sets the fee increase of a transition signed by an identity of the run and
+/// signs it again with the same key. `None` for transitions signed otherwise,
+/// such as identity creates and top ups signed with their asset lock key.
+async fn with_user_fee_increase(
+    transition: &StateTransition,
+    user_fee_increase: u16,
+    signer: &SimpleSigner,
+    identities: &Mutex<Vec<Identity>>,
+) -> Option<StateTransition> {
+    let key_id = transition.signature_public_key_id()?;
+    let public_key = identities
+        .lock()
+        .await
+        .iter()
+        .find(|identity| identity.id() == transition.owner_id())?
+        .public_keys()
+        .get(&key_id)?
+        .clone();
+    let security_level = public_key.security_level();
+
+    let mut transition = transition.clone();
+    transition.set_user_fee_increase(user_fee_increase);
+    transition
+        .sign_external(
+            &public_key,
+            signer,
+            Some(move |_: Identifier, _: String| Ok(security_level)),
+        )
+        .ok()?;
+    Some(transition)
+}
+
+/// Gives a documents batch transition the next identity contract nonce known to
+/// Platform and signs it again with the same key. The last nonce used is
+/// recorded in `refreshed_nonces`. `None` if the transition can't be retried
+/// this way.
+async fn renonce_documents_batch(
+    sdk: &Sdk,
+    transition: &StateTransition,
+    signer: &SimpleSigner,
+    identities: &Mutex<Vec<Identity>>,
+    refreshed_nonces: &Mutex<BTreeMap<(Identifier, Identifier), u64>>,
+) -> Option<StateTransition> {
+    let StateTransition::Batch(BatchTransition::V0(batch)) = transition else {
+        return None;
+    };
+    let mut batch = batch.clone();
+
+    let contract_id = match batch.transitions.first()? {
+        DocumentTransition::Create(DocumentCreateTransition::V0(create_tx)) => {
+            create_tx.base.data_contract_id()
+        }
+        DocumentTransition::Delete(DocumentDeleteTransition::V0(delete_tx)) => {
+            delete_tx.base.data_contract_id()
+        }
+        DocumentTransition::Replace(DocumentReplaceTransition::V0(replace_tx)) => {
+            replace_tx.base.data_contract_id()
+        }
+        DocumentTransition::Transfer(DocumentTransferTransition::V0(transfer_tx)) => {
+            transfer_tx.base.data_contract_id()
+        }
+        _ => return None,
+    };
+    let mut nonce = sdk
+        .get_identity_contract_nonce(
+            batch.owner_id,
+            contract_id,
+            true,
+            Some(dash_sdk::platform::transition::put_settings::PutSettings {
+                request_settings: RequestSettings::default(),
+                identity_nonce_stale_time_s: Some(0),
+                user_fee_increase: None,
+                wait_timeout: None,
+            }),
+        )
+        .await
+        .ok()?;
+    for document_transition in batch.transitions.iter_mut() {
+        match document_transition {
+            DocumentTransition::Create(DocumentCreateTransition::V0(create_tx)) => {
+                create_tx.base.set_identity_contract_nonce(nonce)
+            }
+            DocumentTransition::Delete(DocumentDeleteTransition::V0(delete_tx)) => {
+                delete_tx.base.set_identity_contract_nonce(nonce)
+            }
+            DocumentTransition::Replace(DocumentReplaceTransition::V0(replace_tx)) => {
+                replace_tx.base.set_identity_contract_nonce(nonce)
+            }
+            DocumentTransition::Transfer(DocumentTransferTransition::V0(transfer_tx)) => {
+                transfer_tx.base.set_identity_contract_nonce(nonce)
+            }
+            _ => return None,
+        }
+        nonce += 1;
+    }
+
+    let public_key = identities
+        .lock()
+        .await
+        .iter()
+        .find(|identity| identity.id() == batch.owner_id)?
+        .public_keys()
+        .get(&batch.signature_public_key_id)?
+        .clone();
+    let security_level = public_key.security_level();
+
+    let owner_id = batch.owner_id;
+    let mut transition = StateTransition::Batch(BatchTransition::V0(batch));
+    transition
+        .sign_external(
+            &public_key,
+            signer,
+            Some(move |_: Identifier, _: String| Ok(security_level)),
+        )
+        .ok()?;
+
+    let mut refreshed_nonces = refreshed_nonces.lock().await;
+    let last_used = refreshed_nonces
+        .entry((owner_id, contract_id))
+        .or_insert(nonce - 1);
+    *last_used = (*last_used).max(nonce - 1);
+    Some(transition)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spend_cap_leaves_room_for_a_loop_like_the_last() {
+        assert!(!next_loop_breaks_spend_cap(1000, 600, 400));
+        assert!(next_loop_breaks_spend_cap(1000, 600, 401));
+        assert!(next_loop_breaks_spend_cap(1000, 1001, 0));
+    }
+
+    #[test]
+    fn spend_cap_check_does_not_overflow() {
+        assert!(next_loop_breaks_spend_cap(u64::MAX - 1, u64::MAX, u64::MAX));
+        assert!(!next_loop_breaks_spend_cap(u64::MAX, u64::MAX, 0));
+    }
+}
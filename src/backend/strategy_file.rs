@@ -0,0 +1,673 @@
+//! Strategies as hand-editable TOML or JSON files.
+//!
+//! The binary export is tied to a platform version and can't be read or
+//! diffed. These files keep what makes up a strategy instead: start contracts
+//! and document operations name their contracts as known or supporting
+//! contracts, identities are Base58 IDs and key settings use the names Platform
+//! gives them, e.g. `TRANSFER`. Contract create operations only keep their
+//! document type count, imported ones use the random document type
+//! parameters of the templates.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Debug,
+    ops::{Range, RangeInclusive},
+    path::Path,
+};
+
+use dpp::{
+    data_contract::{
+        accessors::v0::{DataContractV0Getters, DataContractV0Setters},
+        created_data_contract::CreatedDataContract,
+        document_type::{
+            accessors::DocumentTypeV0Getters,
+            random_document::{DocumentFieldFillSize, DocumentFieldFillType},
+        },
+        DataContract,
+    },
+    fee::Credits,
+    identity::{accessors::IdentityGettersV0, KeyType, Purpose, SecurityLevel},
+    platform_value::{string_encoding::Encoding, Identifier},
+    prelude::Identity,
+    version::PlatformVersion,
+};
+use serde::{Deserialize, Serialize};
+use strategy_tests::{
+    frequency::Frequency,
+    operations::{
+        DocumentAction, DocumentOp, IdentityTransferInfo, IdentityUpdateOp, Operation,
+        OperationType,
+    },
+    IdentityInsertInfo, StartIdentities, Strategy,
+};
+
+use super::{state::StrategyContractNames, strategy_templates::random_document_type_parameters};
+
+/// Extra keys of start and inserted identities
+type KeyMaps = BTreeMap<Purpose, BTreeMap<SecurityLevel, Vec<KeyType>>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, strum::Display)]
+pub(crate) enum StrategyFileFormat {
+    #[strum(to_string = "TOML")]
+    Toml,
+    #[strum(to_string = "JSON")]
+    Json,
+}
+
+impl StrategyFileFormat {
+    /// Format named by the file extension.
+    pub(crate) fn from_path(path: &Path) -> Result<Self, String> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => Ok(StrategyFileFormat::Toml),
+            Some("json") => Ok(StrategyFileFormat::Json),
+            _ => Err(format!(
+                "Cannot tell the format of {}, use a .toml or .json file",
+                path.display()
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StrategyFile {
+    #[serde(default)]
+    start_contracts: Vec<StartContractFile>,
+    #[serde(default)]
+    start_identities: StartIdentitiesFile,
+    #[serde(default)]
+    identity_inserts: IdentityInsertsFile,
+    #[serde(default)]
+    operations: Vec<OperationFile>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StartContractFile {
+    contract: String,
+    /// Contracts the start contract is updated to, in order
+    #[serde(default)]
+    updates: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StartIdentitiesFile {
+    count: u16,
+    keys_per_identity: u8,
+    /// Balance each start identity is funded with, in duffs like the asset
+    /// locks funding them
+    balance_duffs: u64,
+    #[serde(default)]
+    extra_keys: Vec<KeysFile>,
+    /// Base58 IDs of known identities
+    #[serde(default)]
+    hard_coded: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct KeysFile {
+    purpose: String,
+    security_level: String,
+    key_types: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct IdentityInsertsFile {
+    #[serde(flatten)]
+    frequency: FrequencyFile,
+    start_keys: u8,
+    min_balance_credits: Credits,
+    max_balance_credits: Credits,
+    #[serde(default)]
+    extra_keys: Vec<KeysFile>,
+}
+
+impl Default for IdentityInsertsFile {
+    fn default() -> Self {
+        IdentityInsertsFile {
+            frequency: FrequencyFile {
+                times_per_block_min: 0,
+                times_per_block_max: 0,
+                chance_per_block: None,
+            },
+            start_keys: 3,
+            min_balance_credits: 0,
+            max_balance_credits: 0,
+            extra_keys: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct FrequencyFile {
+    times_per_block_min: u16,
+    times_per_block_max: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    chance_per_block: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct OperationFile {
+    #[serde(flatten)]
+    operation: OperationTypeFile,
+    #[serde(flatten)]
+    frequency: FrequencyFile,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum OperationTypeFile {
+    DocumentInsertRandom {
+        contract: String,
+        document_type: String,
+        /// `min`, `max` or `any`
+        fill_size: String,
+        fill_not_required: bool,
+    },
+    DocumentDelete {
+        contract: String,
+        document_type: String,
+    },
+    DocumentReplace {
+        contract: String,
+        document_type: String,
+    },
+    DocumentTransfer {
+        contract: String,
+        document_type: String,
+    },
+    IdentityTopUp {
+        min_credits: Credits,
+        max_credits: Credits,
+    },
+    IdentityAddKeys {
+        count: u16,
+    },
+    IdentityDisableKeys {
+        count: u16,
+    },
+    IdentityWithdrawal {
+        min_credits: Credits,
+        max_credits: Credits,
+    },
+    /// Between random identities unless `from` and `to` are given
+    IdentityTransfer {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        from: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        to: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        credits: Option<Credits>,
+    },
+    ContractCreateRandom {
+        min_document_types: u16,
+        max_document_types: u16,
+    },
+}
+
+/// Writes a strategy in the format. `is_portable` tells whether a start
+/// contract name can be resolved again on import, which random variants of a
+/// contract can't.
+pub(crate) fn strategy_to_file(
+    strategy: &Strategy,
+    contract_names: &StrategyContractNames,
+    known_contracts: &BTreeMap<String, DataContract>,
+    is_portable: impl Fn(&str) -> bool,
+    format: StrategyFileFormat,
+) -> Result<String, String> {
+    let mut names_by_id: BTreeMap<Identifier, String> = known_contracts
+        .iter()
+        .map(|(name, contract)| (contract.id(), name.clone()))
+        .collect();
+
+    let mut start_contracts = Vec::new();
+    for ((contract, updates), (name, update_names)) in
+        strategy.start_contracts.iter().zip(contract_names)
+    {
+        let update_names: Vec<String> = update_names
+            .iter()
+            .flat_map(|updates| updates.values().cloned())
+            .collect();
+        if let Some(name) = std::iter::once(name)
+            .chain(&update_names)
+            .find(|name| !is_portable(name))
+        {
+            return Err(format!(
+                "Start contract {} is a random variant, which can't be written to a file",
+                name
+            ));
+        }
+        names_by_id.insert(contract.data_contract().id(), name.clone());
+        for (update, name) in updates.iter().flat_map(|u| u.values()).zip(&update_names) {
+            names_by_id.insert(update.data_contract().id(), name.clone());
+        }
+        start_contracts.push(StartContractFile {
+            contract: name.clone(),
+            updates: update_names,
+        });
+    }
+    let contract_name = |contract: &DataContract| {
+        names_by_id.get(&contract.id()).cloned().ok_or_else(|| {
+            format!(
+                "Contract {} of an operation is neither a start contract nor a known one",
+                contract.id().to_string(Encoding::Base58)
+            )
+        })
+    };
+
+    let operations = strategy
+        .operations
+        .iter()
+        .map(|operation| {
+            let op_type = match &operation.op_type {
+                OperationType::Document(op) => match &op.action {
+                    DocumentAction::DocumentActionInsertRandom(fill_type, fill_size) => {
+                        OperationTypeFile::DocumentInsertRandom {
+                            contract: contract_name(&op.contract)?,
+                            document_type: op.document_type.name().to_owned(),
+                            fill_size: match fill_size {
+                                DocumentFieldFillSize::MinDocumentFillSize => "min",
+                                DocumentFieldFillSize::MaxDocumentFillSize => "max",
+                                DocumentFieldFillSize::AnyDocumentFillSize => "any",
+                            }
+                            .to_owned(),
+                            fill_not_required: matches!(
+                                fill_type,
+                                DocumentFieldFillType::FillIfNotRequired
+                            ),
+                        }
+                    }
+                    DocumentAction::DocumentActionDelete => OperationTypeFile::DocumentDelete {
+                        contract: contract_name(&op.contract)?,
+                        document_type: op.document_type.name().to_owned(),
+                    },
+                    DocumentAction::DocumentActionReplaceRandom => {
+                        OperationTypeFile::DocumentReplace {
+                            contract: contract_name(&op.contract)?,
+                            document_type: op.document_type.name().to_owned(),
+                        }
+                    }
+                    DocumentAction::DocumentActionTransferRandom => {
+                        OperationTypeFile::DocumentTransfer {
+                            contract: contract_name(&op.contract)?,
+                            document_type: op.document_type.name().to_owned(),
+                        }
+                    }
+                    _ => {
+                        return Err("Only random document inserts, deletes, replaces and \
+                                     transfers can be written to a file"
+                            .to_owned())
+                    }
+                },
+                OperationType::IdentityTopUp(amount) => OperationTypeFile::IdentityTopUp {
+                    min_credits: *amount.start(),
+                    max_credits: *amount.end(),
+                },
+                OperationType::IdentityUpdate(IdentityUpdateOp::IdentityUpdateAddKeys(count)) => {
+                    OperationTypeFile::IdentityAddKeys { count: *count }
+                }
+                OperationType::IdentityUpdate(IdentityUpdateOp::IdentityUpdateDisableKey(
+                    count,
+                )) => OperationTypeFile::IdentityDisableKeys { count: *count },
+                OperationType::IdentityWithdrawal(amount) => {
+                    OperationTypeFile::IdentityWithdrawal {
+                        min_credits: *amount.start(),
+                        max_credits: *amount.end(),
+                    }
+                }
+                OperationType::IdentityTransfer(transfer) => OperationTypeFile::IdentityTransfer {
+                    from: transfer
+                        .as_ref()
+                        .map(|t| t.from.to_string(Encoding::Base58)),
+                    to: transfer.as_ref().map(|t| t.to.to_string(Encoding::Base58)),
+                    credits: transfer.as_ref().map(|t| t.amount),
+                },
+                OperationType::ContractCreate(_, document_types) => {
+                    OperationTypeFile::ContractCreateRandom {
+                        min_document_types: document_types.start,
+                        max_document_types: document_types.end.saturating_sub(1),
+                    }
+                }
+                _ => {
+                    return Err(
+                        "Contract updates, votes and token operations can't be written to a file"
+                            .to_owned(),
+                    )
+                }
+            };
+            Ok(OperationFile {
+                operation: op_type,
+                frequency: frequency_to_file(&operation.frequency),
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let start_identities = &strategy.start_identities;
+    let identity_inserts = &strategy.identity_inserts;
+    let file = StrategyFile {
+        start_contracts,
+        start_identities: StartIdentitiesFile {
+            count: start_identities.number_of_identities,
+            keys_per_identity: start_identities.keys_per_identity,
+            balance_duffs: start_identities.starting_balances,
+            extra_keys: keys_to_file(&start_identities.extra_keys),
+            hard_coded: start_identities
+                .hard_coded
+                .iter()
+                .map(|(identity, _)| identity.id().to_string(Encoding::Base58))
+                .collect(),
+        },
+        identity_inserts: IdentityInsertsFile {
+            frequency: frequency_to_file(&identity_inserts.frequency),
+            start_keys: identity_inserts.start_keys,
+            min_balance_credits: *identity_inserts.start_balance_range.start(),
+            max_balance_credits: *identity_inserts.start_balance_range.end(),
+            extra_keys: keys_to_file(&identity_inserts.extra_keys),
+        },
+        operations,
+    };
+
+    match format {
+        StrategyFileFormat::Toml => toml::to_string_pretty(&file).map_err(|e| e.to_string()),
+        StrategyFileFormat::Json => serde_json::to_string_pretty(&file).map_err(|e| e.to_string()),
+    }
+}
+
+/// Reads a strategy written by [strategy_to_file], looking contracts up by
+/// name with `get_contract`.
+pub(crate) fn strategy_from_file(
+    input: &str,
+    format: StrategyFileFormat,
+    get_contract: impl Fn(&str) -> Option<DataContract>,
+    known_identities: &BTreeMap<Identifier, Identity>,
+    platform_version: &PlatformVersion,
+) -> Result<(Strategy, StrategyContractNames), String> {
+    let file: StrategyFile = match format {
+        StrategyFileFormat::Toml => toml::from_str(input).map_err(|e| e.to_string())?,
+        StrategyFileFormat::Json => serde_json::from_str(input).map_err(|e| e.to_string())?,
+    };
+
+    let contract = |name: &str| {
+        get_contract(name).ok_or_else(|| {
+            format!(
+                "Contract {} is neither known nor a supporting contract",
+                name
+            )
+        })
+    };
+    // Real identity nonces are set during strategy execution
+    let created_contract = |name: &str| {
+        let mut contract = contract(name)?;
+        contract.set_version(1);
+        CreatedDataContract::from_contract_and_identity_nonce(contract, 1, platform_version)
+            .map_err(|e| format!("Error converting {} for the strategy: {}", name, e))
+    };
+
+    let mut strategy = Strategy::default();
+    let mut contract_names = StrategyContractNames::new();
+    let mut contracts_by_name = BTreeMap::new();
+    for start_contract in &file.start_contracts {
+        let original = created_contract(&start_contract.contract)?;
+        contracts_by_name.insert(
+            start_contract.contract.clone(),
+            original.data_contract().clone(),
+        );
+        let mut updates = BTreeMap::new();
+        let mut update_names = BTreeMap::new();
+        for (order, name) in (1..).zip(&start_contract.updates) {
+            let update = created_contract(name)?;
+            contracts_by_name.insert(name.clone(), update.data_contract().clone());
+            updates.insert(order, update);
+            update_names.insert(order, name.clone());
+        }
+        strategy.start_contracts.push((
+            original,
+            if updates.is_empty() {
+                None
+            } else {
+                Some(updates)
+            },
+        ));
+        contract_names.push((start_contract.contract.clone(), Some(update_names)));
+    }
+    let operation_contract = |name: &str| match contracts_by_name.get(name) {
+        Some(contract) => Ok(contract.clone()),
+        None => contract(name),
+    };
+    let document_type = |contract: &DataContract, name: &str| {
+        contract
+            .document_type_cloned_for_name(name)
+            .map_err(|_| format!("Contract has no document type {}", name))
+    };
+    let identifier = |b58_id: &str| {
+        Identifier::from_string(b58_id, Encoding::Base58)
+            .map_err(|_| format!("{} is not a Base58 identity ID", b58_id))
+    };
+
+    strategy.operations = file
+        .operations
+        .into_iter()
+        .map(|operation| {
+            let op_type = match operation.operation {
+                OperationTypeFile::DocumentInsertRandom {
+                    contract,
+                    document_type: document_type_name,
+                    fill_size,
+                    fill_not_required,
+                } => {
+                    let contract = operation_contract(&contract)?;
+                    let fill_size = match fill_size.as_str() {
+                        "min" => DocumentFieldFillSize::MinDocumentFillSize,
+                        "max" => DocumentFieldFillSize::MaxDocumentFillSize,
+                        "any" => DocumentFieldFillSize::AnyDocumentFillSize,
+                        other => {
+                            return Err(format!(
+                                "Unknown fill size {}, expected min, max or any",
+                                other
+                            ))
+                        }
+                    };
+                    let fill_type = if fill_not_required {
+                        DocumentFieldFillType::FillIfNotRequired
+                    } else {
+                        DocumentFieldFillType::DoNotFillIfNotRequired
+                    };
+                    OperationType::Document(DocumentOp {
+                        document_type: document_type(&contract, &document_type_name)?,
+                        contract,
+                        action: DocumentAction::DocumentActionInsertRandom(fill_type, fill_size),
+                    })
+                }
+                OperationTypeFile::DocumentDelete {
+                    contract,
+                    document_type: document_type_name,
+                } => {
+                    let contract = operation_contract(&contract)?;
+                    OperationType::Document(DocumentOp {
+                        document_type: document_type(&contract, &document_type_name)?,
+                        contract,
+                        action: DocumentAction::DocumentActionDelete,
+                    })
+                }
+                OperationTypeFile::DocumentReplace {
+                    contract,
+                    document_type: document_type_name,
+                } => {
+                    let contract = operation_contract(&contract)?;
+                    OperationType::Document(DocumentOp {
+                        document_type: document_type(&contract, &document_type_name)?,
+                        contract,
+                        action: DocumentAction::DocumentActionReplaceRandom,
+                    })
+                }
+                OperationTypeFile::DocumentTransfer {
+                    contract,
+                    document_type: document_type_name,
+                } => {
+                    let contract = operation_contract(&contract)?;
+                    OperationType::Document(DocumentOp {
+                        document_type: document_type(&contract, &document_type_name)?,
+                        contract,
+                        action: DocumentAction::DocumentActionTransferRandom,
+                    })
+                }
+                OperationTypeFile::IdentityTopUp {
+                    min_credits,
+                    max_credits,
+                } => OperationType::IdentityTopUp(credits_range(min_credits, max_credits)?),
+                OperationTypeFile::IdentityAddKeys { count } => {
+                    OperationType::IdentityUpdate(IdentityUpdateOp::IdentityUpdateAddKeys(count))
+                }
+                OperationTypeFile::IdentityDisableKeys { count } => {
+                    OperationType::IdentityUpdate(IdentityUpdateOp::IdentityUpdateDisableKey(count))
+                }
+                OperationTypeFile::IdentityWithdrawal {
+                    min_credits,
+                    max_credits,
+                } => OperationType::IdentityWithdrawal(credits_range(min_credits, max_credits)?),
+                OperationTypeFile::IdentityTransfer { from, to, credits } => {
+                    match (from, to, credits) {
+                        (None, None, _) => OperationType::IdentityTransfer(None),
+                        (Some(from), Some(to), Some(amount)) => {
+                            OperationType::IdentityTransfer(Some(IdentityTransferInfo {
+                                from: identifier(&from)?,
+                                to: identifier(&to)?,
+                                amount,
+                            }))
+                        }
+                        _ => {
+                            return Err("A specific identity transfer needs from, to and credits"
+                                .to_owned())
+                        }
+                    }
+                }
+                OperationTypeFile::ContractCreateRandom {
+                    min_document_types,
+                    max_document_types,
+                } => OperationType::ContractCreate(
+                    random_document_type_parameters(),
+                    times_range(min_document_types, max_document_types)?,
+                ),
+            };
+            Ok(Operation {
+                op_type,
+                frequency: frequency_from_file(&operation.frequency)?,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let start_identities = file.start_identities;
+    strategy.start_identities = StartIdentities {
+        number_of_identities: start_identities.count,
+        keys_per_identity: start_identities.keys_per_identity,
+        starting_balances: start_identities.balance_duffs,
+        extra_keys: keys_from_file(&start_identities.extra_keys)?,
+        hard_coded: start_identities
+            .hard_coded
+            .iter()
+            .map(|b58_id| {
+                known_identities
+                    .get(&identifier(b58_id)?)
+                    .map(|identity| (identity.clone(), None))
+                    .ok_or_else(|| format!("Identity {} is not known", b58_id))
+            })
+            .collect::<Result<Vec<_>, String>>()?,
+    };
+
+    let identity_inserts = file.identity_inserts;
+    strategy.identity_inserts = IdentityInsertInfo {
+        frequency: frequency_from_file(&identity_inserts.frequency)?,
+        start_keys: identity_inserts.start_keys,
+        extra_keys: keys_from_file(&identity_inserts.extra_keys)?,
+        start_balance_range: credits_range(
+            identity_inserts.min_balance_credits,
+            identity_inserts.max_balance_credits,
+        )?,
+    };
+
+    Ok((strategy, contract_names))
+}
+
+fn frequency_to_file(frequency: &Frequency) -> FrequencyFile {
+    let range = &frequency.times_per_block_range;
+    FrequencyFile {
+        times_per_block_min: range.start,
+        times_per_block_max: range.end.saturating_sub(1).max(range.start),
+        chance_per_block: frequency.chance_per_block,
+    }
+}
+
+fn frequency_from_file(frequency: &FrequencyFile) -> Result<Frequency, String> {
+    if let Some(chance) = frequency.chance_per_block {
+        if !(0.0..=1.0).contains(&chance) {
+            return Err(format!(
+                "Chance per block {} is not between 0 and 1",
+                chance
+            ));
+        }
+    }
+    Ok(Frequency {
+        times_per_block_range: times_range(
+            frequency.times_per_block_min,
+            frequency.times_per_block_max,
+        )?,
+        chance_per_block: frequency.chance_per_block,
+    })
+}
+
+/// Inclusive bounds as the exclusive range strategies use.
+fn times_range(min: u16, max: u16) -> Result<Range<u16>, String> {
+    if min > max {
+        return Err(format!("Minimum {} is greater than maximum {}", min, max));
+    }
+    Ok(min..max.checked_add(1).ok_or("Maximum is too large")?)
+}
+
+fn credits_range(min: Credits, max: Credits) -> Result<RangeInclusive<Credits>, String> {
+    if min > max {
+        return Err(format!(
+            "Minimum {} credits is greater than maximum {} credits",
+            min, max
+        ));
+    }
+    Ok(min..=max)
+}
+
+fn keys_to_file(keys: &KeyMaps) -> Vec<KeysFile> {
+    keys.iter()
+        .flat_map(|(purpose, levels)| {
+            levels
+                .iter()
+                .map(move |(security_level, key_types)| KeysFile {
+                    purpose: format!("{:?}", purpose),
+                    security_level: format!("{:?}", security_level),
+                    key_types: key_types.iter().map(|t| format!("{:?}", t)).collect(),
+                })
+        })
+        .collect()
+}
+
+fn keys_from_file(keys: &[KeysFile]) -> Result<KeyMaps, String> {
+    let mut key_maps = KeyMaps::new();
+    for keys in keys {
+        let key_types = keys
+            .key_types
+            .iter()
+            .map(|name| parse_name::<KeyType>(name, "key type"))
+            .collect::<Result<Vec<_>, String>>()?;
+        key_maps
+            .entry(parse_name::<Purpose>(&keys.purpose, "key purpose")?)
+            .or_default()
+            .insert(
+                parse_name::<SecurityLevel>(&keys.security_level, "security level")?,
+                key_types,
+            );
+    }
+    Ok(key_maps)
+}
+
+/// Value of a Platform enum by the name it is debug printed with.
+fn parse_name<T: TryFrom<u8> + Debug>(name: &str, kind: &str) -> Result<T, String> {
+    (0..=u8::MAX)
+        .filter_map(|value| T::try_from(value).ok())
+        .find(|value| format!("{:?}", value) == name)
+        .ok_or_else(|| format!("Unknown {} {}", kind, name))
+}
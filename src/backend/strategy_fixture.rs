@@ -0,0 +1,125 @@
+//! Strategies exported as `strategy_tests` fixtures for the platform repo.
+//!
+//! A fixture is the strategy in the versioned binary form
+//! `Strategy::versioned_deserialize` reads, next to a Rust test loading it
+//! with the protocol version it was written for. Private keys in the
+//! strategy's signer and hard coded start identities without a registration
+//! transition are left out: the first must not be committed and the second
+//! only exist on the network they were loaded from, not on a test chain.
+
+use std::path::{Path, PathBuf};
+
+use dpp::{
+    serialization::{
+        PlatformDeserializableWithPotentialValidationFromVersionedStructure,
+        PlatformSerializableWithPlatformVersion,
+    },
+    version::PlatformVersion,
+};
+use strategy_tests::Strategy;
+
+pub(crate) const STRATEGY_FIXTURES_DIR: &str = "supporting_files/strategy_fixtures";
+
+/// Files written for a fixture.
+pub(crate) struct StrategyFixture {
+    pub(crate) strategy_path: PathBuf,
+    pub(crate) test_path: PathBuf,
+    /// Hard coded start identities left out
+    pub(crate) dropped_identities: usize,
+}
+
+/// Writes the strategy and its test to [STRATEGY_FIXTURES_DIR].
+pub(crate) fn write_fixture(
+    strategy_name: &str,
+    strategy: &Strategy,
+    platform_version: &PlatformVersion,
+) -> Result<StrategyFixture, String> {
+    let mut strategy = strategy.clone();
+    strategy.signer = None;
+    let hard_coded_count = strategy.start_identities.hard_coded.len();
+    strategy
+        .start_identities
+        .hard_coded
+        .retain(|(_, transition)| transition.is_some());
+    let dropped_identities = hard_coded_count - strategy.start_identities.hard_coded.len();
+
+    let bytes = strategy
+        .serialize_to_bytes_with_platform_version(platform_version)
+        .map_err(|e| format!("Failed to serialize strategy: {}", e))?;
+    // The test would fail on a fixture that doesn't read back
+    Strategy::versioned_deserialize(&bytes, true, platform_version)
+        .map_err(|e| format!("The serialized strategy doesn't read back: {}", e))?;
+
+    let name = fixture_name(strategy_name);
+    let dir = Path::new(STRATEGY_FIXTURES_DIR);
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create {}: {}", STRATEGY_FIXTURES_DIR, e))?;
+    let strategy_path = dir.join(format!("{}.strategy", name));
+    let test_path = dir.join(format!("{}.rs", name));
+    std::fs::write(&strategy_path, bytes)
+        .map_err(|e| format!("Failed to write {}: {}", strategy_path.display(), e))?;
+    std::fs::write(
+        &test_path,
+        fixture_test(strategy_name, &name, platform_version.protocol_version),
+    )
+    .map_err(|e| format!("Failed to write {}: {}", test_path.display(), e))?;
+
+    Ok(StrategyFixture {
+        strategy_path,
+        test_path,
+        dropped_identities,
+    })
+}
+
+/// Strategy name usable as a file name and a Rust identifier.
+fn fixture_name(strategy_name: &str) -> String {
+    let name: String = strategy_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.starts_with(|c: char| c.is_ascii_digit()) || name.is_empty() {
+        format!("strategy_{}", name)
+    } else {
+        name
+    }
+}
+
+fn fixture_test(strategy_name: &str, name: &str, protocol_version: u32) -> String {
+    let upper_name = name.to_ascii_uppercase();
+    format!(
+        r#"// Strategy "{strategy_name}" exported from platform-tui, keep {name}.strategy
+// next to this file.
+
+use dpp::serialization::{{
+    PlatformDeserializableWithPotentialValidationFromVersionedStructure,
+    PlatformSerializableWithPlatformVersion,
+}};
+use dpp::version::PlatformVersion;
+use strategy_tests::Strategy;
+
+const {upper_name}_STRATEGY: &[u8] = include_bytes!("{name}.strategy");
+
+fn {name}_strategy(platform_version: &PlatformVersion) -> Strategy {{
+    Strategy::versioned_deserialize({upper_name}_STRATEGY, true, platform_version)
+        .expect("expected to deserialize the {name} strategy fixture")
+}}
+
+#[test]
+fn {name}_strategy_fixture_round_trips() {{
+    let platform_version = PlatformVersion::get({protocol_version})
+        .expect("expected protocol version {protocol_version}");
+    let strategy = {name}_strategy(platform_version);
+    let bytes = strategy
+        .serialize_to_bytes_with_platform_version(platform_version)
+        .expect("expected to serialize the strategy");
+    assert_eq!(bytes, {upper_name}_STRATEGY);
+}}
+"#
+    )
+}
@@ -0,0 +1,299 @@
+//! Checks for common strategy misconfigurations.
+//!
+//! A misconfigured strategy still runs, it just spends time and credits
+//! without doing what it was built for. Lints are only warnings: they are shown
+//! with the strategy whenever it changes and logged before a run starts. Only
+//! the preflight checks stop a run, for configurations it can't survive.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::Duration,
+};
+
+use dpp::{
+    data_contract::{
+        accessors::v0::DataContractV0Getters, document_type::accessors::DocumentTypeV0Getters,
+    },
+    identity::{
+        accessors::IdentityGettersV0,
+        identity_public_key::accessors::v0::IdentityPublicKeyGettersV0, Purpose,
+    },
+    platform_value::string_encoding::Encoding,
+};
+use strategy_tests::{
+    frequency::Frequency,
+    operations::{DocumentAction, DocumentOp, OperationType},
+    Strategy,
+};
+
+use super::{state::ContractFileName, strategies::WaitSettings};
+
+/// Rough time for a broadcast state transition to be included and its wait to
+/// complete on a healthy network
+const EXPECTED_INCLUSION_TIME: Duration = Duration::from_secs(10);
+/// Rough time for a node to answer a state transition broadcast
+const EXPECTED_BROADCAST_TIME: Duration = Duration::from_millis(500);
+
+/// Checks of the strategy itself, independent of how it is run.
+pub(crate) fn lint_strategy(
+    strategy: &Strategy,
+    contract_names: &[(ContractFileName, Option<BTreeMap<u64, ContractFileName>>)],
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    // Document types something inserts into during the run
+    let inserted: BTreeSet<(String, String)> = strategy
+        .operations
+        .iter()
+        .filter_map(|op| match &op.op_type {
+            OperationType::Document(doc_op)
+                if matches!(
+                    doc_op.action,
+                    DocumentAction::DocumentActionInsertRandom(..)
+                        | DocumentAction::DocumentActionInsertSpecific(..)
+                ) =>
+            {
+                Some(document_type_key(doc_op))
+            }
+            _ => None,
+        })
+        .collect();
+    for op in &strategy.operations {
+        if let OperationType::Document(doc_op) = &op.op_type {
+            if matches!(doc_op.action, DocumentAction::DocumentActionDelete)
+                && !inserted.contains(&document_type_key(doc_op))
+            {
+                warnings.push(format!(
+                    "Deletes {} documents but no operation inserts them, only documents created \
+                     before the run can be deleted",
+                    doc_op.document_type.name()
+                ));
+            }
+            if matches!(doc_op.action, DocumentAction::DocumentActionReplaceRandom)
+                && !inserted.contains(&document_type_key(doc_op))
+            {
+                warnings.push(format!(
+                    "Replaces {} documents but no operation inserts them, only documents created \
+                     before the run can be replaced",
+                    doc_op.document_type.name()
+                ));
+            }
+            if matches!(doc_op.action, DocumentAction::DocumentActionTransferRandom) {
+                if !inserted.contains(&document_type_key(doc_op)) {
+                    warnings.push(format!(
+                        "Transfers {} documents but no operation inserts them, only documents \
+                         created before the run can be transferred",
+                        doc_op.document_type.name()
+                    ));
+                }
+                // Recipients are picked among the run's identities, the loaded one
+                // aside there are none without start identities
+                if strategy.start_identities.number_of_identities == 0
+                    && strategy.start_identities.hard_coded.is_empty()
+                {
+                    warnings.push(format!(
+                        "Transfers {} documents but the strategy has no start identities to \
+                         receive them",
+                        doc_op.document_type.name()
+                    ));
+                }
+            }
+        }
+    }
+
+    let has_top_ups = strategy
+        .operations
+        .iter()
+        .any(|op| matches!(op.op_type, OperationType::IdentityTopUp(_)));
+    if strategy.start_identities.number_of_identities > 0
+        && strategy.start_identities.starting_balances == 0
+        && has_top_ups
+    {
+        warnings.push(
+            "Start identities have a zero balance, they can't pay for anything until a top up \
+             operation reaches them"
+                .to_owned(),
+        );
+    }
+
+    // Contracts an operation works on
+    let referenced: BTreeSet<String> = strategy
+        .operations
+        .iter()
+        .filter_map(|op| match &op.op_type {
+            OperationType::Document(doc_op) => Some(doc_op.contract.id()),
+            OperationType::ContractUpdate(update_op) => Some(update_op.contract.id()),
+            _ => None,
+        })
+        .map(|id| id.to_string(Encoding::Base58))
+        .collect();
+    for (index, (contract, _)) in strategy.start_contracts.iter().enumerate() {
+        let id = contract.data_contract().id().to_string(Encoding::Base58);
+        if !referenced.contains(&id) {
+            let name = contract_names
+                .get(index)
+                .map(|(name, _)| name.as_str())
+                .unwrap_or(&id);
+            warnings.push(format!(
+                "Start contract {} is not used by any operation",
+                name
+            ));
+        }
+    }
+
+    warnings
+}
+
+/// Checks of the strategy load against the run settings.
+pub(crate) fn lint_run(
+    strategy: &Strategy,
+    seconds_per_loop: u64,
+    wait: &WaitSettings,
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    let transitions_per_loop = strategy
+        .operations
+        .iter()
+        .map(|op| average_count(&op.frequency))
+        .sum::<f64>()
+        + average_count(&strategy.identity_inserts.frequency);
+    let loop_duration = Duration::from_secs(seconds_per_loop.max(1));
+    let requested_tps = transitions_per_loop / loop_duration.as_secs_f64();
+
+    // Each broadcast holds one of the concurrent slots until the node answers
+    if wait.max_concurrent_broadcasts > 0 {
+        let achievable_tps =
+            wait.max_concurrent_broadcasts as f64 / EXPECTED_BROADCAST_TIME.as_secs_f64();
+        if requested_tps > achievable_tps {
+            warnings.push(format!(
+                "Operations average {:.2} transitions/s but {} concurrent broadcasts only keep \
+                 up with about {:.2}/s, loops will overrun",
+                requested_tps, wait.max_concurrent_broadcasts, achievable_tps
+            ));
+        }
+    }
+    // Waits of a loop stay outstanding for the inclusion time or until the next
+    // loop, whichever is longer, anything above the limit is given up on
+    if wait.max_outstanding > 0 {
+        let achievable_tps =
+            wait.max_outstanding as f64 / loop_duration.max(EXPECTED_INCLUSION_TIME).as_secs_f64();
+        if requested_tps > achievable_tps {
+            warnings.push(format!(
+                "Operations average {:.2} transitions/s but {} outstanding waits only keep up \
+                 with about {:.2}/s, the rest will end with an unknown result",
+                requested_tps, wait.max_outstanding, achievable_tps
+            ));
+        }
+    }
+    // Likewise every transition stays in flight for about the inclusion time,
+    // past the cap loops wait before preparing more
+    if wait.max_in_flight > 0 {
+        let achievable_tps = wait.max_in_flight as f64 / EXPECTED_INCLUSION_TIME.as_secs_f64();
+        if requested_tps > achievable_tps {
+            warnings.push(format!(
+                "Operations average {:.2} transitions/s but {} transitions in flight only keep \
+                 up with about {:.2}/s, loops will be held off",
+                requested_tps, wait.max_in_flight, achievable_tps
+            ));
+        }
+    }
+
+    warnings
+}
+
+/// Checks a run can't go without, done before anything is spent.
+pub(crate) fn preflight(strategy: &Strategy) -> Result<(), String> {
+    // Credit transfers and withdrawals are signed with a transfer key of a start
+    // identity, the run would fail on the first one picked without it
+    let uses_transfer_keys = strategy.operations.iter().any(|op| {
+        matches!(
+            op.op_type,
+            OperationType::IdentityWithdrawal(_) | OperationType::IdentityTransfer(_)
+        )
+    });
+    if uses_transfer_keys {
+        let start_identities = &strategy.start_identities;
+        if start_identities.number_of_identities == 0 && start_identities.hard_coded.is_empty() {
+            return Err(
+                "Withdrawal and transfer operations need start identities to use".to_owned(),
+            );
+        }
+        if start_identities.number_of_identities > 0
+            && !start_identities.extra_keys.contains_key(&Purpose::TRANSFER)
+        {
+            return Err(
+                "Withdrawal and transfer operations need start identities with a transfer key, \
+                 add one in the start identities settings"
+                    .to_owned(),
+            );
+        }
+        if let Some((identity, _)) = start_identities.hard_coded.iter().find(|(identity, _)| {
+            !identity
+                .public_keys()
+                .values()
+                .any(|key| key.purpose() == Purpose::TRANSFER)
+        }) {
+            return Err(format!(
+                "Withdrawal and transfer operations need start identities with a transfer key, \
+                 imported identity {} has none",
+                identity.id().to_string(Encoding::Base58)
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn document_type_key(doc_op: &DocumentOp) -> (String, String) {
+    (
+        doc_op.contract.id().to_string(Encoding::Base58),
+        doc_op.document_type.name().to_owned(),
+    )
+}
+
+/// Average number of events of a frequency per block.
+fn average_count(frequency: &Frequency) -> f64 {
+    let range = &frequency.times_per_block_range;
+    let average_times = if range.end <= range.start {
+        0.0
+    } else {
+        (range.start as f64 + (range.end - 1) as f64) / 2.0
+    };
+    average_times * frequency.chance_per_block.unwrap_or(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strategy_with_identity_inserts(per_loop: u16) -> Strategy {
+        let mut strategy = Strategy::default();
+        strategy.identity_inserts.frequency = Frequency {
+            times_per_block_range: per_loop..per_loop + 1,
+            chance_per_block: None,
+        };
+        strategy
+    }
+
+    #[test]
+    fn lints_load_above_default_broadcast_concurrency() {
+        let warnings = lint_run(
+            &strategy_with_identity_inserts(1000),
+            1,
+            &WaitSettings::default(),
+        );
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("concurrent broadcasts"));
+    }
+
+    #[test]
+    fn keeps_quiet_below_default_broadcast_concurrency() {
+        let warnings = lint_run(
+            &strategy_with_identity_inserts(10),
+            1,
+            &WaitSettings::default(),
+        );
+        assert!(warnings.is_empty());
+    }
+}
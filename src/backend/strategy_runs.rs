@@ -0,0 +1,229 @@
+//! Strategy runs going on at the same time.
+//!
+//! A run works on a copy of its strategy and counts its transitions on its
+//! own, what runs going on at once would still share is split between them
+//! here. A run reserves the identities it signs transitions with, so their
+//! nonces are counted by one run only, and the UTXOs funding its asset locks,
+//! so runs never spend the same outputs. Each run is cancelled on its own and
+//! publishes its progress for the screens following it.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::{Arc, Mutex},
+};
+
+use dpp::{dashcore::OutPoint, prelude::Identifier};
+use tokio::sync::watch;
+
+use super::{run_progress::RunProgress, AppState, Wallet};
+
+#[derive(Debug)]
+struct RunEntry {
+    /// Order the run was started in, among the runs of the session
+    number: u64,
+    identity_ids: BTreeSet<Identifier>,
+    utxos: BTreeSet<OutPoint>,
+    cancel: watch::Sender<bool>,
+    progress: Option<RunProgress>,
+}
+
+#[derive(Debug, Default)]
+struct Runs {
+    entries: BTreeMap<String, RunEntry>,
+    started: u64,
+}
+
+/// Strategy runs going on, by strategy name, not persisted. Clones share the
+/// runs.
+#[derive(Debug, Clone, Default)]
+pub struct StrategyRuns(Arc<Mutex<Runs>>);
+
+/// Registration of a going run, removed when it's dropped however the run
+/// ends.
+pub(crate) struct StrategyRun {
+    runs: StrategyRuns,
+    strategy_name: String,
+    cancel: watch::Receiver<bool>,
+}
+
+impl AppState {
+    pub fn strategy_runs(&self) -> &StrategyRuns {
+        &self.strategy_runs
+    }
+}
+
+impl StrategyRuns {
+    /// Registers a run of the strategy, a strategy runs once at a time.
+    pub(crate) fn start(&self, strategy_name: &str) -> Result<StrategyRun, String> {
+        let mut runs = self.0.lock().expect("strategy runs lock");
+        if runs.entries.contains_key(strategy_name) {
+            return Err(format!("Strategy {} is running already", strategy_name));
+        }
+        runs.started += 1;
+        let (cancel, cancel_receiver) = watch::channel(false);
+        let entry = RunEntry {
+            number: runs.started,
+            identity_ids: BTreeSet::new(),
+            utxos: BTreeSet::new(),
+            cancel,
+            progress: None,
+        };
+        runs.entries.insert(strategy_name.to_owned(), entry);
+        Ok(StrategyRun {
+            runs: self.clone(),
+            strategy_name: strategy_name.to_owned(),
+            cancel: cancel_receiver,
+        })
+    }
+
+    pub fn is_running(&self, strategy_name: &str) -> bool {
+        let runs = self.0.lock().expect("strategy runs lock");
+        runs.entries.contains_key(strategy_name)
+    }
+
+    /// Whether any strategy run, or the wait before one, is going on.
+    pub fn any_running(&self) -> bool {
+        let runs = self.0.lock().expect("strategy runs lock");
+        !runs.entries.is_empty()
+    }
+
+    /// Asks the run of the strategy to stop at the start of its next loop, or
+    /// before funding its next asset lock, it then withdraws and wraps up as
+    /// usual. Returns `false` if the strategy isn't running.
+    pub fn cancel(&self, strategy_name: &str) -> bool {
+        let runs = self.0.lock().expect("strategy runs lock");
+        match runs.entries.get(strategy_name) {
+            Some(entry) => {
+                entry.cancel.send_replace(true);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Latest progress of the run of the strategy, `None` if it isn't running
+    /// or didn't get to its loops yet.
+    pub fn progress(&self, strategy_name: &str) -> Option<RunProgress> {
+        let runs = self.0.lock().expect("strategy runs lock");
+        runs.entries.get(strategy_name)?.progress.clone()
+    }
+
+    /// Publishes the progress of the run of the strategy. Returns whether the
+    /// run was started before the other runs going on, its progress is the
+    /// one written for instances following the runs.
+    pub(crate) fn set_progress(&self, strategy_name: &str, mut progress: RunProgress) -> bool {
+        let mut runs = self.0.lock().expect("strategy runs lock");
+        let Some(entry) = runs.entries.get_mut(strategy_name) else {
+            return false;
+        };
+        progress.stamp();
+        entry.progress = Some(progress);
+        let number = entry.number;
+        runs.entries.values().all(|entry| entry.number >= number)
+    }
+}
+
+impl StrategyRun {
+    /// Set once the run is asked to stop.
+    pub(crate) fn cancel_receiver(&self) -> watch::Receiver<bool> {
+        self.cancel.clone()
+    }
+
+    /// Reserves the identities the run signs transitions with, unless another
+    /// run signs with one of them.
+    pub(crate) fn reserve_identities(
+        &self,
+        identity_ids: impl IntoIterator<Item = Identifier>,
+    ) -> Result<(), String> {
+        let mut runs = self.runs.0.lock().expect("strategy runs lock");
+        let identity_ids: BTreeSet<Identifier> = identity_ids.into_iter().collect();
+        if let Some((other, identity_id)) = runs
+            .entries
+            .iter()
+            .filter(|(name, _)| **name != self.strategy_name)
+            .find_map(|(name, entry)| {
+                Some((name, entry.identity_ids.intersection(&identity_ids).next()?))
+            })
+        {
+            return Err(format!(
+                "Identity {} signs transitions of the running strategy {}, runs going on at \
+                 the same time need identities of their own",
+                identity_id, other
+            ));
+        }
+        runs.entries
+            .get_mut(&self.strategy_name)
+            .expect("registered run")
+            .identity_ids = identity_ids;
+        Ok(())
+    }
+
+    /// Reserves `count` spendable UTXOs of the wallet no other run reserved,
+    /// the largest ones. The run may fund with other UTXOs too, as long as no
+    /// other run reserved them.
+    pub(crate) fn reserve_utxos(&self, wallet: &Wallet, count: usize) -> Result<(), String> {
+        let mut runs = self.runs.0.lock().expect("strategy runs lock");
+        let reserved_by_others: BTreeSet<OutPoint> = runs
+            .entries
+            .iter()
+            .filter(|(name, _)| **name != self.strategy_name)
+            .flat_map(|(_, entry)| entry.utxos.iter().copied())
+            .collect();
+        let mut available: Vec<_> = wallet
+            .spendable_utxos()
+            .filter(|(outpoint, _)| !reserved_by_others.contains(outpoint))
+            .map(|(outpoint, output)| (*outpoint, output.value))
+            .collect();
+        if available.len() < count {
+            return Err(format!(
+                "Not enough UTXOs available in wallet. Available: {}{}. Need: {}. Split the \
+                 wallet's UTXOs for this strategy, or scale it to the wallet, from the Strategy \
+                 screen.",
+                available.len(),
+                if reserved_by_others.is_empty() {
+                    String::new()
+                } else {
+                    format!(
+                        ", {} more set aside for other runs",
+                        reserved_by_others.len()
+                    )
+                },
+                count
+            ));
+        }
+        available.sort_by(|(_, a), (_, b)| b.cmp(a));
+        let utxos: BTreeSet<OutPoint> = available
+            .into_iter()
+            .take(count)
+            .map(|(outpoint, _)| outpoint)
+            .collect();
+        runs.entries
+            .get_mut(&self.strategy_name)
+            .expect("registered run")
+            .utxos = utxos;
+        Ok(())
+    }
+
+    /// UTXOs other runs reserved, to be left out of the run's transactions.
+    pub(crate) fn utxos_of_other_runs(&self) -> BTreeSet<OutPoint> {
+        let runs = self.runs.0.lock().expect("strategy runs lock");
+        runs.entries
+            .iter()
+            .filter(|(name, _)| **name != self.strategy_name)
+            .flat_map(|(_, entry)| entry.utxos.iter().copied())
+            .collect()
+    }
+
+    /// Whether other runs are going on alongside this one.
+    pub(crate) fn others_running(&self) -> bool {
+        let runs = self.runs.0.lock().expect("strategy runs lock");
+        runs.entries.len() > 1
+    }
+}
+
+impl Drop for StrategyRun {
+    fn drop(&mut self) {
+        let mut runs = self.runs.0.lock().expect("strategy runs lock");
+        runs.entries.remove(&self.strategy_name);
+    }
+}
@@ -0,0 +1,111 @@
+//! Runtime of strategy runs, apart from the UI's one.
+//!
+//! A strategy run builds and signs transitions between its broadcasts and
+//! spawns a task for each wait, at high rates that's enough to keep a runtime
+//! busy. Polled by the UI loop along with key presses and redraws, a run would
+//! make the UI lag behind. Runs are polled on a thread of their own instead,
+//! and the tasks they spawn go to the worker threads of a runtime of their own.
+//! A run stops when the UI drops it, and a run stopped that way or by a panic
+//! saves the state as its thread exits.
+
+use std::{
+    mem,
+    panic::{self, AssertUnwindSafe},
+    sync::{Mutex, OnceLock},
+    thread::{self, JoinHandle},
+};
+
+use tokio::{
+    runtime::{Builder, Runtime},
+    sync::oneshot,
+};
+use tokio_util::sync::CancellationToken;
+
+use super::{strategies::StrategyTask, BackendEvent, Task, TaskBackend};
+use crate::config::Config;
+
+static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+/// Threads of the runs started, finished ones are removed as new ones start
+static RUN_THREADS: Mutex<Vec<JoinHandle<()>>> = Mutex::new(Vec::new());
+
+fn runtime() -> &'static Runtime {
+    RUNTIME.get_or_init(|| {
+        let mut builder = Builder::new_multi_thread();
+        if let Some(worker_threads) = Config::load().strategy_worker_threads {
+            builder.worker_threads(worker_threads.max(1));
+        }
+        builder
+            .thread_name("strategy-worker")
+            .enable_all()
+            .build()
+            .expect("expected to build the strategy runtime")
+    })
+}
+
+/// Whether a task puts load on the network, or builds the transitions of
+/// such a run, and should stay off the UI's runtime. The signing benchmark
+/// runs there too, where the transitions it's compared with are signed.
+pub fn runs_on_strategy_runtime(task: &Task) -> bool {
+    matches!(
+        task,
+        Task::Strategy(
+            StrategyTask::RunStrategy(..)
+                | StrategyTask::DryRun { .. }
+                | StrategyTask::RunAcrossUpgrade { .. }
+                | StrategyTask::RunChain { .. }
+                | StrategyTask::RunScheduled(_)
+                | StrategyTask::SigningBenchmark
+        )
+    )
+}
+
+/// Runs a task on the strategy runtime, resolving to its event once it's
+/// done. Dropping the future stops the task.
+pub async fn run<B: TaskBackend + Sync>(backend: &'static B, task: Task) -> BackendEvent<'static> {
+    let (sender, receiver) = oneshot::channel();
+    let cancel = CancellationToken::new();
+    let run_cancel = cancel.clone();
+    let thread = thread::Builder::new()
+        .name("strategy-run".to_owned())
+        .spawn(move || {
+            let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+                runtime().block_on(async {
+                    tokio::select! {
+                        event = backend.run_task(task) => Some(event),
+                        _ = run_cancel.cancelled() => None,
+                    }
+                })
+            }));
+            if let Ok(Some(event)) = outcome {
+                // The UI saves the state of runs it gets the event of, the event
+                // may hold state locks the save would wait for
+                match sender.send(event) {
+                    Ok(()) => return,
+                    Err(event) => drop(event),
+                }
+            }
+            backend.save_state();
+        })
+        .expect("expected to start the strategy run thread");
+    {
+        let mut threads = RUN_THREADS.lock().expect("run threads lock");
+        threads.retain(|thread| !thread.is_finished());
+        threads.push(thread);
+    }
+
+    let _cancel_on_drop = cancel.drop_guard();
+    receiver
+        .await
+        .unwrap_or_else(|_| BackendEvent::StrategyError {
+            error: "The strategy run stopped unexpectedly, see the log".to_owned(),
+        })
+}
+
+/// Waits for the threads of the runs started to end, to be called once runs
+/// were dropped so the state isn't saved while they still hold it.
+pub fn join_runs() {
+    let threads = mem::take(&mut *RUN_THREADS.lock().expect("run threads lock"));
+    for thread in threads {
+        let _ = thread.join();
+    }
+}
@@ -0,0 +1,135 @@
+//! Strategies scaled down to the asset locks the loaded wallet can fund.
+//!
+//! A run funds the asset locks of its start identities, identity inserts and
+//! top ups from the wallet, one UTXO per asset lock transaction. A strategy
+//! asking for more than the wallet holds fails while initializing, or once
+//! the wallet is drained part way through. Start identities, identity
+//! inserts and top ups are scaled by the same factor, the largest one whose
+//! asset locks the wallet's spendable balance and UTXOs cover.
+
+use std::fmt::{self, Display};
+
+use strategy_tests::{operations::OperationType, Strategy};
+
+use super::{
+    dry_run::{asset_lock_cost, estimated_asset_lock_proofs},
+    frequency_profile::scale_frequency,
+};
+use crate::units::{self, CREDITS_PER_DUFF};
+
+/// Steps of the search for the largest factor that fits
+const SEARCH_STEPS: usize = 32;
+
+/// Strategy scaled to a wallet, with what changed.
+#[derive(Debug, Clone)]
+pub(crate) struct ScalePlan {
+    pub(crate) strategy: Strategy,
+    pub(crate) factor: f64,
+    /// Asset locks the wallet can fund, by balance and by UTXOs
+    pub(crate) affordable: u64,
+    pub(crate) asset_locks_before: u64,
+    pub(crate) asset_locks_after: u64,
+    pub(crate) start_identities_before: u16,
+    pub(crate) start_identities_after: u16,
+}
+
+impl ScalePlan {
+    /// Whether the strategy already fits the wallet as it is.
+    pub(crate) fn fits(&self) -> bool {
+        self.factor >= 1.0
+    }
+}
+
+impl Display for ScalePlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.fits() {
+            return write!(
+                f,
+                "The strategy fits the wallet: {} asset locks needed, {} affordable",
+                self.asset_locks_before, self.affordable
+            );
+        }
+        write!(
+            f,
+            "The wallet funds {} asset locks, the strategy needs {}.\n\n\
+             Scaled by {:.2}:\n - Start identities: {} -> {}\n - Identity inserts and top ups \
+             frequencies: x{:.2}\n - Asset locks: {} -> {}",
+            self.affordable,
+            self.asset_locks_before,
+            self.factor,
+            self.start_identities_before,
+            self.start_identities_after,
+            self.factor,
+            self.asset_locks_before,
+            self.asset_locks_after
+        )
+    }
+}
+
+/// Asset locks of the strategy a wallet with `spendable_balance` duffs in
+/// `utxos` UTXOs can fund, at one asset lock per transaction.
+fn affordable_asset_locks(strategy: &Strategy, spendable_balance: u64, utxos: usize) -> u64 {
+    let by_balance = spendable_balance * CREDITS_PER_DUFF / asset_lock_cost(strategy).max(1);
+    by_balance.min(utxos as u64)
+}
+
+/// Strategy with its start identities, identity inserts and top ups scaled
+/// by `factor`.
+fn scaled(strategy: &Strategy, factor: f64) -> Strategy {
+    let mut strategy = strategy.clone();
+    strategy.start_identities.number_of_identities =
+        (strategy.start_identities.number_of_identities as f64 * factor).floor() as u16;
+    strategy.identity_inserts.frequency =
+        scale_frequency(&strategy.identity_inserts.frequency, factor);
+    for operation in strategy.operations.iter_mut() {
+        if matches!(operation.op_type, OperationType::IdentityTopUp(_)) {
+            operation.frequency = scale_frequency(&operation.frequency, factor);
+        }
+    }
+    strategy
+}
+
+/// The strategy scaled down so a run of `duration` seconds doesn't need more
+/// asset locks than the wallet can fund, it's left as is if it fits already.
+pub(crate) fn scale_to_wallet(
+    strategy: &Strategy,
+    duration: u64,
+    spendable_balance: u64,
+    utxos: usize,
+) -> Result<ScalePlan, String> {
+    let affordable = affordable_asset_locks(strategy, spendable_balance, utxos);
+    let asset_locks_before = estimated_asset_lock_proofs(strategy, duration);
+    let start_identities_before = strategy.start_identities.number_of_identities;
+    let plan = |strategy: Strategy, factor: f64| ScalePlan {
+        asset_locks_after: estimated_asset_lock_proofs(&strategy, duration),
+        start_identities_after: strategy.start_identities.number_of_identities,
+        strategy,
+        factor,
+        affordable,
+        asset_locks_before,
+        start_identities_before,
+    };
+    if asset_locks_before <= affordable {
+        return Ok(plan(strategy.clone(), 1.0));
+    }
+    if affordable == 0 {
+        return Err(format!(
+            "The wallet can't fund a single asset lock of {}, it has {} spendable in {} UTXOs",
+            units::format_credits(asset_lock_cost(strategy)),
+            units::format_duffs(spendable_balance),
+            utxos
+        ));
+    }
+
+    // Estimates round down, so the needed asset locks only grow with the factor
+    let (mut low, mut high) = (0.0, 1.0);
+    for _ in 0..SEARCH_STEPS {
+        let middle = (low + high) / 2.0;
+        if estimated_asset_lock_proofs(&scaled(strategy, middle), duration) <= affordable {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    Ok(plan(scaled(strategy, low), low))
+}
@@ -0,0 +1,228 @@
+//! Built-in strategies to start from.
+//!
+//! Putting together a strategy takes a start contract, identity inserts and a
+//! few operations, each added through its own form. A template fills in all
+//! three for a common kind of load, the resulting strategy can then be edited
+//! like any other one.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+};
+
+use dpp::{
+    dash_to_credits,
+    data_contract::{
+        accessors::v0::{DataContractV0Getters, DataContractV0Setters},
+        created_data_contract::CreatedDataContract,
+        document_type::{
+            random_document::{DocumentFieldFillSize, DocumentFieldFillType},
+            v0::random_document_type::{
+                FieldMinMaxBounds, FieldTypeWeights, RandomDocumentTypeParameters,
+            },
+        },
+        DataContract,
+    },
+    version::PlatformVersion,
+};
+use strategy_tests::{
+    frequency::Frequency,
+    operations::{DocumentAction, DocumentOp, IdentityUpdateOp, Operation, OperationType},
+    IdentityInsertInfo, Strategy,
+};
+
+use super::state::StrategyContractNames;
+use crate::units::CREDITS_PER_DASH;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StrategyTemplate {
+    /// Random documents of every type of a DashPay contract
+    DocumentFlood,
+    /// Identities created, topped up, updated and transferring credits
+    IdentityChurn,
+    /// Contracts with random document types
+    ContractSpam,
+}
+
+impl Display for StrategyTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StrategyTemplate::DocumentFlood => write!(f, "Document flood"),
+            StrategyTemplate::IdentityChurn => write!(f, "Identity churn"),
+            StrategyTemplate::ContractSpam => write!(f, "Contract spam"),
+        }
+    }
+}
+
+impl StrategyTemplate {
+    pub(crate) const ALL: [StrategyTemplate; 3] = [
+        StrategyTemplate::DocumentFlood,
+        StrategyTemplate::IdentityChurn,
+        StrategyTemplate::ContractSpam,
+    ];
+
+    pub(crate) fn description(&self) -> &'static str {
+        match self {
+            StrategyTemplate::DocumentFlood => {
+                "Registers the all-mutable DashPay contract and inserts 10 random documents of \
+                 each of its types per block, one new identity per block owns them."
+            }
+            StrategyTemplate::IdentityChurn => {
+                "Creates 2 to 4 identities per block. Half of the blocks top up an identity, \
+                 half transfer credits between two and a quarter add a key to one."
+            }
+            StrategyTemplate::ContractSpam => {
+                "Registers 1 to 3 contracts per block with 1 to 4 random document types each, \
+                 one new identity per block pays for them."
+            }
+        }
+    }
+
+    /// Supporting contract the template's strategy starts with, if any.
+    pub(crate) fn start_contract_name(&self) -> Option<&'static str> {
+        match self {
+            StrategyTemplate::DocumentFlood => Some("dashpay-contract-all-mutable"),
+            StrategyTemplate::IdentityChurn | StrategyTemplate::ContractSpam => None,
+        }
+    }
+
+    /// The template's strategy and its start contract names, `start_contract`
+    /// being the contract named by [StrategyTemplate::start_contract_name].
+    pub(crate) fn build(
+        &self,
+        start_contract: Option<DataContract>,
+        platform_version: &PlatformVersion,
+    ) -> Result<(Strategy, StrategyContractNames), String> {
+        let mut strategy = Strategy::default();
+        let mut contract_names = StrategyContractNames::new();
+
+        if let (Some(name), Some(mut contract)) = (self.start_contract_name(), start_contract) {
+            // Real identity nonces are set during strategy execution
+            contract.set_version(1);
+            let created_contract = CreatedDataContract::from_contract_and_identity_nonce(
+                contract,
+                1,
+                platform_version,
+            )
+            .map_err(|e| format!("Error converting {} for the strategy: {}", name, e))?;
+            strategy.start_contracts.push((created_contract, None));
+            contract_names.push((name.to_owned(), Some(BTreeMap::new())));
+        }
+
+        strategy.identity_inserts = IdentityInsertInfo {
+            frequency: match self {
+                StrategyTemplate::IdentityChurn => every_block(2..5),
+                StrategyTemplate::DocumentFlood | StrategyTemplate::ContractSpam => {
+                    every_block(1..2)
+                }
+            },
+            start_keys: 3,
+            extra_keys: BTreeMap::new(),
+            start_balance_range: dash_to_credits!(1)..=dash_to_credits!(1),
+        };
+
+        strategy.operations = match self {
+            StrategyTemplate::DocumentFlood => strategy
+                .start_contracts
+                .iter()
+                .flat_map(|(contract, _)| {
+                    let contract = contract.data_contract();
+                    contract
+                        .document_types()
+                        .values()
+                        .map(|document_type| Operation {
+                            op_type: OperationType::Document(DocumentOp {
+                                contract: contract.clone(),
+                                document_type: document_type.clone(),
+                                action: DocumentAction::DocumentActionInsertRandom(
+                                    DocumentFieldFillType::FillIfNotRequired,
+                                    DocumentFieldFillSize::AnyDocumentFillSize,
+                                ),
+                            }),
+                            frequency: every_block(10..11),
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect(),
+            StrategyTemplate::IdentityChurn => vec![
+                Operation {
+                    op_type: OperationType::IdentityTopUp(
+                        CREDITS_PER_DASH / 10..=CREDITS_PER_DASH / 10,
+                    ),
+                    frequency: some_blocks(0.5),
+                },
+                Operation {
+                    op_type: OperationType::IdentityTransfer(None),
+                    frequency: some_blocks(0.5),
+                },
+                Operation {
+                    op_type: OperationType::IdentityUpdate(
+                        IdentityUpdateOp::IdentityUpdateAddKeys(1),
+                    ),
+                    frequency: some_blocks(0.25),
+                },
+            ],
+            StrategyTemplate::ContractSpam => vec![Operation {
+                op_type: OperationType::ContractCreate(random_document_type_parameters(), 1..5),
+                frequency: every_block(1..4),
+            }],
+        };
+
+        Ok((strategy, contract_names))
+    }
+}
+
+fn every_block(times_per_block_range: std::ops::Range<u16>) -> Frequency {
+    Frequency {
+        times_per_block_range,
+        chance_per_block: None,
+    }
+}
+
+/// Once in the given share of blocks.
+fn some_blocks(chance_per_block: f64) -> Frequency {
+    Frequency {
+        times_per_block_range: 1..2,
+        chance_per_block: Some(chance_per_block),
+    }
+}
+
+/// Middle of the ranges the contract create operation form picks from.
+pub(crate) fn random_document_type_parameters() -> RandomDocumentTypeParameters {
+    RandomDocumentTypeParameters {
+        new_fields_optional_count_range: 1..10,
+        new_fields_required_count_range: 1..10,
+        new_indexes_count_range: 1..4,
+        field_weights: FieldTypeWeights {
+            string_weight: 50,
+            float_weight: 50,
+            integer_weight: 50,
+            date_weight: 50,
+            boolean_weight: 50,
+            byte_array_weight: 50,
+        },
+        field_bounds: FieldMinMaxBounds {
+            string_min_len: 1..10,
+            string_has_min_len_chance: 0.5,
+            string_max_len: 10..63,
+            string_has_max_len_chance: 0.5,
+            integer_min: 1..10,
+            integer_has_min_chance: 0.5,
+            integer_max: 10..10000,
+            integer_has_max_chance: 0.5,
+            float_min: 0.1..10.0,
+            float_has_min_chance: 0.5,
+            float_max: 10.0..1000.0,
+            float_has_max_chance: 0.5,
+            date_min: 0,
+            date_max: 1000000,
+            byte_array_min_len: 1..10,
+            byte_array_has_min_len_chance: 0.5,
+            byte_array_max_len: 10..255,
+            byte_array_has_max_len_chance: 0.5,
+        },
+        keep_history_chance: 0.5,
+        documents_mutable_chance: 0.5,
+        documents_can_be_deleted_chance: 0.5,
+    }
+}
@@ -0,0 +1,142 @@
+//! Bytes a strategy run sent to and received from DAPI, by kind of request.
+//!
+//! A run whose rate stops growing with its load may be held back by the
+//! client's own network link rather than by Platform. Broadcasts, waits for
+//! their results and the queries made along the way are counted with the size
+//! of their request and response messages, and the report gives the totals
+//! and rates of each next to the transition rates. Sizes are those of the
+//! encoded gRPC messages, without the HTTP/2 and TLS framing around them.
+
+use std::{
+    fmt::{self, Display},
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::format::{format_bytes, format_count};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum RequestCategory {
+    Broadcast,
+    Wait,
+    Query,
+}
+
+#[derive(Debug, Default)]
+struct CategoryCounters {
+    requests: AtomicU64,
+    sent: AtomicU64,
+    received: AtomicU64,
+}
+
+impl CategoryCounters {
+    fn report(&self, secs: f64) -> CategoryTraffic {
+        let sent = self.sent.load(Ordering::SeqCst);
+        let received = self.received.load(Ordering::SeqCst);
+        CategoryTraffic {
+            requests: self.requests.load(Ordering::SeqCst),
+            bytes_sent: sent,
+            bytes_received: received,
+            sent_per_sec: sent as f64 / secs,
+            received_per_sec: received as f64 / secs,
+        }
+    }
+}
+
+/// Traffic counters of a run, shared between its broadcasts and waits.
+#[derive(Debug, Default)]
+pub(crate) struct Traffic {
+    broadcasts: CategoryCounters,
+    waits: CategoryCounters,
+    queries: CategoryCounters,
+}
+
+impl Traffic {
+    /// Counts a request of `sent` bytes, and its response of `received`
+    /// bytes, zero if it failed.
+    pub(crate) fn record(&self, category: RequestCategory, sent: usize, received: usize) {
+        let counters = match category {
+            RequestCategory::Broadcast => &self.broadcasts,
+            RequestCategory::Wait => &self.waits,
+            RequestCategory::Query => &self.queries,
+        };
+        counters.requests.fetch_add(1, Ordering::SeqCst);
+        counters.sent.fetch_add(sent as u64, Ordering::SeqCst);
+        counters
+            .received
+            .fetch_add(received as u64, Ordering::SeqCst);
+    }
+
+    /// Totals and rates over the run time.
+    pub(crate) fn report(&self, run_time: Duration) -> TrafficReport {
+        let secs = run_time.as_secs_f64().max(1.0);
+        TrafficReport {
+            broadcasts: self.broadcasts.report(secs),
+            waits: self.waits.report(secs),
+            queries: self.queries.report(secs),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CategoryTraffic {
+    pub requests: u64,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    /// Bytes sent per second
+    pub sent_per_sec: f64,
+    /// Bytes received per second
+    pub received_per_sec: f64,
+}
+
+impl Display for CategoryTraffic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} requests, {} sent ({}/s), {} received ({}/s)",
+            format_count(self.requests),
+            format_bytes(self.bytes_sent),
+            format_bytes(self.sent_per_sec as u64),
+            format_bytes(self.bytes_received),
+            format_bytes(self.received_per_sec as u64)
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrafficReport {
+    pub broadcasts: CategoryTraffic,
+    pub waits: CategoryTraffic,
+    /// Block height checks and other queries made during the run
+    pub queries: CategoryTraffic,
+}
+
+impl TrafficReport {
+    pub fn total(&self) -> CategoryTraffic {
+        let categories = [&self.broadcasts, &self.waits, &self.queries];
+        CategoryTraffic {
+            requests: categories.iter().map(|c| c.requests).sum(),
+            bytes_sent: categories.iter().map(|c| c.bytes_sent).sum(),
+            bytes_received: categories.iter().map(|c| c.bytes_received).sum(),
+            sent_per_sec: categories.iter().map(|c| c.sent_per_sec).sum(),
+            received_per_sec: categories.iter().map(|c| c.received_per_sec).sum(),
+        }
+    }
+}
+
+impl Display for TrafficReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Network traffic (gRPC messages):")?;
+        for (name, category) in [
+            ("broadcasts", &self.broadcasts),
+            ("waits", &self.waits),
+            ("queries", &self.queries),
+            ("total", &self.total()),
+        ] {
+            write!(f, "\n - {}: {}", name, category)?;
+        }
+        Ok(())
+    }
+}
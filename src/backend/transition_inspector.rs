@@ -0,0 +1,263 @@
+//! Transitions of strategy runs kept for inspection.
+//!
+//! Run statistics tell how many transitions of a class Platform refused, not
+//! what was in them. The latest transitions a run or dry run generated and
+//! the latest ones refused on broadcast or rejected while waiting for their
+//! result are kept here, so one can be taken apart: its decoded structure,
+//! serialized size, signature and estimated fee. Like the activity log the
+//! buffer lives in memory only.
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+use chrono::{DateTime, Local};
+use dpp::{
+    identity::{
+        accessors::IdentityGettersV0,
+        identity_public_key::accessors::v0::IdentityPublicKeyGettersV0, KeyType,
+    },
+    platform_value::{string_encoding::Encoding, Identifier},
+    prelude::{Identity, IdentityPublicKey},
+    serialization::PlatformSerializable,
+    state_transition::{StateTransition, StateTransitionLike},
+};
+
+use super::{dry_run::estimated_transition_fee, AppState};
+use crate::units;
+
+/// Generated transitions kept, runs generate thousands of them per minute
+const MAX_GENERATED: usize = 200;
+/// Failed transitions kept
+const MAX_FAILED: usize = 200;
+
+#[derive(Debug, Clone)]
+pub struct BufferedTransition {
+    pub recorded_at: DateTime<Local>,
+    pub strategy_name: Arc<str>,
+    pub transition: StateTransition,
+    /// Key of the run identity the transition is signed with, if it was known
+    /// when the transition was recorded
+    pub signing_key: Option<IdentityPublicKey>,
+    /// Why Platform refused the transition, `None` for generated ones
+    pub error: Option<String>,
+}
+
+/// In-memory buffers of generated and failed transitions, not persisted.
+/// Clones share the buffers, so runs can record into their own.
+#[derive(Debug, Clone, Default)]
+pub struct TransitionBuffer {
+    generated: Arc<Mutex<VecDeque<BufferedTransition>>>,
+    failed: Arc<Mutex<VecDeque<BufferedTransition>>>,
+}
+
+impl AppState {
+    pub fn transition_buffer(&self) -> &TransitionBuffer {
+        &self.transition_buffer
+    }
+}
+
+impl TransitionBuffer {
+    /// Latest generated transitions, oldest first.
+    pub fn generated(&self) -> Vec<BufferedTransition> {
+        let generated = self.generated.lock().expect("transition buffer lock");
+        generated.iter().cloned().collect()
+    }
+
+    /// Latest failed transitions, oldest first.
+    pub fn failed(&self) -> Vec<BufferedTransition> {
+        let failed = self.failed.lock().expect("transition buffer lock");
+        failed.iter().cloned().collect()
+    }
+
+    /// Records the transitions generated for a loop, with the keys of
+    /// `identities` they're signed with. Only the last ones of a large loop
+    /// are kept.
+    pub(crate) fn record_generated(
+        &self,
+        strategy_name: &Arc<str>,
+        transitions: &[StateTransition],
+        identities: &[Identity],
+    ) {
+        let recorded_at = Local::now();
+        let skipped = transitions.len().saturating_sub(MAX_GENERATED);
+        let mut generated = self.generated.lock().expect("transition buffer lock");
+        for transition in &transitions[skipped..] {
+            if generated.len() >= MAX_GENERATED {
+                generated.pop_front();
+            }
+            generated.push_back(BufferedTransition {
+                recorded_at,
+                strategy_name: Arc::clone(strategy_name),
+                transition: transition.clone(),
+                signing_key: signing_key(transition, identities).cloned(),
+                error: None,
+            });
+        }
+    }
+
+    /// Records a transition refused by Platform. Its signing key is taken
+    /// from a generated transition of the same identity signed with the same
+    /// key, if one is still buffered.
+    pub(crate) fn record_failed(
+        &self,
+        strategy_name: &Arc<str>,
+        transition: &StateTransition,
+        error: String,
+    ) {
+        let signing_key = transition.signature_public_key_id().and_then(|key_id| {
+            let generated = self.generated.lock().expect("transition buffer lock");
+            generated
+                .iter()
+                .rev()
+                .filter(|buffered| buffered.transition.owner_id() == transition.owner_id())
+                .find_map(|buffered| {
+                    buffered
+                        .signing_key
+                        .as_ref()
+                        .filter(|key| key.id() == key_id)
+                        .cloned()
+                })
+        });
+        let mut failed = self.failed.lock().expect("transition buffer lock");
+        if failed.len() >= MAX_FAILED {
+            failed.pop_front();
+        }
+        failed.push_back(BufferedTransition {
+            recorded_at: Local::now(),
+            strategy_name: Arc::clone(strategy_name),
+            transition: transition.clone(),
+            signing_key,
+            error: Some(error),
+        });
+    }
+}
+
+/// Public key among `identities` a transition is signed with.
+fn signing_key<'a>(
+    transition: &StateTransition,
+    identities: &'a [Identity],
+) -> Option<&'a IdentityPublicKey> {
+    let key_id = transition.signature_public_key_id()?;
+    identities
+        .iter()
+        .find(|identity| identity.id() == transition.owner_id())?
+        .public_keys()
+        .get(&key_id)
+}
+
+/// Key types a signature of `length` bytes is made with.
+fn signature_kind(length: usize) -> &'static str {
+    match length {
+        0 => "none, the transition is unsigned",
+        65 => "recoverable ECDSA (ECDSA_SECP256K1 or ECDSA_HASH160 keys)",
+        64 => "EdDSA (EDDSA_25519_HASH160 keys)",
+        96 => "BLS (BLS12_381 keys)",
+        _ => "unknown, no key type signs with this length",
+    }
+}
+
+/// Signature length of the key type, `None` for keys not signing transitions.
+fn key_signature_length(key_type: KeyType) -> Option<usize> {
+    match key_type {
+        KeyType::ECDSA_SECP256K1 | KeyType::ECDSA_HASH160 => Some(65),
+        KeyType::EDDSA_25519_HASH160 => Some(64),
+        KeyType::BLS12_381 => Some(96),
+        _ => None,
+    }
+}
+
+impl BufferedTransition {
+    /// Breakdown of the transition: summary, size, signature, estimated fee
+    /// and decoded structure. Signing keys not recorded with the transition
+    /// are looked up among `known_identities`.
+    pub(crate) fn inspect(&self, known_identities: &BTreeMap<Identifier, Identity>) -> String {
+        let transition = &self.transition;
+        let mut text = String::new();
+
+        text.push_str(&format!("Type: {}\n", transition.name()));
+        text.push_str(&format!("Strategy: {}\n", self.strategy_name));
+        text.push_str(&format!(
+            "Recorded: {}\n",
+            self.recorded_at.format("%Y-%m-%d %H:%M:%S%.3f %:z")
+        ));
+        text.push_str(&format!(
+            "Owner: {}\n",
+            transition.owner_id().to_string(Encoding::Base58)
+        ));
+        text.push_str(&format!(
+            "ID: {}\n",
+            transition
+                .transaction_id()
+                .map_or_else(|e| format!("can't be computed: {}", e), hex::encode)
+        ));
+        if let Some(error) = &self.error {
+            text.push_str(&format!("Error: {}\n", error));
+        }
+
+        let size = match transition.serialize_to_bytes() {
+            Ok(bytes) => {
+                text.push_str(&format!("\nSerialized size: {} bytes\n", bytes.len()));
+                Some(bytes.len())
+            }
+            Err(e) => {
+                text.push_str(&format!("\nSerialized size: can't be serialized: {}\n", e));
+                None
+            }
+        };
+
+        let signature_length = transition
+            .signature()
+            .map_or(0, |signature| signature.len());
+        text.push_str(&format!("\nSignature: {} bytes\n", signature_length));
+        text.push_str(&format!("  Kind: {}\n", signature_kind(signature_length)));
+        if let Some(key_id) = transition.signature_public_key_id() {
+            text.push_str(&format!("  Key ID: {}\n", key_id));
+            let key = self.signing_key.as_ref().or_else(|| {
+                known_identities
+                    .get(&transition.owner_id())
+                    .and_then(|identity| identity.public_keys().get(&key_id))
+            });
+            match key {
+                Some(key) => {
+                    text.push_str(&format!(
+                        "  Key: {:?}, {:?}, {:?}{}\n",
+                        key.key_type(),
+                        key.purpose(),
+                        key.security_level(),
+                        if key.disabled_at().is_some() {
+                            ", disabled"
+                        } else {
+                            ""
+                        }
+                    ));
+                    if signature_length > 0
+                        && key_signature_length(key.key_type()) != Some(signature_length)
+                    {
+                        text.push_str(
+                            "  The signature length doesn't match the key type, the \
+                             transition was signed with another key\n",
+                        );
+                    }
+                }
+                None => {
+                    text.push_str("  Key: the owner's keys aren't known\n");
+                }
+            }
+        } else {
+            text.push_str("  Signed with an asset lock key, not an identity key\n");
+        }
+
+        if let Some(size) = size {
+            text.push_str(&format!(
+                "\nEstimated fee: {}, user fee increase {}%\n",
+                units::format_credits(estimated_transition_fee(transition, size)),
+                transition.user_fee_increase()
+            ));
+        }
+
+        text.push_str(&format!("\nDecoded structure:\n{:#?}", transition));
+        text
+    }
+}
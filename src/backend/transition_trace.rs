@@ -0,0 +1,108 @@
+//! Trace of every transition a strategy run broadcasts.
+//!
+//! Run reports sum transitions up into rates and percentiles. A trace keeps
+//! one JSON line per transition instead, written once its outcome is known:
+//! its type, owner and transaction ID, when it was broadcast, how long the
+//! broadcast and the wait for its result took, the result and the error if
+//! any. Tail latencies can be looked into afterwards and transitions found in
+//! node logs by their transaction ID. Runs broadcast a lot of transitions, so
+//! traces are gzip compressed.
+
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::PathBuf,
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use chrono::Utc;
+use flate2::{write::GzEncoder, Compression};
+use serde::Serialize;
+
+pub(crate) const TRACES_DIR: &str = "supporting_files/run_traces";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum TraceResult {
+    BroadcastFailed,
+    Included,
+    /// Rejected by Platform, the error says why
+    Rejected,
+    WaitFailed,
+    /// Given up on because of too many outstanding waits
+    WaitGivenUp,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct TraceRecord {
+    pub(crate) tx_id: String,
+    pub(crate) transition_type: String,
+    pub(crate) owner_id: String,
+    /// Unix time in milliseconds the broadcast started at
+    pub(crate) broadcast_at_ms: u64,
+    pub(crate) broadcast_ms: u64,
+    /// `None` if the transition wasn't waited for
+    pub(crate) wait_ms: Option<u64>,
+    pub(crate) result: TraceResult,
+    pub(crate) error: Option<String>,
+}
+
+/// Compressed trace file of a run, shared between its broadcasts and waits.
+#[derive(Debug)]
+pub(crate) struct TransitionTrace {
+    path: PathBuf,
+    /// `None` once finished, records of waits still going on are dropped
+    encoder: Mutex<Option<GzEncoder<BufWriter<File>>>>,
+}
+
+impl TransitionTrace {
+    /// Starts a trace in the strategy's directory of [TRACES_DIR].
+    pub(crate) fn create(strategy_name: &str) -> Result<Self, String> {
+        let dir = PathBuf::from(TRACES_DIR).join(strategy_name);
+        let path = dir.join(format!("{}.jsonl.gz", Utc::now().format("%Y%m%d%H%M%S")));
+        let file = fs::create_dir_all(&dir)
+            .and_then(|_| File::create(&path))
+            .map_err(|e| format!("Failed to create trace {}: {}", path.display(), e))?;
+        Ok(TransitionTrace {
+            path,
+            encoder: Mutex::new(Some(GzEncoder::new(
+                BufWriter::new(file),
+                Compression::default(),
+            ))),
+        })
+    }
+
+    pub(crate) fn record(&self, record: &TraceRecord) {
+        let mut encoder = self.encoder.lock().expect("trace lock");
+        let Some(encoder) = encoder.as_mut() else {
+            return;
+        };
+        let result = serde_json::to_writer(&mut *encoder, record)
+            .map_err(|e| e.to_string())
+            .and_then(|_| encoder.write_all(b"\n").map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            tracing::debug!("Failed to write transition trace: {}", e);
+        }
+    }
+
+    /// Completes the file, returning its path.
+    pub(crate) fn finish(&self) -> Result<PathBuf, String> {
+        let Some(encoder) = self.encoder.lock().expect("trace lock").take() else {
+            return Ok(self.path.clone());
+        };
+        encoder
+            .finish()
+            .and_then(|mut writer| writer.flush())
+            .map_err(|e| format!("Failed to complete trace {}: {}", self.path.display(), e))?;
+        Ok(self.path.clone())
+    }
+}
+
+/// Unix time in milliseconds, for [TraceRecord::broadcast_at_ms].
+pub(crate) fn unix_time_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_millis() as u64
+}
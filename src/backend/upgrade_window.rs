@@ -0,0 +1,182 @@
+//! Strategy runs across a protocol version upgrade.
+//!
+//! A version with enough votes activates at the start of the next epoch. The
+//! votes are polled until the awaited version has enough of them and the
+//! estimated start of the next epoch is within the lead time, then the
+//! strategy runs until that estimate and again for a while after it. Each
+//! segment saves its own run report, the upgrade window report puts them side
+//! by side and says whether the version actually activated.
+
+use std::{
+    fmt::{self, Display},
+    fs,
+    time::Duration,
+};
+
+use chrono::{TimeZone, Utc};
+use dash_sdk::{
+    platform::{fetch_current_no_parameters::FetchCurrent, FetchMany},
+    Sdk,
+};
+use dpp::{
+    block::extended_epoch_info::{v0::ExtendedEpochInfoV0Getters, ExtendedEpochInfo},
+    version::ProtocolVersionVoteCount,
+};
+use drive_proof_verifier::types::ProtocolVersionUpgrades;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    platform_info::estimated_epoch_duration_ms,
+    run_report::{self, RunReport},
+};
+
+/// How often the votes are checked while waiting for the window
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_secs(60);
+const UPGRADE_WINDOWS_DIR: &str = "upgrade_windows";
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct UpgradeWindowSettings {
+    pub protocol_version: u32,
+    /// Votes the version needs to activate at the next epoch
+    pub votes_needed: u64,
+    /// How long before the estimated activation the strategy starts
+    pub lead_time: Duration,
+    /// How long the strategy keeps running after the estimated activation
+    pub post_activation: Duration,
+}
+
+pub(crate) enum WindowStatus {
+    /// Not there yet, with what is being waited for
+    Waiting(String),
+    /// Activation estimated within the lead time, at this time in milliseconds
+    Approaching(u64),
+    AlreadyActive,
+}
+
+/// Checks the votes and the current epoch against the settings.
+pub(crate) async fn check_window(
+    sdk: &Sdk,
+    settings: &UpgradeWindowSettings,
+) -> Result<WindowStatus, String> {
+    let epoch_info = ExtendedEpochInfo::fetch_current(sdk)
+        .await
+        .map_err(|e| format!("Unable to fetch the current epoch: {}", e))?;
+    if epoch_info.protocol_version() >= settings.protocol_version {
+        return Ok(WindowStatus::AlreadyActive);
+    }
+
+    let votes: ProtocolVersionUpgrades = ProtocolVersionVoteCount::fetch_many(sdk, ())
+        .await
+        .map_err(|e| format!("Unable to fetch the version votes: {}", e))?;
+    let version_votes = votes
+        .get(&settings.protocol_version)
+        .copied()
+        .flatten()
+        .unwrap_or_default();
+    if version_votes < settings.votes_needed {
+        return Ok(WindowStatus::Waiting(format!(
+            "{} of {} votes for version {}",
+            version_votes, settings.votes_needed, settings.protocol_version
+        )));
+    }
+
+    let activation_ms = epoch_info.first_block_time() + estimated_epoch_duration_ms(sdk.network);
+    let until_activation =
+        Duration::from_millis(activation_ms.saturating_sub(Utc::now().timestamp_millis() as u64));
+    if until_activation > settings.lead_time {
+        Ok(WindowStatus::Waiting(format!(
+            "version {} has its votes, activation estimated in {}s",
+            settings.protocol_version,
+            until_activation.as_secs()
+        )))
+    } else {
+        Ok(WindowStatus::Approaching(activation_ms))
+    }
+}
+
+/// Protocol version of the current epoch.
+pub(crate) async fn current_protocol_version(sdk: &Sdk) -> Result<u32, String> {
+    ExtendedEpochInfo::fetch_current(sdk)
+        .await
+        .map(|epoch_info| epoch_info.protocol_version())
+        .map_err(|e| format!("Unable to fetch the current epoch: {}", e))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpgradeWindowReport {
+    pub strategy_name: String,
+    pub protocol_version: u32,
+    /// Estimated activation time in milliseconds, where the segments split
+    pub estimated_activation_ms: u64,
+    /// Whether the version was active once the run ended
+    pub activated: bool,
+    pub pre_activation: Option<RunReport>,
+    pub post_activation: Option<RunReport>,
+}
+
+impl UpgradeWindowReport {
+    /// Writes the report next to the strategy's run reports, returning the
+    /// file name.
+    pub(crate) fn save(&self) -> Result<String, String> {
+        let dir = run_report::strategy_dir(&self.strategy_name).join(UPGRADE_WINDOWS_DIR);
+        let file_name = format!("{}.json", Utc::now().format("%Y%m%d%H%M%S"));
+        let json = serde_json::to_string_pretty(self).expect("report is serializable");
+        fs::create_dir_all(&dir)
+            .and_then(|_| fs::write(dir.join(&file_name), json))
+            .map_err(|e| {
+                format!(
+                    "Failed to write upgrade window report to {}: {}",
+                    dir.display(),
+                    e
+                )
+            })?;
+        Ok(file_name)
+    }
+}
+
+impl Display for UpgradeWindowReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let activation = Utc
+            .timestamp_millis_opt(self.estimated_activation_ms as i64)
+            .single()
+            .map(|time| time.to_rfc2822())
+            .unwrap_or_default();
+        write!(
+            f,
+            "Strategy '{}' across the upgrade to protocol version {}\nEstimated activation: \
+             {}\nVersion {} once the run ended",
+            self.strategy_name,
+            self.protocol_version,
+            activation,
+            if self.activated {
+                "was active"
+            } else {
+                "was NOT active yet"
+            }
+        )?;
+        for (segment, report) in [
+            ("Before activation", &self.pre_activation),
+            ("After activation", &self.post_activation),
+        ] {
+            match report {
+                Some(report) => write!(
+                    f,
+                    "\n\n{}:\nRun time: {}s\nTransitions attempted: {}\nAttempted rate: {:.2} \
+                     tx/s\nSuccessful rate: {:.2} tx/s\nSuccess percentage: {}%\nWait times \
+                     p50/p90/p95: {}s / {}s / {}s",
+                    segment,
+                    report.run_time_secs,
+                    report.transitions_attempted,
+                    report.rate,
+                    report.success_rate,
+                    report.success_percent,
+                    report.wait_percentiles[0],
+                    report.wait_percentiles[1],
+                    report.wait_percentiles[2]
+                )?,
+                None => write!(f, "\n\n{}: the run didn't complete", segment)?,
+            }
+        }
+        Ok(())
+    }
+}
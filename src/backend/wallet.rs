@@ -1,5 +1,6 @@
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt,
     ops::{Deref, DerefMut},
     str::FromStr,
     time::Duration,
@@ -15,28 +16,64 @@ use dapi_grpc::core::v0::{
     BroadcastTransactionRequest, BroadcastTransactionResponse, GetTransactionRequest,
     GetTransactionResponse,
 };
-use dash_sdk::dashcore_rpc::{Client, RpcApi};
+use dash_sdk::dashcore_rpc::{self, Auth, Client, RpcApi};
 use dash_sdk::{RequestSettings, Sdk};
 use dpp::dashcore::secp256k1::SecretKey;
 use dpp::dashcore::{
+    bip32::{ChildNumber, DerivationPath, ExtendedPrivKey},
+    consensus::deserialize,
     hashes::Hash,
     psbt::serialize::Serialize,
     secp256k1::{Message, Secp256k1},
     sighash::SighashCache,
     transaction::special_transaction::{asset_lock::AssetLockPayload, TransactionPayload},
-    Address, OutPoint, PrivateKey, PublicKey, ScriptBuf, Transaction, TxIn, TxOut, Witness,
+    Address, OutPoint, PrivateKey, PublicKey, ScriptBuf, Transaction, TxIn, TxOut, Txid, Witness,
 };
 use rand::{prelude::StdRng, Rng, SeedableRng};
 use rs_dapi_client::{DapiRequestExecutor, IntoInner};
+use serde::Deserialize;
+use sha2::{Digest, Sha256, Sha512};
 use tokio::sync::{Mutex, MutexGuard};
 
-use super::{set_clipboard, AppStateUpdate, BackendEvent, CompletedTaskPayload, Task};
+use super::{
+    dry_run, set_clipboard, AppState, AppStateUpdate, BackendEvent, CompletedTaskPayload, Task,
+};
 use crate::backend::Wallet::SingleKeyWallet as BackendWallet;
 use crate::{
-    backend::insight::{InsightAPIClient, InsightError},
+    backend::{
+        clients::{CoreApi, InsightApi},
+        insight::InsightError,
+    },
     config::Config,
+    units::{self, CREDITS_PER_DUFF},
 };
 
+/// Dash Core only allows 24 outputs per transaction
+const MAX_OUTPUTS_PER_TRANSACTION: usize = 24;
+
+/// Core wallets get more UTXOs from dashd itself
+const CORE_WALLET_SPLIT_UNSUPPORTED: &str =
+    "UTXOs of a Core wallet are split in Core, e.g. with sendmany to its own addresses";
+
+/// Most credit outputs put in one asset lock transaction, kept in line with the
+/// outputs limit of UTXO splitting
+pub(crate) const MAX_ASSET_LOCKS_PER_TRANSACTION: usize = 24;
+
+/// Purpose of DIP-9 feature derivation paths
+const DIP9_PURPOSE: u32 = 9;
+/// DIP-9 feature of identity keys
+const DIP9_IDENTITY_FEATURE: u32 = 5;
+/// DIP-13 sub-feature of identity authentication keys
+const DIP13_AUTHENTICATION_SUB_FEATURE: u32 = 0;
+/// DIP-13 key type of ECDSA keys
+const DIP13_ECDSA_KEY_TYPE: u32 = 0;
+/// Purpose of BIP44 derivation paths
+const BIP44_PURPOSE: u32 = 44;
+/// PBKDF2 rounds turning a BIP39 mnemonic into its seed
+const BIP39_SEED_ROUNDS: u32 = 2048;
+/// Word counts of BIP39 mnemonics
+const BIP39_WORD_COUNTS: [usize; 5] = [12, 15, 18, 21, 24];
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum WalletTask {
     AddByPrivateKey(String),
@@ -45,13 +82,90 @@ pub enum WalletTask {
     CopyAddress,
     ClearLoadedWallet,
     SplitUTXOs(u32),
+    SetSweepWallet(String),
+    ClearSweepWallet,
+    /// Adds a wallet to the wallet manager under a name and loads it, with a
+    /// random key if no private key is given
+    AddNamed {
+        name: String,
+        private_key: Option<String>,
+    },
+    /// Adds a wallet to the wallet manager under a name and loads it, its key
+    /// and identity keys derived from a BIP39 mnemonic
+    AddByMnemonic {
+        name: String,
+        mnemonic: String,
+    },
+    /// Adds a wallet of the connected dashd to the wallet manager under a
+    /// name and loads it, an empty Core wallet name is dashd's default wallet
+    AddCoreWallet {
+        name: String,
+        core_wallet_name: String,
+    },
+    /// Loads a wallet of the wallet manager, storing the loaded one
+    Select(String),
+    /// Removes an empty wallet from the wallet manager
+    Remove(String),
+    /// Freezes an output of the loaded wallet so asset locks don't spend it,
+    /// or unfreezes it if it's frozen
+    ToggleUtxoFrozen(OutPoint),
+    /// Splits the loaded wallet's UTXOs into one per asset lock a run of the
+    /// strategy for `duration` seconds is expected to fund, each sized for it
+    SplitUTXOsForStrategy {
+        strategy_name: String,
+        duration: u64,
+    },
+    /// Pays `amount` duffs from the loaded wallet to an address
+    Send {
+        address: String,
+        amount: u64,
+        fee_level: FeeLevel,
+    },
+}
+
+/// Fee rate of a payment transaction
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FeeLevel {
+    Minimum,
+    Normal,
+    Priority,
+}
+
+impl FeeLevel {
+    pub fn duffs_per_byte(&self) -> u64 {
+        match self {
+            FeeLevel::Minimum => 1,
+            FeeLevel::Normal => 2,
+            FeeLevel::Priority => 10,
+        }
+    }
+}
+
+impl fmt::Display for FeeLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FeeLevel::Minimum => "Minimum",
+            FeeLevel::Normal => "Normal",
+            FeeLevel::Priority => "Priority",
+        };
+        write!(f, "{} ({} duffs/byte)", name, self.duffs_per_byte())
+    }
+}
+
+/// Outputs below this value are non-standard and aren't relayed
+const DUST_LIMIT: u64 = 546;
+
+/// Size in bytes of a P2PKH transaction with `inputs` inputs and `outputs`
+/// outputs, signatures taken at their largest.
+fn p2pkh_transaction_size(inputs: usize, outputs: usize) -> u64 {
+    10 + 148 * inputs as u64 + 34 * outputs as u64
 }
 
 pub async fn add_wallet_by_private_key_as_string<'s>(
     wallet_state: &Mutex<Option<Wallet>>,
     private_key: &String,
-    insight: &'s InsightAPIClient,
-    core_client: &'s Client,
+    insight: &'s impl InsightApi,
+    core_client: &'s impl CoreApi,
 ) -> Result<(), WalletError> {
     let private_key = match private_key.len() {
         64 => {
@@ -79,11 +193,75 @@ pub async fn add_wallet_by_private_key_as_string<'s>(
     Ok(add_wallet_by_private_key(wallet_state, private_key, insight, core_client).await)
 }
 
+/// BIP39 seed of a mnemonic without passphrase. Words aren't checked against
+/// the BIP39 word list, a mistyped word gives the seed of another wallet.
+fn mnemonic_seed(mnemonic: &str) -> Result<Vec<u8>, WalletError> {
+    let words: Vec<String> = mnemonic
+        .split_whitespace()
+        .map(|word| word.to_lowercase())
+        .collect();
+    if !BIP39_WORD_COUNTS.contains(&words.len()) {
+        return Err(WalletError::Custom(format!(
+            "Expected a mnemonic of 12, 15, 18, 21 or 24 words, got {} words",
+            words.len()
+        )));
+    }
+    let mut seed = vec![0u8; 64];
+    pbkdf2::pbkdf2_hmac::<Sha512>(
+        words.join(" ").as_bytes(),
+        b"mnemonic",
+        BIP39_SEED_ROUNDS,
+        &mut seed,
+    );
+    Ok(seed)
+}
+
+/// Adds a wallet of a BIP39 mnemonic. Its key is the first receive key of the
+/// first BIP44 account, `m/44'/<coin type>'/0'/0/0`, the address other wallets
+/// of the mnemonic show first, and its identity keys are derived from the seed.
+pub async fn add_wallet_by_mnemonic<'s>(
+    wallet_state: &'s Mutex<Option<Wallet>>,
+    mnemonic: &str,
+    insight: &'s impl InsightApi,
+    core_client: &impl CoreApi,
+) -> Result<(), WalletError> {
+    let seed = mnemonic_seed(mnemonic)?;
+    let network = Config::load().core_network();
+    let coin_type = if Config::load().is_mainnet() { 5 } else { 1 };
+    let path: DerivationPath = vec![
+        ChildNumber::from_hardened_idx(BIP44_PURPOSE),
+        ChildNumber::from_hardened_idx(coin_type),
+        ChildNumber::from_hardened_idx(0),
+        ChildNumber::from_normal_idx(0),
+        ChildNumber::from_normal_idx(0),
+    ]
+    .into_iter()
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e| WalletError::Custom(format!("Invalid derivation path: {}", e)))?
+    .into();
+    let private_key = ExtendedPrivKey::new_master(network, &seed)
+        .and_then(|master_key| master_key.derive_priv(&Secp256k1::new(), &path))
+        .map(|key| key.to_priv())
+        .map_err(|e| WalletError::Custom(format!("Failed to derive the wallet key: {}", e)))?;
+    add_single_key_wallet(wallet_state, private_key, Some(seed), insight, core_client).await;
+    Ok(())
+}
+
 pub async fn add_wallet_by_private_key<'s>(
     wallet_state: &'s Mutex<Option<Wallet>>,
     private_key: PrivateKey,
-    insight: &'s InsightAPIClient,
-    core_client: &Client,
+    insight: &'s impl InsightApi,
+    core_client: &impl CoreApi,
+) {
+    add_single_key_wallet(wallet_state, private_key, None, insight, core_client).await
+}
+
+async fn add_single_key_wallet<'s>(
+    wallet_state: &'s Mutex<Option<Wallet>>,
+    private_key: PrivateKey,
+    hd_seed: Option<Vec<u8>>,
+    insight: &'s impl InsightApi,
+    core_client: &impl CoreApi,
 ) {
     let secp = Secp256k1::new();
     let public_key = private_key.public_key(&secp);
@@ -94,14 +272,19 @@ pub async fn add_wallet_by_private_key<'s>(
         public_key,
         address,
         utxos: Default::default(),
+        utxo_confirmations: Default::default(),
+        frozen_utxos: Default::default(),
+        change_keys: Vec::new(),
+        next_change_index: 0,
+        hd_seed,
     });
 
     match wallet.reload_utxos(insight, core_client).await {
-        Ok(utxos) => match wallet {
-            BackendWallet(ref mut single_key_wallet) => {
+        Ok(utxos) => {
+            if let BackendWallet(ref mut single_key_wallet) = wallet {
                 single_key_wallet.utxos = utxos;
             }
-        },
+        }
         Err(_) => {
             // nothing
         }
@@ -111,13 +294,56 @@ pub async fn add_wallet_by_private_key<'s>(
     *wallet_guard = Some(wallet);
 }
 
+/// Loads a wallet under a name, keeping the loaded one in the stored wallets
+/// under its name, or its address if it has none.
+pub(super) async fn load_wallet(app_state: &AppState, name: Option<String>, wallet: Wallet) {
+    let mut loaded_wallet = app_state.loaded_wallet.lock().await;
+    let mut loaded_wallet_name = app_state.loaded_wallet_name.lock().await;
+    if let Some(previous) = loaded_wallet.replace(wallet) {
+        let previous_name = loaded_wallet_name
+            .take()
+            .unwrap_or_else(|| previous.receive_address().to_string());
+        app_state
+            .stored_wallets
+            .lock()
+            .await
+            .insert(previous_name, previous);
+    }
+    *loaded_wallet_name = name;
+}
+
+/// A wallet can be added to the wallet manager under `name` and loaded.
+async fn check_new_wallet(app_state: &AppState, name: &str) -> Result<(), String> {
+    let loaded_name_taken = app_state.loaded_wallet_name.lock().await.as_deref() == Some(name);
+    let name_taken = name.is_empty()
+        || loaded_name_taken
+        || app_state.stored_wallets.lock().await.contains_key(name);
+    if name_taken {
+        Err(format!("A wallet is already named \"{}\"", name))
+    } else {
+        check_wallet_switch_allowed(app_state)
+    }
+}
+
+/// Strategy runs fund themselves from the loaded wallet all along, it can't
+/// change under them.
+fn check_wallet_switch_allowed(app_state: &AppState) -> Result<(), String> {
+    if app_state.strategy_runs().any_running() {
+        Err("Can't change the loaded wallet while a strategy runs".to_owned())
+    } else {
+        Ok(())
+    }
+}
+
 pub(super) async fn run_wallet_task<'s>(
     sdk: &Sdk,
-    wallet_state: &'s Mutex<Option<Wallet>>,
+    app_state: &'s AppState,
     task: WalletTask,
-    insight: &'s InsightAPIClient,
-    core_client: &'s Client,
+    insight: &'s impl InsightApi,
+    core_client: &'s impl CoreApi,
 ) -> BackendEvent<'s> {
+    let wallet_state = &app_state.loaded_wallet;
+    let sweep_wallet_state = &app_state.sweep_wallet;
     match task {
         WalletTask::AddByPrivateKey(ref private_key) => {
             match add_wallet_by_private_key_as_string(
@@ -214,6 +440,7 @@ pub(super) async fn run_wallet_task<'s>(
         WalletTask::ClearLoadedWallet => {
             let mut wallet_guard = wallet_state.lock().await;
             *wallet_guard = None;
+            *app_state.loaded_wallet_name.lock().await = None;
             BackendEvent::TaskCompletedStateChange {
                 task: Task::Wallet(task),
                 execution_result: Ok(CompletedTaskPayload::String(
@@ -241,6 +468,10 @@ pub(super) async fn run_wallet_task<'s>(
                             },
                         }
                     }
+                    Wallet::CoreRpcWallet(_) => BackendEvent::TaskCompleted {
+                        task: Task::Wallet(task),
+                        execution_result: Err(CORE_WALLET_SPLIT_UNSUPPORTED.to_owned()),
+                    },
                 }
             } else {
                 BackendEvent::TaskCompleted {
@@ -249,6 +480,422 @@ pub(super) async fn run_wallet_task<'s>(
                 }
             }
         }
+        WalletTask::SetSweepWallet(ref private_key) => {
+            match add_wallet_by_private_key_as_string(
+                sweep_wallet_state,
+                private_key,
+                insight,
+                core_client,
+            )
+            .await
+            {
+                Ok(_) => {
+                    let address = sweep_wallet_state
+                        .lock()
+                        .await
+                        .as_ref()
+                        .map(|wallet| wallet.receive_address().to_string());
+                    BackendEvent::TaskCompletedStateChange {
+                        task: Task::Wallet(task),
+                        execution_result: Ok("Set sweep wallet".into()),
+                        app_state_update: AppStateUpdate::SweepWalletAddress(address),
+                    }
+                }
+                Err(e) => BackendEvent::TaskCompleted {
+                    task: Task::Wallet(task),
+                    execution_result: Err(format!("{e}")),
+                },
+            }
+        }
+        WalletTask::ClearSweepWallet => {
+            *sweep_wallet_state.lock().await = None;
+            BackendEvent::TaskCompletedStateChange {
+                task: Task::Wallet(task),
+                execution_result: Ok("Cleared sweep wallet".into()),
+                app_state_update: AppStateUpdate::SweepWalletAddress(None),
+            }
+        }
+        WalletTask::AddNamed {
+            ref name,
+            ref private_key,
+        } => {
+            if let Err(e) = check_new_wallet(app_state, name).await {
+                return BackendEvent::TaskCompleted {
+                    task: Task::Wallet(task),
+                    execution_result: Err(e),
+                };
+            }
+
+            let new_wallet = Mutex::new(None);
+            let added = match private_key {
+                Some(private_key) => {
+                    add_wallet_by_private_key_as_string(
+                        &new_wallet,
+                        private_key,
+                        insight,
+                        core_client,
+                    )
+                    .await
+                }
+                None => {
+                    let mut rng = StdRng::from_entropy();
+                    let network = Config::load().core_network();
+                    let private_key = PrivateKey::new(SecretKey::new(&mut rng), network);
+                    add_wallet_by_private_key(&new_wallet, private_key, insight, core_client).await;
+                    Ok(())
+                }
+            };
+            match added {
+                Ok(()) => {
+                    let wallet = new_wallet.into_inner().expect("wallet was added above");
+                    let message = format!("Added and loaded wallet {}", name);
+                    load_wallet(app_state, Some(name.clone()), wallet).await;
+
+                    let wallet_guard = wallet_state.lock().await;
+                    BackendEvent::TaskCompletedStateChange {
+                        task: Task::Wallet(task),
+                        execution_result: Ok(message.into()),
+                        app_state_update: AppStateUpdate::LoadedWallet(MutexGuard::map(
+                            wallet_guard,
+                            |opt| opt.as_mut().expect("wallet was loaded above"),
+                        )),
+                    }
+                }
+                Err(e) => BackendEvent::TaskCompleted {
+                    task: Task::Wallet(task),
+                    execution_result: Err(format!("{e}")),
+                },
+            }
+        }
+        WalletTask::AddByMnemonic {
+            ref name,
+            ref mnemonic,
+        } => {
+            if let Err(e) = check_new_wallet(app_state, name).await {
+                return BackendEvent::TaskCompleted {
+                    task: Task::Wallet(task),
+                    execution_result: Err(e),
+                };
+            }
+
+            let new_wallet = Mutex::new(None);
+            if let Err(e) =
+                add_wallet_by_mnemonic(&new_wallet, mnemonic, insight, core_client).await
+            {
+                return BackendEvent::TaskCompleted {
+                    task: Task::Wallet(task),
+                    execution_result: Err(format!("{e}")),
+                };
+            }
+            let wallet = new_wallet.into_inner().expect("wallet was added above");
+            let message = format!(
+                "Added and loaded wallet {} with address {}",
+                name,
+                wallet.receive_address()
+            );
+            load_wallet(app_state, Some(name.clone()), wallet).await;
+
+            let wallet_guard = wallet_state.lock().await;
+            BackendEvent::TaskCompletedStateChange {
+                task: Task::Wallet(task),
+                execution_result: Ok(message.into()),
+                app_state_update: AppStateUpdate::LoadedWallet(MutexGuard::map(
+                    wallet_guard,
+                    |opt| opt.as_mut().expect("wallet was loaded above"),
+                )),
+            }
+        }
+        WalletTask::AddCoreWallet {
+            ref name,
+            ref core_wallet_name,
+        } => {
+            if let Err(e) = check_new_wallet(app_state, name).await {
+                return BackendEvent::TaskCompleted {
+                    task: Task::Wallet(task),
+                    execution_result: Err(e),
+                };
+            }
+            let wallet = match CoreRpcWallet::new(core_wallet_name.trim().to_owned()) {
+                Ok(wallet) => wallet,
+                Err(e) => {
+                    return BackendEvent::TaskCompleted {
+                        task: Task::Wallet(task),
+                        execution_result: Err(format!("Failed to add the Core wallet: {}", e)),
+                    };
+                }
+            };
+            let message = format!(
+                "Added and loaded wallet {}, backed by Core wallet {} with {} in {} UTXOs",
+                name,
+                wallet.display_name(),
+                units::format_duffs(wallet.utxos.values().map(|out| out.value).sum()),
+                wallet.utxos.len()
+            );
+            load_wallet(app_state, Some(name.clone()), Wallet::CoreRpcWallet(wallet)).await;
+
+            let wallet_guard = wallet_state.lock().await;
+            BackendEvent::TaskCompletedStateChange {
+                task: Task::Wallet(task),
+                execution_result: Ok(message.into()),
+                app_state_update: AppStateUpdate::LoadedWallet(MutexGuard::map(
+                    wallet_guard,
+                    |opt| opt.as_mut().expect("wallet was loaded above"),
+                )),
+            }
+        }
+        WalletTask::Select(ref name) => {
+            if let Err(e) = check_wallet_switch_allowed(app_state) {
+                return BackendEvent::TaskCompleted {
+                    task: Task::Wallet(task),
+                    execution_result: Err(e),
+                };
+            }
+            let Some(mut wallet) = app_state.stored_wallets.lock().await.remove(name) else {
+                return BackendEvent::TaskCompleted {
+                    execution_result: Err(format!("No wallet named {}", name)),
+                    task: Task::Wallet(task),
+                };
+            };
+            // The wallet may have been funded since it was stored
+            if let Err(e) = wallet.reload_utxos(insight, core_client).await {
+                tracing::warn!("Failed to refresh wallet {}: {}", name, e);
+            }
+            let message = format!("Loaded wallet {}", name);
+            load_wallet(app_state, Some(name.clone()), wallet).await;
+
+            let wallet_guard = wallet_state.lock().await;
+            BackendEvent::TaskCompletedStateChange {
+                task: Task::Wallet(task),
+                execution_result: Ok(message.into()),
+                app_state_update: AppStateUpdate::LoadedWallet(MutexGuard::map(
+                    wallet_guard,
+                    |opt| opt.as_mut().expect("wallet was loaded above"),
+                )),
+            }
+        }
+        WalletTask::Remove(ref name) => {
+            let mut stored_wallets = app_state.stored_wallets.lock().await;
+            // The wallet may have been funded since it was stored, and removing it
+            // removes the only copy of its key
+            let reloaded = match stored_wallets.get_mut(name) {
+                Some(wallet) => wallet.reload_utxos(insight, core_client).await.map(|_| ()),
+                None => Ok(()),
+            };
+            let execution_result = match (stored_wallets.get(name), reloaded) {
+                (None, _) => Err(format!("No wallet named {}", name)),
+                (Some(_), Err(e)) => Err(format!(
+                    "Couldn't check that wallet {} is empty, it wasn't removed: {}",
+                    name, e
+                )),
+                (Some(wallet), Ok(())) if wallet.balance() > 0 => Err(format!(
+                    "Wallet {} still holds {}, load it and spend its funds before removing it",
+                    name,
+                    wallet.balance_formatted()
+                )),
+                (Some(_), Ok(())) => {
+                    stored_wallets.remove(name);
+                    Ok(format!("Removed wallet {}", name).into())
+                }
+            };
+            BackendEvent::TaskCompleted {
+                task: Task::Wallet(task),
+                execution_result,
+            }
+        }
+        WalletTask::ToggleUtxoFrozen(ref outpoint) => {
+            let mut wallet_guard = wallet_state.lock().await;
+            let Some(wallet) = wallet_guard.deref_mut() else {
+                return BackendEvent::TaskCompleted {
+                    task: Task::Wallet(task),
+                    execution_result: Err("No wallet loaded".to_owned()),
+                };
+            };
+            match wallet.toggle_utxo_frozen(outpoint) {
+                Ok(frozen) => {
+                    let message = if frozen {
+                        format!("Froze {}", outpoint)
+                    } else {
+                        format!("Unfroze {}", outpoint)
+                    };
+                    BackendEvent::TaskCompletedStateChange {
+                        task: Task::Wallet(task),
+                        execution_result: Ok(message.into()),
+                        app_state_update: AppStateUpdate::LoadedWallet(MutexGuard::map(
+                            wallet_guard,
+                            |opt| opt.as_mut().expect("wallet is loaded"),
+                        )),
+                    }
+                }
+                Err(e) => BackendEvent::TaskCompleted {
+                    task: Task::Wallet(task),
+                    execution_result: Err(e),
+                },
+            }
+        }
+        WalletTask::SplitUTXOsForStrategy {
+            ref strategy_name,
+            duration,
+        } => {
+            if app_state.strategy_runs().any_running() {
+                return BackendEvent::TaskCompleted {
+                    task: Task::Wallet(task),
+                    execution_result: Err("Can't split UTXOs while a strategy runs".to_owned()),
+                };
+            }
+            let Some(strategy) = app_state
+                .available_strategies
+                .lock()
+                .await
+                .get(strategy_name)
+                .cloned()
+            else {
+                return BackendEvent::TaskCompleted {
+                    execution_result: Err(format!("No strategy named {}", strategy_name)),
+                    task: Task::Wallet(task),
+                };
+            };
+            // Same estimate the run funds its asset locks by
+            let count = dry_run::estimated_asset_lock_proofs(&strategy, duration);
+            let value = dry_run::asset_lock_cost(&strategy) / CREDITS_PER_DUFF;
+            if count == 0 {
+                return BackendEvent::TaskCompleted {
+                    task: Task::Wallet(task),
+                    execution_result: Ok(format!(
+                        "A run of {} funds no asset locks, no UTXOs needed",
+                        strategy_name
+                    )
+                    .into()),
+                };
+            }
+
+            let mut wallet_guard = wallet_state.lock().await;
+            let wallet = match wallet_guard.deref_mut() {
+                Some(Wallet::SingleKeyWallet(wallet)) => wallet,
+                Some(Wallet::CoreRpcWallet(_)) => {
+                    return BackendEvent::TaskCompleted {
+                        task: Task::Wallet(task),
+                        execution_result: Err(CORE_WALLET_SPLIT_UNSUPPORTED.to_owned()),
+                    };
+                }
+                None => {
+                    return BackendEvent::TaskCompleted {
+                        task: Task::Wallet(task),
+                        execution_result: Err("No wallet loaded".to_owned()),
+                    };
+                }
+            };
+            match wallet.split_into_outputs(sdk, count as usize, value).await {
+                Ok(txids) => BackendEvent::TaskCompletedStateChange {
+                    task: Task::Wallet(task),
+                    execution_result: Ok(format!(
+                        "Created {} UTXOs of {} for the asset locks of {} in {} transactions",
+                        count,
+                        units::format_duffs(value),
+                        strategy_name,
+                        txids.len()
+                    )
+                    .into()),
+                    app_state_update: AppStateUpdate::LoadedWallet(MutexGuard::map(
+                        wallet_guard,
+                        |opt| opt.as_mut().expect("wallet is loaded"),
+                    )),
+                },
+                Err(e) => {
+                    // Inputs of a failed broadcast are still unspent
+                    if let Err(reload_error) = wallet.reload_utxos(insight, core_client).await {
+                        tracing::warn!("Failed to refresh the wallet: {}", reload_error);
+                    }
+                    let error = match e {
+                        WalletError::Balance => format!(
+                            "Not enough spendable balance for {} UTXOs of {}, {} needed \
+                             without fees",
+                            count,
+                            units::format_duffs(value),
+                            units::format_duffs(count * value)
+                        ),
+                        e => e.to_string(),
+                    };
+                    BackendEvent::TaskCompleted {
+                        task: Task::Wallet(task),
+                        execution_result: Err(error),
+                    }
+                }
+            }
+        }
+        WalletTask::Send {
+            ref address,
+            amount,
+            fee_level,
+        } => {
+            let recipient = match Address::from_str(address)
+                .map_err(|e| e.to_string())
+                .and_then(|address| {
+                    address
+                        .require_network(Config::load().core_network())
+                        .map_err(|e| e.to_string())
+                }) {
+                Ok(recipient) => recipient,
+                Err(e) => {
+                    return BackendEvent::TaskCompleted {
+                        task: Task::Wallet(task),
+                        execution_result: Err(format!("Invalid address {}: {}", address, e)),
+                    }
+                }
+            };
+            let mut wallet_guard = wallet_state.lock().await;
+            let Some(wallet) = wallet_guard.as_mut() else {
+                return BackendEvent::TaskCompleted {
+                    task: Task::Wallet(task),
+                    execution_result: Err("No wallet loaded".to_owned()),
+                };
+            };
+            let (transaction, fee) = match wallet.payment_transaction(&recipient, amount, fee_level)
+            {
+                Ok(payment) => payment,
+                Err(e) => {
+                    return BackendEvent::TaskCompleted {
+                        task: Task::Wallet(task),
+                        execution_result: Err(e.to_string()),
+                    }
+                }
+            };
+            let request = BroadcastTransactionRequest {
+                transaction: transaction.serialize(),
+                allow_high_fees: false,
+                bypass_limits: false,
+            };
+            match sdk
+                .execute(request, RequestSettings::default())
+                .await
+                .into_inner()
+            {
+                Ok(_) => BackendEvent::TaskCompletedStateChange {
+                    task: Task::Wallet(task),
+                    execution_result: Ok(format!(
+                        "Sent {} to {} with a fee of {}, transaction {}",
+                        units::format_duffs(amount),
+                        recipient,
+                        units::format_duffs(fee),
+                        transaction.txid()
+                    )
+                    .into()),
+                    app_state_update: AppStateUpdate::LoadedWallet(MutexGuard::map(
+                        wallet_guard,
+                        |opt| opt.as_mut().expect("wallet is loaded"),
+                    )),
+                },
+                Err(e) => {
+                    // The inputs taken for the payment are still unspent
+                    if let Err(reload_error) = wallet.reload_utxos(insight, core_client).await {
+                        tracing::warn!("Failed to refresh the wallet: {}", reload_error);
+                    }
+                    BackendEvent::TaskCompleted {
+                        task: Task::Wallet(task),
+                        execution_result: Err(format!("Failed to broadcast the payment: {}", e)),
+                    }
+                }
+            }
+        }
     }
 }
 
@@ -265,53 +912,83 @@ pub enum WalletError {
 #[derive(Debug, Clone, Encode, Decode)]
 pub enum Wallet {
     SingleKeyWallet(SingleKeyWallet),
+    CoreRpcWallet(CoreRpcWallet),
 }
 
 impl Wallet {
-    pub(crate) fn private_key_for_address(&self, address: &Address) -> &PrivateKey {
-        match self {
-            Wallet::SingleKeyWallet(single_wallet) => {
-                single_wallet.private_key_for_address(address)
-            }
-        }
-    }
-
     pub(crate) fn asset_lock_transaction(
         &mut self,
         seed: Option<u64>,
         amount: u64,
     ) -> Result<(Transaction, PrivateKey), WalletError> {
+        let (tx, mut private_keys) = self.batch_asset_lock_transaction(seed, amount, 1)?;
+        Ok((tx, private_keys.remove(0)))
+    }
+
+    /// Asset lock transaction with `count` credit outputs of `amount` each, one
+    /// per returned one-time private key. Output `i` is used with a proof of
+    /// output index `i`.
+    pub(crate) fn batch_asset_lock_transaction(
+        &mut self,
+        seed: Option<u64>,
+        amount: u64,
+        count: usize,
+    ) -> Result<(Transaction, Vec<PrivateKey>), WalletError> {
         let mut rng = match seed {
             None => StdRng::from_entropy(),
             Some(seed_value) => StdRng::seed_from_u64(seed_value),
         };
         let fee = 30_000;
-        let random_private_key: [u8; 32] = rng.gen();
         let network = Config::load().core_network();
-        let private_key =
-            PrivateKey::from_slice(&random_private_key, network).expect("expected a private key");
-
         let secp = Secp256k1::new();
-        let asset_lock_public_key = private_key.public_key(&secp);
+        let private_keys: Vec<PrivateKey> = (0..count.max(1))
+            .map(|_| {
+                let random_private_key: [u8; 32] = rng.gen();
+                PrivateKey::from_slice(&random_private_key, network)
+                    .expect("expected a private key")
+            })
+            .collect();
+        let total_amount = amount * private_keys.len() as u64;
 
-        let one_time_key_hash = asset_lock_public_key.pubkey_hash();
+        let payload_outputs = private_keys
+            .iter()
+            .map(|private_key| TxOut {
+                value: amount,
+                script_pubkey: ScriptBuf::new_p2pkh(&private_key.public_key(&secp).pubkey_hash()),
+            })
+            .collect();
+        let burn_output = TxOut {
+            value: total_amount,
+            script_pubkey: ScriptBuf::new_op_return(&[]),
+        };
+        let payload = AssetLockPayload {
+            version: 1,
+            credit_outputs: payload_outputs,
+        };
+
+        let wallet = match self {
+            Wallet::SingleKeyWallet(wallet) => wallet,
+            Wallet::CoreRpcWallet(wallet) => {
+                let (tx, _) = wallet.funded_transaction(
+                    vec![burn_output],
+                    fee,
+                    Some(TransactionPayload::AssetLockPayloadType(payload)),
+                )?;
+                return Ok((tx, private_keys));
+            }
+        };
 
         let (mut utxos, change) =
-            self.take_unspent_utxos_for(amount + fee)
+            wallet
+                .take_unspent_utxos_for(total_amount + fee)
                 .ok_or(WalletError::Custom(
                     "take_unspent_utxos_for() returned None".to_string(),
                 ))?;
 
-        let change_address = self.change_address();
+        // A fresh address per transaction, so the change of concurrent asset locks
+        // doesn't pile up on one address whose outputs they would all compete for
+        let change_address = wallet.next_change_address();
 
-        let payload_output = TxOut {
-            value: amount,
-            script_pubkey: ScriptBuf::new_p2pkh(&one_time_key_hash),
-        };
-        let burn_output = TxOut {
-            value: amount,
-            script_pubkey: ScriptBuf::new_op_return(&[]),
-        };
         if change < fee {
             return Err(WalletError::Custom(
                 "Change < Fee in asset_lock_transaction()".to_string(),
@@ -321,10 +998,6 @@ impl Wallet {
             value: change - fee,
             script_pubkey: change_address.script_pubkey(),
         };
-        let payload = AssetLockPayload {
-            version: 1,
-            credit_outputs: vec![payload_output],
-        };
 
         // we need to get all inputs from utxos to add them to the transaction
 
@@ -382,7 +1055,7 @@ impl Wallet {
                 let message =
                     Message::from_slice(sighash.as_byte_array()).expect("Error creating message");
 
-                let private_key = self.private_key_for_address(&input_address);
+                let private_key = wallet.private_key_for_address(&input_address);
 
                 // Sign the message with the private key
                 let sig = secp.sign_ecdsa(&message, &private_key.inner);
@@ -404,62 +1077,215 @@ impl Wallet {
                 input.script_sig = ScriptBuf::from_bytes(sig_script);
             });
 
-        Ok((tx, private_key))
+        Ok((tx, private_keys))
+    }
+
+    /// Signed transaction paying `amount` to `recipient` with its change going
+    /// to a fresh change address, and its fee. The spent UTXOs are taken out
+    /// of the wallet.
+    pub(crate) fn payment_transaction(
+        &mut self,
+        recipient: &Address,
+        amount: u64,
+        fee_level: FeeLevel,
+    ) -> Result<(Transaction, u64), WalletError> {
+        if amount < DUST_LIMIT {
+            return Err(WalletError::Custom(format!(
+                "Can't send less than {}",
+                units::format_duffs(DUST_LIMIT)
+            )));
+        }
+        let recipient_output = TxOut {
+            value: amount,
+            script_pubkey: recipient.script_pubkey(),
+        };
+        let wallet = match self {
+            Wallet::SingleKeyWallet(wallet) => wallet,
+            Wallet::CoreRpcWallet(wallet) => {
+                let fee = wallet
+                    .transaction_fee(amount, 2, fee_level)
+                    .ok_or(WalletError::Balance)?;
+                return wallet.funded_transaction(vec![recipient_output], fee, None);
+            }
+        };
+        let fee = wallet
+            .transaction_fee(amount, 2, fee_level)
+            .ok_or(WalletError::Balance)?;
+        let (utxos, change) = wallet
+            .take_unspent_utxos_for(amount + fee)
+            .ok_or(WalletError::Balance)?;
+
+        let mut outputs = vec![recipient_output];
+        // Change too small to be relayed goes to the fee
+        if change >= DUST_LIMIT {
+            outputs.push(TxOut {
+                value: change,
+                script_pubkey: wallet.next_change_address().script_pubkey(),
+            });
+        }
+        let fee = fee + if change >= DUST_LIMIT { 0 } else { change };
+
+        Ok((wallet.signed_transaction(&utxos, outputs), fee))
+    }
+
+    pub fn receive_address(&self) -> Address {
+        match self {
+            Wallet::SingleKeyWallet(wallet) => wallet.receive_address(),
+            Wallet::CoreRpcWallet(wallet) => wallet.address.clone(),
+        }
+    }
+
+    pub fn change_address(&self) -> Address {
+        match self {
+            Wallet::SingleKeyWallet(wallet) => wallet.change_address(),
+            Wallet::CoreRpcWallet(wallet) => wallet.address.clone(),
+        }
+    }
+
+    /// Derives a new change address and tracks it until its output is spent,
+    /// Core wallets get one from dashd.
+    pub fn next_change_address(&mut self) -> Address {
+        match self {
+            Wallet::SingleKeyWallet(wallet) => wallet.next_change_address(),
+            Wallet::CoreRpcWallet(wallet) => wallet.next_change_address(),
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            Wallet::SingleKeyWallet(wallet) => {
+                format!(
+                    "{} \npublic key: {} \naddress: {} \nbalance: {} \nchange addresses in use: {}",
+                    if wallet.hd_seed.is_some() {
+                        "Mnemonic Wallet"
+                    } else {
+                        "Single Key Wallet"
+                    },
+                    hex::encode(wallet.public_key.inner.serialize()),
+                    wallet.address.to_string().as_str(),
+                    wallet.balance_formatted(),
+                    wallet.change_keys.len()
+                )
+            }
+            Wallet::CoreRpcWallet(wallet) => {
+                format!(
+                    "Core RPC Wallet \nwallet: {} \naddress: {} \nbalance: {}",
+                    wallet.display_name(),
+                    wallet.address,
+                    self.balance_formatted()
+                )
+            }
+        }
+    }
+
+    pub fn balance_formatted(&self) -> String {
+        units::format_duffs(self.balance())
+    }
+
+    /// BIP32 master key of the wallet's HD seed identity keys are derived
+    /// from, see [identity_authentication_key], so identities can be recovered
+    /// from the mnemonic. Core wallets use the HD seed of the dashd wallet.
+    ///
+    /// `None` for single key wallets not added by a mnemonic, they have no HD
+    /// seed.
+    pub(crate) fn identity_master_key(&self) -> Result<Option<ExtendedPrivKey>, WalletError> {
+        let seed = match self {
+            Wallet::SingleKeyWallet(wallet) => match &wallet.hd_seed {
+                Some(seed) => seed.clone(),
+                None => return Ok(None),
+            },
+            Wallet::CoreRpcWallet(wallet) => wallet.hd_seed()?,
+        };
+        ExtendedPrivKey::new_master(Config::load().core_network(), &seed)
+            .map(Some)
+            .map_err(|e| WalletError::Custom(format!("Failed to derive the master key: {}", e)))
+    }
+
+    pub fn balance(&self) -> u64 {
+        self.utxos().values().map(|out| out.value).sum()
     }
 
-    pub fn receive_address(&self) -> Address {
+    /// UTXOs of the wallet, frozen ones included
+    pub fn utxos(&self) -> &HashMap<OutPoint, TxOut> {
         match self {
-            Wallet::SingleKeyWallet(wallet) => wallet.receive_address(),
+            Wallet::SingleKeyWallet(wallet) => &wallet.utxos,
+            Wallet::CoreRpcWallet(wallet) => &wallet.utxos,
         }
     }
 
-    pub fn change_address(&self) -> Address {
+    /// UTXOs left out of asset lock transactions, payments and UTXO splits
+    pub fn frozen_utxos(&self) -> &BTreeSet<OutPoint> {
         match self {
-            Wallet::SingleKeyWallet(wallet) => wallet.change_address(),
+            Wallet::SingleKeyWallet(wallet) => &wallet.frozen_utxos,
+            Wallet::CoreRpcWallet(wallet) => &wallet.frozen_utxos,
         }
     }
 
-    pub fn description(&self) -> String {
+    /// Confirmations of the UTXOs as of their last reload
+    pub fn utxo_confirmations(&self) -> &HashMap<OutPoint, u32> {
         match self {
-            Wallet::SingleKeyWallet(wallet) => {
-                format!(
-                    "Single Key Wallet \npublic key: {} \naddress: {} \nbalance: {}",
-                    hex::encode(wallet.public_key.inner.serialize()),
-                    wallet.address.to_string().as_str(),
-                    wallet.balance_dash_formatted()
-                )
-            }
+            Wallet::SingleKeyWallet(wallet) => &wallet.utxo_confirmations,
+            Wallet::CoreRpcWallet(wallet) => &wallet.utxo_confirmations,
         }
     }
 
-    pub fn balance_dash_formatted(&self) -> String {
-        match self {
-            Wallet::SingleKeyWallet(wallet) => wallet.balance_dash_formatted(),
-        }
+    /// UTXOs that aren't frozen
+    pub fn spendable_utxos(&self) -> impl Iterator<Item = (&OutPoint, &TxOut)> {
+        let frozen_utxos = self.frozen_utxos();
+        self.utxos()
+            .iter()
+            .filter(move |(outpoint, _)| !frozen_utxos.contains(outpoint))
     }
 
-    pub fn balance(&self) -> u64 {
+    pub fn spendable_balance(&self) -> u64 {
+        self.spendable_utxos().map(|(_, out)| out.value).sum()
+    }
+
+    /// Freezes an unfrozen UTXO or unfreezes a frozen one, returning whether
+    /// it's frozen now.
+    pub fn toggle_utxo_frozen(&mut self, outpoint: &OutPoint) -> Result<bool, String> {
         match self {
-            Wallet::SingleKeyWallet(wallet) => wallet.balance(),
+            Wallet::SingleKeyWallet(wallet) => wallet.toggle_utxo_frozen(outpoint),
+            Wallet::CoreRpcWallet(wallet) => wallet.toggle_utxo_frozen(outpoint),
         }
     }
 
-    pub fn take_unspent_utxos_for(
+    /// Runs `fund` with the `left_out` UTXOs frozen along with the frozen
+    /// ones, so the transactions it builds spend none of them.
+    pub(crate) fn without_utxos<T>(
         &mut self,
-        amount: u64,
-    ) -> Option<(BTreeMap<OutPoint, (TxOut, PublicKey, Address)>, u64)> {
-        match self {
-            Wallet::SingleKeyWallet(wallet) => wallet.take_unspent_utxos_for(amount),
+        left_out: &BTreeSet<OutPoint>,
+        fund: impl FnOnce(&mut Wallet) -> T,
+    ) -> T {
+        let frozen_utxos = match self {
+            Wallet::SingleKeyWallet(wallet) => &mut wallet.frozen_utxos,
+            Wallet::CoreRpcWallet(wallet) => &mut wallet.frozen_utxos,
+        };
+        let added: Vec<OutPoint> = left_out
+            .iter()
+            .filter(|outpoint| frozen_utxos.insert(**outpoint))
+            .copied()
+            .collect();
+        let result = fund(self);
+        let frozen_utxos = match self {
+            Wallet::SingleKeyWallet(wallet) => &mut wallet.frozen_utxos,
+            Wallet::CoreRpcWallet(wallet) => &mut wallet.frozen_utxos,
+        };
+        for outpoint in added {
+            frozen_utxos.remove(&outpoint);
         }
+        result
     }
 
     pub async fn reload_utxos(
         &mut self,
-        insight: &InsightAPIClient,
-        core_client: &Client,
+        insight: &impl InsightApi,
+        core_client: &impl CoreApi,
     ) -> Result<HashMap<OutPoint, TxOut>, String> {
         match self {
             Wallet::SingleKeyWallet(wallet) => wallet.reload_utxos(insight, core_client).await,
+            // Core lists the UTXOs of its own wallet, Insight isn't needed
+            Wallet::CoreRpcWallet(wallet) => wallet.reload_utxos(),
         }
     }
 }
@@ -470,6 +1296,16 @@ pub struct SingleKeyWallet {
     pub public_key: PublicKey,
     pub address: Address,
     pub utxos: HashMap<OutPoint, TxOut>,
+    /// Confirmations of the UTXOs as of their last reload, not persisted
+    pub utxo_confirmations: HashMap<OutPoint, u32>,
+    /// UTXOs left out of asset lock transactions and UTXO splits
+    pub frozen_utxos: BTreeSet<OutPoint>,
+    /// Change addresses that received an output not spent yet
+    pub change_keys: Vec<ChangeKey>,
+    pub next_change_index: u32,
+    /// BIP39 seed of the mnemonic the wallet was added by, its key and
+    /// identity keys are derived from it
+    pub hd_seed: Option<Vec<u8>>,
 }
 
 impl Clone for SingleKeyWallet {
@@ -479,6 +1315,72 @@ impl Clone for SingleKeyWallet {
             public_key: self.public_key.clone(),
             address: self.address.clone(),
             utxos: self.utxos.clone(),
+            utxo_confirmations: self.utxo_confirmations.clone(),
+            frozen_utxos: self.frozen_utxos.clone(),
+            change_keys: self.change_keys.clone(),
+            next_change_index: self.next_change_index,
+            hd_seed: self.hd_seed.clone(),
+        }
+    }
+}
+
+/// Authentication key `key_index` of identity number `identity_index` of a
+/// wallet, derived from its [Wallet::identity_master_key] along the DIP-13 path
+/// `m/9'/<coin type>'/5'/0'/0'/<identity index>'/<key index>'`.
+pub(crate) fn identity_authentication_key(
+    master_key: &ExtendedPrivKey,
+    identity_index: u32,
+    key_index: u32,
+) -> Result<PrivateKey, WalletError> {
+    let coin_type = if Config::load().is_mainnet() { 5 } else { 1 };
+    let path: DerivationPath = [
+        DIP9_PURPOSE,
+        coin_type,
+        DIP9_IDENTITY_FEATURE,
+        DIP13_AUTHENTICATION_SUB_FEATURE,
+        DIP13_ECDSA_KEY_TYPE,
+        identity_index,
+        key_index,
+    ]
+    .into_iter()
+    .map(ChildNumber::from_hardened_idx)
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e| WalletError::Custom(format!("Invalid derivation path: {}", e)))?
+    .into();
+
+    master_key
+        .derive_priv(&Secp256k1::new(), &path)
+        .map(|key| key.to_priv())
+        .map_err(|e| WalletError::Custom(format!("Failed to derive identity key: {}", e)))
+}
+
+/// Key of a change address.
+///
+/// Single key wallets may have no HD seed, so change keys are derived from a
+/// hash of the wallet secret and the change address index.
+#[derive(Debug, Clone)]
+pub struct ChangeKey {
+    pub index: u32,
+    pub private_key: PrivateKey,
+    pub public_key: PublicKey,
+    pub address: Address,
+}
+
+impl ChangeKey {
+    fn derive(wallet_private_key: &PrivateKey, index: u32) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(b"change");
+        hasher.update(wallet_private_key.inner.secret_bytes());
+        hasher.update(index.to_le_bytes());
+        let secret: [u8; 32] = hasher.finalize().into();
+        let network = Config::load().core_network();
+        let private_key = PrivateKey::from_slice(&secret, network).expect("expected a private key");
+        let public_key = private_key.public_key(&Secp256k1::new());
+        ChangeKey {
+            index,
+            private_key,
+            public_key,
+            address: Address::p2pkh(&public_key, network),
         }
     }
 }
@@ -497,7 +1399,19 @@ impl Encode for SingleKeyWallet {
                 )
             })
             .collect::<Vec<_>>();
-        string_utxos.encode(encoder)
+        string_utxos.encode(encoder)?;
+        self.next_change_index.encode(encoder)?;
+        self.change_keys
+            .iter()
+            .map(|key| key.index)
+            .collect::<Vec<_>>()
+            .encode(encoder)?;
+        self.frozen_utxos
+            .iter()
+            .map(|outpoint| outpoint.to_string())
+            .collect::<Vec<_>>()
+            .encode(encoder)?;
+        self.hd_seed.encode(encoder)
     }
 }
 
@@ -505,6 +1419,10 @@ impl Decode for SingleKeyWallet {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
         let bytes = <[u8; 32]>::decode(decoder)?;
         let string_utxos = Vec::<(String, u64, String)>::decode(decoder)?;
+        let next_change_index = u32::decode(decoder)?;
+        let change_indexes = Vec::<u32>::decode(decoder)?;
+        let string_frozen_utxos = Vec::<String>::decode(decoder)?;
+        let hd_seed = Option::<Vec<u8>>::decode(decoder)?;
         let network = Config::load().core_network();
 
         let private_key =
@@ -535,11 +1453,26 @@ impl Decode for SingleKeyWallet {
             })
             .collect::<HashMap<_, _>>();
 
+        let frozen_utxos = string_frozen_utxos
+            .iter()
+            .map(|outpoint| OutPoint::from_str(outpoint).expect("expected valid outpoint"))
+            .collect();
+
+        let change_keys = change_indexes
+            .into_iter()
+            .map(|index| ChangeKey::derive(&private_key, index))
+            .collect();
+
         Ok(SingleKeyWallet {
             private_key,
             public_key,
             address,
             utxos,
+            utxo_confirmations: HashMap::new(),
+            frozen_utxos,
+            change_keys,
+            next_change_index,
+            hd_seed,
         })
     }
 }
@@ -548,6 +1481,10 @@ impl<'a> BorrowDecode<'a> for SingleKeyWallet {
     fn borrow_decode<D: BorrowDecoder<'a>>(decoder: &mut D) -> Result<Self, DecodeError> {
         let bytes = <[u8; 32]>::decode(decoder)?;
         let string_utxos = Vec::<(String, u64, String)>::decode(decoder)?;
+        let next_change_index = u32::decode(decoder)?;
+        let change_indexes = Vec::<u32>::decode(decoder)?;
+        let string_frozen_utxos = Vec::<String>::decode(decoder)?;
+        let hd_seed = Option::<Vec<u8>>::decode(decoder)?;
         let network = Config::load().core_network();
 
         let private_key =
@@ -579,26 +1516,84 @@ impl<'a> BorrowDecode<'a> for SingleKeyWallet {
             })
             .collect::<HashMap<_, _>>();
 
+        let frozen_utxos = string_frozen_utxos
+            .iter()
+            .map(|outpoint| OutPoint::from_str(outpoint).expect("expected valid outpoint"))
+            .collect();
+
+        let change_keys = change_indexes
+            .into_iter()
+            .map(|index| ChangeKey::derive(&private_key, index))
+            .collect();
+
         Ok(SingleKeyWallet {
             private_key,
             public_key,
             address,
             utxos,
+            utxo_confirmations: HashMap::new(),
+            frozen_utxos,
+            change_keys,
+            next_change_index,
+            hd_seed,
         })
     }
 }
 
 impl SingleKeyWallet {
-    pub fn balance_dash_formatted(&self) -> String {
-        let satoshis = self.balance();
-        let dash = satoshis as f64 / 100_000_000f64;
-        format!("{:.4}", dash)
+    pub fn balance_formatted(&self) -> String {
+        units::format_duffs(self.balance())
     }
 
     pub fn balance(&self) -> u64 {
         self.utxos.iter().map(|(_, out)| out.value).sum()
     }
 
+    /// UTXOs that aren't frozen
+    pub fn spendable_utxos(&self) -> impl Iterator<Item = (&OutPoint, &TxOut)> {
+        self.utxos
+            .iter()
+            .filter(|(outpoint, _)| !self.frozen_utxos.contains(outpoint))
+    }
+
+    pub fn spendable_balance(&self) -> u64 {
+        self.spendable_utxos().map(|(_, out)| out.value).sum()
+    }
+
+    /// Freezes an unfrozen UTXO or unfreezes a frozen one, returning whether
+    /// it's frozen now.
+    pub fn toggle_utxo_frozen(&mut self, outpoint: &OutPoint) -> Result<bool, String> {
+        if !self.utxos.contains_key(outpoint) {
+            return Err(format!("{} is not an output of the wallet", outpoint));
+        }
+        if self.frozen_utxos.remove(outpoint) {
+            Ok(false)
+        } else {
+            self.frozen_utxos.insert(outpoint.clone());
+            Ok(true)
+        }
+    }
+
+    /// Fee of a transaction with `outputs` outputs, change included, paying
+    /// `amount` in total at the rate of `fee_level`, for the spendable UTXOs
+    /// that would cover both. None if the balance doesn't.
+    pub fn transaction_fee(&self, amount: u64, outputs: usize, fee_level: FeeLevel) -> Option<u64> {
+        let mut inputs = 0;
+        let mut total = 0;
+        for (_, utxo) in self.spendable_utxos() {
+            if self.owner_of(&utxo.script_pubkey).is_none() {
+                continue;
+            }
+            inputs += 1;
+            total += utxo.value;
+            let fee = p2pkh_transaction_size(inputs, outputs) * fee_level.duffs_per_byte();
+            if total >= amount + fee {
+                return Some(fee);
+            }
+        }
+        None
+    }
+
     pub fn take_unspent_utxos_for(
         &mut self,
         amount: u64,
@@ -606,15 +1601,15 @@ impl SingleKeyWallet {
         let mut required: i64 = amount as i64;
         let mut taken_utxos = BTreeMap::new();
 
-        for (outpoint, utxo) in self.utxos.iter() {
+        for (outpoint, utxo) in self.spendable_utxos() {
             if required <= 0 {
                 break;
             }
+            let Some((public_key, address)) = self.owner_of(&utxo.script_pubkey) else {
+                continue;
+            };
             required -= utxo.value as i64;
-            taken_utxos.insert(
-                outpoint.clone(),
-                (utxo.clone(), self.public_key, self.address.clone()),
-            );
+            taken_utxos.insert(outpoint.clone(), (utxo.clone(), public_key, address));
         }
 
         // If we didn't gather enough UTXOs to cover the required amount
@@ -627,48 +1622,65 @@ impl SingleKeyWallet {
             self.utxos.remove(outpoint);
         }
 
+        // A change address gets a single output, once it's spent the address is done
+        let spent_change: Vec<&Address> = taken_utxos
+            .values()
+            .map(|(_, _, address)| address)
+            .filter(|address| **address != self.address)
+            .collect();
+        self.change_keys
+            .retain(|key| !spent_change.contains(&&key.address));
+
         Some((taken_utxos, required.abs() as u64))
     }
 
     pub async fn reload_utxos(
         &mut self,
-        insight: &InsightAPIClient,
-        core_client: &Client,
+        insight: &impl InsightApi,
+        core_client: &impl CoreApi,
     ) -> Result<HashMap<OutPoint, TxOut>, String> {
-        // First, let's try to get UTXOs from the RPC client using `list_unspent`.
-        match core_client.list_unspent(Some(1), None, Some(&[&self.address]), None, None) {
-            Ok(utxos) => {
-                // Convert RPC UTXOs to the desired HashMap format
-                let mut utxo_map = HashMap::new();
-                for utxo in utxos {
-                    let outpoint = OutPoint::new(utxo.txid, utxo.vout);
-                    let tx_out = TxOut {
-                        value: utxo.amount.to_sat(),
-                        script_pubkey: utxo.script_pub_key,
-                    };
-                    utxo_map.insert(outpoint, tx_out);
-                }
-                self.utxos = utxo_map.clone(); // Store the result in `self.utxos`
-                Ok(utxo_map)
-            }
-            Err(first_error) => {
-                // If that doesn't work, use the Insight API as a fallback
-                match insight
-                    .utxos_with_amount_for_addresses(&[&self.address])
-                    .await
-                {
-                    Ok(utxos) => {
-                        self.utxos = utxos.clone();
-                        Ok(utxos)
-                    }
-                    Err(err) => Err(format!(
+        let addresses: Vec<&Address> = std::iter::once(&self.address)
+            .chain(self.change_keys.iter().map(|key| &key.address))
+            .collect();
+
+        // First, let's try to get UTXOs from the RPC client using `list_unspent`,
+        // if that doesn't work, use the Insight API as a fallback
+        let utxos = match core_client.unspent_outputs(&addresses) {
+            Ok(utxos) => utxos,
+            Err(first_error) => match insight.utxos_with_amount_for_addresses(&addresses).await {
+                Ok(utxos) => utxos,
+                Err(err) => {
+                    return Err(format!(
                         "First error from Core: {}, Second Error from Insight: {}",
-                        first_error.to_string(),
-                        err.to_string()
-                    )),
+                        first_error, err
+                    ))
                 }
-            }
-        }
+            },
+        };
+        let confirmations = utxos
+            .iter()
+            .filter_map(|(outpoint, (_, confirmations))| {
+                confirmations.map(|confirmations| (*outpoint, confirmations))
+            })
+            .collect();
+        let utxos: HashMap<OutPoint, TxOut> = utxos
+            .into_iter()
+            .map(|(outpoint, (tx_out, _))| (outpoint, tx_out))
+            .collect();
+        self.set_utxos(utxos.clone(), confirmations);
+        Ok(utxos)
+    }
+
+    /// Stores reloaded UTXOs, forgetting the frozen ones that were spent.
+    fn set_utxos(
+        &mut self,
+        utxos: HashMap<OutPoint, TxOut>,
+        confirmations: HashMap<OutPoint, u32>,
+    ) {
+        self.frozen_utxos
+            .retain(|outpoint| utxos.contains_key(outpoint));
+        self.utxos = utxos;
+        self.utxo_confirmations = confirmations;
     }
 
     /// Takes a usize `desired_utxo_count` specifying the desired number of UTXOs one wants the wallet to have
@@ -685,18 +1697,19 @@ impl SingleKeyWallet {
         &mut self,
         sdk: &Sdk,
         desired_utxo_count: usize,
-        insight: &InsightAPIClient,
-        core_client: &Client,
+        insight: &impl InsightApi,
+        core_client: &impl CoreApi,
     ) -> Result<(), WalletError> {
         tracing::info!("Splitting wallet UTXOs into {} UTXOs", desired_utxo_count);
 
         // Initialize
-        const MAX_OUTPUTS_PER_TRANSACTION: usize = 24; // Dash Core only allows 24 outputs per tx
-        let current_wallet_balance = self.balance();
+        let current_wallet_balance = self.spendable_balance();
         let mut remaining_utxos_in_wallet = self
             .reload_utxos(insight, core_client)
             .await
             .expect("Expected to reload utxos");
+        // Frozen UTXOs are kept as they are
+        remaining_utxos_in_wallet.retain(|outpoint, _| !self.frozen_utxos.contains(outpoint));
         let mut num_utxos_remaining_to_create = desired_utxo_count;
 
         // Say we want 50 UTXOs, we need 3 transactions (24 + 24 + 2)
@@ -777,20 +1790,24 @@ impl SingleKeyWallet {
                 special_transaction_payload: None,
             };
 
-            // Sign the transaction
+            // Sign the transaction, inputs may come from the wallet key or a change key
             let secp = Secp256k1::new();
             let cache = SighashCache::new(tx.clone());
             for (i, input) in tx.input.iter_mut().enumerate() {
+                let script_pubkey = remaining_utxos_in_wallet
+                    .get(&input.previous_output)
+                    .expect("expected a txout")
+                    .script_pubkey
+                    .clone();
+                let (public_key, address) = self
+                    .owner_of(&script_pubkey)
+                    .ok_or_else(|| WalletError::Custom("UTXO of an unknown address".to_owned()))?;
                 let sighash = cache
-                    .legacy_signature_hash(
-                        i,
-                        &self.receive_address().script_pubkey(),
-                        1, /* SIGHASH_ALL */
-                    )
+                    .legacy_signature_hash(i, &script_pubkey, 1 /* SIGHASH_ALL */)
                     .unwrap();
                 let message = Message::from_slice(&sighash[..]).unwrap();
                 let sig = secp
-                    .sign_ecdsa(&message, &self.private_key.inner)
+                    .sign_ecdsa(&message, &self.private_key_for_address(&address).inner)
                     .serialize_der();
                 let mut sig_with_sighash = sig.to_vec();
                 sig_with_sighash.push(1); // SIGHASH_ALL
@@ -799,7 +1816,7 @@ impl SingleKeyWallet {
                         &[sig_with_sighash.len() as u8], // Convert to slice for uniform handling
                         &sig_with_sighash[..], // Convert Vec<u8> to slice for concatenation
                         &[0x21],               // Single-element slice for the public key length
-                        &self.public_key.serialize()[..], // Public key as slice
+                        &public_key.serialize()[..], // Public key as slice
                     ]
                     .concat(),
                 );
@@ -896,18 +1913,521 @@ impl SingleKeyWallet {
         Ok(())
     }
 
+    /// Splits spendable UTXOs into `count` new outputs of `value` duffs each
+    /// to the receive address, broadcasting as many transactions as the
+    /// outputs limit requires. The new outputs are added to the wallet right
+    /// away, so they can be spent before they confirm.
+    pub async fn split_into_outputs(
+        &mut self,
+        sdk: &Sdk,
+        count: usize,
+        value: u64,
+    ) -> Result<Vec<Txid>, WalletError> {
+        let mut txids = Vec::new();
+        let mut created = Vec::new();
+        let mut remaining = count;
+        // The outputs already created are frozen meanwhile, so later transactions
+        // fund themselves from the change rather than by spending them
+        let result = loop {
+            if remaining == 0 {
+                break Ok(());
+            }
+            let batch = remaining.min(MAX_OUTPUTS_PER_TRANSACTION - 1);
+            let amount = value * batch as u64;
+            let Some(fee) = self.transaction_fee(amount, batch + 1, FeeLevel::Normal) else {
+                break Err(WalletError::Balance);
+            };
+            let Some((utxos, change)) = self.take_unspent_utxos_for(amount + fee) else {
+                break Err(WalletError::Balance);
+            };
+            let mut outputs = vec![
+                TxOut {
+                    value,
+                    script_pubkey: self.receive_address().script_pubkey(),
+                };
+                batch
+            ];
+            if change >= DUST_LIMIT {
+                outputs.push(TxOut {
+                    value: change,
+                    script_pubkey: self.next_change_address().script_pubkey(),
+                });
+            }
+            let tx = self.signed_transaction(&utxos, outputs);
+
+            let request = BroadcastTransactionRequest {
+                transaction: tx.serialize(),
+                allow_high_fees: false,
+                bypass_limits: false,
+            };
+            if let Err(e) = sdk
+                .execute(request, RequestSettings::default())
+                .await
+                .into_inner()
+            {
+                break Err(WalletError::Custom(format!(
+                    "Failed to broadcast splitting transaction {}: {}",
+                    txids.len() + 1,
+                    e
+                )));
+            }
+
+            let txid = tx.txid();
+            for (vout, output) in tx.output.into_iter().enumerate() {
+                let outpoint = OutPoint::new(txid, vout as u32);
+                if vout < batch {
+                    self.frozen_utxos.insert(outpoint);
+                    created.push(outpoint);
+                }
+                self.utxos.insert(outpoint, output);
+            }
+            txids.push(txid);
+            remaining -= batch;
+        };
+        for outpoint in &created {
+            self.frozen_utxos.remove(outpoint);
+        }
+        result.map(|_| txids)
+    }
+
+    /// Transaction spending `utxos` to `outputs`, each input signed by the
+    /// wallet key or change key it pays to.
+    fn signed_transaction(
+        &self,
+        utxos: &BTreeMap<OutPoint, (TxOut, PublicKey, Address)>,
+        outputs: Vec<TxOut>,
+    ) -> Transaction {
+        let mut tx = Transaction {
+            version: 1,
+            lock_time: 0,
+            input: utxos
+                .keys()
+                .map(|outpoint| TxIn {
+                    previous_output: *outpoint,
+                    script_sig: ScriptBuf::new(),
+                    sequence: 0xFFFFFFFF,
+                    witness: Witness::new(),
+                })
+                .collect(),
+            output: outputs,
+            special_transaction_payload: None,
+        };
+
+        let secp = Secp256k1::new();
+        let cache = SighashCache::new(tx.clone());
+        for (i, input) in tx.input.iter_mut().enumerate() {
+            let (tx_out, public_key, address) =
+                utxos.get(&input.previous_output).expect("expected a txout");
+            let sighash = cache
+                .legacy_signature_hash(i, &tx_out.script_pubkey, 1 /* SIGHASH_ALL */)
+                .expect("expected sighash");
+            let message = Message::from_slice(&sighash[..]).expect("Error creating message");
+            let sig = secp
+                .sign_ecdsa(&message, &self.private_key_for_address(address).inner)
+                .serialize_der();
+            let mut sig_with_sighash = sig.to_vec();
+            sig_with_sighash.push(1); // SIGHASH_ALL
+            input.script_sig = ScriptBuf::from_bytes(
+                [
+                    &[sig_with_sighash.len() as u8],
+                    &sig_with_sighash[..],
+                    &[0x21],
+                    &public_key.serialize()[..],
+                ]
+                .concat(),
+            );
+        }
+        tx
+    }
+
     pub fn change_address(&self) -> Address {
         self.address.clone()
     }
 
+    pub fn next_change_address(&mut self) -> Address {
+        let key = ChangeKey::derive(&self.private_key, self.next_change_index);
+        self.next_change_index += 1;
+        let address = key.address.clone();
+        self.change_keys.push(key);
+        address
+    }
+
     pub fn receive_address(&self) -> Address {
         self.address.clone()
     }
 
     pub fn private_key_for_address(&self, address: &Address) -> &PrivateKey {
-        if &self.address != address {
-            panic!("address doesn't match");
+        if &self.address == address {
+            return &self.private_key;
+        }
+        &self
+            .change_keys
+            .iter()
+            .find(|key| &key.address == address)
+            .expect("address doesn't match")
+            .private_key
+    }
+
+    /// Public key and address of the wallet key or change key an output pays to.
+    fn owner_of(&self, script_pubkey: &ScriptBuf) -> Option<(PublicKey, Address)> {
+        if &self.address.script_pubkey() == script_pubkey {
+            return Some((self.public_key, self.address.clone()));
+        }
+        self.change_keys
+            .iter()
+            .find(|key| &key.address.script_pubkey() == script_pubkey)
+            .map(|key| (key.public_key, key.address.clone()))
+    }
+}
+
+/// Message signed by a Core wallet's address key to derive its identity keys
+/// secret from
+/// dashd error code of wallet calls made while the wallet is locked
+const RPC_WALLET_UNLOCK_NEEDED: i32 = -13;
+
+/// Wallet of the connected dashd, which lists its UTXOs, gives its addresses
+/// and signs its transactions, its keys never leave Core. Only its HD seed is
+/// asked for when identity keys are derived.
+#[derive(Debug, Clone)]
+pub struct CoreRpcWallet {
+    /// Name of the wallet in dashd, empty for its default wallet
+    pub wallet_name: String,
+    /// Address given by dashd when the wallet was added, to fund it
+    pub address: Address,
+    pub utxos: HashMap<OutPoint, TxOut>,
+    /// Confirmations of the UTXOs as of their last reload, not persisted
+    pub utxo_confirmations: HashMap<OutPoint, u32>,
+    /// UTXOs left out of asset lock transactions and payments
+    pub frozen_utxos: BTreeSet<OutPoint>,
+}
+
+/// HD seed returned by `dumphdinfo`
+#[derive(Deserialize)]
+struct HdInfo {
+    hdseed: String,
+}
+
+/// Signed transaction returned by `signrawtransactionwithwallet`
+#[derive(Deserialize)]
+struct SignedRawTransaction {
+    hex: String,
+    complete: bool,
+}
+
+fn core_error(error: impl fmt::Display) -> WalletError {
+    WalletError::Custom(format!("Core RPC error: {}", error))
+}
+
+/// Same as [core_error], telling how to unlock the wallet if the call needed
+/// it unlocked.
+fn core_wallet_error(wallet_name: &str, error: dashcore_rpc::Error) -> WalletError {
+    match &error {
+        dashcore_rpc::Error::JsonRpc(dashcore_rpc::jsonrpc::error::Error::Rpc(rpc_error))
+            if rpc_error.code == RPC_WALLET_UNLOCK_NEEDED =>
+        {
+            WalletError::Custom(format!(
+                "The Core wallet {} is locked, unlock it in dashd with walletpassphrase first",
+                if wallet_name.is_empty() {
+                    "(default)"
+                } else {
+                    wallet_name
+                }
+            ))
+        }
+        _ => core_error(error),
+    }
+}
+
+/// RPC client of a wallet of the configured dashd, requests go to its
+/// `/wallet/<name>` endpoint when it has a name.
+fn core_wallet_client(wallet_name: &str) -> Result<Client, WalletError> {
+    let config = Config::load();
+    let mut url = format!("http://{}:{}", config.core_host, config.core_rpc_port);
+    if !wallet_name.is_empty() {
+        url.push_str(&format!("/wallet/{}", wallet_name));
+    }
+    Client::new(
+        &url,
+        Auth::UserPass(config.core_rpc_user, config.core_rpc_password),
+    )
+    .map_err(core_error)
+}
+
+fn parse_core_address(address: &str) -> Result<Address, WalletError> {
+    Address::from_str(address)
+        .map_err(|e| e.to_string())
+        .and_then(|address| {
+            address
+                .require_network(Config::load().core_network())
+                .map_err(|e| e.to_string())
+        })
+        .map_err(|e| WalletError::Custom(format!("Invalid address {} from Core: {}", address, e)))
+}
+
+impl CoreRpcWallet {
+    /// Wallet of dashd named `wallet_name`, with a new address of it and its
+    /// UTXOs loaded.
+    pub fn new(wallet_name: String) -> Result<Self, WalletError> {
+        let client = core_wallet_client(&wallet_name)?;
+        let address: String = client.call("getnewaddress", &[]).map_err(core_error)?;
+        let address = parse_core_address(&address)?;
+
+        let mut wallet = CoreRpcWallet {
+            wallet_name,
+            address,
+            utxos: HashMap::new(),
+            utxo_confirmations: HashMap::new(),
+            frozen_utxos: BTreeSet::new(),
+        };
+        wallet.reload_utxos().map_err(WalletError::Custom)?;
+        Ok(wallet)
+    }
+
+    /// HD seed of the dashd wallet. It isn't kept, dashd only gives it while
+    /// the wallet is unlocked.
+    fn hd_seed(&self) -> Result<Vec<u8>, WalletError> {
+        let client = core_wallet_client(&self.wallet_name)?;
+        let hd_info: HdInfo = client
+            .call("dumphdinfo", &[])
+            .map_err(|e| core_wallet_error(&self.wallet_name, e))?;
+        hex::decode(&hd_info.hdseed)
+            .map_err(|e| WalletError::Custom(format!("Invalid HD seed from Core: {}", e)))
+    }
+
+    /// Name of the wallet in dashd as shown to the user.
+    pub fn display_name(&self) -> &str {
+        if self.wallet_name.is_empty() {
+            "(default)"
+        } else {
+            &self.wallet_name
+        }
+    }
+
+    pub fn reload_utxos(&mut self) -> Result<HashMap<OutPoint, TxOut>, String> {
+        let client = core_wallet_client(&self.wallet_name).map_err(|e| e.to_string())?;
+        let unspent = client
+            .list_unspent(Some(1), None, None, None, None)
+            .map_err(|e| format!("Core failed to list the UTXOs of the wallet: {}", e))?;
+        let mut utxos = HashMap::new();
+        let mut confirmations = HashMap::new();
+        for utxo in unspent {
+            let outpoint = OutPoint::new(utxo.txid, utxo.vout);
+            confirmations.insert(outpoint, utxo.confirmations);
+            utxos.insert(
+                outpoint,
+                TxOut {
+                    value: utxo.amount.to_sat(),
+                    script_pubkey: utxo.script_pub_key,
+                },
+            );
+        }
+        self.frozen_utxos
+            .retain(|outpoint| utxos.contains_key(outpoint));
+        self.utxos = utxos.clone();
+        self.utxo_confirmations = confirmations;
+        Ok(utxos)
+    }
+
+    fn spendable_utxos(&self) -> impl Iterator<Item = (&OutPoint, &TxOut)> {
+        self.utxos
+            .iter()
+            .filter(|(outpoint, _)| !self.frozen_utxos.contains(outpoint))
+    }
+
+    pub fn toggle_utxo_frozen(&mut self, outpoint: &OutPoint) -> Result<bool, String> {
+        if !self.utxos.contains_key(outpoint) {
+            return Err(format!("{} is not an output of the wallet", outpoint));
+        }
+        if self.frozen_utxos.remove(outpoint) {
+            Ok(false)
+        } else {
+            self.frozen_utxos.insert(*outpoint);
+            Ok(true)
+        }
+    }
+
+    /// Fee of a transaction with `outputs` outputs, change included, paying
+    /// `amount` in total at the rate of `fee_level`. Core wallet outputs are
+    /// taken to be P2PKH.
+    pub fn transaction_fee(&self, amount: u64, outputs: usize, fee_level: FeeLevel) -> Option<u64> {
+        let mut total = 0;
+        for (inputs, (_, utxo)) in self.spendable_utxos().enumerate() {
+            total += utxo.value;
+            let fee = p2pkh_transaction_size(inputs + 1, outputs) * fee_level.duffs_per_byte();
+            if total >= amount + fee {
+                return Some(fee);
+            }
+        }
+        None
+    }
+
+    /// Takes spendable UTXOs out of the wallet until they cover `amount`,
+    /// returning them with the amount left over.
+    fn take_unspent_utxos_for(&mut self, amount: u64) -> Option<(BTreeMap<OutPoint, TxOut>, u64)> {
+        let mut total = 0;
+        let mut taken_utxos = BTreeMap::new();
+        for (outpoint, utxo) in self.spendable_utxos() {
+            if total >= amount {
+                break;
+            }
+            total += utxo.value;
+            taken_utxos.insert(*outpoint, utxo.clone());
+        }
+        if total < amount {
+            return None;
+        }
+        for outpoint in taken_utxos.keys() {
+            self.utxos.remove(outpoint);
+        }
+        Some((taken_utxos, total - amount))
+    }
+
+    /// A new change address of the wallet, or its address if dashd doesn't
+    /// give one.
+    fn next_change_address(&self) -> Address {
+        let change_address = core_wallet_client(&self.wallet_name).and_then(|client| {
+            let address: String = client
+                .call("getrawchangeaddress", &[])
+                .map_err(core_error)?;
+            parse_core_address(&address)
+        });
+        change_address.unwrap_or_else(|e| {
+            tracing::warn!("Sending change to the wallet address: {}", e);
+            self.address.clone()
+        })
+    }
+
+    /// Transaction paying `outputs` with UTXOs of the wallet and a `fee`, its
+    /// change going to a new change address, signed by dashd, and the fee it
+    /// pays with the change too small to be relayed. The spent UTXOs are
+    /// taken out of the wallet.
+    fn funded_transaction(
+        &mut self,
+        outputs: Vec<TxOut>,
+        fee: u64,
+        payload: Option<TransactionPayload>,
+    ) -> Result<(Transaction, u64), WalletError> {
+        let amount: u64 = outputs.iter().map(|output| output.value).sum();
+        let (utxos, change) = self
+            .take_unspent_utxos_for(amount + fee)
+            .ok_or(WalletError::Balance)?;
+
+        let mut outputs = outputs;
+        if change >= DUST_LIMIT {
+            outputs.push(TxOut {
+                value: change,
+                script_pubkey: self.next_change_address().script_pubkey(),
+            });
+        }
+        let fee = fee + if change >= DUST_LIMIT { 0 } else { change };
+        let tx = Transaction {
+            version: if payload.is_some() { 3 } else { 1 },
+            lock_time: 0,
+            input: utxos
+                .keys()
+                .map(|outpoint| TxIn {
+                    previous_output: *outpoint,
+                    script_sig: ScriptBuf::new(),
+                    sequence: 0xFFFFFFFF,
+                    witness: Witness::new(),
+                })
+                .collect(),
+            output: outputs,
+            special_transaction_payload: payload,
+        };
+
+        match self.signed(&tx) {
+            Ok(tx) => Ok((tx, fee)),
+            Err(e) => {
+                // Nothing was spent
+                self.utxos.extend(utxos);
+                Err(e)
+            }
+        }
+    }
+
+    fn signed(&self, tx: &Transaction) -> Result<Transaction, WalletError> {
+        let client = core_wallet_client(&self.wallet_name)?;
+        let signed: SignedRawTransaction = client
+            .call(
+                "signrawtransactionwithwallet",
+                &[hex::encode(tx.serialize()).into()],
+            )
+            .map_err(|e| core_wallet_error(&self.wallet_name, e))?;
+        if !signed.complete {
+            return Err(WalletError::Custom(
+                "Core couldn't sign every input of the transaction".to_owned(),
+            ));
         }
-        &self.private_key
+        let bytes = hex::decode(&signed.hex)
+            .map_err(|e| WalletError::Custom(format!("Invalid transaction from Core: {}", e)))?;
+        deserialize(&bytes)
+            .map_err(|e| WalletError::Custom(format!("Invalid transaction from Core: {}", e)))
+    }
+}
+
+impl Encode for CoreRpcWallet {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        self.wallet_name.encode(encoder)?;
+        self.address.to_string().encode(encoder)?;
+        self.utxos
+            .iter()
+            .map(|(outpoint, txout)| {
+                (
+                    outpoint.to_string(),
+                    txout.value,
+                    hex::encode(txout.script_pubkey.as_bytes()),
+                )
+            })
+            .collect::<Vec<_>>()
+            .encode(encoder)?;
+        self.frozen_utxos
+            .iter()
+            .map(|outpoint| outpoint.to_string())
+            .collect::<Vec<_>>()
+            .encode(encoder)
+    }
+}
+
+impl Decode for CoreRpcWallet {
+    fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
+        let wallet_name = String::decode(decoder)?;
+        let address = String::decode(decoder)?;
+        let string_utxos = Vec::<(String, u64, String)>::decode(decoder)?;
+        let string_frozen_utxos = Vec::<String>::decode(decoder)?;
+
+        let address =
+            parse_core_address(&address).map_err(|e| DecodeError::OtherString(e.to_string()))?;
+        let utxos = string_utxos
+            .iter()
+            .map(|(outpoint, value, script)| {
+                (
+                    OutPoint::from_str(outpoint).expect("expected valid outpoint"),
+                    TxOut {
+                        value: *value,
+                        script_pubkey: ScriptBuf::from_hex(script)
+                            .expect("expected valid scriptPubKey"),
+                    },
+                )
+            })
+            .collect();
+        let frozen_utxos = string_frozen_utxos
+            .iter()
+            .map(|outpoint| OutPoint::from_str(outpoint).expect("expected valid outpoint"))
+            .collect();
+
+        Ok(CoreRpcWallet {
+            wallet_name,
+            address,
+            utxos,
+            utxo_confirmations: HashMap::new(),
+            frozen_utxos,
+        })
+    }
+}
+
+impl<'a> BorrowDecode<'a> for CoreRpcWallet {
+    fn borrow_decode<D: BorrowDecoder<'a>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        Self::decode(decoder)
     }
 }
@@ -1,14 +1,21 @@
 use clap::{ArgAction, Parser};
 use dash_sdk::dashcore_rpc::{Auth, Client};
 use dash_sdk::{RequestSettings, SdkBuilder};
-use dpp::{identity::accessors::IdentityGettersV0, version::PlatformVersion};
+use dpp::{
+    identity::accessors::IdentityGettersV0,
+    platform_value::{string_encoding::Encoding, Identifier},
+    version::PlatformVersion,
+};
 use rs_platform_explorer::{
     backend::{
         self,
+        document_tracker::DeletePolicy,
         identities::IdentityTask::{self},
         insight::InsightAPIClient,
+        load_profile::LoadProfile,
+        reserve_top_up::ReserveTopUp,
         wallet::WalletTask,
-        Backend, Task,
+        Backend, BackendEvent, CompletedTaskPayload, Task,
     },
     config::Config,
 };
@@ -49,6 +56,163 @@ struct Args {
         help = "Specifies the minimum amount of Dash the loaded identity should have."
     )]
     dash: Option<u64>,
+
+    #[arg(
+        long,
+        help = "Specifies how many seconds to wait for a state transition result. Defaults to \
+                the strategy's retry policy, 75 if it has none."
+    )]
+    wait_timeout: Option<u64>,
+
+    #[arg(
+        long,
+        help = "Specifies how many times to retry waiting for a state transition result. \
+                Defaults to the strategy's retry policy, 5 if it has none."
+    )]
+    wait_retries: Option<usize>,
+
+    #[arg(
+        long,
+        default_value_t = 0,
+        help = "Gives up on the oldest waits for state transition results above this many \
+                outstanding ones. Default 0, no limit."
+    )]
+    max_outstanding_waits: usize,
+
+    #[arg(
+        long,
+        default_value_t = 0,
+        help = "Holds off preparing state transitions while this many broadcasts and waits are \
+                in flight. Default 0, no limit."
+    )]
+    max_in_flight: usize,
+
+    #[arg(
+        long,
+        default_value_t = backend::strategies::DEFAULT_MAX_CONCURRENT_BROADCASTS,
+        help = "Broadcasts this many state transitions of a loop at once at most, 0 for no \
+                limit."
+    )]
+    max_concurrent_broadcasts: usize,
+
+    #[arg(long, action = ArgAction::SetTrue, help = "Withdraws the funds of created identities to the sweep wallet instead of the loaded wallet.")]
+    sweep: bool,
+
+    #[arg(
+        long,
+        help = "Specifies the maximum amount of Dash the run may spend. Required on mainnet."
+    )]
+    spend_cap: Option<f64>,
+
+    #[arg(
+        long,
+        help = "Adjusts the load to keep the 95th percentile inclusion latency under this many \
+                seconds, reporting the maximum sustainable throughput."
+    )]
+    latency_target: Option<u64>,
+
+    #[arg(
+        long,
+        default_value_t = 0,
+        help = "Seconds at the start of the run excluded from the reported rates and wait times."
+    )]
+    warm_up: u64,
+
+    #[arg(
+        long,
+        default_value_t = 0,
+        help = "Seconds at the end of the run excluded from the reported rates and wait times."
+    )]
+    cool_down: u64,
+
+    #[arg(
+        long,
+        default_value_t = 1,
+        help = "Asset locks funded by each L1 transaction when initializing, at most 24. Default 1."
+    )]
+    asset_locks_per_transaction: usize,
+
+    #[arg(
+        long,
+        help = "Sends all requests of the run to this DAPI address only, to measure a single node."
+    )]
+    pin: Option<String>,
+
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "Also broadcasts every transition to EXPLORER_MIRROR_DAPI_ADDRESSES and reports \
+                where the two networks disagree."
+    )]
+    mirror: bool,
+
+    #[arg(
+        long,
+        action = ArgAction::SetTrue,
+        help = "Writes a gzip compressed JSON line for every transition of the load, with its \
+                broadcast and wait times and result, to supporting_files/run_traces."
+    )]
+    trace_transitions: bool,
+
+    #[arg(
+        long,
+        help = "Base58 ID of a known identity topping up identities running out of credits with \
+                transfers of --top-up-amount, instead of asset locks."
+    )]
+    reserve_identity: Option<String>,
+
+    #[arg(
+        long,
+        default_value = "local-drive",
+        help = "Documents delete operations target: local-drive for any in the local Drive, or \
+                oldest, newest or random among the ones the run created."
+    )]
+    delete_policy: DeletePolicy,
+
+    #[arg(
+        long,
+        action = ArgAction::SetTrue,
+        help = "Generates the transitions of the run without broadcasting them and reports the \
+                transitions, asset locks, UTXOs and credits it would need."
+    )]
+    dry_run: bool,
+
+    #[arg(
+        long,
+        action = ArgAction::SetTrue,
+        help = "Lets the Sdk ban DAPI addresses failing requests for a while during the run."
+    )]
+    ban_failed_addresses: bool,
+
+    #[arg(
+        long,
+        help = "Paces broadcasts to this many transitions per second with a token bucket instead \
+                of sleeping out the rest of each loop."
+    )]
+    target_rate: Option<f64>,
+
+    #[arg(
+        long,
+        default_value = "constant",
+        help = "Shape of the load over the run: constant, ramp:<secs>, step:<secs>:<steps> or \
+                spike:<start secs>:<length secs>:<multiplier>. Each phase is reported separately."
+    )]
+    load_profile: LoadProfile,
+
+    #[arg(
+        long,
+        default_value_t = 0,
+        help = "Fee increase of the transitions signed by identities of the run, in percent of \
+                the base fee, to get them prioritized."
+    )]
+    user_fee_increase: u16,
+
+    #[arg(
+        long,
+        help = "Seed of the run's random choices, to replay the transitions of an earlier run. \
+                A random one is drawn and logged if not given."
+    )]
+    seed: Option<u64>,
 }
 
 #[tokio::main]
@@ -238,6 +402,11 @@ async fn main() {
     }
 
     let credit_amount = (args.top_up_amount * 100_000_000_000.0) as u64;
+    let reserve_top_up = args.reserve_identity.map(|b58_id| ReserveTopUp {
+        identity_id: Identifier::from_string(&b58_id, Encoding::Base58)
+            .expect("expected a Base58 reserve identity ID"),
+        credits: credit_amount,
+    });
 
     let addr = format!("http://{}:{}", &config.core_host, config.core_rpc_port);
     let core = Client::new(
@@ -250,6 +419,72 @@ async fn main() {
     .expect("expected core client");
 
     if let Some(test_name) = args.test {
+        let mut retry_policy = backend
+            .state()
+            .strategy_retry_policies
+            .lock()
+            .await
+            .get(&test_name)
+            .copied()
+            .unwrap_or_default();
+        if let Some(wait_timeout) = args.wait_timeout {
+            retry_policy.wait_timeout_secs = wait_timeout;
+        }
+        if let Some(wait_retries) = args.wait_retries {
+            retry_policy.wait_retries = wait_retries;
+        }
+        let run_options = backend::strategies::RunOptions {
+            wait: backend::strategies::WaitSettings {
+                max_outstanding: args.max_outstanding_waits,
+                max_in_flight: args.max_in_flight,
+                max_concurrent_broadcasts: args.max_concurrent_broadcasts,
+            },
+            retry_policy: Some(retry_policy),
+            save_defaults: false,
+            withdraw_to_sweep_wallet: args.sweep,
+            spend_cap: args.spend_cap.map(|dash| (dash * 100_000_000_000.0) as u64),
+            latency_target: args.latency_target.map(Duration::from_secs),
+            warm_up: Duration::from_secs(args.warm_up),
+            cool_down: Duration::from_secs(args.cool_down),
+            asset_locks_per_transaction: args.asset_locks_per_transaction,
+            pinned_address: args.pin,
+            mirror: args.mirror,
+            trace_transitions: args.trace_transitions,
+            reserve_top_up,
+            delete_policy: args.delete_policy,
+            ban_failed_addresses: args.ban_failed_addresses,
+            target_rate: args.target_rate.filter(|rate| *rate > 0.0),
+            load_profile: args.load_profile,
+            user_fee_increase: args.user_fee_increase,
+            seed: args.seed,
+        };
+
+        if args.dry_run {
+            let event = backend
+                .state()
+                .run_strategy_task(
+                    &sdk,
+                    backend::strategies::StrategyTask::DryRun {
+                        strategy_name: test_name.to_string(),
+                        duration: args.blocks,
+                        seconds_per_loop: 1,
+                        run_options,
+                    },
+                    &insight,
+                    &core,
+                )
+                .await;
+            match event {
+                BackendEvent::TaskCompleted {
+                    execution_result: Ok(CompletedTaskPayload::String(report)),
+                    ..
+                } => tracing::info!("\n{}", report),
+                BackendEvent::StrategyError { error } => tracing::error!("{}", error),
+                _ => {}
+            }
+            return;
+        }
+
         backend
             .state()
             .run_strategy_task(
@@ -260,6 +495,7 @@ async fn main() {
                     1,
                     args.prove,
                     credit_amount,
+                    run_options,
                 ),
                 &insight,
                 &core,
@@ -1,10 +1,12 @@
 use std::{path::PathBuf, str::FromStr};
 
 use dash_sdk::sdk::Uri;
-use dpp::dashcore::Network;
+use dpp::{dashcore::Network, fee::Credits};
 use rs_dapi_client::AddressList;
 use serde::Deserialize;
 
+use crate::units;
+
 #[derive(Debug, Deserialize, Clone)]
 /// Configuration for platform explorer.
 ///
@@ -17,6 +19,14 @@ use serde::Deserialize;
 pub struct Config {
     /// Hostname of the Dash Platform node to connect to
     pub dapi_addresses: String,
+    /// Named DAPI address sets to switch between at runtime, written as
+    /// `name=address,address;name=address`
+    #[serde(default)]
+    pub dapi_address_sets: String,
+    /// DAPI addresses of a second network strategy runs can mirror their
+    /// transitions to
+    #[serde(default)]
+    pub mirror_dapi_addresses: Option<String>,
     /// Host of the Dash Core RPC interface
     pub core_host: String,
     /// Port of the Dash Core RPC interface
@@ -31,6 +41,29 @@ pub struct Config {
     pub network: String,
     /// Optional wallet private key to instantiate the wallet
     pub wallet_private_key: Option<String>,
+    /// Allows strategy runs and top ups to spend real Dash when the network is
+    /// mainnet, strategy runs also need a spend cap then
+    #[serde(default)]
+    pub allow_mainnet_spending: bool,
+    /// Most credits a single identity top up may fund when the network is
+    /// mainnet, top ups are refused there without it like strategy runs
+    /// without a spend cap
+    #[serde(default)]
+    pub mainnet_top_up_cap: Option<Credits>,
+    /// Protocol version to use instead of the one detected from the network
+    #[serde(default)]
+    pub protocol_version: Option<u32>,
+    /// HTTP endpoint completed strategy run reports are POSTed to, to gather
+    /// the results of many explorers in one place
+    #[serde(default)]
+    pub report_upload_url: Option<String>,
+    /// Bearer token sent along with uploaded run reports
+    #[serde(default)]
+    pub report_upload_token: Option<String>,
+    /// Worker threads of the runtime strategy runs spawn their broadcasts and
+    /// waits on, one per CPU core by default
+    #[serde(default)]
+    pub strategy_worker_threads: Option<usize>,
 }
 
 impl Config {
@@ -38,6 +71,9 @@ impl Config {
     /// file.
     const CONFIG_PREFIX: &'static str = "EXPLORER_";
 
+    /// Name of the address set made of [Config::dapi_addresses].
+    pub const DEFAULT_ADDRESS_SET: &'static str = "default";
+
     /// Loads a local configuration from operating system environment variables
     /// and `.env` file.
     ///
@@ -45,7 +81,16 @@ impl Config {
     /// `.env` file. Variable names in the
     /// environment and `.env` file must be converted to SCREAMING_SNAKE_CASE
     /// and prefixed with [LOCAL_EXPLORER_](Config::CONFIG_PREFIX).
+    ///
+    /// Panics if the configuration is missing or invalid, see
+    /// [Config::try_load] to handle it.
     pub fn load() -> Self {
+        Self::try_load().unwrap_or_else(|e| panic!("invalid configuration: {}", e))
+    }
+
+    /// Loads the configuration like [Config::load], returning what's missing
+    /// or invalid instead of panicking.
+    pub fn try_load() -> Result<Self, String> {
         // load config from .env file
         if let Err(err) = dotenvy::from_path(".env") {
             tracing::warn!(?err, "failed to load config file");
@@ -53,34 +98,128 @@ impl Config {
 
         let config: Self = envy::prefixed(Self::CONFIG_PREFIX)
             .from_env()
-            .expect("configuration error");
-
-        if !config.is_valid() {
-            panic!("invalid configuration: {:?}", config);
-        }
+            .map_err(|e| format!("{}, variables are prefixed with {}", e, Self::CONFIG_PREFIX))?;
+        config.validate()?;
 
-        config
+        Ok(config)
     }
 
     /// Check if configuration is set
     pub fn is_valid(&self) -> bool {
-        !self.core_rpc_user.is_empty()
-            && !self.core_rpc_password.is_empty()
-            && self.core_rpc_port != 0
-            && !self.dapi_addresses.is_empty()
-            && Uri::from_str(&self.insight_api_url).is_ok()
-            && Network::from_str(&self.core_network_name()).is_ok()
+        self.validate().is_ok()
+    }
+
+    /// Checks the configuration, returning the first value that is missing or
+    /// invalid.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.core_rpc_user.is_empty() || self.core_rpc_password.is_empty() {
+            return Err("Core RPC user and password are required".to_owned());
+        }
+        if self.core_rpc_port == 0 {
+            return Err("Core RPC port is required".to_owned());
+        }
+        if self.dapi_addresses.is_empty() {
+            return Err("DAPI addresses are required".to_owned());
+        }
+        if let Some((name, _)) = self
+            .dapi_address_sets()
+            .into_iter()
+            .find(|(_, addresses)| AddressList::from_str(addresses).is_err())
+        {
+            return Err(format!("Invalid DAPI addresses in address set {}", name));
+        }
+        if Uri::from_str(&self.insight_api_url).is_err() {
+            return Err(format!("Invalid Insight API URL {}", self.insight_api_url));
+        }
+        if let Some(url) = &self.report_upload_url {
+            if reqwest::Url::parse(url).is_err() {
+                return Err(format!("Invalid report upload URL {}", url));
+            }
+        }
+        if Network::from_str(self.core_network_name()).is_err() {
+            return Err(format!("Unknown network {}", self.network));
+        }
+        Ok(())
     }
 
     pub fn core_network(&self) -> Network {
         Network::from_str(self.core_network_name()).expect("invalid network")
     }
 
+    /// Whether the configured network is mainnet
+    pub fn is_mainnet(&self) -> bool {
+        self.core_network() == Network::Dash
+    }
+
+    /// Checks that spending is allowed on the configured network, it is always
+    /// allowed on test networks.
+    pub fn check_spending_allowed(&self) -> Result<(), String> {
+        if self.is_mainnet() && !self.allow_mainnet_spending {
+            Err(
+                "Refusing to spend mainnet funds, set EXPLORER_ALLOW_MAINNET_SPENDING=true to \
+                 allow it"
+                    .to_owned(),
+            )
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Checks that a strategy run is allowed on the configured network. On
+    /// mainnet spending has to be allowed and the run needs a spend cap.
+    pub fn check_run_allowed(&self, spend_cap: Option<Credits>) -> Result<(), String> {
+        self.check_spending_allowed()?;
+        if self.is_mainnet() && spend_cap.is_none() {
+            Err("A spend cap is required to run strategies on mainnet".to_owned())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Checks that a top up funding `credits` is allowed on the configured
+    /// network. On mainnet spending has to be allowed and the top up has to
+    /// fit in [Config::mainnet_top_up_cap].
+    pub fn check_top_up_allowed(&self, credits: Credits) -> Result<(), String> {
+        self.check_spending_allowed()?;
+        if !self.is_mainnet() {
+            return Ok(());
+        }
+        match self.mainnet_top_up_cap {
+            None => Err(
+                "A top up cap is required to top up identities on mainnet, set \
+                 EXPLORER_MAINNET_TOP_UP_CAP to the most credits a top up may fund"
+                    .to_owned(),
+            ),
+            Some(cap) if credits > cap => Err(format!(
+                "Refusing to top up {}, above the mainnet top up cap of {}",
+                units::format_credits(credits),
+                units::format_credits(cap)
+            )),
+            Some(_) => Ok(()),
+        }
+    }
+
     /// List of DAPI addresses
     pub fn dapi_address_list(&self) -> AddressList {
         AddressList::from_str(self.dapi_addresses.as_str()).expect("invalid DAPI addresses")
     }
 
+    /// Named DAPI address sets, the [default](Config::DEFAULT_ADDRESS_SET)
+    /// one first.
+    pub fn dapi_address_sets(&self) -> Vec<(String, String)> {
+        let mut sets = vec![(
+            Self::DEFAULT_ADDRESS_SET.to_owned(),
+            self.dapi_addresses.clone(),
+        )];
+        sets.extend(
+            self.dapi_address_sets
+                .split(';')
+                .filter_map(|set| set.split_once('='))
+                .map(|(name, addresses)| (name.trim().to_owned(), addresses.trim().to_owned())),
+        );
+        sets
+    }
+
     /// Insight API URI
     pub fn insight_api_uri(&self) -> Uri {
         Uri::from_str(&self.insight_api_url).expect("invalid insight API URL")
@@ -91,6 +230,11 @@ impl Config {
         format!("{}_explorer.state", self.network).into()
     }
 
+    /// Live progress of the current strategy run, next to the state file
+    pub fn run_progress_file_path(&self) -> PathBuf {
+        format!("{}_run_progress.json", self.network).into()
+    }
+
     fn core_network_name(&self) -> &str {
         if self.network == "local" {
             "regtest"
@@ -99,3 +243,65 @@ impl Config {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(network: &str, allow_mainnet_spending: bool, cap: Option<Credits>) -> Config {
+        Config {
+            dapi_addresses: "http://127.0.0.1:2443".to_owned(),
+            dapi_address_sets: String::new(),
+            mirror_dapi_addresses: None,
+            core_host: "127.0.0.1".to_owned(),
+            core_rpc_port: 19998,
+            core_rpc_user: "dashmate".to_owned(),
+            core_rpc_password: "password".to_owned(),
+            insight_api_url: "http://127.0.0.1:3001/insight-api".to_owned(),
+            network: network.to_owned(),
+            wallet_private_key: None,
+            allow_mainnet_spending,
+            mainnet_top_up_cap: cap,
+            protocol_version: None,
+            report_upload_url: None,
+            report_upload_token: None,
+            strategy_worker_threads: None,
+        }
+    }
+
+    #[test]
+    fn test_networks_top_up_and_run_without_caps() {
+        let config = config("testnet", false, None);
+        assert_eq!(config.check_top_up_allowed(u64::MAX), Ok(()));
+        assert_eq!(config.check_run_allowed(None), Ok(()));
+    }
+
+    #[test]
+    fn mainnet_refuses_spending_unless_allowed() {
+        let config = config("dash", false, Some(1000));
+        assert!(config.check_top_up_allowed(1).is_err());
+        assert!(config.check_run_allowed(Some(1000)).is_err());
+    }
+
+    #[test]
+    fn mainnet_top_ups_need_a_cap() {
+        let error = config("dash", true, None)
+            .check_top_up_allowed(1)
+            .unwrap_err();
+        assert!(error.contains("EXPLORER_MAINNET_TOP_UP_CAP"));
+    }
+
+    #[test]
+    fn mainnet_top_ups_fit_in_the_cap() {
+        let config = config("dash", true, Some(1000));
+        assert_eq!(config.check_top_up_allowed(1000), Ok(()));
+        assert!(config.check_top_up_allowed(1001).is_err());
+    }
+
+    #[test]
+    fn mainnet_runs_need_a_spend_cap() {
+        let config = config("dash", true, None);
+        assert!(config.check_run_allowed(None).is_err());
+        assert_eq!(config.check_run_allowed(Some(1000)), Ok(()));
+    }
+}
@@ -0,0 +1,137 @@
+//! Counts, durations, rates and sizes as shown in reports and screens.
+//!
+//! Runs go for hours and attempt millions of transitions, raw integers and
+//! `Duration` debug prints of those are hard to read at a glance. Counts get
+//! thousands separators and durations are written in their two largest units,
+//! e.g. `1h 23m`. Separators follow the locale of the `LC_ALL`, `LC_NUMERIC`
+//! or `LANG` environment variables, English ones are used if none is set or
+//! the language isn't known.
+
+use std::{sync::OnceLock, time::Duration};
+
+/// Separators of a locale: between groups of thousands and before decimals.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Separators {
+    thousands: char,
+    decimal: char,
+}
+
+const ENGLISH: Separators = Separators {
+    thousands: ',',
+    decimal: '.',
+};
+/// Languages grouping thousands with a dot, e.g. `1.234,5`
+const DOT_GROUPING: [&str; 9] = ["de", "es", "it", "pt", "nl", "da", "id", "tr", "el"];
+/// Languages grouping thousands with a space, e.g. `1 234,5`
+const SPACE_GROUPING: [&str; 10] = ["fr", "ru", "pl", "cs", "sk", "sv", "fi", "nb", "uk", "hu"];
+
+fn separators() -> Separators {
+    static SEPARATORS: OnceLock<Separators> = OnceLock::new();
+    *SEPARATORS.get_or_init(|| {
+        let locale = ["LC_ALL", "LC_NUMERIC", "LANG"]
+            .into_iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default();
+        let language = locale
+            .split(['_', '.', '-'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        if DOT_GROUPING.contains(&language.as_str()) {
+            Separators {
+                thousands: '.',
+                decimal: ',',
+            }
+        } else if SPACE_GROUPING.contains(&language.as_str()) {
+            Separators {
+                thousands: '\u{a0}',
+                decimal: ',',
+            }
+        } else {
+            ENGLISH
+        }
+    })
+}
+
+/// Whole number with thousands separators, e.g. `1,234,567`.
+pub fn format_count(count: u64) -> String {
+    group_thousands(&count.to_string(), separators().thousands)
+}
+
+/// Number with `decimals` digits after the decimal point and thousands
+/// separators, e.g. `12,345.68`.
+pub fn format_decimal(value: f64, decimals: usize) -> String {
+    let Separators { thousands, decimal } = separators();
+    let formatted = format!("{:.*}", decimals, value);
+    let (sign, unsigned) = match formatted.strip_prefix('-') {
+        Some(unsigned) => ("-", unsigned),
+        None => ("", formatted.as_str()),
+    };
+    let (whole, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    let whole = group_thousands(whole, thousands);
+    if fraction.is_empty() {
+        format!("{}{}", sign, whole)
+    } else {
+        format!("{}{}{}{}", sign, whole, decimal, fraction)
+    }
+}
+
+/// Transitions per second, e.g. `1,234.57 tx/s`.
+pub fn format_rate(rate: f64) -> String {
+    format!("{} tx/s", format_decimal(rate, 2))
+}
+
+/// Byte size in the largest binary unit it reaches, e.g. `1.21 MiB` or
+/// `512 B`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{} {}", format_decimal(value, 2), UNITS[unit])
+}
+
+/// Duration in its two largest units, e.g. `1h 23m`, `4m 5s` or `850ms`.
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs == 0 {
+        return format!("{}ms", duration.as_millis());
+    }
+    let (days, hours, minutes, seconds) =
+        (secs / 86400, secs / 3600 % 24, secs / 60 % 60, secs % 60);
+    let units = [(days, "d"), (hours, "h"), (minutes, "m"), (seconds, "s")];
+    let first = units
+        .iter()
+        .position(|(value, _)| *value > 0)
+        .expect("duration has a non zero unit");
+    units[first..]
+        .iter()
+        .take(2)
+        .filter(|(value, _)| *value > 0)
+        .map(|(value, unit)| format!("{}{}", format_count(*value), unit))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Duration given in seconds, see [format_duration].
+pub fn format_secs(secs: u64) -> String {
+    format_duration(Duration::from_secs(secs))
+}
+
+fn group_thousands(digits: &str, separator: char) -> String {
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index) % 3 == 0 {
+            grouped.push(separator);
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
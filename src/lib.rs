@@ -1,6 +1,8 @@
 pub mod backend;
 pub mod config;
+pub mod format;
 pub mod ui;
+pub mod units;
 
 use backend::BackendEvent;
 use tuirealm::event::KeyEvent;
@@ -9,4 +11,9 @@ pub enum Event<'s> {
     Key(KeyEvent),
     Backend(BackendEvent<'s>),
     RedrawDebounceTimeout,
+    /// Sent every second for screens refreshing their data on their own, which
+    /// should only start a task if the backend is idle
+    Tick {
+        backend_idle: bool,
+    },
 }
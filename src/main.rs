@@ -1,23 +1,74 @@
-use std::{fs::File, panic, time::Duration};
+use std::{fs::File, panic, path::PathBuf, time::Duration};
 
-use crossterm::event::{Event as TuiEvent, EventStream};
+use clap::Parser;
+use crossterm::event::Event as TuiEvent;
 use dapi_grpc::core::v0::core_client::CoreClient;
 use dash_sdk::dashcore_rpc::{Auth, Client};
-use dash_sdk::{sdk, RequestSettings, SdkBuilder};
 use dpp::{identity::accessors::IdentityGettersV0, version::PlatformVersion};
-use futures::{future::OptionFuture, select, FutureExt, StreamExt};
+use futures::{
+    future::{AbortHandle, Abortable, Aborted, FusedFuture, LocalBoxFuture, OptionFuture},
+    select,
+    stream::FuturesUnordered,
+    FutureExt, StreamExt,
+};
 use rs_platform_explorer::{
-    backend::{insight::InsightAPIClient, Backend},
+    backend::{
+        connections::{build_sdk, detect_platform_version},
+        identities::{IdentityTask, MAX_REGISTRATION_RETRIES},
+        insight::InsightAPIClient,
+        mock::MockBackend,
+        strategies::StrategyTask,
+        strategy_runtime, Backend, BackendEvent, Task, TaskBackend,
+    },
     config::Config,
-    ui::{IdentityBalance, Ui, UiFeedback},
+    ui::{follow_run_progress, terminal_events, IdentityBalance, Ui, UiFeedback},
     Event,
 };
+use tokio::sync::mpsc::error::TryRecvError;
 use tracing_subscriber::EnvFilter;
 
+/// How often queued identity registrations are retried, a few blocks apart so
+/// a rejected transition has a chance to be cleared
+const REGISTRATION_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+/// How often schedules are checked for a strategy run that is due
+const SCHEDULE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+/// How often screens get an [Event::Tick]
+const UI_TICK_INTERVAL: Duration = Duration::from_secs(1);
+/// How soon a snapshot that kept a previous copy of locked state is published
+/// again
+const SNAPSHOT_RETRY_INTERVAL: Duration = Duration::from_millis(200);
+/// Delay of a redraw, input coming in meanwhile is handled before it
+const UI_REDRAW_DEBOUNCE: Duration = Duration::from_millis(10);
+
+#[derive(Parser, Debug)]
+#[clap(about, long_about = None)]
+struct Args {
+    #[arg(
+        long,
+        value_name = "STATE_DIR",
+        help = "Displays the strategy run progress of the instance using this state directory, read-only."
+    )]
+    follow: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Runs the UI on canned data without connecting to Platform, Core or Insight."
+    )]
+    demo: bool,
+}
+
 #[tokio::main]
 async fn main() {
-    // Initialize logger
-    let log_file = File::create("explorer.log").expect("create log file");
+    let args = Args::parse();
+
+    // Initialize logger, a follower keeps its own log not to truncate the followed
+    // instance's one
+    let log_file_name = if args.follow.is_some() {
+        "explorer_follow.log"
+    } else {
+        "explorer.log"
+    };
+    let log_file = File::create(log_file_name).expect("create log file");
 
     let filter = EnvFilter::try_new("info")
         .unwrap()
@@ -60,27 +111,28 @@ async fn main() {
     // Load configuration
     let config = Config::load();
 
-    // Setup Platform SDK
-    let address_list = config.dapi_address_list();
-    let request_settings = RequestSettings {
-        connect_timeout: Some(Duration::from_secs(10)),
-        timeout: Some(Duration::from_secs(10)),
-        retries: None,
-        ban_failed_address: Some(false),
-    };
-    let sdk = SdkBuilder::new(address_list)
-        .with_version(PlatformVersion::latest())
-        .with_network(config.core_network())
-        .with_core(
-            &config.core_host,
-            config.core_rpc_port,
-            &config.core_rpc_user,
-            &config.core_rpc_password,
-        )
-        .with_settings(request_settings)
-        .with_network(config.core_network())
-        .build()
+    if let Some(state_dir) = args.follow {
+        follow_run_progress(state_dir.join(config.run_progress_file_path())).await;
+        return;
+    }
+
+    if args.demo {
+        run_ui(Box::leak(Box::new(MockBackend::new()))).await;
+        return;
+    }
+
+    // Setup Platform SDK, asking the network for its protocol version first so
+    // version bumps don't need a new build
+    let probe_sdk = build_sdk(
+        &config,
+        config.dapi_address_list(),
+        PlatformVersion::latest(),
+    )
+    .expect("expected to build sdk");
+    let platform_version = detect_platform_version(&probe_sdk, &config).await;
+    let sdk = build_sdk(&config, config.dapi_address_list(), platform_version)
         .expect("expected to build sdk");
+    let sdk = Box::leak(Box::new(sdk));
 
     let addr = format!("http://{}:{}", &config.core_host, config.core_rpc_port);
     let core = Client::new(
@@ -94,8 +146,17 @@ async fn main() {
 
     let insight = InsightAPIClient::new(config.insight_api_uri());
 
-    let backend = Backend::new(&sdk, insight, core, config).await;
+    // The backend lives as long as the app, strategy runs borrow it from their
+    // own thread
+    let backend = Box::leak(Box::new(Backend::new(sdk, insight, core, config).await));
+    run_ui(backend).await;
+    // Runs still going were stopped with the UI, they save the state as they end
+    strategy_runtime::join_runs();
+    // Leaked backends aren't dropped, the state is saved here instead
+    backend.save_state();
+}
 
+async fn run_ui<B: TaskBackend + Sync>(backend: &'static B) {
     // Add loaded identity to known identities if it's not already there
     // And set selected_strategy to None
     {
@@ -112,6 +173,7 @@ async fn main() {
 
         *selected_strategy = None;
     }
+    backend.state().publish_snapshot();
 
     let initial_identity_balance = backend
         .state()
@@ -121,29 +183,98 @@ async fn main() {
         .as_ref()
         .map(|identity| IdentityBalance::from_credits(identity.balance()));
 
-    let mut ui = Ui::new(initial_identity_balance);
+    let (mut terminal_events, terminal_reader) = terminal_events::terminal_events();
+    let mut ui = Ui::new(initial_identity_balance, terminal_reader);
 
     let mut active = true;
 
-    let mut terminal_event_stream = EventStream::new().fuse();
-    let mut backend_task: OptionFuture<_> = None.into();
+    // Tasks run concurrently, each one is listed in the activity log where it
+    // can be cancelled
+    let mut backend_tasks = FuturesUnordered::new();
+    // Check connections on startup, the report is shown on the main screen
+    backend_tasks.push(run_logged_task(backend, Task::TestConnections));
     let mut ui_debounced_redraw: OptionFuture<_> = None.into();
+    let mut registration_retry = tokio::time::sleep(REGISTRATION_RETRY_INTERVAL)
+        .boxed_local()
+        .fuse();
+    let mut schedule_check = tokio::time::sleep(SCHEDULE_CHECK_INTERVAL)
+        .boxed_local()
+        .fuse();
+    let mut ui_tick = tokio::time::sleep(UI_TICK_INTERVAL).boxed_local().fuse();
+    let mut snapshot_retry = tokio::time::sleep(SNAPSHOT_RETRY_INTERVAL)
+        .boxed_local()
+        .fuse();
 
     while active {
         let event = select! {
-            terminal_event = terminal_event_stream.next() => match terminal_event {
+            terminal_event = terminal_events.recv().fuse() => match terminal_event {
                 None => panic!("terminal event stream closed unexpectedly"),
-                Some(Err(_)) => panic!("terminal event stream closed unexpectedly"),
-                Some(Ok(TuiEvent::Resize(_, _))) => {ui.redraw(); continue },
-                Some(Ok(TuiEvent::Key(key_event))) => Some(Event::Key(key_event.into())),
+                Some(TuiEvent::Resize(_, _)) => {ui.redraw(); continue },
+                Some(TuiEvent::Key(key_event)) => Some(Event::Key(key_event.into())),
                 _ => None
             },
-            backend_task_finished = backend_task => backend_task_finished.map(Event::Backend),
+            backend_event = backend_tasks.select_next_some() => Some(Event::Backend(backend_event)),
             ui_redraw = ui_debounced_redraw => ui_redraw.map(|_| Event::RedrawDebounceTimeout),
+            _ = registration_retry => {
+                registration_retry = tokio::time::sleep(REGISTRATION_RETRY_INTERVAL)
+                    .boxed_local()
+                    .fuse();
+                // Retry only when idle, not to compete with a task in progress
+                let registrations_queued = backend
+                    .state()
+                    .identity_registration_queue
+                    .lock()
+                    .await
+                    .iter()
+                    .any(|(_, retries)| *retries < MAX_REGISTRATION_RETRIES);
+                if backend_tasks.is_empty() && registrations_queued {
+                    let task = Task::Identity(IdentityTask::RetryQueuedRegistrations {
+                        include_given_up: false,
+                    });
+                    backend_tasks.push(run_logged_task(backend, task));
+                }
+                None
+            },
+            _ = schedule_check => {
+                schedule_check = tokio::time::sleep(SCHEDULE_CHECK_INTERVAL)
+                    .boxed_local()
+                    .fuse();
+                // Like registration retries, a scheduled run starts only when idle
+                if backend_tasks.is_empty() {
+                    if let Some(id) = backend.state().due_run_schedule().await {
+                        let task = Task::Strategy(StrategyTask::RunScheduled(id));
+                        backend_tasks.push(run_logged_task(backend, task));
+                    }
+                }
+                None
+            },
+            _ = snapshot_retry => {
+                snapshot_retry = tokio::time::sleep(SNAPSHOT_RETRY_INTERVAL)
+                    .boxed_local()
+                    .fuse();
+                if backend.state().snapshot_stale() {
+                    backend.state().publish_snapshot();
+                    ui.redraw();
+                }
+                None
+            },
+            _ = ui_tick => {
+                ui_tick = tokio::time::sleep(UI_TICK_INTERVAL).boxed_local().fuse();
+                Some(Event::Tick {
+                    backend_idle: backend_tasks.is_empty(),
+                })
+            },
         };
 
-        let ui_feedback = match event {
-            Some(event @ (Event::Backend(_) | Event::Key(_))) => {
+        let mut ui_feedback = match event {
+            Some(event @ Event::Backend(_)) => {
+                let ui_feedback = ui.on_event(backend.state(), event).await;
+                // The state locks the event held are released now, state the task
+                // published its snapshot without gets in
+                backend.state().publish_snapshot();
+                ui_feedback
+            }
+            Some(event @ (Event::Key(_) | Event::Tick { .. })) => {
                 ui.on_event(backend.state(), event).await
             }
             Some(Event::RedrawDebounceTimeout) => {
@@ -153,21 +284,79 @@ async fn main() {
             _ => UiFeedback::None,
         };
 
-        match ui_feedback {
-            UiFeedback::Quit => active = false,
-            UiFeedback::ExecuteTask(task) => {
-                backend_task = Some(backend.run_task(task.clone()).boxed_local().fuse()).into();
-                ui.redraw();
+        loop {
+            let mut redraw_now = false;
+            match ui_feedback {
+                UiFeedback::Quit => active = false,
+                UiFeedback::ExecuteTask(task) => {
+                    backend_tasks.push(run_logged_task(backend, task));
+                    redraw_now = true;
+                }
+                UiFeedback::Redraw => {
+                    // A pending redraw is kept rather than postponed, so the screen
+                    // keeps up while keys come in faster than the debounce delay
+                    if ui_debounced_redraw.is_terminated() {
+                        ui_debounced_redraw =
+                            Some(tokio::time::sleep(UI_REDRAW_DEBOUNCE).boxed_local().fuse())
+                                .into();
+                    }
+                }
+                UiFeedback::None => (),
             }
-            UiFeedback::Redraw => {
-                ui_debounced_redraw = Some(
-                    tokio::time::sleep(Duration::from_millis(10))
-                        .boxed_local()
-                        .fuse(),
-                )
-                .into();
+
+            // Keys typed meanwhile go to the screen in order before anything is
+            // drawn, a key starting a task waits for its screen to be drawn first
+            if !active || redraw_now {
+                if redraw_now {
+                    ui.redraw();
+                }
+                break;
             }
-            UiFeedback::None => (),
+            ui_feedback = match terminal_events.try_recv() {
+                Ok(TuiEvent::Key(key_event)) => {
+                    ui.on_event(backend.state(), Event::Key(key_event.into()))
+                        .await
+                }
+                Ok(TuiEvent::Resize(_, _)) => UiFeedback::Redraw,
+                Ok(_) => UiFeedback::None,
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    panic!("terminal event stream closed unexpectedly")
+                }
+            };
+        }
+    }
+}
+
+/// Starts a task, recording it in the session's activity log where it can be
+/// cancelled. Strategy runs go to the strategy runtime so their load doesn't
+/// hold up the UI.
+fn run_logged_task<B: TaskBackend + Sync>(
+    backend: &'static B,
+    task: Task,
+) -> LocalBoxFuture<'static, BackendEvent<'static>> {
+    let activity_log = backend.state().activity_log();
+    let (abort_handle, abort_registration) = AbortHandle::new_pair();
+    // Recorded right away, so the UI can tell the task it's blocked on by its
+    // number
+    let number = activity_log.record_task_started(&task, abort_handle);
+    let cancelled_task = task.clone();
+    let run = async move {
+        if strategy_runtime::runs_on_strategy_runtime(&task) {
+            strategy_runtime::run(backend, task).await
+        } else {
+            backend.run_task(task).await
         }
+    };
+    async move {
+        let event = Abortable::new(run, abort_registration)
+            .await
+            .unwrap_or_else(|Aborted| BackendEvent::TaskCompleted {
+                task: cancelled_task,
+                execution_result: Err("Task cancelled".to_owned()),
+            });
+        activity_log.record_task_finished(number, &event);
+        event
     }
+    .boxed_local()
 }
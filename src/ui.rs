@@ -4,27 +4,48 @@
 //! and form. At a time only one of them will occupy the application's UI, both
 //! explained in details in their modules.
 
+mod editor;
+mod exit_confirmation;
+mod follow;
 mod form;
+pub(crate) mod id_names;
 mod screen;
+#[cfg(test)]
+mod snapshot_tests;
 mod status_bar;
+mod tabs;
+pub mod terminal_events;
 pub(crate) mod views;
 
 use dpp::identity::accessors::IdentityGettersV0;
-use std::{mem, ops::Deref, time::Instant};
+use std::{
+    fmt::{self, Display},
+    mem,
+    ops::Deref,
+    time::Instant,
+};
 use tuirealm::{
+    event::{Key, KeyEvent, KeyModifiers},
     terminal::TerminalBridge,
     tui::prelude::{Constraint, Direction, Layout},
 };
 
+pub use self::follow::follow_run_progress;
 use self::{
+    exit_confirmation::ExitConfirmation,
     form::{Form, FormController, FormStatus},
     screen::{Screen, ScreenController, ScreenFeedback},
     status_bar::StatusBarState,
-    views::{main::MainScreenController, strategies::StrategiesScreenController},
+    tabs::Tab,
+    terminal_events::TerminalReader,
+    views::{
+        main::MainScreenController, strategies::StrategiesScreenController,
+        wallet::WalletScreenController,
+    },
 };
 use crate::{
     backend::{AppState, AppStateUpdate, BackendEvent, Task},
-    Event,
+    units, Event,
 };
 
 pub struct IdentityBalance {
@@ -35,10 +56,11 @@ impl IdentityBalance {
     pub fn from_credits(credits: u64) -> Self {
         IdentityBalance { credits }
     }
+}
 
-    pub fn dash_str(&self) -> String {
-        let dash_amount = self.credits as f64 * 10f64.powf(-11.0);
-        format!("{:.4} DASH", dash_amount)
+impl Display for IdentityBalance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", units::format_credits(self.credits))
     }
 }
 
@@ -47,11 +69,18 @@ impl IdentityBalance {
 pub struct Ui {
     redraw_ts: Instant,
     terminal: TerminalBridge,
+    /// Stopped while the external editor has the terminal
+    terminal_reader: TerminalReader,
     status_bar_state: StatusBarState,
     screen: Screen<Box<dyn ScreenController>>,
     form: Option<Form<Box<dyn FormController>>>,
-    blocked: bool,
+    /// Task the UI is blocked on, by its number in the activity log
+    blocking_task: Option<u64>,
     screen_stack: Vec<Screen<Box<dyn ScreenController>>>,
+    /// Shown over the screen when quitting would lose work
+    exit_confirmation: Option<ExitConfirmation>,
+    /// Whether the backend was idle at the last tick
+    backend_idle: bool,
 }
 
 /// UI updates delivered to the main application loop.
@@ -64,25 +93,40 @@ pub enum UiFeedback {
 
 impl Ui {
     pub fn redraw(&mut self) {
+        let current_tab = self.current_tab();
         self.terminal
             .raw_mut()
             .draw(|frame| {
                 let layout = Layout::default()
                     .direction(Direction::Vertical)
-                    .constraints([Constraint::Min(10), Constraint::Max(3)].as_ref())
+                    .constraints(
+                        [
+                            Constraint::Length(1),
+                            Constraint::Min(10),
+                            Constraint::Max(3),
+                        ]
+                        .as_ref(),
+                    )
                     .split(frame.size());
 
+                tabs::view(frame, layout[0], current_tab);
                 if let Some(form) = &mut self.form {
-                    form.view(frame, layout[0]);
+                    form.view(frame, layout[1]);
                 } else {
-                    self.screen.view(frame, layout[0])
+                    self.screen.view(frame, layout[1])
                 };
-                self.status_bar_state.view(frame, layout[1]);
+                self.status_bar_state.view(frame, layout[2]);
+                if let Some(exit_confirmation) = &self.exit_confirmation {
+                    exit_confirmation.view(frame, layout[1]);
+                }
             })
             .expect("unable to draw to terminal");
     }
 
-    pub fn new(initial_identity_balance: Option<IdentityBalance>) -> Self {
+    pub fn new(
+        initial_identity_balance: Option<IdentityBalance>,
+        terminal_reader: TerminalReader,
+    ) -> Self {
         let mut terminal = TerminalBridge::new().expect("cannot initialize terminal app");
         terminal
             .enter_alternate_screen()
@@ -104,11 +148,14 @@ impl Ui {
         let mut ui = Ui {
             redraw_ts: Instant::now(),
             terminal,
+            terminal_reader,
             status_bar_state,
             screen,
             form: None,
-            blocked: false,
+            blocking_task: None,
             screen_stack: Vec::new(),
+            exit_confirmation: None,
+            backend_idle: true,
         };
 
         ui.redraw();
@@ -120,6 +167,38 @@ impl Ui {
         app_state: impl Deref<Target = AppState>,
         event: Event<'s>,
     ) -> UiFeedback {
+        if let Event::Tick { backend_idle } = &event {
+            self.backend_idle = *backend_idle;
+        }
+        if let (Some(exit_confirmation), Event::Key(key_event)) = (&self.exit_confirmation, &event)
+        {
+            return match exit_confirmation.on_event(*key_event) {
+                Some(true) => UiFeedback::Quit,
+                Some(false) => {
+                    self.exit_confirmation = None;
+                    UiFeedback::Redraw
+                }
+                None => UiFeedback::None,
+            };
+        }
+
+        let app_state = app_state.deref();
+        match self.handle_event(app_state, event).await {
+            // Ask first if quitting would lose a run, a task or reports
+            UiFeedback::Quit => {
+                let warnings = app_state.exit_warnings(self.backend_idle).await;
+                if warnings.is_empty() {
+                    UiFeedback::Quit
+                } else {
+                    self.exit_confirmation = Some(ExitConfirmation::new(warnings));
+                    UiFeedback::Redraw
+                }
+            }
+            ui_feedback => ui_feedback,
+        }
+    }
+
+    async fn handle_event<'s>(&mut self, app_state: &AppState, event: Event<'s>) -> UiFeedback {
         let mut redraw = false;
 
         // On task completion we shall unfreeze the screen and update status bar
@@ -131,8 +210,14 @@ impl Ui {
             | BackendEvent::StrategyError { .. },
         ) = &event
         {
-            self.status_bar_state.unblock();
-            self.blocked = false;
+            // Other tasks run meanwhile, the UI waits for its own one only
+            if self
+                .blocking_task
+                .is_some_and(|number| !app_state.activity_log().is_running(number))
+            {
+                self.status_bar_state.unblock();
+                self.blocking_task = None;
+            }
             redraw = true;
         }
 
@@ -208,28 +293,96 @@ impl Ui {
             }
         }
 
-        if self.blocked {
+        if let Some(blocking_task) = self.blocking_task {
+            if let Event::Tick { .. } = &event {
+                self.status_bar_state.advance_spinner();
+                // A blocked screen may still show the progress of its task
+                if self.form.is_none() {
+                    self.screen.on_event(&event);
+                }
+                return UiFeedback::Redraw;
+            }
+            // Strategy runs and document deletions wrap up before they stop, other
+            // tasks are dropped
+            if let Event::Key(KeyEvent {
+                code: Key::Char('x'),
+                modifiers: KeyModifiers::CONTROL,
+            }) = event
+            {
+                if app_state.activity_log().stop_strategy_runs(blocking_task)
+                    || app_state.cancel_document_deletion()
+                    || app_state.activity_log().cancel(blocking_task)
+                {
+                    self.status_bar_state.cancel_requested();
+                    return UiFeedback::Redraw;
+                }
+            }
+            return UiFeedback::None;
+        }
+
+        // Screens shouldn't refresh themselves under an open form
+        if matches!(event, Event::Tick { .. }) && self.form.is_some() {
             return UiFeedback::None;
         }
 
+        // Forms included, so a typed amount can be switched to another unit
+        if let Event::Key(KeyEvent {
+            code: Key::Char('u'),
+            modifiers: KeyModifiers::CONTROL,
+        }) = event
+        {
+            units::cycle_unit();
+            return UiFeedback::Redraw;
+        }
+
         let ui_feedback = if let (Some(form), Event::Key(event)) = (&mut self.form, &event) {
-            match form.on_event(*event) {
+            let form_status = match form.on_event(*event) {
+                FormStatus::EditText {
+                    text,
+                    extension,
+                    edited,
+                } => {
+                    let result = editor::edit_text(
+                        &mut self.terminal,
+                        &self.terminal_reader,
+                        &text,
+                        extension,
+                    )
+                    .await;
+                    redraw = true;
+                    match edited {
+                        // The input that asked for it takes the text when it's drawn
+                        Some(edited) => {
+                            edited.set(result);
+                            FormStatus::Redraw
+                        }
+                        None => form.on_text_edited(result),
+                    }
+                }
+                status => status,
+            };
+
+            match form_status {
                 FormStatus::Done { task, block } => {
                     self.form = None;
                     if block {
                         self.status_bar_state.block();
-                        self.blocked = true;
+                        self.blocking_task = Some(app_state.activity_log().next_task_number());
                     }
                     UiFeedback::ExecuteTask(task)
                 }
                 FormStatus::NextScreen(controller_builder) => {
                     self.form = None;
-                    let controller = controller_builder(app_state.deref()).await;
+                    let controller = controller_builder(app_state).await;
                     self.status_bar_state.add_child(controller.name());
                     let old_screen = mem::replace(&mut self.screen, Screen::new(controller));
                     self.screen_stack.push(old_screen);
                     UiFeedback::Redraw
                 }
+                FormStatus::NextForm(controller) => {
+                    self.form = Some(Form::new(controller));
+                    UiFeedback::Redraw
+                }
                 FormStatus::PreviousScreen => {
                     self.form = None;
                     self.status_bar_state.to_parent();
@@ -241,6 +394,8 @@ impl Ui {
                     }
                     UiFeedback::Redraw
                 }
+                // Opening an editor right after another one is not supported
+                FormStatus::EditText { .. } => UiFeedback::Redraw,
                 FormStatus::Redraw => UiFeedback::Redraw,
                 FormStatus::None => UiFeedback::None,
                 FormStatus::Exit => {
@@ -248,10 +403,35 @@ impl Ui {
                     UiFeedback::Redraw
                 }
             }
+        } else if let Event::Key(KeyEvent {
+            code: Key::Char('t'),
+            modifiers: KeyModifiers::CONTROL,
+        }) = event
+        {
+            id_names::toggle_full_ids();
+            UiFeedback::Redraw
+        } else if let Some(tab) = self.tab_switch_requested(&event) {
+            self.switch_tab(tab, app_state).await;
+            UiFeedback::Redraw
         } else {
-            match self.screen.on_event(&event) {
+            let screen_feedback = match self.screen.on_event(&event) {
+                ScreenFeedback::EditText { text, extension } => {
+                    let result = editor::edit_text(
+                        &mut self.terminal,
+                        &self.terminal_reader,
+                        &text,
+                        extension,
+                    )
+                    .await;
+                    redraw = true;
+                    self.screen.controller.on_text_edited(result)
+                }
+                feedback => feedback,
+            };
+
+            match screen_feedback {
                 ScreenFeedback::NextScreen(controller_builder) => {
-                    let controller = controller_builder(app_state.deref()).await;
+                    let controller = controller_builder(app_state).await;
                     self.status_bar_state.add_child(controller.name());
                     let old_screen = mem::replace(&mut self.screen, Screen::new(controller));
                     self.screen_stack.push(old_screen);
@@ -269,10 +449,18 @@ impl Ui {
                     {
                         // Rebuild the StrategiesScreenController when navigating back from
                         // SelectedStrategyScreenController
-                        let new_controller =
-                            StrategiesScreenController::new(app_state.deref()).await;
+                        let new_controller = StrategiesScreenController::new(app_state).await;
                         self.screen_stack.pop(); // Remove the old StrategiesScreenController from the stack
                         self.screen = Screen::new(Box::new(new_controller));
+                    } else if (current_screen_name == "Wallet manager"
+                        || current_screen_name == "Coin control")
+                        && previous_screen_name == Some("Wallet")
+                    {
+                        // The loaded wallet may have changed in the wallet manager, or its
+                        // UTXOs were frozen
+                        let new_controller = WalletScreenController::new(app_state).await;
+                        self.screen_stack.pop();
+                        self.screen = Screen::new(Box::new(new_controller));
                     } else {
                         // Regular back navigation
                         if let Some(previous_screen) = self.screen_stack.pop() {
@@ -292,7 +480,7 @@ impl Ui {
                 ScreenFeedback::FormThenNextScreen { form, screen } => {
                     self.form = Some(Form::new(form));
 
-                    let controller = screen(app_state.deref()).await;
+                    let controller = screen(app_state).await;
                     self.status_bar_state.add_child(controller.name());
                     let old_screen = mem::replace(&mut self.screen, Screen::new(controller));
                     self.screen_stack.push(old_screen);
@@ -302,10 +490,12 @@ impl Ui {
                 ScreenFeedback::Task { task, block } => {
                     if block {
                         self.status_bar_state.block();
-                        self.blocked = true;
+                        self.blocking_task = Some(app_state.activity_log().next_task_number());
                     }
                     UiFeedback::ExecuteTask(task)
                 }
+                // Opening an editor right after another one is not supported
+                ScreenFeedback::EditText { .. } => UiFeedback::Redraw,
                 ScreenFeedback::Redraw => UiFeedback::Redraw,
                 ScreenFeedback::Quit => UiFeedback::Quit,
                 ScreenFeedback::None => UiFeedback::None,
@@ -320,6 +510,58 @@ impl Ui {
     }
 }
 
+impl Ui {
+    /// Section the current screen belongs to.
+    fn current_tab(&self) -> Tab {
+        let section_screen_name = match self.screen_stack.len() {
+            0 => return Tab::Main,
+            1 => self.screen.controller.name(),
+            _ => self.screen_stack[1].controller.name(),
+        };
+        Tab::from_section_screen_name(section_screen_name)
+    }
+
+    /// Checks if the key event is a tab switch. Number keys are left to the
+    /// screen if it uses them as commands.
+    fn tab_switch_requested(&self, event: &Event) -> Option<Tab> {
+        match event {
+            Event::Key(KeyEvent {
+                code: Key::Tab,
+                modifiers: KeyModifiers::NONE,
+            }) => Some(self.current_tab().next()),
+            Event::Key(KeyEvent {
+                code: Key::Char(c),
+                modifiers: KeyModifiers::NONE,
+            }) if !self
+                .screen
+                .controller
+                .command_keys()
+                .iter()
+                .any(|key| key.keybinding == c.to_string()) =>
+            {
+                Tab::from_digit(*c)
+            }
+            _ => None,
+        }
+    }
+
+    async fn switch_tab(&mut self, tab: Tab, app_state: &AppState) {
+        // Main menu is always at the bottom of the stack, unwind to it first
+        if !self.screen_stack.is_empty() {
+            self.screen_stack.truncate(1);
+            self.screen = self.screen_stack.pop().expect("checked above");
+            self.status_bar_state.to_root();
+        }
+
+        if let Some(builder) = tab.builder() {
+            let controller = builder(app_state).await;
+            self.status_bar_state.add_child(controller.name());
+            let main_screen = mem::replace(&mut self.screen, Screen::new(controller));
+            self.screen_stack.push(main_screen);
+        }
+    }
+}
+
 impl Drop for Ui {
     fn drop(&mut self) {
         let _ = self.terminal.leave_alternate_screen();
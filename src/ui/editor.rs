@@ -0,0 +1,67 @@
+//! External editor integration.
+//!
+//! Long texts such as contract JSON are impractical to edit in a single-line
+//! input, so the terminal UI is suspended and the user's `$VISUAL` / `$EDITOR`
+//! is opened on a temporary file instead. Terminal events aren't read
+//! meanwhile, so the editor gets every keystroke and none of them reach the UI.
+
+use std::{fs, process::Command, time::Duration};
+
+use crossterm::event;
+use tuirealm::terminal::TerminalBridge;
+
+use super::terminal_events::TerminalReader;
+
+const FALLBACK_EDITOR: &str = "vi";
+
+/// Opens the external editor on `text` and returns the edited text.
+pub(super) async fn edit_text(
+    terminal: &mut TerminalBridge,
+    terminal_reader: &TerminalReader,
+    text: &str,
+    extension: &str,
+) -> Result<String, String> {
+    let path = std::env::temp_dir().join(format!(
+        "platform_explorer_{}.{}",
+        std::process::id(),
+        extension
+    ));
+    fs::write(&path, text).map_err(|e| format!("Unable to create a temporary file: {e}"))?;
+
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| FALLBACK_EDITOR.to_owned());
+
+    terminal_reader.stop().await;
+    let _ = terminal.leave_alternate_screen();
+    let _ = terminal.disable_raw_mode();
+
+    // Run through the shell so editors configured with arguments (`code -w`) work
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{editor} \"$1\""))
+        .arg("sh")
+        .arg(&path)
+        .status();
+
+    let _ = terminal.enter_alternate_screen();
+    let _ = terminal.enable_raw_mode();
+    let _ = terminal.clear_screen();
+
+    // Drop keystrokes that were meant for the editor
+    while event::poll(Duration::ZERO).unwrap_or(false) {
+        let _ = event::read();
+    }
+    terminal_reader.start();
+
+    let result = match status {
+        Ok(status) if status.success() => {
+            fs::read_to_string(&path).map_err(|e| format!("Unable to read the edited file: {e}"))
+        }
+        Ok(status) => Err(format!("Editor `{editor}` exited with {status}")),
+        Err(e) => Err(format!("Unable to start editor `{editor}`: {e}")),
+    };
+    let _ = fs::remove_file(&path);
+
+    result
+}
@@ -0,0 +1,64 @@
+//! Confirmation asked before quitting while work would be lost.
+
+use tuirealm::{
+    event::{Key, KeyEvent, KeyModifiers},
+    props::{Color, Style},
+    tui::{
+        prelude::Rect,
+        widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    },
+    Frame,
+};
+
+/// Popup listing what quitting would lose, drawn over the current screen.
+pub(crate) struct ExitConfirmation {
+    warnings: Vec<String>,
+}
+
+impl ExitConfirmation {
+    pub(crate) fn new(warnings: Vec<String>) -> Self {
+        ExitConfirmation { warnings }
+    }
+
+    /// `Some(true)` to quit, `Some(false)` to stay, `None` for other keys.
+    pub(crate) fn on_event(&self, event: KeyEvent) -> Option<bool> {
+        match event {
+            KeyEvent {
+                code: Key::Char('y'),
+                modifiers: KeyModifiers::NONE,
+            } => Some(true),
+            KeyEvent {
+                code: Key::Char('n') | Key::Char('q') | Key::Esc,
+                modifiers: KeyModifiers::NONE,
+            } => Some(false),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn view(&self, frame: &mut Frame, area: Rect) {
+        let text = format!(
+            "Quitting now would lose:\n\n{}\n\ny: quit anyway, n: stay",
+            self.warnings
+                .iter()
+                .map(|warning| format!(" - {}", warning))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+        let width = area.width.saturating_sub(4).min(80);
+        let height = (self.warnings.len() as u16 * 2 + 6).min(area.height);
+        let popup = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        );
+        let paragraph = Paragraph::new(text).wrap(Wrap { trim: false }).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Red))
+                .title("Quit?"),
+        );
+        frame.render_widget(Clear, popup);
+        frame.render_widget(paragraph, popup);
+    }
+}
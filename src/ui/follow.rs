@@ -0,0 +1,72 @@
+//! Read-only follow mode.
+//!
+//! Displays the live progress of a strategy run executed by another instance,
+//! refreshed every second, without a backend so no task can be executed.
+
+use std::{path::PathBuf, time::Duration};
+
+use crossterm::event::{Event as TuiEvent, EventStream, KeyCode};
+use futures::{select, FutureExt, StreamExt};
+use tuirealm::{
+    terminal::TerminalBridge,
+    tui::prelude::{Constraint, Direction, Layout},
+};
+
+use super::screen::widgets::info::Info;
+use crate::backend::run_progress::RunProgress;
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Runs the follow mode UI until `q` is pressed.
+pub async fn follow_run_progress(progress_path: PathBuf) {
+    let mut terminal = TerminalBridge::new().expect("cannot initialize terminal app");
+    terminal
+        .enter_alternate_screen()
+        .expect("cannot put terminal into alt mode");
+    terminal
+        .enable_raw_mode()
+        .expect("cannot enable terminal raw mode");
+
+    let mut status = Info::new_fixed(&format!(
+        "Following {} (read-only)    q Quit",
+        progress_path.display()
+    ));
+    let mut terminal_event_stream = EventStream::new().fuse();
+
+    loop {
+        let mut progress = match RunProgress::read(&progress_path) {
+            Ok(progress) => Info::new_fixed(&progress.to_string()),
+            Err(e) => Info::new_error(&format!("{}\n\nWaiting for a strategy run...", e)),
+        };
+
+        terminal
+            .raw_mut()
+            .draw(|frame| {
+                let layout = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Min(10), Constraint::Max(3)].as_ref())
+                    .split(frame.size());
+                progress.view(frame, layout[0]);
+                status.view(frame, layout[1]);
+            })
+            .expect("unable to draw to terminal");
+
+        let quit = select! {
+            terminal_event = terminal_event_stream.next() => matches!(
+                terminal_event,
+                None | Some(Err(_))
+            ) || matches!(
+                terminal_event,
+                Some(Ok(TuiEvent::Key(key_event))) if key_event.code == KeyCode::Char('q')
+            ),
+            _ = tokio::time::sleep(REFRESH_INTERVAL).boxed().fuse() => false,
+        };
+        if quit {
+            break;
+        }
+    }
+
+    let _ = terminal.leave_alternate_screen();
+    let _ = terminal.disable_raw_mode();
+    let _ = terminal.clear_screen();
+}
@@ -3,7 +3,11 @@
 mod utils;
 mod widgets;
 
-use std::ops::{Deref, DerefMut};
+use std::{
+    cell::RefCell,
+    ops::{Deref, DerefMut},
+    rc::Rc,
+};
 
 use tuirealm::{
     event::KeyEvent,
@@ -43,6 +47,28 @@ pub(crate) enum InputStatus<T> {
     None,
     /// Exit
     Exit,
+    /// Suspend the UI and open the text in an external editor, the result is
+    /// put into `edited` for the input to pick up
+    EditText {
+        text: String,
+        extension: &'static str,
+        edited: EditedText,
+    },
+}
+
+/// Slot for the result of editing an input's text in an external editor, the
+/// input takes it when it's drawn next.
+#[derive(Clone, Default)]
+pub(crate) struct EditedText(Rc<RefCell<Option<Result<String, String>>>>);
+
+impl EditedText {
+    pub(crate) fn set(&self, result: Result<String, String>) {
+        *self.0.borrow_mut() = Some(result);
+    }
+
+    pub(crate) fn take(&self) -> Option<Result<String, String>> {
+        self.0.borrow_mut().take()
+    }
 }
 
 /// Partially defined conversion from [InputStatus] to [FormStatus].
@@ -56,6 +82,15 @@ impl<T> From<InputStatus<T>> for FormStatus {
             InputStatus::Redraw => FormStatus::Redraw,
             InputStatus::None => FormStatus::None,
             InputStatus::Exit => FormStatus::Exit,
+            InputStatus::EditText {
+                text,
+                extension,
+                edited,
+            } => FormStatus::EditText {
+                text,
+                extension,
+                edited: Some(edited),
+            },
         }
     }
 }
@@ -75,6 +110,10 @@ impl<C: FormController> Form<C> {
         self.controller.on_event(event)
     }
 
+    pub(crate) fn on_text_edited(&mut self, result: Result<String, String>) -> FormStatus {
+        self.controller.on_text_edited(result)
+    }
+
     pub(crate) fn view(&mut self, frame: &mut Frame, area: Rect) {
         let block = Block::new()
             .borders(BorderSides::ALL)
@@ -96,9 +135,22 @@ impl<C: FormController> Form<C> {
 /// [FormController] to process all of the results to produce a [Task] to
 /// return, since a user's input precedes some action.
 pub(crate) enum FormStatus {
-    Done { task: Task, block: bool },
+    Done {
+        task: Task,
+        block: bool,
+    },
     NextScreen(ScreenControllerBuilder),
+    /// Replace the form with another one continuing it
+    NextForm(Box<dyn FormController>),
     PreviousScreen,
+    /// Suspend the UI and open the text in an external editor, the result is
+    /// put into `edited` if an input asked for it, otherwise it's passed back
+    /// with [FormController::on_text_edited]
+    EditText {
+        text: String,
+        extension: &'static str,
+        edited: Option<EditedText>,
+    },
     Redraw,
     None,
     Exit,
@@ -125,6 +177,12 @@ pub(crate) trait FormController {
 
     /// Number of all form steps
     fn steps_number(&self) -> u8;
+
+    /// Process the text returned from an external editor after a
+    /// [FormStatus::EditText] the controller asked for itself
+    fn on_text_edited(&mut self, _result: Result<String, String>) -> FormStatus {
+        FormStatus::None
+    }
 }
 
 impl FormController for Box<dyn FormController> {
@@ -151,4 +209,8 @@ impl FormController for Box<dyn FormController> {
     fn steps_number(&self) -> u8 {
         self.deref().steps_number()
     }
+
+    fn on_text_edited(&mut self, result: Result<String, String>) -> FormStatus {
+        self.deref_mut().on_text_edited(result)
+    }
 }
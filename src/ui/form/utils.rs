@@ -45,7 +45,7 @@ impl<F> ComposedInput<F> {
 }
 
 /// Macro for internal use to implement [ComposedInput] for field combinations
-/// up to 16 inputs. Because of the craving for type safety and a lack of tools
+/// up to 20 inputs. Because of the craving for type safety and a lack of tools
 /// it's a common pattern in Rust to make use of tuples and declarative macros.
 macro_rules! impl_sequential_input {
     // Macro entry point
@@ -64,7 +64,7 @@ macro_rules! impl_sequential_input {
                     event,
                     (),
                     ($($input),*),
-                    (0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16)
+                    (0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20)
                 }
             }
 
@@ -75,7 +75,7 @@ macro_rules! impl_sequential_input {
                     frame,
                     area,
                     ($($input),*),
-                    (0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16)
+                    (0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20)
                 }
             }
         }
@@ -88,7 +88,7 @@ macro_rules! impl_sequential_input {
                     @step_name_branch
                     self,
                     ($($input),*),
-                    (0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16)
+                    (0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20)
                 }
             }
 
@@ -164,6 +164,9 @@ macro_rules! impl_sequential_input {
                 InputStatus::Redraw => InputStatus::Redraw,
                 InputStatus::None => InputStatus::None,
                 InputStatus::Exit => InputStatus::Exit,
+                InputStatus::EditText { text, extension, edited } => {
+                    InputStatus::EditText { text, extension, edited }
+                }
             }
         }
 
@@ -192,12 +195,15 @@ macro_rules! impl_sequential_input {
             InputStatus::Redraw => InputStatus::Redraw,
             InputStatus::None => InputStatus::None,
             InputStatus::Exit => InputStatus::Exit,
+            InputStatus::EditText { text, extension, edited } => {
+                InputStatus::EditText { text, extension, edited }
+            }
         }
     };
 }
 
 // Following macro calls implement [ComposedInput] for tuples of [Field]'s from
-// 2 to 16. If a form made of only one field none if this machinery is really
+// 2 to 20. If a form made of only one field none if this machinery is really
 // needed and form and its controller are pretty straightforward.
 impl_sequential_input!(I1, I2);
 impl_sequential_input!(I1, I2, I3);
@@ -214,3 +220,13 @@ impl_sequential_input!(I1, I2, I3, I4, I5, I6, I7, I8, I9, I10, I11, I12, I13);
 impl_sequential_input!(I1, I2, I3, I4, I5, I6, I7, I8, I9, I10, I11, I12, I13, I14);
 impl_sequential_input!(I1, I2, I3, I4, I5, I6, I7, I8, I9, I10, I11, I12, I13, I14, I15);
 impl_sequential_input!(I1, I2, I3, I4, I5, I6, I7, I8, I9, I10, I11, I12, I13, I14, I15, I16);
+impl_sequential_input!(I1, I2, I3, I4, I5, I6, I7, I8, I9, I10, I11, I12, I13, I14, I15, I16, I17);
+impl_sequential_input!(
+    I1, I2, I3, I4, I5, I6, I7, I8, I9, I10, I11, I12, I13, I14, I15, I16, I17, I18
+);
+impl_sequential_input!(
+    I1, I2, I3, I4, I5, I6, I7, I8, I9, I10, I11, I12, I13, I14, I15, I16, I17, I18, I19
+);
+impl_sequential_input!(
+    I1, I2, I3, I4, I5, I6, I7, I8, I9, I10, I11, I12, I13, I14, I15, I16, I17, I18, I19, I20
+);
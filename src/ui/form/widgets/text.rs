@@ -7,7 +7,7 @@ use std::{fmt::Display, str::FromStr};
 use tuirealm::{
     command::{self, Cmd},
     event::{Key, KeyEvent, KeyModifiers},
-    props::{BorderSides, Borders, Color, Style},
+    props::{BorderSides, Borders, Color, InputType, Style},
     tui::{
         prelude::{Constraint, Direction, Layout, Rect},
         widgets::Paragraph,
@@ -16,12 +16,16 @@ use tuirealm::{
 };
 
 use self::parsers::{DefaultTextInputParser, TextInputParser};
-use crate::ui::form::{Input, InputStatus};
+use crate::ui::form::{EditedText, Input, InputStatus};
 
 pub(crate) struct TextInput<P> {
     input: tui_realm_stdlib::Input,
     error_msg: Option<String>,
     parser: P,
+    /// Extension of the file the text is edited in with C-e, `None` if it
+    /// can't be edited externally
+    editor_extension: Option<&'static str>,
+    edited: EditedText,
 }
 
 impl<T> TextInput<DefaultTextInputParser<T>>
@@ -80,9 +84,41 @@ impl<P: TextInputParser> TextInput<P> {
             input,
             parser,
             error_msg: None,
+            editor_extension: Some("txt"),
+            edited: EditedText::default(),
         }
     }
 
+    /// Shows the typed text as asterisks, for secrets. Secrets aren't opened
+    /// in an external editor, which would leave them in a temporary file.
+    pub(crate) fn masked(mut self) -> Self {
+        self.input.attr(
+            Attribute::InputType,
+            AttrValue::InputType(InputType::Password('*')),
+        );
+        self.editor_extension = None;
+        self
+    }
+
+    /// Extension of the file the text is opened in with C-e, for the editor's
+    /// syntax highlighting.
+    pub(crate) fn editor_extension(mut self, extension: &'static str) -> Self {
+        self.editor_extension = Some(extension);
+        self
+    }
+
+    /// Current raw text of the input.
+    pub(crate) fn value(&self) -> String {
+        self.input.state().unwrap_one().unwrap_string()
+    }
+
+    /// Replace the input text, e.g. with one written in an external editor.
+    pub(crate) fn set_value(&mut self, value: &str) {
+        self.input
+            .attr(Attribute::Value, AttrValue::String(value.to_owned()));
+        self.reset_error();
+    }
+
     fn set_error(&mut self, error_msg: String) {
         self.input
             .attr(Attribute::Foreground, AttrValue::Color(Color::Red));
@@ -145,6 +181,18 @@ impl<P: TextInputParser> Input for TextInput<P> {
                 modifiers: KeyModifiers::CONTROL,
             } => InputStatus::Exit,
 
+            KeyEvent {
+                code: Key::Char('e'),
+                modifiers: KeyModifiers::CONTROL,
+            } => match self.editor_extension {
+                Some(extension) => InputStatus::EditText {
+                    text: self.value(),
+                    extension,
+                    edited: self.edited.clone(),
+                },
+                None => InputStatus::None,
+            },
+
             KeyEvent {
                 code: Key::Char(c), ..
             } => {
@@ -157,6 +205,21 @@ impl<P: TextInputParser> Input for TextInput<P> {
     }
 
     fn view(&mut self, frame: &mut Frame, area: Rect) {
+        match self.edited.take() {
+            // The input is a single line, so line breaks used in the editor for
+            // readability are folded into spaces
+            Some(Ok(text)) => self.set_value(
+                &text
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            ),
+            Some(Err(error)) => self.set_error(error),
+            None => {}
+        }
+
         let layout = Layout::default()
             .direction(Direction::Vertical)
             .constraints([Constraint::Min(2), Constraint::Min(2), Constraint::Min(0)].as_ref())
@@ -166,6 +229,17 @@ impl<P: TextInputParser> Input for TextInput<P> {
 
         if let Some(error) = &self.error_msg {
             frame.render_widget(Paragraph::new(error.as_str()), layout[1])
+        } else if let Some(hint) = self.parser.hint(&self.value()) {
+            frame.render_widget(
+                Paragraph::new(hint).style(Style::default().fg(Color::Gray)),
+                layout[1],
+            )
+        }
+        if self.editor_extension.is_some() {
+            frame.render_widget(
+                Paragraph::new("C-e to open in $EDITOR").style(Style::default().fg(Color::Gray)),
+                layout[2],
+            )
         }
     }
 }
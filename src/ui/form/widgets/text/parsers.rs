@@ -1,13 +1,23 @@
 //! Parsers for text input.
 
-use std::{marker::PhantomData, str::FromStr};
+use std::{marker::PhantomData, ops::Range, str::FromStr};
 
 use dash_sdk::platform::{DocumentQuery, DriveDocumentQuery};
 use dpp::prelude::DataContract;
 
+use crate::{
+    backend::{load_profile::LoadProfile, run_schedule},
+    units::{self, CREDITS_PER_DUFF},
+};
+
 pub(crate) trait TextInputParser {
     type Output;
     fn parse_input(&self, input: &str) -> Result<Self::Output, String>;
+
+    /// Shown under the input as it's typed, unless there is an error.
+    fn hint(&self, _input: &str) -> Option<String> {
+        None
+    }
 }
 
 pub(crate) struct DefaultTextInputParser<T: FromStr> {
@@ -49,3 +59,128 @@ impl TextInputParser for DocumentQueryTextInputParser {
             .map_err(|e| e.to_string())
     }
 }
+
+/// Parses either a single number of times per block (`3`) or an inclusive
+/// range (`1..5`) into a `times_per_block_range` of a strategy frequency.
+pub(crate) struct TimesPerBlockRangeTextInputParser;
+
+impl TextInputParser for TimesPerBlockRangeTextInputParser {
+    type Output = Range<u16>;
+
+    fn parse_input(&self, input: &str) -> Result<Self::Output, String> {
+        let parse = |s: &str| {
+            s.trim()
+                .parse::<u16>()
+                .map_err(|_| format!("Cannot parse {} as a whole number", s.trim()))
+        };
+
+        let (min, max) = match input.split_once("..") {
+            Some((min, max)) => (parse(min)?, parse(max.trim_start_matches('='))?),
+            None => {
+                let times = parse(input)?;
+                (times, times)
+            }
+        };
+
+        if min > max {
+            return Err("Range minimum is greater than its maximum".to_owned());
+        }
+
+        Ok(min..max.checked_add(1).ok_or("Range maximum is too large")?)
+    }
+}
+
+/// Parses an amount in the shown unit, or the one written after it, into
+/// credits.
+pub(crate) struct CreditsTextInputParser;
+
+impl TextInputParser for CreditsTextInputParser {
+    type Output = u64;
+
+    fn parse_input(&self, input: &str) -> Result<Self::Output, String> {
+        units::parse_credits(input)
+    }
+
+    fn hint(&self, input: &str) -> Option<String> {
+        Some(amount_hint(units::parse_credits(input)))
+    }
+}
+
+/// Same as [CreditsTextInputParser] into duffs, for amounts paid on Core or
+/// withdrawn to it which can't hold fractions of a duff.
+pub(crate) struct DuffsTextInputParser;
+
+impl TextInputParser for DuffsTextInputParser {
+    type Output = u64;
+
+    fn parse_input(&self, input: &str) -> Result<Self::Output, String> {
+        let credits = units::parse_credits(input)?;
+        if credits % CREDITS_PER_DUFF != 0 {
+            return Err("Amount must be whole duffs".to_owned());
+        }
+        Ok(credits / CREDITS_PER_DUFF)
+    }
+
+    fn hint(&self, input: &str) -> Option<String> {
+        Some(amount_hint(units::parse_credits(input)))
+    }
+}
+
+/// Parses a [LoadProfile] keeping its error, which tells the expected syntax.
+pub(crate) struct LoadProfileTextInputParser;
+
+impl TextInputParser for LoadProfileTextInputParser {
+    type Output = LoadProfile;
+
+    fn parse_input(&self, input: &str) -> Result<Self::Output, String> {
+        input.parse()
+    }
+
+    fn hint(&self, _input: &str) -> Option<String> {
+        Some(
+            "constant, ramp:<secs>, step:<secs>:<steps> or spike:<start secs>:<length \
+             secs>:<multiplier>"
+                .to_owned(),
+        )
+    }
+}
+
+/// Takes a passphrase, refusing an empty one.
+pub(crate) struct PassphraseTextInputParser;
+
+impl TextInputParser for PassphraseTextInputParser {
+    type Output = String;
+
+    fn parse_input(&self, input: &str) -> Result<Self::Output, String> {
+        if input.is_empty() {
+            Err("The passphrase can't be empty".to_owned())
+        } else {
+            Ok(input.to_owned())
+        }
+    }
+}
+
+/// Parses when a scheduled run starts into a Unix time in seconds.
+pub(crate) struct StartTimeTextInputParser;
+
+impl TextInputParser for StartTimeTextInputParser {
+    type Output = u64;
+
+    fn parse_input(&self, input: &str) -> Result<Self::Output, String> {
+        run_schedule::parse_start_time(input, run_schedule::now_secs())
+    }
+
+    fn hint(&self, input: &str) -> Option<String> {
+        Some(match self.parse_input(input) {
+            Ok(start) => format!("First run at {}", run_schedule::format_time(start)),
+            Err(_) => "now, +<minutes> or HH:MM (UTC)".to_owned(),
+        })
+    }
+}
+
+fn amount_hint(credits: Result<u64, String>) -> String {
+    match credits {
+        Ok(credits) => units::conversion_hint(credits),
+        Err(_) => format!("Amount in {}, Ctrl+u switches units", units::shown_unit()),
+    }
+}
@@ -0,0 +1,98 @@
+//! Human-readable display of identity and contract IDs.
+//!
+//! IDs with a known name, a DPNS username for identities or the name a
+//! contract was loaded under, are shown as "name (abbreviated ID)". Ctrl+t
+//! switches between abbreviated and full IDs anywhere, text already on screen
+//! picks it up once redrawn or rebuilt.
+
+use std::{
+    collections::BTreeMap,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use dpp::platform_value::{string_encoding::Encoding, Identifier};
+
+use crate::backend::snapshot::AppStateSnapshot;
+
+static SHOW_FULL_IDS: AtomicBool = AtomicBool::new(false);
+
+/// Characters kept from each end of an abbreviated ID
+const ABBREVIATED_ID_PART: usize = 6;
+
+pub(crate) fn toggle_full_ids() {
+    SHOW_FULL_IDS.fetch_xor(true, Ordering::Relaxed);
+}
+
+/// Shortens a Base58 ID unless full IDs are toggled on, any other text is
+/// shown as is.
+pub(crate) fn shown_id(b58_id: &str) -> String {
+    if SHOW_FULL_IDS.load(Ordering::Relaxed)
+        || b58_id.len() <= ABBREVIATED_ID_PART * 2 + 1
+        || Identifier::from_string(b58_id, Encoding::Base58).is_err()
+    {
+        b58_id.to_owned()
+    } else {
+        format!(
+            "{}…{}",
+            &b58_id[..ABBREVIATED_ID_PART],
+            &b58_id[b58_id.len() - ABBREVIATED_ID_PART..]
+        )
+    }
+}
+
+/// Names of known identities and contracts by ID.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct IdNames {
+    identities: BTreeMap<Identifier, String>,
+    contracts: BTreeMap<Identifier, String>,
+}
+
+impl IdNames {
+    pub(crate) fn from_snapshot(snapshot: &AppStateSnapshot) -> Self {
+        IdNames {
+            identities: snapshot
+                .known_identities_names
+                .iter()
+                .filter_map(|(id, names)| Some((*id, names.first()?.clone())))
+                .collect(),
+            contracts: snapshot.known_contract_names.clone(),
+        }
+    }
+
+    pub(crate) fn identity(&self, id: &Identifier) -> String {
+        Self::display(self.identities.get(id), id)
+    }
+
+    pub(crate) fn contract(&self, id: &Identifier) -> String {
+        Self::display(self.contracts.get(id), id)
+    }
+
+    /// Same as [IdNames::contract] for a Base58 ID, any other text is shown
+    /// as is.
+    pub(crate) fn contract_b58(&self, b58_id: &str) -> String {
+        match Identifier::from_string(b58_id, Encoding::Base58) {
+            Ok(id) => self.contract(&id),
+            Err(_) => b58_id.to_owned(),
+        }
+    }
+
+    /// ID of a contract given as a Base58 ID or the name it was loaded under.
+    pub(crate) fn contract_id(&self, name: &str) -> Option<Identifier> {
+        Identifier::from_string(name, Encoding::Base58)
+            .ok()
+            .or_else(|| {
+                self.contracts
+                    .iter()
+                    .find(|(_, contract_name)| *contract_name == name)
+                    .map(|(id, _)| *id)
+            })
+    }
+
+    fn display(name: Option<&String>, id: &Identifier) -> String {
+        let b58_id = id.to_string(Encoding::Base58);
+        match name {
+            Some(name) => format!("{} ({})", name, shown_id(&b58_id)),
+            None => b58_id,
+        }
+    }
+}
@@ -64,6 +64,12 @@ pub(crate) trait ScreenController {
     /// Process key event, returning details on what's needed to be updated on
     /// UI.
     fn on_event(&mut self, event: &Event) -> ScreenFeedback;
+
+    /// Process the text returned from an external editor opened with
+    /// [ScreenFeedback::EditText].
+    fn on_text_edited(&mut self, _result: Result<String, String>) -> ScreenFeedback {
+        ScreenFeedback::None
+    }
 }
 
 impl ScreenController for Box<dyn ScreenController> {
@@ -86,6 +92,10 @@ impl ScreenController for Box<dyn ScreenController> {
     fn on_event(&mut self, event: &Event) -> ScreenFeedback {
         self.deref_mut().on_event(event)
     }
+
+    fn on_text_edited(&mut self, result: Result<String, String>) -> ScreenFeedback {
+        self.deref_mut().on_text_edited(result)
+    }
 }
 
 type Keybinding = &'static str;
@@ -134,6 +144,12 @@ pub(crate) enum ScreenFeedback {
         task: Task,
         block: bool,
     }, // TODO task should define whether it blocks or not
+    /// Suspend the UI to edit the text in an external editor, the result is
+    /// passed to [ScreenController::on_text_edited].
+    EditText {
+        text: String,
+        extension: &'static str,
+    },
     Redraw,
     Quit,
     None,
@@ -48,11 +48,7 @@ impl InfoDisplay for Identity {
             TabbedString::new(1, format!("Id: {}", self.id()).into()),
             TabbedString::new(
                 1,
-                format!(
-                    "Balance: {}",
-                    IdentityBalance::from_credits(self.balance()).dash_str()
-                )
-                .into(),
+                format!("Balance: {}", IdentityBalance::from_credits(self.balance())).into(),
             ),
             TabbedString::new(1, format!("Revision: {}", self.revision()).into()),
             TabbedString::new(1, "Public Keys:".into()),
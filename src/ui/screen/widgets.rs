@@ -2,3 +2,4 @@
 
 pub(crate) mod command_pallet;
 pub(crate) mod info;
+pub(crate) mod virtual_list;
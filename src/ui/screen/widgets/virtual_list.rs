@@ -0,0 +1,102 @@
+//! Virtualized list definitions.
+
+use tuirealm::{
+    event::{Key, KeyEvent, KeyModifiers},
+    props::{Alignment, BorderSides, Borders, Color, TextSpan},
+    tui::prelude::Rect,
+    AttrValue, Attribute, Frame, MockComponent,
+};
+
+/// Selectable list of rows that may hold thousands of them. Only the rows
+/// that fit the drawn area are handed to the underlying component, so drawing
+/// costs the same for ten rows or ten thousand.
+pub(crate) struct VirtualList {
+    rows: Vec<Vec<TextSpan>>,
+    selected: usize,
+    /// First row of the window, it only moves once the selection leaves it
+    offset: usize,
+    /// Rows that fit the last drawn area, a page for paging keys
+    visible_rows: usize,
+    title: Option<String>,
+}
+
+impl VirtualList {
+    pub(crate) fn new(rows: Vec<Vec<TextSpan>>, selected: usize) -> Self {
+        VirtualList {
+            selected: selected.min(rows.len().saturating_sub(1)),
+            rows,
+            offset: 0,
+            visible_rows: 1,
+            title: None,
+        }
+    }
+
+    pub(crate) fn set_title(&mut self, title: Option<String>) {
+        self.title = title;
+    }
+
+    pub(crate) fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Moves the selection with C-n / C-p by one row and with PageDown /
+    /// PageUp by a window, Home and End jump to the ends.
+    pub(crate) fn on_event(&mut self, event: &KeyEvent) -> DoRedraw {
+        let last = self.rows.len().saturating_sub(1);
+        let selected = match event {
+            KeyEvent {
+                code: Key::Char('n'),
+                modifiers: KeyModifiers::CONTROL,
+            } => (self.selected + 1).min(last),
+            KeyEvent {
+                code: Key::Char('p'),
+                modifiers: KeyModifiers::CONTROL,
+            } => self.selected.saturating_sub(1),
+            KeyEvent {
+                code: Key::PageDown,
+                ..
+            } => (self.selected + self.visible_rows).min(last),
+            KeyEvent {
+                code: Key::PageUp, ..
+            } => self.selected.saturating_sub(self.visible_rows),
+            KeyEvent {
+                code: Key::Home, ..
+            } => 0,
+            KeyEvent { code: Key::End, .. } => last,
+            _ => return false,
+        };
+        self.selected = selected;
+        true
+    }
+
+    pub(crate) fn view(&mut self, frame: &mut Frame, area: Rect) {
+        // Top and bottom borders take a line each
+        self.visible_rows = (area.height.saturating_sub(2) as usize).max(1);
+        if self.selected < self.offset {
+            self.offset = self.selected;
+        } else if self.selected >= self.offset + self.visible_rows {
+            self.offset = self.selected + 1 - self.visible_rows;
+        }
+        let window_end = (self.offset + self.visible_rows).min(self.rows.len());
+
+        let mut title = self.title.clone().unwrap_or_default();
+        if self.rows.len() > self.visible_rows {
+            title.push_str(&format!(" [{}/{}]", self.selected + 1, self.rows.len()));
+        }
+
+        let mut list = tui_realm_stdlib::List::default()
+            .rows(self.rows[self.offset..window_end].to_vec())
+            .borders(
+                Borders::default()
+                    .sides(BorderSides::LEFT | BorderSides::TOP | BorderSides::BOTTOM),
+            )
+            .title(title.trim_start(), Alignment::Left)
+            .selected_line(self.selected - self.offset)
+            .highlighted_color(Color::Magenta);
+        list.attr(Attribute::Scroll, AttrValue::Flag(true));
+        list.attr(Attribute::Focus, AttrValue::Flag(true));
+        list.view(frame, area);
+    }
+}
+
+type DoRedraw = bool;
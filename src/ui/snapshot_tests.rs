@@ -0,0 +1,126 @@
+//! Golden file tests of the screens.
+//!
+//! Each test renders a screen over the demo state into a test terminal and
+//! compares the text with its golden file in `src/ui/snapshots`. After an
+//! intended UI change, or for a new screen, run the tests with
+//! `UPDATE_GOLDENS=1` to write the goldens, then review and commit them.
+//! Without it the tests only read the goldens, a missing one fails the test.
+
+use std::{env, fs, path::PathBuf};
+
+use dpp::dashcore::Network;
+use tempfile::TempDir;
+use tuirealm::tui::{backend::TestBackend, Terminal};
+
+use super::{
+    screen::{Screen, ScreenController},
+    views::{
+        contracts::ContractsScreenController, identities::IdentitiesScreenController,
+        main::MainScreenController, strategies::StrategiesScreenController,
+        wallet::WalletScreenController,
+    },
+};
+use crate::backend::{mock::MockBackend, TaskBackend};
+
+const WIDTH: u16 = 120;
+const HEIGHT: u16 = 32;
+
+/// Text of the screen drawn over the whole test terminal, one line per row
+/// without trailing spaces.
+fn render(controller: Box<dyn ScreenController>) -> String {
+    let mut screen = Screen::new(controller);
+    let mut terminal =
+        Terminal::new(TestBackend::new(WIDTH, HEIGHT)).expect("expected a test terminal");
+    terminal
+        .draw(|frame| screen.view(frame, frame.size()))
+        .expect("unable to draw to the test terminal");
+
+    let buffer = terminal.backend().buffer();
+    let mut text = String::new();
+    for y in 0..buffer.area.height {
+        let line: String = (0..buffer.area.width)
+            .map(|x| buffer.get(x, y).symbol())
+            .collect();
+        text.push_str(line.trim_end());
+        text.push('\n');
+    }
+    text
+}
+
+fn assert_golden(name: &str, rendered: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src/ui/snapshots")
+        .join(format!("{}.txt", name));
+    if env::var_os("UPDATE_GOLDENS").is_some() {
+        fs::write(&path, rendered)
+            .unwrap_or_else(|e| panic!("unable to write {}: {}", path.display(), e));
+        return;
+    }
+    let golden = fs::read_to_string(&path).unwrap_or_else(|e| {
+        panic!(
+            "no golden file for the {} screen at {} ({}), run with UPDATE_GOLDENS=1 to write \
+             it, then review and commit it. Rendered:\n{}",
+            name,
+            path.display(),
+            e,
+            rendered
+        )
+    });
+    assert_eq!(
+        golden,
+        rendered,
+        "{} screen differs from {}, run with UPDATE_GOLDENS=1 if the change is intended",
+        name,
+        path.display()
+    );
+}
+
+/// Demo backend of a single test, its local Drive is in a temporary directory
+/// removed along with it.
+struct DemoBackend {
+    backend: MockBackend,
+    _drive_dir: TempDir,
+}
+
+impl DemoBackend {
+    fn new() -> Self {
+        let drive_dir = TempDir::new().expect("expected a temporary directory");
+        DemoBackend {
+            backend: MockBackend::with_drive_path(Network::Testnet, drive_dir.path()),
+            _drive_dir: drive_dir,
+        }
+    }
+}
+
+#[test]
+fn main_screen() {
+    assert_golden("main", &render(Box::new(MainScreenController::new())));
+}
+
+#[tokio::test]
+async fn wallet_screen() {
+    let demo = DemoBackend::new();
+    let controller = WalletScreenController::new(demo.backend.state()).await;
+    assert_golden("wallet", &render(Box::new(controller)));
+}
+
+#[tokio::test]
+async fn identities_screen() {
+    let demo = DemoBackend::new();
+    let controller = IdentitiesScreenController::new(demo.backend.state()).await;
+    assert_golden("identities", &render(Box::new(controller)));
+}
+
+#[tokio::test]
+async fn contracts_screen() {
+    let demo = DemoBackend::new();
+    let controller = ContractsScreenController::new(demo.backend.state()).await;
+    assert_golden("contracts", &render(Box::new(controller)));
+}
+
+#[tokio::test]
+async fn strategies_screen() {
+    let demo = DemoBackend::new();
+    let controller = StrategiesScreenController::new(demo.backend.state()).await;
+    assert_golden("strategies", &render(Box::new(controller)));
+}
@@ -14,10 +14,15 @@ use tuirealm::{
 
 use super::IdentityBalance;
 
+/// Frames of the busy indicator, one per UI tick
+const SPINNER_FRAMES: [&str; 4] = ["|", "/", "-", "\\"];
+
 #[derive(Default)]
 pub(crate) struct StatusBarState {
     breadcrumbs: Vec<&'static str>,
     blocked: bool,
+    cancelling: bool,
+    spinner_frame: usize,
     identity_loaded_balance: IdentityBalanceStatus,
 }
 
@@ -38,7 +43,7 @@ impl Display for IdentityBalanceStatus {
         match self {
             IdentityBalanceStatus::NoIdentity => write!(f, "No identity"),
             IdentityBalanceStatus::Balance(balance) => {
-                write!(f, "Loaded identity balance: {}", balance.dash_str())
+                write!(f, "Loaded identity balance: {}", balance)
             }
             IdentityBalanceStatus::RefreshError => write!(f, "Balance refresh error"),
         }
@@ -67,6 +72,16 @@ impl StatusBarState {
 
     pub(crate) fn block(&mut self) {
         self.blocked = true;
+        self.cancelling = false;
+        self.spinner_frame = 0;
+    }
+
+    pub(crate) fn cancel_requested(&mut self) {
+        self.cancelling = true;
+    }
+
+    pub(crate) fn advance_spinner(&mut self) {
+        self.spinner_frame = (self.spinner_frame + 1) % SPINNER_FRAMES.len();
     }
 
     pub(crate) fn unblock(&mut self) {
@@ -81,6 +96,10 @@ impl StatusBarState {
         self.breadcrumbs.pop();
     }
 
+    pub(crate) fn to_root(&mut self) {
+        self.breadcrumbs.truncate(1);
+    }
+
     pub(crate) fn view(&self, frame: &mut Frame, area: Rect) {
         let block = Block::new().borders(BorderSides::ALL);
 
@@ -94,8 +113,16 @@ impl StatusBarState {
 
         if self.blocked {
             Label::default()
-                .text("Executing a task, please wait")
-                .modifiers(Modifier::RAPID_BLINK) // TODO: doesn't work lol
+                .text(&format!(
+                    "{} {}",
+                    SPINNER_FRAMES[self.spinner_frame],
+                    if self.cancelling {
+                        "Cancelling, wrapping up the run"
+                    } else {
+                        "Executing a task, please wait"
+                    }
+                ))
+                .modifiers(Modifier::BOLD)
         } else {
             Label::default().text(&breadcrumbs_str)
         }
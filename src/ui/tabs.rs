@@ -0,0 +1,108 @@
+//! Top-level sections that can be switched to from any screen.
+
+use tuirealm::{
+    props::{Color, Style},
+    tui::{
+        prelude::{Modifier, Rect},
+        widgets::Tabs,
+    },
+    Frame,
+};
+
+use super::{
+    screen::ScreenControllerBuilder,
+    views::{
+        contracts::ContractsScreenController, identities::IdentitiesScreenController,
+        main::MainScreenController, platform_info::PlatformInfoScreenController,
+        strategies::StrategiesScreenController, wallet::WalletScreenController,
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Tab {
+    Main,
+    Identities,
+    Contracts,
+    Wallet,
+    Strategies,
+    Platform,
+}
+
+impl Tab {
+    pub(crate) const ALL: [Tab; 6] = [
+        Tab::Main,
+        Tab::Identities,
+        Tab::Contracts,
+        Tab::Wallet,
+        Tab::Strategies,
+        Tab::Platform,
+    ];
+
+    pub(crate) fn title(&self) -> &'static str {
+        match self {
+            Tab::Main => "Main",
+            Tab::Identities => "Identities",
+            Tab::Contracts => "Contracts",
+            Tab::Wallet => "Wallet",
+            Tab::Strategies => "Strategies",
+            Tab::Platform => "Platform",
+        }
+    }
+
+    /// Tab selected with a number key, starting from `1`.
+    pub(crate) fn from_digit(digit: char) -> Option<Tab> {
+        let index = digit.to_digit(10)?.checked_sub(1)?;
+        Self::ALL.get(index as usize).copied()
+    }
+
+    /// Tab the screen belongs to, using the screen name right after the main
+    /// menu in the screens stack.
+    pub(crate) fn from_section_screen_name(name: &str) -> Tab {
+        match name {
+            "Identities" => Tab::Identities,
+            "Contracts" => Tab::Contracts,
+            "Wallet" => Tab::Wallet,
+            "Strategies" => Tab::Strategies,
+            "Platform Information" => Tab::Platform,
+            _ => Tab::Main,
+        }
+    }
+
+    pub(crate) fn next(&self) -> Tab {
+        let index = Self::ALL.iter().position(|t| t == self).unwrap_or_default();
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// Builder for the section's screen, `None` for the main menu since it is
+    /// always at the bottom of the screens stack.
+    pub(crate) fn builder(&self) -> Option<ScreenControllerBuilder> {
+        match self {
+            Tab::Main => None,
+            Tab::Identities => Some(IdentitiesScreenController::builder()),
+            Tab::Contracts => Some(ContractsScreenController::builder()),
+            Tab::Wallet => Some(WalletScreenController::builder()),
+            Tab::Strategies => Some(StrategiesScreenController::builder()),
+            Tab::Platform => Some(PlatformInfoScreenController::builder()),
+        }
+    }
+}
+
+pub(crate) fn view(frame: &mut Frame, area: Rect, selected: Tab) {
+    let titles = Tab::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, tab)| format!("{} {}", i + 1, tab.title()))
+        .collect::<Vec<_>>();
+    let selected_index = Tab::ALL
+        .iter()
+        .position(|t| *t == selected)
+        .unwrap_or_default();
+
+    let tabs = Tabs::new(titles).select(selected_index).highlight_style(
+        Style::default()
+            .fg(Color::Magenta)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    frame.render_widget(tabs, area);
+}
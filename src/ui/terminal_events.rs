@@ -0,0 +1,119 @@
+//! Terminal events read on their own task.
+//!
+//! Events queue up while the UI handles an event or draws instead of depending
+//! on when the loop polls. A program taking the terminal over, like the
+//! external editor, needs the input to itself, so the reader is stopped
+//! meanwhile and started again afterwards.
+
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, Mutex,
+};
+
+use crossterm::event::{Event, EventStream, KeyEventKind};
+use futures::StreamExt;
+use tokio::{
+    sync::mpsc::{self, error::TryRecvError, UnboundedReceiver, UnboundedSender},
+    task::JoinHandle,
+};
+
+/// Terminal events for the UI loop. Events are tagged with the start of the
+/// reader that read them, those of a stopped reader are dropped.
+pub struct TerminalEvents {
+    receiver: UnboundedReceiver<(u64, Event)>,
+    generation: Arc<AtomicU64>,
+}
+
+/// Starts and stops the task reading terminal events.
+#[derive(Clone)]
+pub struct TerminalReader {
+    sender: UnboundedSender<(u64, Event)>,
+    generation: Arc<AtomicU64>,
+    task: Arc<Mutex<Option<JoinHandle<()>>>>,
+}
+
+/// Starts reading terminal events.
+pub fn terminal_events() -> (TerminalEvents, TerminalReader) {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let generation = Arc::new(AtomicU64::new(0));
+    let reader = TerminalReader {
+        sender,
+        generation: Arc::clone(&generation),
+        task: Arc::new(Mutex::new(None)),
+    };
+    reader.start();
+    (
+        TerminalEvents {
+            receiver,
+            generation,
+        },
+        reader,
+    )
+}
+
+impl TerminalEvents {
+    /// Waits for the next event read since the reader was last started.
+    pub async fn recv(&mut self) -> Option<Event> {
+        loop {
+            let (generation, event) = self.receiver.recv().await?;
+            if generation == self.generation.load(Ordering::SeqCst) {
+                return Some(event);
+            }
+        }
+    }
+
+    /// Next event read since the reader was last started, if one is queued.
+    pub fn try_recv(&mut self) -> Result<Event, TryRecvError> {
+        loop {
+            let (generation, event) = self.receiver.try_recv()?;
+            if generation == self.generation.load(Ordering::SeqCst) {
+                return Ok(event);
+            }
+        }
+    }
+}
+
+impl TerminalReader {
+    pub(crate) fn start(&self) {
+        let task = tokio::spawn(forward_terminal_events(
+            self.sender.clone(),
+            self.generation.load(Ordering::SeqCst),
+        ));
+        if let Some(previous) = self
+            .task
+            .lock()
+            .expect("terminal reader lock")
+            .replace(task)
+        {
+            previous.abort();
+        }
+    }
+
+    /// Stops reading once the reader task is gone, events it read and the UI
+    /// didn't receive yet are discarded.
+    pub(crate) async fn stop(&self) {
+        let task = self.task.lock().expect("terminal reader lock").take();
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        if let Some(task) = task {
+            task.abort();
+            let _ = task.await;
+        }
+    }
+}
+
+/// Forwards the terminal events to the UI loop until it's gone. Only key
+/// presses and repeats are forwarded, terminals reporting releases would
+/// otherwise have every key handled twice.
+async fn forward_terminal_events(sender: UnboundedSender<(u64, Event)>, generation: u64) {
+    let mut terminal_event_stream = EventStream::new();
+    while let Some(Ok(event)) = terminal_event_stream.next().await {
+        if let Event::Key(key_event) = &event {
+            if key_event.kind == KeyEventKind::Release {
+                continue;
+            }
+        }
+        if sender.send((generation, event)).is_err() {
+            break;
+        }
+    }
+}
@@ -2,13 +2,19 @@
 //! Each module contains definitions of screens and forms related to a
 //! subdomain.
 
+pub(crate) mod activity;
 pub(crate) mod contracts;
 pub(crate) mod documents;
+pub(crate) mod getting_started;
 pub(crate) mod identities;
 pub(crate) mod main;
+pub(crate) mod maintenance;
 pub(crate) mod platform_info;
+pub(crate) mod run_history;
+pub(crate) mod schedules;
 // pub(crate) mod strategies;
 pub(crate) mod strategies;
+pub(crate) mod transition_inspector;
 pub mod usernames;
 pub(crate) mod wallet;
 pub mod withdrawals;
@@ -0,0 +1,242 @@
+//! Session timer and the tasks executed in the session, running ones can be
+//! cancelled. Running strategy runs show their progress.
+
+use std::time::Duration;
+
+use tuirealm::{
+    event::{Key, KeyEvent, KeyModifiers},
+    props::{Color, TextSpan},
+    tui::prelude::{Constraint, Direction, Layout, Rect},
+    Frame,
+};
+
+use crate::{
+    backend::{
+        activity_log::{ActivityEntry, ActivityLog, ActivityOutcome},
+        strategy_runs::StrategyRuns,
+        AppState,
+    },
+    ui::screen::{
+        utils::impl_builder,
+        widgets::{info::Info, virtual_list::VirtualList},
+        ScreenCommandKey, ScreenController, ScreenFeedback, ScreenToggleKey,
+    },
+    Event,
+};
+
+const COMMAND_KEYS: [ScreenCommandKey; 4] = [
+    ScreenCommandKey::new("q", "Back to Main"),
+    ScreenCommandKey::new("C-n", "Next task"),
+    ScreenCommandKey::new("C-p", "Prev task"),
+    ScreenCommandKey::new("c", "Cancel the selected task"),
+];
+
+pub(crate) struct ActivityScreenController {
+    activity_log: ActivityLog,
+    strategy_runs: StrategyRuns,
+    entries: Vec<ActivityEntry>,
+    task_select: VirtualList,
+    task_view: Info,
+}
+
+impl_builder!(ActivityScreenController);
+
+impl ActivityScreenController {
+    pub(crate) async fn new(app_state: &AppState) -> Self {
+        let mut controller = ActivityScreenController {
+            activity_log: app_state.activity_log().clone(),
+            strategy_runs: app_state.strategy_runs().clone(),
+            entries: Vec::new(),
+            task_select: VirtualList::new(Vec::new(), 0),
+            task_view: Info::new_fixed(""),
+        };
+        // Starts on the latest task
+        controller.reload();
+        controller.update_view();
+        controller
+    }
+
+    /// Picks up tasks recorded meanwhile and the session time. The selection
+    /// stays on the same task, or follows the latest one if it was on it.
+    /// Returns whether the selected task changed.
+    fn reload(&mut self) -> bool {
+        let entries = self.activity_log.entries();
+        let previous = self.entries.get(self.task_select.selected());
+        let selected = match previous {
+            Some(entry) if entry.number != self.entries.last().map_or(0, |last| last.number) => {
+                entries
+                    .iter()
+                    .position(|e| e.number == entry.number)
+                    .unwrap_or_default()
+            }
+            _ => entries.len().saturating_sub(1),
+        };
+        let changed = previous != entries.get(selected);
+        self.entries = entries;
+
+        let failed = self
+            .entries
+            .iter()
+            .filter(|entry| matches!(entry.outcome, ActivityOutcome::Failed(_)))
+            .count();
+        let running = self
+            .entries
+            .iter()
+            .filter(|entry| entry.outcome == ActivityOutcome::Running)
+            .count();
+        let (started_at, elapsed) = self.activity_log.session_time();
+
+        self.task_select = VirtualList::new(task_rows(&self.entries), selected);
+        self.task_select.set_title(Some(format!(
+            "Session since {}, {}: {} tasks, {} running, {} failed",
+            started_at.format("%H:%M:%S"),
+            display_duration(elapsed),
+            self.entries.len(),
+            running,
+            failed
+        )));
+        changed
+    }
+
+    fn update_view(&mut self) {
+        let Some(entry) = self.entries.get(self.task_select.selected()) else {
+            self.task_view = Info::new_fixed("No tasks executed yet");
+            return;
+        };
+        let duration = entry
+            .duration
+            .map(|duration| format!("{:.2?}", duration))
+            .unwrap_or_else(|| "-".to_owned());
+        let mut text = format!(
+            "Task: {}\nStarted: {}\nDuration: {}\nOutcome: {}",
+            entry.task,
+            entry.started_at.format("%Y-%m-%d %H:%M:%S%.3f %:z"),
+            duration,
+            entry.outcome
+        );
+        if entry.outcome == ActivityOutcome::Running {
+            for progress in entry
+                .strategies
+                .iter()
+                .filter_map(|strategy_name| self.strategy_runs.progress(strategy_name))
+            {
+                text.push_str(&format!("\n\n{}", progress));
+            }
+        }
+        self.task_view = match &entry.outcome {
+            ActivityOutcome::Failed(error) => Info::new_error(&format!("{}\n\n{}", text, error)),
+            _ => Info::new_scrollable(&text),
+        };
+    }
+}
+
+impl ScreenController for ActivityScreenController {
+    fn name(&self) -> &'static str {
+        "Activity"
+    }
+
+    fn command_keys(&self) -> &[ScreenCommandKey] {
+        COMMAND_KEYS.as_ref()
+    }
+
+    fn toggle_keys(&self) -> &[ScreenToggleKey] {
+        &[]
+    }
+
+    fn on_event(&mut self, event: &Event) -> ScreenFeedback {
+        match event {
+            Event::Key(KeyEvent {
+                code: Key::Char('q'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::PreviousScreen,
+
+            Event::Key(KeyEvent {
+                code: Key::Char('c'),
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                let Some(entry) = self.entries.get(self.task_select.selected()) else {
+                    return ScreenFeedback::None;
+                };
+                if self.activity_log.cancel(entry.number) {
+                    self.reload();
+                    self.update_view();
+                } else {
+                    self.task_view = Info::new_error("The task is not running anymore");
+                }
+                ScreenFeedback::Redraw
+            }
+
+            Event::Key(k) => {
+                if self.task_select.on_event(k) {
+                    self.update_view();
+                    ScreenFeedback::Redraw
+                } else if self.task_view.on_event(k) {
+                    ScreenFeedback::Redraw
+                } else {
+                    ScreenFeedback::None
+                }
+            }
+
+            // Keeps the session time ticking and shows tasks as they finish, and
+            // the progress of a selected strategy run
+            Event::Tick { .. } => {
+                let changed = self.reload();
+                let following_run =
+                    self.entries
+                        .get(self.task_select.selected())
+                        .is_some_and(|entry| {
+                            entry.outcome == ActivityOutcome::Running
+                                && !entry.strategies.is_empty()
+                        });
+                if changed || following_run {
+                    self.update_view();
+                }
+                ScreenFeedback::Redraw
+            }
+
+            _ => ScreenFeedback::None,
+        }
+    }
+
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+            .split(area);
+
+        self.task_select.view(frame, layout[0]);
+        self.task_view.view(frame, layout[1]);
+    }
+}
+
+fn task_rows(entries: &[ActivityEntry]) -> Vec<Vec<TextSpan>> {
+    entries
+        .iter()
+        .map(|entry| {
+            let color = match entry.outcome {
+                ActivityOutcome::Running => Color::Yellow,
+                ActivityOutcome::Succeeded => Color::Reset,
+                ActivityOutcome::Cancelled => Color::Gray,
+                ActivityOutcome::Failed(_) => Color::Red,
+            };
+            vec![TextSpan::new(format!(
+                "#{} {} {} {}",
+                entry.number,
+                entry.started_at.format("%H:%M:%S"),
+                entry.task,
+                entry.outcome
+            ))
+            .fg(color)]
+        })
+        .collect()
+}
+
+fn display_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
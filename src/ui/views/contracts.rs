@@ -15,7 +15,7 @@ use dpp::{
 };
 use tuirealm::{
     event::{Key, KeyEvent, KeyModifiers},
-    tui::prelude::Rect,
+    tui::prelude::{Constraint, Direction, Layout, Rect},
     Frame,
 };
 
@@ -26,7 +26,10 @@ use self::{
 use crate::{
     backend::{AppState, AppStateUpdate, BackendEvent, ContractTask, Task},
     ui::{
-        form::{FormController, FormStatus, Input, InputStatus, SelectInput},
+        form::{
+            parsers::DefaultTextInputParser, ComposedInput, Field, FormController, FormStatus,
+            Input, InputStatus, SelectInput, TextInput,
+        },
         screen::{
             utils::impl_builder, widgets::info::Info, ScreenCommandKey, ScreenController,
             ScreenFeedback, ScreenToggleKey,
@@ -35,7 +38,7 @@ use crate::{
     Event,
 };
 
-const COMMAND_KEYS: [ScreenCommandKey; 8] = [
+const COMMAND_KEYS: [ScreenCommandKey; 13] = [
     ScreenCommandKey::new("q", "Back to Main"),
     ScreenCommandKey::new("s", "Fetch system contract"),
     ScreenCommandKey::new("f", "Fetch contract"),
@@ -44,8 +47,36 @@ const COMMAND_KEYS: [ScreenCommandKey; 8] = [
     ScreenCommandKey::new("Enter", "Select contract"),
     ScreenCommandKey::new("r", "Remove a contract"),
     ScreenCommandKey::new("c", "Clear known contracts"),
+    ScreenCommandKey::new("e", "Edit contract JSON draft"),
+    ScreenCommandKey::new("g", "Register contract draft"),
+    ScreenCommandKey::new("d", "Diff against platform"),
+    ScreenCommandKey::new("C-r", "Refresh known contracts"),
+    ScreenCommandKey::new("x", "Delete my documents"),
 ];
 
+/// Starting point for a new contract draft.
+const CONTRACT_TEMPLATE: &str = r#"{
+    "$format_version": "0",
+    "id": "11111111111111111111111111111111",
+    "ownerId": "11111111111111111111111111111111",
+    "version": 1,
+    "documentSchemas": {
+        "note": {
+            "type": "object",
+            "properties": {
+                "message": {
+                    "type": "string",
+                    "maxLength": 256,
+                    "position": 0
+                }
+            },
+            "required": ["message"],
+            "additionalProperties": false
+        }
+    }
+}
+"#;
+
 /// Data contract name (identifier in app state) wrapper for better display
 #[derive(Clone)]
 struct DataContractEntry {
@@ -79,24 +110,27 @@ impl Display for DataContractEntry {
 pub(crate) struct ContractsScreenController {
     select: Option<SelectInput<DataContractEntry>>,
     known_contracts: BTreeMap<String, DataContract>,
+    contract_draft: Option<String>,
+    draft_info: Option<Info>,
 }
 
 impl_builder!(ContractsScreenController);
 
 impl ContractsScreenController {
     pub(crate) async fn new(app_state: &AppState) -> Self {
-        let known_contracts_lock = app_state.known_contracts.lock().await;
-        let select = if known_contracts_lock.len() > 0 {
+        let known_contracts = app_state.snapshot().known_contracts.clone();
+        let select = if known_contracts.len() > 0 {
             Some(SelectInput::new(Self::contract_entries_vec(
-                known_contracts_lock.iter().map(|(k, v)| (k.clone(), v)),
+                known_contracts.iter().map(|(k, v)| (k.clone(), v)),
             )))
         } else {
             None
         };
-        let known_contracts = known_contracts_lock.clone();
         ContractsScreenController {
             select,
             known_contracts,
+            contract_draft: None,
+            draft_info: None,
         }
     }
 
@@ -112,6 +146,17 @@ impl ContractsScreenController {
 
 impl ScreenController for ContractsScreenController {
     fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let area = if let Some(draft_info) = &mut self.draft_info {
+            let layout = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+                .split(area);
+            draft_info.view(frame, layout[1]);
+            layout[0]
+        } else {
+            area
+        };
+
         if let Some(select) = &mut self.select {
             select.view(frame, area)
         } else {
@@ -161,11 +206,93 @@ impl ScreenController for ContractsScreenController {
                 ScreenFeedback::Form(Box::new(RemoveContractFormController::new(contract_names)))
             }
 
+            Event::Key(KeyEvent {
+                code: Key::Char('r'),
+                modifiers: KeyModifiers::CONTROL,
+            }) if !self.known_contracts.is_empty() => ScreenFeedback::Task {
+                task: Task::Contract(ContractTask::RefreshKnownContracts),
+                block: true,
+            },
+
+            Event::Key(KeyEvent {
+                code: Key::Char('d'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::Form(Box::new(DiffContractFormController::new(
+                self.known_contracts.keys().cloned().collect(),
+            ))),
+
             Event::Key(KeyEvent {
                 code: Key::Char('c'),
                 modifiers: KeyModifiers::NONE,
             }) => ScreenFeedback::Form(Box::new(ConfirmClearKnownContractsFormController::new())),
 
+            Event::Key(KeyEvent {
+                code: Key::Char('x'),
+                modifiers: KeyModifiers::NONE,
+            }) if !self.known_contracts.is_empty() => {
+                ScreenFeedback::Form(Box::new(DeleteOwnDocumentsFormController::new(
+                    self.known_contracts.keys().cloned().collect(),
+                )))
+            }
+
+            Event::Key(KeyEvent {
+                code: Key::Char('e'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::EditText {
+                text: self
+                    .contract_draft
+                    .clone()
+                    .unwrap_or_else(|| CONTRACT_TEMPLATE.to_owned()),
+                extension: "json",
+            },
+
+            Event::Key(KeyEvent {
+                code: Key::Char('g'),
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                if let Some(draft) = &self.contract_draft {
+                    ScreenFeedback::Task {
+                        task: Task::Contract(ContractTask::RegisterContractJson(draft.clone())),
+                        block: true,
+                    }
+                } else {
+                    self.draft_info = Some(Info::new_error(
+                        "No contract draft, press e to write one first",
+                    ));
+                    ScreenFeedback::Redraw
+                }
+            }
+
+            Event::Backend(BackendEvent::TaskCompleted {
+                task:
+                    Task::Contract(
+                        ContractTask::ValidateContractJson(_)
+                        | ContractTask::RegisterContractJson(_)
+                        | ContractTask::DiffContract(_)
+                        | ContractTask::DeleteOwnDocuments { .. },
+                    ),
+                execution_result,
+            }) => {
+                self.draft_info = Some(Info::new_from_result(execution_result));
+                ScreenFeedback::Redraw
+            }
+
+            Event::Backend(BackendEvent::TaskCompletedStateChange {
+                task:
+                    Task::Contract(
+                        ContractTask::RegisterContractJson(_) | ContractTask::RefreshKnownContracts,
+                    ),
+                execution_result,
+                app_state_update: AppStateUpdate::KnownContracts(known_contracts),
+            }) => {
+                self.select = Some(SelectInput::new(Self::contract_entries_vec(
+                    known_contracts.iter().map(|(k, v)| (k.clone(), v)),
+                )));
+                self.known_contracts = (*known_contracts).clone();
+                self.draft_info = Some(Info::new_from_result(execution_result));
+                ScreenFeedback::Redraw
+            }
+
             Event::Key(event) => {
                 if let Some(select) = &mut self.select {
                     match select.on_event(*event) {
@@ -214,6 +341,22 @@ impl ScreenController for ContractsScreenController {
             _ => ScreenFeedback::None,
         }
     }
+
+    fn on_text_edited(&mut self, result: Result<String, String>) -> ScreenFeedback {
+        match result {
+            Ok(draft) => {
+                self.contract_draft = Some(draft.clone());
+                ScreenFeedback::Task {
+                    task: Task::Contract(ContractTask::ValidateContractJson(draft)),
+                    block: true,
+                }
+            }
+            Err(e) => {
+                self.draft_info = Some(Info::new_error(&e));
+                ScreenFeedback::Redraw
+            }
+        }
+    }
 }
 
 pub(super) struct RemoveContractFormController {
@@ -261,6 +404,117 @@ impl FormController for RemoveContractFormController {
     }
 }
 
+pub(super) struct DiffContractFormController {
+    input: SelectInput<String>,
+}
+
+impl DiffContractFormController {
+    pub(super) fn new(contracts: Vec<String>) -> Self {
+        Self {
+            input: SelectInput::new(contracts),
+        }
+    }
+}
+
+impl FormController for DiffContractFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done(contract_name) => FormStatus::Done {
+                task: Task::Contract(ContractTask::DiffContract(contract_name)),
+                block: true,
+            },
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Diff contract against platform"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        "Contract"
+    }
+
+    fn step_index(&self) -> u8 {
+        0
+    }
+
+    fn steps_number(&self) -> u8 {
+        1
+    }
+}
+
+pub(super) struct DeleteOwnDocumentsFormController {
+    input: ComposedInput<(
+        Field<SelectInput<String>>,                    // Contract
+        Field<TextInput<DefaultTextInputParser<f64>>>, // Deletes per second
+        Field<SelectInput<String>>,                    // Confirm
+    )>,
+}
+
+impl DeleteOwnDocumentsFormController {
+    pub(super) fn new(contracts: Vec<String>) -> Self {
+        Self {
+            input: ComposedInput::new((
+                Field::new("Contract", SelectInput::new(contracts)),
+                Field::new(
+                    "Deletes per second (0 for no limit)",
+                    TextInput::new_init_value("Enter a number (decimals ok)", 1.0),
+                ),
+                Field::new(
+                    "Delete every document of the contract owned by the loaded identity?",
+                    SelectInput::new(vec!["No".to_string(), "Yes".to_string()]),
+                ),
+            )),
+        }
+    }
+}
+
+impl FormController for DeleteOwnDocumentsFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done((contract_name, rate, confirm)) => {
+                if confirm == "Yes" {
+                    FormStatus::Done {
+                        task: Task::Contract(ContractTask::DeleteOwnDocuments {
+                            contract_name,
+                            rate,
+                        }),
+                        block: true,
+                    }
+                } else {
+                    FormStatus::Exit
+                }
+            }
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Delete my documents"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        self.input.step_name()
+    }
+
+    fn step_index(&self) -> u8 {
+        self.input.step_index()
+    }
+
+    fn steps_number(&self) -> u8 {
+        self.input.steps_number()
+    }
+}
+
 pub(super) struct ConfirmClearKnownContractsFormController {
     input: SelectInput<String>,
 }
@@ -1,6 +1,7 @@
 //! UI definitions for selected data contract.
 
 mod broadcast_document;
+mod broadcast_document_json;
 mod broadcast_random_documents;
 pub mod contested_resources;
 
@@ -9,6 +10,7 @@ use dpp::{
         accessors::v0::DataContractV0Getters,
         document_type::{accessors::DocumentTypeV0Getters, DocumentType},
     },
+    data_contracts::{dashpay_contract, dpns_contract, withdrawals_contract},
     identifier::Identifier,
     identity::accessors::IdentityGettersV0,
     platform_value::string_encoding::Encoding,
@@ -23,6 +25,7 @@ use tuirealm::{
 
 use self::{
     // broadcast_document::BroadcastSpecificDocumentForm,
+    broadcast_document_json::BroadcastDocumentFromJsonForm,
     broadcast_random_documents::BroadcastRandomDocumentsCountForm,
 };
 use crate::{
@@ -70,9 +73,8 @@ impl FormController for SelectDocumentTypeFormController {
                         Box::new(
                             DocumentTypeScreenController::new(
                                 app_state
+                                    .snapshot()
                                     .loaded_identity
-                                    .lock()
-                                    .await
                                     .as_ref()
                                     .map(|identity| identity.id()),
                                 contract_name,
@@ -110,18 +112,21 @@ impl FormController for SelectDocumentTypeFormController {
     }
 }
 
-const LOADED_IDENTITY_COMMANDS: [ScreenCommandKey; 5] = [
+const LOADED_IDENTITY_COMMANDS: [ScreenCommandKey; 7] = [
     ScreenCommandKey::new("q", "Back to Contracts"),
     ScreenCommandKey::new("f", "Query"),
     ScreenCommandKey::new("o", "Query ours"),
+    ScreenCommandKey::new("s", "Field statistics"),
     ScreenCommandKey::new("c", "Query Contested Resources"),
     ScreenCommandKey::new("r", "Broadcast Random Documents"),
+    ScreenCommandKey::new("j", "Broadcast Document from JSON"),
     // ScreenCommandKey::new("b", "Broadcast Document"),
 ];
 
-const NO_LOADED_IDENTITY_COMMANDS: [ScreenCommandKey; 4] = [
+const NO_LOADED_IDENTITY_COMMANDS: [ScreenCommandKey; 5] = [
     ScreenCommandKey::new("q", "Back to Contracts"),
     ScreenCommandKey::new("f", "Query"),
+    ScreenCommandKey::new("s", "Field statistics"),
     ScreenCommandKey::new("c", "Query Contested Resources"),
     ScreenCommandKey::new("r", "Broadcast Random Documents"),
     // ScreenCommandKey::new("b", "Broadcast Document"),
@@ -143,8 +148,9 @@ impl DocumentTypeScreenController {
         document_type_name: String,
         app_state: &AppState,
     ) -> Self {
-        let known_contracts_lock = app_state.known_contracts.lock().await;
-        let data_contract = known_contracts_lock
+        let data_contract = app_state
+            .snapshot()
+            .known_contracts
             .get(&data_contract_name)
             .expect("expected a contract")
             .clone();
@@ -212,6 +218,16 @@ impl ScreenController for DocumentTypeScreenController {
                 self.document_type_name.clone(),
             ))),
 
+            Event::Key(KeyEvent {
+                code: Key::Char('j'),
+                modifiers: KeyModifiers::NONE,
+            }) if self.identity_identifier.is_some() => {
+                ScreenFeedback::Form(Box::new(BroadcastDocumentFromJsonForm::new(
+                    self.data_contract_name.clone(),
+                    self.document_type_name.clone(),
+                )))
+            }
+
             // Event::Key(KeyEvent {
             //     code: Key::Char('b'),
             //     modifiers: KeyModifiers::NONE,
@@ -234,6 +250,17 @@ impl ScreenController for DocumentTypeScreenController {
                 true,
             ))),
 
+            Event::Key(KeyEvent {
+                code: Key::Char('s'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::Task {
+                task: Task::Document(DocumentTask::DocumentTypeStats {
+                    data_contract: self.data_contract.clone(),
+                    document_type: self.document_type.clone(),
+                }),
+                block: true,
+            },
+
             Event::Key(KeyEvent {
                 code: Key::Char('c'),
                 modifiers: KeyModifiers::NONE,
@@ -264,9 +291,10 @@ impl ScreenController for DocumentTypeScreenController {
 
             // Backend events handling
             Event::Backend(BackendEvent::TaskCompleted {
-                task: Task::Document(DocumentTask::QueryDocuments(_)),
+                task: Task::Document(DocumentTask::QueryDocuments(query)),
                 execution_result: Ok(CompletedTaskPayload::Documents(documents)),
             }) => {
+                let query = query.clone();
                 let data_contract = self.data_contract.clone();
                 let document_type = self.document_type.clone();
                 let identity_id = self.identity_identifier.clone();
@@ -277,6 +305,7 @@ impl ScreenController for DocumentTypeScreenController {
                             data_contract,
                             document_type,
                             identity_id,
+                            query,
                             documents,
                         )) as Box<dyn ScreenController>
                     }
@@ -292,6 +321,18 @@ impl ScreenController for DocumentTypeScreenController {
                 ScreenFeedback::Redraw
             }
 
+            Event::Backend(BackendEvent::TaskCompleted {
+                task:
+                    Task::Document(
+                        DocumentTask::BroadcastDocumentFromJson { .. }
+                        | DocumentTask::DocumentTypeStats { .. },
+                    ),
+                execution_result,
+            }) => {
+                self.info = Info::new_from_result(execution_result);
+                ScreenFeedback::Redraw
+            }
+
             Event::Backend(
                 BackendEvent::TaskCompleted {
                     task: Task::Document(DocumentTask::BroadcastRandomDocuments { .. }),
@@ -331,10 +372,39 @@ impl ScreenController for DocumentTypeScreenController {
     }
 }
 
+/// Prebuilt queries for common lookups in system contracts, the quoted
+/// placeholder is to be replaced with the value looked up.
+fn query_templates(contract_id: Identifier, document_type_name: &str) -> &'static [&'static str] {
+    match document_type_name {
+        "domain" if contract_id == dpns_contract::ID => &[
+            "Select * from domain where normalizedParentDomainName = 'dash' and normalizedLabel \
+             = '<normalized label>'",
+            "Select * from domain where normalizedParentDomainName = 'dash' and normalizedLabel \
+             like '<label prefix>%' order by normalizedLabel asc",
+        ],
+        "contactRequest" if contract_id == dashpay_contract::ID => &[
+            "Select * from contactRequest where toUserId = '<identity id>' order by \
+             $createdAt asc",
+            "Select * from contactRequest where $ownerId = '<identity id>' and toUserId = \
+             '<identity id>'",
+        ],
+        "withdrawal" if contract_id == withdrawals_contract::ID => &[
+            "Select * from withdrawal where status = <0 queued, 1 pooled, 2 broadcasted, 3 \
+             complete, 4 expired>",
+            "Select * from withdrawal where $ownerId = '<identity id>' and status = <status> \
+             order by $createdAt asc",
+        ],
+        _ => &[],
+    }
+}
+
 pub struct QueryDocumentTypeFormController {
     document_type: DocumentType,
     identity_id: Option<Identifier>,
     input: TextInput<DocumentQueryTextInputParser>,
+    templates: &'static [&'static str],
+    /// Template shown last, the next key press shows the one after it
+    template_index: Option<usize>,
 }
 
 impl QueryDocumentTypeFormController {
@@ -353,17 +423,37 @@ impl QueryDocumentTypeFormController {
             String::default()
         };
         let query = format!("Select * from {} {}", document_type.name(), ours_query_part);
+        let templates = query_templates(data_contract.id(), document_type.name());
         let parser = DocumentQueryTextInputParser::new(data_contract);
         Self {
             document_type,
             identity_id,
-            input: TextInput::new_str_value_with_parser(parser, "Document Query", &query),
+            input: TextInput::new_str_value_with_parser(parser, "Document Query", &query)
+                .editor_extension("sql"),
+            templates,
+            template_index: None,
         }
     }
 }
 
 impl FormController for QueryDocumentTypeFormController {
     fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        if let KeyEvent {
+            code: Key::Char('l'),
+            modifiers: KeyModifiers::CONTROL,
+        } = event
+        {
+            if self.templates.is_empty() {
+                return FormStatus::None;
+            }
+            let index = self
+                .template_index
+                .map_or(0, |index| (index + 1) % self.templates.len());
+            self.template_index = Some(index);
+            self.input.set_value(self.templates[index]);
+            return FormStatus::Redraw;
+        }
+
         match self.input.on_event(event) {
             InputStatus::Done(query) => FormStatus::Done {
                 task: Task::Document(DocumentTask::QueryDocuments(query)),
@@ -382,7 +472,11 @@ impl FormController for QueryDocumentTypeFormController {
     }
 
     fn step_name(&self) -> &'static str {
-        "Query"
+        if self.templates.is_empty() {
+            "Query"
+        } else {
+            "Query (C-l for the next template)"
+        }
     }
 
     fn step_index(&self) -> u8 {
@@ -0,0 +1,76 @@
+//! Form to broadcast a document from a JSON file.
+
+use tuirealm::{event::KeyEvent, tui::prelude::Rect, Frame};
+
+use crate::{
+    backend::{documents::DocumentTask, Task},
+    ui::form::{
+        parsers::DefaultTextInputParser, ComposedInput, Field, FormController, FormStatus, Input,
+        InputStatus, TextInput,
+    },
+};
+
+pub(super) struct BroadcastDocumentFromJsonForm {
+    input: ComposedInput<(
+        Field<TextInput<DefaultTextInputParser<String>>>, // JSON file
+        Field<TextInput<DefaultTextInputParser<u16>>>,    // User fee increase
+    )>,
+    data_contract_name: String,
+    document_type_name: String,
+}
+
+impl BroadcastDocumentFromJsonForm {
+    pub fn new(data_contract_name: String, document_type_name: String) -> Self {
+        BroadcastDocumentFromJsonForm {
+            input: ComposedInput::new((
+                Field::new(
+                    "JSON file",
+                    TextInput::new("Path to a JSON file with the document's properties"),
+                ),
+                Field::new(
+                    "Fee increase in percent of the base fee (0 for none)",
+                    TextInput::new_init_value("Enter a whole number", 0),
+                ),
+            )),
+            data_contract_name,
+            document_type_name,
+        }
+    }
+}
+
+impl FormController for BroadcastDocumentFromJsonForm {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done((path, user_fee_increase)) => FormStatus::Done {
+                task: Task::Document(DocumentTask::BroadcastDocumentFromJson {
+                    data_contract_name: self.data_contract_name.clone(),
+                    document_type_name: self.document_type_name.clone(),
+                    path,
+                    user_fee_increase,
+                }),
+                block: true,
+            },
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Broadcast document from JSON"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area);
+    }
+
+    fn step_name(&self) -> &'static str {
+        self.input.step_name()
+    }
+
+    fn step_index(&self) -> u8 {
+        self.input.step_index()
+    }
+
+    fn steps_number(&self) -> u8 {
+        self.input.steps_number()
+    }
+}
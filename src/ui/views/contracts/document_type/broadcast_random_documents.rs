@@ -5,12 +5,16 @@ use tuirealm::{event::KeyEvent, tui::prelude::Rect, Frame};
 use crate::{
     backend::{documents::DocumentTask, Task},
     ui::form::{
-        parsers::DefaultTextInputParser, FormController, FormStatus, Input, InputStatus, TextInput,
+        parsers::DefaultTextInputParser, ComposedInput, Field, FormController, FormStatus, Input,
+        InputStatus, TextInput,
     },
 };
 
 pub(super) struct BroadcastRandomDocumentsCountForm {
-    input: TextInput<DefaultTextInputParser<u16>>,
+    input: ComposedInput<(
+        Field<TextInput<DefaultTextInputParser<u16>>>, // Documents count
+        Field<TextInput<DefaultTextInputParser<u16>>>, // User fee increase
+    )>,
     data_contract_name: String,
     document_type_name: String,
 }
@@ -18,7 +22,16 @@ pub(super) struct BroadcastRandomDocumentsCountForm {
 impl BroadcastRandomDocumentsCountForm {
     pub fn new(data_contract_name: String, document_type_name: String) -> Self {
         BroadcastRandomDocumentsCountForm {
-            input: TextInput::new_init_value("Number of random documents", 1),
+            input: ComposedInput::new((
+                Field::new(
+                    "Documents count",
+                    TextInput::new_init_value("Number of random documents", 1),
+                ),
+                Field::new(
+                    "Fee increase in percent of the base fee (0 for none)",
+                    TextInput::new_init_value("Enter a whole number", 0),
+                ),
+            )),
             data_contract_name,
             document_type_name,
         }
@@ -28,11 +41,12 @@ impl BroadcastRandomDocumentsCountForm {
 impl FormController for BroadcastRandomDocumentsCountForm {
     fn on_event(&mut self, event: KeyEvent) -> FormStatus {
         match self.input.on_event(event) {
-            InputStatus::Done(count) => FormStatus::Done {
+            InputStatus::Done((count, user_fee_increase)) => FormStatus::Done {
                 task: Task::Document(DocumentTask::BroadcastRandomDocuments {
                     data_contract_name: self.data_contract_name.clone(),
                     document_type_name: self.document_type_name.clone(),
                     count,
+                    user_fee_increase,
                 }),
                 block: true,
             },
@@ -49,14 +63,14 @@ impl FormController for BroadcastRandomDocumentsCountForm {
     }
 
     fn step_name(&self) -> &'static str {
-        "Documents count"
+        self.input.step_name()
     }
 
     fn step_index(&self) -> u8 {
-        0
+        self.input.step_index()
     }
 
     fn steps_number(&self) -> u8 {
-        1
+        self.input.steps_number()
     }
 }
@@ -1,69 +1,92 @@
 //! View for fetched documents navigation and inspection.
-use dash_sdk::query_types::IndexMap;
+use std::time::{Duration, Instant};
+
+use dash_sdk::{platform::DocumentQuery, query_types::IndexMap};
 use dpp::{
-    data_contract::{document_type::DocumentType, DataContract},
+    data_contract::{
+        document_type::{accessors::DocumentTypeV0Getters, DocumentType},
+        DataContract,
+    },
     document::{Document, DocumentV0Getters},
     fee::Credits,
     platform_value::{btreemap_extensions::BTreeValueMapHelper, string_encoding::Encoding},
     prelude::Identifier,
 };
 use tuirealm::{
-    command::{self, Cmd},
     event::{Key, KeyEvent, KeyModifiers},
-    props::{BorderSides, Borders, Color, TextSpan},
+    props::{Color, TextSpan},
     tui::prelude::{Constraint, Direction, Layout, Rect},
-    AttrValue, Attribute, Frame, MockComponent,
+    Frame,
 };
 
 use crate::{
-    backend::{as_json_string, documents::DocumentTask, BackendEvent, Task},
+    backend::{
+        as_json_string, document_export::DocumentExportFormat, documents::DocumentTask,
+        BackendEvent, CompletedTaskPayload, Task,
+    },
     ui::{
         form::{
-            parsers::DefaultTextInputParser, FormController, FormStatus, Input, InputStatus,
-            SelectInput, TextInput,
+            parsers::{CreditsTextInputParser, DefaultTextInputParser},
+            ComposedInput, Field, FormController, FormStatus, Input, InputStatus, SelectInput,
+            TextInput,
         },
         screen::{
-            widgets::info::Info, ScreenCommandKey, ScreenController, ScreenFeedback,
-            ScreenToggleKey,
+            widgets::{info::Info, virtual_list::VirtualList},
+            ScreenCommandKey, ScreenController, ScreenFeedback, ScreenToggleKey,
         },
     },
     Event,
 };
 
-const BASE_COMMAND_KEYS: [ScreenCommandKey; 5] = [
+const BASE_COMMAND_KEYS: [ScreenCommandKey; 7] = [
     ScreenCommandKey::new("q", "Back to Contracts"),
     ScreenCommandKey::new("C-n", "Next document"),
     ScreenCommandKey::new("C-p", "Prev document"),
+    ScreenCommandKey::new("PgDn / PgUp", "Page documents"),
     ScreenCommandKey::new("↓", "Scroll doc down"),
     ScreenCommandKey::new("↑", "Scroll doc up"),
+    ScreenCommandKey::new("x", "Export results"),
 ];
 
-const PURCHASE_COMMAND_KEYS: [ScreenCommandKey; 6] = [
+const PURCHASE_COMMAND_KEYS: [ScreenCommandKey; 8] = [
     ScreenCommandKey::new("q", "Back to Contracts"),
     ScreenCommandKey::new("C-n", "Next document"),
     ScreenCommandKey::new("C-p", "Prev document"),
+    ScreenCommandKey::new("PgDn / PgUp", "Page documents"),
     ScreenCommandKey::new("↓", "Scroll doc down"),
     ScreenCommandKey::new("↑", "Scroll doc up"),
+    ScreenCommandKey::new("x", "Export results"),
     ScreenCommandKey::new("p", "Purchase"),
 ];
 
-const DOCUMENT_OWNED_COMMAND_KEYS: [ScreenCommandKey; 7] = [
+const DOCUMENT_OWNED_COMMAND_KEYS: [ScreenCommandKey; 9] = [
     ScreenCommandKey::new("q", "Back to Contracts"),
     ScreenCommandKey::new("C-n", "Next document"),
     ScreenCommandKey::new("C-p", "Prev document"),
+    ScreenCommandKey::new("PgDn / PgUp", "Page documents"),
     ScreenCommandKey::new("↓", "Scroll doc down"),
     ScreenCommandKey::new("↑", "Scroll doc up"),
+    ScreenCommandKey::new("x", "Export results"),
     ScreenCommandKey::new("s", "Set price"),
     ScreenCommandKey::new("t", "Transfer"),
 ];
 
+/// How often the query is re-run in watch mode
+const WATCH_INTERVAL: Duration = Duration::from_secs(5);
+
 pub(crate) struct DocumentsQuerysetScreenController {
     data_contract: DataContract,
     document_type: DocumentType,
     identity_id: Option<Identifier>,
+    query: DocumentQuery,
+    /// Documents as returned by the last query, to compare refreshes with
+    last_result: IndexMap<Identifier, Option<Document>>,
+    /// Displayed documents, the last result plus the ones it no longer has
     current_batch: Vec<Option<Document>>,
-    document_select: tui_realm_stdlib::List,
+    document_select: VirtualList,
     document_view: Info,
+    toggle_keys: [ScreenToggleKey; 1],
+    last_refresh: Instant,
 }
 
 impl DocumentsQuerysetScreenController {
@@ -71,23 +94,16 @@ impl DocumentsQuerysetScreenController {
         data_contract: DataContract,
         document_type: DocumentType,
         identity_id: Option<Identifier>,
+        query: DocumentQuery,
         current_batch: IndexMap<Identifier, Option<Document>>,
     ) -> Self {
-        let mut document_select = tui_realm_stdlib::List::default()
-            .rows(
-                current_batch
-                    .keys()
-                    .map(|v| vec![TextSpan::new(v.to_string(Encoding::Base58))])
-                    .collect(),
-            )
-            .borders(
-                Borders::default()
-                    .sides(BorderSides::LEFT | BorderSides::TOP | BorderSides::BOTTOM),
-            )
-            .selected_line(0)
-            .highlighted_color(Color::Magenta);
-        document_select.attr(Attribute::Scroll, AttrValue::Flag(true));
-        document_select.attr(Attribute::Focus, AttrValue::Flag(true));
+        let document_select = VirtualList::new(
+            current_batch
+                .keys()
+                .map(|v| vec![TextSpan::new(v.to_string(Encoding::Base58))])
+                .collect(),
+            0,
+        );
 
         let document_view = Info::new_scrollable(
             &current_batch
@@ -100,25 +116,89 @@ impl DocumentsQuerysetScreenController {
             data_contract,
             document_type,
             identity_id,
-            current_batch: current_batch.into_values().collect(),
+            query,
+            current_batch: current_batch.values().cloned().collect(),
+            last_result: current_batch,
             document_select,
             document_view,
+            toggle_keys: [ScreenToggleKey::new("w", "watch")],
+            last_refresh: Instant::now(),
+        }
+    }
+
+    fn watching(&self) -> bool {
+        self.toggle_keys[0].toggle
+    }
+
+    fn refresh(&mut self) -> ScreenFeedback {
+        self.last_refresh = Instant::now();
+        ScreenFeedback::Task {
+            task: Task::Document(DocumentTask::QueryDocuments(self.query.clone())),
+            block: false,
         }
     }
 
+    /// Shows a refreshed result, marking documents that are new (+), changed
+    /// (~) or no longer returned (-) since the previous one.
+    fn apply_refresh(&mut self, result: IndexMap<Identifier, Option<Document>>) {
+        let mut rows = Vec::new();
+        let mut current_batch = Vec::new();
+        let (mut new, mut changed, mut removed) = (0, 0, 0);
+
+        for (id, document) in result.iter() {
+            let (marker, color) = match self.last_result.get(id) {
+                None => {
+                    new += 1;
+                    ("+", Color::Green)
+                }
+                Some(previous) if previous != document => {
+                    changed += 1;
+                    ("~", Color::Yellow)
+                }
+                Some(_) => (" ", Color::Reset),
+            };
+            rows.push(vec![TextSpan::new(format!(
+                "{} {}",
+                marker,
+                id.to_string(Encoding::Base58)
+            ))
+            .fg(color)]);
+            current_batch.push(document.clone());
+        }
+        for (id, document) in self.last_result.iter() {
+            if !result.contains_key(id) {
+                removed += 1;
+                rows.push(vec![TextSpan::new(format!(
+                    "- {}",
+                    id.to_string(Encoding::Base58)
+                ))
+                .fg(Color::Red)]);
+                current_batch.push(document.clone());
+            }
+        }
+
+        self.document_select = VirtualList::new(rows, self.document_select.selected());
+        self.document_select.set_title(Some(format!(
+            "Watching: {} new, {} changed, {} removed",
+            new, changed, removed
+        )));
+        self.current_batch = current_batch;
+        self.last_result = result;
+        self.update_document_view();
+    }
+
     fn update_document_view(&mut self) {
         self.document_view = Info::new_scrollable(
             &self
                 .current_batch
-                .get(self.document_select.state().unwrap_one().unwrap_usize())
+                .get(self.document_select.selected())
                 .map(|v| as_json_string(&v))
                 .unwrap_or_else(String::new),
         );
     }
 
     fn get_selected_document(&self) -> Option<&Document> {
-        let state = self.document_select.state();
-        let selected_index = state.unwrap_one().unwrap_usize();
+        let selected_index = self.document_select.selected();
         self.current_batch
             .get(selected_index)
             .and_then(|doc| doc.as_ref())
@@ -175,7 +255,7 @@ impl ScreenController for DocumentsQuerysetScreenController {
 
     fn command_keys(&self) -> &[ScreenCommandKey] {
         // Only display purchase option if the document is purchasable
-        let idx = self.document_select.state().unwrap_one().unwrap_usize();
+        let idx = self.document_select.selected();
         let purchasable = self.document_is_purchasable(idx);
         if purchasable {
             PURCHASE_COMMAND_KEYS.as_ref()
@@ -187,7 +267,7 @@ impl ScreenController for DocumentsQuerysetScreenController {
     }
 
     fn toggle_keys(&self) -> &[ScreenToggleKey] {
-        &[]
+        self.toggle_keys.as_ref()
     }
 
     fn on_event(&mut self, event: &Event) -> ScreenFeedback {
@@ -196,11 +276,35 @@ impl ScreenController for DocumentsQuerysetScreenController {
                 code: Key::Char('q'),
                 modifiers: KeyModifiers::NONE,
             }) => ScreenFeedback::PreviousScreen,
+            Event::Key(KeyEvent {
+                code: Key::Char('w'),
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                self.toggle_keys[0].toggle = !self.toggle_keys[0].toggle;
+                if self.watching() {
+                    self.refresh()
+                } else {
+                    self.document_select.set_title(None);
+                    ScreenFeedback::Redraw
+                }
+            }
+            Event::Key(KeyEvent {
+                code: Key::Char('x'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::Form(Box::new(ExportDocumentsFormController::new(
+                self.document_type.name().to_owned(),
+                self.last_result.values().flatten().cloned().collect(),
+            ))),
+            Event::Tick { backend_idle: true }
+                if self.watching() && self.last_refresh.elapsed() >= WATCH_INTERVAL =>
+            {
+                self.refresh()
+            }
             Event::Key(KeyEvent {
                 code: Key::Char('p'),
                 modifiers: KeyModifiers::NONE,
             }) => {
-                let idx = self.document_select.state().unwrap_one().unwrap_usize();
+                let idx = self.document_select.selected();
                 if self.document_is_purchasable(idx) {
                     if let Some(Some(doc)) = self.current_batch.get(idx) {
                         ScreenFeedback::Form(Box::new(ConfirmDocumentPurchaseFormController::new(
@@ -219,7 +323,7 @@ impl ScreenController for DocumentsQuerysetScreenController {
                 code: Key::Char('s'),
                 modifiers: KeyModifiers::NONE,
             }) => {
-                let idx = self.document_select.state().unwrap_one().unwrap_usize();
+                let idx = self.document_select.selected();
                 if self.document_is_ours(idx) {
                     if let Some(Some(doc)) = self.current_batch.get(idx) {
                         ScreenFeedback::Form(Box::new(SetDocumentPriceFormController::new(
@@ -238,7 +342,7 @@ impl ScreenController for DocumentsQuerysetScreenController {
                 code: Key::Char('t'),
                 modifiers: KeyModifiers::NONE,
             }) => {
-                let idx = self.document_select.state().unwrap_one().unwrap_usize();
+                let idx = self.document_select.selected();
                 if self.document_is_ours(idx) {
                     if let Some(Some(doc)) = self.current_batch.get(idx) {
                         ScreenFeedback::Form(Box::new(TransferDocumentFormController::new(
@@ -266,26 +370,32 @@ impl ScreenController for DocumentsQuerysetScreenController {
             }
 
             // Document selection keys
-            Event::Key(KeyEvent {
-                code: Key::Char('n'),
-                modifiers: KeyModifiers::CONTROL,
-            }) => {
-                self.document_select
-                    .perform(Cmd::Move(command::Direction::Down));
+            Event::Key(key_event) if self.document_select.on_event(key_event) => {
                 self.update_document_view();
                 ScreenFeedback::Redraw
             }
-            Event::Key(KeyEvent {
-                code: Key::Char('p'),
-                modifiers: KeyModifiers::CONTROL,
+
+            // Backend events handling
+            Event::Backend(BackendEvent::TaskCompleted {
+                task: Task::Document(DocumentTask::QueryDocuments(_)),
+                execution_result,
+            }) if self.watching() => {
+                match execution_result {
+                    Ok(CompletedTaskPayload::Documents(documents)) => {
+                        self.apply_refresh(documents.clone())
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Failed to refresh watched documents: {}", e),
+                }
+                ScreenFeedback::Redraw
+            }
+            Event::Backend(BackendEvent::TaskCompleted {
+                task: Task::Document(DocumentTask::ExportDocuments { .. }),
+                execution_result,
             }) => {
-                self.document_select
-                    .perform(Cmd::Move(command::Direction::Up));
-                self.update_document_view();
+                self.document_view = Info::new_from_result(execution_result);
                 ScreenFeedback::Redraw
             }
-
-            // Backend events handling
             Event::Backend(BackendEvent::TaskCompleted {
                 task: Task::Document(DocumentTask::PurchaseDocument { .. }),
                 execution_result,
@@ -313,6 +423,76 @@ impl ScreenController for DocumentsQuerysetScreenController {
     }
 }
 
+struct ExportDocumentsFormController {
+    input: ComposedInput<(
+        Field<SelectInput<DocumentExportFormat>>,
+        Field<TextInput<DefaultTextInputParser<String>>>,
+    )>,
+    document_type_name: String,
+    documents: Vec<Document>,
+}
+
+impl ExportDocumentsFormController {
+    fn new(document_type_name: String, documents: Vec<Document>) -> Self {
+        Self {
+            input: ComposedInput::new((
+                Field::new(
+                    "Format",
+                    SelectInput::new(vec![DocumentExportFormat::Json, DocumentExportFormat::Csv]),
+                ),
+                Field::new(
+                    "Fields, nested ones with dots (records.identity)",
+                    TextInput::new("comma separated, empty for all"),
+                ),
+            )),
+            document_type_name,
+            documents,
+        }
+    }
+}
+
+impl FormController for ExportDocumentsFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done((format, fields)) => FormStatus::Done {
+                task: Task::Document(DocumentTask::ExportDocuments {
+                    document_type_name: self.document_type_name.clone(),
+                    documents: self.documents.clone(),
+                    format,
+                    fields: fields
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|field| !field.is_empty())
+                        .map(str::to_owned)
+                        .collect(),
+                }),
+                block: true,
+            },
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Export query results"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        self.input.step_name()
+    }
+
+    fn step_index(&self) -> u8 {
+        self.input.step_index()
+    }
+
+    fn steps_number(&self) -> u8 {
+        self.input.steps_number()
+    }
+}
+
 pub struct ConfirmDocumentPurchaseFormController {
     confirm_input: SelectInput<String>,
     data_contract: DataContract,
@@ -378,7 +558,7 @@ impl FormController for ConfirmDocumentPurchaseFormController {
 }
 
 pub struct SetDocumentPriceFormController {
-    input: TextInput<DefaultTextInputParser<f64>>,
+    input: TextInput<CreditsTextInputParser>,
     data_contract: DataContract,
     document_type: DocumentType,
     document: Document,
@@ -391,7 +571,7 @@ impl SetDocumentPriceFormController {
         document: Document,
     ) -> Self {
         Self {
-            input: TextInput::new("Amount (in Dash)"),
+            input: TextInput::new_with_parser(CreditsTextInputParser, "Price"),
             data_contract,
             document_type,
             document,
@@ -404,7 +584,7 @@ impl FormController for SetDocumentPriceFormController {
         match self.input.on_event(event) {
             InputStatus::Done(amount) => FormStatus::Done {
                 task: Task::Document(DocumentTask::SetDocumentPrice {
-                    amount: (amount * 100_000_000_000.0) as u64,
+                    amount,
                     data_contract: self.data_contract.clone(),
                     document_type: self.document_type.clone(),
                     document: self.document.clone(),
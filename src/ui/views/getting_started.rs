@@ -0,0 +1,376 @@
+//! Checklist of what a first strategy run needs, in the order of the setup
+//! guide: configuration, Core, DAPI, a funded wallet and a funded identity.
+
+use std::{path::Path, sync::Arc};
+
+use dpp::{fee::Credits, identity::accessors::IdentityGettersV0};
+use tokio::sync::watch;
+use tuirealm::{
+    event::{Key, KeyEvent, KeyModifiers},
+    props::TextSpan,
+    tui::prelude::{Constraint, Direction, Layout, Rect},
+    Frame,
+};
+
+use super::{identities::IdentitiesScreenController, wallet::WalletScreenController};
+use crate::{
+    backend::{
+        connections::ConnectionCheck, snapshot::AppStateSnapshot, AppState, BackendEvent,
+        CompletedTaskPayload, Task,
+    },
+    config::Config,
+    ui::screen::{
+        utils::impl_builder,
+        widgets::{info::Info, virtual_list::VirtualList},
+        ScreenCommandKey, ScreenController, ScreenFeedback, ScreenToggleKey,
+    },
+    units::{self, CREDITS_PER_DASH},
+    Event,
+};
+
+const COMMAND_KEYS: [ScreenCommandKey; 5] = [
+    ScreenCommandKey::new("q", "Back to Main"),
+    ScreenCommandKey::new("Enter", "Go to the screen resolving the selected item"),
+    ScreenCommandKey::new("t", "Test connections again"),
+    ScreenCommandKey::new("C-n", "Next item"),
+    ScreenCommandKey::new("C-p", "Prev item"),
+];
+
+/// UTXOs a wallet needs to fund the asset locks of a small run
+const MIN_WALLET_UTXOS: usize = 10;
+/// Credits an identity needs to register a contract and some documents
+const MIN_IDENTITY_CREDITS: Credits = CREDITS_PER_DASH / 10;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Step {
+    Config,
+    Core,
+    Dapi,
+    Wallet,
+    Identity,
+}
+
+impl Step {
+    const ALL: [Step; 5] = [
+        Step::Config,
+        Step::Core,
+        Step::Dapi,
+        Step::Wallet,
+        Step::Identity,
+    ];
+
+    fn title(&self) -> String {
+        match self {
+            Step::Config => "Configuration present".to_owned(),
+            Step::Core => "Core RPC reachable".to_owned(),
+            Step::Dapi => "DAPI reachable".to_owned(),
+            Step::Wallet => format!("Wallet loaded with at least {} UTXOs", MIN_WALLET_UTXOS),
+            Step::Identity => format!(
+                "Identity loaded with at least {}",
+                units::format_credits(MIN_IDENTITY_CREDITS)
+            ),
+        }
+    }
+}
+
+/// Whether an item is met, `None` while it's being checked, and what to do
+/// about it.
+struct ItemStatus {
+    done: Option<bool>,
+    details: String,
+}
+
+pub(crate) struct GettingStartedScreenController {
+    snapshots: watch::Receiver<Arc<AppStateSnapshot>>,
+    /// Connection checks, `None` until they're done
+    connection_checks: Option<Vec<ConnectionCheck>>,
+    checks_requested: bool,
+    /// What's wrong with the configuration, read once when the screen opens
+    config_error: Option<String>,
+    statuses: Vec<ItemStatus>,
+    item_select: VirtualList,
+    info: Info,
+}
+
+impl_builder!(GettingStartedScreenController);
+
+impl GettingStartedScreenController {
+    pub(crate) async fn new(app_state: &AppState) -> Self {
+        let mut controller = GettingStartedScreenController {
+            snapshots: app_state.subscribe_snapshot(),
+            connection_checks: None,
+            checks_requested: false,
+            config_error: Config::try_load().err(),
+            statuses: Vec::new(),
+            item_select: VirtualList::new(Vec::new(), 0),
+            info: Info::new_fixed(""),
+        };
+        controller.reload(&app_state.snapshot());
+        controller
+    }
+
+    fn reload(&mut self, snapshot: &AppStateSnapshot) {
+        self.statuses = Step::ALL
+            .iter()
+            .map(|step| self.status(*step, snapshot))
+            .collect();
+        let rows = Step::ALL
+            .iter()
+            .zip(&self.statuses)
+            .map(|(step, status)| {
+                let mark = match status.done {
+                    Some(true) => "✓",
+                    Some(false) => "✗",
+                    None => "…",
+                };
+                vec![TextSpan::new(format!("{} {}", mark, step.title()))]
+            })
+            .collect();
+        let selected = self.item_select.selected().min(Step::ALL.len() - 1);
+        self.item_select = VirtualList::new(rows, selected);
+        let met = self
+            .statuses
+            .iter()
+            .filter(|status| status.done == Some(true))
+            .count();
+        self.item_select.set_title(Some(format!(
+            "Getting started: {} of {} done",
+            met,
+            Step::ALL.len()
+        )));
+        self.update_info();
+    }
+
+    fn status(&self, step: Step, snapshot: &AppStateSnapshot) -> ItemStatus {
+        match step {
+            Step::Config => {
+                // Variables may come from the environment rather than from a .env file
+                match &self.config_error {
+                    None => ItemStatus {
+                        done: Some(true),
+                        details: "Configuration loaded".to_owned(),
+                    },
+                    Some(error) if !Path::new(".env").exists() => ItemStatus {
+                        done: Some(false),
+                        details: format!(
+                            "{} — no .env file in the working directory, copy one of \
+                             .env.local, .env.devnet, .env.testnet or .env.mainnet to .env, \
+                             fill it in and restart",
+                            error
+                        ),
+                    },
+                    Some(error) => ItemStatus {
+                        done: Some(false),
+                        details: format!(
+                            "The .env file is invalid: {} — fix it and restart",
+                            error
+                        ),
+                    },
+                }
+            }
+            Step::Core | Step::Dapi => {
+                let group = if step == Step::Core { "Core" } else { "DAPI" };
+                let Some(checks) = &self.connection_checks else {
+                    return ItemStatus {
+                        done: None,
+                        details: "Testing connections...".to_owned(),
+                    };
+                };
+                let checks: Vec<_> = checks.iter().filter(|check| check.group == group).collect();
+                ItemStatus {
+                    done: Some(!checks.is_empty() && checks.iter().all(|check| check.ok)),
+                    details: checks
+                        .iter()
+                        .map(|check| format!("{}: {}", check.service, check.message))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                }
+            }
+            Step::Wallet => match snapshot.loaded_wallet.as_ref() {
+                None => ItemStatus {
+                    done: Some(false),
+                    details: "No wallet loaded — add one by private key on the Wallet screen \
+                              and send it some Dash from a faucet"
+                        .to_owned(),
+                },
+                Some(wallet) => {
+                    let utxos = wallet.spendable_utxos().count();
+                    ItemStatus {
+                        done: Some(utxos >= MIN_WALLET_UTXOS),
+                        details: if utxos >= MIN_WALLET_UTXOS {
+                            format!(
+                                "Wallet holds {} in {} UTXOs",
+                                wallet.balance_formatted(),
+                                utxos
+                            )
+                        } else {
+                            format!(
+                                "Wallet holds {} in {} spendable UTXOs — fund it if it's empty, \
+                                 then get more UTXOs on the Wallet screen",
+                                wallet.balance_formatted(),
+                                utxos
+                            )
+                        },
+                    }
+                }
+            },
+            Step::Identity => match snapshot.loaded_identity.as_ref() {
+                None => ItemStatus {
+                    done: Some(false),
+                    details: "No identity loaded — register one funded by the wallet, or load \
+                              an existing one, on the Identities screen"
+                        .to_owned(),
+                },
+                Some(identity) => ItemStatus {
+                    done: Some(identity.balance() >= MIN_IDENTITY_CREDITS),
+                    details: if identity.balance() >= MIN_IDENTITY_CREDITS {
+                        format!(
+                            "Identity holds {}",
+                            units::format_credits(identity.balance())
+                        )
+                    } else {
+                        format!(
+                            "Identity holds {} — top it up from the wallet on the Identities \
+                             screen",
+                            units::format_credits(identity.balance())
+                        )
+                    },
+                },
+            },
+        }
+    }
+
+    fn update_info(&mut self) {
+        let index = self.item_select.selected();
+        let (Some(step), Some(status)) = (Step::ALL.get(index), self.statuses.get(index)) else {
+            return;
+        };
+        let action = match (status.done, resolving_screen(*step)) {
+            (Some(false), Some(screen)) => {
+                format!("\n\nPress Enter to go to the {} screen", screen)
+            }
+            (Some(false), None) if *step != Step::Config => {
+                "\n\nPress t to test connections again once it's fixed".to_owned()
+            }
+            _ => String::new(),
+        };
+        let text = format!("{}\n\n{}{}", step.title(), status.details, action);
+        self.info = if status.done == Some(false) {
+            Info::new_error(&text)
+        } else {
+            Info::new_fixed(&text)
+        };
+    }
+}
+
+/// Name of the screen an item is resolved on, if it's not fixed outside of
+/// the application.
+fn resolving_screen(step: Step) -> Option<&'static str> {
+    match step {
+        Step::Wallet => Some("Wallet"),
+        Step::Identity => Some("Identities"),
+        Step::Config | Step::Core | Step::Dapi => None,
+    }
+}
+
+impl ScreenController for GettingStartedScreenController {
+    fn name(&self) -> &'static str {
+        "Getting started"
+    }
+
+    fn command_keys(&self) -> &[ScreenCommandKey] {
+        COMMAND_KEYS.as_ref()
+    }
+
+    fn toggle_keys(&self) -> &[ScreenToggleKey] {
+        &[]
+    }
+
+    fn on_event(&mut self, event: &Event) -> ScreenFeedback {
+        match event {
+            Event::Key(KeyEvent {
+                code: Key::Char('q'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::PreviousScreen,
+
+            Event::Key(KeyEvent {
+                code: Key::Enter,
+                modifiers: KeyModifiers::NONE,
+            }) => match Step::ALL.get(self.item_select.selected()) {
+                Some(Step::Wallet) => ScreenFeedback::NextScreen(WalletScreenController::builder()),
+                Some(Step::Identity) => {
+                    ScreenFeedback::NextScreen(IdentitiesScreenController::builder())
+                }
+                _ => ScreenFeedback::None,
+            },
+
+            Event::Key(KeyEvent {
+                code: Key::Char('t'),
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                self.connection_checks = None;
+                let snapshot = self.snapshots.borrow().clone();
+                self.reload(&snapshot);
+                ScreenFeedback::Task {
+                    task: Task::TestConnections,
+                    block: false,
+                }
+            }
+
+            Event::Key(k) => {
+                if self.item_select.on_event(k) {
+                    self.update_info();
+                    ScreenFeedback::Redraw
+                } else if self.info.on_event(k) {
+                    ScreenFeedback::Redraw
+                } else {
+                    ScreenFeedback::None
+                }
+            }
+
+            Event::Backend(BackendEvent::TaskCompleted {
+                task: Task::TestConnections,
+                execution_result,
+            }) => {
+                self.connection_checks = Some(match execution_result {
+                    Ok(CompletedTaskPayload::ConnectionChecks(checks)) => checks.clone(),
+                    _ => Vec::new(),
+                });
+                let snapshot = self.snapshots.borrow().clone();
+                self.reload(&snapshot);
+                ScreenFeedback::Redraw
+            }
+
+            // Connections are checked once the screen is shown, without waiting
+            // for a key press
+            Event::Tick { .. } if !self.checks_requested => {
+                self.checks_requested = true;
+                ScreenFeedback::Task {
+                    task: Task::TestConnections,
+                    block: false,
+                }
+            }
+
+            Event::Tick { .. } => {
+                if self.snapshots.has_changed().unwrap_or(false) {
+                    let snapshot = self.snapshots.borrow_and_update().clone();
+                    self.reload(&snapshot);
+                    ScreenFeedback::Redraw
+                } else {
+                    ScreenFeedback::None
+                }
+            }
+
+            _ => ScreenFeedback::None,
+        }
+    }
+
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+            .split(area);
+        self.item_select.view(frame, layout[0]);
+        self.info.view(frame, layout[1]);
+    }
+}
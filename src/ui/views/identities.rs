@@ -1,11 +1,12 @@
 //! UI definitions related to identities.
 
-use std::collections::BTreeMap;
+mod key_vault;
+
+use std::collections::{BTreeMap, BTreeSet};
 
 use crate::ui::views::wallet::add_identity_key::AddIdentityKeyFormController;
 use dpp::{
     identity::accessors::IdentityGettersV0,
-    platform_value::string_encoding::Encoding,
     prelude::{Identifier, Identity},
 };
 use itertools::Itertools;
@@ -24,13 +25,18 @@ use tuirealm::{
     AttrValue, Attribute, Frame,
 };
 
+use self::key_vault::KeyVaultScreenController;
 use crate::{
-    backend::{as_json_string, identities::IdentityTask, AppState, BackendEvent, Task},
+    backend::{
+        as_json_string, identities::IdentityTask, AppState, AppStateUpdate, BackendEvent, Task,
+    },
     ui::{
         form::{
-            parsers::DefaultTextInputParser, ComposedInput, Field, FormController, FormStatus,
-            Input, InputStatus, SelectInput, TextInput,
+            parsers::{CreditsTextInputParser, DefaultTextInputParser, DuffsTextInputParser},
+            ComposedInput, Field, FormController, FormStatus, Input, InputStatus, SelectInput,
+            TextInput,
         },
+        id_names::IdNames,
         screen::{
             utils::impl_builder, widgets::info::Info, ScreenCommandKey, ScreenController,
             ScreenFeedback, ScreenToggleKey,
@@ -39,18 +45,19 @@ use crate::{
     Event,
 };
 
-const IDENTITY_LOADED_COMMAND_KEYS: [ScreenCommandKey; 17] = [
+const IDENTITY_LOADED_COMMAND_KEYS: [ScreenCommandKey; 18] = [
     ScreenCommandKey::new("q", "Back to Main"),
     ScreenCommandKey::new("r", "Register new"),
     ScreenCommandKey::new("l", "Load identity with private key(s)"),
     ScreenCommandKey::new("m", "Load masternode identity"),
     ScreenCommandKey::new("s", "Set loaded"),
     ScreenCommandKey::new("b", "Refresh loaded"),
-    ScreenCommandKey::new("a", "Refresh all"),
+    ScreenCommandKey::new("a / C-r", "Refresh all"),
     ScreenCommandKey::new("n", "Transfer credits from loaded"),
     ScreenCommandKey::new("d", "Register DPNS name for loaded"),
     ScreenCommandKey::new("k", "Add key to loaded"),
     ScreenCommandKey::new("C-f", "Forget selected"),
+    ScreenCommandKey::new("e", "Selected signs strategies"),
     ScreenCommandKey::new("c", "Copy loaded ID"),
     ScreenCommandKey::new("i", "Query by ID"),
     ScreenCommandKey::new("C-n", "Next"),
@@ -67,13 +74,15 @@ const NO_KNOWN_IDENTITIES_COMMAND_KEYS: [ScreenCommandKey; 5] = [
     ScreenCommandKey::new("i", "Query by ID"),
 ];
 
-const IDENTITIES_KNOWN_NONE_LOADED_COMMAND_KEYS: [ScreenCommandKey; 11] = [
+const IDENTITIES_KNOWN_NONE_LOADED_COMMAND_KEYS: [ScreenCommandKey; 13] = [
     ScreenCommandKey::new("q", "Back to Main"),
     ScreenCommandKey::new("r", "Register new"),
     ScreenCommandKey::new("l", "Load identity with private key(s)"),
     ScreenCommandKey::new("m", "Load masternode identity"),
     ScreenCommandKey::new("s", "Set loaded"),
+    ScreenCommandKey::new("C-r", "Refresh all"),
     ScreenCommandKey::new("C-f", "Forget selected"),
+    ScreenCommandKey::new("e", "Selected signs strategies"),
     ScreenCommandKey::new("i", "Query by ID"),
     ScreenCommandKey::new("C-n", "Next"),
     ScreenCommandKey::new("C-p", "Previous"),
@@ -88,6 +97,7 @@ fn join_commands(
     identity_top_up_in_progress: bool,
     no_known_identities: bool,
     wallet_loaded: bool,
+    registrations_queued: bool,
 ) -> &'static [ScreenCommandKey] {
     let mut commands;
 
@@ -118,7 +128,10 @@ fn join_commands(
 
     if !wallet_loaded {
         commands.remove(1);
+    } else if registrations_queued {
+        commands.push(ScreenCommandKey::new("u", "Retry queued registrations"));
     };
+    commands.push(ScreenCommandKey::new("v", "Key vault"));
 
     commands.leak()
 }
@@ -133,37 +146,36 @@ pub(crate) struct IdentitiesScreenController {
     identity_registration_in_progress: bool,
     identity_top_up_in_progress: bool,
     wallet_loaded: bool,
+    queued_registrations: usize,
+    strategy_signing_identities: BTreeSet<Identifier>,
+    id_names: IdNames,
 }
 
 impl_builder!(IdentitiesScreenController);
 
 impl IdentitiesScreenController {
     pub(crate) async fn new(app_state: &AppState) -> Self {
-        let known_identities = app_state.known_identities.lock().await;
+        let snapshot = app_state.snapshot();
+        let known_identities = &snapshot.known_identities;
+        let id_names = IdNames::from_snapshot(&snapshot);
         let known_identities_vec = known_identities
             .iter()
             .map(|(k, _)| k.clone())
             .collect_vec();
 
-        let loaded_identity = app_state.loaded_identity.lock().await;
-        let loaded_identity_id = loaded_identity.as_ref().map(|identity| identity.id());
+        let loaded_identity_id = snapshot
+            .loaded_identity
+            .as_ref()
+            .map(|identity| identity.id());
 
-        let identity_registration_in_progress = match *loaded_identity {
-            Some(_) => false,
-            None => app_state
-                .identity_asset_lock_private_key_in_creation
-                .lock()
-                .await
-                .is_some(),
-        };
+        let identity_registration_in_progress =
+            snapshot.loaded_identity.is_none() && snapshot.identity_registration_in_progress;
+
+        let identity_top_up_in_progress = snapshot.identity_top_up_in_progress;
 
-        let identity_top_up_in_progress = app_state
-            .identity_asset_lock_private_key_in_top_up
-            .lock()
-            .await
-            .is_some();
+        let wallet_loaded = snapshot.loaded_wallet.is_some();
 
-        let wallet_loaded = app_state.loaded_wallet.lock().await.is_some();
+        let queued_registrations = snapshot.queued_registrations;
 
         let identity_view = if wallet_loaded {
             Info::new_scrollable(
@@ -183,11 +195,15 @@ impl IdentitiesScreenController {
                 known_identities
                     .keys()
                     .map(|id| {
-                        let mut text_span = TextSpan::new(id.to_string(Encoding::Base58));
+                        let mut text_span = TextSpan::new(id_names.identity(id));
                         // Check if the current id matches the loaded identity id
                         if Some(id) == loaded_identity_id.as_ref() {
                             text_span = text_span.bold(); // Make the loaded identity bold
                         }
+                        // Identities signing strategy runs are in italics
+                        if snapshot.strategy_signing_identities.contains(id) {
+                            text_span = text_span.italic();
+                        }
                         vec![text_span]
                     })
                     .collect(),
@@ -207,11 +223,14 @@ impl IdentitiesScreenController {
             identity_select,
             identity_view,
             known_identities: known_identities.clone(),
-            loaded_identity: loaded_identity.clone(),
+            loaded_identity: snapshot.loaded_identity.clone(),
             current_batch: known_identities_vec,
             identity_registration_in_progress,
             identity_top_up_in_progress,
             wallet_loaded,
+            queued_registrations,
+            strategy_signing_identities: snapshot.strategy_signing_identities.clone(),
+            id_names,
         }
     }
 
@@ -241,11 +260,15 @@ impl IdentitiesScreenController {
                 known_identities
                     .keys()
                     .map(|id| {
-                        let mut text_span = TextSpan::new(id.to_string(Encoding::Base58));
+                        let mut text_span = TextSpan::new(self.id_names.identity(id));
                         // Check if the current id matches the loaded identity id
                         if Some(id) == loaded_identity_id.as_ref() {
                             text_span = text_span.bold(); // Make the loaded identity bold
                         }
+                        // Identities signing strategy runs are in italics
+                        if self.strategy_signing_identities.contains(id) {
+                            text_span = text_span.italic();
+                        }
                         vec![text_span]
                     })
                     .collect(),
@@ -294,6 +317,7 @@ impl ScreenController for IdentitiesScreenController {
             self.identity_top_up_in_progress,
             self.current_batch.is_empty(),
             self.wallet_loaded,
+            self.queued_registrations > 0,
         )
     }
 
@@ -357,6 +381,14 @@ impl ScreenController for IdentitiesScreenController {
                 block: true,
             },
 
+            Event::Key(KeyEvent {
+                code: Key::Char('r'),
+                modifiers: KeyModifiers::CONTROL,
+            }) if !self.known_identities.is_empty() => ScreenFeedback::Task {
+                task: Task::Identity(IdentityTask::RefreshAllKnown),
+                block: true,
+            },
+
             Event::Key(KeyEvent {
                 code: Key::Char('d'),
                 modifiers: KeyModifiers::NONE,
@@ -406,6 +438,21 @@ impl ScreenController for IdentitiesScreenController {
                 }
             }
 
+            Event::Key(KeyEvent {
+                code: Key::Char('u'),
+                modifiers: KeyModifiers::NONE,
+            }) if self.wallet_loaded && self.queued_registrations > 0 => ScreenFeedback::Task {
+                task: Task::Identity(IdentityTask::RetryQueuedRegistrations {
+                    include_given_up: true,
+                }),
+                block: true,
+            },
+
+            Event::Key(KeyEvent {
+                code: Key::Char('v'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::NextScreen(KeyVaultScreenController::builder()),
+
             Event::Key(KeyEvent {
                 code: Key::Char('c'),
                 modifiers: KeyModifiers::NONE,
@@ -443,6 +490,17 @@ impl ScreenController for IdentitiesScreenController {
                 }
             }
 
+            Event::Key(KeyEvent {
+                code: Key::Char('e'),
+                modifiers: KeyModifiers::NONE,
+            }) => match self.get_selected_identity().map(|identity| identity.id()) {
+                Some(identity_id) => ScreenFeedback::Task {
+                    task: Task::Identity(IdentityTask::ToggleStrategySigning(identity_id)),
+                    block: true,
+                },
+                None => ScreenFeedback::None,
+            },
+
             Event::Key(KeyEvent {
                 code: Key::Char('k'),
                 modifiers: KeyModifiers::NONE,
@@ -517,6 +575,17 @@ impl ScreenController for IdentitiesScreenController {
                 app_state_update: _,
             }) => ScreenFeedback::Form(Box::new(AddPrivateKeysFormController::new())),
 
+            Event::Backend(BackendEvent::TaskCompletedStateChange {
+                task: Task::Identity(_),
+                execution_result,
+                app_state_update: AppStateUpdate::IdentityRegistrationQueue(queued),
+            }) => {
+                self.queued_registrations = *queued;
+                self.identity_registration_in_progress = false;
+                self.identity_view = Info::new_from_result(execution_result);
+                ScreenFeedback::Redraw
+            }
+
             Event::Backend(BackendEvent::TaskCompletedStateChange {
                 task: Task::Identity(_),
                 execution_result,
@@ -525,6 +594,18 @@ impl ScreenController for IdentitiesScreenController {
                 self.identity_view = Info::new_from_result(execution_result);
                 ScreenFeedback::Redraw
             }
+            Event::Backend(BackendEvent::TaskCompleted {
+                task: Task::Identity(IdentityTask::ToggleStrategySigning(identity_id)),
+                execution_result,
+            }) => {
+                if execution_result.is_ok() && !self.strategy_signing_identities.remove(identity_id)
+                {
+                    self.strategy_signing_identities.insert(*identity_id);
+                }
+                self.update_identity_list();
+                self.identity_view = Info::new_from_result(execution_result);
+                ScreenFeedback::Redraw
+            }
             Event::Backend(BackendEvent::TaskCompleted {
                 task: Task::Identity(_),
                 execution_result,
@@ -584,7 +665,7 @@ impl FormController for GetIdentityByIdFormController {
 pub(crate) struct TransferCreditsFormController {
     input: ComposedInput<(
         Field<TextInput<DefaultTextInputParser<String>>>,
-        Field<TextInput<DefaultTextInputParser<f64>>>,
+        Field<TextInput<CreditsTextInputParser>>,
     )>,
 }
 
@@ -594,8 +675,8 @@ impl TransferCreditsFormController {
             input: ComposedInput::new((
                 Field::new("Enter the recipient base58 ID", TextInput::new("Base58 ID")),
                 Field::new(
-                    "Enter the amount to transfer in Dash (Ex: .5)",
-                    TextInput::new("Amount to transfer in Dash"),
+                    "Enter the amount to transfer",
+                    TextInput::new_with_parser(CreditsTextInputParser, "Amount to transfer"),
                 ),
             )),
         }
@@ -692,13 +773,13 @@ impl FormController for RegisterDPNSNameFormController {
 }
 
 struct RegisterIdentityFormController {
-    input: TextInput<DefaultTextInputParser<f64>>,
+    input: TextInput<DuffsTextInputParser>,
 }
 
 impl RegisterIdentityFormController {
     fn new() -> Self {
         RegisterIdentityFormController {
-            input: TextInput::new("Quantity (in Dash)"),
+            input: TextInput::new_with_parser(DuffsTextInputParser, "Amount"),
         }
     }
 }
@@ -707,9 +788,7 @@ impl FormController for RegisterIdentityFormController {
     fn on_event(&mut self, event: KeyEvent) -> FormStatus {
         match self.input.on_event(event) {
             InputStatus::Done(amount) => FormStatus::Done {
-                task: Task::Identity(IdentityTask::RegisterIdentity(
-                    (amount * 100000000.0) as u64,
-                )),
+                task: Task::Identity(IdentityTask::RegisterIdentity(amount)),
                 block: true,
             },
             status => status.into(),
@@ -738,13 +817,13 @@ impl FormController for RegisterIdentityFormController {
 }
 
 struct TopUpIdentityFormController {
-    input: TextInput<DefaultTextInputParser<f64>>,
+    input: TextInput<DuffsTextInputParser>,
 }
 
 impl TopUpIdentityFormController {
     fn new() -> Self {
         TopUpIdentityFormController {
-            input: TextInput::new("Quantity (in Dash)"),
+            input: TextInput::new_with_parser(DuffsTextInputParser, "Amount"),
         }
     }
 }
@@ -753,12 +832,10 @@ impl FormController for TopUpIdentityFormController {
     fn on_event(&mut self, event: KeyEvent) -> FormStatus {
         match self.input.on_event(event) {
             InputStatus::Done(amount) => FormStatus::Done {
-                task: Task::Identity(IdentityTask::TopUpIdentity((amount * 100000000.0) as u64)),
+                task: Task::Identity(IdentityTask::TopUpIdentity(amount)),
                 block: true,
             },
-            InputStatus::Redraw => FormStatus::Redraw,
-            InputStatus::None => FormStatus::None,
-            InputStatus::Exit => FormStatus::Exit,
+            status => status.into(),
         }
     }
 
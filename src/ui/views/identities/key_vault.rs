@@ -0,0 +1,374 @@
+//! Private keys vault screen, shows which identities can sign for which key
+//! purposes and moves the keys to and from the vault file.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+};
+
+use dpp::{
+    identity::{
+        accessors::IdentityGettersV0,
+        identity_public_key::accessors::v0::IdentityPublicKeyGettersV0, KeyID,
+    },
+    platform_value::string_encoding::Encoding,
+    prelude::{Identifier, Identity},
+};
+use itertools::Itertools;
+use tuirealm::{
+    event::{Key, KeyEvent, KeyModifiers},
+    tui::prelude::{Constraint, Direction, Layout, Rect},
+    Frame,
+};
+
+use crate::{
+    backend::{
+        identities::IdentityTask, key_vault::VAULT_FILE_PATH, AppState, AppStateUpdate,
+        BackendEvent, Task,
+    },
+    ui::{
+        form::{
+            parsers::PassphraseTextInputParser, ComposedInput, Field, FormController, FormStatus,
+            Input, InputStatus, SelectInput, TextInput,
+        },
+        screen::{
+            utils::impl_builder, widgets::info::Info, ScreenCommandKey, ScreenController,
+            ScreenFeedback, ScreenToggleKey,
+        },
+    },
+    Event,
+};
+
+const COMMAND_KEYS: [ScreenCommandKey; 5] = [
+    ScreenCommandKey::new("q", "Back to Identities"),
+    ScreenCommandKey::new("e", "Export to vault file"),
+    ScreenCommandKey::new("i", "Import from vault file"),
+    ScreenCommandKey::new("↓", "Scroll down"),
+    ScreenCommandKey::new("↑", "Scroll up"),
+];
+
+pub(crate) struct KeyVaultScreenController {
+    capabilities: Info,
+    result_info: Option<Info>,
+    known_identities: BTreeMap<Identifier, Identity>,
+}
+
+impl_builder!(KeyVaultScreenController);
+
+impl KeyVaultScreenController {
+    pub(crate) async fn new(app_state: &AppState) -> Self {
+        let snapshot = app_state.snapshot();
+        let known_identities = snapshot.known_identities.clone();
+
+        KeyVaultScreenController {
+            capabilities: Info::new_scrollable(&signing_capabilities(
+                &known_identities,
+                &snapshot.private_key_ids,
+            )),
+            result_info: None,
+            known_identities,
+        }
+    }
+}
+
+/// Lists identities with private keys, and for each key purpose which keys
+/// they can sign with.
+fn signing_capabilities(
+    known_identities: &BTreeMap<Identifier, Identity>,
+    private_key_ids: &BTreeSet<(Identifier, KeyID)>,
+) -> String {
+    let identity_ids: BTreeSet<&Identifier> = private_key_ids.iter().map(|(id, _)| id).collect();
+    if identity_ids.is_empty() {
+        return format!(
+            "No private keys known yet, load an identity or import keys from {}",
+            VAULT_FILE_PATH
+        );
+    }
+
+    let mut text = String::new();
+    for identity_id in identity_ids {
+        text.push_str(&format!("{}\n", identity_id.to_string(Encoding::Base58)));
+
+        let Some(identity) = known_identities.get(identity_id) else {
+            let key_ids = private_key_ids
+                .iter()
+                .filter(|(id, _)| id == identity_id)
+                .map(|(_, key_id)| key_id)
+                .join(", ");
+            text.push_str(&format!(
+                "  Unknown identity, private keys for key ids {}\n\n",
+                key_ids
+            ));
+            continue;
+        };
+
+        let keys_by_purpose = identity
+            .public_keys()
+            .values()
+            .into_group_map_by(|key| format!("{:?}", key.purpose()));
+        for (purpose, keys) in keys_by_purpose.into_iter().sorted_by(|a, b| a.0.cmp(&b.0)) {
+            let keys = keys
+                .iter()
+                .map(|key| {
+                    let signable = private_key_ids.contains(&(*identity_id, key.id()));
+                    format!(
+                        "{} {:?}{}",
+                        key.id(),
+                        key.security_level(),
+                        if signable { "" } else { " (no private key)" }
+                    )
+                })
+                .join(", ");
+            text.push_str(&format!("  {}: {}\n", purpose, keys));
+        }
+        text.push('\n');
+    }
+    text
+}
+
+impl ScreenController for KeyVaultScreenController {
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        if let Some(result_info) = &mut self.result_info {
+            let layout = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(60), Constraint::Percentage(40)].as_ref())
+                .split(area);
+            self.capabilities.view(frame, layout[0]);
+            result_info.view(frame, layout[1]);
+        } else {
+            self.capabilities.view(frame, area);
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "Key vault"
+    }
+
+    fn command_keys(&self) -> &[ScreenCommandKey] {
+        COMMAND_KEYS.as_ref()
+    }
+
+    fn toggle_keys(&self) -> &[ScreenToggleKey] {
+        &[]
+    }
+
+    fn on_event(&mut self, event: &Event) -> ScreenFeedback {
+        match event {
+            Event::Key(KeyEvent {
+                code: Key::Char('q'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::PreviousScreen,
+
+            Event::Key(KeyEvent {
+                code: Key::Char('e'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::Form(Box::new(ExportKeyVaultFormController::new(false))),
+
+            Event::Key(KeyEvent {
+                code: Key::Char('i'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::Form(Box::new(ImportKeyVaultFormController::new())),
+
+            Event::Key(
+                key_event @ KeyEvent {
+                    code: Key::Down | Key::Up,
+                    modifiers: KeyModifiers::NONE,
+                },
+            ) => {
+                self.capabilities.on_event(key_event);
+                ScreenFeedback::Redraw
+            }
+
+            Event::Backend(BackendEvent::TaskCompletedStateChange {
+                task: Task::Identity(IdentityTask::ImportKeyVault(_)),
+                execution_result,
+                app_state_update: AppStateUpdate::IdentityPrivateKeys(private_keys),
+            }) => {
+                self.capabilities = Info::new_scrollable(&signing_capabilities(
+                    &self.known_identities,
+                    &private_keys.keys().copied().collect(),
+                ));
+                self.result_info = Some(Info::new_from_result(execution_result));
+                ScreenFeedback::Redraw
+            }
+
+            Event::Backend(BackendEvent::TaskCompleted {
+                task:
+                    Task::Identity(
+                        IdentityTask::ExportKeyVault { .. } | IdentityTask::ImportKeyVault(_),
+                    ),
+                execution_result,
+            }) => {
+                self.result_info = Some(Info::new_from_result(execution_result));
+                ScreenFeedback::Redraw
+            }
+
+            _ => ScreenFeedback::None,
+        }
+    }
+}
+
+/// Asks for the vault passphrase twice, then whether to replace an existing
+/// vault file.
+struct ExportKeyVaultFormController {
+    input: ComposedInput<(
+        Field<TextInput<PassphraseTextInputParser>>,
+        Field<TextInput<PassphraseTextInputParser>>,
+    )>,
+    /// The passphrases entered before didn't match
+    mismatch: bool,
+}
+
+impl ExportKeyVaultFormController {
+    fn new(mismatch: bool) -> Self {
+        ExportKeyVaultFormController {
+            input: ComposedInput::new((
+                Field::new(
+                    "Vault passphrase",
+                    TextInput::new_with_parser(PassphraseTextInputParser, "passphrase").masked(),
+                ),
+                Field::new(
+                    "Confirm passphrase",
+                    TextInput::new_with_parser(PassphraseTextInputParser, "passphrase again")
+                        .masked(),
+                ),
+            )),
+            mismatch,
+        }
+    }
+}
+
+impl FormController for ExportKeyVaultFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done((passphrase, confirmation)) => {
+                if passphrase != confirmation {
+                    FormStatus::NextForm(Box::new(ExportKeyVaultFormController::new(true)))
+                } else if Path::new(VAULT_FILE_PATH).exists() {
+                    FormStatus::NextForm(Box::new(OverwriteKeyVaultFormController {
+                        input: SelectInput::new(vec!["No", "Yes"]),
+                        passphrase,
+                    }))
+                } else {
+                    FormStatus::Done {
+                        task: Task::Identity(IdentityTask::ExportKeyVault {
+                            passphrase,
+                            overwrite: false,
+                        }),
+                        block: true,
+                    }
+                }
+            }
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        if self.mismatch {
+            "Export private keys, the passphrases didn't match"
+        } else {
+            "Export private keys"
+        }
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        self.input.step_name()
+    }
+
+    fn step_index(&self) -> u8 {
+        self.input.step_index()
+    }
+
+    fn steps_number(&self) -> u8 {
+        3
+    }
+}
+
+struct OverwriteKeyVaultFormController {
+    input: SelectInput<&'static str>,
+    passphrase: String,
+}
+
+impl FormController for OverwriteKeyVaultFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done("Yes") => FormStatus::Done {
+                task: Task::Identity(IdentityTask::ExportKeyVault {
+                    passphrase: std::mem::take(&mut self.passphrase),
+                    overwrite: true,
+                }),
+                block: true,
+            },
+            InputStatus::Done(_) => FormStatus::Exit,
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Export private keys"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        "Replace the existing vault file?"
+    }
+
+    fn step_index(&self) -> u8 {
+        2
+    }
+
+    fn steps_number(&self) -> u8 {
+        3
+    }
+}
+
+struct ImportKeyVaultFormController {
+    input: TextInput<PassphraseTextInputParser>,
+}
+
+impl ImportKeyVaultFormController {
+    fn new() -> Self {
+        ImportKeyVaultFormController {
+            input: TextInput::new_with_parser(PassphraseTextInputParser, "passphrase").masked(),
+        }
+    }
+}
+
+impl FormController for ImportKeyVaultFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done(passphrase) => FormStatus::Done {
+                task: Task::Identity(IdentityTask::ImportKeyVault(passphrase)),
+                block: true,
+            },
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Import private keys"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        "Vault passphrase"
+    }
+
+    fn step_index(&self) -> u8 {
+        0
+    }
+
+    fn steps_number(&self) -> u8 {
+        1
+    }
+}
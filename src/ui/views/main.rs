@@ -12,22 +12,39 @@ use super::{
 };
 use crate::ui::views::strategies::StrategiesScreenController;
 use crate::{
+    backend::{
+        connections::{summarize_checks, ConnectionCheck},
+        genesis_import::GENESIS_DUMP_PATH,
+        BackendEvent, CompletedTaskPayload, Task,
+    },
+    config::Config,
     ui::{
+        form::{
+            parsers::DefaultTextInputParser, FormController, FormStatus, Input, InputStatus,
+            SelectInput, TextInput,
+        },
         screen::{
             utils::impl_builder_no_args, widgets::info::Info, ScreenCommandKey, ScreenController,
             ScreenFeedback, ScreenToggleKey,
         },
         views::{
+            activity::ActivityScreenController,
+            getting_started::GettingStartedScreenController,
             identities::IdentitiesScreenController,
+            maintenance::MaintenanceScreenController,
             platform_info::PlatformInfoScreenController,
+            run_history::RunHistoryScreenController,
+            schedules::SchedulesScreenController,
+            transition_inspector::TransitionInspectorScreenController,
             //            strategies::StrategiesScreenController,
         },
     },
     Event,
 };
 
-const COMMAND_KEYS: [ScreenCommandKey; 7] = [
+const COMMAND_KEYS: [ScreenCommandKey; 18] = [
     ScreenCommandKey::new("q", "Quit"),
+    ScreenCommandKey::new("o", "Getting started"),
     ScreenCommandKey::new("i", "Identities"),
     ScreenCommandKey::new("c", "Contracts"),
     ScreenCommandKey::new("s", "Strategies"),
@@ -35,20 +52,27 @@ const COMMAND_KEYS: [ScreenCommandKey; 7] = [
     // ScreenCommandKey::new("v", "Version Upgrade"),
     ScreenCommandKey::new("p", "Platform Information"),
     ScreenCommandKey::new("d", "DPNS"),
+    ScreenCommandKey::new("t", "Test connections"),
+    ScreenCommandKey::new("e", "Expand/collapse health details"),
+    ScreenCommandKey::new("b", "Unban DAPI address"),
+    ScreenCommandKey::new("a", "Switch DAPI address set"),
+    ScreenCommandKey::new("m", "Maintenance"),
+    ScreenCommandKey::new("l", "Activity log"),
+    ScreenCommandKey::new("g", "Import devnet genesis dump"),
+    ScreenCommandKey::new("r", "Scheduled runs"),
+    ScreenCommandKey::new("h", "Run history"),
+    ScreenCommandKey::new("x", "Transition inspector"),
     // ScreenCommandKey::new("t", "Withdrawal Testing"),
 ];
 
-pub(crate) struct MainScreenController {
-    info: Info,
-}
-
-impl MainScreenController {
-    pub(crate) fn new() -> Self {
-        MainScreenController {
-            info: Info::new_fixed(
-                r#"Welcome to Platform TUI!
+const WELCOME_TEXT: &str = r#"Welcome to Platform TUI!
 
 Use keys listed in the section below to switch screens and execute commands.
+New here? Press o for a checklist of what a first strategy run needs.
+Number keys 1-6 or Tab switch between the sections listed on top from any screen.
+Ctrl+t switches between abbreviated and full IDs next to identity and contract names.
+Ctrl+u switches amounts between Dash, duffs and credits, typed amounts included.
+Ctrl+r fetches the data shown on the identities, contracts, platform info and wallet screens again.
 Some of them require signature and are disabled until an identity key is loaded.
 
 Italics are used to mark flags.
@@ -56,10 +80,58 @@ Bold italics are flags that are enabled.
 
 Text inputs with completions support both arrows and Ctrl+n / Ctrl+p keys for selection.
 Use q to go back from completion list or once again to leave input at all.
-"#,
-            ),
+"#;
+
+pub(crate) struct MainScreenController {
+    info: Info,
+    /// Latest environment checks, run on startup and on demand
+    health_checks: Option<Vec<ConnectionCheck>>,
+    health_expanded: bool,
+}
+
+impl MainScreenController {
+    pub(crate) fn new() -> Self {
+        MainScreenController {
+            info: Info::new_fixed(WELCOME_TEXT),
+            health_checks: None,
+            health_expanded: false,
         }
     }
+
+    /// Shows the health banner above the welcome text, with each check's
+    /// details and fix suggestions when expanded.
+    fn update_info(&mut self) {
+        let Some(checks) = &self.health_checks else {
+            self.info = Info::new_fixed(WELCOME_TEXT);
+            return;
+        };
+
+        let summary = summarize_checks(checks)
+            .into_iter()
+            .map(|(group, ok)| format!("{} {}", group, if ok { "✓" } else { "✗" }))
+            .collect::<Vec<_>>()
+            .join("  ");
+        let mut banner = format!("Health: {summary}\n");
+        if self.health_expanded {
+            for check in checks {
+                banner.push_str(&format!(
+                    "  {} {}: {}\n",
+                    if check.ok { "✓" } else { "✗" },
+                    check.service,
+                    check.message
+                ));
+            }
+        } else if checks.iter().any(|check| !check.ok) {
+            banner.push_str("Press e for details and how to fix them\n");
+        }
+
+        let text = format!("{banner}\n{WELCOME_TEXT}");
+        self.info = if checks.iter().all(|check| check.ok) {
+            Info::new_fixed(&text)
+        } else {
+            Info::new_error(&text)
+        };
+    }
 }
 
 impl_builder_no_args!(MainScreenController);
@@ -83,6 +155,10 @@ impl ScreenController for MainScreenController {
                 code: Key::Char('q'),
                 modifiers: KeyModifiers::NONE,
             }) => ScreenFeedback::Quit,
+            Event::Key(KeyEvent {
+                code: Key::Char('o'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::NextScreen(GettingStartedScreenController::builder()),
             Event::Key(KeyEvent {
                 code: Key::Char('i'),
                 modifiers: KeyModifiers::NONE,
@@ -111,6 +187,118 @@ impl ScreenController for MainScreenController {
                 code: Key::Char('d'),
                 modifiers: KeyModifiers::NONE,
             }) => ScreenFeedback::NextScreen(DpnsUsernamesScreenController::builder()),
+            Event::Key(KeyEvent {
+                code: Key::Char('t'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::Task {
+                task: Task::TestConnections,
+                block: true,
+            },
+            Event::Key(KeyEvent {
+                code: Key::Char('e'),
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                self.health_expanded = !self.health_expanded;
+                self.update_info();
+                ScreenFeedback::Redraw
+            }
+            Event::Key(KeyEvent {
+                code: Key::Char('b'),
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                let banned_addresses: Vec<String> = self
+                    .health_checks
+                    .iter()
+                    .flatten()
+                    .filter_map(|check| check.banned_address.clone())
+                    .collect();
+                if banned_addresses.is_empty() {
+                    self.info = Info::new_fixed(&format!(
+                        "No banned DAPI addresses, test connections (t) to check again\n\n\
+                         {WELCOME_TEXT}"
+                    ));
+                    ScreenFeedback::Redraw
+                } else {
+                    ScreenFeedback::Form(Box::new(UnbanAddressFormController::new(
+                        banned_addresses,
+                    )))
+                }
+            }
+            Event::Key(KeyEvent {
+                code: Key::Char('a'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::Form(Box::new(AddressSetFormController::new())),
+            Event::Key(KeyEvent {
+                code: Key::Char('m'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::NextScreen(MaintenanceScreenController::builder()),
+            Event::Key(KeyEvent {
+                code: Key::Char('l'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::NextScreen(ActivityScreenController::builder()),
+            Event::Key(KeyEvent {
+                code: Key::Char('g'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::Form(Box::new(ImportGenesisDumpFormController::new())),
+            Event::Key(KeyEvent {
+                code: Key::Char('r'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::NextScreen(SchedulesScreenController::builder()),
+            Event::Key(KeyEvent {
+                code: Key::Char('h'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::NextScreen(RunHistoryScreenController::builder()),
+            Event::Key(KeyEvent {
+                code: Key::Char('x'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::NextScreen(TransitionInspectorScreenController::builder()),
+            Event::Backend(BackendEvent::TaskCompleted {
+                task: Task::SwitchAddressSet(_) | Task::ImportGenesisDump(_),
+                execution_result,
+            }) => {
+                match execution_result {
+                    Ok(message) => {
+                        self.info = Info::new_fixed(&format!("{message}\n\n{WELCOME_TEXT}"))
+                    }
+                    Err(error) => {
+                        self.info = Info::new_error(&format!("{error}\n\n{WELCOME_TEXT}"))
+                    }
+                }
+                ScreenFeedback::Redraw
+            }
+            Event::Backend(BackendEvent::TaskCompleted {
+                task: Task::UnbanAddress(address),
+                execution_result,
+            }) => {
+                match execution_result {
+                    Ok(_) => {
+                        if let Some(checks) = self.health_checks.as_mut() {
+                            checks.retain(|check| check.banned_address.as_ref() != Some(address));
+                        }
+                        self.update_info();
+                    }
+                    Err(error) => {
+                        self.info = Info::new_error(&format!("{error}\n\n{WELCOME_TEXT}"))
+                    }
+                }
+                ScreenFeedback::Redraw
+            }
+            Event::Backend(BackendEvent::TaskCompleted {
+                task: Task::TestConnections,
+                execution_result,
+            }) => {
+                match execution_result {
+                    Ok(CompletedTaskPayload::ConnectionChecks(checks)) => {
+                        self.health_checks = Some(checks.clone());
+                        self.update_info();
+                    }
+                    Ok(payload) => {
+                        self.info = Info::new_fixed(&format!("{WELCOME_TEXT}\n{payload}"))
+                    }
+                    Err(error) => self.info = Info::new_error(&format!("{WELCOME_TEXT}\n{error}")),
+                }
+                ScreenFeedback::Redraw
+            }
             // Event::Key(KeyEvent {
             //     code: Key::Char('t'),
             //     modifiers: KeyModifiers::NONE,
@@ -123,3 +311,146 @@ impl ScreenController for MainScreenController {
         self.info.view(frame, area)
     }
 }
+
+/// Picks one of the configured DAPI address sets.
+struct AddressSetFormController {
+    input: SelectInput<String>,
+}
+
+impl AddressSetFormController {
+    fn new() -> Self {
+        let names = Config::load()
+            .dapi_address_sets()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        AddressSetFormController {
+            input: SelectInput::new(names),
+        }
+    }
+}
+
+impl FormController for AddressSetFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done(name) => FormStatus::Done {
+                task: Task::SwitchAddressSet(name),
+                block: true,
+            },
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "DAPI address set"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        "Address set"
+    }
+
+    fn step_index(&self) -> u8 {
+        0
+    }
+
+    fn steps_number(&self) -> u8 {
+        1
+    }
+}
+
+/// Picks a banned DAPI address to unban.
+struct UnbanAddressFormController {
+    input: SelectInput<String>,
+}
+
+impl UnbanAddressFormController {
+    fn new(banned_addresses: Vec<String>) -> Self {
+        UnbanAddressFormController {
+            input: SelectInput::new(banned_addresses),
+        }
+    }
+}
+
+impl FormController for UnbanAddressFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done(address) => FormStatus::Done {
+                task: Task::UnbanAddress(address),
+                block: false,
+            },
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Unban DAPI address"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        "Banned address"
+    }
+
+    fn step_index(&self) -> u8 {
+        0
+    }
+
+    fn steps_number(&self) -> u8 {
+        1
+    }
+}
+
+/// Picks the genesis dump file of a local network to import.
+struct ImportGenesisDumpFormController {
+    input: TextInput<DefaultTextInputParser<String>>,
+}
+
+impl ImportGenesisDumpFormController {
+    fn new() -> Self {
+        ImportGenesisDumpFormController {
+            input: TextInput::new_init_value(
+                "Path to the genesis dump JSON",
+                GENESIS_DUMP_PATH.to_owned(),
+            ),
+        }
+    }
+}
+
+impl FormController for ImportGenesisDumpFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done(path) => FormStatus::Done {
+                task: Task::ImportGenesisDump(path),
+                block: true,
+            },
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Import devnet genesis dump"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        "Genesis dump file"
+    }
+
+    fn step_index(&self) -> u8 {
+        0
+    }
+
+    fn steps_number(&self) -> u8 {
+        1
+    }
+}
@@ -0,0 +1,251 @@
+//! Persisted state size and pruning screen.
+
+use std::time::{Duration, Instant};
+
+use tuirealm::{
+    event::{Key, KeyEvent, KeyModifiers},
+    tui::prelude::Rect,
+    Frame,
+};
+
+use crate::{
+    backend::{maintenance::MaintenanceTask, AppState, BackendEvent, Task},
+    ui::{
+        form::{
+            parsers::DefaultTextInputParser, FormController, FormStatus, Input, InputStatus,
+            SelectInput, TextInput,
+        },
+        screen::{
+            utils::impl_builder, widgets::info::Info, ScreenCommandKey, ScreenController,
+            ScreenFeedback, ScreenToggleKey,
+        },
+    },
+    Event,
+};
+
+/// How often the sizes are measured again while the screen is open
+const MEASURE_INTERVAL: Duration = Duration::from_secs(30);
+const DEFAULT_REPORT_AGE_DAYS: u32 = 30;
+
+const COMMAND_KEYS: [ScreenCommandKey; 4] = [
+    ScreenCommandKey::new("q", "Back to Main"),
+    ScreenCommandKey::new("m", "Measure now"),
+    ScreenCommandKey::new("r", "Delete old run reports"),
+    ScreenCommandKey::new("i", "Forget identities with no balance and no keys"),
+];
+
+pub(crate) struct MaintenanceScreenController {
+    info: Info,
+    /// Outcome of the last pruning action, shown above the sizes
+    last_action: Option<Result<String, String>>,
+    last_measured: Option<Instant>,
+}
+
+impl_builder!(MaintenanceScreenController);
+
+impl MaintenanceScreenController {
+    pub(crate) async fn new(_app_state: &AppState) -> Self {
+        MaintenanceScreenController {
+            info: Info::new_fixed("Measuring the persisted state..."),
+            last_action: None,
+            last_measured: None,
+        }
+    }
+
+    fn measure(&mut self) -> ScreenFeedback {
+        self.last_measured = Some(Instant::now());
+        ScreenFeedback::Task {
+            task: Task::Maintenance(MaintenanceTask::MeasureStorage),
+            block: false,
+        }
+    }
+
+    fn show_sizes(&mut self, sizes: &str) {
+        self.info = match &self.last_action {
+            Some(Ok(message)) => Info::new_scrollable(&format!("{}\n\n{}", message, sizes)),
+            Some(Err(error)) => Info::new_error(&format!("{}\n\n{}", error, sizes)),
+            None => Info::new_scrollable(sizes),
+        };
+    }
+}
+
+impl ScreenController for MaintenanceScreenController {
+    fn name(&self) -> &'static str {
+        "Maintenance"
+    }
+
+    fn command_keys(&self) -> &[ScreenCommandKey] {
+        COMMAND_KEYS.as_ref()
+    }
+
+    fn toggle_keys(&self) -> &[ScreenToggleKey] {
+        &[]
+    }
+
+    fn on_event(&mut self, event: &Event) -> ScreenFeedback {
+        match event {
+            Event::Key(KeyEvent {
+                code: Key::Char('q'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::PreviousScreen,
+
+            Event::Key(KeyEvent {
+                code: Key::Char('m'),
+                modifiers: KeyModifiers::NONE,
+            }) => self.measure(),
+
+            Event::Key(KeyEvent {
+                code: Key::Char('r'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::Form(Box::new(PruneRunReportsFormController::new())),
+
+            Event::Key(KeyEvent {
+                code: Key::Char('i'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::Form(Box::new(ConfirmPruneIdentitiesFormController::new())),
+
+            Event::Key(k) => {
+                if self.info.on_event(k) {
+                    ScreenFeedback::Redraw
+                } else {
+                    ScreenFeedback::None
+                }
+            }
+
+            Event::Tick { backend_idle: true }
+                if self
+                    .last_measured
+                    .map_or(true, |time| time.elapsed() >= MEASURE_INTERVAL) =>
+            {
+                self.measure()
+            }
+
+            Event::Backend(BackendEvent::TaskCompleted {
+                task: Task::Maintenance(MaintenanceTask::MeasureStorage),
+                execution_result,
+            }) => {
+                match execution_result {
+                    Ok(sizes) => self.show_sizes(&sizes.to_string()),
+                    Err(e) => self.info = Info::new_error(e),
+                }
+                ScreenFeedback::Redraw
+            }
+
+            // Sizes changed after a pruning action, measure them again
+            Event::Backend(
+                BackendEvent::TaskCompleted {
+                    task: Task::Maintenance(_),
+                    execution_result,
+                }
+                | BackendEvent::TaskCompletedStateChange {
+                    task: Task::Maintenance(_),
+                    execution_result,
+                    ..
+                },
+            ) => {
+                self.last_action = Some(
+                    execution_result
+                        .as_ref()
+                        .map(ToString::to_string)
+                        .map_err(Clone::clone),
+                );
+                self.measure()
+            }
+
+            _ => ScreenFeedback::None,
+        }
+    }
+
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        self.info.view(frame, area)
+    }
+}
+
+struct PruneRunReportsFormController {
+    input: TextInput<DefaultTextInputParser<u32>>,
+}
+
+impl PruneRunReportsFormController {
+    fn new() -> Self {
+        PruneRunReportsFormController {
+            input: TextInput::new_init_value("Days", DEFAULT_REPORT_AGE_DAYS),
+        }
+    }
+}
+
+impl FormController for PruneRunReportsFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done(days) => FormStatus::Done {
+                task: Task::Maintenance(MaintenanceTask::PruneRunReports(days)),
+                block: true,
+            },
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Delete old run reports"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        "Delete reports older than this many days, baselines are kept"
+    }
+
+    fn step_index(&self) -> u8 {
+        0
+    }
+
+    fn steps_number(&self) -> u8 {
+        1
+    }
+}
+
+struct ConfirmPruneIdentitiesFormController {
+    input: SelectInput<String>,
+}
+
+impl ConfirmPruneIdentitiesFormController {
+    fn new() -> Self {
+        ConfirmPruneIdentitiesFormController {
+            input: SelectInput::new(vec!["No".to_string(), "Yes".to_string()]),
+        }
+    }
+}
+
+impl FormController for ConfirmPruneIdentitiesFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done(answer) if answer == "Yes" => FormStatus::Done {
+                task: Task::Maintenance(MaintenanceTask::PruneEmptyIdentities),
+                block: true,
+            },
+            InputStatus::Done(_) => FormStatus::Exit,
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Forget identities with no balance and no keys"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        "Confirm, the loaded identity is kept"
+    }
+
+    fn step_index(&self) -> u8 {
+        0
+    }
+
+    fn steps_number(&self) -> u8 {
+        1
+    }
+}
@@ -15,7 +15,7 @@ use crate::backend::platform_info::PlatformInfoTask::{
 use crate::{
     backend::{
         platform_info::PlatformInfoTask::{
-            FetchCurrentEpochInfo, FetchCurrentVersionVotingState, FetchSpecificEpochInfo,
+            self, FetchCurrentEpochInfo, FetchCurrentVersionVotingState, FetchSpecificEpochInfo,
         },
         AppState, BackendEvent, Task,
     },
@@ -32,7 +32,7 @@ use crate::{
     Event,
 };
 
-const COMMAND_KEYS: [ScreenCommandKey; 10] = [
+const COMMAND_KEYS: [ScreenCommandKey; 11] = [
     ScreenCommandKey::new("q", "Back to Main"),
     ScreenCommandKey::new("c", "Fetch current Platform epoch info"),
     ScreenCommandKey::new("m", "Fetch total credits on platform"),
@@ -43,10 +43,13 @@ const COMMAND_KEYS: [ScreenCommandKey; 10] = [
     ScreenCommandKey::new("l", "View Reduced Proposer Queues"),
     ScreenCommandKey::new("w", "View Withdrawals in Queue"),
     ScreenCommandKey::new("e", "View Recently Completed Withdrawals"),
+    ScreenCommandKey::new("C-r", "Refresh shown info"),
 ];
 
 pub(crate) struct PlatformInfoScreenController {
     info: Info,
+    /// Task of the info shown, fetched again on refresh
+    shown_task: Option<PlatformInfoTask>,
 }
 
 impl_builder!(PlatformInfoScreenController);
@@ -55,6 +58,7 @@ impl PlatformInfoScreenController {
     pub(crate) async fn new(_app_state: &AppState) -> Self {
         PlatformInfoScreenController {
             info: Info::new_scrollable("Platform info"),
+            shown_task: None,
         }
     }
 }
@@ -148,6 +152,14 @@ impl ScreenController for PlatformInfoScreenController {
                 modifiers: KeyModifiers::NONE,
             }) => ScreenFeedback::Form(Box::new(EpochNumberChooserFormController::new())),
 
+            Event::Key(KeyEvent {
+                code: Key::Char('r'),
+                modifiers: KeyModifiers::CONTROL,
+            }) => ScreenFeedback::Task {
+                task: Task::PlatformInfo(self.shown_task.clone().unwrap_or(FetchCurrentEpochInfo)),
+                block: true,
+            },
+
             // Forward event to upper part of the screen for scrolls and stuff
             Event::Key(k) => {
                 if self.info.on_event(k) {
@@ -158,10 +170,11 @@ impl ScreenController for PlatformInfoScreenController {
             }
 
             Event::Backend(BackendEvent::TaskCompleted {
-                task: Task::PlatformInfo(_),
+                task: Task::PlatformInfo(task),
                 execution_result,
             }) => {
                 self.info = Info::new_from_result(execution_result);
+                self.shown_task = Some(task.clone());
                 ScreenFeedback::Redraw
             }
             _ => ScreenFeedback::None,
@@ -192,9 +205,7 @@ impl FormController for EpochNumberChooserFormController {
                 task: Task::PlatformInfo(FetchSpecificEpochInfo(epoch)),
                 block: true,
             },
-            InputStatus::Redraw => FormStatus::Redraw,
-            InputStatus::None => FormStatus::None,
-            InputStatus::Exit => FormStatus::Exit,
+            status => status.into(),
         }
     }
 
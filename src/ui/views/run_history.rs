@@ -0,0 +1,219 @@
+//! Past strategy runs and comparisons between two of them.
+
+use std::sync::Arc;
+
+use tokio::sync::watch;
+use tuirealm::{
+    event::{Key, KeyEvent, KeyModifiers},
+    props::TextSpan,
+    tui::prelude::{Constraint, Direction, Layout, Rect},
+    Frame,
+};
+
+use crate::{
+    backend::{
+        run_history::{self, RunHistory, RunRecord},
+        snapshot::AppStateSnapshot,
+        AppState,
+    },
+    ui::screen::{
+        utils::impl_builder,
+        widgets::{info::Info, virtual_list::VirtualList},
+        ScreenCommandKey, ScreenController, ScreenFeedback, ScreenToggleKey,
+    },
+    Event,
+};
+
+const COMMAND_KEYS: [ScreenCommandKey; 5] = [
+    ScreenCommandKey::new("q", "Back to Main"),
+    ScreenCommandKey::new("m", "Mark selected run to compare with"),
+    ScreenCommandKey::new("u", "Unmark run"),
+    ScreenCommandKey::new("C-n", "Next run"),
+    ScreenCommandKey::new("C-p", "Prev run"),
+];
+
+pub(crate) struct RunHistoryScreenController {
+    snapshots: watch::Receiver<Arc<AppStateSnapshot>>,
+    history: RunHistory,
+    /// Run the selected one is compared to, by default the previous run of
+    /// the same strategy
+    marked: Option<u64>,
+    run_select: VirtualList,
+    comparison_view: Info,
+}
+
+impl_builder!(RunHistoryScreenController);
+
+impl RunHistoryScreenController {
+    pub(crate) async fn new(app_state: &AppState) -> Self {
+        let mut controller = RunHistoryScreenController {
+            snapshots: app_state.subscribe_snapshot(),
+            history: RunHistory::default(),
+            marked: None,
+            run_select: VirtualList::new(Vec::new(), 0),
+            comparison_view: Info::new_fixed(""),
+        };
+        controller.reload(&app_state.snapshot());
+        controller
+    }
+
+    /// Picks up runs from the latest snapshot, keeping the selection on the
+    /// same run.
+    fn reload(&mut self, snapshot: &AppStateSnapshot) {
+        let history = snapshot.run_history.clone();
+        let selected = self
+            .selected_run()
+            .and_then(|previous| {
+                history
+                    .runs
+                    .iter()
+                    .rev()
+                    .position(|record| record.id == previous.id)
+            })
+            .unwrap_or_default();
+        self.history = history;
+        if self.marked.is_some_and(|id| self.history.get(id).is_none()) {
+            self.marked = None;
+        }
+        self.update_rows(selected);
+    }
+
+    /// Lists the runs again, with the marked one flagged.
+    fn update_rows(&mut self, selected: usize) {
+        let rows = self
+            .history
+            .runs
+            .iter()
+            .rev()
+            .map(|record| {
+                let mark = if Some(record.id) == self.marked {
+                    "* "
+                } else {
+                    ""
+                };
+                vec![TextSpan::new(format!("{}{}", mark, record))]
+            })
+            .collect();
+        self.run_select = VirtualList::new(rows, selected);
+        self.run_select.set_title(Some(format!(
+            "Past runs, latest first: {}",
+            self.history.runs.len()
+        )));
+        self.update_comparison();
+    }
+
+    fn selected_run(&self) -> Option<&RunRecord> {
+        self.history
+            .runs
+            .iter()
+            .rev()
+            .nth(self.run_select.selected())
+    }
+
+    /// Run the selected one is compared to: the marked one, or else the
+    /// previous run of the same strategy.
+    fn compared_run(&self, selected: &RunRecord) -> Option<&RunRecord> {
+        match self.marked {
+            Some(id) if id != selected.id => self.history.get(id),
+            Some(_) => None,
+            None => self.history.runs.iter().rev().find(|record| {
+                record.id < selected.id && record.strategy_name == selected.strategy_name
+            }),
+        }
+    }
+
+    fn update_comparison(&mut self) {
+        self.comparison_view = match self.selected_run() {
+            None => Info::new_fixed("No strategy runs yet"),
+            Some(selected) => match self.compared_run(selected) {
+                Some(compared) => {
+                    let (before, after) = if compared.id < selected.id {
+                        (compared, selected)
+                    } else {
+                        (selected, compared)
+                    };
+                    Info::new_scrollable(&run_history::compare(before, after))
+                }
+                None => Info::new_fixed(&format!(
+                    "{}\n\nNothing to compare with, mark another run with m",
+                    selected
+                )),
+            },
+        };
+    }
+}
+
+impl ScreenController for RunHistoryScreenController {
+    fn name(&self) -> &'static str {
+        "Run history"
+    }
+
+    fn command_keys(&self) -> &[ScreenCommandKey] {
+        COMMAND_KEYS.as_ref()
+    }
+
+    fn toggle_keys(&self) -> &[ScreenToggleKey] {
+        &[]
+    }
+
+    fn on_event(&mut self, event: &Event) -> ScreenFeedback {
+        match event {
+            Event::Key(KeyEvent {
+                code: Key::Char('q'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::PreviousScreen,
+
+            Event::Key(KeyEvent {
+                code: Key::Char('m'),
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                self.marked = self.selected_run().map(|record| record.id);
+                self.update_rows(self.run_select.selected());
+                ScreenFeedback::Redraw
+            }
+
+            Event::Key(KeyEvent {
+                code: Key::Char('u'),
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                self.marked = None;
+                self.update_rows(self.run_select.selected());
+                ScreenFeedback::Redraw
+            }
+
+            Event::Key(k) => {
+                if self.run_select.on_event(k) {
+                    self.update_comparison();
+                    ScreenFeedback::Redraw
+                } else if self.comparison_view.on_event(k) {
+                    ScreenFeedback::Redraw
+                } else {
+                    ScreenFeedback::None
+                }
+            }
+
+            // Follows runs completed while the screen is open
+            Event::Tick { .. } => {
+                if self.snapshots.has_changed().unwrap_or(false) {
+                    let snapshot = self.snapshots.borrow_and_update().clone();
+                    self.reload(&snapshot);
+                    ScreenFeedback::Redraw
+                } else {
+                    ScreenFeedback::None
+                }
+            }
+
+            _ => ScreenFeedback::None,
+        }
+    }
+
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(35), Constraint::Percentage(65)].as_ref())
+            .split(area);
+
+        self.run_select.view(frame, layout[0]);
+        self.comparison_view.view(frame, layout[1]);
+    }
+}
@@ -0,0 +1,297 @@
+//! Scheduled strategy runs and the outcome of past ones.
+
+use std::sync::Arc;
+
+use tokio::sync::watch;
+use tuirealm::{
+    event::{Key, KeyEvent, KeyModifiers},
+    props::TextSpan,
+    tui::prelude::{Constraint, Direction, Layout, Rect},
+    Frame,
+};
+
+use crate::{
+    backend::{
+        run_schedule::{RunSchedule, RunSchedules},
+        snapshot::AppStateSnapshot,
+        AppState, BackendEvent, StrategyTask, Task,
+    },
+    ui::{
+        form::{
+            parsers::{CreditsTextInputParser, DefaultTextInputParser, StartTimeTextInputParser},
+            ComposedInput, Field, FormController, FormStatus, Input, InputStatus, SelectInput,
+            TextInput,
+        },
+        screen::{
+            utils::impl_builder,
+            widgets::{info::Info, virtual_list::VirtualList},
+            ScreenCommandKey, ScreenController, ScreenFeedback, ScreenToggleKey,
+        },
+    },
+    Event,
+};
+
+const COMMAND_KEYS: [ScreenCommandKey; 5] = [
+    ScreenCommandKey::new("q", "Back to Main"),
+    ScreenCommandKey::new("n", "New schedule"),
+    ScreenCommandKey::new("x", "Cancel selected schedule"),
+    ScreenCommandKey::new("C-n", "Next schedule"),
+    ScreenCommandKey::new("C-p", "Prev schedule"),
+];
+
+pub(crate) struct SchedulesScreenController {
+    snapshots: watch::Receiver<Arc<AppStateSnapshot>>,
+    schedules: RunSchedules,
+    available_strategies: Vec<String>,
+    schedule_select: VirtualList,
+    history_view: Info,
+}
+
+impl_builder!(SchedulesScreenController);
+
+impl SchedulesScreenController {
+    pub(crate) async fn new(app_state: &AppState) -> Self {
+        let snapshot = app_state.snapshot();
+        let mut controller = SchedulesScreenController {
+            snapshots: app_state.subscribe_snapshot(),
+            schedules: RunSchedules::default(),
+            available_strategies: snapshot.available_strategies.keys().cloned().collect(),
+            schedule_select: VirtualList::new(Vec::new(), 0),
+            history_view: Info::new_fixed(""),
+        };
+        controller.reload(&snapshot);
+        controller
+    }
+
+    /// Picks up schedules and runs from the latest snapshot, keeping the
+    /// selection on the same schedule if it's still pending.
+    fn reload(&mut self, snapshot: &AppStateSnapshot) {
+        let schedules = snapshot.run_schedules.clone();
+        let selected = self
+            .selected_schedule()
+            .and_then(|previous| {
+                schedules
+                    .pending
+                    .iter()
+                    .position(|schedule| schedule.id == previous.id)
+            })
+            .unwrap_or_default();
+        self.schedules = schedules;
+
+        let rows = self
+            .schedules
+            .pending
+            .iter()
+            .map(|schedule| vec![TextSpan::new(schedule.to_string())])
+            .collect();
+        self.schedule_select = VirtualList::new(rows, selected);
+        self.schedule_select.set_title(Some(format!(
+            "Pending schedules: {}",
+            self.schedules.pending.len()
+        )));
+
+        self.history_view = if self.schedules.history.is_empty() {
+            Info::new_fixed("No scheduled runs yet")
+        } else {
+            let text = self
+                .schedules
+                .history
+                .iter()
+                .rev()
+                .map(|record| format!("{} {}", if record.success { "✓" } else { "✗" }, record))
+                .collect::<Vec<_>>()
+                .join("\n");
+            Info::new_scrollable(&format!("Past runs, latest first:\n{}", text))
+        };
+    }
+
+    fn selected_schedule(&self) -> Option<&RunSchedule> {
+        self.schedules.pending.get(self.schedule_select.selected())
+    }
+}
+
+impl ScreenController for SchedulesScreenController {
+    fn name(&self) -> &'static str {
+        "Scheduled runs"
+    }
+
+    fn command_keys(&self) -> &[ScreenCommandKey] {
+        COMMAND_KEYS.as_ref()
+    }
+
+    fn toggle_keys(&self) -> &[ScreenToggleKey] {
+        &[]
+    }
+
+    fn on_event(&mut self, event: &Event) -> ScreenFeedback {
+        match event {
+            Event::Key(KeyEvent {
+                code: Key::Char('q'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::PreviousScreen,
+
+            Event::Key(KeyEvent {
+                code: Key::Char('n'),
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                if self.available_strategies.is_empty() {
+                    self.history_view = Info::new_error("No strategies to schedule");
+                    ScreenFeedback::Redraw
+                } else {
+                    ScreenFeedback::Form(Box::new(ScheduleRunFormController::new(
+                        self.available_strategies.clone(),
+                    )))
+                }
+            }
+
+            Event::Key(KeyEvent {
+                code: Key::Char('x'),
+                modifiers: KeyModifiers::NONE,
+            }) => match self.selected_schedule() {
+                Some(schedule) => ScreenFeedback::Task {
+                    task: Task::Strategy(StrategyTask::CancelSchedule(schedule.id)),
+                    block: false,
+                },
+                None => ScreenFeedback::None,
+            },
+
+            Event::Key(k) => {
+                if self.schedule_select.on_event(k) || self.history_view.on_event(k) {
+                    ScreenFeedback::Redraw
+                } else {
+                    ScreenFeedback::None
+                }
+            }
+
+            Event::Backend(BackendEvent::TaskCompleted {
+                task: Task::Strategy(StrategyTask::ScheduleRun(_) | StrategyTask::CancelSchedule(_)),
+                execution_result: Err(error),
+            }) => {
+                self.history_view = Info::new_error(error);
+                ScreenFeedback::Redraw
+            }
+
+            // Follows schedules changed by any task, scheduled runs included
+            Event::Tick { .. } => {
+                if self.snapshots.has_changed().unwrap_or(false) {
+                    let snapshot = self.snapshots.borrow_and_update().clone();
+                    self.reload(&snapshot);
+                    ScreenFeedback::Redraw
+                } else {
+                    ScreenFeedback::None
+                }
+            }
+
+            _ => ScreenFeedback::None,
+        }
+    }
+
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+            .split(area);
+
+        self.schedule_select.view(frame, layout[0]);
+        self.history_view.view(frame, layout[1]);
+    }
+}
+
+struct ScheduleRunFormController {
+    input: ComposedInput<(
+        Field<SelectInput<String>>,                    // Strategy
+        Field<TextInput<StartTimeTextInputParser>>,    // First run
+        Field<TextInput<DefaultTextInputParser<u64>>>, // Repeat hours
+        Field<TextInput<DefaultTextInputParser<u64>>>, // Duration seconds
+        Field<TextInput<DefaultTextInputParser<u64>>>, // Seconds per loop
+        Field<SelectInput<String>>,                    // Verify proofs?
+        Field<TextInput<CreditsTextInputParser>>,      // Spend cap
+    )>,
+}
+
+impl ScheduleRunFormController {
+    fn new(strategies: Vec<String>) -> Self {
+        ScheduleRunFormController {
+            input: ComposedInput::new((
+                Field::new("Strategy to run", SelectInput::new(strategies)),
+                Field::new(
+                    "First run at",
+                    TextInput::new_str_value_with_parser(
+                        StartTimeTextInputParser,
+                        "Start time",
+                        "now",
+                    ),
+                ),
+                Field::new(
+                    "Repeat every this many hours (0 to run once)",
+                    TextInput::new_init_value("Enter a whole number", 0),
+                ),
+                Field::new(
+                    "Number of seconds to run the strategy",
+                    TextInput::new("Enter a whole number"),
+                ),
+                Field::new(
+                    "Number of seconds between each batch of transitions",
+                    TextInput::new("Enter a whole number"),
+                ),
+                Field::new(
+                    "Verify proofs?",
+                    SelectInput::new(vec!["No".to_string(), "Yes".to_string()]),
+                ),
+                Field::new(
+                    "Maximum amount each run may spend (0 for no cap, required on mainnet)",
+                    TextInput::new_str_value_with_parser(CreditsTextInputParser, "Amount", "0"),
+                ),
+            )),
+        }
+    }
+}
+
+impl FormController for ScheduleRunFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done((
+                strategy_name,
+                next_run,
+                repeat_hours,
+                duration_secs,
+                seconds_per_loop,
+                verify_proofs,
+                spend_cap,
+            )) => FormStatus::Done {
+                task: Task::Strategy(StrategyTask::ScheduleRun(RunSchedule {
+                    id: 0,
+                    strategy_name,
+                    duration_secs,
+                    seconds_per_loop,
+                    verify_proofs: verify_proofs == "Yes",
+                    spend_cap: (spend_cap > 0).then_some(spend_cap),
+                    next_run,
+                    repeat_hours: (repeat_hours > 0).then_some(repeat_hours),
+                })),
+                block: false,
+            },
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Schedule strategy run"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        self.input.step_name()
+    }
+
+    fn step_index(&self) -> u8 {
+        self.input.step_index()
+    }
+
+    fn steps_number(&self) -> u8 {
+        self.input.steps_number()
+    }
+}
@@ -6,19 +6,33 @@ mod run_strategy;
 pub mod selected_strategy;
 mod start_contracts;
 mod start_identities;
+mod templates;
 
+use std::path::Path;
+
+use dpp::platform_value::{string_encoding::Encoding, Identifier};
+use strategy_tests::frequency::Frequency;
 use tuirealm::{
     event::{Key, KeyEvent, KeyModifiers},
     tui::prelude::Rect,
     Frame,
 };
 
-use self::selected_strategy::SelectedStrategyScreenController;
+use self::{
+    selected_strategy::SelectedStrategyScreenController,
+    templates::StrategyTemplatesScreenController,
+};
 use crate::{
-    backend::{AppState, AppStateUpdate, BackendEvent},
-    ui::screen::{
-        utils::impl_builder, widgets::info::Info, ScreenCommandKey, ScreenController,
-        ScreenFeedback, ScreenToggleKey,
+    backend::{
+        state::ContractAliases, strategies::add_random_variant_aliases, AppState, AppStateUpdate,
+        BackendEvent,
+    },
+    ui::{
+        id_names::{shown_id, IdNames},
+        screen::{
+            utils::impl_builder, widgets::info::Info, ScreenCommandKey, ScreenController,
+            ScreenFeedback, ScreenToggleKey,
+        },
     },
     Event,
 };
@@ -26,18 +40,26 @@ use crate::{
 use crate::{
     backend::{StrategyTask, Task},
     ui::form::{
-        parsers::DefaultTextInputParser, FormController, FormStatus, Input, InputStatus,
-        SelectInput, TextInput,
+        parsers::DefaultTextInputParser, ComposedInput, Field, FormController, FormStatus, Input,
+        InputStatus, SelectInput, TextInput,
     },
 };
 
-const COMMAND_KEYS: [ScreenCommandKey; 6] = [
+/// Where strategy files are suggested to go, next to the binary exports
+const STRATEGY_FILES_DIR: &str = "supporting_files/strategy_exports";
+
+const COMMAND_KEYS: [ScreenCommandKey; 11] = [
     ScreenCommandKey::new("q", "Back to Main"),
     ScreenCommandKey::new("n", "New strategy"),
+    ScreenCommandKey::new("t", "New strategy from a template"),
     ScreenCommandKey::new("i", "Import a strategy"),
+    ScreenCommandKey::new("f", "Import a strategy from a TOML/JSON file"),
+    ScreenCommandKey::new("b", "Benchmark transition signing"),
     ScreenCommandKey::new("e", "Export a strategy"),
+    ScreenCommandKey::new("x", "Export a strategy to a TOML/JSON file"),
     ScreenCommandKey::new("s", "Select a strategy"),
     ScreenCommandKey::new("d", "Delete a strategy"),
+    ScreenCommandKey::new("p", "Export a strategy as a strategy-tests fixture"),
 ];
 
 pub(crate) struct StrategiesScreenController {
@@ -50,8 +72,9 @@ impl_builder!(StrategiesScreenController);
 
 impl StrategiesScreenController {
     pub(crate) async fn new(app_state: &AppState) -> Self {
-        let available_strategies_lock = app_state.available_strategies.lock().await;
-        let strategies = available_strategies_lock
+        let strategies = app_state
+            .snapshot()
+            .available_strategies
             .keys()
             .cloned()
             .collect::<Vec<_>>();
@@ -78,7 +101,7 @@ impl ScreenController for StrategiesScreenController {
 
     fn command_keys(&self) -> &[ScreenCommandKey] {
         if self.available_strategies.is_empty() {
-            &COMMAND_KEYS[..3] // Exclude certain operations when there are no available strategies
+            &COMMAND_KEYS[..6] // Exclude certain operations when there are no available strategies
         } else {
             COMMAND_KEYS.as_ref()
         }
@@ -101,6 +124,10 @@ impl ScreenController for StrategiesScreenController {
                 form: Box::new(NewStrategyFormController::new()),
                 screen: SelectedStrategyScreenController::builder(),
             },
+            Event::Key(KeyEvent {
+                code: Key::Char('t'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::NextScreen(StrategyTemplatesScreenController::builder()),
             Event::Key(KeyEvent {
                 code: Key::Char('i'),
                 modifiers: KeyModifiers::NONE,
@@ -108,6 +135,44 @@ impl ScreenController for StrategiesScreenController {
                 form: Box::new(ImportStrategyFormController::new()),
                 screen: SelectedStrategyScreenController::builder(),
             },
+            Event::Key(KeyEvent {
+                code: Key::Char('f'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::FormThenNextScreen {
+                form: Box::new(ImportStrategyFileFormController::new()),
+                screen: SelectedStrategyScreenController::builder(),
+            },
+            Event::Key(KeyEvent {
+                code: Key::Char('b'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::Task {
+                task: Task::Strategy(StrategyTask::SigningBenchmark),
+                block: true,
+            },
+            Event::Key(KeyEvent {
+                code: Key::Char('x'),
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                if !self.available_strategies.is_empty() {
+                    ScreenFeedback::Form(Box::new(ExportStrategyFileFormController::new(
+                        self.available_strategies.clone(),
+                    )))
+                } else {
+                    ScreenFeedback::None
+                }
+            }
+            Event::Key(KeyEvent {
+                code: Key::Char('p'),
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                if !self.available_strategies.is_empty() {
+                    ScreenFeedback::Form(Box::new(ExportStrategyFixtureFormController::new(
+                        self.available_strategies.clone(),
+                    )))
+                } else {
+                    ScreenFeedback::None
+                }
+            }
             Event::Key(KeyEvent {
                 code: Key::Char('e'),
                 modifiers: KeyModifiers::NONE,
@@ -291,6 +356,123 @@ impl FormController for ImportStrategyFormController {
     }
 }
 
+pub(crate) struct ImportStrategyFileFormController {
+    input: TextInput<DefaultTextInputParser<String>>,
+}
+
+impl ImportStrategyFileFormController {
+    pub(crate) fn new() -> Self {
+        Self {
+            input: TextInput::new_init_value(
+                "Path to a .toml or .json file, the strategy is named after it",
+                format!("{}/", STRATEGY_FILES_DIR),
+            ),
+        }
+    }
+}
+
+impl FormController for ImportStrategyFileFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done(path) => FormStatus::Done {
+                task: Task::Strategy(StrategyTask::ImportStrategyFile(path)),
+                block: false,
+            },
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Import strategy from file"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        "Path"
+    }
+
+    fn step_index(&self) -> u8 {
+        0
+    }
+
+    fn steps_number(&self) -> u8 {
+        1
+    }
+}
+
+pub(super) struct ExportStrategyFileFormController {
+    input: ComposedInput<(
+        Field<SelectInput<String>>,                       // Strategy
+        Field<TextInput<DefaultTextInputParser<String>>>, // Path
+    )>,
+}
+
+impl ExportStrategyFileFormController {
+    pub(super) fn new(strategies: Vec<String>) -> Self {
+        Self {
+            input: ComposedInput::new((
+                Field::new("Strategy", SelectInput::new(strategies)),
+                Field::new(
+                    "File, a directory to name it after the strategy",
+                    TextInput::new_init_value(
+                        "Path to a .toml or .json file or to a directory",
+                        format!("{}/", STRATEGY_FILES_DIR),
+                    ),
+                ),
+            )),
+        }
+    }
+}
+
+impl FormController for ExportStrategyFileFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done((strategy_name, path)) => {
+                // TOML unless a file is named
+                let path = if path.is_empty() || path.ends_with('/') || Path::new(&path).is_dir() {
+                    Path::new(&path)
+                        .join(format!("{}.toml", strategy_name))
+                        .display()
+                        .to_string()
+                } else {
+                    path
+                };
+                FormStatus::Done {
+                    task: Task::Strategy(StrategyTask::ExportStrategyFile {
+                        strategy_name,
+                        path,
+                    }),
+                    block: false,
+                }
+            }
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Export strategy to file"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        self.input.step_name()
+    }
+
+    fn step_index(&self) -> u8 {
+        self.input.step_index()
+    }
+
+    fn steps_number(&self) -> u8 {
+        self.input.steps_number()
+    }
+}
+
 pub(super) struct ExportStrategyFormController {
     input: SelectInput<String>,
 }
@@ -336,6 +518,50 @@ impl FormController for ExportStrategyFormController {
     }
 }
 
+struct ExportStrategyFixtureFormController {
+    input: SelectInput<String>,
+}
+
+impl ExportStrategyFixtureFormController {
+    fn new(strategies: Vec<String>) -> Self {
+        Self {
+            input: SelectInput::new(strategies),
+        }
+    }
+}
+
+impl FormController for ExportStrategyFixtureFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done(strategy_name) => FormStatus::Done {
+                task: Task::Strategy(StrategyTask::ExportStrategyFixture(strategy_name)),
+                block: false,
+            },
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Strategy fixture export"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        "Strategy"
+    }
+
+    fn step_index(&self) -> u8 {
+        0
+    }
+
+    fn steps_number(&self) -> u8 {
+        1
+    }
+}
+
 pub(super) struct SelectStrategyFormController {
     input: SelectInput<String>,
 }
@@ -457,3 +683,46 @@ impl FormController for DeleteStrategyFormController {
         2
     }
 }
+
+/// Displays a frequency's times per block, either as a single number or as an
+/// inclusive `min..max` range.
+fn times_per_block_display(frequency: &Frequency) -> String {
+    let range = &frequency.times_per_block_range;
+    if range.end <= range.start {
+        range.end.to_string()
+    } else if range.end - range.start == 1 {
+        range.start.to_string()
+    } else {
+        format!("{}..{}", range.start, range.end - 1)
+    }
+}
+
+/// Displays a strategy contract name, which is a Base58 ID for random
+/// contracts, together with its alias or known contract name if it has one.
+fn contract_display(contract_name: &str, aliases: &ContractAliases, id_names: &IdNames) -> String {
+    let contract_id = id_names.contract_id(contract_name);
+    match contract_id.and_then(|id| aliases.get(&id).map(|alias| (id, alias))) {
+        Some((id, alias)) => format!("{} ({})", alias, shown_id(&id.to_string(Encoding::Base58))),
+        None => id_names.contract_b58(contract_name),
+    }
+}
+
+/// Keeps a screen's aliases in sync with the ones the backend gives to random
+/// start contracts, as the strategy update doesn't carry them.
+fn apply_random_variant_aliases(event: &Event, aliases: &mut ContractAliases) {
+    if let Event::Backend(BackendEvent::TaskCompletedStateChange {
+        task: Task::Strategy(StrategyTask::SetStartContractsRandom(_, contract_name, variants)),
+        execution_result: Ok(_),
+        app_state_update: AppStateUpdate::SelectedStrategy(_, _, contract_names),
+    }) = event
+    {
+        let added = contract_names.len().saturating_sub(*variants as usize);
+        add_random_variant_aliases(
+            aliases,
+            contract_name,
+            contract_names[added..]
+                .iter()
+                .filter_map(|(id, _)| Identifier::from_string(id, Encoding::Base58).ok()),
+        );
+    }
+}
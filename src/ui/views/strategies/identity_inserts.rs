@@ -7,12 +7,13 @@ use tuirealm::{
     Frame,
 };
 
+use super::times_per_block_display;
 use crate::{
     backend::{AppState, AppStateUpdate, BackendEvent, StrategyTask, Task},
     ui::{
         form::{
-            parsers::DefaultTextInputParser, ComposedInput, Field, FormController, FormStatus,
-            Input, InputStatus, SelectInput, TextInput,
+            parsers::TimesPerBlockRangeTextInputParser, ComposedInput, Field, FormController,
+            FormStatus, Input, InputStatus, SelectInput, TextInput,
         },
         screen::{
             utils::impl_builder, widgets::info::Info, ScreenCommandKey, ScreenController,
@@ -40,12 +41,11 @@ impl_builder!(IdentityInsertsScreenController);
 
 impl IdentityInsertsScreenController {
     pub(crate) async fn new(app_state: &AppState) -> Self {
-        let available_strategies_lock = app_state.available_strategies.lock().await;
-        let selected_strategy_lock = app_state.selected_strategy.lock().await;
+        let snapshot = app_state.snapshot();
 
         let (info_text, current_strategy) =
-            if let Some(selected_strategy_name) = &*selected_strategy_lock {
-                if let Some(strategy) = available_strategies_lock.get(selected_strategy_name) {
+            if let Some(selected_strategy_name) = &snapshot.selected_strategy {
+                if let Some(strategy) = snapshot.available_strategies.get(selected_strategy_name) {
                     let info_text = format!("Selected Strategy: {}", selected_strategy_name);
                     (info_text, Some(strategy.clone()))
                 } else {
@@ -59,7 +59,7 @@ impl IdentityInsertsScreenController {
 
         Self {
             info,
-            strategy_name: selected_strategy_lock.clone(),
+            strategy_name: snapshot.selected_strategy.clone(),
             selected_strategy: current_strategy,
         }
     }
@@ -129,34 +129,12 @@ impl ScreenController for IdentityInsertsScreenController {
         let display_text = if let Some(strategy) = &self.selected_strategy {
             // Extracting times per block and chance per block from
             // strategy.identity_inserts
-            let times_per_block_display = if strategy
-                .identity_inserts
-                .frequency
-                .times_per_block_range
-                .end
-                > strategy
-                    .identity_inserts
-                    .frequency
-                    .times_per_block_range
-                    .start
-            {
-                strategy
-                    .identity_inserts
-                    .frequency
-                    .times_per_block_range
-                    .end
-                    - 1
-            } else {
-                strategy
-                    .identity_inserts
-                    .frequency
-                    .times_per_block_range
-                    .end
-            };
+            let times_per_block_display =
+                times_per_block_display(&strategy.identity_inserts.frequency);
 
             let mut identity_inserts_text = String::new();
 
-            if times_per_block_display == 0 {
+            if times_per_block_display == "0" {
                 identity_inserts_text = format!(
                     "Identity inserts:\nTimes per block: {}; Chance per block: {}",
                     times_per_block_display,
@@ -196,7 +174,7 @@ impl ScreenController for IdentityInsertsScreenController {
 
 pub(super) struct StrategyIdentityInsertsFormController {
     input: ComposedInput<(
-        Field<TextInput<DefaultTextInputParser<u16>>>,
+        Field<TextInput<TimesPerBlockRangeTextInputParser>>,
         Field<SelectInput<f64>>,
     )>,
     selected_strategy: String,
@@ -206,7 +184,13 @@ impl StrategyIdentityInsertsFormController {
     pub(super) fn new(selected_strategy: String) -> Self {
         StrategyIdentityInsertsFormController {
             input: ComposedInput::new((
-                Field::new("Times per block", TextInput::new("Enter a whole number")),
+                Field::new(
+                    "Times per block",
+                    TextInput::new_with_parser(
+                        TimesPerBlockRangeTextInputParser,
+                        "Enter a whole number or a range like 1..5",
+                    ),
+                ),
                 Field::new(
                     "Chance per block",
                     SelectInput::new(vec![1.0, 0.9, 0.75, 0.5, 0.25, 0.1, 0.05, 0.01]),
@@ -224,7 +208,7 @@ impl FormController for StrategyIdentityInsertsFormController {
                 task: Task::Strategy(StrategyTask::SetIdentityInserts {
                     strategy_name: self.selected_strategy.clone(),
                     identity_inserts_frequency: Frequency {
-                        times_per_block_range: count..count + 1,
+                        times_per_block_range: count,
                         chance_per_block: Some(chance),
                     },
                     start_balance: dash_to_credits!(1), //todo, set this
@@ -18,7 +18,15 @@ use dpp::{
 };
 use identity_transfer::StrategyOpIdentityTransferSpecificFormController;
 use tracing::error;
-use tuirealm::{event::KeyEvent, tui::prelude::Rect, Frame};
+use tuirealm::{
+    event::KeyEvent,
+    props::{Color, Style},
+    tui::{
+        prelude::{Constraint, Direction, Layout, Modifier, Rect},
+        widgets::{Block, Borders, Paragraph, Row, Table, TableState},
+    },
+    Frame,
+};
 
 use self::{
     contract_create::StrategyOpContractCreateFormController,
@@ -26,17 +34,29 @@ use self::{
     document::StrategyOpDocumentFormController,
     identity_top_up::StrategyOpIdentityTopUpFormController,
     identity_transfer::StrategyOpIdentityTransferRandomFormController,
-    identity_update::StrategyOpIdentityUpdateFormController,
+    identity_update::{
+        StrategyOpIdentityUpdateFormController, StrategyOpKeyMaintenanceFormController,
+    },
     identity_withdrawal::StrategyOpIdentityWithdrawalFormController,
 };
+use super::{contract_display, times_per_block_display};
 use crate::{
-    backend::{StrategyContractNames, StrategyTask, Task},
-    ui::form::{ComposedInput, Field, FormController, FormStatus, Input, InputStatus, SelectInput},
+    backend::{
+        document_generators::{DocumentGeneratorKind, DocumentGenerators},
+        key_maintenance::KeyMaintenanceOp,
+        state::ContractAliases,
+        StrategyContractNames, StrategyTask, Task,
+    },
+    ui::form::{
+        parsers::DefaultTextInputParser, ComposedInput, Field, FormController, FormStatus, Input,
+        InputStatus, SelectInput, TextInput,
+    },
 };
 
 use dpp::{
     data_contract::{
         accessors::v0::DataContractV0Getters, created_data_contract::CreatedDataContract,
+        document_type::accessors::DocumentTypeV0Getters,
     },
     platform_value::string_encoding::Encoding,
     tests::json_document::json_document_to_contract,
@@ -45,7 +65,7 @@ use dpp::{
 use strategy_tests::{
     operations::{
         DataContractUpdateAction::{DataContractNewDocumentTypes, DataContractNewOptionalFields},
-        DocumentAction, OperationType as StrategyOperationType,
+        DocumentAction, Operation, OperationType as StrategyOperationType,
     },
     Strategy,
 };
@@ -54,6 +74,7 @@ use walkdir::WalkDir;
 
 use crate::{
     backend::{AppState, AppStateUpdate, BackendEvent},
+    ui::id_names::IdNames,
     ui::screen::{
         utils::impl_builder, widgets::info::Info, ScreenCommandKey, ScreenController,
         ScreenFeedback, ScreenToggleKey,
@@ -61,12 +82,16 @@ use crate::{
     Event,
 };
 
-const COMMAND_KEYS: [ScreenCommandKey; 5] = [
+const COMMAND_KEYS: [ScreenCommandKey; 9] = [
     ScreenCommandKey::new("q", "Back to Strategy"),
+    ScreenCommandKey::new("↓", "Next operation"),
+    ScreenCommandKey::new("↑", "Prev operation"),
+    ScreenCommandKey::new("Enter", "Expand/collapse details"),
     ScreenCommandKey::new("a", "Add"),
     ScreenCommandKey::new("r", "Remove last"),
     ScreenCommandKey::new("c", "Clear all"),
     ScreenCommandKey::new("x", "Register x documents to all contracts"),
+    ScreenCommandKey::new("g", "Document generator"),
 ];
 
 pub struct OperationsScreenController {
@@ -81,29 +106,31 @@ pub struct OperationsScreenController {
     supporting_contracts: BTreeMap<String, DataContract>,
     strategy_contract_names: BTreeMap<String, Vec<(String, Option<BTreeMap<u64, String>>)>>,
     loaded_identity_id: Option<String>,
+    contract_aliases: ContractAliases,
+    key_maintenance_ops: Vec<KeyMaintenanceOp>,
+    document_generators: DocumentGenerators,
+    id_names: IdNames,
+    selected_row: usize,
+    /// Full details of the selected operation, when expanded
+    details: Option<String>,
 }
 
 impl_builder!(OperationsScreenController);
 
 impl OperationsScreenController {
     pub(crate) async fn new(app_state: &AppState) -> Self {
-        let available_strategies_lock = app_state.available_strategies.lock().await;
-        let selected_strategy_lock = app_state.selected_strategy.lock().await;
-        let known_contracts_lock = app_state.known_contracts.lock().await;
-        let supporting_contracts_lock = app_state.supporting_contracts.lock().await;
-        let strategy_contract_names_lock =
-            app_state.available_strategies_contract_names.lock().await;
-        let loaded_identity = app_state.loaded_identity.lock().await;
-
-        let loaded_identity_id = if let Some(identity) = &*loaded_identity {
+        let snapshot = app_state.snapshot();
+        let selected_strategy = snapshot.selected_strategy.as_ref();
+
+        let loaded_identity_id = if let Some(identity) = &snapshot.loaded_identity {
             Some(identity.id().to_string(Encoding::Base58))
         } else {
             None
         };
 
         let (info_text, current_strategy, current_start_contracts) =
-            if let Some(selected_strategy_name) = &*selected_strategy_lock {
-                if let Some(strategy) = available_strategies_lock.get(selected_strategy_name) {
+            if let Some(selected_strategy_name) = selected_strategy {
+                if let Some(strategy) = snapshot.available_strategies.get(selected_strategy_name) {
                     // Construct the info_text and get the start_contracts for the selected
                     // strategy
                     let info_text = format!("Selected Strategy: {}", selected_strategy_name);
@@ -118,15 +145,51 @@ impl OperationsScreenController {
 
         let info = Info::new_scrollable(&info_text);
 
+        let contract_aliases = if let Some(selected_strategy_name) = selected_strategy {
+            snapshot
+                .strategy_contract_aliases
+                .get(selected_strategy_name)
+                .cloned()
+                .unwrap_or_default()
+        } else {
+            ContractAliases::new()
+        };
+
+        let key_maintenance_ops = if let Some(selected_strategy_name) = selected_strategy {
+            snapshot
+                .strategy_key_maintenance
+                .get(selected_strategy_name)
+                .cloned()
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+
+        let document_generators = if let Some(selected_strategy_name) = selected_strategy {
+            snapshot
+                .strategy_document_generators
+                .get(selected_strategy_name)
+                .cloned()
+                .unwrap_or_default()
+        } else {
+            DocumentGenerators::new()
+        };
+
         Self {
             info,
-            selected_strategy_name: selected_strategy_lock.clone(),
+            selected_strategy_name: snapshot.selected_strategy.clone(),
             selected_strategy: current_strategy,
             start_contracts: current_start_contracts,
-            known_contracts: known_contracts_lock.clone(),
-            supporting_contracts: supporting_contracts_lock.clone(),
-            strategy_contract_names: strategy_contract_names_lock.clone(),
+            known_contracts: snapshot.known_contracts.clone(),
+            supporting_contracts: snapshot.supporting_contracts.clone(),
+            strategy_contract_names: snapshot.strategy_contract_names.clone(),
             loaded_identity_id,
+            contract_aliases,
+            key_maintenance_ops,
+            document_generators,
+            id_names: IdNames::from_snapshot(&snapshot),
+            selected_row: 0,
+            details: None,
         }
     }
 
@@ -221,6 +284,7 @@ impl ScreenController for OperationsScreenController {
                 modifiers: KeyModifiers::NONE,
             }) => {
                 if let Some(strategy_name) = &self.selected_strategy_name {
+                    self.key_maintenance_ops.clear();
                     ScreenFeedback::Task {
                         task: Task::Strategy(StrategyTask::ClearOperations(strategy_name.clone())),
                         block: false,
@@ -241,15 +305,66 @@ impl ScreenController for OperationsScreenController {
                     ScreenFeedback::None
                 }
             }
+            Event::Key(KeyEvent {
+                code: Key::Char('g'),
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                let selected_insert = self
+                    .selected_strategy
+                    .as_ref()
+                    .and_then(|strategy| strategy.operations.get(self.selected_row))
+                    .and_then(|operation| match &operation.op_type {
+                        StrategyOperationType::Document(op)
+                            if matches!(
+                                op.action,
+                                DocumentAction::DocumentActionInsertRandom(..)
+                                    | DocumentAction::DocumentActionInsertSpecific(..)
+                            ) =>
+                        {
+                            Some((
+                                op.contract.id().to_string(Encoding::Base58),
+                                op.document_type.name().to_owned(),
+                            ))
+                        }
+                        _ => None,
+                    });
+                match (self.selected_strategy_name.clone(), selected_insert) {
+                    (Some(strategy_name), Some((contract_id, document_type))) => {
+                        ScreenFeedback::Form(Box::new(DocumentGeneratorFormController::new(
+                            strategy_name,
+                            contract_id,
+                            document_type,
+                        )))
+                    }
+                    _ => ScreenFeedback::None,
+                }
+            }
 
-            // Scrolling
-            Event::Key(
-                key_event @ KeyEvent {
-                    code: Key::Down | Key::Up,
-                    modifiers: KeyModifiers::NONE,
-                },
-            ) => {
-                self.info.on_event(key_event);
+            Event::Key(KeyEvent {
+                code: Key::Down,
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                self.select_row(self.selected_row + 1);
+                ScreenFeedback::Redraw
+            }
+            Event::Key(KeyEvent {
+                code: Key::Up,
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                self.select_row(self.selected_row.saturating_sub(1));
+                ScreenFeedback::Redraw
+            }
+            Event::Key(KeyEvent {
+                code: Key::Enter,
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                self.details = if self.details.is_some() {
+                    None
+                } else {
+                    self.operation_rows()
+                        .get(self.selected_row)
+                        .map(|row| row.details_json())
+                };
                 ScreenFeedback::Redraw
             }
 
@@ -274,93 +389,332 @@ impl ScreenController for OperationsScreenController {
                     self.strategy_contract_names
                         .insert(strategy_name.clone(), contract_names.to_vec());
                 }
+                self.select_row(self.selected_row);
 
                 ScreenFeedback::Redraw
             }
+            Event::Backend(BackendEvent::TaskCompletedStateChange {
+                app_state_update: AppStateUpdate::StrategyKeyMaintenance(strategy_name, ops),
+                ..
+            }) => {
+                if self.selected_strategy_name.as_ref() == Some(strategy_name) {
+                    self.key_maintenance_ops = (*ops).clone();
+                }
+                self.select_row(self.selected_row);
+                ScreenFeedback::Redraw
+            }
+            Event::Backend(BackendEvent::TaskCompletedStateChange {
+                app_state_update:
+                    AppStateUpdate::StrategyDocumentGenerators(strategy_name, generators),
+                ..
+            }) => {
+                if self.selected_strategy_name.as_ref() == Some(strategy_name) {
+                    self.document_generators = (*generators).clone();
+                }
+                self.select_row(self.selected_row);
+                ScreenFeedback::Redraw
+            }
             _ => ScreenFeedback::None,
         }
     }
 
     fn view(&mut self, frame: &mut Frame, area: Rect) {
-        let display_text = if let Some(strategy) = &self.selected_strategy {
-            let mut operations_lines = String::new();
-            for op in &strategy.operations {
-                let op_name = format_operation_name(&op.op_type);
-                let times_per_block_display = if op.frequency.times_per_block_range.end
-                    > op.frequency.times_per_block_range.start
-                {
-                    op.frequency.times_per_block_range.end - 1
-                } else {
-                    op.frequency.times_per_block_range.end
-                };
-                operations_lines.push_str(&format!(
-                    "{:indent$}{}; Times per block: {}, chance per block: {}\n",
-                    "",
-                    op_name,
-                    times_per_block_display,
-                    op.frequency.chance_per_block.unwrap_or(0.0),
-                    indent = 0
-                ));
-            }
-
-            if operations_lines.is_empty() {
-                "No operations defined for this strategy.".to_string()
+        let rows = self.operation_rows();
+        let Some(strategy_name) = self
+            .selected_strategy_name
+            .as_ref()
+            .filter(|_| !rows.is_empty())
+        else {
+            let text = if self.selected_strategy.is_some() {
+                "No operations defined for this strategy."
             } else {
-                format!(
-                    "Strategy: {}\nOperations:\n{}",
-                    self.selected_strategy_name
-                        .as_ref()
-                        .unwrap_or(&"Unknown".to_string()),
-                    operations_lines
-                )
-            }
+                "Select a strategy to view its operations."
+            };
+            self.info = Info::new_fixed(text);
+            self.info.view(frame, area);
+            return;
+        };
+
+        let (table_area, details_area) = if let Some(details) = &self.details {
+            let layout = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(area);
+            (layout[0], Some((details, layout[1])))
         } else {
-            "Select a strategy to view its operations.".to_string()
+            (area, None)
+        };
+
+        let table = Table::new(
+            rows.iter().map(|row| Row::new(row.columns.clone())),
+            [
+                Constraint::Percentage(16),
+                Constraint::Percentage(24),
+                Constraint::Percentage(12),
+                Constraint::Percentage(18),
+                Constraint::Percentage(14),
+                Constraint::Percentage(8),
+                Constraint::Percentage(8),
+            ],
+        )
+        .header(Row::new(OPERATION_COLUMNS).style(Style::default().add_modifier(Modifier::BOLD)))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Strategy: {}", strategy_name)),
+        )
+        .highlight_style(Style::default().fg(Color::Magenta))
+        .highlight_symbol(">");
+        let mut table_state = TableState::default().with_selected(Some(self.selected_row));
+        frame.render_stateful_widget(table, table_area, &mut table_state);
+
+        if let Some((details, details_area)) = details_area {
+            let details = Paragraph::new(details.as_str()).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Operation details"),
+            );
+            frame.render_widget(details, details_area);
+        }
+    }
+}
+
+const OPERATION_COLUMNS: [&str; 7] = [
+    "Operation",
+    "Contract",
+    "Document type",
+    "Action",
+    "Fill",
+    "Times per block",
+    "Chance",
+];
+
+/// An operation as shown in the operations table, with its full details shown
+/// when the row is expanded.
+struct OperationRow {
+    columns: [String; 7],
+    details: serde_json::Value,
+}
+
+impl OperationsScreenController {
+    fn operation_rows(&self) -> Vec<OperationRow> {
+        let Some(strategy) = &self.selected_strategy else {
+            return Vec::new();
         };
+        strategy
+            .operations
+            .iter()
+            .map(|op| {
+                operation_row(
+                    op,
+                    &self.contract_aliases,
+                    &self.document_generators,
+                    &self.id_names,
+                )
+            })
+            .chain(self.key_maintenance_ops.iter().map(key_maintenance_row))
+            .collect()
+    }
 
-        self.info = Info::new_scrollable(&display_text);
-        self.info.view(frame, area);
+    /// Moves the selection, keeping the expanded details on the selected row.
+    fn select_row(&mut self, row: usize) {
+        let rows = self.operation_rows();
+        self.selected_row = row.min(rows.len().saturating_sub(1));
+        if self.details.is_some() {
+            self.details = rows.get(self.selected_row).map(|row| row.details_json());
+        }
+    }
+}
+
+impl OperationRow {
+    fn details_json(&self) -> String {
+        serde_json::to_string_pretty(&self.details).expect("json values are serializable")
     }
 }
 
-// Helper function to format the operation name
-fn format_operation_name(op_type: &StrategyOperationType) -> String {
-    match op_type {
+fn operation_row(
+    op: &Operation,
+    aliases: &ContractAliases,
+    generators: &DocumentGenerators,
+    id_names: &IdNames,
+) -> OperationRow {
+    let contract = |contract: &DataContract| {
+        let id = contract.id().to_string(Encoding::Base58);
+        (contract_display(&id, aliases, id_names), id)
+    };
+    let mut generator = None;
+    let (name, (contract, contract_id), document_type, action, fill) = match &op.op_type {
         StrategyOperationType::Document(op) => {
-            let op_type = match op.action {
-                DocumentAction::DocumentActionInsertRandom(..) => "InsertRandom",
-                DocumentAction::DocumentActionDelete => "Delete",
-                // DocumentAction::DocumentActionReplace => "Replace",
-                _ => "Unknown",
+            if matches!(
+                op.action,
+                DocumentAction::DocumentActionInsertRandom(..)
+                    | DocumentAction::DocumentActionInsertSpecific(..)
+            ) {
+                generator = Some(
+                    generators
+                        .get(&(
+                            op.contract.id().to_string(Encoding::Base58),
+                            op.document_type.name().to_owned(),
+                        ))
+                        .copied()
+                        .unwrap_or_default(),
+                );
+            }
+            let (action, fill) = match &op.action {
+                DocumentAction::DocumentActionInsertRandom(fill_type, fill_size) => (
+                    "InsertRandom".to_owned(),
+                    format!("{:?}, {:?}", fill_type, fill_size),
+                ),
+                DocumentAction::DocumentActionInsertSpecific(values, _, fill_type, fill_size) => (
+                    "InsertSpecific".to_owned(),
+                    format!("{} values, {:?}, {:?}", values.len(), fill_type, fill_size),
+                ),
+                DocumentAction::DocumentActionDelete => ("Delete".to_owned(), String::new()),
+                DocumentAction::DocumentActionReplaceRandom => {
+                    ("ReplaceRandom".to_owned(), String::new())
+                }
+                DocumentAction::DocumentActionTransferRandom => {
+                    ("TransferRandom".to_owned(), String::new())
+                }
+                _ => ("Unknown".to_owned(), String::new()),
             };
-            format!(
-                "Document({}): Contract: {}",
-                op_type,
-                op.contract.id().to_string(Encoding::Base58)
+            (
+                "Document",
+                contract(&op.contract),
+                op.document_type.name().to_owned(),
+                action,
+                fill,
             )
         }
-        StrategyOperationType::IdentityTopUp(amount) => {
-            format!("IdentityTopUp [{}..{}]", amount.start(), amount.end())
-        }
-        StrategyOperationType::IdentityUpdate(op) => format!("IdentityUpdate({:?})", op),
-        StrategyOperationType::IdentityWithdrawal(amount) => {
-            format!("IdentityWithdrawal [{}..{}]", amount.start(), amount.end())
+        StrategyOperationType::IdentityTopUp(amount) => (
+            "IdentityTopUp",
+            Default::default(),
+            String::new(),
+            format!("Amount {}..={}", amount.start(), amount.end()),
+            String::new(),
+        ),
+        StrategyOperationType::IdentityUpdate(op) => (
+            "IdentityUpdate",
+            Default::default(),
+            String::new(),
+            format!("{:?}", op),
+            String::new(),
+        ),
+        StrategyOperationType::IdentityWithdrawal(amount) => (
+            "IdentityWithdrawal",
+            Default::default(),
+            String::new(),
+            format!("Amount {}..={}", amount.start(), amount.end()),
+            String::new(),
+        ),
+        StrategyOperationType::ContractCreate(..) => (
+            "ContractCreateRandom",
+            Default::default(),
+            String::new(),
+            String::new(),
+            String::new(),
+        ),
+        StrategyOperationType::ContractUpdate(op) => {
+            let action = match op.action {
+                DataContractNewDocumentTypes(_) => "NewDocTypesRandom",
+                DataContractNewOptionalFields(..) => "NewFieldsRandom",
+            };
+            (
+                "ContractUpdate",
+                contract(&op.contract),
+                String::new(),
+                action.to_owned(),
+                String::new(),
+            )
         }
-        StrategyOperationType::ContractCreate(..) => "ContractCreateRandom".to_string(),
-        StrategyOperationType::ContractUpdate(op) => match op.action {
-            DataContractNewDocumentTypes(_) => format!(
-                "ContractUpdate(NewDocTypesRandom): Contract: {}",
-                op.contract.id().to_string(Encoding::Base58)
-            ),
-            DataContractNewOptionalFields(..) => format!(
-                "ContractUpdate(NewFieldsRandom): Contract: {}",
-                op.contract.id().to_string(Encoding::Base58)
-            ),
+        StrategyOperationType::IdentityTransfer(transfer) => (
+            "IdentityTransfer",
+            Default::default(),
+            String::new(),
+            transfer
+                .as_ref()
+                .map(|transfer| {
+                    format!(
+                        "{} -> {}",
+                        id_names.identity(&transfer.from),
+                        id_names.identity(&transfer.to)
+                    )
+                })
+                .unwrap_or_else(|| "Random".to_owned()),
+            String::new(),
+        ),
+        StrategyOperationType::ResourceVote(_) => (
+            "ResourceVote",
+            Default::default(),
+            String::new(),
+            String::new(),
+            String::new(),
+        ),
+        StrategyOperationType::Token(_) => (
+            "Token",
+            Default::default(),
+            String::new(),
+            String::new(),
+            String::new(),
+        ),
+    };
+
+    let fill = match generator {
+        Some(generator) if generator != DocumentGeneratorKind::Random => {
+            format!("{} [{}]", fill, generator)
         }
-        .to_string(),
-        StrategyOperationType::IdentityTransfer(_) => "IdentityTransfer".to_string(),
-        StrategyOperationType::ResourceVote(_) => "ResourceVote".to_string(),
-        StrategyOperationType::Token(token_op) => todo!(),
+        _ => fill,
+    };
+    let range = &op.frequency.times_per_block_range;
+    let chance_per_block = op.frequency.chance_per_block.unwrap_or(0.0);
+    let details = serde_json::json!({
+        "operation": name,
+        "contract": contract,
+        "contract_id": contract_id,
+        "document_type": document_type,
+        "action": action,
+        "fill": fill,
+        "generator": generator.map(|generator| generator.to_string()),
+        "times_per_block": { "start": range.start, "end": range.end },
+        "chance_per_block": chance_per_block,
+    });
+    OperationRow {
+        columns: [
+            name.to_owned(),
+            contract,
+            document_type,
+            action,
+            fill,
+            times_per_block_display(&op.frequency),
+            chance_per_block.to_string(),
+        ],
+        details,
+    }
+}
+
+fn key_maintenance_row(op: &KeyMaintenanceOp) -> OperationRow {
+    let details = serde_json::json!({
+        "operation": "IdentityUpdateKeys",
+        "add_count": op.add_count,
+        "add_purpose": format!("{:?}", op.add_purpose),
+        "add_security_level": format!("{:?}", op.add_security_level),
+        "add_key_type": format!("{:?}", op.add_key_type),
+        "disable_count": op.disable_count,
+        "disable_purpose": format!("{:?}", op.disable_purpose),
+        "rotate_master": op.rotate_master,
+        "chance_per_block": op.chance_per_block,
+    });
+    OperationRow {
+        columns: [
+            "IdentityUpdateKeys".to_owned(),
+            String::new(),
+            String::new(),
+            op.changes(),
+            String::new(),
+            "1".to_owned(),
+            op.chance_per_block.to_string(),
+        ],
+        details,
     }
 }
 
@@ -370,6 +724,7 @@ enum OperationType {
     IdentityTopUp,
     IdentityAddKeys,
     IdentityDisableKeys,
+    IdentityUpdateKeys,
     IdentityWithdrawal,
     IdentityTransferRandom,
     IdentityTransferSpecific,
@@ -403,6 +758,7 @@ impl StrategyAddOperationFormController {
             "IdentityTopUp".to_string(),
             "IdentityAddKeys".to_string(),
             "IdentityDisableKeys".to_string(),
+            "IdentityUpdateKeys".to_string(),
             "IdentityWithdrawal".to_string(),
             "IdentityTransferRandom".to_string(),
             "IdentityTransferSpecific".to_string(),
@@ -445,6 +801,9 @@ impl StrategyAddOperationFormController {
                     identity_update::KeyUpdateOp::DisableKeys,
                 ))
             }
+            OperationType::IdentityUpdateKeys => Box::new(
+                StrategyOpKeyMaintenanceFormController::new(self.strategy_name.clone()),
+            ),
             OperationType::IdentityWithdrawal => Box::new(
                 StrategyOpIdentityWithdrawalFormController::new(self.strategy_name.clone()),
             ),
@@ -485,6 +844,7 @@ impl FormController for StrategyAddOperationFormController {
                         "IdentityTopUp" => OperationType::IdentityTopUp,
                         "IdentityAddKeys" => OperationType::IdentityAddKeys,
                         "IdentityDisableKeys" => OperationType::IdentityDisableKeys,
+                        "IdentityUpdateKeys" => OperationType::IdentityUpdateKeys,
                         "IdentityWithdrawal" => OperationType::IdentityWithdrawal,
                         "IdentityTransferRandom" => OperationType::IdentityTransferRandom,
                         "IdentityTransferSpecific" => OperationType::IdentityTransferSpecific,
@@ -550,9 +910,10 @@ impl FormController for StrategyAddOperationFormController {
 pub(super) struct StrategyAutomaticDocumentsFormController {
     strategy_name: String,
     input: ComposedInput<(
-        Field<SelectInput<u16>>,    // Num documents
-        Field<SelectInput<String>>, // Fill size
-        Field<SelectInput<String>>, // Fill type
+        Field<SelectInput<u16>>,                          // Num documents
+        Field<SelectInput<String>>,                       // Fill size
+        Field<SelectInput<String>>,                       // Fill type
+        Field<TextInput<DefaultTextInputParser<String>>>, // Payload file
     )>,
 }
 
@@ -578,6 +939,10 @@ impl StrategyAutomaticDocumentsFormController {
                     "Populate not-required fields?",
                     SelectInput::new(vec!["No".to_string(), "Yes".to_string()]),
                 ),
+                Field::new(
+                    "JSON file of document values by document type (empty for random values)",
+                    TextInput::new("Path to a JSON file"),
+                ),
             )),
         }
     }
@@ -586,7 +951,7 @@ impl StrategyAutomaticDocumentsFormController {
 impl FormController for StrategyAutomaticDocumentsFormController {
     fn on_event(&mut self, event: KeyEvent) -> FormStatus {
         match self.input.on_event(event) {
-            InputStatus::Done((num_docs, fill_size_string, fill_type_string)) => {
+            InputStatus::Done((num_docs, fill_size_string, fill_type_string, payload_path)) => {
                 let fill_size = match &fill_size_string as &str {
                     "Minimum" => DocumentFieldFillSize::MinDocumentFillSize,
                     "Maximum" => DocumentFieldFillSize::MaxDocumentFillSize,
@@ -611,6 +976,7 @@ impl FormController for StrategyAutomaticDocumentsFormController {
                         num_docs,
                         fill_size,
                         fill_type,
+                        (!payload_path.trim().is_empty()).then(|| payload_path.trim().to_owned()),
                     )),
                     block: false,
                 }
@@ -639,3 +1005,73 @@ impl FormController for StrategyAutomaticDocumentsFormController {
         self.input.steps_number()
     }
 }
+
+pub(super) struct DocumentGeneratorFormController {
+    strategy_name: String,
+    contract_id: String,
+    document_type: String,
+    input: SelectInput<DocumentGeneratorKind>,
+    help: Info,
+}
+
+impl DocumentGeneratorFormController {
+    pub(super) fn new(strategy_name: String, contract_id: String, document_type: String) -> Self {
+        Self {
+            strategy_name,
+            contract_id,
+            input: SelectInput::new(DocumentGeneratorKind::ALL.to_vec()),
+            help: Info::new_fixed(&format!(
+                "Values of the {} documents inserted by the strategy, for all its inserts of \
+                 this document type:\n - Random: the operation's own random fill\n - Realistic: \
+                 names, addresses, emails and the like picked by property name\n - Boundary \
+                 values: the smallest and largest values and sizes of each property\n - Unicode \
+                 stress: strings of combining marks, right to left text, emoji sequences and \
+                 invisible characters\n\nIdentifiers and nested objects keep random values.",
+                document_type
+            )),
+            document_type,
+        }
+    }
+}
+
+impl FormController for DocumentGeneratorFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done(generator) => FormStatus::Done {
+                task: Task::Strategy(StrategyTask::SetDocumentGenerator {
+                    strategy_name: self.strategy_name.clone(),
+                    contract_id: self.contract_id.clone(),
+                    document_type: self.document_type.clone(),
+                    generator,
+                }),
+                block: false,
+            },
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Document generator"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(10), Constraint::Length(6)].as_ref())
+            .split(area);
+        self.help.view(frame, layout[0]);
+        self.input.view(frame, layout[1]);
+    }
+
+    fn step_name(&self) -> &'static str {
+        "Generator"
+    }
+
+    fn step_index(&self) -> u8 {
+        0
+    }
+
+    fn steps_number(&self) -> u8 {
+        1
+    }
+}
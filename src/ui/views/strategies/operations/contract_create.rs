@@ -15,15 +15,16 @@ use tuirealm::{event::KeyEvent, tui::prelude::Rect, Frame};
 use crate::{
     backend::{StrategyTask, Task},
     ui::form::{
-        parsers::DefaultTextInputParser, ComposedInput, Field, FormController, FormStatus, Input,
-        InputStatus, SelectInput, TextInput,
+        parsers::{DefaultTextInputParser, TimesPerBlockRangeTextInputParser},
+        ComposedInput, Field, FormController, FormStatus, Input, InputStatus, SelectInput,
+        TextInput,
     },
 };
 
 pub(super) struct StrategyOpContractCreateFormController {
     input: ComposedInput<(
         Field<TextInput<DefaultTextInputParser<u16>>>,
-        Field<TextInput<DefaultTextInputParser<u16>>>,
+        Field<TextInput<TimesPerBlockRangeTextInputParser>>,
         Field<SelectInput<f64>>,
     )>,
     selected_strategy: String,
@@ -37,7 +38,13 @@ impl StrategyOpContractCreateFormController {
                     "Number of document types",
                     TextInput::new("Enter a whole number."),
                 ),
-                Field::new("Times per block", TextInput::new("Enter a whole number")),
+                Field::new(
+                    "Times per block",
+                    TextInput::new_with_parser(
+                        TimesPerBlockRangeTextInputParser,
+                        "Enter a whole number or a range like 1..5",
+                    ),
+                ),
                 Field::new(
                     "Chance per block",
                     SelectInput::new(vec![1.0, 0.9, 0.75, 0.5, 0.25, 0.1]),
@@ -102,7 +109,7 @@ impl FormController for StrategyOpContractCreateFormController {
                                 1..num_document_types + 1,
                             ),
                             frequency: Frequency {
-                                times_per_block_range: times_per_block..times_per_block + 1,
+                                times_per_block_range: times_per_block,
                                 chance_per_block: Some(chance_per_block),
                             },
                         },
@@ -20,8 +20,8 @@ use tuirealm::{event::KeyEvent, tui::prelude::Rect, Frame};
 use crate::{
     backend::{StrategyContractNames, StrategyTask, Task},
     ui::form::{
-        parsers::DefaultTextInputParser, ComposedInput, Field, FormController, FormStatus, Input,
-        InputStatus, SelectInput, TextInput,
+        parsers::TimesPerBlockRangeTextInputParser, ComposedInput, Field, FormController,
+        FormStatus, Input, InputStatus, SelectInput, TextInput,
     },
 };
 
@@ -160,10 +160,10 @@ impl FormController for StrategyOpDocumentFormController {
 
 pub(super) struct DocumentTypeFormController {
     input: ComposedInput<(
-        Field<SelectInput<String>>,                    // Document types
-        Field<SelectInput<String>>,                    // Operation type
-        Field<TextInput<DefaultTextInputParser<u16>>>, // Times per block
-        Field<SelectInput<f64>>,                       // Chance per block
+        Field<SelectInput<String>>,                          // Document types
+        Field<SelectInput<String>>,                          // Operation type
+        Field<TextInput<TimesPerBlockRangeTextInputParser>>, // Times per block
+        Field<SelectInput<f64>>,                             // Chance per block
     )>,
     selected_strategy_name: String,
     selected_contract: DataContract,
@@ -184,9 +184,17 @@ impl DocumentTypeFormController {
                     SelectInput::new(vec![
                         "DocumentInsertRandom".to_string(),
                         "DocumentDeleteRandom".to_string(),
+                        "DocumentReplaceRandom".to_string(),
+                        "DocumentTransferRandom".to_string(),
                     ]),
                 ),
-                Field::new("Times per block", TextInput::new("Enter a whole number")),
+                Field::new(
+                    "Times per block",
+                    TextInput::new_with_parser(
+                        TimesPerBlockRangeTextInputParser,
+                        "Enter a whole number or a range like 1..5",
+                    ),
+                ),
                 Field::new(
                     "Chance per block",
                     SelectInput::new(vec![1.0, 0.75, 0.5, 0.25, 0.1]),
@@ -231,6 +239,8 @@ impl FormController for DocumentTypeFormController {
                             DocumentFieldFillSize::MinDocumentFillSize,    // dummy value
                         ),
                         "DocumentDeleteRandom" => DocumentAction::DocumentActionDelete,
+                        "DocumentReplaceRandom" => DocumentAction::DocumentActionReplaceRandom,
+                        "DocumentTransferRandom" => DocumentAction::DocumentActionTransferRandom,
                         _ => panic!("Unrecognized string for Operation Type in Document Operation"),
                     };
 
@@ -242,13 +252,15 @@ impl FormController for DocumentTypeFormController {
                                     .document_type_cloned_for_name(&document_type)
                                     .expect("Expected the document type to be there"),
                                 Frequency {
-                                    times_per_block_range: times_per_block..times_per_block + 1,
+                                    times_per_block_range: times_per_block,
                                     chance_per_block: Some(chance_per_block),
                                 },
                             );
                             FormStatus::Redraw
                         }
-                        DocumentAction::DocumentActionDelete => FormStatus::Done {
+                        DocumentAction::DocumentActionDelete
+                        | DocumentAction::DocumentActionReplaceRandom
+                        | DocumentAction::DocumentActionTransferRandom => FormStatus::Done {
                             task: Task::Strategy(StrategyTask::AddOperation {
                                 strategy_name: self.selected_strategy_name.clone(),
                                 operation: Operation {
@@ -261,7 +273,7 @@ impl FormController for DocumentTypeFormController {
                                         action,
                                     }),
                                     frequency: Frequency {
-                                        times_per_block_range: times_per_block..times_per_block + 1,
+                                        times_per_block_range: times_per_block,
                                         chance_per_block: Some(chance_per_block),
                                     },
                                 },
@@ -269,8 +281,6 @@ impl FormController for DocumentTypeFormController {
                             block: false,
                         },
                         DocumentAction::DocumentActionInsertSpecific(_, _, _, _) => todo!(),
-                        DocumentAction::DocumentActionReplaceRandom => todo!(),
-                        DocumentAction::DocumentActionTransferRandom => todo!(),
                     }
                 }
                 status => status.into(),
@@ -10,14 +10,14 @@ use tuirealm::{event::KeyEvent, tui::prelude::Rect, Frame};
 use crate::{
     backend::{StrategyTask, Task},
     ui::form::{
-        parsers::DefaultTextInputParser, ComposedInput, Field, FormController, FormStatus, Input,
-        InputStatus, SelectInput, TextInput,
+        parsers::TimesPerBlockRangeTextInputParser, ComposedInput, Field, FormController,
+        FormStatus, Input, InputStatus, SelectInput, TextInput,
     },
 };
 
 pub(super) struct StrategyOpIdentityTopUpFormController {
     input: ComposedInput<(
-        Field<TextInput<DefaultTextInputParser<u16>>>,
+        Field<TextInput<TimesPerBlockRangeTextInputParser>>,
         Field<SelectInput<f64>>,
     )>,
     selected_strategy: String,
@@ -27,7 +27,13 @@ impl StrategyOpIdentityTopUpFormController {
     pub(super) fn new(selected_strategy: String) -> Self {
         StrategyOpIdentityTopUpFormController {
             input: ComposedInput::new((
-                Field::new("Times per block", TextInput::new("Enter a whole number")),
+                Field::new(
+                    "Times per block",
+                    TextInput::new_with_parser(
+                        TimesPerBlockRangeTextInputParser,
+                        "Enter a whole number or a range like 1..5",
+                    ),
+                ),
                 Field::new(
                     "Chance per block",
                     SelectInput::new(vec![1.0, 0.9, 0.75, 0.5, 0.25, 0.1, 0.05, 0.01]),
@@ -49,7 +55,7 @@ impl FormController for StrategyOpIdentityTopUpFormController {
                             dash_to_credits!(1)..=dash_to_credits!(1),
                         ),
                         frequency: Frequency {
-                            times_per_block_range: times_per_block..times_per_block + 1,
+                            times_per_block_range: times_per_block,
                             chance_per_block: Some(chance_per_block),
                         },
                     },
@@ -1,5 +1,6 @@
 //! Forms for strategy operations related to identity updates.
 
+use dpp::identity::{KeyType, Purpose, SecurityLevel};
 use strategy_tests::{
     frequency::Frequency,
     operations::{IdentityUpdateOp, Operation, OperationType},
@@ -7,7 +8,10 @@ use strategy_tests::{
 use tuirealm::{event::KeyEvent, tui::prelude::Rect, Frame};
 
 use crate::{
-    backend::{StrategyTask, Task},
+    backend::{
+        key_maintenance::{forced_security_level, KeyMaintenanceOp},
+        StrategyTask, Task,
+    },
     ui::form::{
         parsers::DefaultTextInputParser, ComposedInput, Field, FormController, FormStatus, Input,
         InputStatus, SelectInput, TextInput,
@@ -98,3 +102,214 @@ impl FormController for StrategyOpIdentityUpdateFormController {
         3
     }
 }
+
+const PURPOSES: [Purpose; 4] = [
+    Purpose::AUTHENTICATION,
+    Purpose::ENCRYPTION,
+    Purpose::DECRYPTION,
+    Purpose::TRANSFER,
+];
+
+enum KeyMaintenanceFormStep {
+    AddCount(TextInput<DefaultTextInputParser<u16>>),
+    AddPurpose(SelectInput<Purpose>),
+    AddSecurity(SelectInput<SecurityLevel>),
+    AddKeyType(SelectInput<KeyType>),
+    DisableCount(TextInput<DefaultTextInputParser<u16>>),
+    DisablePurpose(SelectInput<Purpose>),
+    RotateMaster(SelectInput<String>),
+    Chance(SelectInput<f64>),
+}
+
+/// Identity update operation choosing the purpose, security level and type of
+/// the keys to add, the purpose of the keys to disable and whether to rotate
+/// the master key.
+pub(super) struct StrategyOpKeyMaintenanceFormController {
+    step: KeyMaintenanceFormStep,
+    selected_strategy: String,
+    operation: KeyMaintenanceOp,
+}
+
+impl StrategyOpKeyMaintenanceFormController {
+    pub(super) fn new(selected_strategy: String) -> Self {
+        StrategyOpKeyMaintenanceFormController {
+            step: KeyMaintenanceFormStep::AddCount(TextInput::new_init_value(
+                "Enter a whole number",
+                1,
+            )),
+            selected_strategy,
+            operation: KeyMaintenanceOp {
+                add_count: 0,
+                add_purpose: Purpose::AUTHENTICATION,
+                add_security_level: SecurityLevel::HIGH,
+                add_key_type: KeyType::ECDSA_SECP256K1,
+                disable_count: 0,
+                disable_purpose: Purpose::AUTHENTICATION,
+                rotate_master: false,
+                chance_per_block: 1.0,
+            },
+        }
+    }
+
+    fn disable_count_step() -> KeyMaintenanceFormStep {
+        KeyMaintenanceFormStep::DisableCount(TextInput::new_init_value("Enter a whole number", 0))
+    }
+
+    fn rotate_master_step() -> KeyMaintenanceFormStep {
+        KeyMaintenanceFormStep::RotateMaster(SelectInput::new(vec![
+            "No".to_string(),
+            "Yes".to_string(),
+        ]))
+    }
+}
+
+impl FormController for StrategyOpKeyMaintenanceFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match &mut self.step {
+            KeyMaintenanceFormStep::AddCount(input) => match input.on_event(event) {
+                InputStatus::Done(count) => {
+                    self.operation.add_count = count;
+                    self.step = if count > 0 {
+                        KeyMaintenanceFormStep::AddPurpose(SelectInput::new(PURPOSES.to_vec()))
+                    } else {
+                        Self::disable_count_step()
+                    };
+                    FormStatus::Redraw
+                }
+                status => status.into(),
+            },
+            KeyMaintenanceFormStep::AddPurpose(input) => match input.on_event(event) {
+                InputStatus::Done(purpose) => {
+                    self.operation.add_purpose = purpose;
+                    // Transfer and en/decryption keys have a fixed security level
+                    self.step = if let Some(security_level) = forced_security_level(purpose) {
+                        self.operation.add_security_level = security_level;
+                        KeyMaintenanceFormStep::AddKeyType(SelectInput::new(vec![
+                            KeyType::ECDSA_SECP256K1,
+                            KeyType::BLS12_381,
+                            KeyType::ECDSA_HASH160,
+                        ]))
+                    } else {
+                        KeyMaintenanceFormStep::AddSecurity(SelectInput::new(vec![
+                            SecurityLevel::CRITICAL,
+                            SecurityLevel::HIGH,
+                            SecurityLevel::MEDIUM,
+                        ]))
+                    };
+                    FormStatus::Redraw
+                }
+                status => status.into(),
+            },
+            KeyMaintenanceFormStep::AddSecurity(input) => match input.on_event(event) {
+                InputStatus::Done(security_level) => {
+                    self.operation.add_security_level = security_level;
+                    self.step = KeyMaintenanceFormStep::AddKeyType(SelectInput::new(vec![
+                        KeyType::ECDSA_SECP256K1,
+                        KeyType::BLS12_381,
+                        KeyType::ECDSA_HASH160,
+                    ]));
+                    FormStatus::Redraw
+                }
+                status => status.into(),
+            },
+            KeyMaintenanceFormStep::AddKeyType(input) => match input.on_event(event) {
+                InputStatus::Done(key_type) => {
+                    self.operation.add_key_type = key_type;
+                    self.step = Self::disable_count_step();
+                    FormStatus::Redraw
+                }
+                status => status.into(),
+            },
+            KeyMaintenanceFormStep::DisableCount(input) => match input.on_event(event) {
+                InputStatus::Done(count) => {
+                    self.operation.disable_count = count;
+                    self.step = if count > 0 {
+                        KeyMaintenanceFormStep::DisablePurpose(SelectInput::new(PURPOSES.to_vec()))
+                    } else {
+                        Self::rotate_master_step()
+                    };
+                    FormStatus::Redraw
+                }
+                status => status.into(),
+            },
+            KeyMaintenanceFormStep::DisablePurpose(input) => match input.on_event(event) {
+                InputStatus::Done(purpose) => {
+                    self.operation.disable_purpose = purpose;
+                    self.step = Self::rotate_master_step();
+                    FormStatus::Redraw
+                }
+                status => status.into(),
+            },
+            KeyMaintenanceFormStep::RotateMaster(input) => match input.on_event(event) {
+                InputStatus::Done(rotate) => {
+                    self.operation.rotate_master = rotate == "Yes";
+                    self.step = KeyMaintenanceFormStep::Chance(SelectInput::new(vec![
+                        1.0, 0.9, 0.75, 0.5, 0.25, 0.1, 0.05, 0.01,
+                    ]));
+                    FormStatus::Redraw
+                }
+                status => status.into(),
+            },
+            KeyMaintenanceFormStep::Chance(input) => match input.on_event(event) {
+                InputStatus::Done(chance_per_block) => {
+                    self.operation.chance_per_block = chance_per_block;
+                    FormStatus::Done {
+                        task: Task::Strategy(StrategyTask::AddKeyMaintenanceOperation {
+                            strategy_name: self.selected_strategy.clone(),
+                            operation: self.operation.clone(),
+                        }),
+                        block: false,
+                    }
+                }
+                status => status.into(),
+            },
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Identity key maintenance operation"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        match &mut self.step {
+            KeyMaintenanceFormStep::AddCount(input) => input.view(frame, area),
+            KeyMaintenanceFormStep::AddPurpose(input) => input.view(frame, area),
+            KeyMaintenanceFormStep::AddSecurity(input) => input.view(frame, area),
+            KeyMaintenanceFormStep::AddKeyType(input) => input.view(frame, area),
+            KeyMaintenanceFormStep::DisableCount(input) => input.view(frame, area),
+            KeyMaintenanceFormStep::DisablePurpose(input) => input.view(frame, area),
+            KeyMaintenanceFormStep::RotateMaster(input) => input.view(frame, area),
+            KeyMaintenanceFormStep::Chance(input) => input.view(frame, area),
+        }
+    }
+
+    fn step_name(&self) -> &'static str {
+        match self.step {
+            KeyMaintenanceFormStep::AddCount(_) => "How many keys to add",
+            KeyMaintenanceFormStep::AddPurpose(_) => "Purpose of added keys",
+            KeyMaintenanceFormStep::AddSecurity(_) => "Security level of added keys",
+            KeyMaintenanceFormStep::AddKeyType(_) => "Type of added keys",
+            KeyMaintenanceFormStep::DisableCount(_) => "How many keys to disable",
+            KeyMaintenanceFormStep::DisablePurpose(_) => "Purpose of disabled keys",
+            KeyMaintenanceFormStep::RotateMaster(_) => "Rotate the master key?",
+            KeyMaintenanceFormStep::Chance(_) => "Chance per block",
+        }
+    }
+
+    fn step_index(&self) -> u8 {
+        match self.step {
+            KeyMaintenanceFormStep::AddCount(_) => 0,
+            KeyMaintenanceFormStep::AddPurpose(_) => 1,
+            KeyMaintenanceFormStep::AddSecurity(_) => 2,
+            KeyMaintenanceFormStep::AddKeyType(_) => 3,
+            KeyMaintenanceFormStep::DisableCount(_) => 4,
+            KeyMaintenanceFormStep::DisablePurpose(_) => 5,
+            KeyMaintenanceFormStep::RotateMaster(_) => 6,
+            KeyMaintenanceFormStep::Chance(_) => 7,
+        }
+    }
+
+    fn steps_number(&self) -> u8 {
+        8
+    }
+}
@@ -1,6 +1,9 @@
 //! Identity withdrawal operations form for strategy.
+//!
+//! Withdrawals are always direct (no pooling), Platform doesn't accept pooled
+//! ones yet. They are signed with a transfer key, so the strategy's start
+//! identities need one, which is checked before a run starts.
 
-use dpp::dash_to_credits;
 use strategy_tests::{
     frequency::Frequency,
     operations::{Operation, OperationType},
@@ -9,18 +12,59 @@ use tuirealm::{event::KeyEvent, tui::prelude::Rect, Frame};
 
 use crate::{
     backend::{StrategyTask, Task},
-    ui::form::{ComposedInput, Field, FormController, FormStatus, Input, InputStatus, SelectInput},
+    ui::form::{
+        parsers::{DuffsTextInputParser, TimesPerBlockRangeTextInputParser},
+        ComposedInput, Field, FormController, FormStatus, Input, InputStatus, SelectInput,
+        TextInput,
+    },
+    units::{self, CREDITS_PER_DUFF},
 };
 
+/// Amount withdrawn when not changed, 0.01 Dash
+const DEFAULT_WITHDRAWAL_DUFFS: u64 = 1_000_000;
+
 pub(super) struct StrategyOpIdentityWithdrawalFormController {
-    input: SelectInput<f64>,
+    input: ComposedInput<(
+        Field<TextInput<DuffsTextInputParser>>,
+        Field<TextInput<DuffsTextInputParser>>,
+        Field<TextInput<TimesPerBlockRangeTextInputParser>>,
+        Field<SelectInput<f64>>,
+    )>,
     selected_strategy: String,
 }
 
 impl StrategyOpIdentityWithdrawalFormController {
     pub(super) fn new(selected_strategy: String) -> Self {
         StrategyOpIdentityWithdrawalFormController {
-            input: SelectInput::new(vec![1.0, 0.9, 0.75, 0.5, 0.25, 0.1]),
+            input: ComposedInput::new((
+                Field::new(
+                    "Minimum amount to withdraw",
+                    TextInput::new_str_value_with_parser(
+                        DuffsTextInputParser,
+                        "Amount",
+                        &units::format_duffs(DEFAULT_WITHDRAWAL_DUFFS),
+                    ),
+                ),
+                Field::new(
+                    "Maximum amount to withdraw",
+                    TextInput::new_str_value_with_parser(
+                        DuffsTextInputParser,
+                        "Amount",
+                        &units::format_duffs(DEFAULT_WITHDRAWAL_DUFFS),
+                    ),
+                ),
+                Field::new(
+                    "Times per block",
+                    TextInput::new_with_parser(
+                        TimesPerBlockRangeTextInputParser,
+                        "Enter a whole number or a range like 1..5",
+                    ),
+                ),
+                Field::new(
+                    "Chance per block",
+                    SelectInput::new(vec![1.0, 0.9, 0.75, 0.5, 0.25, 0.1, 0.05, 0.01]),
+                ),
+            )),
             selected_strategy,
         }
     }
@@ -29,21 +73,23 @@ impl StrategyOpIdentityWithdrawalFormController {
 impl FormController for StrategyOpIdentityWithdrawalFormController {
     fn on_event(&mut self, event: KeyEvent) -> FormStatus {
         match self.input.on_event(event) {
-            InputStatus::Done(chance_per_block) => FormStatus::Done {
-                task: Task::Strategy(StrategyTask::AddOperation {
-                    strategy_name: self.selected_strategy.clone(),
-                    operation: Operation {
-                        op_type: OperationType::IdentityWithdrawal(
-                            dash_to_credits!(2)..=dash_to_credits!(2),
-                        ),
-                        frequency: Frequency {
-                            times_per_block_range: 1..2,
-                            chance_per_block: Some(chance_per_block),
+            InputStatus::Done((min_duffs, max_duffs, times_per_block, chance_per_block)) => {
+                let min_amount = min_duffs * CREDITS_PER_DUFF;
+                let max_amount = (max_duffs * CREDITS_PER_DUFF).max(min_amount);
+                FormStatus::Done {
+                    task: Task::Strategy(StrategyTask::AddOperation {
+                        strategy_name: self.selected_strategy.clone(),
+                        operation: Operation {
+                            op_type: OperationType::IdentityWithdrawal(min_amount..=max_amount),
+                            frequency: Frequency {
+                                times_per_block_range: times_per_block,
+                                chance_per_block: Some(chance_per_block),
+                            },
                         },
-                    },
-                }),
-                block: false,
-            },
+                    }),
+                    block: false,
+                }
+            }
             status => status.into(),
         }
     }
@@ -57,14 +103,14 @@ impl FormController for StrategyOpIdentityWithdrawalFormController {
     }
 
     fn step_name(&self) -> &'static str {
-        "Chance per block"
+        self.input.step_name()
     }
 
     fn step_index(&self) -> u8 {
-        0
+        self.input.step_index()
     }
 
     fn steps_number(&self) -> u8 {
-        1
+        self.input.steps_number()
     }
 }
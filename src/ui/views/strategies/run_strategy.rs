@@ -1,5 +1,12 @@
 //! Run strategy screen and forms.
 
+use std::{sync::Arc, time::Duration};
+
+use dpp::{
+    identity::accessors::IdentityGettersV0,
+    platform_value::{string_encoding::Encoding, Identifier},
+};
+use tokio::sync::watch;
 use tuirealm::{
     event::{Key, KeyEvent, KeyModifiers},
     tui::prelude::Rect,
@@ -7,37 +14,62 @@ use tuirealm::{
 };
 
 use crate::{
-    backend::{AppState, BackendEvent, StrategyCompletionResult, StrategyTask, Task},
+    backend::{
+        document_tracker::DeletePolicy,
+        load_profile::LoadProfile,
+        reserve_top_up::ReserveTopUp,
+        retry_policy::RetryPolicy,
+        run_report::Tolerances,
+        snapshot::AppStateSnapshot,
+        strategies::{RunOptions, WaitSettings},
+        strategy_runs::StrategyRuns,
+        upgrade_window::UpgradeWindowSettings,
+        AppState, BackendEvent, StrategyCompletionResult, StrategyTask, Task,
+    },
+    config::Config,
+    format::{format_count, format_duration, format_rate, format_secs},
     ui::form::{
-        parsers::DefaultTextInputParser, ComposedInput, Field, FormController, FormStatus, Input,
-        InputStatus, SelectInput, TextInput,
+        parsers::{CreditsTextInputParser, DefaultTextInputParser, LoadProfileTextInputParser},
+        ComposedInput, Field, FormController, FormStatus, Input, InputStatus, SelectInput,
+        TextInput,
     },
     ui::screen::{
         utils::impl_builder, widgets::info::Info, ScreenCommandKey, ScreenController,
         ScreenFeedback, ScreenToggleKey,
     },
-    Event,
+    units, Event,
 };
 
-const COMMAND_KEYS: [ScreenCommandKey; 2] = [
+/// Pinned address choice letting the Sdk rotate between addresses
+const NO_PINNED_ADDRESS: &str = "No, rotate between addresses";
+/// Reserve choice topping up identities with asset locks
+const NO_RESERVE: &str = "No, use asset locks";
+/// Run form choice going on to the rarely used options
+const ADVANCED_OPTIONS: &str = "Set advanced options first";
+/// Run form choice leaving the screens free while the run goes on, other
+/// strategies can then run alongside it
+const RUN_IN_BACKGROUND: &str = "Yes, in the background (follow it on the activity screen)";
+
+const COMMAND_KEYS: [ScreenCommandKey; 3] = [
     ScreenCommandKey::new("q", "Back to Strategy"),
     ScreenCommandKey::new("r", "Rerun strategy"),
+    ScreenCommandKey::new("C-x", "Cancel the running strategy"),
 ];
 
 pub(crate) struct RunStrategyScreenController {
     info: Info,
     strategy_running: bool,
     selected_strategy: Option<String>,
+    strategy_runs: StrategyRuns,
+    snapshots: watch::Receiver<Arc<AppStateSnapshot>>,
 }
 
 impl_builder!(RunStrategyScreenController);
 
 impl RunStrategyScreenController {
     pub(crate) async fn new(app_state: &AppState) -> Self {
-        let selected_strategy_lock = app_state.selected_strategy.lock().await;
-
         let (info, strategy_running, selected_strategy) =
-            if let Some(current_strategy) = selected_strategy_lock.as_ref() {
+            if let Some(current_strategy) = app_state.snapshot().selected_strategy.as_ref() {
                 let info = Info::new_fixed("Strategy is running, please wait.");
                 (info, true, Some(current_strategy.clone()))
             } else {
@@ -45,12 +77,12 @@ impl RunStrategyScreenController {
                 (info, false, None)
             };
 
-        drop(selected_strategy_lock);
-
         Self {
             info,
             strategy_running,
             selected_strategy,
+            strategy_runs: app_state.strategy_runs().clone(),
+            snapshots: app_state.subscribe_snapshot(),
         }
     }
 }
@@ -79,16 +111,21 @@ impl ScreenController for RunStrategyScreenController {
                 modifiers: KeyModifiers::NONE,
             }) => {
                 self.strategy_running = true;
+                self.info = Info::new_fixed("Strategy is running, please wait.");
+                let strategy_name = self
+                    .selected_strategy
+                    .clone()
+                    .expect("No selected strategy available");
                 ScreenFeedback::Form(Box::new(RunStrategyFormController::new(
-                    self.selected_strategy
-                        .clone()
-                        .expect("No selected strategy available"),
+                    strategy_name,
+                    &self.snapshots.borrow(),
                 )))
             }
+            // Runs of other strategies going on in the background end here too
             Event::Backend(BackendEvent::StrategyCompleted {
                 strategy_name,
                 result,
-            }) => {
+            }) if self.selected_strategy.as_ref() == Some(strategy_name) => {
                 self.strategy_running = false;
 
                 let display_text = match result {
@@ -105,28 +142,152 @@ impl ScreenController for RunStrategyScreenController {
                         init_time,
                         dash_spent_identity,
                         dash_spent_wallet,
+                        nonce_recoveries,
+                        wait_unknowns,
+                        halt_reason,
+                        backlog_incidents,
+                        lint_warnings,
+                        top_ups,
+                        load_control,
+                        load_profile,
+                        load_phases,
+                        reused_contracts,
+                        contract_aliases,
+                        rejections,
+                        transition_trace,
+                        operation_types,
+                        measured_time,
+                        baseline_comparison,
+                        pinned_address,
+                        banned_addresses,
+                        mirror_report,
+                        seed,
+                        throttled_loops,
+                        throttled_time,
+                        traffic,
                     } => {
                         let mode = match block_mode {
                             true => String::from("block"),
                             false => String::from("time"),
                         };
-                        format!(
+                        let mut text = format!(
                             "Strategy '{}' completed:\n\nMode: {}\nState transitions attempted: {}\nState \
                              transitions succeeded: {}\nNumber of blocks (or loops): {}\nRun time: \
-                             {}s\nInitialization time: {}\nAttempted rate (approx): {:.2} tx/s\nSuccessful rate: {:.2} tx/s\nSuccess percentage: {}%\nDash spent (Identity): {}\nDash spent (Wallet): {}",
+                             {}\nInitialization time: {}\nAttempted rate (approx): {}\nSuccessful rate: {}\nSuccess percentage: {}%\nDash spent (Identity): {}\nDash spent (Wallet): {}\nRecovered nonce errors: {}\nWaits given up (unknown result): {}",
                             strategy_name,
                             mode,
-                            transition_count,
-                            success_count,
-                            (final_block_height - start_block_height - 2), // -2 so we don't count the 2 init blocks
-                            run_time.as_secs(),
-                            init_time.as_secs(),
-                            rate,
-                            success_rate,
+                            format_count(*transition_count),
+                            format_count(*success_count),
+                            format_count(final_block_height - start_block_height - 2), // -2 so we don't count the 2 init blocks
+                            format_duration(*run_time),
+                            format_duration(*init_time),
+                            format_rate((*rate).into()),
+                            format_rate((*success_rate).into()),
                             success_percent,
                             dash_spent_identity,
                             dash_spent_wallet,
-                        )
+                            format_count(*nonce_recoveries),
+                            format_count(*wait_unknowns),
+                        );
+                        if !reused_contracts.is_empty() {
+                            text.push_str(&format!(
+                                "\nStart contracts reused from an earlier run: {}",
+                                reused_contracts.join(", ")
+                            ));
+                        }
+                        if !contract_aliases.is_empty() {
+                            text.push_str(&format!(
+                                "\nContract aliases: {}",
+                                contract_aliases.join(", ")
+                            ));
+                        }
+                        text.push_str(&format!("\nSeed (to replay the run): {}", seed));
+                        if let Some(path) = transition_trace {
+                            text.push_str(&format!("\nTransition trace: {}", path));
+                        }
+                        if let Some(address) = pinned_address {
+                            text.push_str(&format!("\nPinned to DAPI address: {}", address));
+                        }
+                        if !banned_addresses.is_empty() {
+                            text.push_str(&format!(
+                                "\nBanned DAPI addresses (unban from the main screen): {}",
+                                banned_addresses.join(", ")
+                            ));
+                        }
+                        if *throttled_loops > 0 {
+                            text.push_str(&format!(
+                                "\nLoops held off by the in-flight cap: {}, for {}",
+                                format_count(*throttled_loops),
+                                format_duration(*throttled_time)
+                            ));
+                        }
+                        if let Some(reason) = halt_reason {
+                            text.push_str(&format!("\n\nStopped early: {}", reason));
+                        }
+                        if let Some(measured_time) = measured_time {
+                            text.push_str(&format!(
+                                "\n\nRates and wait times cover the {}s steady phase, warm-up \
+                                 and cool-down excluded",
+                                measured_time.as_secs()
+                            ));
+                        }
+                        if let Some(comparison) = baseline_comparison {
+                            text.push_str(&format!("\n\n{}", comparison));
+                        }
+                        if let Some(report) = load_control {
+                            text.push_str(&format!("\n\n{}", report));
+                        }
+                        if !load_phases.is_empty() {
+                            text.push_str(&format!("\n\nLoad phases ({}):", load_profile));
+                            for phase in load_phases {
+                                text.push_str(&format!("\n - {}", phase));
+                            }
+                        }
+                        text.push_str(&format!("\n\n{}", traffic));
+                        if !operation_types.is_empty() {
+                            text.push_str("\n\nBy transition type:");
+                            for report in operation_types {
+                                text.push_str(&format!("\n - {}", report));
+                            }
+                        }
+                        if !rejections.is_empty() {
+                            text.push_str("\n\nTransitions rejected by Platform:");
+                            for (category, count) in rejections {
+                                text.push_str(&format!("\n - {}: {}", category, count));
+                            }
+                        }
+                        if let Some(report) = mirror_report {
+                            text.push_str(&format!("\n\n{}", report));
+                        }
+                        if !backlog_incidents.is_empty() {
+                            text.push_str("\n\nNetwork backlog incidents:");
+                            for incident in backlog_incidents {
+                                text.push_str(&format!("\n - {}", incident));
+                            }
+                        }
+                        if !top_ups.is_empty() {
+                            let injected: u64 = top_ups
+                                .iter()
+                                .filter(|top_up| top_up.success)
+                                .map(|top_up| top_up.credits)
+                                .sum();
+                            text.push_str(&format!(
+                                "\n\nIdentity top ups: {} ({} succeeded), injected: {}",
+                                top_ups.len(),
+                                top_ups.iter().filter(|top_up| top_up.success).count(),
+                                units::format_credits(injected)
+                            ));
+                            for top_up in top_ups {
+                                text.push_str(&format!("\n - {}", top_up));
+                            }
+                        }
+                        if !lint_warnings.is_empty() {
+                            text.push_str("\n\nStrategy lint warnings:");
+                            for warning in lint_warnings {
+                                text.push_str(&format!("\n - {}", warning));
+                            }
+                        }
+                        text
                     }
                     StrategyCompletionResult::PartiallyCompleted {
                         reached_block_height,
@@ -137,11 +298,60 @@ impl ScreenController for RunStrategyScreenController {
                             strategy_name, reached_block_height, reason
                         )
                     }
+                    StrategyCompletionResult::Cancelled {
+                        run_time,
+                        transition_count,
+                        success_count,
+                        dash_spent_identity,
+                        dash_spent_wallet,
+                    } => {
+                        format!(
+                            "Strategy '{}' cancelled after {}, no run report saved.\n\nState \
+                             transitions attempted: {}\nState transitions succeeded: {}\nDash \
+                             spent (Identity): {}\nDash spent (Wallet): {}",
+                            strategy_name,
+                            format_duration(*run_time),
+                            format_count(*transition_count),
+                            format_count(*success_count),
+                            dash_spent_identity,
+                            dash_spent_wallet
+                        )
+                    }
                 };
 
                 self.info = Info::new_fixed(&display_text);
                 ScreenFeedback::Redraw
             }
+            Event::Backend(BackendEvent::TaskCompleted {
+                task:
+                    Task::Strategy(
+                        StrategyTask::RunAcrossUpgrade { .. } | StrategyTask::RunChain { .. },
+                    ),
+                execution_result,
+            }) => {
+                self.strategy_running = false;
+                self.info = Info::new_from_result(execution_result);
+                ScreenFeedback::Redraw
+            }
+            Event::Tick { .. } if self.strategy_running => {
+                let progress = self
+                    .selected_strategy
+                    .as_ref()
+                    .and_then(|strategy_name| self.strategy_runs.progress(strategy_name));
+                let text = match progress {
+                    Some(progress) if !progress.finished => {
+                        format!(
+                            "Strategy is running, please wait.\n\nElapsed: {} of {}\n\n{}",
+                            format_secs(progress.elapsed_secs),
+                            format_secs(progress.duration_secs),
+                            progress.stage_gauges()
+                        )
+                    }
+                    _ => "Strategy is running, please wait.".to_owned(),
+                };
+                self.info = Info::new_fixed(&text);
+                ScreenFeedback::Redraw
+            }
             Event::Backend(BackendEvent::StrategyError { error }) => {
                 self.strategy_running = false;
 
@@ -153,39 +363,168 @@ impl ScreenController for RunStrategyScreenController {
     }
 
     fn view(&mut self, frame: &mut Frame, area: Rect) {
-        if self.strategy_running {
-            self.info = Info::new_fixed("Strategy is running, please wait.");
-        }
         self.info.view(frame, area)
     }
 }
 
+/// Addresses of every configured DAPI address set, a run may be pinned to any
+/// of them.
+fn pinnable_addresses() -> Vec<String> {
+    let mut addresses = vec![NO_PINNED_ADDRESS.to_owned()];
+    for (_, set) in Config::load().dapi_address_sets() {
+        for address in set.split(',').map(str::trim) {
+            if !address.is_empty() && !addresses.iter().any(|known| known == address) {
+                addresses.push(address.to_owned());
+            }
+        }
+    }
+    addresses
+}
+
+/// Known identities other than the loaded one that could fund top ups, by
+/// their label in the run form.
+fn reserve_candidates(snapshot: &AppStateSnapshot) -> Vec<(String, Identifier)> {
+    let loaded_id = snapshot
+        .loaded_identity
+        .as_ref()
+        .map(|identity| identity.id());
+    snapshot
+        .known_identities
+        .iter()
+        .filter(|(id, identity)| Some(**id) != loaded_id && identity.balance() > 0)
+        .map(|(id, identity)| {
+            let b58_id = id.to_string(Encoding::Base58);
+            let label = match snapshot
+                .known_identities_names
+                .get(id)
+                .and_then(|names| names.first())
+            {
+                Some(name) => format!(
+                    "{} ({}), {}",
+                    name,
+                    b58_id,
+                    units::format_credits(identity.balance())
+                ),
+                None => format!("{}, {}", b58_id, units::format_credits(identity.balance())),
+            };
+            (label, *id)
+        })
+        .collect()
+}
+
 pub(super) struct RunStrategyFormController {
     input: ComposedInput<(
         Field<TextInput<DefaultTextInputParser<u64>>>, // Number of blocks or seconds
         Field<TextInput<DefaultTextInputParser<u64>>>, // Seconds per loop
         Field<SelectInput<String>>,                    // Verify proofs?
+        Field<SelectInput<String>>,                    // Withdrawals destination
+        Field<TextInput<CreditsTextInputParser>>,      // Spend cap
+        Field<TextInput<DefaultTextInputParser<f64>>>, // Target broadcast rate
+        Field<TextInput<LoadProfileTextInputParser>>,  // Load profile
+        Field<SelectInput<String>>,                    // Reserve identity
+        Field<TextInput<CreditsTextInputParser>>,      // Reserve top up amount
         // Field<TextInput<DefaultTextInputParser<f64>>>, // Top up amount
         Field<SelectInput<String>>, // Confirm
     )>,
     selected_strategy: String,
+    reserve_candidates: Vec<(String, Identifier)>,
+    /// Stored timeouts and retries of the strategy, the advanced options
+    /// start from them
+    retry_policy: RetryPolicy,
 }
 
 impl RunStrategyFormController {
-    pub(super) fn new(selected_strategy: String) -> Self {
+    /// Form of a run of the strategy, filled with the settings it last ran
+    /// with as of the snapshot.
+    pub(super) fn new(selected_strategy: String, snapshot: &AppStateSnapshot) -> Self {
+        let reserve_candidates = reserve_candidates(snapshot);
+        let retry_policy = snapshot
+            .strategy_retry_policies
+            .get(&selected_strategy)
+            .copied()
+            .unwrap_or_default();
+        let defaults = snapshot.strategy_run_defaults.get(&selected_strategy);
+
+        let reserve_choices = std::iter::once(NO_RESERVE.to_owned())
+            .chain(reserve_candidates.iter().map(|(label, _)| label.clone()))
+            .collect();
+        // Line of the reserve choice, the first one being asset locks
+        let reserve_line = defaults
+            .and_then(|defaults| defaults.reserve_identity)
+            .and_then(|reserve_id| {
+                reserve_candidates
+                    .iter()
+                    .position(|(_, identity_id)| *identity_id == reserve_id)
+            })
+            .map(|index| index + 1)
+            .unwrap_or_default();
+        let (duration_input, seconds_per_loop_input) = match defaults {
+            Some(defaults) => (
+                TextInput::new_init_value("Enter a whole number", defaults.duration_secs),
+                TextInput::new_init_value("Enter a whole number", defaults.seconds_per_loop),
+            ),
+            None => (
+                TextInput::new("Enter a whole number"),
+                TextInput::new("Enter a whole number"),
+            ),
+        };
         RunStrategyFormController {
             input: ComposedInput::new((
-                Field::new(
-                    "Number of seconds to run the strategy",
-                    TextInput::new("Enter a whole number"),
-                ),
+                Field::new("Number of seconds to run the strategy", duration_input),
                 Field::new(
                     "Number of seconds between each batch of transitions",
-                    TextInput::new("Enter a whole number"),
+                    seconds_per_loop_input,
                 ),
                 Field::new(
                     "Verify proofs?",
-                    SelectInput::new(vec!["No".to_string(), "Yes".to_string()]),
+                    SelectInput::new_init_line(
+                        vec!["No".to_string(), "Yes".to_string()],
+                        defaults.is_some_and(|defaults| defaults.verify_proofs) as usize,
+                    ),
+                ),
+                Field::new(
+                    "Withdraw the funds of created identities to",
+                    SelectInput::new(vec![
+                        "Loaded wallet".to_string(),
+                        "Sweep wallet".to_string(),
+                    ]),
+                ),
+                Field::new(
+                    "Maximum amount the run may spend (0 for no cap, required on mainnet)",
+                    TextInput::new_str_value_with_parser(CreditsTextInputParser, "Amount", "0"),
+                ),
+                Field::new(
+                    "Pace broadcasts to this many transitions per second (0 for no pacing)",
+                    TextInput::new_init_value(
+                        "Enter a number (decimals ok)",
+                        defaults
+                            .and_then(|defaults| defaults.target_rate)
+                            .unwrap_or_default(),
+                    ),
+                ),
+                Field::new(
+                    "Shape of the load over the run, each phase is reported separately",
+                    TextInput::new_str_value_with_parser(
+                        LoadProfileTextInputParser,
+                        "Load profile",
+                        &LoadProfile::Constant.to_string(),
+                    ),
+                ),
+                Field::new(
+                    "Top up identities running out of credits from a reserve identity?",
+                    SelectInput::new_init_line(reserve_choices, reserve_line),
+                ),
+                Field::new(
+                    "Amount of each top up from the reserve (ignored with asset locks)",
+                    TextInput::new_str_value_with_parser(
+                        CreditsTextInputParser,
+                        "Amount",
+                        &units::format_credits(
+                            defaults
+                                .map(|defaults| defaults.reserve_credits)
+                                .unwrap_or_default(),
+                        ),
+                    ),
                 ),
                 // Field::new(
                 //     "Amount to top up identities. Enter 0 for none.",
@@ -193,10 +532,17 @@ impl RunStrategyFormController {
                 // ),
                 Field::new(
                     "Confirm start",
-                    SelectInput::new(vec!["No".to_string(), "Yes".to_string()]),
+                    SelectInput::new(vec![
+                        "No".to_string(),
+                        "Yes".to_string(),
+                        RUN_IN_BACKGROUND.to_string(),
+                        ADVANCED_OPTIONS.to_string(),
+                    ]),
                 ),
             )),
             selected_strategy,
+            reserve_candidates,
+            retry_policy,
         }
     }
 }
@@ -208,41 +554,60 @@ impl FormController for RunStrategyFormController {
                 num_blocks,
                 seconds_per_loop,
                 verify_proofs,
+                withdrawals_destination,
+                spend_cap,
+                target_rate,
+                load_profile,
+                reserve,
+                reserve_credits,
                 // top_up_amount_dash,
                 confirm,
             )) => {
+                // Rarely used options keep their defaults unless set in the
+                // advanced options form, timeouts and retries are the stored ones
+                let run_options = RunOptions {
+                    save_defaults: true,
+                    withdraw_to_sweep_wallet: withdrawals_destination == "Sweep wallet",
+                    spend_cap: (spend_cap > 0).then_some(spend_cap),
+                    reserve_top_up: self
+                        .reserve_candidates
+                        .iter()
+                        .find(|(label, _)| *label == reserve)
+                        .map(|(_, identity_id)| ReserveTopUp {
+                            identity_id: *identity_id,
+                            credits: reserve_credits,
+                        }),
+                    target_rate: (target_rate > 0.0).then_some(target_rate),
+                    load_profile,
+                    ..Default::default()
+                };
                 // let top_up_amount_credits = (top_up_amount_dash * 100_000_000_000.0) as u64;
-                if confirm == "Yes" {
-                    if verify_proofs == "Yes" {
-                        FormStatus::Done {
-                            task: Task::Strategy(StrategyTask::RunStrategy(
-                                self.selected_strategy.clone(),
-                                num_blocks,
-                                seconds_per_loop,
-                                true,
-                                0, // top up amount
-                            )),
-                            block: true,
-                        }
-                    } else {
-                        FormStatus::Done {
-                            task: Task::Strategy(StrategyTask::RunStrategy(
-                                self.selected_strategy.clone(),
-                                num_blocks,
-                                seconds_per_loop,
-                                false,
-                                0, // top up amount
-                            )),
-                            block: true,
-                        }
+                if confirm == "Yes" || confirm == RUN_IN_BACKGROUND {
+                    FormStatus::Done {
+                        task: Task::Strategy(StrategyTask::RunStrategy(
+                            self.selected_strategy.clone(),
+                            num_blocks,
+                            seconds_per_loop,
+                            verify_proofs == "Yes",
+                            0, // top up amount
+                            run_options,
+                        )),
+                        block: confirm == "Yes",
                     }
+                } else if confirm == ADVANCED_OPTIONS {
+                    FormStatus::NextForm(Box::new(AdvancedRunOptionsFormController::new(
+                        self.selected_strategy.clone(),
+                        num_blocks,
+                        seconds_per_loop,
+                        verify_proofs == "Yes",
+                        run_options,
+                        self.retry_policy,
+                    )))
                 } else {
                     FormStatus::PreviousScreen
                 }
             }
-            InputStatus::Redraw => FormStatus::Redraw,
-            InputStatus::None => FormStatus::None,
-            InputStatus::Exit => FormStatus::Exit,
+            status => status.into(),
         }
     }
 
@@ -266,3 +631,636 @@ impl FormController for RunStrategyFormController {
         self.input.steps_number()
     }
 }
+
+/// Rarely used options of a run, set after the run form when chosen there.
+struct AdvancedRunOptionsFormController {
+    input: ComposedInput<(
+        Field<TextInput<DefaultTextInputParser<u64>>>, // Wait timeout seconds
+        Field<TextInput<DefaultTextInputParser<usize>>>, // Wait retries
+        Field<TextInput<DefaultTextInputParser<u64>>>, // Broadcast connect timeout ms
+        Field<TextInput<DefaultTextInputParser<u64>>>, // Broadcast timeout ms
+        Field<TextInput<DefaultTextInputParser<usize>>>, // Broadcast retries
+        Field<TextInput<DefaultTextInputParser<usize>>>, // Max outstanding waits
+        Field<TextInput<DefaultTextInputParser<usize>>>, // Max transitions in flight
+        Field<TextInput<DefaultTextInputParser<usize>>>, // Max concurrent broadcasts
+        Field<TextInput<DefaultTextInputParser<u64>>>, // p95 latency target seconds
+        Field<TextInput<DefaultTextInputParser<u16>>>, // User fee increase
+        Field<TextInput<DefaultTextInputParser<u64>>>, // Warm-up seconds
+        Field<TextInput<DefaultTextInputParser<u64>>>, // Cool-down seconds
+        Field<TextInput<DefaultTextInputParser<usize>>>, // Asset locks per transaction
+        Field<SelectInput<String>>,                    // Pinned DAPI address
+        Field<SelectInput<String>>,                    // Ban failing addresses?
+        Field<SelectInput<String>>,                    // Mirror transitions?
+        Field<SelectInput<String>>,                    // Trace transitions?
+        Field<SelectInput<DeletePolicy>>,              // Delete policy
+        Field<TextInput<DefaultTextInputParser<u64>>>, // Seed
+        Field<SelectInput<String>>,                    // Confirm
+    )>,
+    selected_strategy: String,
+    duration: u64,
+    seconds_per_loop: u64,
+    verify_proofs: bool,
+    /// Options chosen in the run form
+    run_options: RunOptions,
+}
+
+impl AdvancedRunOptionsFormController {
+    fn new(
+        selected_strategy: String,
+        duration: u64,
+        seconds_per_loop: u64,
+        verify_proofs: bool,
+        run_options: RunOptions,
+        retry_policy: RetryPolicy,
+    ) -> Self {
+        AdvancedRunOptionsFormController {
+            input: ComposedInput::new((
+                Field::new(
+                    "Seconds to wait for a transition result",
+                    TextInput::new_init_value(
+                        "Enter a whole number",
+                        retry_policy.wait_timeout_secs,
+                    ),
+                ),
+                Field::new(
+                    "Retries when waiting for a transition result",
+                    TextInput::new_init_value("Enter a whole number", retry_policy.wait_retries),
+                ),
+                Field::new(
+                    "Milliseconds to connect to a node when broadcasting (at most half a loop)",
+                    TextInput::new_init_value(
+                        "Enter a whole number",
+                        retry_policy.broadcast_connect_timeout_ms,
+                    ),
+                ),
+                Field::new(
+                    "Milliseconds a broadcast may take (at most half a loop)",
+                    TextInput::new_init_value(
+                        "Enter a whole number",
+                        retry_policy.broadcast_timeout_ms,
+                    ),
+                ),
+                Field::new(
+                    "Retries of a failed broadcast, on another node",
+                    TextInput::new_init_value(
+                        "Enter a whole number",
+                        retry_policy.broadcast_retries,
+                    ),
+                ),
+                Field::new(
+                    "Give up on the oldest waits above this many outstanding (0 for no limit)",
+                    TextInput::new_init_value(
+                        "Enter a whole number",
+                        run_options.wait.max_outstanding,
+                    ),
+                ),
+                Field::new(
+                    "Hold off preparing transitions above this many broadcasts and waits in \
+                     flight (0 for no limit)",
+                    TextInput::new_init_value(
+                        "Enter a whole number",
+                        run_options.wait.max_in_flight,
+                    ),
+                ),
+                Field::new(
+                    "Broadcast at most this many transitions of a loop at once (0 for no limit)",
+                    TextInput::new_init_value(
+                        "Enter a whole number",
+                        run_options.wait.max_concurrent_broadcasts,
+                    ),
+                ),
+                Field::new(
+                    "Adjust the load to keep p95 inclusion latency under this many seconds (0 \
+                     for a fixed load)",
+                    TextInput::new_init_value("Enter a whole number", 0),
+                ),
+                Field::new(
+                    "Fee increase of identity signed transitions, in percent of the base fee (0 \
+                     for none)",
+                    TextInput::new_init_value("Enter a whole number", 0),
+                ),
+                Field::new(
+                    "Seconds of warm-up excluded from the rates and wait times",
+                    TextInput::new_init_value("Enter a whole number", 0),
+                ),
+                Field::new(
+                    "Seconds of cool-down excluded from the rates and wait times",
+                    TextInput::new_init_value("Enter a whole number", 0),
+                ),
+                Field::new(
+                    "Asset locks funded by each L1 transaction when initializing (at most 24)",
+                    TextInput::new_init_value("Enter a whole number", 1),
+                ),
+                Field::new(
+                    "Send all requests to a single DAPI address?",
+                    SelectInput::new(pinnable_addresses()),
+                ),
+                Field::new(
+                    "Ban DAPI addresses failing requests for a while?",
+                    SelectInput::new(vec!["No".to_string(), "Yes".to_string()]),
+                ),
+                Field::new(
+                    "Mirror transitions to EXPLORER_MIRROR_DAPI_ADDRESSES? (experimental)",
+                    SelectInput::new(vec!["No".to_string(), "Yes".to_string()]),
+                ),
+                Field::new(
+                    "Write a trace of every transition to supporting_files/run_traces?",
+                    SelectInput::new(vec!["No".to_string(), "Yes".to_string()]),
+                ),
+                Field::new(
+                    "Documents delete operations target (local-drive: any known, others: those \
+                     created by the run)",
+                    SelectInput::new(DeletePolicy::ALL.to_vec()),
+                ),
+                Field::new(
+                    "Seed of the random choices, to replay an earlier run (0 for a random one)",
+                    TextInput::new_init_value("Enter a whole number", 0),
+                ),
+                Field::new(
+                    "Confirm start",
+                    SelectInput::new(vec![
+                        "No".to_string(),
+                        "Yes".to_string(),
+                        RUN_IN_BACKGROUND.to_string(),
+                    ]),
+                ),
+            )),
+            selected_strategy,
+            duration,
+            seconds_per_loop,
+            verify_proofs,
+            run_options,
+        }
+    }
+}
+
+impl FormController for AdvancedRunOptionsFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done((
+                wait_timeout,
+                wait_retries,
+                broadcast_connect_timeout_ms,
+                broadcast_timeout_ms,
+                broadcast_retries,
+                max_outstanding_waits,
+                max_in_flight,
+                max_concurrent_broadcasts,
+                latency_target_secs,
+                user_fee_increase,
+                warm_up_secs,
+                cool_down_secs,
+                asset_locks_per_transaction,
+                pinned_address,
+                ban_failed_addresses,
+                mirror,
+                trace_transitions,
+                delete_policy,
+                seed,
+                confirm,
+            )) => {
+                if confirm != "Yes" && confirm != RUN_IN_BACKGROUND {
+                    return FormStatus::PreviousScreen;
+                }
+                let run_options = RunOptions {
+                    wait: WaitSettings {
+                        max_outstanding: max_outstanding_waits,
+                        max_in_flight,
+                        max_concurrent_broadcasts,
+                    },
+                    retry_policy: Some(RetryPolicy {
+                        wait_timeout_secs: wait_timeout,
+                        wait_retries,
+                        broadcast_connect_timeout_ms,
+                        broadcast_timeout_ms,
+                        broadcast_retries,
+                    }),
+                    latency_target: (latency_target_secs > 0)
+                        .then(|| Duration::from_secs(latency_target_secs)),
+                    warm_up: Duration::from_secs(warm_up_secs),
+                    cool_down: Duration::from_secs(cool_down_secs),
+                    asset_locks_per_transaction,
+                    pinned_address: (pinned_address != NO_PINNED_ADDRESS).then_some(pinned_address),
+                    mirror: mirror == "Yes",
+                    trace_transitions: trace_transitions == "Yes",
+                    delete_policy,
+                    ban_failed_addresses: ban_failed_addresses == "Yes",
+                    user_fee_increase,
+                    seed: (seed > 0).then_some(seed),
+                    ..self.run_options.clone()
+                };
+                FormStatus::Done {
+                    task: Task::Strategy(StrategyTask::RunStrategy(
+                        self.selected_strategy.clone(),
+                        self.duration,
+                        self.seconds_per_loop,
+                        self.verify_proofs,
+                        0, // top up amount
+                        run_options,
+                    )),
+                    block: confirm == "Yes",
+                }
+            }
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Advanced run options"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        self.input.step_name()
+    }
+
+    fn step_index(&self) -> u8 {
+        self.input.step_index()
+    }
+
+    fn steps_number(&self) -> u8 {
+        self.input.steps_number()
+    }
+}
+
+pub(super) struct RunBaselineFormController {
+    input: ComposedInput<(
+        Field<SelectInput<String>>,                    // Report
+        Field<TextInput<DefaultTextInputParser<f64>>>, // p95 increase tolerance
+        Field<TextInput<DefaultTextInputParser<f64>>>, // Successful rate drop tolerance
+        Field<TextInput<DefaultTextInputParser<u64>>>, // Success percentage drop tolerance
+    )>,
+    strategy_name: String,
+}
+
+impl RunBaselineFormController {
+    pub(super) fn new(strategy_name: String, reports: Vec<String>) -> Self {
+        let tolerances = Tolerances::default();
+        RunBaselineFormController {
+            input: ComposedInput::new((
+                Field::new("Run report", SelectInput::new(reports)),
+                Field::new(
+                    "Flag p95 wait times higher than the baseline by more than (%)",
+                    TextInput::new_init_value(
+                        "Enter a percentage",
+                        tolerances.p95_increase_percent,
+                    ),
+                ),
+                Field::new(
+                    "Flag successful rates lower than the baseline by more than (%)",
+                    TextInput::new_init_value(
+                        "Enter a percentage",
+                        tolerances.success_rate_drop_percent,
+                    ),
+                ),
+                Field::new(
+                    "Flag success percentages lower than the baseline by more than (points)",
+                    TextInput::new_init_value(
+                        "Enter a whole number",
+                        tolerances.success_percent_drop,
+                    ),
+                ),
+            )),
+            strategy_name,
+        }
+    }
+}
+
+impl FormController for RunBaselineFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done((
+                report_file,
+                p95_increase_percent,
+                success_rate_drop_percent,
+                success_percent_drop,
+            )) => FormStatus::Done {
+                task: Task::Strategy(StrategyTask::SetRunBaseline {
+                    strategy_name: self.strategy_name.clone(),
+                    report_file,
+                    tolerances: Tolerances {
+                        p95_increase_percent,
+                        success_rate_drop_percent,
+                        success_percent_drop,
+                    },
+                }),
+                block: false,
+            },
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Run baseline"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        self.input.step_name()
+    }
+
+    fn step_index(&self) -> u8 {
+        self.input.step_index()
+    }
+
+    fn steps_number(&self) -> u8 {
+        self.input.steps_number()
+    }
+}
+
+pub(super) struct DryRunFormController {
+    input: ComposedInput<(
+        Field<TextInput<DefaultTextInputParser<u64>>>, // Number of seconds
+        Field<TextInput<DefaultTextInputParser<u64>>>, // Seconds per loop
+        Field<TextInput<DefaultTextInputParser<usize>>>, // Asset locks per transaction
+        Field<SelectInput<DeletePolicy>>,              // Delete policy
+        Field<TextInput<DefaultTextInputParser<u64>>>, // Seed
+    )>,
+    strategy_name: String,
+}
+
+impl DryRunFormController {
+    pub(super) fn new(strategy_name: String) -> Self {
+        DryRunFormController {
+            input: ComposedInput::new((
+                Field::new(
+                    "Number of seconds the run would last",
+                    TextInput::new("Enter a whole number"),
+                ),
+                Field::new(
+                    "Number of seconds between each batch of transitions",
+                    TextInput::new_init_value("Enter a whole number", 1),
+                ),
+                Field::new(
+                    "Asset locks funded by each L1 transaction when initializing (at most 24)",
+                    TextInput::new_init_value("Enter a whole number", 1),
+                ),
+                Field::new(
+                    "Documents delete operations target (local-drive: any known, others: those \
+                     created by the run)",
+                    SelectInput::new(DeletePolicy::ALL.to_vec()),
+                ),
+                Field::new(
+                    "Seed of the random choices, to replay an earlier run (0 for a random one)",
+                    TextInput::new_init_value("Enter a whole number", 0),
+                ),
+            )),
+            strategy_name,
+        }
+    }
+}
+
+impl FormController for DryRunFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done((
+                duration,
+                seconds_per_loop,
+                asset_locks_per_transaction,
+                delete_policy,
+                seed,
+            )) => FormStatus::Done {
+                task: Task::Strategy(StrategyTask::DryRun {
+                    strategy_name: self.strategy_name.clone(),
+                    duration,
+                    seconds_per_loop,
+                    run_options: RunOptions {
+                        asset_locks_per_transaction,
+                        delete_policy,
+                        seed: (seed > 0).then_some(seed),
+                        ..Default::default()
+                    },
+                }),
+                block: true,
+            },
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Dry run"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        self.input.step_name()
+    }
+
+    fn step_index(&self) -> u8 {
+        self.input.step_index()
+    }
+
+    fn steps_number(&self) -> u8 {
+        self.input.steps_number()
+    }
+}
+
+pub(super) struct RunAcrossUpgradeFormController {
+    input: ComposedInput<(
+        Field<TextInput<DefaultTextInputParser<u32>>>, // Protocol version
+        Field<TextInput<DefaultTextInputParser<u64>>>, // Votes needed
+        Field<TextInput<DefaultTextInputParser<u64>>>, // Lead time minutes
+        Field<TextInput<DefaultTextInputParser<u64>>>, // Post-activation minutes
+        Field<TextInput<DefaultTextInputParser<u64>>>, // Seconds per loop
+        Field<SelectInput<String>>,                    // Confirm
+    )>,
+    strategy_name: String,
+}
+
+impl RunAcrossUpgradeFormController {
+    pub(super) fn new(strategy_name: String) -> Self {
+        RunAcrossUpgradeFormController {
+            input: ComposedInput::new((
+                Field::new(
+                    "Protocol version to wait for",
+                    TextInput::new("Enter a whole number"),
+                ),
+                Field::new(
+                    "Votes the version needs to activate at the next epoch",
+                    TextInput::new("Enter a whole number"),
+                ),
+                Field::new(
+                    "Minutes before the estimated activation to start the run",
+                    TextInput::new_init_value("Enter a whole number", 10),
+                ),
+                Field::new(
+                    "Minutes to keep running after the estimated activation",
+                    TextInput::new_init_value("Enter a whole number", 10),
+                ),
+                Field::new(
+                    "Number of seconds between each batch of transitions",
+                    TextInput::new_init_value("Enter a whole number", 1),
+                ),
+                Field::new(
+                    "Confirm waiting for the upgrade (the screen stays busy until then)",
+                    SelectInput::new(vec!["No".to_string(), "Yes".to_string()]),
+                ),
+            )),
+            strategy_name,
+        }
+    }
+}
+
+impl FormController for RunAcrossUpgradeFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done((
+                protocol_version,
+                votes_needed,
+                lead_minutes,
+                post_activation_minutes,
+                seconds_per_loop,
+                confirm,
+            )) => {
+                if confirm == "Yes" {
+                    FormStatus::Done {
+                        task: Task::Strategy(StrategyTask::RunAcrossUpgrade {
+                            strategy_name: self.strategy_name.clone(),
+                            seconds_per_loop,
+                            run_options: RunOptions::default(),
+                            upgrade_window: UpgradeWindowSettings {
+                                protocol_version,
+                                votes_needed,
+                                lead_time: Duration::from_secs(lead_minutes * 60),
+                                post_activation: Duration::from_secs(post_activation_minutes * 60),
+                            },
+                        }),
+                        block: true,
+                    }
+                } else {
+                    FormStatus::PreviousScreen
+                }
+            }
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Run across upgrade"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        self.input.step_name()
+    }
+
+    fn step_index(&self) -> u8 {
+        self.input.step_index()
+    }
+
+    fn steps_number(&self) -> u8 {
+        self.input.steps_number()
+    }
+}
+
+pub(super) struct RunChainFormController {
+    input: ComposedInput<(
+        Field<TextInput<DefaultTextInputParser<String>>>, // Next strategies
+        Field<TextInput<DefaultTextInputParser<u64>>>,    // Number of seconds
+        Field<TextInput<DefaultTextInputParser<u64>>>,    // Seconds per loop
+        Field<SelectInput<String>>,                       // Verify proofs?
+        Field<SelectInput<String>>,                       // Confirm
+    )>,
+    strategy_name: String,
+}
+
+impl RunChainFormController {
+    pub(super) fn new(strategy_name: String) -> Self {
+        RunChainFormController {
+            input: ComposedInput::new((
+                Field::new(
+                    "Strategies to run next, in order, separated by commas",
+                    TextInput::new("strategy names"),
+                ),
+                Field::new(
+                    "Number of seconds each strategy runs for",
+                    TextInput::new("Enter a whole number"),
+                ),
+                Field::new(
+                    "Number of seconds between each batch of transitions",
+                    TextInput::new_init_value("Enter a whole number", 1),
+                ),
+                Field::new(
+                    "Verify proofs?",
+                    SelectInput::new(vec!["No".to_string(), "Yes".to_string()]),
+                ),
+                Field::new(
+                    "Confirm running the chain (identities are withdrawn from at its end)",
+                    SelectInput::new(vec!["No".to_string(), "Yes".to_string()]),
+                ),
+            )),
+            strategy_name,
+        }
+    }
+}
+
+impl FormController for RunChainFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done((
+                next_strategies,
+                duration,
+                seconds_per_loop,
+                verify_proofs,
+                confirm,
+            )) => {
+                if confirm == "Yes" {
+                    let strategy_names = std::iter::once(self.strategy_name.clone())
+                        .chain(
+                            next_strategies
+                                .split(',')
+                                .map(str::trim)
+                                .filter(|name| !name.is_empty())
+                                .map(str::to_owned),
+                        )
+                        .collect();
+                    FormStatus::Done {
+                        task: Task::Strategy(StrategyTask::RunChain {
+                            strategy_names,
+                            duration,
+                            seconds_per_loop,
+                            verify_proofs: verify_proofs == "Yes",
+                            run_options: RunOptions::default(),
+                        }),
+                        block: true,
+                    }
+                } else {
+                    FormStatus::PreviousScreen
+                }
+            }
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Run a chain of strategies"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        self.input.step_name()
+    }
+
+    fn step_index(&self) -> u8 {
+        self.input.step_index()
+    }
+
+    fn steps_number(&self) -> u8 {
+        self.input.steps_number()
+    }
+}
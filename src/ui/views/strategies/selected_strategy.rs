@@ -1,10 +1,11 @@
 //! Screens and forms related to strategies manipulation.
 
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, sync::Arc};
 
 use dpp::{
     data_contract::accessors::v0::DataContractV0Getters, platform_value::string_encoding::Encoding,
 };
+use itertools::Itertools;
 use strategy_tests::{
     operations::{
         DataContractUpdateAction::{DataContractNewDocumentTypes, DataContractNewOptionalFields},
@@ -12,20 +13,28 @@ use strategy_tests::{
     },
     Strategy,
 };
+use tokio::sync::watch;
 use tuirealm::{
     event::{Key, KeyEvent, KeyModifiers},
-    tui::prelude::Rect,
+    tui::prelude::{Constraint, Direction, Layout, Rect},
     Frame,
 };
 
 use super::{
+    apply_random_variant_aliases, contract_display,
     identity_inserts::IdentityInsertsScreenController, operations::OperationsScreenController,
+    run_strategy::DryRunFormController, run_strategy::RunAcrossUpgradeFormController,
+    run_strategy::RunBaselineFormController, run_strategy::RunChainFormController,
     run_strategy::RunStrategyFormController, run_strategy::RunStrategyScreenController,
     start_contracts::StartContractsScreenController,
-    start_identities::StartIdentitiesScreenController,
+    start_identities::StartIdentitiesScreenController, times_per_block_display,
 };
 use crate::{
-    backend::{AppState, AppStateUpdate, BackendEvent},
+    backend::{
+        snapshot::AppStateSnapshot, state::ContractAliases, AppState, AppStateUpdate, BackendEvent,
+        StrategyContractNames, WalletTask,
+    },
+    ui::id_names::IdNames,
     ui::screen::{
         utils::impl_builder, widgets::info::Info, ScreenCommandKey, ScreenController,
         ScreenFeedback, ScreenToggleKey,
@@ -34,22 +43,50 @@ use crate::{
 };
 
 use crate::{
-    backend::{StrategyTask, Task},
+    backend::{
+        contract_weights::{self, ContractWeights},
+        dry_run::StrategySummary,
+        frequency_profile::FrequencyProfile,
+        run_report,
+        strategy_lint::lint_strategy,
+        strategy_scaling::{self, ScalePlan},
+        StrategyTask, Task,
+    },
     ui::form::{
-        parsers::DefaultTextInputParser, FormController, FormStatus, Input, InputStatus, TextInput,
+        parsers::DefaultTextInputParser, FormController, FormStatus, Input, InputStatus,
+        SelectInput, TextInput,
     },
 };
 
-const COMMAND_KEYS: [ScreenCommandKey; 7] = [
+const COMMAND_KEYS: [ScreenCommandKey; 18] = [
     ScreenCommandKey::new("q", "Back to Strategies"),
     ScreenCommandKey::new("r", "Run strategy"),
+    ScreenCommandKey::new("y", "Dry run"),
     ScreenCommandKey::new("l", "Clone this strategy"),
     ScreenCommandKey::new("c", "Start contracts"),
     ScreenCommandKey::new("i", "Identity inserts"),
     ScreenCommandKey::new("o", "Operations"),
     ScreenCommandKey::new("s", "Start identities"),
+    ScreenCommandKey::new("x", "Export signer bundle"),
+    ScreenCommandKey::new("h", "Hourly frequency profile"),
+    ScreenCommandKey::new("w", "Edit contract weights in $EDITOR"),
+    ScreenCommandKey::new("b", "Set run baseline"),
+    ScreenCommandKey::new("u", "Run across upgrade"),
+    ScreenCommandKey::new("a", "Run a chain starting with this strategy"),
+    ScreenCommandKey::new("d", "Cycle summary run duration"),
+    ScreenCommandKey::new("p", "Cycle summary seconds per loop"),
+    ScreenCommandKey::new(
+        "f",
+        "Split wallet UTXOs to fund a run of the summary duration",
+    ),
+    ScreenCommandKey::new("g", "Scale to the wallet for a run of the summary duration"),
 ];
 
+/// Run durations in seconds the summary can be computed for
+const SUMMARY_DURATIONS: [u64; 5] = [60, 300, 900, 3600, 86400];
+/// Seconds per loop the summary can be computed for
+const SUMMARY_SECONDS_PER_LOOP: [u64; 4] = [1, 2, 5, 10];
+
 const COMMAND_KEYS_NO_SELECTION: [ScreenCommandKey; 1] =
     [ScreenCommandKey::new("q", "Back to Strategies")];
 
@@ -57,40 +94,105 @@ pub struct SelectedStrategyScreenController {
     info: Info,
     available_strategies: Vec<String>,
     selected_strategy: Option<String>,
+    frequency_profile: Option<FrequencyProfile>,
+    contract_weights: ContractWeights,
+    contract_aliases: ContractAliases,
+    id_names: IdNames,
+    snapshots: watch::Receiver<Arc<AppStateSnapshot>>,
+    /// Strategy shown with its contract names, kept to compute the summary
+    /// again
+    shown_strategy: Option<(String, Strategy, StrategyContractNames)>,
+    summary_duration: u64,
+    summary_seconds_per_loop: u64,
 }
 
 impl_builder!(SelectedStrategyScreenController);
 
 impl SelectedStrategyScreenController {
     pub(crate) async fn new(app_state: &AppState) -> Self {
-        let available_strategies_lock = app_state.available_strategies.lock().await;
-        let selected_strategy_lock = app_state.selected_strategy.lock().await;
-
-        let info = if let Some(name) = selected_strategy_lock.as_ref() {
-            let strategy = available_strategies_lock
-                .get(name.as_str())
-                .expect("inconsistent data");
-            let contract_names_lock = app_state.available_strategies_contract_names.lock().await;
-
-            Info::new_fixed(&display_strategy(
-                &name,
-                strategy,
-                contract_names_lock
-                    .get(name.as_str())
-                    .expect("inconsistent data"),
-            ))
+        let snapshot = app_state.snapshot();
+        let selected_strategy = snapshot.selected_strategy.as_ref();
+        let frequency_profile = if let Some(name) = selected_strategy {
+            snapshot.strategy_frequency_profiles.get(name).cloned()
         } else {
-            Info::new_fixed("No strategy selected. Go back.")
+            None
         };
-
-        SelectedStrategyScreenController {
-            info,
-            available_strategies: available_strategies_lock.keys().cloned().collect(),
+        let contract_weights = if let Some(name) = selected_strategy {
+            snapshot
+                .strategy_contract_weights
+                .get(name)
+                .cloned()
+                .unwrap_or_default()
+        } else {
+            ContractWeights::new()
+        };
+        let contract_aliases = if let Some(name) = selected_strategy {
+            snapshot
+                .strategy_contract_aliases
+                .get(name)
+                .cloned()
+                .unwrap_or_default()
+        } else {
+            ContractAliases::new()
+        };
+        let id_names = IdNames::from_snapshot(&snapshot);
+
+        // The strategies may lag behind the selection while a task holds them
+        let shown_strategy = selected_strategy.and_then(|name| {
+            let strategy = snapshot.available_strategies.get(name)?;
+            let contract_names = snapshot.strategy_contract_names.get(name)?;
+            Some((name.clone(), strategy.clone(), contract_names.clone()))
+        });
+
+        let mut controller = SelectedStrategyScreenController {
+            info: Info::new_fixed("No strategy selected. Go back."),
+            available_strategies: snapshot.available_strategies.keys().cloned().collect(),
             selected_strategy: None,
+            frequency_profile,
+            contract_weights,
+            contract_aliases,
+            id_names,
+            snapshots: app_state.subscribe_snapshot(),
+            shown_strategy,
+            summary_duration: SUMMARY_DURATIONS[0],
+            summary_seconds_per_loop: SUMMARY_SECONDS_PER_LOOP[0],
+        };
+        controller.update_info();
+        controller
+    }
+
+    /// Displays the shown strategy again, with its summary for the chosen
+    /// run duration.
+    fn update_info(&mut self) {
+        if let Some((strategy_name, strategy, contract_names)) = &self.shown_strategy {
+            self.info = Info::new_fixed(&display_strategy(
+                strategy_name,
+                strategy,
+                contract_names,
+                self.frequency_profile.as_ref(),
+                &self.contract_weights,
+                &self.contract_aliases,
+                &self.id_names,
+                &StrategySummary::new(
+                    strategy,
+                    self.summary_duration,
+                    self.summary_seconds_per_loop,
+                ),
+            ));
         }
     }
 }
 
+/// Value following `current` in `values`, back to the first after the last.
+fn next_preset(values: &[u64], current: u64) -> u64 {
+    let index = values
+        .iter()
+        .position(|value| *value == current)
+        .map(|index| (index + 1) % values.len())
+        .unwrap_or_default();
+    values[index]
+}
+
 impl ScreenController for SelectedStrategyScreenController {
     fn name(&self) -> &'static str {
         "Strategy"
@@ -109,6 +211,8 @@ impl ScreenController for SelectedStrategyScreenController {
     }
 
     fn on_event(&mut self, event: &Event) -> ScreenFeedback {
+        apply_random_variant_aliases(event, &mut self.contract_aliases);
+
         match event {
             Event::Key(KeyEvent {
                 code: Key::Char('q'),
@@ -133,8 +237,36 @@ impl ScreenController for SelectedStrategyScreenController {
             Event::Key(KeyEvent {
                 code: Key::Char('r'),
                 modifiers: KeyModifiers::NONE,
+            }) => {
+                let strategy_name = self.selected_strategy.clone().unwrap();
+                ScreenFeedback::FormThenNextScreen {
+                    form: Box::new(RunStrategyFormController::new(
+                        strategy_name,
+                        &self.snapshots.borrow(),
+                    )),
+                    screen: RunStrategyScreenController::builder(),
+                }
+            }
+            Event::Key(KeyEvent {
+                code: Key::Char('y'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::Form(Box::new(DryRunFormController::new(
+                self.selected_strategy.clone().unwrap(),
+            ))),
+            Event::Key(KeyEvent {
+                code: Key::Char('u'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::FormThenNextScreen {
+                form: Box::new(RunAcrossUpgradeFormController::new(
+                    self.selected_strategy.clone().unwrap(),
+                )),
+                screen: RunStrategyScreenController::builder(),
+            },
+            Event::Key(KeyEvent {
+                code: Key::Char('a'),
+                modifiers: KeyModifiers::NONE,
             }) => ScreenFeedback::FormThenNextScreen {
-                form: Box::new(RunStrategyFormController::new(
+                form: Box::new(RunChainFormController::new(
                     self.selected_strategy.clone().unwrap(),
                 )),
                 screen: RunStrategyScreenController::builder(),
@@ -143,6 +275,224 @@ impl ScreenController for SelectedStrategyScreenController {
                 code: Key::Char('l'),
                 modifiers: KeyModifiers::NONE,
             }) => ScreenFeedback::Form(Box::new(CloneStrategyFormController::new())),
+            Event::Key(KeyEvent {
+                code: Key::Char('d'),
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                self.summary_duration = next_preset(&SUMMARY_DURATIONS, self.summary_duration);
+                self.update_info();
+                ScreenFeedback::Redraw
+            }
+            Event::Key(KeyEvent {
+                code: Key::Char('p'),
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                self.summary_seconds_per_loop =
+                    next_preset(&SUMMARY_SECONDS_PER_LOOP, self.summary_seconds_per_loop);
+                self.update_info();
+                ScreenFeedback::Redraw
+            }
+            Event::Key(KeyEvent {
+                code: Key::Char('f'),
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                if let Some(strategy_name) = &self.selected_strategy {
+                    ScreenFeedback::Task {
+                        task: Task::Wallet(WalletTask::SplitUTXOsForStrategy {
+                            strategy_name: strategy_name.clone(),
+                            duration: self.summary_duration,
+                        }),
+                        block: true,
+                    }
+                } else {
+                    ScreenFeedback::None
+                }
+            }
+            Event::Key(KeyEvent {
+                code: Key::Char('g'),
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                let Some((strategy_name, strategy, _)) = &self.shown_strategy else {
+                    return ScreenFeedback::None;
+                };
+                let (spendable_balance, utxos) = match &self.snapshots.borrow().loaded_wallet {
+                    Some(wallet) => (wallet.spendable_balance(), wallet.spendable_utxos().count()),
+                    None => {
+                        self.info = Info::new_error("No wallet loaded");
+                        return ScreenFeedback::Redraw;
+                    }
+                };
+                match strategy_scaling::scale_to_wallet(
+                    strategy,
+                    self.summary_duration,
+                    spendable_balance,
+                    utxos,
+                ) {
+                    Ok(plan) if plan.fits() => {
+                        self.info = Info::new_fixed(&plan.to_string());
+                        ScreenFeedback::Redraw
+                    }
+                    Ok(plan) => ScreenFeedback::Form(Box::new(ScaleToWalletFormController::new(
+                        strategy_name.clone(),
+                        self.summary_duration,
+                        &plan,
+                    ))),
+                    Err(e) => {
+                        self.info = Info::new_error(&e);
+                        ScreenFeedback::Redraw
+                    }
+                }
+            }
+            Event::Key(KeyEvent {
+                code: Key::Char('x'),
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                if let Some(strategy_name) = &self.selected_strategy {
+                    ScreenFeedback::Task {
+                        task: Task::Strategy(StrategyTask::ExportSignerBundle(
+                            strategy_name.clone(),
+                        )),
+                        block: true,
+                    }
+                } else {
+                    ScreenFeedback::None
+                }
+            }
+            Event::Key(KeyEvent {
+                code: Key::Char('h'),
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                if let Some(strategy_name) = &self.selected_strategy {
+                    ScreenFeedback::Form(Box::new(FrequencyProfileFormController::new(
+                        strategy_name.clone(),
+                        self.frequency_profile.clone(),
+                    )))
+                } else {
+                    ScreenFeedback::None
+                }
+            }
+            Event::Key(KeyEvent {
+                code: Key::Char('w'),
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                let Some((_, strategy, _)) = &self.shown_strategy else {
+                    return ScreenFeedback::None;
+                };
+                let contracts: Vec<(String, String)> = strategy
+                    .operations
+                    .iter()
+                    .filter_map(|operation| match &operation.op_type {
+                        OperationType::Document(op) => {
+                            Some(op.contract.id().to_string(Encoding::Base58))
+                        }
+                        _ => None,
+                    })
+                    .unique()
+                    .map(|contract| {
+                        let display_name =
+                            contract_display(&contract, &self.contract_aliases, &self.id_names);
+                        (contract, display_name)
+                    })
+                    .collect();
+                if contracts.is_empty() {
+                    self.info = Info::new_error("The strategy has no document operations to weigh");
+                    ScreenFeedback::Redraw
+                } else {
+                    ScreenFeedback::EditText {
+                        text: contract_weights::weights_template(
+                            &contracts,
+                            &self.contract_weights,
+                        ),
+                        extension: "txt",
+                    }
+                }
+            }
+            Event::Key(KeyEvent {
+                code: Key::Char('b'),
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                if let Some(strategy_name) = &self.selected_strategy {
+                    let reports = run_report::list_reports(strategy_name);
+                    if reports.is_empty() {
+                        self.info = Info::new_error(
+                            "No run reports for this strategy yet, run it at least once",
+                        );
+                        ScreenFeedback::Redraw
+                    } else {
+                        ScreenFeedback::Form(Box::new(RunBaselineFormController::new(
+                            strategy_name.clone(),
+                            reports,
+                        )))
+                    }
+                } else {
+                    ScreenFeedback::None
+                }
+            }
+            Event::Backend(BackendEvent::TaskCompleted {
+                task:
+                    Task::Strategy(StrategyTask::SetRunBaseline { .. } | StrategyTask::DryRun { .. }),
+                execution_result,
+            }) => {
+                self.info = Info::new_from_result(execution_result);
+                ScreenFeedback::Redraw
+            }
+            // Only sent back unchanged when the strategy fits the wallet, or
+            // doesn't fit a single asset lock
+            Event::Backend(BackendEvent::TaskCompleted {
+                task: Task::Strategy(StrategyTask::ScaleToWallet { .. }),
+                execution_result,
+            }) => {
+                self.info = Info::new_from_result(execution_result);
+                ScreenFeedback::Redraw
+            }
+            Event::Backend(BackendEvent::TaskCompleted {
+                task: Task::Strategy(StrategyTask::SetContractWeights(_, weights)),
+                execution_result,
+            }) => {
+                match execution_result {
+                    Ok(_) => {
+                        self.contract_weights = weights.clone();
+                        self.update_info();
+                    }
+                    Err(e) => self.info = Info::new_error(e),
+                }
+                ScreenFeedback::Redraw
+            }
+            Event::Backend(BackendEvent::TaskCompleted {
+                task: Task::Strategy(StrategyTask::SetFrequencyProfile(_, profile)),
+                execution_result,
+            }) => {
+                if execution_result.is_ok() {
+                    self.frequency_profile = profile.clone();
+                }
+                self.info = Info::new_from_result(execution_result);
+                ScreenFeedback::Redraw
+            }
+            Event::Backend(BackendEvent::TaskCompletedStateChange {
+                app_state_update: AppStateUpdate::StrategyContractAliases(strategy_name, aliases),
+                ..
+            }) => {
+                if self.selected_strategy.as_ref() == Some(strategy_name) {
+                    self.contract_aliases = (*aliases).clone();
+                }
+                ScreenFeedback::None
+            }
+            Event::Backend(
+                BackendEvent::TaskCompleted {
+                    task:
+                        Task::Strategy(StrategyTask::ExportSignerBundle(_))
+                        | Task::Wallet(WalletTask::SplitUTXOsForStrategy { .. }),
+                    execution_result,
+                }
+                | BackendEvent::TaskCompletedStateChange {
+                    task: Task::Wallet(WalletTask::SplitUTXOsForStrategy { .. }),
+                    execution_result,
+                    ..
+                },
+            ) => {
+                self.info = Info::new_from_result(execution_result);
+                ScreenFeedback::Redraw
+            }
             Event::Backend(
                 BackendEvent::AppStateUpdated(AppStateUpdate::SelectedStrategy(
                     strategy_name,
@@ -155,11 +505,12 @@ impl ScreenController for SelectedStrategyScreenController {
                     ..
                 },
             ) => {
-                self.info = Info::new_fixed(&display_strategy(
-                    &strategy_name,
-                    &strategy,
-                    &contract_names,
+                self.shown_strategy = Some((
+                    strategy_name.clone(),
+                    (**strategy).clone(),
+                    (**contract_names).clone(),
                 ));
+                self.update_info();
                 self.selected_strategy = Some(strategy_name.clone());
                 ScreenFeedback::Redraw
             }
@@ -184,20 +535,45 @@ impl ScreenController for SelectedStrategyScreenController {
     fn view(&mut self, frame: &mut Frame, area: Rect) {
         self.info.view(frame, area)
     }
+
+    fn on_text_edited(&mut self, result: Result<String, String>) -> ScreenFeedback {
+        let Some(strategy_name) = &self.selected_strategy else {
+            return ScreenFeedback::None;
+        };
+        match result.and_then(|text| contract_weights::parse_weights(&text)) {
+            Ok(weights) => ScreenFeedback::Task {
+                task: Task::Strategy(StrategyTask::SetContractWeights(
+                    strategy_name.clone(),
+                    weights,
+                )),
+                block: false,
+            },
+            Err(e) => {
+                self.info = Info::new_error(&e);
+                ScreenFeedback::Redraw
+            }
+        }
+    }
 }
 
 fn display_strategy(
     strategy_name: &str,
     strategy: &Strategy,
     contract_updates: &[(String, Option<BTreeMap<u64, String>>)],
+    frequency_profile: Option<&FrequencyProfile>,
+    contract_weights: &ContractWeights,
+    contract_aliases: &ContractAliases,
+    id_names: &IdNames,
+    summary: &StrategySummary,
 ) -> String {
     let mut start_contracts_lines = String::new();
     // Only display the individual contract details in this screen if the number is less than 5
     if contract_updates.len() <= 5 {
         for (contract, updates) in contract_updates.iter() {
             start_contracts_lines.push_str(&format!(
-                "{:indent$}Contract: {contract}\n",
+                "{:indent$}Contract: {}\n",
                 "",
+                contract_display(contract, contract_aliases, id_names),
                 indent = 8
             ));
             for (block, update) in updates.iter().flatten() {
@@ -212,30 +588,7 @@ fn display_strategy(
         }
     }
 
-    let times_per_block_display = if strategy
-        .identity_inserts
-        .frequency
-        .times_per_block_range
-        .end
-        > strategy
-            .identity_inserts
-            .frequency
-            .times_per_block_range
-            .start
-    {
-        strategy
-            .identity_inserts
-            .frequency
-            .times_per_block_range
-            .end
-            - 1
-    } else {
-        strategy
-            .identity_inserts
-            .frequency
-            .times_per_block_range
-            .end
-    };
+    let times_per_block_display = times_per_block_display(&strategy.identity_inserts.frequency);
 
     let identity_inserts_line = format!(
         "{:indent$}Times per block: {}; chance per block: {}\n",
@@ -259,14 +612,24 @@ fn display_strategy(
                         DocumentAction::DocumentActionInsertRandom(..) => {
                             "InsertRandom".to_string()
                         }
+                        DocumentAction::DocumentActionInsertSpecific(..) => {
+                            "InsertSpecific".to_string()
+                        }
                         DocumentAction::DocumentActionDelete => "Delete".to_string(),
-                        // DocumentAction::DocumentActionReplace => "Replace".to_string(),
+                        DocumentAction::DocumentActionReplaceRandom => "ReplaceRandom".to_string(),
+                        DocumentAction::DocumentActionTransferRandom => {
+                            "TransferRandom".to_string()
+                        }
                         _ => panic!("invalid document action selected"),
                     };
                     format!(
                         "Document({}): Contract: {}",
                         op_type,
-                        op.contract.id().to_string(Encoding::Base58)
+                        contract_display(
+                            &op.contract.id().to_string(Encoding::Base58),
+                            contract_aliases,
+                            id_names,
+                        )
                     )
                 }
                 OperationType::IdentityTopUp(amount) => {
@@ -285,23 +648,26 @@ fn display_strategy(
                     format!(
                         "ContractUpdate({}): Contract: {}",
                         op_type,
-                        op.contract.id().to_string(Encoding::Base58)
+                        contract_display(
+                            &op.contract.id().to_string(Encoding::Base58),
+                            contract_aliases,
+                            id_names,
+                        )
                     )
                 }
-                OperationType::IdentityTransfer(_) => "IdentityTransfer".to_string(),
+                OperationType::IdentityTransfer(Some(transfer)) => format!(
+                    "IdentityTransfer: {} -> {}",
+                    id_names.identity(&transfer.from),
+                    id_names.identity(&transfer.to)
+                ),
+                OperationType::IdentityTransfer(None) => "IdentityTransfer".to_string(),
                 OperationType::ResourceVote(_) => "ResourceVote".to_string(),
                 OperationType::Token(token_op) => todo!(),
             };
 
-            let times_per_block_display = if op.frequency.times_per_block_range.end
-                > op.frequency.times_per_block_range.start
-            {
-                op.frequency.times_per_block_range.end - 1
-            } else {
-                op.frequency.times_per_block_range.end
-            };
+            let times_per_block_display = times_per_block_display(&op.frequency);
 
-            if times_per_block_display == 0 {
+            if times_per_block_display == "0" {
                 operations_lines.push_str(&format!(
                     "{:indent$}{}; Times per block: {}, chance per block: {}\n",
                     "",
@@ -323,8 +689,40 @@ fn display_strategy(
         }
     }
 
+    let lint_warnings = lint_strategy(strategy, contract_updates);
+    let mut lint_lines = String::new();
+    if !lint_warnings.is_empty() {
+        lint_lines.push_str("\n\n    Lint warnings:");
+        for warning in lint_warnings {
+            lint_lines.push_str(&format!("\n{:indent$}{}", "", warning, indent = 8));
+        }
+    }
+
     let start_contracts_len = strategy.start_contracts.len();
     let operations_len = strategy.operations.len();
+    let frequency_profile = frequency_profile
+        .map(|profile| profile.to_string())
+        .unwrap_or_else(|| "Constant".to_owned());
+    let total_weight: f64 = contract_weights.values().sum();
+    let contract_weights = if contract_weights.is_empty() {
+        "None".to_owned()
+    } else {
+        contract_weights
+            .iter()
+            .map(|(contract, weight)| {
+                format!(
+                    "{} {:.0}%",
+                    contract_display(contract, contract_aliases, id_names),
+                    if total_weight > 0.0 {
+                        weight / total_weight * 100.0
+                    } else {
+                        0.0
+                    }
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
 
     format!(
         r#"{strategy_name}:
@@ -335,7 +733,11 @@ fn display_strategy(
     Identity inserts:
 {identity_inserts_line}
     Operations ({operations_len}):
-{operations_lines}"#,
+{operations_lines}
+    Hourly frequency profile: {frequency_profile}
+    Contract weights: {contract_weights}{lint_lines}
+
+{summary}"#,
         strategy.start_identities.hard_coded.len(),
         strategy.start_identities.number_of_identities,
     )
@@ -384,3 +786,163 @@ impl FormController for CloneStrategyFormController {
         1
     }
 }
+
+const NO_FREQUENCY_PROFILE: &str = "Constant (no profile)";
+const CUSTOM_FREQUENCY_PROFILE: &str = "Custom (edit in $EDITOR)";
+
+pub(crate) struct FrequencyProfileFormController {
+    input: SelectInput<String>,
+    strategy_name: String,
+    current_profile: Option<FrequencyProfile>,
+    error: Option<Info>,
+}
+
+impl FrequencyProfileFormController {
+    pub(crate) fn new(strategy_name: String, current_profile: Option<FrequencyProfile>) -> Self {
+        let mut variants = vec![NO_FREQUENCY_PROFILE.to_owned()];
+        variants.extend(FrequencyProfile::PRESETS.iter().map(|p| p.to_string()));
+        variants.push(CUSTOM_FREQUENCY_PROFILE.to_owned());
+
+        FrequencyProfileFormController {
+            input: SelectInput::new(variants),
+            strategy_name,
+            current_profile,
+            error: None,
+        }
+    }
+
+    fn set_profile(&self, profile: Option<FrequencyProfile>) -> FormStatus {
+        FormStatus::Done {
+            task: Task::Strategy(StrategyTask::SetFrequencyProfile(
+                self.strategy_name.clone(),
+                profile,
+            )),
+            block: false,
+        }
+    }
+}
+
+impl FormController for FrequencyProfileFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done(choice) if choice == NO_FREQUENCY_PROFILE => self.set_profile(None),
+            InputStatus::Done(choice) if choice == CUSTOM_FREQUENCY_PROFILE => {
+                FormStatus::EditText {
+                    text: self
+                        .current_profile
+                        .as_ref()
+                        .unwrap_or(&FrequencyProfile::Custom(Vec::new()))
+                        .custom_template(),
+                    extension: "txt",
+                    edited: None,
+                }
+            }
+            InputStatus::Done(choice) => {
+                let profile = FrequencyProfile::PRESETS
+                    .into_iter()
+                    .find(|p| p.to_string() == choice);
+                self.set_profile(profile)
+            }
+            status => status.into(),
+        }
+    }
+
+    fn on_text_edited(&mut self, result: Result<String, String>) -> FormStatus {
+        match result.and_then(|text| FrequencyProfile::parse_custom(&text)) {
+            Ok(profile) => self.set_profile(Some(profile)),
+            Err(e) => {
+                self.error = Some(Info::new_error(&e));
+                FormStatus::Redraw
+            }
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Hourly frequency profile"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        if let Some(error) = &mut self.error {
+            let layout = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(3), Constraint::Length(3)].as_ref())
+                .split(area);
+            self.input.view(frame, layout[0]);
+            error.view(frame, layout[1]);
+        } else {
+            self.input.view(frame, area)
+        }
+    }
+
+    fn step_name(&self) -> &'static str {
+        "Multiplies operation frequencies by the hour of day (UTC)"
+    }
+
+    fn step_index(&self) -> u8 {
+        0
+    }
+
+    fn steps_number(&self) -> u8 {
+        1
+    }
+}
+
+/// Confirms scaling the strategy down to the wallet, showing what changes.
+pub(crate) struct ScaleToWalletFormController {
+    input: SelectInput<String>,
+    strategy_name: String,
+    duration: u64,
+    plan: Info,
+}
+
+impl ScaleToWalletFormController {
+    pub(crate) fn new(strategy_name: String, duration: u64, plan: &ScalePlan) -> Self {
+        ScaleToWalletFormController {
+            input: SelectInput::new(vec!["No".to_string(), "Yes".to_string()]),
+            strategy_name,
+            duration,
+            plan: Info::new_fixed(&plan.to_string()),
+        }
+    }
+}
+
+impl FormController for ScaleToWalletFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done(choice) if choice == "Yes" => FormStatus::Done {
+                task: Task::Strategy(StrategyTask::ScaleToWallet {
+                    strategy_name: self.strategy_name.clone(),
+                    duration: self.duration,
+                }),
+                block: false,
+            },
+            InputStatus::Done(_) => FormStatus::Exit,
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Scale to wallet"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(10), Constraint::Length(4)].as_ref())
+            .split(area);
+        self.plan.view(frame, layout[0]);
+        self.input.view(frame, layout[1]);
+    }
+
+    fn step_name(&self) -> &'static str {
+        "Scale the strategy down?"
+    }
+
+    fn step_index(&self) -> u8 {
+        0
+    }
+
+    fn steps_number(&self) -> u8 {
+        1
+    }
+}
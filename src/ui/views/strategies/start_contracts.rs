@@ -4,12 +4,14 @@ use std::collections::BTreeMap;
 
 use walkdir::WalkDir;
 
+use super::{apply_random_variant_aliases, contract_display};
 use crate::{
-    backend::{AppState, AppStateUpdate, BackendEvent, StrategyTask, Task},
+    backend::{state::ContractAliases, AppState, AppStateUpdate, BackendEvent, StrategyTask, Task},
     ui::form::{
         parsers::DefaultTextInputParser, ComposedInput, Field, FormController, FormStatus, Input,
         InputStatus, SelectInput, TextInput,
     },
+    ui::id_names::IdNames,
     ui::screen::{
         utils::impl_builder, widgets::info::Info, ScreenCommandKey, ScreenController,
         ScreenFeedback, ScreenToggleKey,
@@ -30,12 +32,13 @@ use dpp::{
 };
 use strategy_tests::Strategy;
 
-const COMMAND_KEYS: [ScreenCommandKey; 5] = [
+const COMMAND_KEYS: [ScreenCommandKey; 6] = [
     ScreenCommandKey::new("q", "Back to Strategy"),
     ScreenCommandKey::new("s", "Add specific"),
     ScreenCommandKey::new("x", "Add x random"),
     ScreenCommandKey::new("r", "Remove last"),
     ScreenCommandKey::new("c", "Clear all"),
+    ScreenCommandKey::new("a", "Set alias"),
 ];
 
 pub(crate) struct StartContractsScreenController {
@@ -49,22 +52,19 @@ pub(crate) struct StartContractsScreenController {
     known_contracts: BTreeMap<String, DataContract>,
     supporting_contracts: BTreeMap<String, DataContract>,
     strategy_contract_names: BTreeMap<String, Vec<(String, Option<BTreeMap<u64, String>>)>>,
+    contract_aliases: ContractAliases,
+    id_names: IdNames,
 }
 
 impl_builder!(StartContractsScreenController);
 
 impl StartContractsScreenController {
     pub(crate) async fn new(app_state: &AppState) -> Self {
-        let available_strategies_lock = app_state.available_strategies.lock().await;
-        let selected_strategy_lock = app_state.selected_strategy.lock().await;
-        let known_contracts_lock = app_state.known_contracts.lock().await;
-        let supporting_contracts_lock = app_state.supporting_contracts.lock().await;
-        let strategy_contract_names_lock =
-            app_state.available_strategies_contract_names.lock().await;
+        let snapshot = app_state.snapshot();
 
         let (info_text, current_strategy, current_start_contracts) =
-            if let Some(selected_strategy_name) = &*selected_strategy_lock {
-                if let Some(strategy) = available_strategies_lock.get(selected_strategy_name) {
+            if let Some(selected_strategy_name) = &snapshot.selected_strategy {
+                if let Some(strategy) = snapshot.available_strategies.get(selected_strategy_name) {
                     // Construct the info_text and get the start_contracts for the selected
                     // strategy
                     let info_text = format!("Selected Strategy: {}", selected_strategy_name);
@@ -79,14 +79,26 @@ impl StartContractsScreenController {
 
         let info = Info::new_fixed(&info_text);
 
+        let contract_aliases = if let Some(selected_strategy_name) = &snapshot.selected_strategy {
+            snapshot
+                .strategy_contract_aliases
+                .get(selected_strategy_name)
+                .cloned()
+                .unwrap_or_default()
+        } else {
+            ContractAliases::new()
+        };
+
         Self {
             info,
-            strategy_name: selected_strategy_lock.clone(),
+            strategy_name: snapshot.selected_strategy.clone(),
             selected_strategy: current_strategy,
             start_contracts: current_start_contracts,
-            known_contracts: known_contracts_lock.clone(),
-            supporting_contracts: supporting_contracts_lock.clone(),
-            strategy_contract_names: strategy_contract_names_lock.clone(),
+            known_contracts: snapshot.known_contracts.clone(),
+            supporting_contracts: snapshot.supporting_contracts.clone(),
+            strategy_contract_names: snapshot.strategy_contract_names.clone(),
+            contract_aliases,
+            id_names: IdNames::from_snapshot(&snapshot),
         }
     }
 
@@ -138,6 +150,8 @@ impl ScreenController for StartContractsScreenController {
     }
 
     fn on_event(&mut self, event: &Event) -> ScreenFeedback {
+        apply_random_variant_aliases(event, &mut self.contract_aliases);
+
         match event {
             Event::Key(KeyEvent {
                 code: Key::Char('q'),
@@ -205,6 +219,33 @@ impl ScreenController for StartContractsScreenController {
                     ScreenFeedback::None
                 }
             }
+            Event::Key(KeyEvent {
+                code: Key::Char('a'),
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                if let Some(strategy_name) = &self.strategy_name {
+                    let contract_names = self
+                        .strategy_contract_names
+                        .get(strategy_name)
+                        .map(|names| names.iter().map(|(name, _)| name.clone()).collect())
+                        .unwrap_or_default();
+                    ScreenFeedback::Form(Box::new(ContractAliasFormController::new(
+                        strategy_name.clone(),
+                        contract_names,
+                    )))
+                } else {
+                    ScreenFeedback::None
+                }
+            }
+            Event::Backend(BackendEvent::TaskCompletedStateChange {
+                app_state_update: AppStateUpdate::StrategyContractAliases(strategy_name, aliases),
+                ..
+            }) => {
+                if self.strategy_name.as_ref() == Some(strategy_name) {
+                    self.contract_aliases = (*aliases).clone();
+                }
+                ScreenFeedback::Redraw
+            }
             Event::Backend(
                 BackendEvent::AppStateUpdated(AppStateUpdate::SelectedStrategy(
                     strategy_name,
@@ -246,7 +287,7 @@ impl ScreenController for StartContractsScreenController {
                         start_contracts_lines.push_str(&format!(
                             "{:indent$}Contract: {}\n",
                             "",
-                            contract_name,
+                            contract_display(contract_name, &self.contract_aliases, &self.id_names),
                             indent = 0
                         ));
                         if let Some(updates_map) = updates {
@@ -469,3 +510,62 @@ impl FormController for RandomContractsFormController {
         2
     }
 }
+
+pub(super) struct ContractAliasFormController {
+    selected_strategy: String,
+    input: ComposedInput<(
+        Field<SelectInput<String>>,
+        Field<TextInput<DefaultTextInputParser<String>>>,
+    )>,
+}
+
+impl ContractAliasFormController {
+    pub(super) fn new(selected_strategy: String, contract_names: Vec<String>) -> Self {
+        Self {
+            selected_strategy,
+            input: ComposedInput::new((
+                Field::new("Select contract", SelectInput::new(contract_names)),
+                Field::new(
+                    "Alias",
+                    TextInput::new("Enter an alias, leave empty to remove it"),
+                ),
+            )),
+        }
+    }
+}
+
+impl FormController for ContractAliasFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done((contract_name, alias)) => FormStatus::Done {
+                task: Task::Strategy(StrategyTask::SetContractAlias {
+                    strategy_name: self.selected_strategy.clone(),
+                    contract_name,
+                    alias,
+                }),
+                block: false,
+            },
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Contract alias"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        self.input.step_name()
+    }
+
+    fn step_index(&self) -> u8 {
+        self.input.step_index()
+    }
+
+    fn steps_number(&self) -> u8 {
+        2
+    }
+}
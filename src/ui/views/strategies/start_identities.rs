@@ -7,6 +7,7 @@ use dpp::{
     platform_value::string_encoding::Encoding,
     prelude::Identity,
 };
+use itertools::Itertools;
 use tuirealm::{
     event::{Key, KeyEvent, KeyModifiers},
     tui::prelude::Rect,
@@ -14,26 +15,33 @@ use tuirealm::{
 };
 
 use crate::{
-    backend::{AppState, AppStateUpdate, BackendEvent, StrategyTask, Task},
+    backend::{
+        strategies::START_IDENTITY_KEY_TYPES, AppState, AppStateUpdate, BackendEvent, StrategyTask,
+        Task,
+    },
     ui::form::{
-        parsers::DefaultTextInputParser, ComposedInput, Field, FormController, FormStatus, Input,
-        InputStatus, SelectInput, TextInput,
+        parsers::{DefaultTextInputParser, DuffsTextInputParser},
+        ComposedInput, Field, FormController, FormStatus, Input, InputStatus, SelectInput,
+        TextInput,
     },
     ui::screen::{
         utils::impl_builder, widgets::info::Info, ScreenCommandKey, ScreenController,
         ScreenFeedback, ScreenToggleKey,
     },
-    Event,
+    units, Event,
 };
 
 use strategy_tests::Strategy;
 
-const COMMAND_KEYS: [ScreenCommandKey; 5] = [
+const COMMAND_KEYS: [ScreenCommandKey; 8] = [
     ScreenCommandKey::new("q", "Back to Strategy"),
     ScreenCommandKey::new("a", "Add/edit"),
     ScreenCommandKey::new("b", "Set balance"),
     ScreenCommandKey::new("c", "Remove"),
     ScreenCommandKey::new("k", "Add known identity"),
+    ScreenCommandKey::new("f", "Import identities from file"),
+    ScreenCommandKey::new("e", "Add extra keys"),
+    ScreenCommandKey::new("x", "Clear extra keys"),
 ];
 
 pub(crate) struct StartIdentitiesScreenController {
@@ -42,20 +50,18 @@ pub(crate) struct StartIdentitiesScreenController {
     selected_strategy: Option<Strategy>,
     known_identities: Vec<Identity>,
     loaded_identity: Option<Identity>,
+    import_result: Option<String>,
 }
 
 impl_builder!(StartIdentitiesScreenController);
 
 impl StartIdentitiesScreenController {
     pub(crate) async fn new(app_state: &AppState) -> Self {
-        let available_strategies_lock = app_state.available_strategies.lock().await;
-        let selected_strategy_lock = app_state.selected_strategy.lock().await;
-        let known_identities_lock = app_state.known_identities.lock().await;
-        let loaded_identity_lock = app_state.loaded_identity.lock().await;
+        let snapshot = app_state.snapshot();
 
         let (info_text, current_strategy) =
-            if let Some(selected_strategy_name) = &*selected_strategy_lock {
-                if let Some(strategy) = available_strategies_lock.get(selected_strategy_name) {
+            if let Some(selected_strategy_name) = &snapshot.selected_strategy {
+                if let Some(strategy) = snapshot.available_strategies.get(selected_strategy_name) {
                     let info_text = format!("Selected Strategy: {}", selected_strategy_name);
                     (info_text, Some(strategy.clone()))
                 } else {
@@ -69,10 +75,11 @@ impl StartIdentitiesScreenController {
 
         Self {
             info,
-            strategy_name: selected_strategy_lock.clone(),
+            strategy_name: snapshot.selected_strategy.clone(),
             selected_strategy: current_strategy,
-            known_identities: known_identities_lock.values().cloned().collect(),
-            loaded_identity: loaded_identity_lock.clone(),
+            known_identities: snapshot.known_identities.values().cloned().collect(),
+            loaded_identity: snapshot.loaded_identity.clone(),
+            import_result: None,
         }
     }
 }
@@ -150,7 +157,66 @@ impl ScreenController for StartIdentitiesScreenController {
                 }
             }
 
+            Event::Key(KeyEvent {
+                code: Key::Char('f'),
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                if let Some(strategy_name) = &self.strategy_name {
+                    ScreenFeedback::Form(Box::new(
+                        StrategyImportStartIdentitiesFormController::new(strategy_name.clone()),
+                    ))
+                } else {
+                    ScreenFeedback::None
+                }
+            }
+            Event::Key(KeyEvent {
+                code: Key::Char('e'),
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                if let Some(strategy_name) = &self.strategy_name {
+                    ScreenFeedback::Form(Box::new(StrategyStartIdentityKeysFormController::new(
+                        strategy_name.clone(),
+                    )))
+                } else {
+                    ScreenFeedback::None
+                }
+            }
+            Event::Key(KeyEvent {
+                code: Key::Char('x'),
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                if let Some(strategy_name) = &self.strategy_name {
+                    ScreenFeedback::Task {
+                        task: Task::Strategy(StrategyTask::ClearStartIdentityKeys(
+                            strategy_name.clone(),
+                        )),
+                        block: false,
+                    }
+                } else {
+                    ScreenFeedback::None
+                }
+            }
+
             // Backend events
+            Event::Backend(BackendEvent::TaskCompletedStateChange {
+                task: Task::Strategy(StrategyTask::ImportHardCodedStartIdentities { .. }),
+                execution_result,
+                app_state_update: AppStateUpdate::SelectedStrategy(_, updated_strategy, _),
+            }) => {
+                self.selected_strategy = Some((*updated_strategy).clone());
+                self.import_result = Some(match execution_result {
+                    Ok(payload) => payload.to_string(),
+                    Err(e) => format!("Error: {}", e),
+                });
+                ScreenFeedback::Redraw
+            }
+            Event::Backend(BackendEvent::TaskCompleted {
+                task: Task::Strategy(StrategyTask::ImportHardCodedStartIdentities { .. }),
+                execution_result: Err(e),
+            }) => {
+                self.import_result = Some(format!("Error: {}", e));
+                ScreenFeedback::Redraw
+            }
             Event::Backend(BackendEvent::AppStateUpdated(AppStateUpdate::SelectedStrategy(
                 strategy_name,
                 strategy,
@@ -194,13 +260,45 @@ impl ScreenController for StartIdentitiesScreenController {
         let display_text = if let Some(strategy) = &self.selected_strategy {
             // Construct the text to display new start identities
             let new_start_identities_text = format!(
-                "New identities: {} (Keys: {}, Balance: {:.2} dash)",
+                "New identities: {} (Keys: {}, Balance: {})",
                 strategy.start_identities.number_of_identities,
-                strategy.start_identities.keys_per_identity
-                    + strategy.start_identities.extra_keys.len() as u8,
-                strategy.start_identities.starting_balances as f64 / 100_000_000.0,
+                strategy.start_identities.keys_per_identity as usize
+                    + strategy
+                        .start_identities
+                        .extra_keys
+                        .values()
+                        .flat_map(|security_levels| security_levels.values())
+                        .map(|key_types| key_types.len())
+                        .sum::<usize>(),
+                units::format_duffs(strategy.start_identities.starting_balances),
             );
 
+            // Keys added on top of the authentication keys
+            let extra_keys_text = {
+                let extra_keys = strategy
+                    .start_identities
+                    .extra_keys
+                    .iter()
+                    .flat_map(|(purpose, security_levels)| {
+                        security_levels
+                            .iter()
+                            .flat_map(move |(security_level, key_types)| {
+                                key_types
+                                    .iter()
+                                    .dedup_with_count()
+                                    .map(move |(count, key_type)| {
+                                        format!("  {count} x {purpose} {security_level} {key_type}")
+                                    })
+                            })
+                    })
+                    .collect::<Vec<_>>();
+                if extra_keys.is_empty() {
+                    "Extra keys: none".to_string()
+                } else {
+                    format!("Extra keys:\n{}", extra_keys.join("\n"))
+                }
+            };
+
             // Known start identities
             let known_start_identities_text = {
                 let mut identities_list = Vec::new();
@@ -230,12 +328,14 @@ impl ScreenController for StartIdentitiesScreenController {
             };
 
             format!(
-                "Strategy: {}\n{}\n{}",
+                "Strategy: {}\n{}\n{}\n{}\n{}",
                 self.strategy_name
                     .as_ref()
                     .unwrap_or(&"Unknown".to_string()),
                 new_start_identities_text,
-                known_start_identities_text
+                extra_keys_text,
+                known_start_identities_text,
+                self.import_result.as_deref().unwrap_or_default(),
             )
         } else {
             "Select a strategy to view start identities.".to_string()
@@ -331,14 +431,14 @@ impl FormController for StrategyStartIdentitiesFormController {
 }
 
 pub(super) struct StrategyStartIdentitiesBalanceFormController {
-    input: TextInput<DefaultTextInputParser<f64>>,
+    input: TextInput<DuffsTextInputParser>,
     selected_strategy: String,
 }
 
 impl StrategyStartIdentitiesBalanceFormController {
     pub(super) fn new(selected_strategy: String) -> Self {
         Self {
-            input: TextInput::new("Quantity (in Dash)"),
+            input: TextInput::new_with_parser(DuffsTextInputParser, "Amount"),
             selected_strategy,
         }
     }
@@ -350,7 +450,7 @@ impl FormController for StrategyStartIdentitiesBalanceFormController {
             InputStatus::Done(balance) => FormStatus::Done {
                 task: Task::Strategy(StrategyTask::SetStartIdentitiesBalance(
                     self.selected_strategy.clone(),
-                    (balance * 100000000.0) as u64,
+                    balance,
                 )),
                 block: false,
             },
@@ -478,3 +578,202 @@ impl FormController for StrategyAddKnownStartIdentityFormController {
         1
     }
 }
+
+enum StartIdentityKeysFormStep {
+    Purpose(SelectInput<Purpose>),
+    Security(SelectInput<SecurityLevel>),
+    KeyType(SelectInput<KeyType>),
+    Count(TextInput<DefaultTextInputParser<u8>>),
+}
+
+/// Adds a number of keys of the same purpose, security level and key type to
+/// every new start identity, on top of the authentication keys.
+pub(super) struct StrategyStartIdentityKeysFormController {
+    step: StartIdentityKeysFormStep,
+    selected_strategy: String,
+    purpose_result: Option<Purpose>,
+    security_result: Option<SecurityLevel>,
+    key_type_result: Option<KeyType>,
+}
+
+impl StrategyStartIdentityKeysFormController {
+    pub(super) fn new(selected_strategy: String) -> Self {
+        StrategyStartIdentityKeysFormController {
+            step: StartIdentityKeysFormStep::Purpose(SelectInput::new(vec![
+                Purpose::AUTHENTICATION,
+                Purpose::ENCRYPTION,
+                Purpose::DECRYPTION,
+                Purpose::TRANSFER,
+            ])),
+            selected_strategy,
+            purpose_result: None,
+            security_result: None,
+            key_type_result: None,
+        }
+    }
+}
+
+impl FormController for StrategyStartIdentityKeysFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match &mut self.step {
+            StartIdentityKeysFormStep::Purpose(input) => match input.on_event(event) {
+                // En/Decryption keys have medium security so the field will be skipped
+                InputStatus::Done(purpose @ (Purpose::ENCRYPTION | Purpose::DECRYPTION)) => {
+                    self.purpose_result = Some(purpose);
+                    self.security_result = Some(SecurityLevel::MEDIUM);
+                    self.step = StartIdentityKeysFormStep::KeyType(SelectInput::new(
+                        START_IDENTITY_KEY_TYPES.into(),
+                    ));
+                    FormStatus::Redraw
+                }
+                // Transfer keys have critical security so the field will be skipped
+                InputStatus::Done(purpose @ Purpose::TRANSFER) => {
+                    self.purpose_result = Some(purpose);
+                    self.security_result = Some(SecurityLevel::CRITICAL);
+                    self.step = StartIdentityKeysFormStep::KeyType(SelectInput::new(
+                        START_IDENTITY_KEY_TYPES.into(),
+                    ));
+                    FormStatus::Redraw
+                }
+                // Authentication keys, the master key is always created with an identity
+                InputStatus::Done(purpose) => {
+                    self.purpose_result = Some(purpose);
+                    self.step = StartIdentityKeysFormStep::Security(SelectInput::new(vec![
+                        SecurityLevel::CRITICAL,
+                        SecurityLevel::HIGH,
+                        SecurityLevel::MEDIUM,
+                    ]));
+                    FormStatus::Redraw
+                }
+                status => status.into(),
+            },
+            StartIdentityKeysFormStep::Security(input) => match input.on_event(event) {
+                InputStatus::Done(security_level) => {
+                    self.security_result = Some(security_level);
+                    self.step = StartIdentityKeysFormStep::KeyType(SelectInput::new(
+                        START_IDENTITY_KEY_TYPES.into(),
+                    ));
+                    FormStatus::Redraw
+                }
+                status => status.into(),
+            },
+            StartIdentityKeysFormStep::KeyType(input) => match input.on_event(event) {
+                InputStatus::Done(key_type) => {
+                    self.key_type_result = Some(key_type);
+                    self.step = StartIdentityKeysFormStep::Count(TextInput::new_init_value(
+                        "Enter a whole number",
+                        1,
+                    ));
+                    FormStatus::Redraw
+                }
+                status => status.into(),
+            },
+            StartIdentityKeysFormStep::Count(input) => match input.on_event(event) {
+                InputStatus::Done(count) => FormStatus::Done {
+                    task: Task::Strategy(StrategyTask::AddStartIdentityKeys {
+                        strategy_name: self.selected_strategy.clone(),
+                        purpose: self
+                            .purpose_result
+                            .expect("must be selected on previous steps"),
+                        security_level: self
+                            .security_result
+                            .expect("must be selected on previous steps"),
+                        key_type: self
+                            .key_type_result
+                            .expect("must be selected on previous steps"),
+                        count,
+                    }),
+                    block: false,
+                },
+                status => status.into(),
+            },
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Add start identity keys"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        match &mut self.step {
+            StartIdentityKeysFormStep::Purpose(input) => input.view(frame, area),
+            StartIdentityKeysFormStep::Security(input) => input.view(frame, area),
+            StartIdentityKeysFormStep::KeyType(input) => input.view(frame, area),
+            StartIdentityKeysFormStep::Count(input) => input.view(frame, area),
+        }
+    }
+
+    fn step_name(&self) -> &'static str {
+        match self.step {
+            StartIdentityKeysFormStep::Purpose(_) => "Key purpose",
+            StartIdentityKeysFormStep::Security(_) => "Key security level",
+            StartIdentityKeysFormStep::KeyType(_) => "Key type",
+            StartIdentityKeysFormStep::Count(_) => "Number of keys per identity",
+        }
+    }
+
+    fn step_index(&self) -> u8 {
+        match self.step {
+            StartIdentityKeysFormStep::Purpose(_) => 0,
+            StartIdentityKeysFormStep::Security(_) => 1,
+            StartIdentityKeysFormStep::KeyType(_) => 2,
+            StartIdentityKeysFormStep::Count(_) => 3,
+        }
+    }
+
+    fn steps_number(&self) -> u8 {
+        4
+    }
+}
+
+pub(super) struct StrategyImportStartIdentitiesFormController {
+    input: TextInput<DefaultTextInputParser<String>>,
+    selected_strategy: String,
+}
+
+impl StrategyImportStartIdentitiesFormController {
+    pub(super) fn new(selected_strategy: String) -> Self {
+        Self {
+            input: TextInput::new_init_value(
+                "Path to a JSON file",
+                "supporting_files/start_identities.json".to_owned(),
+            ),
+            selected_strategy,
+        }
+    }
+}
+
+impl FormController for StrategyImportStartIdentitiesFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done(path) => FormStatus::Done {
+                task: Task::Strategy(StrategyTask::ImportHardCodedStartIdentities {
+                    strategy_name: self.selected_strategy.clone(),
+                    path,
+                }),
+                block: true,
+            },
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Import hard-coded start identities"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        "JSON list of identity IDs, optionally with private keys"
+    }
+
+    fn step_index(&self) -> u8 {
+        0
+    }
+
+    fn steps_number(&self) -> u8 {
+        1
+    }
+}
@@ -0,0 +1,170 @@
+//! Strategy template picker screen and form.
+
+use tuirealm::{
+    event::{Key, KeyEvent, KeyModifiers},
+    props::TextSpan,
+    tui::prelude::{Constraint, Direction, Layout, Rect},
+    Frame,
+};
+
+use super::selected_strategy::SelectedStrategyScreenController;
+use crate::{
+    backend::{strategy_templates::StrategyTemplate, AppState, StrategyTask, Task},
+    ui::{
+        form::{
+            parsers::DefaultTextInputParser, FormController, FormStatus, Input, InputStatus,
+            TextInput,
+        },
+        screen::{
+            utils::impl_builder,
+            widgets::{info::Info, virtual_list::VirtualList},
+            ScreenCommandKey, ScreenController, ScreenFeedback, ScreenToggleKey,
+        },
+    },
+    Event,
+};
+
+const COMMAND_KEYS: [ScreenCommandKey; 4] = [
+    ScreenCommandKey::new("q", "Back to Strategies"),
+    ScreenCommandKey::new("C-n", "Next template"),
+    ScreenCommandKey::new("C-p", "Prev template"),
+    ScreenCommandKey::new("c", "Create strategy from template"),
+];
+
+pub(crate) struct StrategyTemplatesScreenController {
+    template_select: VirtualList,
+    template_view: Info,
+}
+
+impl_builder!(StrategyTemplatesScreenController);
+
+impl StrategyTemplatesScreenController {
+    pub(crate) async fn new(_app_state: &AppState) -> Self {
+        let rows = StrategyTemplate::ALL
+            .iter()
+            .map(|template| vec![TextSpan::new(template.to_string())])
+            .collect();
+        let mut template_select = VirtualList::new(rows, 0);
+        template_select.set_title(Some("Templates".to_owned()));
+        let mut controller = StrategyTemplatesScreenController {
+            template_select,
+            template_view: Info::new_fixed(""),
+        };
+        controller.update_view();
+        controller
+    }
+
+    fn selected_template(&self) -> StrategyTemplate {
+        StrategyTemplate::ALL[self.template_select.selected()]
+    }
+
+    fn update_view(&mut self) {
+        let template = self.selected_template();
+        self.template_view = Info::new_fixed(&format!(
+            "{}\n\n{}\n\nStart contracts, identity inserts and operations can be changed \
+             once the strategy is created.",
+            template,
+            template.description()
+        ));
+    }
+}
+
+impl ScreenController for StrategyTemplatesScreenController {
+    fn name(&self) -> &'static str {
+        "Strategy templates"
+    }
+
+    fn command_keys(&self) -> &[ScreenCommandKey] {
+        COMMAND_KEYS.as_ref()
+    }
+
+    fn toggle_keys(&self) -> &[ScreenToggleKey] {
+        &[]
+    }
+
+    fn on_event(&mut self, event: &Event) -> ScreenFeedback {
+        match event {
+            Event::Key(KeyEvent {
+                code: Key::Char('q'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::PreviousScreen,
+            Event::Key(KeyEvent {
+                code: Key::Char('c'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::FormThenNextScreen {
+                form: Box::new(NewStrategyFromTemplateFormController::new(
+                    self.selected_template(),
+                )),
+                screen: SelectedStrategyScreenController::builder(),
+            },
+            Event::Key(k) => {
+                if self.template_select.on_event(k) {
+                    self.update_view();
+                    ScreenFeedback::Redraw
+                } else {
+                    ScreenFeedback::None
+                }
+            }
+            _ => ScreenFeedback::None,
+        }
+    }
+
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(30), Constraint::Percentage(70)].as_ref())
+            .split(area);
+
+        self.template_select.view(frame, layout[0]);
+        self.template_view.view(frame, layout[1]);
+    }
+}
+
+struct NewStrategyFromTemplateFormController {
+    input: TextInput<DefaultTextInputParser<String>>,
+    template: StrategyTemplate,
+}
+
+impl NewStrategyFromTemplateFormController {
+    fn new(template: StrategyTemplate) -> Self {
+        NewStrategyFromTemplateFormController {
+            input: TextInput::new("strategy name"),
+            template,
+        }
+    }
+}
+
+impl FormController for NewStrategyFromTemplateFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done(strategy_name) => FormStatus::Done {
+                task: Task::Strategy(StrategyTask::CreateFromTemplate {
+                    strategy_name,
+                    template: self.template,
+                }),
+                block: false,
+            },
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Create strategy from template"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        "Strategy name"
+    }
+
+    fn step_index(&self) -> u8 {
+        0
+    }
+
+    fn steps_number(&self) -> u8 {
+        1
+    }
+}
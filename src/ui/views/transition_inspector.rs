@@ -0,0 +1,216 @@
+//! Generated and failed transitions of strategy runs, taken apart.
+
+use std::collections::BTreeMap;
+
+use dpp::{
+    identity::accessors::IdentityGettersV0,
+    platform_value::{string_encoding::Encoding, Identifier},
+    prelude::Identity,
+    state_transition::StateTransitionLike,
+};
+use tuirealm::{
+    event::{Key, KeyEvent, KeyModifiers},
+    props::{Color, TextSpan},
+    tui::prelude::{Constraint, Direction, Layout, Rect},
+    Frame,
+};
+
+use crate::{
+    backend::{
+        transition_inspector::{BufferedTransition, TransitionBuffer},
+        AppState,
+    },
+    ui::screen::{
+        utils::impl_builder,
+        widgets::{info::Info, virtual_list::VirtualList},
+        ScreenCommandKey, ScreenController, ScreenFeedback, ScreenToggleKey,
+    },
+    Event,
+};
+
+const COMMAND_KEYS: [ScreenCommandKey; 3] = [
+    ScreenCommandKey::new("q", "Back to Main"),
+    ScreenCommandKey::new("C-n", "Next transition"),
+    ScreenCommandKey::new("C-p", "Prev transition"),
+];
+
+pub(crate) struct TransitionInspectorScreenController {
+    transition_buffer: TransitionBuffer,
+    /// Identities whose keys signatures are checked against, for transitions
+    /// recorded without their signing key
+    known_identities: BTreeMap<Identifier, Identity>,
+    entries: Vec<BufferedTransition>,
+    transition_select: VirtualList,
+    transition_view: Info,
+    toggle_keys: [ScreenToggleKey; 1],
+}
+
+impl_builder!(TransitionInspectorScreenController);
+
+impl TransitionInspectorScreenController {
+    pub(crate) async fn new(app_state: &AppState) -> Self {
+        let snapshot = app_state.snapshot();
+        let mut known_identities = snapshot.known_identities.clone();
+        if let Some(identity) = &snapshot.loaded_identity {
+            known_identities.insert(identity.id(), identity.clone());
+        }
+        let mut controller = TransitionInspectorScreenController {
+            transition_buffer: app_state.transition_buffer().clone(),
+            known_identities,
+            entries: Vec::new(),
+            transition_select: VirtualList::new(Vec::new(), 0),
+            transition_view: Info::new_fixed(""),
+            toggle_keys: [ScreenToggleKey::new("f", "failed")],
+        };
+        controller.reload();
+        controller.update_view();
+        controller
+    }
+
+    fn showing_failed(&self) -> bool {
+        self.toggle_keys[0].toggle
+    }
+
+    /// Picks up the transitions recorded meanwhile and selects the latest
+    /// one. Returns whether the buffer changed.
+    fn reload(&mut self) -> bool {
+        let entries = if self.showing_failed() {
+            self.transition_buffer.failed()
+        } else {
+            self.transition_buffer.generated()
+        };
+        let changed = entries.len() != self.entries.len()
+            || match (entries.last(), self.entries.last()) {
+                (Some(new), Some(old)) => {
+                    new.recorded_at != old.recorded_at || new.transition != old.transition
+                }
+                _ => false,
+            };
+        self.entries = entries;
+
+        self.transition_select = VirtualList::new(
+            transition_rows(&self.entries),
+            self.entries.len().saturating_sub(1),
+        );
+        self.transition_select.set_title(Some(format!(
+            "{} {} transitions",
+            self.entries.len(),
+            if self.showing_failed() {
+                "failed"
+            } else {
+                "generated"
+            }
+        )));
+        changed
+    }
+
+    /// Whether the latest transition is selected, the list only follows new
+    /// ones then so the inspected transition doesn't move away.
+    fn following(&self) -> bool {
+        self.transition_select.selected() + 1 >= self.entries.len()
+    }
+
+    fn update_view(&mut self) {
+        let Some(entry) = self.entries.get(self.transition_select.selected()) else {
+            self.transition_view = Info::new_fixed(if self.showing_failed() {
+                "No failed transitions yet, transitions refused on broadcast or rejected while \
+                 waiting for their result are listed here"
+            } else {
+                "No generated transitions yet, the latest ones of strategy runs and dry runs \
+                 are listed here"
+            });
+            return;
+        };
+        let text = entry.inspect(&self.known_identities);
+        self.transition_view = match entry.error {
+            Some(_) => Info::new_error(&text),
+            None => Info::new_scrollable(&text),
+        };
+    }
+}
+
+impl ScreenController for TransitionInspectorScreenController {
+    fn name(&self) -> &'static str {
+        "Transition inspector"
+    }
+
+    fn command_keys(&self) -> &[ScreenCommandKey] {
+        COMMAND_KEYS.as_ref()
+    }
+
+    fn toggle_keys(&self) -> &[ScreenToggleKey] {
+        self.toggle_keys.as_ref()
+    }
+
+    fn on_event(&mut self, event: &Event) -> ScreenFeedback {
+        match event {
+            Event::Key(KeyEvent {
+                code: Key::Char('q'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::PreviousScreen,
+
+            Event::Key(KeyEvent {
+                code: Key::Char('f'),
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                self.toggle_keys[0].toggle = !self.toggle_keys[0].toggle;
+                self.reload();
+                self.update_view();
+                ScreenFeedback::Redraw
+            }
+
+            Event::Key(k) => {
+                if self.transition_select.on_event(k) {
+                    self.update_view();
+                    ScreenFeedback::Redraw
+                } else if self.transition_view.on_event(k) {
+                    ScreenFeedback::Redraw
+                } else {
+                    ScreenFeedback::None
+                }
+            }
+
+            // Shows transitions of a run as they're generated or fail
+            Event::Tick { .. } if self.following() => {
+                if self.reload() {
+                    self.update_view();
+                    ScreenFeedback::Redraw
+                } else {
+                    ScreenFeedback::None
+                }
+            }
+
+            _ => ScreenFeedback::None,
+        }
+    }
+
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)].as_ref())
+            .split(area);
+
+        self.transition_select.view(frame, layout[0]);
+        self.transition_view.view(frame, layout[1]);
+    }
+}
+
+fn transition_rows(entries: &[BufferedTransition]) -> Vec<Vec<TextSpan>> {
+    entries
+        .iter()
+        .map(|entry| {
+            let owner_id = entry.transition.owner_id().to_string(Encoding::Base58);
+            let text = format!(
+                "{} {} {} {}",
+                entry.recorded_at.format("%H:%M:%S"),
+                entry.strategy_name,
+                entry.transition.name(),
+                &owner_id[..owner_id.len().min(8)]
+            );
+            vec![match entry.error {
+                Some(_) => TextSpan::new(text).fg(Color::Red),
+                None => TextSpan::new(text),
+            }]
+        })
+        .collect()
+}
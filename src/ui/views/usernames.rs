@@ -83,8 +83,12 @@ impl_builder!(DpnsUsernamesScreenController);
 
 impl DpnsUsernamesScreenController {
     pub(crate) async fn new(app_state: &AppState) -> Self {
-        let known_identities_lock = app_state.known_identities.lock().await;
-        let identity_ids_vec = known_identities_lock.iter().map(|(k, _)| *k).collect_vec();
+        let snapshot = app_state.snapshot();
+        let identity_ids_vec = snapshot
+            .known_identities
+            .iter()
+            .map(|(k, _)| *k)
+            .collect_vec();
         let mut identity_select = tui_realm_stdlib::List::default()
             .rows(
                 identity_ids_vec
@@ -101,20 +105,20 @@ impl DpnsUsernamesScreenController {
         identity_select.attr(Attribute::Scroll, AttrValue::Flag(true));
         identity_select.attr(Attribute::Focus, AttrValue::Flag(true));
 
-        let known_contracts_lock = app_state.known_contracts.lock().await;
-        let maybe_dpns_contract = match known_contracts_lock.get(
+        let maybe_dpns_contract = match snapshot.known_contracts.get(
             &Identifier::from_bytes(&dpns_contract::ID_BYTES)
                 .unwrap()
                 .to_string(Encoding::Base58),
         ) {
             Some(contract) => Some(contract),
-            None => known_contracts_lock.get(&String::from("DPNS")),
+            None => snapshot.known_contracts.get(&String::from("DPNS")),
         };
 
         let identity_view = if maybe_dpns_contract.is_some() {
             if let Some(first_identity_id) = identity_ids_vec.get(0) {
                 Info::new_scrollable(
-                    &known_identities_lock
+                    &snapshot
+                        .known_identities
                         .get(first_identity_id)
                         .and_then(|identity_info| Some(as_json_string(identity_info)))
                         .unwrap_or_else(String::new),
@@ -126,11 +130,9 @@ impl DpnsUsernamesScreenController {
             Info::new_fixed("DPNS contract not known yet. Please press 'f' to fetch it.")
         };
 
-        let identities_names_map_lock = app_state.known_identities_names.lock().await;
-
         Self {
-            identities_map: known_identities_lock.clone(),
-            identities_names_map: identities_names_map_lock.clone(),
+            identities_map: snapshot.known_identities.clone(),
+            identities_names_map: snapshot.known_identities_names.clone(),
             identity_select,
             identity_view,
             identity_ids_vec,
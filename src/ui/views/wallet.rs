@@ -3,22 +3,30 @@
 use dpp::dashcore::psbt::serialize::Serialize;
 
 pub(crate) mod add_identity_key;
+pub(crate) mod coin_control;
+pub(crate) mod wallet_manager;
 
 use std::ops::Deref;
 
+use qrcode::{render::unicode::Dense1x2, QrCode};
 use tuirealm::{
     event::{Key, KeyEvent, KeyModifiers},
     tui::prelude::{Constraint, Direction, Layout, Rect},
     Frame,
 };
 
+use self::{
+    coin_control::CoinControlScreenController, wallet_manager::WalletManagerScreenController,
+};
 use crate::{
     backend::{
-        identities::IdentityTask, AppState, AppStateUpdate, BackendEvent, Task, Wallet, WalletTask,
+        identities::IdentityTask, wallet::FeeLevel, AppState, AppStateUpdate, BackendEvent, Task,
+        Wallet, WalletTask,
     },
     ui::{
         form::{
-            parsers::DefaultTextInputParser, FormController, FormStatus, Input, InputStatus,
+            parsers::{CreditsTextInputParser, DefaultTextInputParser, DuffsTextInputParser},
+            ComposedInput, Field, FormController, FormStatus, Input, InputStatus, SelectInput,
             TextInput,
         },
         screen::{
@@ -29,11 +37,16 @@ use crate::{
     Event,
 };
 
-const WALLET_LOADED_COMMANDS: [ScreenCommandKey; 4] = [
-    ScreenCommandKey::new("b", "Refresh wallet utxos and balance"),
+const WALLET_LOADED_COMMANDS: [ScreenCommandKey; 9] = [
+    ScreenCommandKey::new("b / C-r", "Refresh wallet utxos and balance"),
     ScreenCommandKey::new("c", "Copy Receive Address"),
+    ScreenCommandKey::new("v", "Show/hide Receive Address QR"),
+    ScreenCommandKey::new("p", "Send Dash to an address"),
     ScreenCommandKey::new("u", "Get more utxos"),
+    ScreenCommandKey::new("o", "Coin control"),
     ScreenCommandKey::new("C-w", "Clear loaded wallet"),
+    ScreenCommandKey::new("s", "Set sweep wallet by private key"),
+    ScreenCommandKey::new("C-s", "Clear sweep wallet"),
 ];
 
 const IDENTITY_LOADED_COMMANDS: [ScreenCommandKey; 2] = [
@@ -43,7 +56,10 @@ const IDENTITY_LOADED_COMMANDS: [ScreenCommandKey; 2] = [
 
 #[memoize::memoize]
 fn join_commands(wallet_loaded: bool, identity_loaded: bool) -> &'static [ScreenCommandKey] {
-    let mut commands = vec![ScreenCommandKey::new("q", "Back to Main")];
+    let mut commands = vec![
+        ScreenCommandKey::new("q", "Back to Main"),
+        ScreenCommandKey::new("m", "Wallet manager"),
+    ];
 
     if wallet_loaded {
         commands.extend_from_slice(&WALLET_LOADED_COMMANDS);
@@ -63,35 +79,41 @@ pub(crate) struct WalletScreenController {
     wallet_loaded: bool,
     identity_loaded: bool,
     identity_registration_in_progress: bool,
+    sweep_wallet_address: Option<String>,
+    receive_address: Option<String>,
+    /// Receive address QR code, when shown
+    receive_address_qr: Option<Info>,
 }
 
 impl_builder!(WalletScreenController);
 
 impl WalletScreenController {
     pub(crate) async fn new(app_state: &AppState) -> Self {
+        let snapshot = app_state.snapshot();
+        let sweep_wallet_address = snapshot.sweep_wallet_address.clone();
+        let receive_address = snapshot
+            .loaded_wallet
+            .as_ref()
+            .map(|wallet| wallet.receive_address().to_string());
         let (
             wallet_info,
             identity_info,
             wallet_loaded,
             identity_loaded,
             identity_registration_in_progress,
-        ) = if let Some(wallet) = app_state.loaded_wallet.lock().await.as_ref() {
-            if let Some(identity) = app_state.loaded_identity.lock().await.as_ref() {
+        ) = if let Some(wallet) = snapshot.loaded_wallet.as_ref() {
+            if let Some(identity) = snapshot.loaded_identity.as_ref() {
                 (
-                    Info::new_fixed(&display_wallet(wallet)),
+                    Info::new_fixed(&display_wallet(wallet, sweep_wallet_address.as_deref())),
                     Info::new_fixed(&display_info(identity)),
                     true,
                     true,
                     false,
                 )
             } else {
-                let identity_registration_in_progress = app_state
-                    .identity_asset_lock_private_key_in_creation
-                    .lock()
-                    .await
-                    .is_some();
+                let identity_registration_in_progress = snapshot.identity_registration_in_progress;
                 (
-                    Info::new_fixed(&display_wallet(wallet)),
+                    Info::new_fixed(&display_wallet(wallet, sweep_wallet_address.as_deref())),
                     Info::new_fixed(
                         "No identity loaded yet. Go to Identities screen to load or register one.",
                     ),
@@ -101,7 +123,7 @@ impl WalletScreenController {
                 )
             }
         } else {
-            if let Some(identity) = app_state.loaded_identity.lock().await.as_ref() {
+            if let Some(identity) = snapshot.loaded_identity.as_ref() {
                 (
                     Info::new_fixed("Wallet management commands\n\nNo wallet loaded yet"),
                     Info::new_fixed(&display_info(identity)),
@@ -110,11 +132,7 @@ impl WalletScreenController {
                     false,
                 )
             } else {
-                let identity_registration_in_progress = app_state
-                    .identity_asset_lock_private_key_in_creation
-                    .lock()
-                    .await
-                    .is_some();
+                let identity_registration_in_progress = snapshot.identity_registration_in_progress;
                 (
                     Info::new_fixed("Wallet management commands\n\nNo wallet loaded yet"),
                     Info::new_fixed(
@@ -133,6 +151,9 @@ impl WalletScreenController {
             wallet_loaded,
             identity_loaded,
             identity_registration_in_progress,
+            sweep_wallet_address,
+            receive_address,
+            receive_address_qr: None,
         }
     }
 }
@@ -143,7 +164,16 @@ impl ScreenController for WalletScreenController {
             .direction(Direction::Vertical)
             .constraints([Constraint::Min(20), Constraint::Min(20)].as_ref())
             .split(area);
-        self.wallet_info.view(frame, layout[0]);
+        if let Some(qr) = &mut self.receive_address_qr {
+            let wallet_layout = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Min(40), Constraint::Length(QR_WIDTH)].as_ref())
+                .split(layout[0]);
+            self.wallet_info.view(frame, wallet_layout[0]);
+            qr.view(frame, wallet_layout[1]);
+        } else {
+            self.wallet_info.view(frame, layout[0]);
+        }
         self.identity_info.view(frame, layout[1]);
     }
 
@@ -166,6 +196,11 @@ impl ScreenController for WalletScreenController {
                 modifiers: KeyModifiers::NONE,
             }) => ScreenFeedback::PreviousScreen,
 
+            Event::Key(KeyEvent {
+                code: Key::Char('m'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::NextScreen(WalletManagerScreenController::builder()),
+
             Event::Key(KeyEvent {
                 code: Key::Char('a'),
                 modifiers: KeyModifiers::NONE,
@@ -173,10 +208,16 @@ impl ScreenController for WalletScreenController {
                 ScreenFeedback::Form(Box::new(AddWalletPrivateKeyFormController::new()))
             }
 
-            Event::Key(KeyEvent {
-                code: Key::Char('b'),
-                modifiers: KeyModifiers::NONE,
-            }) if self.wallet_loaded => ScreenFeedback::Task {
+            Event::Key(
+                KeyEvent {
+                    code: Key::Char('b'),
+                    modifiers: KeyModifiers::NONE,
+                }
+                | KeyEvent {
+                    code: Key::Char('r'),
+                    modifiers: KeyModifiers::CONTROL,
+                },
+            ) if self.wallet_loaded => ScreenFeedback::Task {
                 task: Task::Wallet(WalletTask::Refresh),
                 block: true,
             },
@@ -209,6 +250,18 @@ impl ScreenController for WalletScreenController {
                 modifiers: KeyModifiers::NONE,
             }) => ScreenFeedback::Form(Box::new(SplitUTXOsFormController::new())),
 
+            Event::Key(KeyEvent {
+                code: Key::Char('p'),
+                modifiers: KeyModifiers::NONE,
+            }) if self.wallet_loaded => ScreenFeedback::Form(Box::new(SendFormController::new())),
+
+            Event::Key(KeyEvent {
+                code: Key::Char('o'),
+                modifiers: KeyModifiers::NONE,
+            }) if self.wallet_loaded => {
+                ScreenFeedback::NextScreen(CoinControlScreenController::builder())
+            }
+
             Event::Key(KeyEvent {
                 code: Key::Char('c'),
                 modifiers: KeyModifiers::NONE,
@@ -217,6 +270,27 @@ impl ScreenController for WalletScreenController {
                 block: true,
             },
 
+            Event::Key(KeyEvent {
+                code: Key::Char('v'),
+                modifiers: KeyModifiers::NONE,
+            }) if self.wallet_loaded => {
+                if self.receive_address_qr.take().is_some() {
+                    return ScreenFeedback::Redraw;
+                }
+                let Some(address) = &self.receive_address else {
+                    return ScreenFeedback::None;
+                };
+                self.receive_address_qr = Some(match address_qr(address) {
+                    Ok(qr) => Info::new_fixed(&qr),
+                    Err(e) => Info::new_error(&e),
+                });
+                // Copied too, for funding from a wallet on the same machine
+                ScreenFeedback::Task {
+                    task: Task::Wallet(WalletTask::CopyAddress),
+                    block: true,
+                }
+            }
+
             Event::Key(KeyEvent {
                 code: Key::Char('w'),
                 modifiers: KeyModifiers::CONTROL,
@@ -228,6 +302,46 @@ impl ScreenController for WalletScreenController {
                 }
             }
 
+            Event::Key(KeyEvent {
+                code: Key::Char('s'),
+                modifiers: KeyModifiers::NONE,
+            }) if self.wallet_loaded => {
+                ScreenFeedback::Form(Box::new(SetSweepWalletFormController::new()))
+            }
+
+            Event::Key(KeyEvent {
+                code: Key::Char('s'),
+                modifiers: KeyModifiers::CONTROL,
+            }) if self.wallet_loaded => ScreenFeedback::Task {
+                task: Task::Wallet(WalletTask::ClearSweepWallet),
+                block: false,
+            },
+
+            Event::Backend(BackendEvent::TaskCompletedStateChange {
+                app_state_update: AppStateUpdate::SweepWalletAddress(address),
+                ..
+            }) => {
+                self.sweep_wallet_address = address.clone();
+                // Refresh to show the loaded wallet along with the new sweep wallet
+                ScreenFeedback::Task {
+                    task: Task::Wallet(WalletTask::Refresh),
+                    block: true,
+                }
+            }
+
+            Event::Backend(BackendEvent::TaskCompletedStateChange {
+                task: Task::Wallet(WalletTask::Send { .. }),
+                execution_result: Ok(message),
+                app_state_update: AppStateUpdate::LoadedWallet(wallet),
+            }) => {
+                self.wallet_info = Info::new_fixed(&format!(
+                    "{}\n\n{}",
+                    message,
+                    display_wallet(&wallet, self.sweep_wallet_address.as_deref())
+                ));
+                ScreenFeedback::Redraw
+            }
+
             Event::Backend(
                 BackendEvent::AppStateUpdated(AppStateUpdate::LoadedWallet(wallet))
                 | BackendEvent::TaskCompletedStateChange {
@@ -235,8 +349,17 @@ impl ScreenController for WalletScreenController {
                     ..
                 },
             ) => {
-                self.wallet_info = Info::new_fixed(&display_wallet(&wallet));
+                self.wallet_info = Info::new_fixed(&display_wallet(
+                    &wallet,
+                    self.sweep_wallet_address.as_deref(),
+                ));
                 self.wallet_loaded = true;
+                let receive_address = wallet.receive_address().to_string();
+                if self.receive_address.as_ref() != Some(&receive_address) {
+                    // Another wallet was loaded
+                    self.receive_address_qr = None;
+                }
+                self.receive_address = Some(receive_address);
                 ScreenFeedback::Redraw
             }
 
@@ -251,6 +374,17 @@ impl ScreenController for WalletScreenController {
                 ScreenFeedback::Redraw
             }
 
+            Event::Backend(BackendEvent::TaskCompletedStateChange {
+                task: Task::Identity(IdentityTask::RegisterIdentity(_)),
+                execution_result,
+                app_state_update: AppStateUpdate::IdentityRegistrationQueue(_),
+            }) => {
+                self.identity_info = Info::new_from_result(execution_result);
+                self.identity_loaded = false;
+                self.identity_registration_in_progress = false;
+                ScreenFeedback::Redraw
+            }
+
             Event::Backend(BackendEvent::TaskCompletedStateChange {
                 task: Task::Identity(IdentityTask::ClearLoadedIdentity),
                 execution_result: _,
@@ -267,6 +401,8 @@ impl ScreenController for WalletScreenController {
             }) => {
                 self.wallet_info =
                     Info::new_fixed("Wallet management commands\n\nNo wallet loaded yet");
+                self.receive_address = None;
+                self.receive_address_qr = None;
                 ScreenFeedback::Redraw
             }
 
@@ -383,6 +519,50 @@ impl FormController for AddWalletPrivateKeyFormController {
     }
 }
 
+struct SetSweepWalletFormController {
+    input: TextInput<DefaultTextInputParser<String>>,
+}
+
+impl SetSweepWalletFormController {
+    fn new() -> Self {
+        SetSweepWalletFormController {
+            input: TextInput::new("64 hex character or WIF private key"),
+        }
+    }
+}
+
+impl FormController for SetSweepWalletFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done(private_key) => FormStatus::Done {
+                task: Task::Wallet(WalletTask::SetSweepWallet(private_key)),
+                block: true,
+            },
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Set sweep wallet for strategy withdrawals"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        "Private key"
+    }
+
+    fn step_index(&self) -> u8 {
+        0
+    }
+
+    fn steps_number(&self) -> u8 {
+        1
+    }
+}
+
 struct SplitUTXOsFormController {
     input: TextInput<DefaultTextInputParser<u32>>,
 }
@@ -427,29 +607,129 @@ impl FormController for SplitUTXOsFormController {
     }
 }
 
-fn display_wallet(wallet: &Wallet) -> String {
+struct SendFormController {
+    input: ComposedInput<(
+        Field<TextInput<DefaultTextInputParser<String>>>,
+        Field<TextInput<DuffsTextInputParser>>,
+        Field<SelectInput<FeeLevel>>,
+    )>,
+}
+
+impl SendFormController {
+    fn new() -> Self {
+        Self {
+            input: ComposedInput::new((
+                Field::new("Recipient address", TextInput::new("Dash address")),
+                Field::new(
+                    "Amount",
+                    TextInput::new_with_parser(DuffsTextInputParser, "Amount in Dash"),
+                ),
+                Field::new(
+                    "Fee",
+                    SelectInput::new(vec![
+                        FeeLevel::Minimum,
+                        FeeLevel::Normal,
+                        FeeLevel::Priority,
+                    ]),
+                ),
+            )),
+        }
+    }
+}
+
+impl FormController for SendFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done((address, amount, fee_level)) => FormStatus::Done {
+                task: Task::Wallet(WalletTask::Send {
+                    address,
+                    amount,
+                    fee_level,
+                }),
+                block: true,
+            },
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Send Dash"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        self.input.step_name()
+    }
+
+    fn step_index(&self) -> u8 {
+        self.input.step_index()
+    }
+
+    fn steps_number(&self) -> u8 {
+        self.input.steps_number()
+    }
+}
+
+/// Columns of the receive address QR code, quiet zone and borders included
+const QR_WIDTH: u16 = 41;
+
+/// Receive address as a `dash:` URI in a QR code of half blocks, light on
+/// dark as terminals usually are, with the address below it.
+fn address_qr(address: &str) -> Result<String, String> {
+    let code = QrCode::new(format!("dash:{}", address))
+        .map_err(|e| format!("Cannot encode the address in a QR code: {}", e))?;
+    let qr = code
+        .render::<Dense1x2>()
+        .dark_color(Dense1x2::Light)
+        .light_color(Dense1x2::Dark)
+        .build();
+    Ok(format!("{}\n{}", qr, address))
+}
+
+fn display_wallet(wallet: &Wallet, sweep_wallet_address: Option<&str>) -> String {
+    let sweep_wallet =
+        sweep_wallet_address.unwrap_or("not set, strategy runs withdraw to the loaded wallet");
     match wallet {
         Wallet::SingleKeyWallet(single_key_wallet) => {
             let description = format!(
                 "Single Key Wallet\nPublic Key: {}\nAddress: {}\nBalance: {}",
                 hex::encode(single_key_wallet.public_key.serialize()),
                 single_key_wallet.address,
-                single_key_wallet.balance_dash_formatted()
+                single_key_wallet.balance_formatted()
             );
             let utxo_count = single_key_wallet.utxos.len();
-            format!("{}\nNumber of UTXOs: {}", description, utxo_count)
+            format!(
+                "{}\nNumber of UTXOs: {} ({} frozen)\nSweep wallet: {}",
+                description,
+                utxo_count,
+                single_key_wallet.frozen_utxos.len(),
+                sweep_wallet
+            )
         }
+        Wallet::CoreRpcWallet(core_wallet) => format!(
+            "Core RPC Wallet\nCore wallet: {}\nAddress: {}\nBalance: {}\nNumber of UTXOs: {} ({} \
+             frozen)\nSweep wallet: {}",
+            core_wallet.display_name(),
+            core_wallet.address,
+            wallet.balance_formatted(),
+            core_wallet.utxos.len(),
+            core_wallet.frozen_utxos.len(),
+            sweep_wallet
+        ),
     }
 }
 
 pub struct WithdrawFromIdentityFormController {
-    input: TextInput<DefaultTextInputParser<f64>>,
+    input: TextInput<CreditsTextInputParser>,
 }
 
 impl WithdrawFromIdentityFormController {
     pub fn new() -> Self {
         WithdrawFromIdentityFormController {
-            input: TextInput::new("Quantity (in Dash)"),
+            input: TextInput::new_with_parser(CreditsTextInputParser, "Amount"),
         }
     }
 }
@@ -458,14 +738,10 @@ impl FormController for WithdrawFromIdentityFormController {
     fn on_event(&mut self, event: KeyEvent) -> FormStatus {
         match self.input.on_event(event) {
             InputStatus::Done(amount) => FormStatus::Done {
-                task: Task::Identity(IdentityTask::WithdrawFromIdentity(
-                    (amount * 100000000000.0) as u64,
-                )),
+                task: Task::Identity(IdentityTask::WithdrawFromIdentity(amount)),
                 block: true,
             },
-            InputStatus::Redraw => FormStatus::Redraw,
-            InputStatus::None => FormStatus::None,
-            InputStatus::Exit => FormStatus::Exit,
+            status => status.into(),
         }
     }
 
@@ -0,0 +1,194 @@
+//! Screen of the loaded wallet's UTXOs to freeze the ones asset locks shouldn't
+//! spend.
+
+use std::sync::Arc;
+
+use dpp::dashcore::OutPoint;
+use tokio::sync::watch;
+use tuirealm::{
+    event::{Key, KeyEvent, KeyModifiers},
+    props::TextSpan,
+    tui::prelude::{Constraint, Direction, Layout, Rect},
+    Frame,
+};
+
+use crate::{
+    backend::{snapshot::AppStateSnapshot, AppState, BackendEvent, Task, WalletTask},
+    ui::screen::{
+        utils::impl_builder,
+        widgets::{info::Info, virtual_list::VirtualList},
+        ScreenCommandKey, ScreenController, ScreenFeedback, ScreenToggleKey,
+    },
+    units, Event,
+};
+
+const COMMAND_KEYS: [ScreenCommandKey; 5] = [
+    ScreenCommandKey::new("q", "Back to Wallet"),
+    ScreenCommandKey::new("f", "Freeze/unfreeze selected UTXO"),
+    ScreenCommandKey::new("b", "Refresh wallet utxos"),
+    ScreenCommandKey::new("C-n", "Next UTXO"),
+    ScreenCommandKey::new("C-p", "Prev UTXO"),
+];
+
+pub(crate) struct CoinControlScreenController {
+    snapshots: watch::Receiver<Arc<AppStateSnapshot>>,
+    /// Outpoints in the order of the list
+    outpoints: Vec<OutPoint>,
+    utxo_select: VirtualList,
+    info: Info,
+}
+
+impl_builder!(CoinControlScreenController);
+
+impl CoinControlScreenController {
+    pub(crate) async fn new(app_state: &AppState) -> Self {
+        let mut controller = CoinControlScreenController {
+            snapshots: app_state.subscribe_snapshot(),
+            outpoints: Vec::new(),
+            utxo_select: VirtualList::new(Vec::new(), 0),
+            info: Info::new_fixed(""),
+        };
+        controller.reload(&app_state.snapshot());
+        controller
+    }
+
+    /// Lists the UTXOs of the latest snapshot, keeping the selection on the
+    /// same UTXO if it's still there.
+    fn reload(&mut self, snapshot: &AppStateSnapshot) {
+        let previous = self.selected_outpoint();
+        let Some(wallet) = snapshot.loaded_wallet.as_ref() else {
+            self.outpoints = Vec::new();
+            self.utxo_select = VirtualList::new(Vec::new(), 0);
+            self.info = Info::new_fixed("No wallet loaded");
+            return;
+        };
+
+        let mut utxos: Vec<_> = wallet.utxos().iter().collect();
+        utxos.sort_by_key(|(outpoint, _)| **outpoint);
+        let rows = utxos
+            .iter()
+            .map(|(outpoint, tx_out)| {
+                vec![TextSpan::new(format!(
+                    "{} {}  {}  {} conf.",
+                    if wallet.frozen_utxos().contains(outpoint) {
+                        "F"
+                    } else {
+                        " "
+                    },
+                    outpoint,
+                    units::format_duffs(tx_out.value),
+                    wallet
+                        .utxo_confirmations()
+                        .get(outpoint)
+                        .map(u32::to_string)
+                        .unwrap_or_else(|| "?".to_owned())
+                ))]
+            })
+            .collect();
+        self.outpoints = utxos.into_iter().map(|(outpoint, _)| *outpoint).collect();
+        let selected = previous
+            .and_then(|previous| self.outpoints.iter().position(|o| *o == previous))
+            .unwrap_or_default();
+        self.utxo_select = VirtualList::new(rows, selected);
+        self.utxo_select
+            .set_title(Some(format!("UTXOs, F frozen: {}", self.outpoints.len())));
+        self.info = Info::new_fixed(&format!(
+            "Asset lock transactions and UTXO splits only spend unfrozen UTXOs\n\n\
+             Spendable: {} in {} UTXOs\nFrozen: {} in {} UTXOs",
+            units::format_duffs(wallet.spendable_balance()),
+            wallet.spendable_utxos().count(),
+            units::format_duffs(wallet.balance() - wallet.spendable_balance()),
+            wallet.frozen_utxos().len()
+        ));
+    }
+
+    fn selected_outpoint(&self) -> Option<OutPoint> {
+        self.outpoints.get(self.utxo_select.selected()).copied()
+    }
+}
+
+impl ScreenController for CoinControlScreenController {
+    fn name(&self) -> &'static str {
+        "Coin control"
+    }
+
+    fn command_keys(&self) -> &[ScreenCommandKey] {
+        COMMAND_KEYS.as_ref()
+    }
+
+    fn toggle_keys(&self) -> &[ScreenToggleKey] {
+        &[]
+    }
+
+    fn on_event(&mut self, event: &Event) -> ScreenFeedback {
+        match event {
+            Event::Key(KeyEvent {
+                code: Key::Char('q'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::PreviousScreen,
+
+            Event::Key(KeyEvent {
+                code: Key::Char('f'),
+                modifiers: KeyModifiers::NONE,
+            }) => match self.selected_outpoint() {
+                Some(outpoint) => ScreenFeedback::Task {
+                    task: Task::Wallet(WalletTask::ToggleUtxoFrozen(outpoint)),
+                    block: false,
+                },
+                None => ScreenFeedback::None,
+            },
+
+            Event::Key(KeyEvent {
+                code: Key::Char('b'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::Task {
+                task: Task::Wallet(WalletTask::Refresh),
+                block: true,
+            },
+
+            Event::Key(k) => {
+                if self.utxo_select.on_event(k) || self.info.on_event(k) {
+                    ScreenFeedback::Redraw
+                } else {
+                    ScreenFeedback::None
+                }
+            }
+
+            Event::Backend(
+                BackendEvent::TaskCompleted {
+                    task: Task::Wallet(WalletTask::ToggleUtxoFrozen(_) | WalletTask::Refresh),
+                    execution_result: Err(e),
+                }
+                | BackendEvent::TaskCompletedStateChange {
+                    task: Task::Wallet(WalletTask::ToggleUtxoFrozen(_) | WalletTask::Refresh),
+                    execution_result: Err(e),
+                    ..
+                },
+            ) => {
+                self.info = Info::new_error(e);
+                ScreenFeedback::Redraw
+            }
+
+            Event::Tick { .. } => {
+                if self.snapshots.has_changed().unwrap_or(false) {
+                    let snapshot = self.snapshots.borrow_and_update().clone();
+                    self.reload(&snapshot);
+                    ScreenFeedback::Redraw
+                } else {
+                    ScreenFeedback::None
+                }
+            }
+
+            _ => ScreenFeedback::None,
+        }
+    }
+
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+            .split(area);
+        self.utxo_select.view(frame, layout[0]);
+        self.info.view(frame, layout[1]);
+    }
+}
@@ -0,0 +1,479 @@
+//! Screen of the named wallets to pick the loaded one from.
+
+use std::sync::Arc;
+
+use tokio::sync::watch;
+use tuirealm::{
+    event::{Key, KeyEvent, KeyModifiers},
+    props::TextSpan,
+    tui::prelude::{Constraint, Direction, Layout, Rect},
+    Frame,
+};
+
+use crate::{
+    backend::{snapshot::AppStateSnapshot, AppState, BackendEvent, Task, Wallet, WalletTask},
+    ui::{
+        form::{
+            parsers::DefaultTextInputParser, ComposedInput, Field, FormController, FormStatus,
+            Input, InputStatus, TextInput,
+        },
+        screen::{
+            utils::impl_builder,
+            widgets::{info::Info, virtual_list::VirtualList},
+            ScreenCommandKey, ScreenController, ScreenFeedback, ScreenToggleKey,
+        },
+    },
+    Event,
+};
+
+const COMMAND_KEYS: [ScreenCommandKey; 9] = [
+    ScreenCommandKey::new("q", "Back to Wallet"),
+    ScreenCommandKey::new("a", "Add wallet by private key"),
+    ScreenCommandKey::new("m", "Add wallet by mnemonic"),
+    ScreenCommandKey::new("r", "Add brand new random wallet"),
+    ScreenCommandKey::new("c", "Add wallet of the connected Core"),
+    ScreenCommandKey::new("s", "Load selected wallet"),
+    ScreenCommandKey::new("x", "Remove selected wallet"),
+    ScreenCommandKey::new("C-n", "Next wallet"),
+    ScreenCommandKey::new("C-p", "Prev wallet"),
+];
+
+/// Name shown for a loaded wallet that wasn't added in the wallet manager, it's
+/// stored under its address once another one is loaded.
+const UNNAMED_WALLET: &str = "(unnamed)";
+
+pub(crate) struct WalletManagerScreenController {
+    snapshots: watch::Receiver<Arc<AppStateSnapshot>>,
+    /// Wallet names in the order of the list, the loaded one first
+    names: Vec<String>,
+    /// Whether the first wallet of the list is the loaded one
+    loaded_listed: bool,
+    wallet_select: VirtualList,
+    info: Info,
+}
+
+impl_builder!(WalletManagerScreenController);
+
+impl WalletManagerScreenController {
+    pub(crate) async fn new(app_state: &AppState) -> Self {
+        let mut controller = WalletManagerScreenController {
+            snapshots: app_state.subscribe_snapshot(),
+            names: Vec::new(),
+            loaded_listed: false,
+            wallet_select: VirtualList::new(Vec::new(), 0),
+            info: Info::new_fixed(
+                "Strategies and identity registrations are funded from the loaded wallet, \
+                 the others are kept under their name until loaded",
+            ),
+        };
+        controller.reload(&app_state.snapshot());
+        controller
+    }
+
+    /// Lists the wallets of the latest snapshot, keeping the selection on the
+    /// same wallet if it's still there.
+    fn reload(&mut self, snapshot: &AppStateSnapshot) {
+        let previous = self.selected_name().map(str::to_owned);
+        let loaded = snapshot.loaded_wallet.as_ref().map(|wallet| {
+            (
+                snapshot
+                    .loaded_wallet_name
+                    .clone()
+                    .unwrap_or_else(|| UNNAMED_WALLET.to_owned()),
+                wallet,
+            )
+        });
+        let wallets: Vec<(String, &Wallet)> = loaded
+            .into_iter()
+            .chain(
+                snapshot
+                    .stored_wallets
+                    .iter()
+                    .map(|(name, wallet)| (name.clone(), wallet)),
+            )
+            .collect();
+
+        let rows = wallets
+            .iter()
+            .enumerate()
+            .map(|(index, (name, wallet))| {
+                vec![TextSpan::new(format!(
+                    "{} {}: {}, {}",
+                    if index == 0 && snapshot.loaded_wallet.is_some() {
+                        "*"
+                    } else {
+                        " "
+                    },
+                    name,
+                    wallet.receive_address(),
+                    wallet.balance_formatted()
+                ))]
+            })
+            .collect();
+        self.names = wallets.into_iter().map(|(name, _)| name).collect();
+        self.loaded_listed = snapshot.loaded_wallet.is_some();
+        let selected = previous
+            .and_then(|previous| self.names.iter().position(|name| *name == previous))
+            .unwrap_or_default();
+        self.wallet_select = VirtualList::new(rows, selected);
+        self.wallet_select
+            .set_title(Some(format!("Wallets, * loaded: {}", self.names.len())));
+    }
+
+    fn selected_name(&self) -> Option<&str> {
+        self.names
+            .get(self.wallet_select.selected())
+            .map(String::as_str)
+    }
+
+    fn loaded_selected(&self) -> bool {
+        self.loaded_listed && self.wallet_select.selected() == 0
+    }
+}
+
+impl ScreenController for WalletManagerScreenController {
+    fn name(&self) -> &'static str {
+        "Wallet manager"
+    }
+
+    fn command_keys(&self) -> &[ScreenCommandKey] {
+        COMMAND_KEYS.as_ref()
+    }
+
+    fn toggle_keys(&self) -> &[ScreenToggleKey] {
+        &[]
+    }
+
+    fn on_event(&mut self, event: &Event) -> ScreenFeedback {
+        match event {
+            Event::Key(KeyEvent {
+                code: Key::Char('q'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::PreviousScreen,
+
+            Event::Key(KeyEvent {
+                code: Key::Char('a'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::Form(Box::new(AddNamedWalletFormController::new())),
+
+            Event::Key(KeyEvent {
+                code: Key::Char('m'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::Form(Box::new(AddMnemonicWalletFormController::new())),
+
+            Event::Key(KeyEvent {
+                code: Key::Char('r'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::Form(Box::new(AddRandomWalletFormController::new())),
+
+            Event::Key(KeyEvent {
+                code: Key::Char('c'),
+                modifiers: KeyModifiers::NONE,
+            }) => ScreenFeedback::Form(Box::new(AddCoreWalletFormController::new())),
+
+            // The loaded wallet is first, it's already loaded
+            Event::Key(KeyEvent {
+                code: Key::Char('s'),
+                modifiers: KeyModifiers::NONE,
+            }) => match self.selected_name() {
+                Some(name) if !self.loaded_selected() => ScreenFeedback::Task {
+                    task: Task::Wallet(WalletTask::Select(name.to_owned())),
+                    block: true,
+                },
+                _ => ScreenFeedback::None,
+            },
+
+            Event::Key(KeyEvent {
+                code: Key::Char('x'),
+                modifiers: KeyModifiers::NONE,
+            }) if self.loaded_selected() => {
+                self.info = Info::new_error(
+                    "The loaded wallet can't be removed, load another one first or clear it from \
+                     the wallet screen",
+                );
+                ScreenFeedback::Redraw
+            }
+
+            Event::Key(KeyEvent {
+                code: Key::Char('x'),
+                modifiers: KeyModifiers::NONE,
+            }) => match self.selected_name() {
+                Some(name) => ScreenFeedback::Task {
+                    task: Task::Wallet(WalletTask::Remove(name.to_owned())),
+                    block: false,
+                },
+                None => ScreenFeedback::None,
+            },
+
+            Event::Key(k) => {
+                if self.wallet_select.on_event(k) || self.info.on_event(k) {
+                    ScreenFeedback::Redraw
+                } else {
+                    ScreenFeedback::None
+                }
+            }
+
+            Event::Backend(
+                BackendEvent::TaskCompleted {
+                    task:
+                        Task::Wallet(
+                            WalletTask::AddNamed { .. }
+                            | WalletTask::AddByMnemonic { .. }
+                            | WalletTask::AddCoreWallet { .. }
+                            | WalletTask::Select(_)
+                            | WalletTask::Remove(_),
+                        ),
+                    execution_result,
+                }
+                | BackendEvent::TaskCompletedStateChange {
+                    task:
+                        Task::Wallet(
+                            WalletTask::AddNamed { .. }
+                            | WalletTask::AddByMnemonic { .. }
+                            | WalletTask::AddCoreWallet { .. }
+                            | WalletTask::Select(_)
+                            | WalletTask::Remove(_),
+                        ),
+                    execution_result,
+                    ..
+                },
+            ) => {
+                self.info = Info::new_from_result(execution_result);
+                ScreenFeedback::Redraw
+            }
+
+            Event::Tick { .. } => {
+                if self.snapshots.has_changed().unwrap_or(false) {
+                    let snapshot = self.snapshots.borrow_and_update().clone();
+                    self.reload(&snapshot);
+                    ScreenFeedback::Redraw
+                } else {
+                    ScreenFeedback::None
+                }
+            }
+
+            _ => ScreenFeedback::None,
+        }
+    }
+
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+            .split(area);
+        self.wallet_select.view(frame, layout[0]);
+        self.info.view(frame, layout[1]);
+    }
+}
+
+struct AddNamedWalletFormController {
+    input: ComposedInput<(
+        Field<TextInput<DefaultTextInputParser<String>>>, // Name
+        Field<TextInput<DefaultTextInputParser<String>>>, // Private key
+    )>,
+}
+
+impl AddNamedWalletFormController {
+    fn new() -> Self {
+        AddNamedWalletFormController {
+            input: ComposedInput::new((
+                Field::new("Name of the wallet", TextInput::new("Wallet name")),
+                Field::new(
+                    "Private key",
+                    TextInput::new("64 hex character or WIF private key"),
+                ),
+            )),
+        }
+    }
+}
+
+impl FormController for AddNamedWalletFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done((name, private_key)) => FormStatus::Done {
+                task: Task::Wallet(WalletTask::AddNamed {
+                    name,
+                    private_key: Some(private_key),
+                }),
+                block: true,
+            },
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Add wallet with private key"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        self.input.step_name()
+    }
+
+    fn step_index(&self) -> u8 {
+        self.input.step_index()
+    }
+
+    fn steps_number(&self) -> u8 {
+        self.input.steps_number()
+    }
+}
+
+struct AddMnemonicWalletFormController {
+    input: ComposedInput<(
+        Field<TextInput<DefaultTextInputParser<String>>>, // Name
+        Field<TextInput<DefaultTextInputParser<String>>>, // Mnemonic
+    )>,
+}
+
+impl AddMnemonicWalletFormController {
+    fn new() -> Self {
+        AddMnemonicWalletFormController {
+            input: ComposedInput::new((
+                Field::new("Name of the wallet", TextInput::new("Wallet name")),
+                Field::new(
+                    "Mnemonic",
+                    TextInput::new("12 to 24 words separated by spaces"),
+                ),
+            )),
+        }
+    }
+}
+
+impl FormController for AddMnemonicWalletFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done((name, mnemonic)) => FormStatus::Done {
+                task: Task::Wallet(WalletTask::AddByMnemonic { name, mnemonic }),
+                block: true,
+            },
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Add wallet by mnemonic"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        self.input.step_name()
+    }
+
+    fn step_index(&self) -> u8 {
+        self.input.step_index()
+    }
+
+    fn steps_number(&self) -> u8 {
+        self.input.steps_number()
+    }
+}
+
+struct AddRandomWalletFormController {
+    input: TextInput<DefaultTextInputParser<String>>,
+}
+
+impl AddRandomWalletFormController {
+    fn new() -> Self {
+        AddRandomWalletFormController {
+            input: TextInput::new("Wallet name"),
+        }
+    }
+}
+
+impl FormController for AddRandomWalletFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done(name) => FormStatus::Done {
+                task: Task::Wallet(WalletTask::AddNamed {
+                    name,
+                    private_key: None,
+                }),
+                block: true,
+            },
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Add brand new random wallet"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        "Name of the wallet"
+    }
+
+    fn step_index(&self) -> u8 {
+        0
+    }
+
+    fn steps_number(&self) -> u8 {
+        1
+    }
+}
+
+struct AddCoreWalletFormController {
+    input: ComposedInput<(
+        Field<TextInput<DefaultTextInputParser<String>>>, // Name
+        Field<TextInput<DefaultTextInputParser<String>>>, // Core wallet name
+    )>,
+}
+
+impl AddCoreWalletFormController {
+    fn new() -> Self {
+        AddCoreWalletFormController {
+            input: ComposedInput::new((
+                Field::new("Name of the wallet", TextInput::new("Wallet name")),
+                Field::new(
+                    "Name of the wallet in Core",
+                    TextInput::new("Empty for Core's default wallet"),
+                ),
+            )),
+        }
+    }
+}
+
+impl FormController for AddCoreWalletFormController {
+    fn on_event(&mut self, event: KeyEvent) -> FormStatus {
+        match self.input.on_event(event) {
+            InputStatus::Done((name, core_wallet_name)) => FormStatus::Done {
+                task: Task::Wallet(WalletTask::AddCoreWallet {
+                    name,
+                    core_wallet_name,
+                }),
+                block: true,
+            },
+            status => status.into(),
+        }
+    }
+
+    fn form_name(&self) -> &'static str {
+        "Add wallet of the connected Core"
+    }
+
+    fn step_view(&mut self, frame: &mut Frame, area: Rect) {
+        self.input.view(frame, area)
+    }
+
+    fn step_name(&self) -> &'static str {
+        self.input.step_name()
+    }
+
+    fn step_index(&self) -> u8 {
+        self.input.step_index()
+    }
+
+    fn steps_number(&self) -> u8 {
+        self.input.steps_number()
+    }
+}
@@ -40,12 +40,11 @@ impl_builder!(WithdrawalsScreenController);
 
 impl WithdrawalsScreenController {
     pub(crate) async fn new(app_state: &AppState) -> Self {
-        let known_identities_lock = app_state.known_identities.lock().await;
         let info = Info::new_fixed("Withdrawals testing screen");
 
         Self {
             info,
-            identities_map: known_identities_lock.clone(),
+            identities_map: app_state.snapshot().known_identities.clone(),
         }
     }
 }
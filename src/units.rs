@@ -0,0 +1,192 @@
+//! Amounts of Dash in the unit chosen for display.
+//!
+//! Platform keeps balances in credits and Core in duffs, a thousand credits
+//! to a duff and a hundred million duffs to a Dash. Ctrl+u cycles the unit
+//! amounts are shown and typed in between Dash, duffs and credits anywhere,
+//! text already on screen picks it up once redrawn or rebuilt. Typed amounts
+//! may name their unit, e.g. `1500 duffs`, to use another one than shown.
+
+use std::{
+    fmt::{self, Display},
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+pub const CREDITS_PER_DUFF: u64 = 1000;
+pub const DUFFS_PER_DASH: u64 = 100_000_000;
+pub const CREDITS_PER_DASH: u64 = CREDITS_PER_DUFF * DUFFS_PER_DASH;
+
+static SHOWN_UNIT: AtomicU8 = AtomicU8::new(Unit::Dash as u8);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Dash,
+    Duffs,
+    Credits,
+}
+
+impl Unit {
+    const ALL: [Unit; 3] = [Unit::Dash, Unit::Duffs, Unit::Credits];
+
+    fn credits(self) -> u64 {
+        match self {
+            Unit::Dash => CREDITS_PER_DASH,
+            Unit::Duffs => CREDITS_PER_DUFF,
+            Unit::Credits => 1,
+        }
+    }
+
+    /// Digits after the decimal point needed to write any amount of credits
+    fn decimals(self) -> usize {
+        match self {
+            Unit::Dash => 11,
+            Unit::Duffs => 3,
+            Unit::Credits => 0,
+        }
+    }
+
+    /// Unit named after an amount, singular or plural and in any case.
+    fn from_name(name: &str) -> Option<Unit> {
+        match name.to_lowercase().as_str() {
+            "dash" => Some(Unit::Dash),
+            "duff" | "duffs" => Some(Unit::Duffs),
+            "credit" | "credits" => Some(Unit::Credits),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Unit::Dash => write!(f, "DASH"),
+            Unit::Duffs => write!(f, "duffs"),
+            Unit::Credits => write!(f, "credits"),
+        }
+    }
+}
+
+pub fn shown_unit() -> Unit {
+    Unit::ALL[SHOWN_UNIT.load(Ordering::Relaxed) as usize]
+}
+
+/// Switches to the next unit, Dash follows credits.
+pub fn cycle_unit() {
+    let next = match shown_unit() {
+        Unit::Dash => Unit::Duffs,
+        Unit::Duffs => Unit::Credits,
+        Unit::Credits => Unit::Dash,
+    };
+    SHOWN_UNIT.store(next as u8, Ordering::Relaxed);
+}
+
+/// Credits in the shown unit, e.g. `0.5 DASH`.
+pub fn format_credits(credits: u64) -> String {
+    format_credits_in(credits, shown_unit())
+}
+
+/// Duffs in the shown unit, e.g. `0.5 DASH`.
+pub fn format_duffs(duffs: u64) -> String {
+    format_credits(duffs.saturating_mul(CREDITS_PER_DUFF))
+}
+
+/// Exact amount, trailing zeros of the fraction are left out.
+fn format_credits_in(credits: u64, unit: Unit) -> String {
+    let whole = credits / unit.credits();
+    let fraction = credits % unit.credits();
+    if fraction == 0 {
+        format!("{} {}", whole, unit)
+    } else {
+        let fraction = format!("{:0width$}", fraction, width = unit.decimals());
+        format!("{}.{} {}", whole, fraction.trim_end_matches('0'), unit)
+    }
+}
+
+/// The amount in every unit, to check a typed one before it's used.
+pub fn conversion_hint(credits: u64) -> String {
+    Unit::ALL
+        .iter()
+        .map(|unit| format_credits_in(credits, *unit))
+        .collect::<Vec<_>>()
+        .join(" = ")
+}
+
+/// Parses an amount in the shown unit, or in the one written after it, into
+/// credits.
+pub fn parse_credits(input: &str) -> Result<u64, String> {
+    let input = input.trim();
+    let (number, unit) = match input.split_once(char::is_whitespace) {
+        Some((number, name)) => (
+            number,
+            Unit::from_name(name.trim()).ok_or_else(|| format!("Unknown unit {}", name.trim()))?,
+        ),
+        None => (input, shown_unit()),
+    };
+
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+    if fraction.len() > unit.decimals() {
+        return Err(match unit.decimals() {
+            0 => format!("Amounts in {} are whole numbers", unit),
+            decimals => format!("Amounts in {} have at most {} decimals", unit, decimals),
+        });
+    }
+    let parse = |digits: &str| {
+        if digits.is_empty() {
+            Ok(0)
+        } else if digits.chars().all(|c| c.is_ascii_digit()) {
+            digits
+                .parse::<u64>()
+                .map_err(|_| "Amount is too large".to_owned())
+        } else {
+            Err(format!("Cannot parse {} as an amount", number))
+        }
+    };
+    if whole.is_empty() && fraction.is_empty() {
+        return Err("Enter an amount".to_owned());
+    }
+
+    let fraction_credits = parse(fraction)? * 10u64.pow((unit.decimals() - fraction.len()) as u32);
+    parse(whole)?
+        .checked_mul(unit.credits())
+        .and_then(|credits| credits.checked_add(fraction_credits))
+        .ok_or_else(|| "Amount is too large".to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_amounts_with_decimals() {
+        assert_eq!(parse_credits("1.5 dash"), Ok(150_000_000_000));
+        assert_eq!(parse_credits(".5 DASH"), Ok(50_000_000_000));
+        assert_eq!(parse_credits("2. dash"), Ok(200_000_000_000));
+        assert_eq!(parse_credits("0.00000000001 dash"), Ok(1));
+        assert_eq!(parse_credits("1500 duffs"), Ok(1_500_000));
+        assert_eq!(parse_credits("0.001 duff"), Ok(1));
+        assert_eq!(parse_credits(" 42 credits "), Ok(42));
+    }
+
+    #[test]
+    fn refuses_more_decimals_than_the_unit_has() {
+        assert!(parse_credits("0.000000000001 dash").is_err());
+        assert!(parse_credits("0.0001 duffs").is_err());
+        assert!(parse_credits("1.5 credits").is_err());
+    }
+
+    #[test]
+    fn refuses_amounts_above_u64() {
+        assert_eq!(parse_credits("18446744073709551615 credits"), Ok(u64::MAX));
+        assert!(parse_credits("18446744073709551616 credits").is_err());
+        assert!(parse_credits("184467440737.1 dash").is_err());
+        assert!(parse_credits("18446744073709552 duffs").is_err());
+    }
+
+    #[test]
+    fn refuses_negative_empty_and_unknown_input() {
+        assert!(parse_credits("-1 dash").is_err());
+        assert!(parse_credits("1,5 dash").is_err());
+        assert_eq!(parse_credits(""), Err("Enter an amount".to_owned()));
+        assert_eq!(parse_credits(". dash"), Err("Enter an amount".to_owned()));
+        assert_eq!(parse_credits("1 euro"), Err("Unknown unit euro".to_owned()));
+    }
+}